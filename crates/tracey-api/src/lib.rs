@@ -28,6 +28,18 @@ pub enum GitStatus {
 pub struct ApiConfig {
     pub project_root: String,
     pub specs: Vec<ApiSpecInfo>,
+    /// Every reference verb and the coverage role it has, sorted by name
+    #[facet(default)]
+    pub verbs: Vec<ApiVerb>,
+}
+
+/// A reference verb and how it contributes to coverage
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiVerb {
+    pub name: String,
+    /// One of `impl`, `verify`, `depends`, `docs` or `informational`
+    pub role: String,
 }
 
 #[derive(Debug, Clone, Facet)]
@@ -49,6 +61,10 @@ pub struct ApiSpecInfo {
 
     /// Available implementations for this spec
     pub implementations: Vec<String>,
+
+    /// Coverage of this spec never counts toward thresholds
+    #[facet(default)]
+    pub advisory: bool,
 }
 
 /// Forward traceability: rules with their code references
@@ -75,6 +91,12 @@ pub struct ApiRule {
     pub status: Option<String>,
     #[facet(default)]
     pub level: Option<String>,
+    /// Tags from the rule's metadata
+    #[facet(default)]
+    pub tags: Vec<String>,
+    /// Team or person owning the rule
+    #[facet(default)]
+    pub owner: Option<String>,
     #[facet(default)]
     pub source_file: Option<String>,
     #[facet(default)]
@@ -87,9 +109,21 @@ pub struct ApiRule {
     /// Section title (heading text) that this rule belongs to
     #[facet(default)]
     pub section_title: Option<String>,
+    /// Titles of the headings enclosing this rule, outermost first
+    #[facet(default)]
+    pub section_path: Vec<String>,
     pub impl_refs: Vec<ApiCodeRef>,
     pub verify_refs: Vec<ApiCodeRef>,
     pub depends_refs: Vec<ApiCodeRef>,
+    /// References whose verb counts toward documentation coverage
+    #[facet(default)]
+    pub docs_refs: Vec<ApiCodeRef>,
+    /// Verify references in proof files (also listed in `verify_refs`)
+    #[facet(default)]
+    pub proof_refs: Vec<ApiCodeRef>,
+    /// True if a proof of this rule was checked by a verification tool
+    #[facet(default)]
+    pub formally_verified: bool,
     /// True if any reference to this rule is stale (points to an older version).
     /// A stale rule is not counted as covered.
     #[facet(default)]
@@ -97,12 +131,133 @@ pub struct ApiRule {
     /// Stale references pointing to older versions of this rule.
     #[facet(default)]
     pub stale_refs: Vec<ApiStaleRef>,
+    /// Other rules of the same spec mentioned in this rule's prose
+    /// (implicit `related` edges).
+    #[facet(default)]
+    pub related: Vec<RuleId>,
+    /// Required implementation sites, for rules configured with a fan-out.
+    #[facet(default)]
+    pub fanout: Option<ApiFanout>,
+    /// `e[...]` examples and counter-examples written next to the rule
+    #[facet(default)]
+    pub examples: Vec<ApiRuleExample>,
+    /// Accepted coverage gap from the project's waivers file
+    #[facet(default)]
+    pub waiver: Option<ApiWaiver>,
+    /// Weight the rule counts toward implementation coverage when all its
+    /// impl references are marked `coverage=partial`, from 0 to 1.
+    #[facet(default)]
+    pub partial: Option<f64>,
 }
 
+/// A documented exception to coverage checks for one rule
 #[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiWaiver {
+    pub reason: String,
+    /// Last day the waiver applies (`YYYY-MM-DD`)
+    #[facet(default)]
+    pub expires: Option<String>,
+    /// The expiry date has passed, so the waiver no longer applies
+    pub expired: bool,
+}
+
+/// An example or counter-example of a rule
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiRuleExample {
+    /// `good` or `bad`
+    pub kind: String,
+    /// Markdown of the example, without its marker
+    pub markdown: String,
+}
+
+impl ApiRule {
+    /// Whether the rule has all the impl references it needs: at least one,
+    /// or as many as its fan-out requires.
+    pub fn is_implemented(&self) -> bool {
+        !self.impl_refs.is_empty() && self.fanout.as_ref().is_none_or(ApiFanout::is_satisfied)
+    }
+
+    /// Whether the rule is implemented, but only by references marked
+    /// `coverage=partial`.
+    pub fn is_partial(&self) -> bool {
+        self.partial.is_some() && self.is_implemented()
+    }
+
+    /// How much the rule counts toward implementation coverage: 1 when it
+    /// is fully implemented, its partial weight when only partly, else 0.
+    pub fn impl_weight(&self) -> f64 {
+        match self.partial {
+            _ if !self.is_implemented() => 0.0,
+            Some(weight) => weight,
+            None => 1.0,
+        }
+    }
+
+    /// Whether the rule has a waiver that has not expired, which takes it
+    /// out of failing coverage checks.
+    pub fn is_waived(&self) -> bool {
+        self.waiver.as_ref().is_some_and(|w| !w.expired)
+    }
+
+    /// Heading chain the rule sits under, e.g. `Transport > Flow Control`.
+    pub fn section_label(&self) -> Option<String> {
+        if self.section_path.is_empty() {
+            self.section_title.clone()
+        } else {
+            Some(self.section_path.join(" > "))
+        }
+    }
+}
+
+/// Fan-out requirement of a rule and how much of it is met.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiFanout {
+    /// Number of implementation sites required.
+    pub required: usize,
+    /// Number of required sites that are annotated.
+    pub annotated: usize,
+    /// Glob for `per-file` fan-outs; `None` for a plain count.
+    #[facet(default)]
+    pub per_file: Option<String>,
+    /// Matching files without an impl reference (`per-file` only).
+    #[facet(default)]
+    pub missing_files: Vec<String>,
+}
+
+impl ApiFanout {
+    pub fn is_satisfied(&self) -> bool {
+        self.annotated >= self.required
+    }
+
+    /// Human-readable shortfall, e.g. `3 of 17 files annotated`.
+    pub fn summary(&self) -> String {
+        let unit = if self.per_file.is_some() {
+            "files"
+        } else {
+            "references"
+        };
+        format!("{} of {} {unit} annotated", self.annotated, self.required)
+    }
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
 pub struct ApiCodeRef {
     pub file: String,
     pub line: usize,
+    /// Last line of the region a `begin` marker claims
+    #[facet(default)]
+    pub end_line: Option<usize>,
+    /// Note trailing the rule ID in the reference, saying how the rule
+    /// is satisfied
+    #[facet(default)]
+    pub note: Option<String>,
+    /// The reference is marked `coverage=partial`
+    #[facet(default)]
+    pub partial: bool,
 }
 
 /// A stale reference: code points to an older version of a rule.
@@ -180,6 +335,9 @@ pub struct OutlineCoverage {
     pub impl_count: usize,
     /// Number of rules with verification refs
     pub verify_count: usize,
+    /// Number of rules with refs whose verb has the `docs` role
+    #[facet(default)]
+    pub docs_count: usize,
     /// Total number of rules
     pub total: usize,
 }
@@ -268,6 +426,18 @@ pub enum ValidationErrorCode {
     ImplInTestFile,
     /// File matched by include/test_include could not be parsed
     IncludeUnparseableFile,
+    /// Coverage of a tag's rules is below the configured minimum
+    TagThreshold,
+    /// Coverage of a spec, or of the rules under an ID prefix, is below
+    /// its configured threshold
+    CoverageThreshold,
+    /// Source file skipped because it exceeded an extraction limit (a warning)
+    SkippedFile,
+    /// File included by several implementations of a spec and owned by
+    /// another one (a warning)
+    OwnershipConflict,
+    /// Wildcard reference that matches no rules in the spec (a warning)
+    EmptyWildcard,
 }
 
 /// Validation results for a spec/implementation pair
@@ -9,7 +9,9 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
-use super::{is_pid_alive, local_endpoint, pid_file_path, read_pid_file_at};
+use crate::state::{
+    ensure_state_dir, is_pid_alive, local_endpoint, pid_file_path, read_pid_file_at, state_dir,
+};
 
 // Re-export the generated client from tracey-proto
 pub use tracey_proto::TraceyDaemonClient;
@@ -18,21 +20,40 @@ pub use tracey_proto::TraceyDaemonClient;
 #[derive(Clone)]
 pub struct DaemonClient {
     project_root: PathBuf,
+    daemon_exe: Option<PathBuf>,
 }
 
 /// Create a new daemon client for the given project root.
 pub fn new_client(project_root: PathBuf) -> DaemonClient {
-    DaemonClient { project_root }
+    DaemonClient {
+        project_root,
+        daemon_exe: None,
+    }
 }
 
 impl DaemonClient {
+    /// Use this `tracey` executable to auto-start the daemon, instead of the
+    /// current executable (when it is `tracey`) or `tracey` from `PATH`.
+    pub fn with_daemon_exe(mut self, exe: PathBuf) -> Self {
+        self.daemon_exe = Some(exe);
+        self
+    }
+
+    /// The project root this client talks to the daemon of.
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
     async fn connect_inner(&self) -> io::Result<roam_stream::LocalLink> {
         let start = Instant::now();
         debug!(
             project_root = %self.project_root.display(),
             "daemon client: connect_inner start"
         );
-        let connector = DaemonConnector::new(self.project_root.clone());
+        let mut connector = DaemonConnector::new(self.project_root.clone());
+        if let Some(exe) = &self.daemon_exe {
+            connector = connector.with_daemon_exe(exe.clone());
+        }
         let stream = connector.connect().await?;
         debug!(
             elapsed_ms = start.elapsed().as_millis(),
@@ -144,6 +165,34 @@ impl DaemonClient {
         self.with_client(|c| async move { c.rule(rule_id).await })
             .await
     }
+    pub async fn annotation_snippet(
+        &self,
+        req: tracey_proto::AnnotationSnippetRequest,
+    ) -> Result<tracey_proto::AnnotationSnippet, roam::RoamError<String>> {
+        self.with_client(|c| async move { c.annotation_snippet(req).await })
+            .await
+    }
+    pub async fn compare(
+        &self,
+        req: tracey_proto::CompareRequest,
+    ) -> Result<tracey_proto::CompareResponse, roam::RoamError<String>> {
+        self.with_client(|c| async move { c.compare(req).await })
+            .await
+    }
+    pub async fn refs_in(
+        &self,
+        req: tracey_proto::RefsInRequest,
+    ) -> Result<tracey_proto::RefsInResponse, roam::RoamError> {
+        self.with_client(|c| async move { c.refs_in(req).await })
+            .await
+    }
+    pub async fn breakdown(
+        &self,
+        req: tracey_proto::BreakdownRequest,
+    ) -> Result<tracey_proto::BreakdownResponse, roam::RoamError<String>> {
+        self.with_client(|c| async move { c.breakdown(req).await })
+            .await
+    }
     pub async fn config(&self) -> Result<tracey_api::ApiConfig, roam::RoamError> {
         self.with_client(|c| async move { c.config().await }).await
     }
@@ -159,9 +208,23 @@ impl DaemonClient {
         self.with_client(|c| async move { c.vfs_close(path).await })
             .await
     }
+    pub async fn vfs_sync(
+        &self,
+        req: tracey_proto::VfsSyncRequest,
+    ) -> Result<tracey_proto::VfsSyncResponse, roam::RoamError> {
+        self.with_client(|c| async move { c.vfs_sync(req).await })
+            .await
+    }
     pub async fn reload(&self) -> Result<tracey_proto::ReloadResponse, roam::RoamError> {
         self.with_client(|c| async move { c.reload().await }).await
     }
+    pub async fn reload_scope(
+        &self,
+        req: tracey_proto::ReloadScopeRequest,
+    ) -> Result<tracey_proto::ReloadScopeResponse, roam::RoamError<String>> {
+        self.with_client(|c| async move { c.reload_scope(req).await })
+            .await
+    }
     pub async fn version(&self) -> Result<u64, roam::RoamError> {
         self.with_client(|c| async move { c.version().await }).await
     }
@@ -224,6 +287,13 @@ impl DaemonClient {
         self.with_client(|c| async move { c.update_file_range(req).await })
             .await
     }
+    pub async fn audit(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<tracey_proto::AuditEntry>, roam::RoamError<String>> {
+        self.with_client(|c| async move { c.audit(limit).await })
+            .await
+    }
     pub async fn is_test_file(&self, path: String) -> Result<bool, roam::RoamError> {
         self.with_client(|c| async move { c.is_test_file(path).await })
             .await
@@ -297,6 +367,13 @@ impl DaemonClient {
         self.with_client(|c| async move { c.lsp_code_lens(req).await })
             .await
     }
+    pub async fn lsp_folding_ranges(
+        &self,
+        req: tracey_proto::LspDocumentRequest,
+    ) -> Result<Vec<tracey_proto::LspFoldingRange>, roam::RoamError> {
+        self.with_client(|c| async move { c.lsp_folding_ranges(req).await })
+            .await
+    }
     pub async fn lsp_inlay_hints(
         &self,
         req: tracey_proto::InlayHintsRequest,
@@ -363,6 +440,7 @@ impl DaemonClient {
 /// and wait for it to be ready before connecting.
 pub struct DaemonConnector {
     project_root: PathBuf,
+    daemon_exe: Option<PathBuf>,
 }
 
 struct StartupLock {
@@ -418,12 +496,33 @@ impl DaemonConnector {
 
     /// Create a new connector for the given project root.
     pub fn new(project_root: PathBuf) -> Self {
-        Self { project_root }
+        Self {
+            project_root,
+            daemon_exe: None,
+        }
+    }
+
+    /// Use this `tracey` executable to auto-start the daemon.
+    pub fn with_daemon_exe(mut self, exe: PathBuf) -> Self {
+        self.daemon_exe = Some(exe);
+        self
+    }
+
+    /// The executable to start the daemon with: the configured one, else the
+    /// current executable if it is `tracey` itself, else `tracey` from `PATH`.
+    fn daemon_exe(&self) -> PathBuf {
+        if let Some(exe) = &self.daemon_exe {
+            return exe.clone();
+        }
+        std::env::current_exe()
+            .ok()
+            .filter(|exe| exe.file_stem().is_some_and(|stem| stem == "tracey"))
+            .unwrap_or_else(|| PathBuf::from("tracey"))
     }
 
     /// Spawn the daemon process in the background.
     fn spawn_daemon(&self) -> io::Result<()> {
-        let exe = std::env::current_exe().map_err(io::Error::other)?;
+        let exe = self.daemon_exe();
         let config_path = self.project_root.join(".config/tracey/config.styx");
 
         info!("Auto-starting daemon for {}", self.project_root.display());
@@ -456,11 +555,11 @@ impl DaemonConnector {
     }
 
     fn startup_lock_path(&self) -> PathBuf {
-        super::state_dir(&self.project_root).join("daemon-start.lock")
+        state_dir(&self.project_root).join("daemon-start.lock")
     }
 
     fn acquire_startup_lock(&self, timeout: Duration) -> io::Result<StartupLock> {
-        super::ensure_state_dir(&self.project_root).map_err(io::Error::other)?;
+        ensure_state_dir(&self.project_root)?;
 
         let lock_path = self.startup_lock_path();
         let started = Instant::now();
@@ -529,7 +628,7 @@ impl DaemonConnector {
                          Check logs at {}/daemon.log",
                         timeout.as_secs(),
                         last_connect_error.as_deref().unwrap_or("unavailable"),
-                        super::state_dir(&self.project_root).display()
+                        state_dir(&self.project_root).display()
                     ),
                 ));
             }
@@ -0,0 +1,32 @@
+//! Async client for the tracey daemon.
+//!
+//! Every tracey bridge (CLI queries, HTTP dashboard, MCP, LSP) talks to a
+//! per-project daemon over local IPC. This crate is that client on its own,
+//! for tools and editor extensions that want the same data without depending
+//! on the whole `tracey` CLI crate.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = tracey_client::new_client("/path/to/project".into());
+//! let status = client.status().await.map_err(|e| format!("{e:?}"))?;
+//! for row in status.impls {
+//!     println!("{}/{}: {}/{}", row.spec, row.impl_name, row.covered_rules, row.total_rules);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each call connects on its own, so a [`DaemonClient`] is cheap to clone and
+//! keep around. If no daemon is running for the project, the first call
+//! starts one (see [`DaemonConnector`]) and waits for it to come up.
+//! [`DaemonClient::subscribe`] streams a [`tracey_proto::DataUpdate`] every
+//! time the daemon's data changes.
+
+mod client;
+pub mod state;
+
+pub use client::{DaemonClient, DaemonConnector, TraceyDaemonClient, new_client};
+
+// Request and response types, re-exported so callers need no extra dependency.
+pub use tracey_api;
+pub use tracey_proto;
@@ -0,0 +1,143 @@
+//! Where a project's daemon keeps its runtime state.
+//!
+//! Runtime state (socket, PID file, lock file, logs) is stored under
+//! `$XDG_STATE_HOME/tracey/<hash>` (or platform equivalent), where `<hash>`
+//! is a truncated Blake3 hash of the canonical project root path. Both the
+//! daemon and its clients derive every path from the project root alone.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Socket file name within the state directory (Unix only)
+#[cfg(unix)]
+const SOCKET_FILENAME: &str = "daemon.sock";
+
+/// Return the base directory that contains all per-project state directories.
+///
+/// Resolved via `dirs::state_dir()` with platform-specific fallbacks
+/// (`~/.local/state` on Linux, `~/Library/Application Support` on macOS
+/// via `dirs::data_local_dir()`). Returns `{state_home}/tracey/`.
+pub fn state_base_dir() -> PathBuf {
+    let base = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .unwrap_or_else(|| {
+            dirs::home_dir()
+                .expect("could not determine home directory")
+                .join(".local/state")
+        });
+
+    base.join("tracey")
+}
+
+fn state_dir_inner(project_root: &Path) -> (PathBuf, PathBuf) {
+    let canonical =
+        std::fs::canonicalize(project_root).unwrap_or_else(|_| project_root.to_path_buf());
+    let hash = blake3::hash(canonical.as_os_str().as_encoded_bytes());
+    let short_hash = &hash.to_hex()[..16];
+
+    (state_base_dir().join(short_hash), canonical)
+}
+
+/// Compute the per-project state directory path.
+///
+/// Returns `{state_home}/tracey/{hash}` where `hash` is the first 16 hex
+/// characters of the Blake3 hash of the canonical project root path.
+pub fn state_dir(project_root: &Path) -> PathBuf {
+    state_dir_inner(project_root).0
+}
+
+/// Create the per-project state directory and write a `project-root` metadata
+/// file for reverse lookups. Returns the directory path.
+pub fn ensure_state_dir(project_root: &Path) -> io::Result<PathBuf> {
+    let (dir, canonical) = state_dir_inner(project_root);
+    std::fs::create_dir_all(&dir)?;
+
+    let meta_path = dir.join("project-root");
+    std::fs::write(&meta_path, canonical.as_os_str().as_encoded_bytes())?;
+
+    Ok(dir)
+}
+
+/// Get the local IPC endpoint for a workspace.
+///
+/// On Unix, this returns a string representing a path to `<state_dir>/daemon.sock`.
+/// On Windows, this returns a string representing a named pipe path like `\\.\pipe\tracey-{hash}`.
+///
+/// r[impl daemon.roam.unix-socket]
+#[cfg(unix)]
+pub fn local_endpoint(project_root: &Path) -> String {
+    state_dir(project_root)
+        .join(SOCKET_FILENAME)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Get the local IPC endpoint for a workspace.
+///
+/// On Unix, this returns a string representing a path to `<state_dir>/daemon.sock`.
+/// On Windows, this returns a string representing a named pipe path like `\\.\pipe\tracey-{hash}`.
+#[cfg(windows)]
+pub fn local_endpoint(project_root: &Path) -> String {
+    let dir = state_dir(project_root);
+    let hash = dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .expect("state_dir hash");
+    format!(r"\\.\pipe\tracey-{hash}")
+}
+
+/// Path to the daemon PID file within the state directory.
+pub fn pid_file_path(project_root: &Path) -> PathBuf {
+    state_dir(project_root).join("daemon.pid")
+}
+
+/// Check whether a process with the given PID is alive.
+#[cfg(unix)]
+pub fn is_pid_alive(pid: u32) -> bool {
+    // Signal 0 doesn't send a signal; it just checks whether the process exists.
+    unsafe extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    unsafe { kill(pid as i32, 0) == 0 }
+}
+
+/// Check whether a process with the given PID is alive.
+#[cfg(not(unix))]
+pub fn is_pid_alive(_pid: u32) -> bool {
+    true // best-effort on non-Unix; rely on socket connect to detect dead daemon
+}
+
+/// Read a PID file at the given path and return `(pid, protocol_version)` if it
+/// parses correctly. Returns `None` if the file doesn't exist or is malformed.
+pub fn read_pid_file_at(path: &Path) -> Option<(u32, u32)> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Failed to read PID file {}: {e}", path.display());
+            return None;
+        }
+    };
+
+    let mut pid = None;
+    let mut version = None;
+    for line in content.lines() {
+        if let Some(v) = line.strip_prefix("pid=") {
+            pid = v.parse().ok();
+        } else if let Some(v) = line.strip_prefix("version=") {
+            version = v.parse().ok();
+        }
+    }
+
+    match (pid, version) {
+        (Some(p), Some(v)) => Some((p, v)),
+        _ => {
+            warn!(
+                "PID file {} has unexpected format, ignoring it",
+                path.display()
+            );
+            None
+        }
+    }
+}
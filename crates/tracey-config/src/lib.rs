@@ -6,17 +6,262 @@
 //! Config lives at `.config/tracey/config.styx` relative to the project root.
 
 use facet::Facet;
+use std::collections::BTreeMap;
 
 /// Root configuration for tracey
 #[derive(Debug, Clone, Default, Facet)]
 pub struct Config {
+    /// Pin the project root explicitly, relative to the directory that
+    /// contains `.config/tracey/` (e.g. `.` to stop root detection from
+    /// climbing to an enclosing Cargo workspace).
+    /// r[impl config.root]
+    #[facet(default)]
+    pub root: Option<String>,
+
+    /// Source patterns inherited by every impl that does not set its own
+    /// r[impl config.defaults]
+    #[facet(default)]
+    pub defaults: Option<DefaultsConfig>,
+
     /// Specifications to track coverage against
     #[facet(default)]
     pub specs: Vec<SpecConfig>,
+
+    /// How each verb counts toward coverage, keyed by verb name.
+    ///
+    /// Values are `impl`, `verify`, `depends`, `docs` or `informational`.
+    /// Built-in verbs can be remapped; any other key declares a custom verb.
+    /// r[impl config.verbs]
+    #[facet(default)]
+    pub verbs: BTreeMap<String, String>,
+
+    /// Limits for logs kept in the per-project state directory
+    /// r[impl config.retention]
+    #[facet(default)]
+    pub retention: Option<RetentionConfig>,
+
+    /// Resource limits for extracting references from source files
+    /// r[impl config.limits]
+    #[facet(default)]
+    pub limits: Option<LimitsConfig>,
+
+    /// Outbound webhook the daemon notifies on every coverage change
+    /// r[impl config.webhook]
+    #[facet(default)]
+    pub webhook: Option<WebhookConfig>,
+
+    /// Extraction cache shared between machines, e.g. a CI fleet
+    /// r[impl config.remote-cache]
+    #[facet(default)]
+    pub remote_cache: Option<RemoteCacheConfig>,
+
+    /// Formatter commands used by `tracey fmt-check`, keyed by file extension.
+    ///
+    /// Each command reads a file on stdin and writes it formatted to stdout;
+    /// `{path}` is replaced by the file's path. An entry overrides the
+    /// built-in formatter for that extension, and an empty command disables it.
+    /// r[impl config.formatters]
+    #[facet(default)]
+    pub formatters: BTreeMap<String, String>,
+
+    /// Comment markers for file types tracey has no parser for, keyed by
+    /// file extension. Files with these extensions are scanned for
+    /// references in the comments the markers delimit.
+    /// r[impl config.comments]
+    #[facet(default)]
+    pub comments: BTreeMap<String, CommentConfig>,
+
+    /// Coverage gates run by `tracey policy`, keyed by policy name.
+    ///
+    /// Each command reads the coverage report as JSON on stdin and prints
+    /// `{"pass": bool, "messages": [...]}` on stdout.
+    /// r[impl config.policies]
+    #[facet(default)]
+    pub policies: BTreeMap<String, String>,
+
+    /// Coverage minimums for the rules carrying a tag, keyed by tag name.
+    /// Checked by `tracey query validate`.
+    /// r[impl config.tags]
+    #[facet(default)]
+    pub tags: BTreeMap<String, TagThreshold>,
+
+    /// CODEOWNERS-style file, relative to the project root, assigning an
+    /// owner to the rules whose marker has no `owner=` attribute.
+    /// r[impl config.owners-file]
+    #[facet(default)]
+    pub owners_file: Option<String>,
+
+    /// Lints `tracey lint-spec` runs over spec markdown
+    /// r[impl config.spec-lints]
+    #[facet(default)]
+    pub spec_lints: Option<SpecLintConfig>,
 }
 
-/// Configuration for a single specification
+impl Config {
+    /// Fold the `defaults` block into each impl.
+    ///
+    /// Each pattern list an impl leaves empty is taken from `defaults`; a
+    /// non-empty list overrides it entirely. The returned config has no
+    /// `defaults` block left.
+    /// r[impl config.defaults.inherit]
+    pub fn resolve_defaults(mut self) -> Self {
+        let Some(defaults) = self.defaults.take() else {
+            return self;
+        };
+        for impl_config in self.specs.iter_mut().flat_map(|s| s.impls.iter_mut()) {
+            if impl_config.include.is_empty() {
+                impl_config.include = defaults.include.clone();
+            }
+            if impl_config.exclude.is_empty() {
+                impl_config.exclude = defaults.exclude.clone();
+            }
+            if impl_config.test_include.is_empty() {
+                impl_config.test_include = defaults.test_include.clone();
+            }
+        }
+        self
+    }
+}
+
+/// Source patterns shared by all impls
+#[derive(Debug, Clone, Default, Facet)]
+pub struct DefaultsConfig {
+    /// Glob patterns for source files to scan
+    #[facet(default)]
+    pub include: Vec<String>,
+
+    /// Glob patterns to exclude
+    #[facet(default)]
+    pub exclude: Vec<String>,
+
+    /// Glob patterns for test files (only verify annotations allowed)
+    #[facet(default)]
+    pub test_include: Vec<String>,
+}
+
+/// Retention limits for the per-project state directory
+#[derive(Debug, Clone, Default, Facet)]
+pub struct RetentionConfig {
+    /// Maximum total size of log files, in megabytes (default: 50)
+    #[facet(default)]
+    pub max_log_mb: Option<u64>,
+
+    /// Remove logs of exited bridges after this many days (default: 7)
+    #[facet(default)]
+    pub max_log_age_days: Option<u64>,
+
+    /// Keep only this many of the newest coverage snapshots in
+    /// `.tracey/history.jsonl` (default: unlimited)
+    #[facet(default)]
+    pub max_history_entries: Option<u64>,
+
+    /// Keep only this many of the newest entries in `.tracey/audit.jsonl`
+    /// (default: unlimited)
+    #[facet(default)]
+    pub max_audit_entries: Option<u64>,
+}
+
+/// Resource limits for source extraction, so a build on a constrained
+/// machine skips pathological files instead of running out of time or memory.
+/// Every limit is unset (unlimited) by default.
+#[derive(Debug, Clone, Default, Facet)]
+pub struct LimitsConfig {
+    /// Skip source files larger than this, in kilobytes
+    #[facet(default)]
+    pub max_file_kb: Option<u64>,
+
+    /// Scan at most this many source files per build
+    #[facet(default)]
+    pub max_files: Option<u64>,
+
+    /// Skip a file whose parsing takes longer than this, in milliseconds
+    #[facet(default)]
+    pub file_timeout_ms: Option<u64>,
+
+    /// Skip the remaining files once extraction has run this long, in seconds
+    #[facet(default)]
+    pub budget_secs: Option<u64>,
+}
+
+/// How comments are written in one file type
+#[derive(Debug, Clone, Default, Facet)]
+pub struct CommentConfig {
+    /// Markers starting a comment that runs to the end of the line, e.g. `--`
+    #[facet(default)]
+    pub line: Vec<String>,
+
+    /// Marker opening a block comment, e.g. `<!--`
+    #[facet(default)]
+    pub block_start: Option<String>,
+
+    /// Marker closing a block comment, e.g. `-->`
+    #[facet(default)]
+    pub block_end: Option<String>,
+}
+
+/// Coverage minimums, in percent, for the rules carrying one tag
+#[derive(Debug, Clone, Default, Facet)]
+pub struct TagThreshold {
+    /// Share of the tag's rules that must have an impl reference
+    #[facet(default)]
+    pub min_impl: Option<u32>,
+
+    /// Share of the tag's rules that must have a verify reference
+    #[facet(default)]
+    pub min_verify: Option<u32>,
+}
+
+/// Settings for `tracey lint-spec`
+#[derive(Debug, Clone, Default, Facet)]
+pub struct SpecLintConfig {
+    /// Severity of each lint, keyed by lint name: `error`, `warning` or `off`
+    #[facet(default)]
+    pub levels: BTreeMap<String, String>,
+
+    /// Sentences a rule may have before `long-rule` reports it (default: 5)
+    #[facet(default)]
+    pub max_sentences: Option<u32>,
+
+    /// Similarity, in percent, at which `duplicate-text` reports two rules
+    /// (default: 90)
+    #[facet(default)]
+    pub min_similarity: Option<u32>,
+}
+
+/// Outbound webhook notified by the daemon on every version change
+#[derive(Debug, Clone, Facet)]
+pub struct WebhookConfig {
+    /// URL the coverage update is POSTed to
+    pub url: String,
+
+    /// Name of the environment variable holding the HMAC signing secret.
+    /// The secret itself is never stored in the config file.
+    #[facet(default)]
+    pub secret_env: Option<String>,
+}
+
+/// HTTP or S3-compatible store for parsed source files, keyed by content
 #[derive(Debug, Clone, Facet)]
+pub struct RemoteCacheConfig {
+    /// Base URL entries are read from and written to, as `<url>/<key>`
+    pub url: String,
+
+    /// Name of the environment variable holding a bearer token
+    #[facet(default)]
+    pub token_env: Option<String>,
+
+    /// Sign requests for S3 in this region, with the credentials in
+    /// `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`
+    #[facet(default)]
+    pub s3_region: Option<String>,
+
+    /// Only read from the cache, never upload (e.g. for untrusted PR jobs)
+    #[facet(default)]
+    pub read_only: bool,
+}
+
+/// Configuration for a single specification
+#[derive(Debug, Clone, Default, Facet)]
 pub struct SpecConfig {
     /// Name of the spec (for display purposes)
     /// r[impl config.spec.name]
@@ -43,6 +288,91 @@ pub struct SpecConfig {
     /// Each impl block specifies which source files to scan
     #[facet(default)]
     pub impls: Vec<Impl>,
+
+    /// `enforced` (default) or `advisory`. An advisory spec, such as a
+    /// vendored third-party spec, never counts toward coverage thresholds;
+    /// its references are still validated.
+    /// r[impl config.spec.enforcement]
+    #[facet(default)]
+    pub enforcement: Option<String>,
+
+    /// `impl` (default) or `verify`. With `verify`, `impl` references in
+    /// files matched by an implementation's `test_include` only count toward
+    /// verification, never implementation.
+    /// r[impl config.spec.test-impls]
+    #[facet(default)]
+    pub test_impls: Option<String>,
+
+    /// Which implementation owns a file matched by several of them:
+    /// `first` (default) for the one listed first, or `specific` for the one
+    /// whose matching pattern names the deepest directory.
+    /// r[impl config.spec.ownership]
+    #[facet(default)]
+    pub ownership: Option<String>,
+
+    /// Glob patterns for files shared by the implementations. References
+    /// in them count for every implementation that includes them.
+    /// r[impl config.spec.shared]
+    #[facet(default)]
+    pub shared: Vec<String>,
+
+    /// String literals also scanned for references in this spec's
+    /// implementations: `doc-attributes` for Rust `#[doc = "..."]`, and
+    /// `test-names` for names passed to `it`, `test`, `describe` and `t.Run`.
+    /// r[impl config.spec.strings]
+    #[facet(default)]
+    pub strings: Vec<String>,
+
+    /// Share of the spec's rules, in percent, that must be implemented.
+    /// Draft and removed rules are not counted.
+    /// r[impl config.spec.threshold]
+    #[facet(default)]
+    pub threshold: Option<u32>,
+
+    /// Per-section thresholds, keyed by rule ID prefix: the share of rules
+    /// whose ID starts with the key that must be implemented, e.g.
+    /// `"channel." 100` while the rest of the spec is still being adopted.
+    /// r[impl config.spec.thresholds]
+    #[facet(default)]
+    pub thresholds: BTreeMap<String, u32>,
+
+    /// How much a rule implemented only by `coverage=partial` references
+    /// counts toward coverage, in percent of a fully implemented rule.
+    /// Defaults to 50.
+    /// r[impl config.spec.partial-weight]
+    #[facet(default)]
+    pub partial_weight: Option<u32>,
+
+    /// Rules that need more than one implementation site, keyed by rule ID.
+    ///
+    /// Each value is either a count (`"3"`) or `per-file:<glob>`, which
+    /// requires an impl reference in every scanned file matching the glob.
+    /// r[impl config.spec.fanout]
+    #[facet(default)]
+    pub fanout: BTreeMap<String, String>,
+
+    /// Treat this spec's sources as untrusted: enforce size and rule count
+    /// limits and sanitize the rendered HTML. Enabled by the block's presence.
+    /// r[impl config.spec.sandbox]
+    #[facet(default)]
+    pub sandbox: Option<SandboxConfig>,
+}
+
+/// Limits applied to a spec whose sources are not trusted
+#[derive(Debug, Clone, Default, Facet)]
+pub struct SandboxConfig {
+    /// Largest spec file accepted, in kilobytes (default: 512)
+    #[facet(default)]
+    pub max_file_kb: Option<u64>,
+
+    /// Most rules the spec may define (default: 5000)
+    #[facet(default)]
+    pub max_rules: Option<u64>,
+
+    /// URL schemes links and images may use (default: http, https, mailto).
+    /// Relative URLs and fragments are always allowed.
+    #[facet(default)]
+    pub url_schemes: Vec<String>,
 }
 
 /// Configuration for a single implementation of a spec
@@ -66,4 +396,10 @@ pub struct Impl {
     /// r[impl config.impl.test_include]
     #[facet(default)]
     pub test_include: Vec<String>,
+
+    /// Glob patterns for proof files: Kani harnesses, Creusot or Prusti
+    /// sources, TLA+ specs. Their verify annotations count as proofs.
+    /// r[impl config.impl.proof_include]
+    #[facet(default)]
+    pub proof_include: Vec<String>,
 }
@@ -0,0 +1,125 @@
+//! Extraction throughput on a generated corpus.
+//!
+//! ```text
+//! cargo bench -p tracey-core --bench extract
+//! ```
+//!
+//! `TRACEY_BENCH_FILES` and `TRACEY_BENCH_LINES` size the corpus (default
+//! 2000 files of 400 lines).
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracey_core::{PathSources, Reqs};
+
+const RUNS: usize = 5;
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Write a corpus of Rust files: mostly code and plain comments, with a
+/// reference every few dozen lines, like a typical annotated codebase.
+fn corpus(dir: &Path, files: usize, lines: usize) -> (Vec<PathBuf>, u64) {
+    let mut paths = Vec::with_capacity(files);
+    let mut bytes = 0;
+    for f in 0..files {
+        let mut text = String::new();
+        for l in 0..lines {
+            match l % 40 {
+                0 => text.push_str(&format!("// r[impl bench.m{}.f{f}.l{l}]\n", f % 16)),
+                1 => text.push_str("/// Returns the value, see `docs[0]` for details.\n"),
+                2..=9 => text.push_str("    // Plain explanatory comment about the code below\n"),
+                20 => text.push_str(&format!("    // r[verify bench.m{}.f{f}.l{l}]\n", f % 16)),
+                _ => text.push_str("    let value = compute(input, &mut state)?;\n"),
+            }
+        }
+        let path = dir.join(format!("m{}/f{f}.rs", f % 16));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &text).unwrap();
+        bytes += text.len() as u64;
+        paths.push(path);
+    }
+    (paths, bytes)
+}
+
+/// Best of `RUNS` runs of `f`.
+fn time(mut f: impl FnMut() -> usize) -> (Duration, usize) {
+    let mut best = Duration::MAX;
+    let mut found = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        found = f();
+        best = best.min(start.elapsed());
+    }
+    (best, found)
+}
+
+fn report(name: &str, bytes: u64, (elapsed, found): (Duration, usize)) {
+    let mib = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{name:<24} {:>9.2} ms {:>9.1} MiB/s {found:>8} refs",
+        elapsed.as_secs_f64() * 1000.0,
+        mib / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let files = env_or("TRACEY_BENCH_FILES", 2000);
+    let lines = env_or("TRACEY_BENCH_LINES", 400);
+    let dir = std::env::temp_dir().join(format!("tracey-bench-{}", std::process::id()));
+    let (paths, bytes) = corpus(&dir, files, lines);
+    println!(
+        "{files} files, {lines} lines each, {:.1} MiB",
+        bytes as f64 / (1024.0 * 1024.0)
+    );
+
+    // Lexing alone, with every file already in memory.
+    let contents: Vec<(PathBuf, String)> = paths
+        .iter()
+        .map(|p| (p.clone(), std::fs::read_to_string(p).unwrap()))
+        .collect();
+    report(
+        "lex (in memory)",
+        bytes,
+        time(|| {
+            contents
+                .iter()
+                .map(|(path, text)| Reqs::extract_from_content(path, text).len())
+                .sum()
+        }),
+    );
+
+    report(
+        "read only",
+        bytes,
+        time(|| paths.iter().map(|p| std::fs::read(p).unwrap().len()).sum()),
+    );
+
+    report(
+        "PathSources",
+        bytes,
+        time(|| {
+            Reqs::extract(PathSources::new(paths.clone()))
+                .unwrap()
+                .reqs
+                .len()
+        }),
+    );
+
+    #[cfg(feature = "walk")]
+    report(
+        "WalkSources",
+        bytes,
+        time(|| {
+            Reqs::extract(tracey_core::WalkSources::new(&dir))
+                .unwrap()
+                .reqs
+                .len()
+        }),
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -17,6 +17,7 @@
 use crate::positions::{ByteOffset, LineNumber, RefLocation};
 use crate::{RuleId, parse_rule_id};
 use arborium::tree_sitter::{Node, Parser};
+use facet::Facet;
 use std::path::{Path, PathBuf};
 
 /// A semantic unit of code (function, struct, impl, etc.)
@@ -41,7 +42,8 @@ pub struct CodeUnit {
 }
 
 /// The kind of code unit
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[repr(u8)]
 pub enum CodeUnitKind {
     /// A function or method
     Function,
@@ -184,6 +186,27 @@ pub fn extract(path: &Path, source: &str) -> CodeUnits {
     }
 }
 
+/// Attribute each code unit lying within a region claimed by
+/// `r[verb rule.id begin]` ... `r[verb rule.id end]` to the region's rule,
+/// so every unit of the block counts as covered.
+///
+/// r[impl ref.syntax.region]
+pub fn attribute_regions(units: &mut [CodeUnit], references: &[crate::ReqReference]) {
+    for reference in references {
+        let Some(end_line) = reference.end_line else {
+            continue;
+        };
+        for unit in units.iter_mut() {
+            if unit.start_line >= reference.line
+                && unit.end_line <= end_line
+                && !unit.req_refs.contains(&reference.req_id)
+            {
+                unit.req_refs.push(reference.req_id.clone());
+            }
+        }
+    }
+}
+
 /// Extract code units from Rust source code
 pub fn extract_rust(path: &Path, source: &str) -> CodeUnits {
     let mut parser = Parser::new();
@@ -199,7 +222,7 @@ pub fn extract_rust(path: &Path, source: &str) -> CodeUnits {
     let root = tree.root_node();
 
     // Walk the tree and extract code units
-    extract_units_recursive(path, source, root, &mut units, rust_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, rust_node_kind);
 
     units
 }
@@ -217,7 +240,7 @@ pub fn extract_swift(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, swift_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, swift_node_kind);
     units
 }
 
@@ -234,7 +257,7 @@ pub fn extract_go(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, go_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, go_node_kind);
     units
 }
 
@@ -251,7 +274,7 @@ pub fn extract_java(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, java_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, java_node_kind);
     units
 }
 
@@ -268,7 +291,7 @@ pub fn extract_python(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, python_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, python_node_kind);
     units
 }
 
@@ -285,7 +308,7 @@ pub fn extract_typescript(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, typescript_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, typescript_node_kind);
     units
 }
 
@@ -406,7 +429,7 @@ pub fn extract_php(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, php_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, php_node_kind);
     units
 }
 
@@ -423,7 +446,7 @@ pub fn extract_c(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, c_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, c_node_kind);
     units
 }
 
@@ -440,7 +463,7 @@ pub fn extract_cpp(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, cpp_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, cpp_node_kind);
     units
 }
 
@@ -457,7 +480,7 @@ pub fn extract_ruby(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, ruby_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, ruby_node_kind);
     units
 }
 
@@ -483,7 +506,7 @@ pub fn extract_r(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, r_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, r_node_kind);
     units
 }
 
@@ -507,7 +530,7 @@ pub fn extract_dart(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, dart_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, dart_node_kind);
     units
 }
 
@@ -535,7 +558,7 @@ pub fn extract_lua(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, lua_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, lua_node_kind);
     units
 }
 
@@ -559,7 +582,7 @@ pub fn extract_asm(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, asm_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, asm_node_kind);
     units
 }
 
@@ -584,7 +607,7 @@ pub fn extract_matlab(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, matlab_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, matlab_node_kind);
     units
 }
 
@@ -609,7 +632,7 @@ pub fn extract_perl(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, perl_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, perl_node_kind);
     units
 }
 
@@ -639,7 +662,7 @@ pub fn extract_haskell(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, haskell_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, haskell_node_kind);
     units
 }
 
@@ -743,7 +766,7 @@ pub fn extract_erlang(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, erlang_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, erlang_node_kind);
     units
 }
 
@@ -844,7 +867,7 @@ pub fn extract_fsharp(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, fsharp_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, fsharp_node_kind);
     units
 }
 
@@ -870,7 +893,7 @@ pub fn extract_vb(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, vb_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, vb_node_kind);
     units
 }
 
@@ -897,7 +920,7 @@ pub fn extract_cobol(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, cobol_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, cobol_node_kind);
     units
 }
 
@@ -922,7 +945,7 @@ pub fn extract_julia(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, julia_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, julia_node_kind);
     units
 }
 
@@ -951,7 +974,7 @@ pub fn extract_d(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, d_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, d_node_kind);
     units
 }
 
@@ -978,7 +1001,7 @@ pub fn extract_powershell(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, powershell_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, powershell_node_kind);
     units
 }
 
@@ -1004,7 +1027,7 @@ pub fn extract_cmake(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, cmake_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, cmake_node_kind);
     units
 }
 
@@ -1029,7 +1052,7 @@ pub fn extract_ocaml(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, ocaml_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, ocaml_node_kind);
     units
 }
 
@@ -1055,7 +1078,7 @@ pub fn extract_bash(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, bash_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, bash_node_kind);
     units
 }
 
@@ -1079,7 +1102,7 @@ pub fn extract_nix(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, nix_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, nix_node_kind);
     units
 }
 
@@ -1104,7 +1127,7 @@ pub fn extract_lean(path: &Path, source: &str) -> CodeUnits {
 
     let mut units = CodeUnits::new();
     let root = tree.root_node();
-    extract_units_recursive(path, source, root, &mut units, lean_node_kind);
+    extract_units_from_tree(path, source, root, &mut units, lean_node_kind);
     units
 }
 
@@ -1120,7 +1143,7 @@ fn lean_node_kind(kind: &str) -> Option<CodeUnitKind> {
     }
 }
 
-fn extract_units_recursive<F>(
+fn extract_units_from_tree<F>(
     path: &Path,
     source: &str,
     node: Node,
@@ -1129,15 +1152,20 @@ fn extract_units_recursive<F>(
 ) where
     F: Fn(&str) -> Option<CodeUnitKind> + Copy,
 {
-    // Check if this node is a code unit we care about
-    if let Some(unit) = node_to_code_unit(path, source, node, &node_kind_mapper) {
-        units.units.push(unit);
-    }
-
-    // Recurse into children
+    // Walk in pre-order with an explicit stack: pathological inputs (e.g. a
+    // 64 KiB attribute chain) produce trees deep enough to overflow the
+    // call stack.
+    let mut stack = vec![node];
     let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        extract_units_recursive(path, source, child, units, node_kind_mapper);
+    while let Some(node) = stack.pop() {
+        // Check if this node is a code unit we care about
+        if let Some(unit) = node_to_code_unit(path, source, node, &node_kind_mapper) {
+            units.units.push(unit);
+        }
+
+        let first_child = stack.len();
+        stack.extend(node.children(&mut cursor));
+        stack[first_child..].reverse();
     }
 }
 
@@ -1355,6 +1383,10 @@ fn collect_inner_comment_refs(source: &str, node: Node, refs: &mut Vec<RuleId>)
             | "block_inner_doc_comment" => {
                 collect_inner_comment_refs(source, child, refs);
             }
+            // Rust attributes are children of the item they annotate
+            "attribute_item" => {
+                collect_comment_refs(source, child, refs);
+            }
             "doc_comment" => {
                 // The actual content of a doc comment
                 let text = &source[child.byte_range()];
@@ -1380,6 +1412,15 @@ fn collect_comment_refs(source: &str, node: Node, refs: &mut Vec<RuleId>) {
             extract_refs_from_comment_text(source, node, refs);
         }
         "attribute_item" | "decorator" => {
+            // r[impl ref.attributes]
+            let text = &source[node.byte_range()];
+            if let Some(attribute) = crate::lexer::TraceyAttribute::parse(text) {
+                for id in attribute.rule_ids(text).flatten() {
+                    if !refs.contains(&id) {
+                        refs.push(id);
+                    }
+                }
+            }
             // Could be a doc attribute or decorator, check children
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
@@ -1405,6 +1446,9 @@ fn extract_refs_from_comment_text(source: &str, node: Node, refs: &mut Vec<RuleI
 /// Extract requirement IDs from comment text
 fn find_req_refs(text: &str) -> Vec<RuleId> {
     let mut refs = Vec::new();
+    if !text.contains('[') {
+        return refs;
+    }
     let code_mask = crate::markdown::markdown_code_mask(text);
     let mut chars = text.char_indices().peekable();
 
@@ -1414,8 +1458,8 @@ fn find_req_refs(text: &str) -> Vec<RuleId> {
         }
         if ch == '[' {
             // Try to parse a requirement reference
-            if let Some(req_id) = try_parse_req_ref(&mut chars) {
-                refs.push(req_id);
+            if let Some(req_ids) = try_parse_req_ref(&mut chars) {
+                refs.extend(req_ids);
             }
         }
     }
@@ -1438,6 +1482,10 @@ pub struct FullReqRef {
     pub byte_offset: usize,
     /// Byte length of the reference
     pub byte_length: usize,
+    /// Note trailing the rule ID
+    pub note: Option<String>,
+    /// Marked `coverage=partial`
+    pub partial: bool,
 }
 
 impl RefLocation {
@@ -1446,6 +1494,8 @@ impl RefLocation {
             prefix,
             verb,
             req_id,
+            note: None,
+            partial: false,
             line: self.line().as_usize(),
             byte_offset: self.span().offset().as_usize(),
             byte_length: self.span().length().as_usize(),
@@ -1490,9 +1540,37 @@ pub fn extract_refs(path: &Path, source: &str) -> Vec<FullReqRef> {
 
 /// Extract all requirement references and malformed-reference warnings.
 pub fn extract_refs_with_warnings(path: &Path, source: &str) -> ExtractedRefs {
+    extract_refs_with_verbs(path, source, &[])
+}
+
+/// Like [`extract_refs_with_warnings`], also keeping references that use one
+/// of the given custom verbs.
+pub fn extract_refs_with_verbs(
+    path: &Path,
+    source: &str,
+    custom_verbs: &[String],
+) -> ExtractedRefs {
+    let mut extracted = extract_all_refs(path, source);
+    extracted
+        .references
+        .retain(|r| BUILTIN_VERBS.contains(&r.verb.as_str()) || custom_verbs.contains(&r.verb));
+    extracted
+}
+
+/// Verbs recognized without any configuration.
+const BUILTIN_VERBS: [&str; 5] = ["impl", "verify", "define", "depends", "related"];
+
+/// Whether `word` has the shape of a verb: lowercase ASCII letters and dashes.
+fn is_verb_like(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_lowercase())
+        && word.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+}
+
+fn extract_all_refs(path: &Path, source: &str) -> ExtractedRefs {
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
 
     let language = match ext {
+        "tla" => return extract_tla_refs(source),
         "rs" => arborium_rust::language(),
         "swift" => arborium_swift::language(),
         "go" => arborium_go::language(),
@@ -1541,7 +1619,7 @@ pub fn extract_refs_with_warnings(path: &Path, source: &str) -> ExtractedRefs {
     let mut refs = Vec::new();
     let mut warnings = Vec::new();
     let mut ignore_state = IgnoreState::default();
-    extract_refs_recursive(
+    extract_refs_from_tree(
         source,
         tree.root_node(),
         &mut refs,
@@ -1555,14 +1633,89 @@ pub fn extract_refs_with_warnings(path: &Path, source: &str) -> ExtractedRefs {
     }
 }
 
+/// Extract references from the comments of a TLA+ module: `\*` line
+/// comments and `(* *)` block comments, which may nest. There is no
+/// tree-sitter grammar for TLA+, so comments are found by scanning; strings
+/// are skipped so that `"(*"` does not open a comment.
+///
+/// r[impl ref.comments.tla]
+fn extract_tla_refs(source: &str) -> ExtractedRefs {
+    let file_code_mask = crate::markdown::compute_doc_comment_code_mask(source);
+    let mut refs = Vec::new();
+    let mut warnings = Vec::new();
+    let mut ignore_state = IgnoreState::default();
+    let mut comment = |start: usize, end: usize| {
+        let text = &source[start..end];
+        let line = LineNumber::from_zero_based(source[..start].matches('\n').count());
+        if check_ignore_directives(text, line, &mut ignore_state) {
+            extract_full_refs_from_text(
+                text,
+                line,
+                ByteOffset::from_usize(start),
+                &file_code_mask,
+                &mut refs,
+                &mut warnings,
+            );
+        }
+    };
+
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'"', _) => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            (b'\\', Some(b'*')) => {
+                let end = source[i..].find('\n').map_or(source.len(), |n| i + n);
+                comment(i, end);
+                i = end;
+            }
+            (b'(', Some(b'*')) => {
+                let start = i;
+                let mut depth = 0;
+                while i < bytes.len() {
+                    if bytes[i..].starts_with(b"(*") {
+                        depth += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*)") {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                // One line at a time, so references keep their own line.
+                let mut line_start = start;
+                for line in source[start..i.min(source.len())].split_inclusive('\n') {
+                    comment(line_start, line_start + line.trim_end().len());
+                    line_start += line.len();
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    ExtractedRefs {
+        references: refs,
+        warnings,
+    }
+}
+
 /// State for tracking ignore directives across comment nodes.
 ///
 /// r[impl ref.ignore.prefix]
 #[derive(Default)]
 struct IgnoreState {
-    /// Skip the next line (set by @tracey:ignore-next-line)
+    /// Skip the next line (set by the `ignore-next-line` directive)
     ignore_next_line: Option<LineNumber>,
-    /// Currently inside an ignore block (set by @tracey:ignore-start)
+    /// Currently inside an ignore block (set by the `ignore-start` directive)
     /// r[impl ref.ignore.block]
     in_ignore_block: bool,
 }
@@ -1609,7 +1762,7 @@ fn check_ignore_directives(text: &str, line: LineNumber, state: &mut IgnoreState
     true
 }
 
-fn extract_refs_recursive(
+fn extract_refs_from_tree(
     source: &str,
     node: Node,
     refs: &mut Vec<FullReqRef>,
@@ -1617,42 +1770,53 @@ fn extract_refs_recursive(
     ignore_state: &mut IgnoreState,
     file_code_mask: &[bool],
 ) {
-    // Check if this is a comment node
-    // Different languages and comment styles:
-    // - Rust: line_comment (//), block_comment (/* */),
-    //         line_outer_doc_comment (///), line_inner_doc_comment (//!),
-    //         block_outer_doc_comment (/** */), block_inner_doc_comment (/*! */)
-    // - Swift/Go/TypeScript: comment
-    // - Python: comment
-    let is_comment = matches!(
-        node.kind(),
-        "line_comment"
-            | "block_comment"
-            | "comment"
-            | "multiline_comment"
-            | "bracket_comment"
-            | "documentation_comment"
-            | "line_outer_doc_comment"
-            | "line_inner_doc_comment"
-            | "block_outer_doc_comment"
-            | "block_inner_doc_comment"
-    );
+    // Pre-order walk with an explicit stack, so deeply nested trees cannot
+    // overflow the call stack (see `extract_units_from_tree`).
+    let mut stack = vec![node];
+    let mut cursor = node.walk();
+    while let Some(node) = stack.pop() {
+        // Check if this is a comment node
+        // Different languages and comment styles:
+        // - Rust: line_comment (//), block_comment (/* */),
+        //         line_outer_doc_comment (///), line_inner_doc_comment (//!),
+        //         block_outer_doc_comment (/** */), block_inner_doc_comment (/*! */)
+        // - Swift/Go/TypeScript: comment
+        // - Python: comment
+        let is_comment = matches!(
+            node.kind(),
+            "line_comment"
+                | "block_comment"
+                | "comment"
+                | "multiline_comment"
+                | "bracket_comment"
+                | "documentation_comment"
+                | "line_outer_doc_comment"
+                | "line_inner_doc_comment"
+                | "block_outer_doc_comment"
+                | "block_inner_doc_comment"
+        );
 
-    if is_comment {
-        let text = &source[node.byte_range()];
-        let line = LineNumber::from_zero_based(node.start_position().row);
-        let base_offset = ByteOffset::from_usize(node.start_byte());
+        if is_comment {
+            let text = &source[node.byte_range()];
+            let line = LineNumber::from_zero_based(node.start_position().row);
+            let base_offset = ByteOffset::from_usize(node.start_byte());
 
-        // Check ignore directives and determine if we should extract refs
-        if check_ignore_directives(text, line, ignore_state) {
-            extract_full_refs_from_text(text, line, base_offset, file_code_mask, refs, warnings);
+            // Check ignore directives and determine if we should extract refs
+            if check_ignore_directives(text, line, ignore_state) {
+                extract_full_refs_from_text(
+                    text,
+                    line,
+                    base_offset,
+                    file_code_mask,
+                    refs,
+                    warnings,
+                );
+            }
         }
-    }
 
-    // Recurse into children
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        extract_refs_recursive(source, child, refs, warnings, ignore_state, file_code_mask);
+        let first_child = stack.len();
+        stack.extend(node.children(&mut cursor));
+        stack[first_child..].reverse();
     }
 }
 
@@ -1665,6 +1829,11 @@ fn extract_full_refs_from_text(
     refs: &mut Vec<FullReqRef>,
     warnings: &mut Vec<FullReqRefWarning>,
 ) {
+    // Every reference has a bracket or is an `@relation`; most comments
+    // have neither, and can skip the markdown parse below.
+    if !text.contains('[') && !text.contains("@relation") {
+        return;
+    }
     let code_mask = crate::markdown::markdown_code_mask(text);
     extract_full_relation_annotations_from_text(
         text,
@@ -1696,21 +1865,23 @@ fn extract_full_refs_from_text(
                 prev_ch = Some(ch);
                 continue;
             }
+            // The prefix is ASCII, sliced out of `text` rather than copied
+            // until a reference is found.
             let prefix_start = start_idx;
-            let mut prefix = String::new();
-            prefix.push(ch);
+            let mut prefix_end = start_idx + 1;
 
             // Continue reading prefix
-            while let Some(&(_, next_ch)) = chars.peek() {
+            while let Some(&(idx, next_ch)) = chars.peek() {
                 if next_ch == '[' {
                     break;
                 } else if next_ch.is_ascii_lowercase() || next_ch.is_ascii_digit() {
-                    prefix.push(next_ch);
+                    prefix_end = idx + 1;
                     chars.next();
                 } else {
                     break;
                 }
             }
+            let prefix = &text[prefix_start..prefix_end];
 
             // Check for '['
             if chars.peek().map(|(_, c)| *c) != Some('[') {
@@ -1722,7 +1893,9 @@ fn extract_full_refs_from_text(
             match try_parse_full_ref(&mut chars) {
                 Some(ParsedFullRef::Parsed {
                     verb,
-                    req_id,
+                    req_ids,
+                    note,
+                    partial,
                     end_idx,
                 }) => {
                     let location = RefLocation::from_relative_indices(
@@ -1731,7 +1904,13 @@ fn extract_full_refs_from_text(
                         prefix_start,
                         end_idx,
                     );
-                    refs.push(location.into_full_ref(prefix, verb, req_id));
+                    for req_id in req_ids {
+                        refs.push(FullReqRef {
+                            note: note.clone(),
+                            partial,
+                            ..location.into_full_ref(prefix.to_string(), verb.clone(), req_id)
+                        });
+                    }
                 }
                 Some(ParsedFullRef::Malformed { end_idx }) => {
                     let location = RefLocation::from_relative_indices(
@@ -1822,7 +2001,10 @@ fn extract_full_relation_annotations_from_text(
 enum ParsedFullRef {
     Parsed {
         verb: String,
-        req_id: RuleId,
+        /// Several when a `{a,b}` group was expanded
+        req_ids: Vec<RuleId>,
+        note: Option<String>,
+        partial: bool,
         end_idx: usize,
     },
     Malformed {
@@ -1830,9 +2012,9 @@ enum ParsedFullRef {
     },
 }
 
-// r[impl ref.syntax.req-id]
+// r[impl ref.syntax.req-id+3]
 fn try_parse_full_ref(
-    chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)>>,
+    chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)> + Clone>,
 ) -> Option<ParsedFullRef> {
     // First char must be an ASCII letter. Case is preserved (StrictDoc UIDs).
     let first_char = chars.peek().map(|(_, c)| *c)?;
@@ -1850,7 +2032,7 @@ fn try_parse_full_ref(
         end_idx = idx;
         if c == ']' || c == ' ' {
             break;
-        } else if c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '+' {
+        } else if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+') {
             first_word.push(c);
             chars.next();
         } else {
@@ -1861,9 +2043,11 @@ fn try_parse_full_ref(
     // Check what follows
     match chars.peek().map(|(_, c)| *c) {
         Some(' ') => {
-            // Might be [verb req.id]
-            let verbs = ["impl", "verify", "define", "depends", "related"];
-            if verbs.contains(&first_word.as_str()) {
+            // Might be [verb req.id]. Any lowercase word is accepted here so
+            // that configured custom verbs are found; callers drop the verbs
+            // they don't know.
+            if is_verb_like(&first_word) {
+                let builtin = BUILTIN_VERBS.contains(&first_word.as_str());
                 let verb = first_word;
                 chars.next(); // consume space
 
@@ -1879,32 +2063,61 @@ fn try_parse_full_ref(
                     }
                 }
 
+                let mut note = None;
+                let mut partial = false;
                 while let Some(&(idx, c)) = chars.peek() {
                     end_idx = idx;
                     if c == ']' {
                         chars.next();
                         break;
                     } else if c.is_ascii_alphanumeric()
-                        || c == '-'
-                        || c == '_'
-                        || c == '+'
-                        || c == '.'
+                        || matches!(c, '-' | '_' | '+' | '.' | '*' | '{' | '}')
+                        || crate::lexer::continues_rule_list(&req_id, c)
                     {
                         req_id.push(c);
                         chars.next();
                     } else {
-                        return None;
+                        // r[impl ref.syntax.region]
+                        if let Some((_, close_idx)) = crate::lexer::take_region_marker(chars) {
+                            end_idx = close_idx;
+                            break;
+                        }
+                        // r[impl ref.coverage.partial]
+                        partial = crate::lexer::take_partial(chars);
+                        // r[impl ref.syntax.note]
+                        if let Some((text, close_idx)) = crate::lexer::take_note(chars) {
+                            note = Some(text);
+                            end_idx = close_idx;
+                        } else if partial && let Some(&(idx, ']')) = chars.peek() {
+                            chars.next();
+                            end_idx = idx;
+                        } else {
+                            return None;
+                        }
+                        break;
                     }
                 }
 
-                if is_valid_req_id(&req_id) {
-                    return parse_rule_id(&req_id).map(|parsed| ParsedFullRef::Parsed {
+                // r[impl ref.syntax.patterns]
+                // r[impl ref.syntax.list]
+                let req_ids: Option<Vec<RuleId>> =
+                    crate::expand_rule_list(&req_id).and_then(|ids| {
+                        ids.iter()
+                            .map(|id| is_valid_req_id(id).then(|| parse_rule_id(id)).flatten())
+                            .collect()
+                    });
+                if let Some(req_ids) = req_ids {
+                    return Some(ParsedFullRef::Parsed {
                         verb,
-                        req_id: parsed,
+                        req_ids,
+                        note,
+                        partial,
                         end_idx,
                     });
                 }
-                return Some(ParsedFullRef::Malformed { end_idx });
+                // Only built-in verbs make prose like `[payload Bytes]` a
+                // malformed reference.
+                return builtin.then_some(ParsedFullRef::Malformed { end_idx });
             }
             None
         }
@@ -1914,7 +2127,9 @@ fn try_parse_full_ref(
             if is_valid_req_id(&first_word) {
                 parse_rule_id(&first_word).map(|parsed| ParsedFullRef::Parsed {
                     verb: "impl".to_string(),
-                    req_id: parsed,
+                    req_ids: vec![parsed],
+                    note: None,
+                    partial: false,
                     end_idx,
                 })
             } else {
@@ -1926,8 +2141,8 @@ fn try_parse_full_ref(
 }
 
 fn try_parse_req_ref(
-    chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)>>,
-) -> Option<RuleId> {
+    chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)> + Clone>,
+) -> Option<Vec<RuleId>> {
     // First char must be an ASCII letter. Case is preserved.
     let first_char = chars.peek().map(|(_, c)| *c)?;
     if !first_char.is_ascii_alphabetic() {
@@ -1942,7 +2157,7 @@ fn try_parse_req_ref(
     while let Some(&(_, c)) = chars.peek() {
         if c == ']' || c == ' ' {
             break;
-        } else if c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '+' {
+        } else if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+') {
             first_word.push(c);
             chars.next();
         } else {
@@ -1975,17 +2190,34 @@ fn try_parse_req_ref(
                     if c == ']' {
                         chars.next();
                         break;
-                    } else if c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '.' {
+                    } else if c.is_ascii_alphanumeric()
+                        || matches!(c, '-' | '+' | '.' | '*' | '{' | '}')
+                        || crate::lexer::continues_rule_list(&req_id, c)
+                    {
                         req_id.push(c);
                         chars.next();
                     } else {
-                        return None;
+                        // The code after an `end` marker is outside the region.
+                        match crate::lexer::take_region_marker(chars) {
+                            Some((crate::lexer::RegionMarker::End, _)) => return None,
+                            Some((crate::lexer::RegionMarker::Begin, _)) => {}
+                            None => {
+                                let partial = crate::lexer::take_partial(chars);
+                                if crate::lexer::take_note(chars).is_none()
+                                    && !(partial && chars.next_if(|&(_, c)| c == ']').is_some())
+                                {
+                                    return None;
+                                }
+                            }
+                        }
+                        break;
                     }
                 }
 
-                if is_valid_req_id(&req_id) {
-                    return parse_rule_id(&req_id);
-                }
+                return crate::expand_rule_list(&req_id)?
+                    .iter()
+                    .map(|id| is_valid_req_id(id).then(|| parse_rule_id(id)).flatten())
+                    .collect();
             }
             None
         }
@@ -1993,7 +2225,7 @@ fn try_parse_req_ref(
             chars.next(); // consume ]
             // [req.id] format
             if is_valid_req_id(&first_word) {
-                parse_rule_id(&first_word)
+                parse_rule_id(&first_word).map(|id| vec![id])
             } else {
                 None
             }
@@ -2006,7 +2238,12 @@ fn is_valid_req_id(req_id: &str) -> bool {
     let Some(parsed) = parse_rule_id(req_id) else {
         return false;
     };
+    // A wildcard stands for whole segments only
     !parsed.base.ends_with('.')
+        && parsed
+            .base
+            .split('.')
+            .all(|segment| segment == "*" || !segment.contains('*'))
 }
 
 #[cfg(test)]
@@ -2042,6 +2279,75 @@ fn do_thing() {}
         assert_eq!(refs[0].verb, "impl");
     }
 
+    // r[verify ref.syntax.req-id+3]
+    #[test]
+    fn test_extract_refs_with_underscores() {
+        let source = r#"
+// r[user_profile.update_email]
+// r[verify user_profile.delete]
+fn do_thing() {}
+"#;
+        let refs = extract_refs(Path::new("test.rs"), source);
+        assert_eq!(refs.len(), 2, "Expected 2 refs, got {:?}", refs);
+        assert_eq!(refs[0].req_id, "user_profile.update_email");
+        assert_eq!(refs[0].verb, "impl");
+        assert_eq!(refs[1].req_id, "user_profile.delete");
+        assert_eq!(refs[1].verb, "verify");
+    }
+
+    // r[verify ref.syntax.region]
+    #[test]
+    fn test_region_claims_enclosed_code_units() {
+        let source = "\
+fn before() {}
+
+// r[impl auth.login begin]
+fn first() {}
+
+fn second() {}
+// r[impl auth.login end]
+
+fn after() {}
+";
+        let path = Path::new("test.rs");
+        let reqs = crate::Reqs::extract_from_content(path, source);
+        assert_eq!(reqs.len(), 1, "{:?}", reqs.references);
+        let mut units = extract(path, source).units;
+        attribute_regions(&mut units, &reqs.references);
+        let covered: Vec<_> = units
+            .iter()
+            .filter(|u| !u.req_refs.is_empty())
+            .filter_map(|u| u.name.as_deref())
+            .collect();
+        assert_eq!(covered, ["first", "second"]);
+    }
+
+    // r[verify ref.attributes]
+    #[test]
+    fn test_tracey_attribute_covers_its_item() {
+        let source = "\
+#[tracey::impls(\"channel.id.allocation\", \"channel.id.parity\")]
+fn allocate_id() {}
+
+fn other() {}
+";
+        let units = extract(Path::new("test.rs"), source).units;
+        let unit = units
+            .iter()
+            .find(|u| u.name.as_deref() == Some("allocate_id"))
+            .unwrap();
+        assert_eq!(
+            unit.req_refs,
+            ["channel.id.allocation", "channel.id.parity"]
+        );
+        assert_eq!(unit.start_line, 1);
+        let other = units
+            .iter()
+            .find(|u| u.name.as_deref() == Some("other"))
+            .unwrap();
+        assert!(other.req_refs.is_empty());
+    }
+
     #[test]
     fn test_extract_refs_byte_span_uses_inclusive_end() {
         let source = "// r[foo.bar]\n";
@@ -2137,6 +2443,10 @@ fn uncovered() {}
             find_req_refs("// r[impl auth.login+2] and r[verify auth.logout+3]"),
             vec![rid("auth.login+2"), rid("auth.logout+3")]
         );
+        assert_eq!(
+            find_req_refs("// r[verify frame.{header,payload}.parse]"),
+            vec![rid("frame.header.parse"), rid("frame.payload.parse")]
+        );
         assert!(find_req_refs("// no refs here").is_empty());
         assert!(find_req_refs("// [invalid.]").is_empty()); // trailing dot
         assert!(find_req_refs("// r[impl auth.login+]").is_empty());
@@ -3298,6 +3608,32 @@ endmacro()
         );
     }
 
+    // r[verify ref.comments.tla]
+    #[test]
+    fn test_tla_refs() {
+        let source = r#"---- MODULE Framing ----
+\* r[verify frame.header]
+Msg == "(* r[verify not.a.comment] *)"
+(* Invariant: (* nested *)
+   r[verify frame.max-length] *)
+Inv == TRUE
+====
+"#;
+        let refs = extract_refs(Path::new("Framing.tla"), source);
+        let found: Vec<(String, usize)> = refs
+            .iter()
+            .map(|r| (r.req_id.to_string(), r.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("frame.header".to_string(), 2),
+                ("frame.max-length".to_string(), 5)
+            ]
+        );
+        assert!(refs.iter().all(|r| r.verb == "verify"));
+    }
+
     #[test]
     fn test_ocaml_code_units() {
         let source = r#"(* r[impl ocaml.feature] *)
@@ -4,6 +4,204 @@ use crate::RuleId;
 use crate::lexer::{RefVerb, ReqReference, Reqs};
 use facet::Facet;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Whether a spec's coverage counts toward pass/fail thresholds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Facet)]
+#[repr(u8)]
+pub enum Enforcement {
+    /// Coverage gaps fail thresholds (the default).
+    #[default]
+    Enforced,
+    /// Coverage is reported but never fails a threshold, e.g. for a vendored
+    /// third-party spec. References are still validated.
+    Advisory,
+}
+
+impl Enforcement {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Enforced => "enforced",
+            Self::Advisory => "advisory",
+        }
+    }
+
+    pub fn is_advisory(&self) -> bool {
+        *self == Self::Advisory
+    }
+}
+
+impl std::str::FromStr for Enforcement {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "enforced" => Ok(Self::Enforced),
+            "advisory" => Ok(Self::Advisory),
+            _ => Err(format!("expected `enforced` or `advisory`, got `{value}`")),
+        }
+    }
+}
+
+impl std::fmt::Display for Enforcement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How `impl` references in test files (`test_include`) count toward coverage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Facet)]
+#[repr(u8)]
+pub enum TestImpls {
+    /// They count toward implementation coverage like any other (the default).
+    #[default]
+    Impl,
+    /// They only count toward verification coverage, so a rule whose only
+    /// `impl` reference is in a test does not look implemented.
+    Verify,
+}
+
+impl TestImpls {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Impl => "impl",
+            Self::Verify => "verify",
+        }
+    }
+}
+
+impl std::str::FromStr for TestImpls {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "impl" => Ok(Self::Impl),
+            "verify" => Ok(Self::Verify),
+            _ => Err(format!("expected `impl` or `verify`, got `{value}`")),
+        }
+    }
+}
+
+impl std::fmt::Display for TestImpls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How references with a given verb count toward coverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[repr(u8)]
+pub enum VerbRole {
+    /// Counts toward implementation coverage.
+    Impl,
+    /// Counts toward verification coverage.
+    Verify,
+    /// Listed as a dependency of the rule.
+    Depends,
+    /// Counts toward documentation coverage.
+    Docs,
+    /// Shown alongside the rule but never counts as coverage.
+    Informational,
+}
+
+impl VerbRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Impl => "impl",
+            Self::Verify => "verify",
+            Self::Depends => "depends",
+            Self::Docs => "docs",
+            Self::Informational => "informational",
+        }
+    }
+}
+
+impl std::str::FromStr for VerbRole {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "impl" => Ok(Self::Impl),
+            "verify" => Ok(Self::Verify),
+            "depends" => Ok(Self::Depends),
+            "docs" => Ok(Self::Docs),
+            "informational" => Ok(Self::Informational),
+            _ => Err(format!(
+                "expected `impl`, `verify`, `depends`, `docs` or `informational`, got `{value}`"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for VerbRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The coverage role of every verb: built-in defaults plus overrides and
+/// custom verbs from the `verbs` config block.
+///
+/// r[impl config.verbs.roles]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerbRoles {
+    roles: HashMap<String, VerbRole>,
+}
+
+impl Default for VerbRoles {
+    fn default() -> Self {
+        let roles = [
+            ("define", VerbRole::Impl),
+            ("impl", VerbRole::Impl),
+            ("verify", VerbRole::Verify),
+            ("depends", VerbRole::Depends),
+            ("related", VerbRole::Depends),
+        ]
+        .into_iter()
+        .map(|(verb, role)| (verb.to_string(), role))
+        .collect();
+        Self { roles }
+    }
+}
+
+impl VerbRoles {
+    /// Set the role of a verb, declaring it if it is not built in.
+    pub fn with(mut self, verb: impl Into<String>, role: VerbRole) -> Self {
+        self.roles.insert(verb.into(), role);
+        self
+    }
+
+    /// Role of `verb`; undeclared custom verbs are informational.
+    pub fn role(&self, verb: &RefVerb) -> VerbRole {
+        self.roles
+            .get(verb.as_str())
+            .copied()
+            .unwrap_or(VerbRole::Informational)
+    }
+
+    /// Every verb with its role, sorted by name.
+    pub fn iter(&self) -> Vec<(&str, VerbRole)> {
+        let mut verbs: Vec<(&str, VerbRole)> = self
+            .roles
+            .iter()
+            .map(|(verb, role)| (verb.as_str(), *role))
+            .collect();
+        verbs.sort_by_key(|(verb, _)| *verb);
+        verbs
+    }
+
+    /// Declared verbs that are not built in, sorted by name.
+    pub fn custom_verbs(&self) -> Vec<String> {
+        let mut verbs: Vec<String> = self
+            .roles
+            .keys()
+            .filter(|verb| RefVerb::parse(verb).is_none())
+            .cloned()
+            .collect();
+        verbs.sort();
+        verbs
+    }
+}
 
 /// Coverage analysis results for a single spec
 #[derive(Debug, Facet)]
@@ -11,15 +209,34 @@ pub struct CoverageReport {
     /// Name of the spec
     pub spec_name: String,
 
+    /// Whether this spec's coverage counts toward thresholds
+    pub enforcement: Enforcement,
+
+    /// How `impl` references in test files count
+    pub test_impls: TestImpls,
+
     /// Total number of rules in the spec
     pub total_rules: usize,
 
     /// Rules that are referenced at least once
     pub covered_rules: HashSet<RuleId>,
 
+    /// Covered rules whose references are all marked `coverage=partial`
+    pub partial_rules: HashSet<RuleId>,
+
+    /// How much a partially covered rule counts toward the coverage
+    /// percentage, from 0 to 1
+    pub partial_weight: f64,
+
+    /// Rules whose only references point at an older version of the rule
+    pub stale_rules: HashSet<RuleId>,
+
     /// Rules that have no references (orphaned)
     pub uncovered_rules: HashSet<RuleId>,
 
+    /// References to an older version of a rule in the spec
+    pub stale_references: Vec<ReqReference>,
+
     /// References to rules that don't exist in the spec
     pub invalid_references: Vec<ReqReference>,
 
@@ -30,10 +247,14 @@ pub struct CoverageReport {
     pub references_by_verb: HashMap<RefVerb, HashMap<RuleId, Vec<ReqReference>>>,
 }
 
+/// Weight of a partially covered rule unless configured otherwise
+pub const DEFAULT_PARTIAL_WEIGHT: f64 = 0.5;
+
 impl CoverageReport {
     /// Compute coverage from rules and a set of known rule IDs
     ///
     /// r[impl coverage.compute.covered+2]
+    /// r[impl coverage.compute.stale]
     /// r[impl coverage.compute.uncovered]
     /// r[impl coverage.compute.invalid]
     /// r[impl validation.broken-refs]
@@ -41,9 +262,27 @@ impl CoverageReport {
         spec_name: impl Into<String>,
         known_rule_ids: &HashSet<RuleId>,
         reqs: &Reqs,
+    ) -> Self {
+        Self::compute_with_roles(spec_name, known_rule_ids, reqs, &VerbRoles::default())
+    }
+
+    /// Compute coverage, ignoring references whose verb is informational
+    /// when deciding which rules are covered
+    pub fn compute_with_roles(
+        spec_name: impl Into<String>,
+        known_rule_ids: &HashSet<RuleId>,
+        reqs: &Reqs,
+        roles: &VerbRoles,
     ) -> Self {
         let spec_name = spec_name.into();
+        let current: HashMap<&str, &RuleId> = known_rule_ids
+            .iter()
+            .map(|id| (id.base.as_str(), id))
+            .collect();
         let mut covered_rules = HashSet::new();
+        let mut fully_covered = HashSet::new();
+        let mut stale_rules = HashSet::new();
+        let mut stale_references = Vec::new();
         let mut invalid_references = Vec::new();
         let mut references_by_rule: HashMap<RuleId, Vec<ReqReference>> = HashMap::new();
         let mut references_by_verb: HashMap<RefVerb, HashMap<RuleId, Vec<ReqReference>>> =
@@ -51,7 +290,12 @@ impl CoverageReport {
 
         for reference in &reqs.references {
             if known_rule_ids.contains(&reference.req_id) {
-                covered_rules.insert(reference.req_id.clone());
+                if roles.role(&reference.verb) != VerbRole::Informational {
+                    covered_rules.insert(reference.req_id.clone());
+                    if !reference.partial {
+                        fully_covered.insert(reference.req_id.clone());
+                    }
+                }
                 references_by_rule
                     .entry(reference.req_id.clone())
                     .or_default()
@@ -59,24 +303,45 @@ impl CoverageReport {
 
                 // Also group by verb
                 references_by_verb
-                    .entry(reference.verb)
+                    .entry(reference.verb.clone())
                     .or_default()
                     .entry(reference.req_id.clone())
                     .or_default()
                     .push(reference.clone());
+            } else if let Some(rule_id) = current
+                .get(reference.req_id.base.as_str())
+                .filter(|id| reference.req_id.version < id.version)
+            {
+                if roles.role(&reference.verb) != VerbRole::Informational {
+                    stale_rules.insert((*rule_id).clone());
+                }
+                stale_references.push(reference.clone());
             } else {
                 invalid_references.push(reference.clone());
             }
         }
 
-        let uncovered_rules: HashSet<RuleId> =
-            known_rule_ids.difference(&covered_rules).cloned().collect();
+        stale_rules.retain(|id| !covered_rules.contains(id));
+        // r[impl coverage.partial]
+        let partial_rules: HashSet<RuleId> =
+            covered_rules.difference(&fully_covered).cloned().collect();
+        let uncovered_rules: HashSet<RuleId> = known_rule_ids
+            .iter()
+            .filter(|id| !covered_rules.contains(*id) && !stale_rules.contains(*id))
+            .cloned()
+            .collect();
 
         CoverageReport {
             spec_name,
+            enforcement: Enforcement::default(),
+            test_impls: TestImpls::default(),
             total_rules: known_rule_ids.len(),
             covered_rules,
+            partial_rules,
+            partial_weight: DEFAULT_PARTIAL_WEIGHT,
+            stale_rules,
             uncovered_rules,
+            stale_references,
             invalid_references,
             references_by_rule,
             references_by_verb,
@@ -90,11 +355,244 @@ impl CoverageReport {
         if self.total_rules == 0 {
             return 100.0;
         }
-        (self.covered_rules.len() as f64 / self.total_rules as f64) * 100.0
+        let partial = self.partial_rules.len() as f64;
+        let covered = self.covered_rules.len() as f64 - partial + partial * self.partial_weight;
+        (covered / self.total_rules as f64) * 100.0
+    }
+
+    /// Set how much a partially covered rule counts toward the coverage
+    /// percentage, from 0 to 1
+    ///
+    /// r[impl coverage.partial]
+    pub fn with_partial_weight(mut self, weight: f64) -> Self {
+        self.partial_weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set how this report counts toward thresholds
+    pub fn with_enforcement(mut self, enforcement: Enforcement) -> Self {
+        self.enforcement = enforcement;
+        self
+    }
+
+    /// Set how `impl` references in files for which `is_test` holds count.
+    /// With [`TestImpls::Verify`] they are regrouped under `verify`.
+    ///
+    /// r[impl coverage.test-impls]
+    pub fn with_test_impls(
+        mut self,
+        test_impls: TestImpls,
+        is_test: impl Fn(&Path) -> bool,
+    ) -> Self {
+        self.test_impls = test_impls;
+        if test_impls == TestImpls::Impl {
+            return self;
+        }
+        let Some(impls) = self.references_by_verb.remove(&RefVerb::Impl) else {
+            return self;
+        };
+        for (rule_id, references) in impls {
+            let (tests, sources): (Vec<_>, Vec<_>) =
+                references.into_iter().partition(|r| is_test(&r.file));
+            if !sources.is_empty() {
+                self.references_by_verb
+                    .entry(RefVerb::Impl)
+                    .or_default()
+                    .insert(rule_id.clone(), sources);
+            }
+            if !tests.is_empty() {
+                self.references_by_verb
+                    .entry(RefVerb::Verify)
+                    .or_default()
+                    .entry(rule_id)
+                    .or_default()
+                    .extend(tests);
+            }
+        }
+        self
     }
 
     /// Whether the coverage is "passing" (no invalid refs, >= threshold coverage)
+    ///
+    /// Advisory specs pass regardless of coverage, but invalid references
+    /// still fail them.
+    ///
+    /// r[impl coverage.enforcement]
     pub fn is_passing(&self, threshold: f64) -> bool {
-        self.invalid_references.is_empty() && self.coverage_percent() >= threshold
+        self.invalid_references.is_empty()
+            && (self.enforcement.is_advisory() || self.coverage_percent() >= threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify coverage.enforcement]
+    #[test]
+    fn advisory_reports_pass_regardless_of_coverage() {
+        let known: HashSet<RuleId> = ["rfc.a", "rfc.b"]
+            .into_iter()
+            .map(|id| crate::parse_rule_id(id).unwrap())
+            .collect();
+        let reqs = Reqs::extract_from_content(Path::new("lib.rs"), "// r[impl rfc.a]\n");
+        let report = CoverageReport::compute("rfc", &known, &reqs);
+        assert!(!report.is_passing(100.0));
+
+        let report = report.with_enforcement(Enforcement::Advisory);
+        assert!(report.is_passing(100.0));
+
+        let reqs = Reqs::extract_from_content(Path::new("lib.rs"), "// r[impl rfc.missing]\n");
+        let report =
+            CoverageReport::compute("rfc", &known, &reqs).with_enforcement(Enforcement::Advisory);
+        assert!(
+            !report.is_passing(0.0),
+            "invalid references still fail advisory specs"
+        );
+    }
+
+    // r[verify coverage.compute.stale]
+    #[test]
+    fn older_versions_are_stale_not_invalid() {
+        let known: HashSet<RuleId> = ["rfc.a+2", "rfc.b+3", "rfc.c"]
+            .into_iter()
+            .map(|id| crate::parse_rule_id(id).unwrap())
+            .collect();
+        let reqs = Reqs::extract_from_content(
+            Path::new("lib.rs"),
+            "// r[impl rfc.a]
+// r[impl rfc.b+2]
+// r[verify rfc.b+3]
+// r[impl rfc.c+2]
+",
+        );
+        let report = CoverageReport::compute("rfc", &known, &reqs);
+
+        let id = |s: &str| crate::parse_rule_id(s).unwrap();
+        assert_eq!(report.stale_rules, HashSet::from([id("rfc.a+2")]));
+        assert_eq!(report.covered_rules, HashSet::from([id("rfc.b+3")]));
+        assert_eq!(report.uncovered_rules, HashSet::from([id("rfc.c")]));
+        let stale: Vec<_> = report
+            .stale_references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        assert_eq!(stale, ["rfc.a", "rfc.b+2"]);
+        let invalid: Vec<_> = report
+            .invalid_references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        assert_eq!(invalid, ["rfc.c+2"], "newer than the spec is not stale");
+        assert!(!report.is_passing(0.0));
+    }
+
+    // r[verify config.verbs.roles]
+    #[test]
+    fn informational_verbs_do_not_cover() {
+        let known: HashSet<RuleId> = ["rfc.a", "rfc.b"]
+            .into_iter()
+            .map(|id| crate::parse_rule_id(id).unwrap())
+            .collect();
+        let reqs = Reqs::extract_from_content(
+            Path::new("lib.rs"),
+            "// r[impl rfc.a]\n// r[related rfc.b]\n",
+        );
+        let report = CoverageReport::compute("rfc", &known, &reqs);
+        assert_eq!(report.covered_rules.len(), 2);
+
+        let roles = VerbRoles::default().with("related", VerbRole::Informational);
+        let report = CoverageReport::compute_with_roles("rfc", &known, &reqs, &roles);
+        assert_eq!(report.covered_rules.len(), 1);
+        assert_eq!(report.references_by_rule.len(), 2);
+    }
+
+    // r[verify coverage.partial]
+    #[test]
+    fn partial_references_count_at_their_weight() {
+        let known: HashSet<RuleId> = ["rfc.a", "rfc.b", "rfc.c", "rfc.d"]
+            .into_iter()
+            .map(|id| crate::parse_rule_id(id).unwrap())
+            .collect();
+        let reqs = Reqs::extract_from_content(
+            Path::new("lib.rs"),
+            "// r[impl rfc.a]\n// r[impl rfc.b coverage=partial]\n\
+             // r[impl rfc.c coverage=partial]\n// r[impl rfc.c]\n",
+        );
+        let report = CoverageReport::compute("rfc", &known, &reqs);
+        assert_eq!(report.covered_rules.len(), 3);
+        let partial: Vec<_> = report
+            .partial_rules
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(partial, ["rfc.b"], "a full reference wins");
+        assert_eq!(report.coverage_percent(), 62.5);
+
+        let report = report.with_partial_weight(0.0);
+        assert_eq!(report.coverage_percent(), 50.0);
+    }
+
+    // r[verify coverage.test-impls]
+    #[test]
+    fn test_impls_can_count_only_as_verification() {
+        let known: HashSet<RuleId> = ["rfc.a", "rfc.b"]
+            .into_iter()
+            .map(|id| crate::parse_rule_id(id).unwrap())
+            .collect();
+        let mut reqs = Reqs::extract_from_content(Path::new("src/lib.rs"), "// r[impl rfc.a]\n");
+        reqs.references.extend(
+            Reqs::extract_from_content(
+                Path::new("tests/a.rs"),
+                "// r[impl rfc.a]\n// r[impl rfc.b]\n",
+            )
+            .references,
+        );
+        let is_test = |path: &Path| path.starts_with("tests");
+        let count = |report: &CoverageReport, verb: RefVerb| {
+            report
+                .references_by_verb
+                .get(&verb)
+                .map_or(0, |by_rule| by_rule.values().map(Vec::len).sum())
+        };
+
+        let report =
+            CoverageReport::compute("rfc", &known, &reqs).with_test_impls(TestImpls::Impl, is_test);
+        assert_eq!(count(&report, RefVerb::Impl), 3);
+
+        let report = CoverageReport::compute("rfc", &known, &reqs)
+            .with_test_impls(TestImpls::Verify, is_test);
+        assert_eq!(count(&report, RefVerb::Impl), 1);
+        assert_eq!(count(&report, RefVerb::Verify), 2);
+        let implemented: Vec<_> = report.references_by_verb[&RefVerb::Impl]
+            .keys()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(implemented, ["rfc.a"]);
+        assert_eq!(
+            report.covered_rules.len(),
+            2,
+            "tests still cover as verification"
+        );
+    }
+
+    // r[verify config.verbs.custom]
+    #[test]
+    fn custom_verbs_are_extracted_only_when_declared() {
+        let source = "// r[documents rfc.a]\n// r[impl rfc.b]\n";
+        let reqs = Reqs::extract_from_content(Path::new("lib.rs"), source);
+        assert_eq!(reqs.references.len(), 1);
+
+        let roles = VerbRoles::default().with("documents", VerbRole::Docs);
+        assert_eq!(roles.custom_verbs(), ["documents"]);
+        let reqs = Reqs::extract_from_content_with_verbs(
+            Path::new("lib.rs"),
+            source,
+            &roles.custom_verbs(),
+        );
+        assert_eq!(reqs.references.len(), 2);
+        let verb = &reqs.references[0].verb;
+        assert_eq!(*verb, RefVerb::Custom("documents".to_string()));
+        assert_eq!(roles.role(verb), VerbRole::Docs);
     }
 }
@@ -0,0 +1,188 @@
+//! Decoding of source files that are not valid UTF-8
+//!
+//! Legacy codebases contain Latin-1 files and Windows tools like to write
+//! UTF-16. Rather than dropping such files from extraction, they are decoded
+//! with a best guess and flagged with a warning.
+
+use crate::lexer::{ParseWarning, SourceSpan, WarningKind};
+use std::path::Path;
+
+/// Encoding a source file was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEncoding {
+    /// Valid UTF-8, taken as-is
+    Utf8,
+    /// UTF-16 little endian
+    Utf16Le,
+    /// UTF-16 big endian
+    Utf16Be,
+    /// Anything else: every byte is read as the Latin-1 code point of the
+    /// same value
+    Latin1,
+}
+
+impl SourceEncoding {
+    /// Human-readable name of the encoding
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceEncoding::Utf8 => "UTF-8",
+            SourceEncoding::Utf16Le => "UTF-16LE",
+            SourceEncoding::Utf16Be => "UTF-16BE",
+            SourceEncoding::Latin1 => "Latin-1",
+        }
+    }
+
+    /// Warning to attach to a file decoded from this encoding, or `None` for UTF-8.
+    ///
+    /// r[impl ref.encoding.warning]
+    pub fn warning(self, file: &Path) -> Option<ParseWarning> {
+        if self == SourceEncoding::Utf8 {
+            return None;
+        }
+        Some(ParseWarning {
+            file: file.to_path_buf(),
+            line: 1,
+            span: SourceSpan::new(0, 0),
+            kind: WarningKind::NonUtf8Encoding(self.as_str().to_string()),
+        })
+    }
+}
+
+impl std::fmt::Display for SourceEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Decode raw file bytes into text.
+///
+/// Valid UTF-8 is returned unchanged. UTF-16 is recognized by its byte order
+/// mark or, lacking one, by NUL bytes in alternating positions; unpaired
+/// surrogates become U+FFFD. Everything else is read as Latin-1, which maps
+/// every byte to a character, so ASCII reference markers are always found.
+///
+/// r[impl ref.encoding.fallback]
+pub fn decode_source(bytes: Vec<u8>) -> (String, SourceEncoding) {
+    if let Some(decoded) = decode_utf16(&bytes) {
+        return decoded;
+    }
+    match String::from_utf8(bytes) {
+        Ok(content) => (content, SourceEncoding::Utf8),
+        Err(err) => (latin1(err.as_bytes()), SourceEncoding::Latin1),
+    }
+}
+
+/// Decode `bytes` if they look like UTF-16.
+fn decode_utf16(bytes: &[u8]) -> Option<(String, SourceEncoding)> {
+    // Checked first: BOM-less UTF-16 of ASCII text is also valid UTF-8.
+    let (encoding, bom_len) = match bytes {
+        [0xFF, 0xFE, ..] => (SourceEncoding::Utf16Le, 2),
+        [0xFE, 0xFF, ..] => (SourceEncoding::Utf16Be, 2),
+        _ => (sniff_utf16(bytes)?, 0),
+    };
+    let units: Vec<u16> = bytes[bom_len..]
+        .chunks_exact(2)
+        .map(|pair| match encoding {
+            SourceEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+            _ => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect();
+    Some((String::from_utf16_lossy(&units), encoding))
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+/// Guess UTF-16 byte order for BOM-less input from where the NUL bytes are.
+///
+/// Source code is mostly ASCII, so in UTF-16 every other byte is NUL. Only
+/// the first few kilobytes are inspected.
+fn sniff_utf16(bytes: &[u8]) -> Option<SourceEncoding> {
+    let sample = &bytes[..bytes.len().min(4096)];
+    let pairs = sample.len() / 2;
+    if pairs == 0 {
+        return None;
+    }
+    let (mut even_nuls, mut odd_nuls) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        even_nuls += usize::from(pair[0] == 0);
+        odd_nuls += usize::from(pair[1] == 0);
+    }
+    // Require the NUL pattern in most pairs and almost never on the other side.
+    let mostly = |n: usize| n * 10 >= pairs * 7;
+    let rarely = |n: usize| n * 10 <= pairs;
+    if mostly(odd_nuls) && rarely(even_nuls) {
+        Some(SourceEncoding::Utf16Le)
+    } else if mostly(even_nuls) && rarely(odd_nuls) {
+        Some(SourceEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Read a file from disk, decoding it with [`decode_source`].
+pub fn read_source(path: &Path) -> std::io::Result<(String, SourceEncoding)> {
+    std::fs::read(path).map(decode_source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            if big_endian {
+                bytes.extend(unit.to_be_bytes());
+            } else {
+                bytes.extend(unit.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn utf8_is_untouched() {
+        let (content, encoding) = decode_source("// r[impl café.rule]\n".into());
+        assert_eq!(encoding, SourceEncoding::Utf8);
+        assert_eq!(content, "// r[impl café.rule]\n");
+        assert!(encoding.warning(Path::new("a.rs")).is_none());
+    }
+
+    #[test]
+    fn latin1_fallback_keeps_every_byte() {
+        let (content, encoding) = decode_source(b"// caf\xe9\n// r[impl a.b]\n".to_vec());
+        assert_eq!(encoding, SourceEncoding::Latin1);
+        assert_eq!(content, "// café\n// r[impl a.b]\n");
+        let warning = encoding.warning(Path::new("a.rs")).unwrap();
+        assert!(matches!(warning.kind, WarningKind::NonUtf8Encoding(ref e) if e == "Latin-1"));
+    }
+
+    #[test]
+    fn utf16_with_bom() {
+        let text = "// r[impl a.b]\nfn f() {}\n";
+        assert_eq!(
+            decode_source(utf16(text, false, true)),
+            (text.to_string(), SourceEncoding::Utf16Le)
+        );
+        assert_eq!(
+            decode_source(utf16(text, true, true)),
+            (text.to_string(), SourceEncoding::Utf16Be)
+        );
+    }
+
+    #[test]
+    fn utf16_without_bom_is_sniffed() {
+        let text = "// r[impl a.b]\nfn f() {}\n";
+        assert_eq!(
+            decode_source(utf16(text, false, false)),
+            (text.to_string(), SourceEncoding::Utf16Le)
+        );
+        assert_eq!(
+            decode_source(utf16(text, true, false)),
+            (text.to_string(), SourceEncoding::Utf16Be)
+        );
+    }
+}
@@ -4,12 +4,10 @@
 //! It scans comments for patterns like `r[verb rule.id]`.
 
 use crate::RuleId;
-#[cfg(not(feature = "reverse"))]
-use crate::parse_rule_id;
 use crate::positions::ByteSpan;
-#[cfg(not(feature = "reverse"))]
 use crate::positions::{ByteOffset, LineNumber, LineStarts, RefLocation};
-use crate::sources::{ExtractionResult, Sources};
+use crate::sources::{ExtractionResult, FileReqs, Sources};
+use crate::{is_valid_rule_id, parse_rule_id};
 use eyre::Result;
 use facet::Facet;
 use std::path::{Path, PathBuf};
@@ -44,7 +42,7 @@ impl From<ByteSpan> for SourceSpan {
 /// r[impl ref.verb.verify]
 /// r[impl ref.verb.depends]
 /// r[impl ref.verb.related]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Facet)]
 #[repr(u8)]
 pub enum RefVerb {
     /// Where the requirement is defined (typically in specs/docs)
@@ -57,10 +55,12 @@ pub enum RefVerb {
     Depends,
     /// Loose connection - show when reviewing
     Related,
+    /// A verb declared in the `verbs` config block (e.g. `documents`)
+    Custom(String),
 }
 
 impl RefVerb {
-    /// Parse a verb from its string representation
+    /// Parse a built-in verb from its string representation
     pub fn parse(s: &str) -> Option<Self> {
         match s {
             "define" => Some(RefVerb::Define),
@@ -72,14 +72,39 @@ impl RefVerb {
         }
     }
 
+    /// Parse a built-in verb or one of the configured custom verbs
+    ///
+    /// r[impl config.verbs.custom]
+    pub fn parse_with(s: &str, custom_verbs: &[String]) -> Option<Self> {
+        Self::parse(s).or_else(|| {
+            custom_verbs
+                .iter()
+                .any(|verb| verb == s)
+                .then(|| RefVerb::Custom(s.to_string()))
+        })
+    }
+
     /// Get the string representation of this verb
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             RefVerb::Define => "define",
             RefVerb::Impl => "impl",
             RefVerb::Verify => "verify",
             RefVerb::Depends => "depends",
             RefVerb::Related => "related",
+            RefVerb::Custom(verb) => verb,
+        }
+    }
+
+    /// Position in the canonical verb order; custom verbs sort last
+    pub fn order(&self) -> u8 {
+        match self {
+            RefVerb::Define => 0,
+            RefVerb::Impl => 1,
+            RefVerb::Verify => 2,
+            RefVerb::Depends => 3,
+            RefVerb::Related => 4,
+            RefVerb::Custom(_) => 5,
         }
     }
 }
@@ -105,10 +130,128 @@ pub struct ReqReference {
     pub file: PathBuf,
     /// Line number (1-indexed)
     pub line: usize,
+    /// Last line of the region claimed by a `begin` marker, which `line`
+    /// starts; `None` for a reference to its own line or code unit
+    pub end_line: Option<usize>,
+    /// Note trailing the rule ID, as in `r[impl a.b — only the TCP path]`
+    pub note: Option<String>,
+    /// Marked `coverage=partial`: the code satisfies the rule only in part
+    pub partial: bool,
     /// Byte span of the reference in source
     pub span: SourceSpan,
 }
 
+/// Suffix of a reference opening or closing a region, as in
+/// `r[impl auth.login begin]` and `r[impl auth.login end]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RegionMarker {
+    Begin,
+    End,
+}
+
+impl RegionMarker {
+    /// The marker ending the text of a reference, e.g. `r[impl a.b end]`
+    fn of(reference: &str) -> Option<Self> {
+        if reference.ends_with(" begin]") {
+            Some(Self::Begin)
+        } else if reference.ends_with(" end]") {
+            Some(Self::End)
+        } else {
+            None
+        }
+    }
+}
+
+/// With `chars` at the space after a rule ID, consume a ` begin]` or
+/// ` end]` suffix and return the index of its closing bracket. Leaves
+/// `chars` untouched when there is no such suffix.
+pub(crate) fn take_region_marker<I>(
+    chars: &mut std::iter::Peekable<I>,
+) -> Option<(RegionMarker, usize)>
+where
+    I: Iterator<Item = (usize, char)> + Clone,
+{
+    let mut ahead = chars.clone();
+    if ahead.next()?.1 != ' ' {
+        return None;
+    }
+    let mut word = String::new();
+    while let Some((idx, c)) = ahead.next() {
+        if c == ']' {
+            let marker = match word.as_str() {
+                "begin" => RegionMarker::Begin,
+                "end" => RegionMarker::End,
+                _ => return None,
+            };
+            *chars = ahead;
+            return Some((marker, idx));
+        }
+        if !c.is_ascii_lowercase() {
+            return None;
+        }
+        word.push(c);
+    }
+    None
+}
+
+/// With `chars` at the space after a rule ID, consume a ` coverage=partial`
+/// attribute followed by the closing bracket or a note. Leaves `chars`
+/// untouched and returns false when there is no such attribute.
+pub(crate) fn take_partial<I>(chars: &mut std::iter::Peekable<I>) -> bool
+where
+    I: Iterator<Item = (usize, char)> + Clone,
+{
+    let mut ahead = chars.clone();
+    for expected in " coverage=partial".chars() {
+        if ahead.next().map(|(_, c)| c) != Some(expected) {
+            return false;
+        }
+    }
+    if !matches!(ahead.peek(), Some((_, ']' | ' '))) {
+        return false;
+    }
+    *chars = ahead;
+    true
+}
+
+/// With `chars` at the space after a rule ID, consume a ` — note]` (or
+/// ` -- note]`) suffix and return the trimmed note and the index of its
+/// closing bracket. Leaves `chars` untouched when there is no such suffix.
+pub(crate) fn take_note<I>(chars: &mut std::iter::Peekable<I>) -> Option<(String, usize)>
+where
+    I: Iterator<Item = (usize, char)> + Clone,
+{
+    let mut ahead = chars.clone();
+    if ahead.next()?.1 != ' ' {
+        return None;
+    }
+    match ahead.next()?.1 {
+        '—' => {}
+        '-' if ahead.next()?.1 == '-' => {}
+        _ => return None,
+    }
+    if ahead.next()?.1 != ' ' {
+        return None;
+    }
+    let mut note = String::new();
+    while let Some((idx, c)) = ahead.next() {
+        if c == ']' {
+            let note = note.trim();
+            if note.is_empty() {
+                return None;
+            }
+            let note = note.to_string();
+            *chars = ahead;
+            return Some((note, idx));
+        }
+        if matches!(c, '[' | '\n') {
+            return None;
+        }
+        note.push(c);
+    }
+    None
+}
+
 /// Warning during parsing
 #[derive(Debug, Clone, Facet)]
 pub struct ParseWarning {
@@ -132,8 +275,55 @@ pub enum WarningKind {
     UnknownVerb(String),
     /// Malformed reference
     MalformedReference,
+    /// File is not valid UTF-8 and was decoded from the named encoding
+    NonUtf8Encoding(String),
+}
+
+/// Comment markers for scanning a file type tracey has no parser for
+///
+/// r[impl config.comments]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CommentDelimiters {
+    /// Markers starting a comment that runs to the end of the line, e.g. `--`
+    pub line: Vec<String>,
+    /// Opening and closing markers of block comments, e.g. `<!--` and `-->`
+    pub block: Vec<(String, String)>,
+}
+
+impl CommentDelimiters {
+    /// `//` line comments and `/* */` block comments
+    #[cfg(not(feature = "reverse"))]
+    fn c_like() -> Self {
+        Self {
+            line: vec!["//".to_string()],
+            block: vec![("/*".to_string(), "*/".to_string())],
+        }
+    }
 }
 
+/// String literals scanned for references on top of comments, for specs
+/// that opt in
+///
+/// r[impl config.spec.strings]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StringReferences {
+    /// Rust `#[doc = "..."]` and `#![doc = "..."]` attributes
+    pub doc_attributes: bool,
+    /// Test names passed to `it(...)`, `test(...)`, `describe(...)` and
+    /// Go's `t.Run(...)`
+    pub test_names: bool,
+}
+
+impl StringReferences {
+    /// Whether no string literals are scanned
+    pub fn is_empty(&self) -> bool {
+        !self.doc_attributes && !self.test_names
+    }
+}
+
+/// Calls whose first argument names a test
+const TEST_NAME_CALLS: &[&str] = &["it", "test", "describe", "t.Run"];
+
 /// Collection of requirement references extracted from source files
 #[derive(Debug, Clone, Default, Facet)]
 pub struct Reqs {
@@ -164,10 +354,58 @@ impl Reqs {
         sources.extract()
     }
 
+    /// Extract requirements from any source, handing each file's
+    /// requirements to `on_file` as soon as that file is done, so callers
+    /// can report progress or start indexing before the walk completes.
+    /// Files arrive in no particular order; see [`Sources::extract_streaming`].
+    pub fn extract_streaming<F>(sources: impl Sources, on_file: F) -> Result<Vec<String>>
+    where
+        F: Fn(FileReqs) + Sync,
+    {
+        sources.extract_streaming(on_file)
+    }
+
     /// Extract requirements from raw content (no I/O)
     pub fn extract_from_content(path: &Path, content: &str) -> Self {
+        Self::extract_from_content_with_verbs(path, content, &[])
+    }
+
+    /// Extract requirements from raw content, also accepting the given
+    /// custom verbs alongside the built-in ones
+    pub fn extract_from_content_with_verbs(
+        path: &Path,
+        content: &str,
+        custom_verbs: &[String],
+    ) -> Self {
+        let mut reqs = Reqs::new();
+        extract_from_content(path, content, custom_verbs, &mut reqs);
+        reqs
+    }
+
+    /// Extract requirements from raw content, finding comments by the given
+    /// markers instead of parsing the file's language
+    pub fn extract_from_content_with_delimiters(
+        path: &Path,
+        content: &str,
+        custom_verbs: &[String],
+        delimiters: &CommentDelimiters,
+    ) -> Self {
         let mut reqs = Reqs::new();
-        extract_from_content(path, content, &mut reqs);
+        extract_with_delimiters(path, content, custom_verbs, delimiters, &mut reqs);
+        pair_regions(content, 0, &mut reqs);
+        reqs
+    }
+
+    /// Extract requirements from the string literals selected by `kinds`,
+    /// which comment extraction skips
+    pub fn extract_from_strings(
+        path: &Path,
+        content: &str,
+        custom_verbs: &[String],
+        kinds: StringReferences,
+    ) -> Self {
+        let mut reqs = Reqs::new();
+        extract_from_strings(path, content, custom_verbs, kinds, &mut reqs);
         reqs
     }
 
@@ -176,28 +414,41 @@ impl Reqs {
         self.references.extend(other.references);
         self.warnings.extend(other.warnings);
     }
+
+    /// Order references and warnings by file, then by byte offset, so the
+    /// result does not depend on the order files were processed in.
+    ///
+    /// r[impl ref.ordering]
+    pub fn sort_by_location(&mut self) {
+        self.references
+            .sort_by(|a, b| (&a.file, a.span.offset).cmp(&(&b.file, b.span.offset)));
+        self.warnings
+            .sort_by(|a, b| (&a.file, a.span.offset).cmp(&(&b.file, b.span.offset)));
+    }
 }
 
 /// Extract requirement references from source content into the Reqs collection
 ///
 /// When the "reverse" feature is enabled, this uses tree-sitter for proper
 /// comment parsing. Otherwise, falls back to text-based scanning.
-pub(crate) fn extract_from_content(path: &Path, content: &str, reqs: &mut Reqs) {
+pub(crate) fn extract_from_content(
+    path: &Path,
+    content: &str,
+    custom_verbs: &[String],
+    reqs: &mut Reqs,
+) {
+    let refs_before = reqs.references.len();
+
     #[cfg(feature = "reverse")]
     {
         // Use tree-sitter based extraction
         // r[impl ref.comments.line]
         // r[impl ref.comments.doc]
         // r[impl ref.comments.block]
-        let extracted = crate::code_units::extract_refs_with_warnings(path, content);
+        let extracted = crate::code_units::extract_refs_with_verbs(path, content, custom_verbs);
         for full_ref in extracted.references {
-            let verb = match full_ref.verb.as_str() {
-                "define" => RefVerb::Define,
-                "impl" => RefVerb::Impl,
-                "verify" => RefVerb::Verify,
-                "depends" => RefVerb::Depends,
-                "related" => RefVerb::Related,
-                _ => continue,
+            let Some(verb) = RefVerb::parse_with(&full_ref.verb, custom_verbs) else {
+                continue;
             };
             reqs.references.push(ReqReference {
                 prefix: full_ref.prefix,
@@ -205,6 +456,9 @@ pub(crate) fn extract_from_content(path: &Path, content: &str, reqs: &mut Reqs)
                 req_id: full_ref.req_id,
                 file: path.to_path_buf(),
                 line: full_ref.line,
+                end_line: None,
+                note: full_ref.note,
+                partial: full_ref.partial,
                 span: SourceSpan::new(full_ref.byte_offset, full_ref.byte_length),
             });
         }
@@ -221,19 +475,202 @@ pub(crate) fn extract_from_content(path: &Path, content: &str, reqs: &mut Reqs)
     #[cfg(not(feature = "reverse"))]
     {
         // Fallback: text-based scanning
-        extract_from_content_text_based(path, content, reqs);
+        extract_with_delimiters(
+            path,
+            content,
+            custom_verbs,
+            &CommentDelimiters::c_like(),
+            reqs,
+        );
+    }
+
+    if path.extension().is_some_and(|ext| ext == "rs") {
+        extract_attribute_references(path, content, reqs);
+    }
+    pair_regions(content, refs_before, reqs);
+}
+
+/// Attributes from the `tracey-macros` crate, under the paths it is used by
+const TRACEY_ATTRIBUTES: &[(&str, RefVerb)] = &[
+    ("tracey::impls", RefVerb::Impl),
+    ("tracey::verifies", RefVerb::Verify),
+    ("tracey_macros::impls", RefVerb::Impl),
+    ("tracey_macros::verifies", RefVerb::Verify),
+];
+
+/// A `#[tracey::impls(...)]` or `#[tracey::verifies(...)]` attribute.
+pub(crate) struct TraceyAttribute {
+    pub verb: RefVerb,
+    /// Byte ranges of the rule IDs, relative to the start of the attribute
+    pub ids: Vec<(usize, usize)>,
+    /// Length of the attribute, up to and including its closing bracket
+    pub len: usize,
+}
+
+impl TraceyAttribute {
+    /// Parse a `#[tracey::impls("a.b", "c.d")]` attribute at the start of
+    /// `text`.
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let offset_of = |rest: &str| text.len() - rest.len();
+        let rest = text.strip_prefix("#[")?.trim_start();
+        let (path, verb) = TRACEY_ATTRIBUTES
+            .iter()
+            .find(|(path, _)| rest.starts_with(path))?;
+        let mut rest = rest[path.len()..].trim_start().strip_prefix('(')?;
+        let mut ids = Vec::new();
+        loop {
+            let literal = rest.trim_start().strip_prefix('"')?;
+            let len = literal.find('"')?;
+            let start = offset_of(literal);
+            ids.push((start, start + len));
+            rest = literal[len + 1..].trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after;
+                // Trailing comma
+                if let Some(after) = rest.trim_start().strip_prefix(')') {
+                    rest = after;
+                    break;
+                }
+            } else {
+                rest = rest.strip_prefix(')')?;
+                break;
+            }
+        }
+        let rest = rest.trim_start().strip_prefix(']')?;
+        Some(Self {
+            verb: verb.clone(),
+            ids,
+            len: offset_of(rest),
+        })
+    }
+
+    /// The attribute's rule IDs, skipping any that tracey-macros would
+    /// reject.
+    pub(crate) fn rule_ids<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> impl Iterator<Item = Option<RuleId>> + 'a {
+        self.ids.iter().map(|&(start, end)| {
+            let id = &text[start..end];
+            parse_rule_id(id).filter(|_| is_valid_rule_id(id))
+        })
+    }
+}
+
+/// Extract references from `tracey-macros` attributes starting a line.
+///
+/// r[impl ref.attributes]
+fn extract_attribute_references(path: &Path, content: &str, reqs: &mut Reqs) {
+    let line_starts = LineStarts::from_content(content);
+    for (line_idx, line) in content.lines().enumerate() {
+        let indent = line.len() - line.trim_start().len();
+        if !line[indent..].starts_with("#[") {
+            continue;
+        }
+        let start = line_starts.line_start_for_index(line_idx).as_usize() + indent;
+        let Some(attribute) = TraceyAttribute::parse(&content[start..]) else {
+            continue;
+        };
+        let line = LineNumber::from_zero_based(line_idx).as_usize();
+        // Like `@relation`, every ID of the attribute shares its span.
+        let span = SourceSpan::new(start, attribute.len);
+        for req_id in attribute.rule_ids(&content[start..]) {
+            match req_id {
+                Some(req_id) => reqs.references.push(ReqReference {
+                    prefix: "r".to_string(),
+                    verb: attribute.verb.clone(),
+                    req_id,
+                    file: path.to_path_buf(),
+                    line,
+                    end_line: None,
+                    note: None,
+                    partial: false,
+                    span,
+                }),
+                None => reqs.warnings.push(ParseWarning {
+                    file: path.to_path_buf(),
+                    line,
+                    span,
+                    kind: WarningKind::MalformedReference,
+                }),
+            }
+        }
+    }
+}
+
+/// Close the regions opened by `begin` markers among the references from
+/// `refs_from` on: each takes the line of the next matching `end` marker as
+/// its `end_line`, and the `end` reference itself is dropped. Markers left
+/// unmatched are reported as malformed; an unclosed `begin` still counts as
+/// a reference to its own line.
+///
+/// r[impl ref.syntax.region]
+fn pair_regions(content: &str, refs_from: usize, reqs: &mut Reqs) {
+    // A note could end in ` begin]` too
+    let marker_of = |r: &ReqReference| {
+        if r.note.is_some() {
+            return None;
+        }
+        content
+            .get(r.span.offset..r.span.offset + r.span.length)
+            .and_then(RegionMarker::of)
+    };
+    let mut order: Vec<usize> = (refs_from..reqs.references.len()).collect();
+    order.sort_by_key(|&i| reqs.references[i].span.offset);
+
+    let mut open: std::collections::HashMap<(String, String, String), Vec<usize>> =
+        Default::default();
+    let mut closed = Vec::new();
+    let mut unmatched = Vec::new();
+    for i in order {
+        let r = &reqs.references[i];
+        let Some(marker) = marker_of(r) else {
+            continue;
+        };
+        let key = (
+            r.prefix.clone(),
+            r.verb.as_str().to_string(),
+            r.req_id.to_string(),
+        );
+        match marker {
+            RegionMarker::Begin => open.entry(key).or_default().push(i),
+            RegionMarker::End => match open.get_mut(&key).and_then(Vec::pop) {
+                Some(begin) => {
+                    reqs.references[begin].end_line = Some(reqs.references[i].line);
+                    closed.push(i);
+                }
+                None => {
+                    unmatched.push(i);
+                    closed.push(i);
+                }
+            },
+        }
+    }
+    unmatched.extend(open.into_values().flatten());
+    unmatched.sort_unstable();
+    for i in unmatched {
+        let r = &reqs.references[i];
+        reqs.warnings.push(ParseWarning {
+            file: r.file.clone(),
+            line: r.line,
+            span: r.span,
+            kind: WarningKind::MalformedReference,
+        });
+    }
+    closed.sort_unstable();
+    for i in closed.into_iter().rev() {
+        reqs.references.remove(i);
     }
 }
 
 /// State for tracking ignore directives across lines.
 ///
 /// r[impl ref.ignore.prefix]
-#[cfg(not(feature = "reverse"))]
 #[derive(Default)]
 struct IgnoreState {
-    /// Skip the next line (set by @tracey:ignore-next-line)
+    /// Skip the next line (set by the `ignore-next-line` directive)
     ignore_next_line: Option<LineNumber>,
-    /// Currently inside an ignore block (set by @tracey:ignore-start)
+    /// Currently inside an ignore block (set by the `ignore-start` directive)
     /// r[impl ref.ignore.block]
     in_ignore_block: bool,
 }
@@ -241,7 +678,6 @@ struct IgnoreState {
 /// Check if a comment contains ignore directives and update state accordingly.
 ///
 /// Returns true if the current comment's refs should be extracted (not ignored).
-#[cfg(not(feature = "reverse"))]
 fn check_ignore_directives(text: &str, line: LineNumber, state: &mut IgnoreState) -> bool {
     // Check for ignore directives
     // r[impl ref.ignore.next-line]
@@ -278,8 +714,15 @@ fn check_ignore_directives(text: &str, line: LineNumber, state: &mut IgnoreState
     true
 }
 
-#[cfg(not(feature = "reverse"))]
-fn extract_from_content_text_based(path: &Path, content: &str, reqs: &mut Reqs) {
+/// Scan `content` for comments delimited by `delimiters` and extract the
+/// references in them.
+fn extract_with_delimiters(
+    path: &Path,
+    content: &str,
+    custom_verbs: &[String],
+    delimiters: &CommentDelimiters,
+    reqs: &mut Reqs,
+) {
     // Track line starts for computing line numbers from byte offsets
     let line_starts = LineStarts::from_content(content);
 
@@ -289,77 +732,195 @@ fn extract_from_content_text_based(path: &Path, content: &str, reqs: &mut Reqs)
 
     let mut ignore_state = IgnoreState::default();
 
-    // Scan for comments and extract references
+    // Scan for line comments, starting at the earliest marker on the line
     for (line_idx, line) in content.lines().enumerate() {
         let line_num = LineNumber::from_zero_based(line_idx);
         let line_start = line_starts.line_start_for_index(line_idx);
 
-        // Check for line comments (// or ///)
-        if let Some(comment_pos) = line.find("//") {
-            let comment = &line[comment_pos..];
-            let comment_start = line_start.add(comment_pos);
+        let Some(comment_pos) = delimiters
+            .line
+            .iter()
+            .filter(|marker| !marker.is_empty())
+            .filter_map(|marker| line.find(marker.as_str()))
+            .min()
+        else {
+            continue;
+        };
+        let comment = &line[comment_pos..];
+        let comment_start = line_start.add(comment_pos);
+
+        // Check ignore directives before extracting
+        if check_ignore_directives(comment, line_num, &mut ignore_state) {
+            extract_references_from_text(
+                path,
+                custom_verbs,
+                comment,
+                comment_start,
+                line_num,
+                &file_code_mask,
+                reqs,
+            );
+        }
+    }
 
-            // Check ignore directives before extracting
-            if check_ignore_directives(comment, line_num, &mut ignore_state) {
+    // Handle block comments
+    for (open, close) in &delimiters.block {
+        if open.is_empty() || close.is_empty() {
+            continue;
+        }
+        let mut search_from = 0;
+        while let Some(found) = content[search_from..].find(open.as_str()) {
+            let open_at = search_from + found;
+            let block_start = open_at + open.len();
+            let Some(len) = content[block_start..].find(close.as_str()) else {
+                break;
+            };
+            let block_content = &content[block_start..block_start + len];
+            let block_line = line_starts.line_number_for_offset(ByteOffset::from_usize(open_at));
+            // Check ignore directives for block comments too
+            if check_ignore_directives(block_content, block_line, &mut ignore_state) {
+                let (refs_before, warnings_before) = (reqs.references.len(), reqs.warnings.len());
                 extract_references_from_text(
                     path,
-                    comment,
-                    comment_start,
-                    line_num,
+                    custom_verbs,
+                    block_content,
+                    ByteOffset::from_usize(block_start),
+                    block_line,
                     &file_code_mask,
                     reqs,
                 );
+                // Report each reference on its own line of a multi-line block
+                let line_of = |offset: usize| {
+                    line_starts
+                        .line_number_for_offset(ByteOffset::from_usize(offset))
+                        .as_usize()
+                };
+                for reference in &mut reqs.references[refs_before..] {
+                    reference.line = line_of(reference.span.offset);
+                }
+                for warning in &mut reqs.warnings[warnings_before..] {
+                    warning.line = line_of(warning.span.offset);
+                }
             }
+            search_from = block_start + len + close.len();
         }
     }
+}
 
-    // Handle block comments /* */
-    let mut in_block_comment = false;
-    let mut block_start = 0;
-    let mut block_line = LineNumber::from_one_based(1);
-    let mut i = 0;
-    let bytes = content.as_bytes();
-
-    while i < bytes.len() {
-        if in_block_comment {
-            if i + 1 < bytes.len() && bytes[i] == b'*' && bytes[i + 1] == b'/' {
-                let block_content = &content[block_start..i];
-                // Check ignore directives for block comments too
-                if check_ignore_directives(block_content, block_line, &mut ignore_state) {
-                    extract_references_from_text(
-                        path,
-                        block_content,
-                        ByteOffset::from_usize(block_start),
-                        block_line,
-                        &file_code_mask,
-                        reqs,
-                    );
+/// Scan the string literals selected by `kinds` for references.
+fn extract_from_strings(
+    path: &Path,
+    content: &str,
+    custom_verbs: &[String],
+    kinds: StringReferences,
+    reqs: &mut Reqs,
+) {
+    let mut literals = Vec::new();
+    let is_rust = path.extension().is_some_and(|ext| ext == "rs");
+    if kinds.doc_attributes && is_rust {
+        for open in ["#[doc", "#![doc"] {
+            for (at, _) in content.match_indices(open) {
+                let rest = &content[at + open.len()..];
+                let trimmed = rest.trim_start();
+                let Some(value) = trimmed.strip_prefix('=') else {
+                    continue;
+                };
+                let quote_at = content.len() - value.trim_start().len();
+                literals.extend(string_literal_at(content, quote_at));
+            }
+        }
+    }
+    if kinds.test_names {
+        for call in TEST_NAME_CALLS {
+            for (at, _) in content.match_indices(call) {
+                // `it` must not be the tail of `split` or `self.it`
+                let boundary = content[..at]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '.'));
+                let Some(args) = content[at + call.len()..].strip_prefix('(') else {
+                    continue;
+                };
+                if !boundary {
+                    continue;
                 }
-                in_block_comment = false;
-                i += 2;
-                continue;
+                let quote_at = content.len() - args.trim_start().len();
+                literals.extend(string_literal_at(content, quote_at));
             }
-        } else if i + 1 < bytes.len() && bytes[i] == b'/' && bytes[i + 1] == b'*' {
-            in_block_comment = true;
-            block_start = i + 2;
-            block_line = line_starts.line_number_for_offset(ByteOffset::from_usize(i));
-            i += 2;
-            continue;
         }
-        i += 1;
     }
+    literals.sort_unstable();
+    literals.dedup();
+
+    let line_starts = LineStarts::from_content(content);
+    let line_of =
+        |offset: usize| line_starts.line_number_for_offset(ByteOffset::from_usize(offset));
+    for (start, end) in literals {
+        let (refs_before, warnings_before) = (reqs.references.len(), reqs.warnings.len());
+        extract_references_from_text(
+            path,
+            custom_verbs,
+            &content[start..end],
+            ByteOffset::from_usize(start),
+            line_of(start),
+            &[],
+            reqs,
+        );
+        // Multi-line literals report each reference on its own line
+        for reference in &mut reqs.references[refs_before..] {
+            reference.line = line_of(reference.span.offset).as_usize();
+        }
+        for warning in &mut reqs.warnings[warnings_before..] {
+            warning.line = line_of(warning.span.offset).as_usize();
+        }
+    }
+}
+
+/// Byte range of the contents of the string literal starting at `at`: a
+/// `"`, `'` or backtick quoted string with backslash escapes, or a Rust raw
+/// string such as `r#"..."#`.
+fn string_literal_at(content: &str, at: usize) -> Option<(usize, usize)> {
+    let rest = &content[at..];
+    if let Some(raw) = rest.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        let start = content.len() - body.len();
+        let close = format!("\"{}", "#".repeat(hashes));
+        let len = body.find(&close)?;
+        return Some((start, start + len));
+    }
+    let quote = rest
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\'' | '`'))?;
+    let start = at + 1;
+    let mut escaped = false;
+    for (i, c) in content[start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return Some((start, start + i)),
+            _ => {}
+        }
+    }
+    None
 }
 
 /// Extract rule references from a piece of text (comment content)
-#[cfg(not(feature = "reverse"))]
 fn extract_references_from_text(
     path: &Path,
+    custom_verbs: &[String],
     text: &str,
     text_offset: ByteOffset,
     base_line: LineNumber,
     file_code_mask: &[bool],
     reqs: &mut Reqs,
 ) {
+    // Every reference has a bracket or is an `@relation`; most comments
+    // have neither, and can skip the markdown parse below.
+    if !text.contains('[') && !text.contains("@relation") {
+        return;
+    }
     let code_mask = crate::markdown::markdown_code_mask(text);
     let mut chars = text.char_indices().peekable();
     let mut prev_ch: Option<char> = None;
@@ -383,21 +944,23 @@ fn extract_references_from_text(
                 prev_ch = Some(ch);
                 continue;
             }
+            // Prefix, verb and rule ID are all ASCII, and are sliced out of
+            // `text` rather than copied until a reference is found.
             let prefix_start = start_idx;
-            let mut prefix = String::new();
-            prefix.push(ch);
+            let mut prefix_end = start_idx + 1;
 
             // Continue reading prefix (can be multi-char like "h2")
-            while let Some(&(_, next_ch)) = chars.peek() {
+            while let Some(&(idx, next_ch)) = chars.peek() {
                 if next_ch == '[' {
                     break; // Found the bracket
                 } else if next_ch.is_ascii_lowercase() || next_ch.is_ascii_digit() {
-                    prefix.push(next_ch);
+                    prefix_end = idx + 1;
                     chars.next();
                 } else {
                     break; // Not a valid prefix character
                 }
             }
+            let prefix = &text[prefix_start..prefix_end];
 
             // Check if we have '[' after the prefix
             if let Some(&(_bracket_idx, next_ch)) = chars.peek() {
@@ -409,16 +972,16 @@ fn extract_references_from_text(
                 continue;
             }
 
-            // Try to parse: r[verb rule.id] or r[rule.id]
-            let mut first_word = String::new();
+            // Try to parse a verb and rule ID, or a bare rule ID, in the brackets
+            let (mut first_start, mut first_end) = (0, 0);
             let mut valid = true;
 
             // First char must be an ASCII letter. Case is preserved: lowercase
             // first words are still tried as a verb (the only verbs are lowercase);
             // uppercase or mixed-case first words flow through as a rule-ID body.
-            if let Some(&(_, first_char)) = chars.peek() {
+            if let Some(&(idx, first_char)) = chars.peek() {
                 if first_char.is_ascii_alphabetic() {
-                    first_word.push(first_char);
+                    (first_start, first_end) = (idx, idx + 1);
                     chars.next();
                 } else {
                     valid = false;
@@ -429,12 +992,12 @@ fn extract_references_from_text(
 
             if valid {
                 // Read the first word (could be verb or start of rule ID)
-                // r[impl ref.syntax.req-id]
-                while let Some(&(_, c)) = chars.peek() {
+                // r[impl ref.syntax.req-id+3]
+                while let Some(&(idx, c)) = chars.peek() {
                     if c == ']' || c == ' ' {
                         break;
-                    } else if c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '+' {
-                        first_word.push(c);
+                    } else if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+') {
+                        first_end = idx + 1;
                         chars.next();
                     } else {
                         valid = false;
@@ -443,6 +1006,7 @@ fn extract_references_from_text(
                 }
             }
 
+            let first_word = &text[first_start..first_end];
             if !valid || first_word.is_empty() {
                 continue;
             }
@@ -450,19 +1014,20 @@ fn extract_references_from_text(
             // Check what follows
             if let Some(&(end_idx, next_char)) = chars.peek() {
                 if next_char == ' ' {
-                    // Space after first word - might be r[verb rule.id]
+                    // Space after first word - might be a verb and a rule ID
                     // r[impl ref.syntax.verb]
-                    if let Some(verb) = RefVerb::parse(&first_word) {
+                    if let Some(verb) = RefVerb::parse_with(first_word, custom_verbs) {
                         chars.next(); // consume space
 
                         // Now read the rule ID
-                        let mut req_id = String::new();
+                        let req_start = chars.peek().map_or(text.len(), |&(idx, _)| idx);
+                        let mut req_end = req_start;
 
                         // First char of rule ID must be an ASCII letter.
                         // Case is preserved (StrictDoc-style UIDs like BR-001).
                         if let Some(&(_, c)) = chars.peek() {
                             if c.is_ascii_alphabetic() {
-                                req_id.push(c);
+                                req_end += 1;
                                 chars.next();
                             } else {
                                 continue; // invalid, skip
@@ -471,35 +1036,62 @@ fn extract_references_from_text(
 
                         // Continue reading rule ID
                         let mut final_idx = end_idx;
+                        let mut note = None;
+                        let mut partial = false;
                         while let Some(&(idx, c)) = chars.peek() {
                             final_idx = idx;
                             if c == ']' {
                                 chars.next();
                                 break;
-                            } else if c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '.'
+                            } else if c.is_ascii_alphanumeric()
+                                || matches!(c, '-' | '_' | '+' | '.' | '*' | '{' | '}')
+                                || continues_rule_list(&text[req_start..req_end], c)
                             {
-                                req_id.push(c);
+                                req_end = idx + c.len_utf8();
                                 chars.next();
                             } else {
+                                // r[impl ref.syntax.region]
+                                if let Some((_, close_idx)) = take_region_marker(&mut chars) {
+                                    final_idx = close_idx;
+                                    break;
+                                }
+                                // r[impl ref.coverage.partial]
+                                partial = take_partial(&mut chars);
+                                // r[impl ref.syntax.note]
+                                if let Some((text, close_idx)) = take_note(&mut chars) {
+                                    note = Some(text);
+                                    final_idx = close_idx;
+                                } else if partial && let Some(&(idx, ']')) = chars.peek() {
+                                    chars.next();
+                                    final_idx = idx;
+                                }
                                 break; // invalid char
                             }
                         }
 
                         // Validate rule ID
-                        if is_valid_req_id(&req_id) {
+                        // r[impl ref.syntax.patterns]
+                        // r[impl ref.syntax.list]
+                        let ids = crate::expand_rule_list(&text[req_start..req_end])
+                            .filter(|ids| ids.iter().all(|id| is_valid_req_id(id)));
+                        if let Some(ids) = ids {
                             let location = RefLocation::from_relative_indices(
                                 base_line,
                                 text_offset,
                                 prefix_start,
                                 final_idx,
                             );
-                            if let Some(rule_id) = parse_rule_id(&req_id) {
+                            // Listed IDs and brace alternatives share the span
+                            for rule_id in ids.iter().filter_map(|id| parse_rule_id(id)) {
                                 reqs.references.push(ReqReference {
-                                    prefix: prefix.clone(),
-                                    verb,
+                                    prefix: prefix.to_string(),
+                                    verb: verb.clone(),
                                     req_id: rule_id,
                                     file: path.to_path_buf(),
                                     line: location.line().as_usize(),
+                                    end_line: None,
+                                    note: note.clone(),
+                                    partial,
                                     span: location.span().into(),
                                 });
                             }
@@ -523,25 +1115,28 @@ fn extract_references_from_text(
                         // This avoids false positives on things like [payload bytes]
                     }
                 } else if next_char == ']' {
-                    // Immediate close - this is r[rule.id] format (defaults to impl)
+                    // Immediate close - a bare rule ID (defaults to impl)
                     // r[impl ref.verb.default]
                     chars.next(); // consume ]
 
                     // Validate requirement ID syntax
-                    if is_valid_req_id(&first_word) {
+                    if is_valid_req_id(first_word) {
                         let location = RefLocation::from_relative_indices(
                             base_line,
                             text_offset,
                             prefix_start,
                             end_idx,
                         );
-                        if let Some(rule_id) = parse_rule_id(&first_word) {
+                        if let Some(rule_id) = parse_rule_id(first_word) {
                             reqs.references.push(ReqReference {
-                                prefix: prefix.clone(),
+                                prefix: prefix.to_string(),
                                 verb: RefVerb::Impl, // default to impl
                                 req_id: rule_id,
                                 file: path.to_path_buf(),
                                 line: location.line().as_usize(),
+                                end_line: None,
+                                note: None,
+                                partial: false,
                                 span: location.span().into(),
                             });
                         }
@@ -579,7 +1174,6 @@ fn extract_references_from_text(
 ///
 /// Multi-UID annotations expand to one reference per UID, all sharing the call's span.
 /// `role=Refines` and unknown roles emit a parse warning and produce no references.
-#[cfg(not(feature = "reverse"))]
 fn extract_relation_annotations(
     path: &Path,
     text: &str,
@@ -643,10 +1237,13 @@ fn extract_relation_annotations(
             if let Some(rule_id) = parse_rule_id(uid) {
                 reqs.references.push(ReqReference {
                     prefix: "r".to_string(),
-                    verb,
+                    verb: verb.clone(),
                     req_id: rule_id,
                     file: path.to_path_buf(),
                     line: location.line().as_usize(),
+                    end_line: None,
+                    note: None,
+                    partial: false,
                     span: location.span().into(),
                 });
             } else {
@@ -664,12 +1261,22 @@ fn extract_relation_annotations(
 }
 
 // r[impl ref.syntax.req-id+3]
-#[cfg(not(feature = "reverse"))]
 fn is_valid_req_id(req_id: &str) -> bool {
     let Some(parsed) = parse_rule_id(req_id) else {
         return false;
     };
+    // A wildcard stands for whole segments only
     !parsed.base.ends_with('.')
+        && parsed
+            .base
+            .split('.')
+            .all(|segment| segment == "*" || !segment.contains('*'))
+}
+
+/// Whether `c` continues a partly read list of rule IDs: a comma, or a
+/// space following one.
+pub(crate) fn continues_rule_list(partial: &str, c: char) -> bool {
+    c == ',' || (c == ' ' && partial.trim_end().ends_with(','))
 }
 
 #[cfg(test)]
@@ -900,6 +1507,281 @@ mod tests {
         assert_eq!(reqs.references[0].req_id, "dashboard.header.search");
     }
 
+    // r[verify config.comments]
+    #[test]
+    fn test_configured_comment_delimiters() {
+        let sql = CommentDelimiters {
+            line: vec!["--".to_string()],
+            block: vec![],
+        };
+        let content = "-- r[impl db.users.schema]\nCREATE TABLE users (id INT); -- r[verify db.users.id]\n// r[impl db.nope]\n";
+        let reqs =
+            Reqs::extract_from_content_with_delimiters(Path::new("schema.sql"), content, &[], &sql);
+        let ids: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        assert_eq!(ids, ["db.users.schema", "db.users.id"]);
+        assert_eq!(reqs.references[1].verb, RefVerb::Verify);
+        assert_eq!(reqs.references[1].line, 2);
+
+        let html = CommentDelimiters {
+            line: vec![],
+            block: vec![("<!--".to_string(), "-->".to_string())],
+        };
+        let content = "<p>r[impl not.comment]</p>\n<!--\n  r[impl page.title]\n-->\n";
+        let reqs =
+            Reqs::extract_from_content_with_delimiters(Path::new("page.html"), content, &[], &html);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs.references[0].req_id, "page.title");
+        assert_eq!(reqs.references[0].line, 3);
+        let span = reqs.references[0].span;
+        assert_eq!(
+            &content[span.offset..span.offset + span.length],
+            "r[impl page.title]"
+        );
+    }
+
+    // r[verify config.spec.strings]
+    #[test]
+    fn test_references_in_doc_attributes_and_test_names() {
+        let content = concat!(
+            "#![doc = \"Crate docs, r[impl crate.docs]\"]\n",
+            "#[doc = r#\"Generated: r[impl gen.macro]\"#]\n",
+            "fn f() { split(\"r[impl not.a.test]\"); }\n",
+        );
+        let path = Path::new("lib.rs");
+        assert!(Reqs::extract_from_content(path, content).is_empty());
+
+        let docs = StringReferences {
+            doc_attributes: true,
+            test_names: false,
+        };
+        let reqs = Reqs::extract_from_strings(path, content, &[], docs);
+        let ids: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        assert_eq!(ids, ["crate.docs", "gen.macro"]);
+        assert_eq!(reqs.references[1].line, 2);
+
+        let content = "describe('auth', () => {\n  it(\"r[verify auth.login] \\\"logs in\\\"\", () => {});\n});\n";
+        let tests = StringReferences {
+            doc_attributes: true,
+            test_names: true,
+        };
+        let reqs = Reqs::extract_from_strings(Path::new("auth.test.ts"), content, &[], tests);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs.references[0].verb, RefVerb::Verify);
+        assert_eq!(reqs.references[0].req_id, "auth.login");
+        assert_eq!(reqs.references[0].line, 2);
+        let span = reqs.references[0].span;
+        assert_eq!(
+            &content[span.offset..span.offset + span.length],
+            "r[verify auth.login]"
+        );
+    }
+
+    // r[verify ref.syntax.region]
+    #[test]
+    fn test_region_markers_pair_into_line_ranges() {
+        let content = "\
+// r[impl auth.login begin]
+fn a() {}
+fn b() {}
+// r[impl auth.login end]
+// r[verify auth.logout end]
+// r[impl auth.session begin]
+fn c() {}
+";
+        let reqs = Reqs::extract_from_content(Path::new("auth.rs"), content);
+        assert_eq!(reqs.len(), 2, "{:?}", reqs.references);
+        let login = &reqs.references[0];
+        assert_eq!(login.req_id, "auth.login");
+        assert_eq!((login.line, login.end_line), (1, Some(4)));
+        assert_eq!(
+            &content[login.span.offset..login.span.offset + login.span.length],
+            "r[impl auth.login begin]"
+        );
+        let session = &reqs.references[1];
+        assert_eq!(session.req_id, "auth.session");
+        assert_eq!((session.line, session.end_line), (6, None));
+        // The stray `end` and the unclosed `begin`
+        let warned: Vec<_> = reqs.warnings.iter().map(|w| w.line).collect();
+        assert_eq!(warned, [5, 6]);
+    }
+
+    // r[verify ref.syntax.patterns]
+    #[test]
+    fn test_brace_groups_and_wildcards() {
+        let content = "\
+// r[verify frame.{header,payload}.parse]
+// r[impl channel.id.*]
+// r[impl chan*.id]
+";
+        let reqs = Reqs::extract_from_content(Path::new("frame.rs"), content);
+        let found: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| (r.line, r.req_id.to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (1, "frame.header.parse".to_string()),
+                (1, "frame.payload.parse".to_string()),
+                (2, "channel.id.*".to_string()),
+            ]
+        );
+        // Both alternatives point at the whole annotation
+        assert_eq!(reqs.references[0].span, reqs.references[1].span);
+    }
+
+    // r[verify ref.syntax.list]
+    #[test]
+    fn test_comma_separated_rule_ids_share_span() {
+        let content = "\
+// r[impl auth.login, auth.session+2,  auth.logout]
+// r[verify auth.login, ]
+";
+        let reqs = Reqs::extract_from_content(Path::new("auth.rs"), content);
+        let found: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        assert_eq!(found, ["auth.login", "auth.session+2", "auth.logout"]);
+        assert!(
+            reqs.references
+                .iter()
+                .all(|r| r.span == reqs.references[0].span && r.verb == RefVerb::Impl)
+        );
+        // A trailing comma leaves an empty entry
+        assert_eq!(reqs.warnings.len(), 1);
+        assert_eq!(reqs.warnings[0].line, 2);
+    }
+
+    // r[verify ref.syntax.req-id+3]
+    #[test]
+    fn test_underscores_in_rule_ids() {
+        let content = "// r[user_profile.update_email]\n// r[verify user_profile.delete]\n";
+        let reqs = Reqs::extract_from_content(Path::new("profile.rs"), content);
+        let found: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| (r.verb.as_str(), r.req_id.to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("impl", "user_profile.update_email".to_string()),
+                ("verify", "user_profile.delete".to_string())
+            ]
+        );
+    }
+
+    // r[verify ref.syntax.note]
+    #[test]
+    fn test_trailing_note_is_kept() {
+        let content = "\
+// r[impl net.connect — partial: only the TCP path]
+// r[verify net.connect, net.close -- fuzzed]
+";
+        let reqs = Reqs::extract_from_content(Path::new("net.rs"), content);
+        let found: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| (r.req_id.to_string(), r.note.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "net.connect".to_string(),
+                    Some("partial: only the TCP path")
+                ),
+                ("net.connect".to_string(), Some("fuzzed")),
+                ("net.close".to_string(), Some("fuzzed")),
+            ]
+        );
+        // The span covers the note
+        let first = &reqs.references[0];
+        assert!(
+            content[first.span.offset..first.span.offset + first.span.length].ends_with("path]")
+        );
+        assert!(reqs.warnings.is_empty());
+    }
+
+    // r[verify ref.coverage.partial]
+    #[test]
+    fn test_partial_coverage_marker() {
+        let content = "\
+// r[impl net.connect coverage=partial]
+// r[impl net.close coverage=partial — IPv4 only]
+// r[impl net.send]
+";
+        let reqs = Reqs::extract_from_content(Path::new("net.rs"), content);
+        let found: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| (r.req_id.to_string(), r.partial, r.note.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("net.connect".to_string(), true, None),
+                ("net.close".to_string(), true, Some("IPv4 only")),
+                ("net.send".to_string(), false, None),
+            ]
+        );
+        assert!(reqs.warnings.is_empty());
+    }
+
+    // r[verify ref.attributes]
+    #[test]
+    fn test_tracey_macro_attributes() {
+        let content = "\
+#[tracey::impls(\"channel.id.allocation\")]
+fn allocate_id() {}
+
+    #[tracey_macros::verifies(
+        \"channel.id.allocation\",
+        \"channel.id.parity\",
+    )]
+    fn ids_alternate() {}
+
+// #[tracey::impls(\"not.an.attribute\")]
+#[tracey::impls(\"bad..id\")]
+fn f() {}
+";
+        let reqs = Reqs::extract_from_content(Path::new("lib.rs"), content);
+        let found: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| (r.verb.clone(), r.req_id.to_string(), r.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (RefVerb::Impl, "channel.id.allocation".to_string(), 1),
+                (RefVerb::Verify, "channel.id.allocation".to_string(), 4),
+                (RefVerb::Verify, "channel.id.parity".to_string(), 4),
+            ]
+        );
+        let span = reqs.references[0].span;
+        assert_eq!(
+            &content[span.offset..span.offset + span.length],
+            "#[tracey::impls(\"channel.id.allocation\")]"
+        );
+        assert_eq!(reqs.warnings.len(), 1);
+        assert_eq!(reqs.warnings[0].line, 11);
+
+        // Only Rust files
+        assert!(Reqs::extract_from_content(Path::new("lib.py"), content).is_empty());
+    }
+
     #[test]
     fn test_ignore_refs_inside_inline_backticks_in_comments() {
         let content = r#"
@@ -5,25 +5,33 @@
 //! - Computing coverage statistics
 
 mod coverage;
+mod encoding;
 mod lexer;
-mod markdown;
+pub mod markdown;
 mod positions;
 mod rule_id;
+mod snippet;
 mod sources;
 mod spec;
 
 #[cfg(feature = "reverse")]
 pub mod code_units;
 
-pub use coverage::CoverageReport;
-pub use lexer::{ParseWarning, RefVerb, ReqReference, Reqs, SourceSpan, WarningKind};
+pub use coverage::{CoverageReport, Enforcement, TestImpls, VerbRole, VerbRoles};
+pub use encoding::{SourceEncoding, decode_source, read_source};
+pub use lexer::{
+    CommentDelimiters, ParseWarning, RefVerb, ReqReference, Reqs, SourceSpan, StringReferences,
+    WarningKind,
+};
 pub use rule_id::{
     RuleId, RuleIdMatch, classify_reference_for_rule, classify_reference_for_rule_str,
-    parse_rule_id,
+    expand_rule_braces, expand_rule_list, find_rule_id_mentions, is_rule_pattern, is_valid_rule_id,
+    parse_rule_id, rule_pattern_matches,
 };
+pub use snippet::{CommentSyntax, format_annotation, format_annotation_with};
 pub use sources::{
-    ExtractionResult, MemorySources, PathSources, SUPPORTED_EXTENSIONS, Sources, is_spec_extension,
-    is_supported_extension,
+    ExtractionResult, FileReqs, MemorySources, PathSources, SUPPORTED_EXTENSIONS, Sources,
+    is_spec_extension, is_supported_extension,
 };
 pub use spec::ReqDefinition;
 
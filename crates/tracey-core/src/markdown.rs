@@ -1,3 +1,8 @@
+//! Markdown helpers: code masking for doc comments, and [`edit`] for
+//! round-trip edits of spec files.
+
+pub mod edit;
+
 use crate::positions::ByteOffset;
 use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
 
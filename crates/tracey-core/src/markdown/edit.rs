@@ -0,0 +1,504 @@
+//! Round-trip editing of spec markdown.
+//!
+//! Edits are expressed as byte-range replacements over the original source,
+//! so everything outside the replaced ranges is preserved byte for byte.
+//! Rule markers (`r[auth.login+2 status=draft]`) are located with
+//! [`find_rule_markers`] and modified with [`RuleMarker::set_version`],
+//! [`RuleMarker::rename`], and [`RuleMarker::set_attribute`], which only touch
+//! the marker's own bytes.
+
+use std::fmt;
+use std::ops::Range;
+
+use super::markdown_code_mask;
+use crate::{RuleId, parse_rule_id};
+
+/// Replace `range` of the source with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+/// Why a set of edits could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// The range is reversed or extends past the end of the source.
+    OutOfBounds { range: Range<usize>, len: usize },
+    /// The range starts or ends inside a multi-byte character.
+    NotCharBoundary { range: Range<usize> },
+    /// Two edits cover overlapping bytes.
+    Overlap {
+        first: Range<usize>,
+        second: Range<usize>,
+    },
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { range, len } => write!(
+                f,
+                "Invalid range: {}..{} (file length: {len})",
+                range.start, range.end
+            ),
+            Self::NotCharBoundary { range } => write!(
+                f,
+                "Invalid range: {}..{} does not fall on character boundaries",
+                range.start, range.end
+            ),
+            Self::Overlap { first, second } => write!(
+                f,
+                "Overlapping edits: {}..{} and {}..{}",
+                first.start, first.end, second.start, second.end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Apply `edits` to `source`.
+///
+/// Edits may be given in any order but must not overlap. Insertions (empty
+/// ranges) at the same offset are applied in the order given.
+pub fn apply_edits(source: &str, mut edits: Vec<TextEdit>) -> Result<String, EditError> {
+    for edit in &edits {
+        let range = edit.range.clone();
+        if range.start > range.end || range.end > source.len() {
+            return Err(EditError::OutOfBounds {
+                range,
+                len: source.len(),
+            });
+        }
+        if !source.is_char_boundary(range.start) || !source.is_char_boundary(range.end) {
+            return Err(EditError::NotCharBoundary { range });
+        }
+    }
+    // Stable sort keeps same-offset insertions in the order given.
+    edits.sort_by_key(|e| (e.range.start, e.range.end));
+    for pair in edits.windows(2) {
+        if pair[1].range.start < pair[0].range.end {
+            return Err(EditError::Overlap {
+                first: pair[0].range.clone(),
+                second: pair[1].range.clone(),
+            });
+        }
+    }
+
+    let delta: isize = edits
+        .iter()
+        .map(|e| e.replacement.len() as isize - e.range.len() as isize)
+        .sum();
+    let mut out = String::with_capacity(source.len().saturating_add_signed(delta));
+    let mut cursor = 0;
+    for edit in &edits {
+        out.push_str(&source[cursor..edit.range.start]);
+        out.push_str(&edit.replacement);
+        cursor = edit.range.end;
+    }
+    out.push_str(&source[cursor..]);
+    Ok(out)
+}
+
+/// A `key=value` attribute inside a rule marker.
+///
+/// Values containing whitespace or `]` are written in double quotes
+/// (`reason="not on wasm"`), with `\"` and `\\` as escapes; `value` holds
+/// the unquoted text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerAttribute {
+    pub key: String,
+    pub value: String,
+    /// Bytes of the whole `key=value` token, quotes included.
+    pub span: Range<usize>,
+}
+
+impl MarkerAttribute {
+    /// Whether the value was written in quotes.
+    pub fn is_quoted(&self, source: &str) -> bool {
+        source
+            .get(self.span.clone())
+            .and_then(|token| token.split_once('='))
+            .is_some_and(|(_, value)| value.starts_with('"'))
+    }
+}
+
+/// `value` as it must be written in a marker: bare when it can be, quoted
+/// otherwise.
+pub fn quote_attribute_value(value: &str) -> String {
+    if !value.is_empty()
+        && !value.contains(|c: char| c.is_whitespace() || matches!(c, ']' | '"' | '\\'))
+    {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A rule definition marker located in markdown source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMarker {
+    /// Bytes of the whole marker, from the prefix through the closing `]`.
+    pub span: Range<usize>,
+    /// Marker prefix, e.g. `r`.
+    pub prefix: String,
+    /// The rule ID as parsed.
+    pub id: RuleId,
+    /// Bytes of the rule ID token (including any `+N` suffix).
+    pub id_span: Range<usize>,
+    /// Attributes following the ID, in source order.
+    pub attributes: Vec<MarkerAttribute>,
+}
+
+impl RuleMarker {
+    /// Change the marker's version, leaving the rest of the marker untouched.
+    pub fn set_version(&self, version: u32) -> TextEdit {
+        TextEdit::new(self.id_span.clone(), format!("{}+{version}", self.id.base))
+    }
+
+    /// Give the rule a new base ID, keeping its version.
+    pub fn rename(&self, base: &str) -> TextEdit {
+        let token = if self.id.version == 1 {
+            base.to_string()
+        } else {
+            format!("{base}+{}", self.id.version)
+        };
+        TextEdit::new(self.id_span.clone(), token)
+    }
+
+    /// Set (or with `None`, remove) an attribute.
+    ///
+    /// Only the attribute's own token is rewritten; a new attribute is
+    /// appended after the last one, so the layout of markers spread over
+    /// several lines is kept.
+    pub fn set_attribute(&self, key: &str, value: Option<&str>) -> TextEdit {
+        let position = self.attributes.iter().position(|a| a.key == key);
+        let end_of =
+            |idx: Option<usize>| idx.map_or(self.id_span.end, |i| self.attributes[i].span.end);
+        match (position, value) {
+            (Some(idx), Some(value)) => TextEdit::new(
+                self.attributes[idx].span.clone(),
+                format!("{key}={}", quote_attribute_value(value)),
+            ),
+            (Some(idx), None) => TextEdit::new(
+                end_of(idx.checked_sub(1))..self.attributes[idx].span.end,
+                "",
+            ),
+            (None, Some(value)) => {
+                let at = end_of(self.attributes.len().checked_sub(1));
+                TextEdit::new(at..at, format!(" {key}={}", quote_attribute_value(value)))
+            }
+            (None, None) => {
+                let at = end_of(self.attributes.len().checked_sub(1));
+                TextEdit::new(at..at, "")
+            }
+        }
+    }
+}
+
+/// Find every rule definition marker in `markdown`.
+///
+/// A marker is `prefix[id attrs...]` at the start of a line, optionally
+/// inside a blockquote (`> r[...]`). Outside a blockquote, its attributes
+/// may continue on following lines as long as those are indented. Markers
+/// in code blocks and inline code are ignored, as are inline references in
+/// running text.
+pub fn find_rule_markers(markdown: &str) -> Vec<RuleMarker> {
+    let code_mask = markdown_code_mask(markdown);
+    let mut markers: Vec<RuleMarker> = Vec::new();
+    let mut line_start = 0;
+    for line in markdown.split_inclusive('\n') {
+        let consumed = markers.last().is_some_and(|m| m.span.end > line_start);
+        let content = line.trim_end_matches(['\n', '\r']);
+        let mut offset = content.len() - content.trim_start().len();
+        let quoted = content[offset..].starts_with('>');
+        while content[offset..].starts_with('>') {
+            offset += 1;
+            offset += content[offset..].len() - content[offset..].trim_start().len();
+        }
+        let start = line_start + offset;
+        let text = if quoted {
+            &content[offset..]
+        } else {
+            &markdown[start..]
+        };
+        if !consumed
+            && !code_mask.get(start).copied().unwrap_or(false)
+            && let Some(marker) = parse_marker(text, start)
+        {
+            markers.push(marker);
+        }
+        line_start += line.len();
+    }
+    markers
+}
+
+/// Find the definition marker for the rule with base ID `base`.
+pub fn find_rule_marker(markdown: &str, base: &str) -> Option<RuleMarker> {
+    find_rule_markers(markdown)
+        .into_iter()
+        .find(|m| m.id.base == base)
+}
+
+/// Parse a marker at the start of `text`, which begins at byte `base_offset`
+/// of the file.
+fn parse_marker(text: &str, base_offset: usize) -> Option<RuleMarker> {
+    let bracket = text.find('[')?;
+    let prefix = &text[..bracket];
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let id_start = bracket + 1;
+    let id_len = text[id_start..].find(|c: char| c.is_whitespace() || c == ']')?;
+    let id = parse_rule_id(&text[id_start..id_start + id_len])?;
+
+    let mut attributes = Vec::new();
+    let mut pos = id_start + id_len;
+    let close = loop {
+        pos = skip_marker_whitespace(text, pos)?;
+        if text[pos..].starts_with(']') {
+            break pos;
+        }
+        let key_len =
+            text[pos..].find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_')))?;
+        if key_len == 0 || !text[pos + key_len..].starts_with('=') {
+            return None;
+        }
+        let key = &text[pos..pos + key_len];
+        let (value, value_len) = parse_attribute_value(&text[pos + key_len + 1..])?;
+        let end = pos + key_len + 1 + value_len;
+        if !text[end..].starts_with(|c: char| c.is_whitespace() || c == ']') {
+            return None;
+        }
+        attributes.push(MarkerAttribute {
+            key: key.to_string(),
+            value,
+            span: base_offset + pos..base_offset + end,
+        });
+        pos = end;
+    };
+
+    Some(RuleMarker {
+        span: base_offset..base_offset + close + 1,
+        prefix: prefix.to_string(),
+        id,
+        id_span: base_offset + id_start..base_offset + id_start + id_len,
+        attributes,
+    })
+}
+
+/// Skip whitespace inside a marker from `pos`. A line break is only allowed
+/// when the next line is indented and not blank.
+fn skip_marker_whitespace(text: &str, mut pos: usize) -> Option<usize> {
+    loop {
+        let rest = &text[pos..];
+        let c = rest.chars().next()?;
+        if c == '\n' {
+            let next = &rest[1..];
+            let indent = next.len() - next.trim_start_matches([' ', '\t']).len();
+            if indent == 0 || next[indent..].starts_with(['\n', '\r']) || next[indent..].is_empty()
+            {
+                return None;
+            }
+            pos += 1 + indent;
+        } else if c.is_whitespace() {
+            pos += c.len_utf8();
+        } else {
+            return Some(pos);
+        }
+    }
+}
+
+/// Parse an attribute value at the start of `text`: a bare word, or a
+/// double-quoted string on one line. Returns the value and the bytes it
+/// took up.
+fn parse_attribute_value(text: &str) -> Option<(String, usize)> {
+    let Some(quoted) = text.strip_prefix('"') else {
+        let len = text
+            .find(|c: char| c.is_whitespace() || c == ']')
+            .unwrap_or(text.len());
+        return Some((text[..len].to_string(), len));
+    };
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((at, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, at + 2)),
+            '\\' => value.push(chars.next()?.1),
+            '\n' => return None,
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "# Auth\n\
+        \n\
+        r[auth.login]\n\
+        Users MUST log in. See r[auth.logout] too.\n\
+        \n\
+        > r[auth.logout+2 status=draft]\n\
+        > Users MUST be able to log out.\n\
+        \n\
+        ```markdown\n\
+        r[example.rule]\n\
+        ```\n";
+
+    fn edit(source: &str, edit: TextEdit) -> String {
+        apply_edits(source, vec![edit]).unwrap()
+    }
+
+    #[test]
+    fn finds_markers_outside_code() {
+        let markers = find_rule_markers(SPEC);
+        let ids: Vec<_> = markers.iter().map(|m| m.id.to_string()).collect();
+        assert_eq!(ids, ["auth.login", "auth.logout+2"]);
+        assert_eq!(&SPEC[markers[0].span.clone()], "r[auth.login]");
+        assert_eq!(
+            &SPEC[markers[1].span.clone()],
+            "r[auth.logout+2 status=draft]"
+        );
+        assert_eq!(markers[1].attributes[0].key, "status");
+    }
+
+    #[test]
+    fn bump_version_preserves_everything_else() {
+        let marker = find_rule_marker(SPEC, "auth.logout").unwrap();
+        let out = edit(SPEC, marker.set_version(3));
+        assert_eq!(
+            out,
+            SPEC.replace("auth.logout+2 status", "auth.logout+3 status")
+        );
+
+        let marker = find_rule_marker(SPEC, "auth.login").unwrap();
+        let out = edit(SPEC, marker.set_version(2));
+        assert_eq!(out, SPEC.replacen("r[auth.login]", "r[auth.login+2]", 1));
+    }
+
+    #[test]
+    fn rename_keeps_version() {
+        let marker = find_rule_marker(SPEC, "auth.logout").unwrap();
+        let out = edit(SPEC, marker.rename("session.end"));
+        assert!(out.contains("> r[session.end+2 status=draft]"));
+    }
+
+    #[test]
+    fn set_and_remove_attributes() {
+        let marker = find_rule_marker(SPEC, "auth.login").unwrap();
+        let out = edit(SPEC, marker.set_attribute("level", Some("should")));
+        assert!(out.contains("\nr[auth.login level=should]\n"));
+
+        let marker = find_rule_marker(SPEC, "auth.logout").unwrap();
+        let out = edit(SPEC, marker.set_attribute("status", Some("stable")));
+        assert!(out.contains("> r[auth.logout+2 status=stable]"));
+        let out = edit(SPEC, marker.set_attribute("status", None));
+        assert!(out.contains("> r[auth.logout+2]\n"));
+    }
+
+    #[test]
+    fn multiple_edits_apply_in_any_order() {
+        let markers = find_rule_markers(SPEC);
+        let edits = vec![markers[1].set_version(3), markers[0].set_version(2)];
+        let out = apply_edits(SPEC, edits).unwrap();
+        assert!(out.contains("r[auth.login+2]"));
+        assert!(out.contains("r[auth.logout+3 status=draft]"));
+        assert!(out.ends_with("```markdown\nr[example.rule]\n```\n"));
+    }
+
+    #[test]
+    fn invalid_edits_are_rejected() {
+        assert!(matches!(
+            apply_edits("abc", vec![TextEdit::new(2..5, "")]),
+            Err(EditError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            apply_edits("é", vec![TextEdit::new(1..2, "")]),
+            Err(EditError::NotCharBoundary { .. })
+        ));
+        assert!(matches!(
+            apply_edits(
+                "abcdef",
+                vec![TextEdit::new(0..3, ""), TextEdit::new(2..4, "")]
+            ),
+            Err(EditError::Overlap { .. })
+        ));
+    }
+    #[test]
+    fn quoted_values_keep_spaces_commas_and_brackets() {
+        let source =
+            "r[net.retry reason=\"not on wasm, yet\" note=\"a ] b \\\" c\" level=must]\nText.\n";
+        let marker = find_rule_marker(source, "net.retry").unwrap();
+        let values: Vec<_> = marker
+            .attributes
+            .iter()
+            .map(|a| (a.key.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("reason", "not on wasm, yet"),
+                ("note", "a ] b \" c"),
+                ("level", "must")
+            ]
+        );
+        assert_eq!(&source[marker.span.clone()], source.lines().next().unwrap());
+        assert!(marker.attributes[0].is_quoted(source));
+        assert!(!marker.attributes[2].is_quoted(source));
+
+        let unterminated = "r[net.retry reason=\"open]\nText.\n";
+        assert!(find_rule_markers(unterminated).is_empty());
+    }
+
+    #[test]
+    fn attributes_continue_on_indented_lines() {
+        let source = "r[net.retry level=must\n    reason=\"flaky links\"\n    ]\nText r[not.a.marker].\n\nr[net.next]\nMore.\n";
+        let markers = find_rule_markers(source);
+        let ids: Vec<_> = markers.iter().map(|m| m.id.to_string()).collect();
+        assert_eq!(ids, ["net.retry", "net.next"]);
+        assert_eq!(markers[0].attributes[1].value, "flaky links");
+        assert!(source[markers[0].span.clone()].ends_with("\n    ]"));
+
+        // An unindented or blank line ends the marker without closing it.
+        assert!(find_rule_markers("r[a.b level=must\nreason=x]\n").is_empty());
+        assert!(find_rule_markers("r[a.b level=must\n\n    ]\n").is_empty());
+        // Blockquoted markers stay on one line.
+        assert!(find_rule_markers("> r[a.b level=must\n>     ]\n").is_empty());
+    }
+
+    #[test]
+    fn set_attribute_quotes_and_keeps_layout() {
+        let source = "r[net.retry level=must\n    reason=x\n    ]\nText.\n";
+        let marker = find_rule_marker(source, "net.retry").unwrap();
+        let out = edit(source, marker.set_attribute("reason", Some("not on wasm")));
+        assert_eq!(
+            out,
+            "r[net.retry level=must\n    reason=\"not on wasm\"\n    ]\nText.\n"
+        );
+        let out = edit(source, marker.set_attribute("level", None));
+        assert_eq!(out, "r[net.retry\n    reason=x\n    ]\nText.\n");
+        let out = edit(source, marker.set_attribute("owner", Some("net")));
+        assert!(out.starts_with("r[net.retry level=must\n    reason=x owner=net\n"));
+    }
+}
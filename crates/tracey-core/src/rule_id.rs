@@ -94,6 +94,23 @@ pub fn parse_rule_id(id: &str) -> Option<RuleId> {
     }
 }
 
+/// Whether `id` is a rule ID as written in a spec or an attribute: one or
+/// more dot-separated segments of ASCII letters, digits, `-` and `_`, with
+/// an optional `+N` version. [`parse_rule_id`] only splits off the version;
+/// this also checks every segment, so wildcard patterns are rejected.
+///
+/// r[impl ref.syntax.req-id+3]
+pub fn is_valid_rule_id(id: &str) -> bool {
+    parse_rule_id(id).is_some_and(|parsed| {
+        parsed.base.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        })
+    })
+}
+
 /// Compare two structured rule IDs.
 pub fn classify_reference_for_rule(rule_id: &RuleId, reference_id: &RuleId) -> RuleIdMatch {
     if rule_id.base != reference_id.base {
@@ -119,10 +136,168 @@ pub fn classify_reference_for_rule_str(rule_id: &str, reference_id: &str) -> Rul
     classify_reference_for_rule(&rule, &reference)
 }
 
+/// Expand the `{a,b}` groups of a reference's rule ID, so that
+/// `frame.{header,payload}.parse` becomes `frame.header.parse` and
+/// `frame.payload.parse`. Returns `None` for unbalanced or nested braces,
+/// empty alternatives, and commas outside a group.
+///
+/// r[impl ref.syntax.patterns]
+pub fn expand_rule_braces(id: &str) -> Option<Vec<String>> {
+    let Some(open) = id.find('{') else {
+        return (!id.contains(['}', ','])).then(|| vec![id.to_string()]);
+    };
+    let (head, rest) = (&id[..open], &id[open + 1..]);
+    if head.contains(['}', ',']) {
+        return None;
+    }
+    let close = rest.find('}')?;
+    let (group, tail) = (&rest[..close], &rest[close + 1..]);
+    if group.contains('{') {
+        return None;
+    }
+    let tails = expand_rule_braces(tail)?;
+    let mut out = Vec::new();
+    for alternative in group.split(',') {
+        if alternative.is_empty() {
+            return None;
+        }
+        for tail in &tails {
+            out.push(format!("{head}{alternative}{tail}"));
+        }
+    }
+    Some(out)
+}
+
+/// Split a reference's comma-separated list of rule IDs, as in
+/// `a.b, c.d`, and expand each one's brace groups. Returns `None` if any
+/// entry is empty or has malformed braces.
+///
+/// r[impl ref.syntax.list]
+pub fn expand_rule_list(text: &str) -> Option<Vec<String>> {
+    let text: String = text.split_whitespace().collect();
+    let expand = |entry: &str| {
+        if entry.is_empty() {
+            None
+        } else {
+            expand_rule_braces(entry)
+        }
+    };
+    let mut out = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                out.extend(expand(&text[start..idx])?);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    out.extend(expand(&text[start..])?);
+    Some(out)
+}
+
+/// Whether a reference's rule ID is a wildcard pattern like `channel.id.*`,
+/// to be expanded against the spec's rules.
+pub fn is_rule_pattern(id: &RuleId) -> bool {
+    id.base.contains('*')
+}
+
+/// Whether the wildcard `pattern` matches the rule ID base `base`. A `*`
+/// segment matches any one segment, or any number of segments (at least
+/// one) when it is the last.
+///
+/// r[impl ref.syntax.patterns]
+pub fn rule_pattern_matches(pattern: &str, base: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let segments: Vec<&str> = base.split('.').collect();
+    let segment_matches = |(p, s): (&&str, &&str)| *p == "*" || p == s;
+    match pattern.split_last() {
+        Some((&"*", init)) => {
+            segments.len() > init.len() && init.iter().zip(&segments).all(segment_matches)
+        }
+        _ => pattern.len() == segments.len() && pattern.iter().zip(&segments).all(segment_matches),
+    }
+}
+
+/// Find rule-ID-shaped tokens mentioned in prose.
+///
+/// A candidate is a run of identifier characters containing at least one dot
+/// (e.g. `auth.login` or `auth.login+2`), with trailing sentence punctuation
+/// trimmed. Callers are expected to filter candidates against known rule IDs.
+/// Each ID is reported once, in order of first appearance.
+pub fn find_rule_id_mentions(text: &str) -> Vec<RuleId> {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+');
+
+    let mut mentions: Vec<RuleId> = Vec::new();
+    for token in text.split(|c: char| !is_id_char(c)) {
+        let token = token.trim_end_matches(['.', '-', '+']);
+        let base = token.split('+').next().unwrap_or(token);
+        if !base.contains('.') || base.split('.').any(str::is_empty) {
+            continue;
+        }
+        if let Some(id) = parse_rule_id(token)
+            && !mentions.contains(&id)
+        {
+            mentions.push(id);
+        }
+    }
+    mentions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // r[verify ref.syntax.patterns]
+    #[test]
+    fn braces_expand_and_wildcards_match() {
+        assert_eq!(
+            expand_rule_braces("frame.{header,payload}.parse").unwrap(),
+            ["frame.header.parse", "frame.payload.parse"]
+        );
+        assert_eq!(
+            expand_rule_braces("a.{b,c}.{d,e}").unwrap(),
+            ["a.b.d", "a.b.e", "a.c.d", "a.c.e"]
+        );
+        assert_eq!(expand_rule_braces("auth.login").unwrap(), ["auth.login"]);
+        for malformed in ["a.{b", "a.b}", "a.{b,}", "a.{{b}}", "a,b"] {
+            assert!(expand_rule_braces(malformed).is_none(), "{malformed}");
+        }
+
+        assert!(rule_pattern_matches(
+            "channel.id.*",
+            "channel.id.allocation"
+        ));
+        assert!(rule_pattern_matches(
+            "channel.id.*",
+            "channel.id.parity.even"
+        ));
+        assert!(!rule_pattern_matches("channel.id.*", "channel.id"));
+        assert!(!rule_pattern_matches("channel.id.*", "channel.ids.x"));
+        assert!(rule_pattern_matches("frame.*.parse", "frame.header.parse"));
+        assert!(!rule_pattern_matches("frame.*.parse", "frame.a.b.parse"));
+    }
+
+    // r[verify ref.syntax.list]
+    #[test]
+    fn rule_lists_split_outside_braces() {
+        assert_eq!(
+            expand_rule_list("a.b, c.d,e.f").unwrap(),
+            ["a.b", "c.d", "e.f"]
+        );
+        assert_eq!(
+            expand_rule_list("a.{b,c}, d.e").unwrap(),
+            ["a.b", "a.c", "d.e"]
+        );
+        for malformed in ["a.b,", "a.b,, c.d", ", a.b", "a.{b, c.d"] {
+            assert!(expand_rule_list(malformed).is_none(), "{malformed}");
+        }
+    }
+
     #[test]
     fn parse_rule_id_supports_implicit_v1() {
         let parsed = parse_rule_id("auth.login").expect("must parse");
@@ -174,6 +349,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn find_rule_id_mentions_in_prose() {
+        let mentions = find_rule_id_mentions(
+            "See auth.login and `r[auth.logout+2]`. Unlike auth.login, this (e.g. not a.) ends.",
+        );
+        assert_eq!(
+            mentions,
+            vec![
+                parse_rule_id("auth.login").unwrap(),
+                parse_rule_id("auth.logout+2").unwrap(),
+                parse_rule_id("e.g").unwrap(),
+            ]
+        );
+    }
+
     #[test]
     fn classify_reference_detects_no_match() {
         let rule = parse_rule_id("auth.login+2").expect("must parse");
@@ -188,4 +378,28 @@ mod tests {
             RuleIdMatch::NoMatch
         );
     }
+
+    // r[verify ref.syntax.req-id+3]
+    #[test]
+    fn valid_rule_ids_follow_the_segment_grammar() {
+        for id in ["auth.login", "auth.login+2", "A_b.c-d.9", "single"] {
+            assert!(is_valid_rule_id(id), "{id}");
+        }
+        for id in [
+            "",
+            "bad..id",
+            ".auth",
+            "auth.",
+            "auth.*",
+            "auth login",
+            "auth/login",
+            "auth.login+0",
+            "auth.login+",
+            "auth.login+x",
+            "auth+1+2",
+            "+2",
+        ] {
+            assert!(!is_valid_rule_id(id), "{id}");
+        }
+    }
 }
@@ -0,0 +1,194 @@
+//! Annotation snippets formatted for a target language
+//!
+//! Produces ready-to-paste comments like `// r[impl auth.login]` or
+//! `(* r[impl auth.login] *)` so tooling can hand users and agents an
+//! annotation that the extractor will pick up as-is. Only languages in
+//! [`SUPPORTED_EXTENSIONS`](crate::SUPPORTED_EXTENSIONS) are known here;
+//! file types declared with custom comment markers go through
+//! [`format_annotation_with`].
+
+use crate::{CommentDelimiters, RefVerb, RuleId};
+
+/// How a comment is written in a given language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentSyntax {
+    /// A line comment introduced by a marker (e.g. `//`, `#`, `--`).
+    Line(&'static str),
+    /// A block comment delimited by an opening and closing marker.
+    Block(&'static str, &'static str),
+}
+
+impl CommentSyntax {
+    /// Look up the comment syntax for a language.
+    ///
+    /// Accepts either a file extension the extractor scans (`rs`, `py`,
+    /// `.ml`) or the name of its language (`rust`, `python`, `ocaml`).
+    /// Matching is case-insensitive.
+    pub fn for_language(language: &str) -> Option<Self> {
+        let lower = language.trim().trim_start_matches('.').to_ascii_lowercase();
+        let syntax = match lower.as_str() {
+            "rs" | "rust" | "swift" | "ts" | "tsx" | "typescript" | "js" | "jsx" | "javascript"
+            | "go" | "golang" | "c" | "h" | "cpp" | "hpp" | "cc" | "cxx" | "c++" | "m" | "mm"
+            | "objc" | "objective-c" | "java" | "kt" | "kts" | "kotlin" | "scala" | "groovy"
+            | "cs" | "csharp" | "c#" | "zig" | "php" | "dart" | "d" | "fs" | "fsi" | "fsx"
+            | "fsharp" | "f#" => Self::Line("//"),
+            "py" | "python" | "rb" | "ruby" | "r" | "pl" | "pm" | "perl" | "ex" | "exs"
+            | "elixir" | "sh" | "bash" | "zsh" | "shell" | "nix" | "ps1" | "psm1" | "psd1"
+            | "powershell" | "cmake" | "jl" | "julia" => Self::Line("#"),
+            "lua" | "hs" | "lhs" | "haskell" => Self::Line("--"),
+            "erl" | "hrl" | "erlang" => Self::Line("%"),
+            "clj" | "cljs" | "cljc" | "edn" | "clojure" => Self::Line(";;"),
+            "asm" | "s" | "assembly" => Self::Line(";"),
+            "vb" | "vbs" | "vbnet" | "visualbasic" => Self::Line("'"),
+            "cob" | "cbl" | "cpy" | "cobol" => Self::Line("*>"),
+            "tla" | "tla+" => Self::Line("\\*"),
+            "ml" | "mli" | "ocaml" => Self::Block("(*", "*)"),
+            _ => return None,
+        };
+        Some(syntax)
+    }
+
+    /// Wrap `text` in this comment syntax.
+    pub fn wrap(&self, text: &str) -> String {
+        match self {
+            Self::Line(marker) => format!("{marker} {text}"),
+            Self::Block(open, close) => format!("{open} {text} {close}"),
+        }
+    }
+}
+
+/// Format an annotation marker (`prefix[verb rule.id]`) as a comment for `language`.
+///
+/// The rule ID is rendered in canonical form, so versioned rules carry their
+/// `+N` suffix. Returns `None` if the language is not recognized.
+pub fn format_annotation(
+    prefix: &str,
+    verb: RefVerb,
+    rule_id: &RuleId,
+    language: &str,
+) -> Option<String> {
+    let syntax = CommentSyntax::for_language(language)?;
+    Some(syntax.wrap(&format!("{prefix}[{verb} {rule_id}]")))
+}
+
+/// Format an annotation marker as a comment using custom comment markers,
+/// preferring a line comment. Returns `None` if `delimiters` is empty.
+pub fn format_annotation_with(
+    prefix: &str,
+    verb: RefVerb,
+    rule_id: &RuleId,
+    delimiters: &CommentDelimiters,
+) -> Option<String> {
+    let text = format!("{prefix}[{verb} {rule_id}]");
+    match (delimiters.line.first(), delimiters.block.first()) {
+        (Some(marker), _) => Some(format!("{marker} {text}")),
+        (None, Some((open, close))) => Some(format!("{open} {text} {close}")),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rule_id;
+
+    fn rid(id: &str) -> RuleId {
+        parse_rule_id(id).expect("valid rule id")
+    }
+
+    #[test]
+    fn line_comment_languages() {
+        let id = rid("auth.login");
+        assert_eq!(
+            format_annotation("r", RefVerb::Impl, &id, "rust").as_deref(),
+            Some("// r[impl auth.login]")
+        );
+        assert_eq!(
+            format_annotation("r", RefVerb::Verify, &id, "py").as_deref(),
+            Some("# r[verify auth.login]")
+        );
+        assert_eq!(
+            format_annotation("r", RefVerb::Impl, &id, "lua").as_deref(),
+            Some("-- r[impl auth.login]")
+        );
+    }
+
+    #[test]
+    fn block_comment_languages() {
+        let id = rid("auth.login");
+        assert_eq!(
+            format_annotation("r", RefVerb::Impl, &id, ".ml").as_deref(),
+            Some("(* r[impl auth.login] *)")
+        );
+    }
+
+    #[test]
+    fn only_scanned_extensions_are_known() {
+        for ext in crate::SUPPORTED_EXTENSIONS {
+            assert!(CommentSyntax::for_language(ext).is_some(), "{ext}");
+        }
+        for ext in [
+            "yaml", "toml", "sql", "html", "xml", "md", "vue", "css", "mjs",
+        ] {
+            assert!(
+                !crate::is_supported_extension(std::ffi::OsStr::new(ext)),
+                "{ext}"
+            );
+            assert_eq!(CommentSyntax::for_language(ext), None, "{ext}");
+        }
+    }
+
+    #[cfg(feature = "reverse")]
+    #[test]
+    fn extracted_as_written() {
+        let id = rid("auth.login");
+        for ext in ["rs", "py", "lua", "erl", "clj", "ml", "tla", "vb"] {
+            let snippet = format_annotation("r", RefVerb::Impl, &id, ext).unwrap();
+            let path = std::path::PathBuf::from(format!("x.{ext}"));
+            let reqs = crate::Reqs::extract_from_content(&path, &format!("{snippet}\n"));
+            assert_eq!(reqs.references.len(), 1, "{ext}: {snippet}");
+        }
+    }
+
+    #[test]
+    fn custom_delimiters() {
+        let id = rid("auth.login");
+        let sql = CommentDelimiters {
+            line: vec!["--".to_string()],
+            block: vec![("/*".to_string(), "*/".to_string())],
+        };
+        assert_eq!(
+            format_annotation_with("r", RefVerb::Impl, &id, &sql).as_deref(),
+            Some("-- r[impl auth.login]")
+        );
+        let html = CommentDelimiters {
+            line: Vec::new(),
+            block: vec![("<!--".to_string(), "-->".to_string())],
+        };
+        assert_eq!(
+            format_annotation_with("r", RefVerb::Verify, &id, &html).as_deref(),
+            Some("<!-- r[verify auth.login] -->")
+        );
+        assert_eq!(
+            format_annotation_with("r", RefVerb::Impl, &id, &CommentDelimiters::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn versioned_rule_keeps_suffix() {
+        let id = rid("auth.login+3");
+        assert_eq!(
+            format_annotation("shm", RefVerb::Impl, &id, "ts").as_deref(),
+            Some("// shm[impl auth.login+3]")
+        );
+    }
+
+    #[test]
+    fn unknown_language_is_rejected() {
+        assert_eq!(
+            format_annotation("r", RefVerb::Impl, &rid("a.b"), "brainfuck"),
+            None
+        );
+    }
+}
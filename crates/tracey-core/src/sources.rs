@@ -1,11 +1,12 @@
 //! Source providers for requirement extraction
 
+use crate::encoding::read_source;
 use crate::lexer::{Reqs, extract_from_content};
 use eyre::Result;
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
-#[cfg(feature = "walk")]
-use std::path::Path;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// r[impl ref.cross-workspace.missing-paths]
 /// Result of extracting requirements, including any warnings about missing files
@@ -15,6 +16,15 @@ pub struct ExtractionResult {
     pub warnings: Vec<String>,
 }
 
+/// Requirements extracted from a single file, as handed out by
+/// [`Sources::extract_streaming`]
+#[derive(Debug)]
+pub struct FileReqs {
+    pub path: PathBuf,
+    /// References and warnings of this file, ordered by byte offset
+    pub reqs: Reqs,
+}
+
 /// File extensions that tracey knows how to scan for requirement references.
 pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     "rs",     // Rust
@@ -81,6 +91,7 @@ pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     "bash",   // Bash
     "zsh",    // Zsh
     "nix",    // Nix
+    "tla",    // TLA+
 ];
 
 /// Check if a file extension is supported for scanning
@@ -101,55 +112,108 @@ pub fn is_spec_extension(ext: &OsStr) -> bool {
 pub trait Sources {
     /// Extract requirements from all sources
     fn extract(self) -> Result<ExtractionResult>;
+
+    /// Extract requirements file by file, calling `on_file` as soon as each
+    /// file is done instead of buffering everything. Files arrive in no
+    /// particular order, from several threads at once when the `parallel`
+    /// feature is on. Returns the warnings that are not about one file.
+    ///
+    /// The default implementation runs [`Sources::extract`] and hands out
+    /// its result one file at a time.
+    ///
+    /// r[impl ref.streaming]
+    fn extract_streaming<F>(self, on_file: F) -> Result<Vec<String>>
+    where
+        Self: Sized,
+        F: Fn(FileReqs) + Sync,
+    {
+        let result = self.extract()?;
+        let mut by_file: BTreeMap<PathBuf, Reqs> = BTreeMap::new();
+        for reference in result.reqs.references {
+            by_file
+                .entry(reference.file.clone())
+                .or_default()
+                .references
+                .push(reference);
+        }
+        for warning in result.reqs.warnings {
+            by_file
+                .entry(warning.file.clone())
+                .or_default()
+                .warnings
+                .push(warning);
+        }
+        for (path, mut reqs) in by_file {
+            reqs.sort_by_location();
+            on_file(FileReqs { path, reqs });
+        }
+        Ok(result.warnings)
+    }
+}
+
+/// Collect a streamed extraction into one result, ordered by location.
+fn collect_streamed(sources: impl Sources) -> Result<ExtractionResult> {
+    let reqs = Mutex::new(Reqs::new());
+    let warnings = sources.extract_streaming(|file| reqs.lock().unwrap().extend(file.reqs))?;
+    let mut reqs = reqs.into_inner().unwrap();
+    reqs.sort_by_location();
+    Ok(ExtractionResult { reqs, warnings })
+}
+
+/// Read and extract one file.
+fn extract_file(path: &Path, on_file: &impl Fn(FileReqs)) -> Result<()> {
+    let (content, encoding) = read_source(path)?;
+    let mut reqs = Reqs::new();
+    reqs.warnings.extend(encoding.warning(path));
+    extract_from_content(path, &content, &[], &mut reqs);
+    reqs.sort_by_location();
+    on_file(FileReqs {
+        path: path.to_path_buf(),
+        reqs,
+    });
+    Ok(())
 }
 
 /// Sources from an explicit list of file paths
-pub struct PathSources(Vec<PathBuf>);
+pub struct PathSources {
+    paths: Vec<PathBuf>,
+}
 
 impl PathSources {
     /// Create from an iterator of paths
     pub fn new(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
-        Self(paths.into_iter().map(Into::into).collect())
+        Self {
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
     }
 }
 
 impl Sources for PathSources {
     fn extract(self) -> Result<ExtractionResult> {
+        collect_streamed(self)
+    }
+
+    fn extract_streaming<F>(self, on_file: F) -> Result<Vec<String>>
+    where
+        F: Fn(FileReqs) + Sync,
+    {
         #[cfg(feature = "parallel")]
         {
             use rayon::prelude::*;
-            use std::sync::Mutex;
-
-            let reqs_mutex = Mutex::new(Reqs::new());
-
-            self.0.par_iter().try_for_each(|path| -> Result<()> {
-                let content = std::fs::read_to_string(path)?;
-                let mut file_reqs = Reqs::new();
-                extract_from_content(path, &content, &mut file_reqs);
-
-                let mut guard = reqs_mutex.lock().unwrap();
-                guard.extend(file_reqs);
-                Ok(())
-            })?;
 
-            Ok(ExtractionResult {
-                reqs: reqs_mutex.into_inner().unwrap(),
-                warnings: Vec::new(),
-            })
+            self.paths
+                .par_iter()
+                .try_for_each(|path| extract_file(path, &on_file))?;
         }
 
         #[cfg(not(feature = "parallel"))]
         {
-            let mut reqs = Reqs::new();
-            for path in self.0 {
-                let content = std::fs::read_to_string(&path)?;
-                extract_from_content(&path, &content, &mut reqs);
+            for path in &self.paths {
+                extract_file(path, &on_file)?;
             }
-            Ok(ExtractionResult {
-                reqs,
-                warnings: Vec::new(),
-            })
         }
+
+        Ok(Vec::new())
     }
 }
 
@@ -179,13 +243,26 @@ impl Sources for MemorySources {
     fn extract(self) -> Result<ExtractionResult> {
         let mut reqs = Reqs::new();
         for (path, content) in self.0 {
-            extract_from_content(&path, &content, &mut reqs);
+            extract_from_content(&path, &content, &[], &mut reqs);
         }
         Ok(ExtractionResult {
             reqs,
             warnings: Vec::new(),
         })
     }
+
+    fn extract_streaming<F>(self, on_file: F) -> Result<Vec<String>>
+    where
+        F: Fn(FileReqs) + Sync,
+    {
+        for (path, content) in self.0 {
+            let mut reqs = Reqs::new();
+            extract_from_content(&path, &content, &[], &mut reqs);
+            reqs.sort_by_location();
+            on_file(FileReqs { path, reqs });
+        }
+        Ok(Vec::new())
+    }
 }
 
 /// Gitignore-aware directory walker
@@ -223,10 +300,15 @@ impl WalkSources {
 #[cfg(feature = "walk")]
 impl Sources for WalkSources {
     fn extract(self) -> Result<ExtractionResult> {
+        collect_streamed(self)
+    }
+
+    fn extract_streaming<F>(self, on_file: F) -> Result<Vec<String>>
+    where
+        F: Fn(FileReqs) + Sync,
+    {
         use ignore::WalkBuilder;
-        use std::sync::Mutex;
 
-        let reqs = Mutex::new(Reqs::new());
         let warnings = Mutex::new(Vec::new());
 
         // r[impl ref.cross-workspace.paths]
@@ -250,7 +332,7 @@ impl Sources for WalkSources {
 
             // Process files in parallel using ignore's parallel walker
             walker.run(|| {
-                let reqs_ref = &reqs;
+                let on_file = &on_file;
                 let include_patterns = include_patterns.to_vec();
                 let exclude_patterns = exclude_patterns.to_vec();
                 let root = root.to_path_buf();
@@ -282,14 +364,8 @@ impl Sources for WalkSources {
                         return ignore::WalkState::Continue;
                     }
 
-                    // Read and extract
-                    if let Ok(content) = std::fs::read_to_string(path) {
-                        let mut file_reqs = Reqs::new();
-                        extract_from_content(path, &content, &mut file_reqs);
-
-                        let mut guard = reqs_ref.lock().unwrap();
-                        guard.extend(file_reqs);
-                    }
+                    // Read and extract; unreadable files are skipped
+                    let _ = extract_file(path, on_file);
 
                     ignore::WalkState::Continue
                 })
@@ -327,10 +403,7 @@ impl Sources for WalkSources {
             walk_with_patterns(&resolved_path, &[adjusted_pattern], &self.exclude);
         }
 
-        Ok(ExtractionResult {
-            reqs: reqs.into_inner().unwrap(),
-            warnings: warnings.into_inner().unwrap(),
-        })
+        Ok(warnings.into_inner().unwrap())
     }
 }
 
@@ -412,6 +485,198 @@ mod tests {
         assert!(result.warnings.is_empty());
     }
 
+    // r[verify ref.encoding.fallback]
+    // r[verify ref.encoding.warning]
+    #[test]
+    fn test_path_sources_non_utf8() {
+        let dir = std::env::temp_dir().join(format!("tracey-encoding-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let latin1 = dir.join("legacy.c");
+        std::fs::write(&latin1, b"/* caf\xe9 */\n// r[impl legacy.req]\n").unwrap();
+        let utf16 = dir.join("windows.ts");
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "// r[impl windows.req]\n".encode_utf16() {
+            bytes.extend(unit.to_le_bytes());
+        }
+        std::fs::write(&utf16, bytes).unwrap();
+
+        let result = Reqs::extract(PathSources::new([&latin1, &utf16])).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut ids: Vec<_> = result
+            .reqs
+            .references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["legacy.req", "windows.req"]);
+        assert_eq!(result.reqs.warnings.len(), 2);
+        assert!(
+            result
+                .reqs
+                .warnings
+                .iter()
+                .all(|w| matches!(w.kind, crate::WarningKind::NonUtf8Encoding(_)))
+        );
+    }
+
+    /// Extract the files one after another, in path order.
+    fn extract_serially(paths: &[PathBuf]) -> Reqs {
+        let mut reqs = Reqs::new();
+        for path in paths {
+            let content = std::fs::read_to_string(path).unwrap();
+            extract_from_content(path, &content, &[], &mut reqs);
+        }
+        reqs
+    }
+
+    // r[verify ref.ordering]
+    #[test]
+    fn test_parallel_extraction_matches_serial() {
+        let dir = std::env::temp_dir().join(format!("tracey-ordering-{}", std::process::id()));
+        let mut paths = Vec::new();
+        for d in 0..4 {
+            for f in 0..12 {
+                let path = dir.join(format!("m{d}/f{f:02}.rs"));
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(
+                    &path,
+                    format!(
+                        "// r[impl a.m{d}.f{f}]\nfn f() {{}}\n// r[impl b.m{d}.]\n// r[verify c.m{d}.f{f}]\n"
+                    ),
+                )
+                .unwrap();
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let serial = extract_serially(&paths);
+        assert_eq!(serial.warnings.len(), paths.len());
+        let expected = format!("{serial:?}");
+
+        for _ in 0..4 {
+            let reversed: Vec<_> = paths.iter().rev().cloned().collect();
+            let result = Reqs::extract(PathSources::new(reversed)).unwrap();
+            assert_eq!(format!("{:?}", result.reqs), expected);
+
+            #[cfg(feature = "walk")]
+            {
+                let result = Reqs::extract(WalkSources::new(&dir)).unwrap();
+                assert_eq!(format!("{:?}", result.reqs), expected);
+            }
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // r[verify ref.streaming]
+    #[test]
+    fn test_streaming_hands_out_each_file() {
+        let dir = std::env::temp_dir().join(format!("tracey-streaming-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut paths = Vec::new();
+        for f in 0..8 {
+            let path = dir.join(format!("f{f}.rs"));
+            std::fs::write(
+                &path,
+                format!("// r[verify s.f{f}.b]\nfn f() {{}}\n// r[impl s.f{f}.a]\n"),
+            )
+            .unwrap();
+            paths.push(path);
+        }
+
+        let files = Mutex::new(Vec::new());
+        let warnings = Reqs::extract_streaming(PathSources::new(paths.clone()), |file| {
+            files.lock().unwrap().push(file)
+        })
+        .unwrap();
+        assert!(warnings.is_empty());
+        let mut files = files.into_inner().unwrap();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let streamed: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(streamed, paths);
+        for (f, file) in files.iter().enumerate() {
+            let ids: Vec<_> = file
+                .reqs
+                .references
+                .iter()
+                .map(|r| r.req_id.to_string())
+                .collect();
+            assert_eq!(ids, [format!("s.f{f}.b"), format!("s.f{f}.a")]);
+        }
+
+        let mut merged = Reqs::new();
+        for file in files {
+            merged.extend(file.reqs);
+        }
+        let buffered = Reqs::extract(PathSources::new(paths)).unwrap();
+        assert_eq!(format!("{merged:?}"), format!("{:?}", buffered.reqs));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Sources that only implement `extract`.
+    struct Buffered(MemorySources);
+
+    impl Sources for Buffered {
+        fn extract(self) -> Result<ExtractionResult> {
+            self.0.extract()
+        }
+    }
+
+    #[test]
+    fn test_default_streaming_groups_by_file() {
+        let files = Mutex::new(Vec::new());
+        Reqs::extract_streaming(
+            Buffered(
+                MemorySources::new()
+                    .add("b.rs", "// r[impl b.one]\n// r[impl b.two]")
+                    .add("a.rs", "// r[impl a.one]"),
+            ),
+            |file| {
+                let ids: Vec<_> = file
+                    .reqs
+                    .references
+                    .iter()
+                    .map(|r| r.req_id.to_string())
+                    .collect();
+                files.lock().unwrap().push((file.path, ids));
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            files.into_inner().unwrap(),
+            [
+                (PathBuf::from("a.rs"), vec!["a.one".to_string()]),
+                (
+                    PathBuf::from("b.rs"),
+                    vec!["b.one".to_string(), "b.two".to_string()]
+                ),
+            ]
+        );
+    }
+
+    // r[verify ref.streaming]
+    #[test]
+    fn test_memory_streaming_orders_each_file_by_offset() {
+        let files = Mutex::new(Vec::new());
+        Reqs::extract_streaming(
+            MemorySources::new().add(
+                "a.rs",
+                "#[tracey::impls(\"a.one\")]\nfn one() {}\n// r[impl a.two]\nfn two() {}\n",
+            ),
+            |file| files.lock().unwrap().push(file),
+        )
+        .unwrap();
+        let files = files.into_inner().unwrap();
+        let ids: Vec<_> = files[0]
+            .reqs
+            .references
+            .iter()
+            .map(|r| r.req_id.to_string())
+            .collect();
+        assert_eq!(ids, ["a.one", "a.two"]);
+    }
+
     #[test]
     fn test_memory_sources_swift() {
         let result = Reqs::extract(
@@ -0,0 +1,213 @@
+//! Attributes tying Rust items to tracey spec requirements
+//!
+//! r[impl ref.attributes]
+//!
+//! ```ignore
+//! #[tracey::impls("channel.id.allocation")]
+//! fn allocate_id() -> u32 { /* ... */ }
+//!
+//! #[tracey::verifies("channel.id.allocation", "channel.id.parity")]
+//! #[test]
+//! fn ids_alternate_parity() { /* ... */ }
+//! ```
+//!
+//! tracey reads these attributes from source like `r[impl ...]` comments,
+//! so they need no build step to count. The attributes themselves leave the
+//! item unchanged apart from a line added to its docs, and reject rule IDs
+//! that tracey would not parse.
+
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Mark an item as implementing the given requirements.
+#[proc_macro_attribute]
+pub fn impls(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    annotate("Implements", attr.into(), item.into()).into()
+}
+
+/// Mark an item as verifying the given requirements.
+#[proc_macro_attribute]
+pub fn verifies(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    annotate("Verifies", attr.into(), item.into()).into()
+}
+
+fn annotate(verb: &str, attr: TokenStream, item: TokenStream) -> TokenStream {
+    let ids = match rule_ids(attr) {
+        Ok(ids) => ids,
+        Err((message, span)) => {
+            // Keep the item so the error isn't buried under unresolved names
+            let mut out = compile_error(&message, span);
+            out.extend(item);
+            return out;
+        }
+    };
+    let listed: Vec<String> = ids.iter().map(|id| format!("`{id}`")).collect();
+    let doc = format!("\n\n{verb} {}.", listed.join(", "));
+
+    let mut out = doc_attribute(&doc);
+    out.extend(item);
+    out
+}
+
+/// The string literals of `attr`, which must be comma-separated rule IDs.
+fn rule_ids(attr: TokenStream) -> Result<Vec<String>, (String, Span)> {
+    let mut ids = Vec::new();
+    let mut expect_id = true;
+    for token in attr {
+        match (&token, expect_id) {
+            (TokenTree::Literal(literal), true) => {
+                let id = string_value(literal)
+                    .ok_or_else(|| ("expected a rule ID string".to_string(), literal.span()))?;
+                if !tracey_core::is_valid_rule_id(&id) {
+                    return Err((format!("`{id}` is not a valid rule ID"), literal.span()));
+                }
+                ids.push(id);
+                expect_id = false;
+            }
+            (TokenTree::Punct(punct), false) if punct.as_char() == ',' => expect_id = true,
+            _ => {
+                return Err((
+                    "expected rule ID strings separated by commas".to_string(),
+                    token.span(),
+                ));
+            }
+        }
+    }
+    if ids.is_empty() {
+        return Err((
+            "expected at least one rule ID".to_string(),
+            Span::call_site(),
+        ));
+    }
+    Ok(ids)
+}
+
+/// Contents of a plain string literal, which rule IDs never need to escape.
+fn string_value(literal: &Literal) -> Option<String> {
+    let text = literal.to_string();
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    (!inner.contains('\\')).then(|| inner.to_string())
+}
+
+/// `#[doc = "..."]`
+fn doc_attribute(doc: &str) -> TokenStream {
+    let body: TokenStream = [
+        TokenTree::Ident(Ident::new("doc", Span::call_site())),
+        TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+        TokenTree::Literal(Literal::string(doc)),
+    ]
+    .into_iter()
+    .collect();
+    [
+        TokenTree::Punct(Punct::new('#', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Bracket, body)),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// `compile_error!("...")` at `span`
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    args.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut semi = Punct::new(';', Spacing::Alone);
+    semi.set_span(span);
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(args),
+        TokenTree::Punct(semi),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(attr: &str) -> Result<Vec<String>, String> {
+        rule_ids(attr.parse().unwrap()).map_err(|(message, _)| message)
+    }
+
+    // r[verify ref.attributes]
+    #[test]
+    fn rule_ids_are_comma_separated_strings() {
+        assert_eq!(ids(r#""auth.login""#).unwrap(), ["auth.login"]);
+        assert_eq!(
+            ids(r#""auth.login+2", "auth.logout","#).unwrap(),
+            ["auth.login+2", "auth.logout"]
+        );
+
+        for (attr, expected) in [
+            ("", "expected at least one rule ID"),
+            (r#""bad..id""#, "`bad..id` is not a valid rule ID"),
+            (r#""auth.*""#, "`auth.*` is not a valid rule ID"),
+            (r#""auth.login+0""#, "`auth.login+0` is not a valid rule ID"),
+            (r#""a\x2eb""#, "expected a rule ID string"),
+            ("42", "expected a rule ID string"),
+            (
+                r#""a.b" "c.d""#,
+                "expected rule ID strings separated by commas",
+            ),
+            ("auth.login", "expected rule ID strings separated by commas"),
+            (r#", "a.b""#, "expected rule ID strings separated by commas"),
+        ] {
+            assert_eq!(ids(attr).unwrap_err(), expected, "{attr}");
+        }
+    }
+
+    #[test]
+    fn rule_ids_follow_tracey_core() {
+        for id in [
+            "auth.login",
+            "A_b.c-d.9",
+            "single+3",
+            "bad..id",
+            "auth.",
+            "a b",
+            "a+1+2",
+        ] {
+            let literal = format!("{id:?}");
+            assert_eq!(
+                ids(&literal).is_ok(),
+                tracey_core::is_valid_rule_id(id),
+                "{id}"
+            );
+        }
+    }
+
+    #[test]
+    fn annotate_documents_the_item() {
+        let out = annotate(
+            "Verifies",
+            r#""auth.login", "auth.logout""#.parse().unwrap(),
+            "fn f() {}".parse().unwrap(),
+        )
+        .to_string();
+        assert_eq!(
+            out,
+            r#"# [doc = "\n\nVerifies `auth.login`, `auth.logout`."] fn f () { }"#
+        );
+
+        let out = annotate(
+            "Implements",
+            r#""bad..id""#.parse().unwrap(),
+            "fn f() {}".parse().unwrap(),
+        )
+        .to_string();
+        assert_eq!(
+            out,
+            r#"compile_error ! ("`bad..id` is not a valid rule ID") ; fn f () { }"#
+        );
+    }
+}
@@ -0,0 +1,7 @@
+// r[verify ref.attributes]
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass.rs");
+    t.compile_fail("tests/ui/fail-*.rs");
+}
@@ -0,0 +1,6 @@
+#[tracey_macros::impls("channel..allocation")]
+fn allocate_id() {}
+
+fn main() {
+    allocate_id();
+}
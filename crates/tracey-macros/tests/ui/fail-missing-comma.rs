@@ -0,0 +1,6 @@
+#[tracey_macros::verifies("channel.id.allocation" "channel.id.parity")]
+fn ids_alternate_parity() {}
+
+fn main() {
+    ids_alternate_parity();
+}
@@ -0,0 +1,14 @@
+#[tracey_macros::impls("channel.id.allocation")]
+fn allocate_id() -> u32 {
+    2
+}
+
+#[tracey_macros::verifies("channel.id.allocation", "channel.id.parity+2",)]
+#[test]
+fn ids_alternate_parity() {
+    assert_eq!(allocate_id() % 2, 0);
+}
+
+fn main() {
+    allocate_id();
+}
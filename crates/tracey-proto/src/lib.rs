@@ -14,7 +14,7 @@ pub use tracey_api::*;
 /// Protocol version — bump this whenever any RPC method is added, removed, or changed.
 /// The daemon writes this into its PID file; connectors compare it before connecting
 /// to detect stale daemons running an incompatible build.
-pub const PROTOCOL_VERSION: u32 = 4;
+pub const PROTOCOL_VERSION: u32 = 18;
 
 // ============================================================================
 // Request/Response types for the TraceyDaemon service
@@ -45,6 +45,10 @@ pub struct UncoveredResponse {
     pub uncovered_count: usize,
     /// Rules grouped by section
     pub by_section: Vec<SectionRules>,
+    /// Uncovered rules with an active waiver, not counted in `uncovered_count`;
+    /// each `text` is the waiver's reason
+    #[facet(default)]
+    pub waived: Vec<RuleRef>,
 }
 
 /// Rules within a section
@@ -59,6 +63,7 @@ pub struct SectionRules {
 #[derive(Debug, Clone, Facet)]
 pub struct RuleRef {
     pub id: RuleId,
+    /// Short annotation, such as a fan-out shortfall (`3 of 17 files annotated`)
     #[facet(default)]
     pub text: Option<String>,
 }
@@ -84,6 +89,10 @@ pub struct UntestedResponse {
     pub total_rules: usize,
     pub untested_count: usize,
     pub by_section: Vec<SectionRules>,
+    /// Untested rules with an active waiver, not counted in `untested_count`;
+    /// each `text` is the waiver's reason
+    #[facet(default)]
+    pub waived: Vec<RuleRef>,
 }
 
 /// Request for stale references query
@@ -190,12 +199,27 @@ pub struct ImplStatus {
     pub spec: String,
     pub impl_name: String,
     pub total_rules: usize,
-    /// Rules with at least one exact implementation reference (not stale).
+    /// Rules with at least one exact implementation reference (not stale),
+    /// not counting partially implemented ones.
     pub covered_rules: usize,
+    /// Rules implemented only by references marked `coverage=partial`.
+    /// Not included in covered_rules.
+    #[facet(default)]
+    pub partial_rules: usize,
     /// Rules where any reference is stale (points to an older rule version).
     /// Not included in covered_rules. covered_rules + stale_rules + uncovered = total.
     pub stale_rules: usize,
     pub verified_rules: usize,
+    /// The spec is advisory: its coverage never counts toward thresholds.
+    pub advisory: bool,
+    /// Rules with a reference whose verb has the `docs` role, or `None`
+    /// when no verb is configured with that role.
+    #[facet(default)]
+    pub documented_rules: Option<usize>,
+    /// Rules with a proof a verification tool checked, or `None` when the
+    /// implementation has no `proof_include` patterns.
+    #[facet(default)]
+    pub proven_rules: Option<usize>,
 }
 
 /// Information about a specific rule
@@ -210,12 +234,21 @@ pub struct RuleInfo {
     pub source_file: Option<String>,
     #[facet(default)]
     pub source_line: Option<usize>,
+    /// Titles of the headings enclosing the rule, outermost first
+    #[facet(default)]
+    pub section_path: Vec<String>,
+    /// Team or person owning the rule
+    #[facet(default)]
+    pub owner: Option<String>,
     /// Coverage across all implementations
     pub coverage: Vec<RuleCoverage>,
     /// Diff from the previous rule version (N-1 → N), if version > 1 and git history is available.
     /// Inline markdown format: ~~removed~~ / **added**.
     #[facet(default)]
     pub version_diff: Option<String>,
+    /// Rules mentioned in this rule's prose (implicit `related` edges)
+    #[facet(default)]
+    pub related: Vec<RuleId>,
 }
 
 /// Coverage of a rule in a specific implementation
@@ -228,6 +261,200 @@ pub struct RuleCoverage {
     pub verify_refs: Vec<ApiCodeRef>,
 }
 
+/// Request for a clipboard-ready annotation comment
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct AnnotationSnippetRequest {
+    /// Rule to annotate (the version suffix is optional; the current version is used)
+    pub rule_id: RuleId,
+    /// Target language: a file extension ("rs", "py") or language name ("rust", "python")
+    pub language: String,
+    /// Reference verb (default: "impl")
+    #[facet(default)]
+    pub verb: Option<String>,
+}
+
+/// A formatted annotation comment ready to paste into source code
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct AnnotationSnippet {
+    /// Current rule ID (including version suffix when versioned)
+    pub rule_id: RuleId,
+    /// Spec the rule belongs to
+    pub spec: String,
+    /// The comment text, e.g. `// r[impl auth.login]`
+    pub snippet: String,
+}
+
+/// Request to compare implementations of the same spec
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct CompareRequest {
+    /// Spec name (optional if only one spec configured)
+    #[facet(default)]
+    pub spec: Option<String>,
+    /// Implementations to compare, two or more
+    pub impls: Vec<String>,
+}
+
+/// Coverage of one rule by one implementation
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct CompareCoverage {
+    pub impl_count: usize,
+    pub verify_count: usize,
+    pub is_stale: bool,
+}
+
+/// Per-rule coverage across the compared implementations
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct CompareRule {
+    pub id: RuleId,
+    /// Coverage in each implementation, in the order of `impls`
+    pub coverage: Vec<CompareCoverage>,
+}
+
+/// Rules one implementation is missing
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct CompareGap {
+    pub impl_name: String,
+    /// Rules another compared implementation covers but this one doesn't
+    pub missing: Vec<RuleId>,
+}
+
+/// Coverage matrix of several implementations
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct CompareResponse {
+    pub spec: String,
+    /// The compared implementations, in request order
+    pub impls: Vec<String>,
+    /// Every rule in the spec, in spec order
+    pub rules: Vec<CompareRule>,
+    /// One entry per implementation, in the order of `impls`
+    pub gaps: Vec<CompareGap>,
+}
+
+/// Request for every annotation under a path
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RefsInRequest {
+    /// File or directory, relative to the project root (empty or `.` for everything)
+    pub path: String,
+}
+
+/// References to one rule with one verb
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RefsInVerb {
+    /// Verb as written (`impl`, `verify`, ...)
+    pub verb: String,
+    pub locations: Vec<ApiCodeRef>,
+}
+
+/// All references to one rule under the requested path
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RefsInRule {
+    /// Marker prefix (e.g. `r`)
+    pub prefix: String,
+    pub id: RuleId,
+    /// Verbs in canonical order (define, impl, verify, depends, related)
+    pub verbs: Vec<RefsInVerb>,
+}
+
+/// Response for refs_in query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RefsInResponse {
+    /// The normalized path that was searched
+    pub path: String,
+    /// Number of files under the path that contain annotations
+    pub files: usize,
+    /// Total number of annotations
+    pub total: usize,
+    /// Rules referenced under the path, sorted by prefix and ID
+    pub rules: Vec<RefsInRule>,
+}
+
+/// Request for coverage rolled up by directory or crate
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BreakdownRequest {
+    /// Spec name (optional if only one spec configured)
+    #[facet(default)]
+    pub spec: Option<String>,
+    /// Implementation name (optional if only one impl for the spec)
+    #[facet(default)]
+    pub impl_name: Option<String>,
+    /// `dir` (default) or `crate`
+    #[facet(default)]
+    pub by: Option<String>,
+    /// With `dir`, keep only the first N components of each directory
+    #[facet(default)]
+    pub depth: Option<usize>,
+}
+
+/// Forward coverage of the files in one directory or crate
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BreakdownGroup {
+    /// Directory relative to the project root (`.` for the root itself)
+    pub path: String,
+    /// Number of source files in the group
+    pub files: usize,
+    pub total_units: usize,
+    pub covered_units: usize,
+    /// Number of impl references in the group
+    pub impl_count: usize,
+    /// Number of verify references in the group
+    pub verify_count: usize,
+    /// Rules with at least one impl reference in the group, sorted by ID
+    pub implemented: Vec<RuleId>,
+    /// Rules with at least one verify reference in the group, sorted by ID
+    pub verified: Vec<RuleId>,
+}
+
+/// Response for breakdown query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BreakdownResponse {
+    pub spec: String,
+    pub impl_name: String,
+    /// `dir` or `crate`
+    pub by: String,
+    /// Every group containing source files, sorted by path
+    pub groups: Vec<BreakdownGroup>,
+}
+
+/// A file the client holds open, identified by the hash of its content
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct VfsSyncEntry {
+    /// Absolute path
+    pub path: String,
+    /// Blake3 hex digest of the content
+    pub hash: String,
+}
+
+/// Request for vfs_sync
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct VfsSyncRequest {
+    pub entries: Vec<VfsSyncEntry>,
+}
+
+/// Response from vfs_sync
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct VfsSyncResponse {
+    /// Paths whose overlay is missing or out of date; the client should
+    /// send their content with `vfs_open`
+    pub needed: Vec<String>,
+}
+
 /// Response from reload command
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -236,6 +463,40 @@ pub struct ReloadResponse {
     pub rebuild_time_ms: u64,
 }
 
+/// Request for a rebuild limited to some implementations
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ReloadScopeRequest {
+    /// Only rebuild implementations of this spec
+    #[facet(default)]
+    pub spec: Option<String>,
+    /// Only rebuild implementations with this name
+    #[facet(default)]
+    pub impl_name: Option<String>,
+    /// Files that changed, relative to the project root or absolute. When
+    /// empty, the implementations in scope are rescanned in full.
+    #[facet(default)]
+    pub paths: Vec<String>,
+}
+
+/// Response from a scoped reload
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ReloadScopeResponse {
+    pub version: u64,
+    pub rebuild_time_ms: u64,
+    /// Implementations whose coverage data changed
+    pub changed: Vec<ReloadedImpl>,
+}
+
+/// A spec/implementation pair touched by a reload
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ReloadedImpl {
+    pub spec: String,
+    pub impl_name: String,
+}
+
 /// Request for file content
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -263,6 +524,9 @@ pub struct SearchResult {
     #[facet(default)]
     pub highlighted: Option<String>,
     pub score: f32,
+    /// For rules: the heading chain it sits under (`Transport > Flow Control`)
+    #[facet(default)]
+    pub section: Option<String>,
 }
 
 /// Request to update a file range (for inline editing)
@@ -274,6 +538,30 @@ pub struct UpdateFileRangeRequest {
     pub end: usize,
     pub content: String,
     pub file_hash: String,
+    /// Who is making the change (e.g. `dashboard`), recorded in the audit log
+    #[facet(default)]
+    pub client: Option<String>,
+}
+
+/// One mutation made through the daemon, as recorded in `.tracey/audit.jsonl`
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Client identity as declared by the caller, or `unknown`
+    pub client: String,
+    /// RPC that made the change, e.g. `update_file_range`
+    pub action: String,
+    /// Changed file, relative to the project root when inside it
+    pub path: String,
+    /// Blake3 hex digest of the file before the change
+    pub before_hash: String,
+    /// Blake3 hex digest of the file after the change
+    pub after_hash: String,
+    /// What changed, e.g. `bytes 10..42` or `rust/main: include src/**/*.rs`
+    #[facet(default)]
+    pub detail: Option<String>,
 }
 
 /// Error from file update
@@ -335,6 +623,35 @@ pub struct HealthResponse {
 
     /// Daemon uptime in seconds
     pub uptime_secs: u64,
+
+    /// Open connections and request queue depths
+    #[facet(default)]
+    pub load: RequestLoad,
+}
+
+/// Connection count and request queue depths, split by scheduling class.
+#[derive(Debug, Clone, Default, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RequestLoad {
+    /// Currently open client connections
+    pub connections: u64,
+
+    /// Editor (LSP) requests
+    pub interactive: QueueDepth,
+
+    /// MCP, HTTP and CLI requests
+    pub batch: QueueDepth,
+}
+
+/// Requests running and waiting in one scheduling class.
+#[derive(Debug, Clone, Default, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct QueueDepth {
+    /// Requests currently being handled
+    pub in_flight: u64,
+
+    /// Requests waiting for a free slot
+    pub queued: u64,
 }
 
 /// Summary of what changed in a rebuild
@@ -384,6 +701,9 @@ pub struct HoverRef {
     pub file: String,
     /// Line number (1-indexed)
     pub line: usize,
+    /// Note trailing the rule ID in the reference
+    #[facet(default)]
+    pub note: Option<String>,
 }
 
 /// Hover information for a requirement reference
@@ -421,6 +741,9 @@ pub struct HoverInfo {
     /// Present for tail annotations (current version, version > 1) and stale annotations.
     #[facet(default)]
     pub version_diff: Option<String>,
+    /// Examples and counter-examples of the rule
+    #[facet(default)]
+    pub examples: Vec<ApiRuleExample>,
 }
 
 /// A completion item
@@ -496,10 +819,24 @@ pub struct LspSemanticToken {
     pub length: u32,
     /// Token type index
     pub token_type: u32,
-    /// Token modifiers bitmask
+    /// Token modifiers bitmask (see [`semantic_modifiers`])
     pub modifiers: u32,
 }
 
+/// Bits of [`LspSemanticToken::modifiers`], in the order of the LSP legend.
+pub mod semantic_modifiers {
+    /// A requirement definition.
+    pub const DEFINITION: u32 = 1 << 0;
+    /// A reference to a current rule.
+    pub const DECLARATION: u32 = 1 << 1;
+    /// A definition of, or reference to, a deprecated or removed rule.
+    pub const DEPRECATED: u32 = 1 << 2;
+    /// A reference to an older version of a rule.
+    pub const STALE: u32 = 1 << 3;
+    /// A reference to a rule that does not exist in its spec.
+    pub const UNKNOWN: u32 = 1 << 4;
+}
+
 /// A code lens
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -517,6 +854,20 @@ pub struct LspCodeLens {
     pub arguments: Vec<String>,
 }
 
+/// A foldable range of lines
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct LspFoldingRange {
+    /// First line of the range (0-indexed, stays visible when folded)
+    pub start_line: u32,
+    /// Last line of the range (0-indexed)
+    pub end_line: u32,
+    /// Text to show in place of the folded lines; `None` leaves it to the
+    /// editor
+    #[facet(default)]
+    pub collapsed_text: Option<String>,
+}
+
 /// An inlay hint
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -590,6 +941,9 @@ pub struct ConfigPatternRequest {
     #[facet(default)]
     pub impl_name: Option<String>,
     pub pattern: String,
+    /// Who is making the change (e.g. `mcp`), recorded in the audit log
+    #[facet(default)]
+    pub client: Option<String>,
 }
 
 /// Request for LSP operations that need path, content, and position
@@ -662,6 +1016,21 @@ pub trait TraceyDaemon {
     /// Get details for a specific rule by ID
     async fn rule(&self, rule_id: RuleId) -> Option<RuleInfo>;
 
+    /// Format an annotation comment for a rule in the given language
+    async fn annotation_snippet(
+        &self,
+        req: AnnotationSnippetRequest,
+    ) -> Result<AnnotationSnippet, String>;
+
+    /// Compare rule coverage of two implementations of the same spec
+    async fn compare(&self, req: CompareRequest) -> Result<CompareResponse, String>;
+
+    /// List every annotation under a file or directory, grouped by rule and verb
+    async fn refs_in(&self, req: RefsInRequest) -> RefsInResponse;
+
+    /// Roll up forward coverage by the directory or crate containing each file
+    async fn breakdown(&self, req: BreakdownRequest) -> Result<BreakdownResponse, String>;
+
     // === Configuration ===
 
     /// Get current configuration
@@ -678,11 +1047,19 @@ pub trait TraceyDaemon {
     /// Notify that a file was closed (remove from overlay)
     async fn vfs_close(&self, path: String);
 
+    /// Report the files the client holds open by content hash; returns the
+    /// ones whose content the daemon needs
+    async fn vfs_sync(&self, req: VfsSyncRequest) -> VfsSyncResponse;
+
     // === Control ===
 
     /// Force a rebuild of the dashboard data
     async fn reload(&self) -> ReloadResponse;
 
+    /// Rebuild only the implementations of one spec, one implementation, or
+    /// both; the rest keep their data from the previous build
+    async fn reload_scope(&self, req: ReloadScopeRequest) -> Result<ReloadScopeResponse, String>;
+
     /// Get current data version
     async fn version(&self) -> u64;
 
@@ -718,6 +1095,9 @@ pub trait TraceyDaemon {
     /// Update a byte range in a file (for inline editing)
     async fn update_file_range(&self, req: UpdateFileRangeRequest) -> Result<(), UpdateError>;
 
+    /// The most recent `limit` entries of the audit log, newest first
+    async fn audit(&self, limit: u32) -> Result<Vec<AuditEntry>, String>;
+
     // === LSP Support ===
 
     /// Check if a path is a test file (for LSP diagnostics)
@@ -758,6 +1138,9 @@ pub trait TraceyDaemon {
     /// Get code lens items
     async fn lsp_code_lens(&self, req: LspDocumentRequest) -> Vec<LspCodeLens>;
 
+    /// Get folding ranges for rule bodies and annotated code units
+    async fn lsp_folding_ranges(&self, req: LspDocumentRequest) -> Vec<LspFoldingRange>;
+
     /// Get inlay hints for a range
     async fn lsp_inlay_hints(&self, req: InlayHintsRequest) -> Vec<LspInlayHint>;
 
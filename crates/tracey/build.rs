@@ -131,6 +131,7 @@ fn generate_typescript_types() {
     generator.add_type::<ApiForwardData>();
     generator.add_type::<ApiSpecForward>();
     generator.add_type::<ApiRule>();
+    generator.add_type::<ApiRuleExample>();
     generator.add_type::<ApiCodeRef>();
     generator.add_type::<ApiReverseData>();
     generator.add_type::<ApiFileEntry>();
@@ -0,0 +1,393 @@
+//! `tracey report --about` implementation.
+//!
+//! Summarizes the tracey setup for support tickets: versions, enabled
+//! features, a digest of the config, spec and impl counts, extraction timing,
+//! and daemon state. Everything is gathered locally; nothing is sent
+//! anywhere. The project root and home directory are redacted from the
+//! output so it can be pasted into an issue as-is.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// Summary of one configured spec.
+#[derive(Debug)]
+pub struct SpecSummary {
+    pub name: String,
+    pub include_patterns: usize,
+    pub impls: Vec<String>,
+    pub fanouts: usize,
+}
+
+/// Counts for one spec/impl pair after extraction.
+#[derive(Debug)]
+pub struct ImplSummary {
+    pub spec: String,
+    pub impl_name: String,
+    pub rules: usize,
+    pub implemented: usize,
+    pub verified: usize,
+    pub files: usize,
+    pub code_units: usize,
+}
+
+/// Result of running extraction once, offline.
+#[derive(Debug)]
+pub enum Extraction {
+    Done {
+        elapsed: Duration,
+        impls: Vec<ImplSummary>,
+    },
+    Failed(String),
+    /// Skipped because the config could not be loaded.
+    Skipped,
+}
+
+/// What the daemon for this project is doing.
+#[derive(Debug)]
+pub enum DaemonState {
+    NotRunning,
+    Running {
+        pid: Option<u32>,
+        protocol: Option<u32>,
+        uptime_secs: u64,
+        data_version: u64,
+        watcher_active: bool,
+        watched_directories: usize,
+        config_error: bool,
+    },
+    /// A socket accepted the connection but the health check failed.
+    Unresponsive {
+        pid: Option<u32>,
+    },
+}
+
+/// Everything `tracey report --about` prints.
+#[derive(Debug)]
+pub struct AboutReport {
+    pub version: &'static str,
+    pub protocol_version: u32,
+    pub platform: String,
+    pub features: Vec<&'static str>,
+    /// Short Blake3 digest of the config file, if it exists.
+    pub config_digest: Option<String>,
+    pub config_error: Option<String>,
+    pub specs: Vec<SpecSummary>,
+    pub extraction: Extraction,
+    pub daemon: DaemonState,
+}
+
+impl AboutReport {
+    /// Render as a fenced block ready to paste into an issue, with
+    /// `project_root` and the home directory redacted.
+    pub fn to_text(&self, project_root: &Path) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "```text");
+        let _ = writeln!(
+            out,
+            "tracey {} (protocol {})",
+            self.version, self.protocol_version
+        );
+        let _ = writeln!(out, "platform: {}", self.platform);
+        let features = if self.features.is_empty() {
+            "none".to_string()
+        } else {
+            self.features.join(", ")
+        };
+        let _ = writeln!(out, "features: {features}");
+
+        match (&self.config_digest, &self.config_error) {
+            (None, _) => {
+                let _ = writeln!(out, "config: not found");
+            }
+            (Some(digest), None) => {
+                let _ = writeln!(out, "config: ok (digest {digest})");
+            }
+            (Some(digest), Some(error)) => {
+                let _ = writeln!(out, "config: invalid (digest {digest})");
+                for line in error.lines() {
+                    let _ = writeln!(out, "  {line}");
+                }
+            }
+        }
+        for spec in &self.specs {
+            let _ = writeln!(
+                out,
+                "  spec {}: {} include pattern(s), impls [{}]{}",
+                spec.name,
+                spec.include_patterns,
+                spec.impls.join(", "),
+                if spec.fanouts > 0 {
+                    format!(", {} fan-out rule(s)", spec.fanouts)
+                } else {
+                    String::new()
+                }
+            );
+        }
+
+        match &self.extraction {
+            Extraction::Done { elapsed, impls } => {
+                let _ = writeln!(out, "extraction: {} ms", elapsed.as_millis());
+                for i in impls {
+                    let _ = writeln!(
+                        out,
+                        "  {}/{}: {} rules, {} implemented, {} verified, {} files, {} code units",
+                        i.spec,
+                        i.impl_name,
+                        i.rules,
+                        i.implemented,
+                        i.verified,
+                        i.files,
+                        i.code_units
+                    );
+                }
+            }
+            Extraction::Failed(error) => {
+                let _ = writeln!(out, "extraction: failed");
+                for line in error.lines() {
+                    let _ = writeln!(out, "  {line}");
+                }
+            }
+            Extraction::Skipped => {
+                let _ = writeln!(out, "extraction: skipped (no usable config)");
+            }
+        }
+
+        match &self.daemon {
+            DaemonState::NotRunning => {
+                let _ = writeln!(out, "daemon: not running");
+            }
+            DaemonState::Running {
+                pid,
+                protocol,
+                uptime_secs,
+                data_version,
+                watcher_active,
+                watched_directories,
+                config_error,
+            } => {
+                let _ = writeln!(
+                    out,
+                    "daemon: running (pid {}, protocol {}, up {uptime_secs}s, data version {data_version})",
+                    pid.map_or("?".to_string(), |p| p.to_string()),
+                    protocol.map_or("?".to_string(), |p| p.to_string()),
+                );
+                let _ = writeln!(
+                    out,
+                    "  watcher {}, {watched_directories} watched dir(s){}",
+                    if *watcher_active {
+                        "active"
+                    } else {
+                        "inactive"
+                    },
+                    if *config_error {
+                        ", config error reported"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            DaemonState::Unresponsive { pid } => {
+                let _ = writeln!(
+                    out,
+                    "daemon: not responding (pid {})",
+                    pid.map_or("?".to_string(), |p| p.to_string())
+                );
+            }
+        }
+        let _ = writeln!(out, "```");
+        redact(&out, project_root, dirs::home_dir().as_deref())
+    }
+}
+
+/// Replace the project root with `<root>` and the home directory with `~`.
+fn redact(text: &str, project_root: &Path, home: Option<&Path>) -> String {
+    let mut out = text.to_string();
+    let mut replacements = vec![(project_root.display().to_string(), "<root>")];
+    if let Ok(canonical) = project_root.canonicalize() {
+        replacements.push((canonical.display().to_string(), "<root>"));
+    }
+    if let Some(home) = home {
+        replacements.push((home.display().to_string(), "~"));
+    }
+    // Longest first, so the root wins over a home directory that contains it.
+    replacements.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+    for (from, to) in replacements {
+        if from.len() > 1 {
+            out = out.replace(&from, to);
+        }
+    }
+    out
+}
+
+fn digest(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex()[..16].to_string()
+}
+
+/// Gather the report for the project at `project_root`.
+// r[impl cli.report.about]
+pub async fn gather(project_root: &Path, config_path: &Path) -> AboutReport {
+    let config_digest = std::fs::read(config_path).ok().map(|bytes| digest(&bytes));
+    let config = if config_digest.is_some() {
+        crate::load_config(&config_path.to_path_buf())
+    } else {
+        Err(eyre::eyre!("config file not found"))
+    };
+
+    let (specs, extraction, config_error) = match &config {
+        Ok(config) => (
+            summarize_specs(config),
+            extract(project_root, config).await,
+            None,
+        ),
+        Err(err) => (
+            Vec::new(),
+            Extraction::Skipped,
+            config_digest.as_ref().map(|_| format!("{err:#}")),
+        ),
+    };
+
+    let mut features = Vec::new();
+    if cfg!(feature = "search") {
+        features.push("search");
+    }
+
+    AboutReport {
+        version: env!("CARGO_PKG_VERSION"),
+        protocol_version: tracey_proto::PROTOCOL_VERSION,
+        platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        features,
+        config_digest,
+        config_error,
+        specs,
+        extraction,
+        daemon: probe_daemon(project_root).await,
+    }
+}
+
+fn summarize_specs(config: &Config) -> Vec<SpecSummary> {
+    config
+        .specs
+        .iter()
+        .map(|spec| SpecSummary {
+            name: spec.name.clone(),
+            include_patterns: spec.include.len(),
+            impls: spec.impls.iter().map(|i| i.name.clone()).collect(),
+            fanouts: spec.fanout.len(),
+        })
+        .collect()
+}
+
+async fn extract(project_root: &Path, config: &Config) -> Extraction {
+    let start = Instant::now();
+    let data = match crate::data::build_dashboard_data(project_root, config, 0, true).await {
+        Ok(data) => data,
+        Err(err) => return Extraction::Failed(format!("{err:#}")),
+    };
+    let elapsed = start.elapsed();
+
+    let impls = data
+        .forward_by_impl
+        .iter()
+        .map(|((spec, impl_name), forward)| {
+            let reverse = data.reverse_by_impl.get(&(spec.clone(), impl_name.clone()));
+            ImplSummary {
+                spec: spec.clone(),
+                impl_name: impl_name.clone(),
+                rules: forward.rules.len(),
+                implemented: forward
+                    .rules
+                    .iter()
+                    .filter(|r| !r.is_stale && r.is_implemented())
+                    .count(),
+                verified: forward
+                    .rules
+                    .iter()
+                    .filter(|r| !r.verify_refs.is_empty())
+                    .count(),
+                files: reverse.map_or(0, |r| r.files.len()),
+                code_units: reverse.map_or(0, |r| r.total_units),
+            }
+        })
+        .collect();
+    Extraction::Done { elapsed, impls }
+}
+
+/// Ask the project's daemon for its health, without starting one.
+async fn probe_daemon(project_root: &Path) -> DaemonState {
+    let pid_info = crate::daemon::read_pid_file_at(&crate::daemon::pid_file_path(project_root));
+    let pid = pid_info.map(|(pid, _)| pid);
+    let protocol = pid_info.map(|(_, protocol)| protocol);
+
+    let endpoint = crate::daemon::local_endpoint(project_root);
+    let Ok(stream) = roam_stream::LocalLink::connect(&endpoint).await else {
+        return DaemonState::NotRunning;
+    };
+    let Ok((client, _session_handle)) = roam::initiator(stream)
+        .establish::<tracey_proto::TraceyDaemonClient>(())
+        .await
+    else {
+        return DaemonState::Unresponsive { pid };
+    };
+    match tokio::time::timeout(Duration::from_secs(1), client.health()).await {
+        Ok(Ok(health)) => DaemonState::Running {
+            pid,
+            protocol,
+            uptime_secs: health.uptime_secs,
+            data_version: health.version,
+            watcher_active: health.watcher_active,
+            watched_directories: health.watched_directories.len(),
+            config_error: health.config_error.is_some(),
+        },
+        _ => DaemonState::Unresponsive { pid },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_root_before_home() {
+        let text = "/home/me/work/app/spec.md and /home/me/.cache";
+        let out = redact(
+            text,
+            Path::new("/home/me/work/app"),
+            Some(Path::new("/home/me")),
+        );
+        assert_eq!(out, "<root>/spec.md and ~/.cache");
+    }
+
+    // r[verify cli.report.about]
+    #[tokio::test]
+    async fn report_is_fenced_and_path_free() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("spec.md"), "# Spec\n\nr[a.b]\nA MUST B.\n").unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "// r[impl a.b]\nfn f() {}\n").unwrap();
+        let config_path = root.join("config.styx");
+        std::fs::write(
+            &config_path,
+            "specs (\n  {\n    name app\n    include (spec.md)\n    impls (\n      {\n        name rust\n        include (src/**/*.rs)\n      }\n    )\n  }\n)\n",
+        )
+        .unwrap();
+
+        let report = gather(root, &config_path).await;
+        assert!(report.config_error.is_none(), "{:?}", report.config_error);
+        let Extraction::Done { impls, .. } = &report.extraction else {
+            panic!("extraction should succeed: {:?}", report.extraction);
+        };
+        assert_eq!(impls[0].rules, 1);
+        assert_eq!(impls[0].implemented, 1);
+
+        let text = report.to_text(root);
+        assert!(text.starts_with("```text\ntracey "), "{text}");
+        assert!(text.trim_end().ends_with("```"), "{text}");
+        assert!(text.contains("  spec app: 1 include pattern(s), impls [rust]"));
+        assert!(text.contains("  app/rust: 1 rules, 1 implemented, 0 verified"));
+        assert!(!text.contains(&root.display().to_string()), "{text}");
+    }
+}
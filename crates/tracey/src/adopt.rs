@@ -0,0 +1,871 @@
+//! `tracey adopt` implementation.
+//!
+//! Helps bring tracey to a codebase that has no annotations yet. Every rule
+//! without references is matched against the code units of the
+//! implementation: the words of the rule ID and text are looked up in symbol
+//! names, doc comments, and file paths, and the best matches are listed per
+//! rule with a confidence. With `--annotate`, the best match of each rule gets
+//! a `related` reference, which marks the link without counting as coverage,
+//! so reviewers can confirm it and turn it into `impl`.
+//!
+//! `tracey annotate` runs the same matching the other way around: for every
+//! code unit without a reference, it lists the rules that best describe it.
+
+use eyre::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use tracey_core::code_units::CodeUnit;
+use tracey_core::{RefVerb, RuleId};
+
+use crate::config::{Config, annotation_snippet};
+use crate::patch::PatchSet;
+
+/// Words too common in rule text to say anything about where it is implemented.
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "again", "against", "allow", "allowed", "also", "always", "another",
+    "before", "being", "between", "cannot", "could", "current", "during", "each", "either",
+    "every", "given", "have", "into", "least", "more", "most", "must", "never", "only", "other",
+    "should", "shall", "since", "such", "than", "that", "their", "them", "then", "there", "these",
+    "they", "this", "those", "through", "under", "until", "used", "using", "when", "where",
+    "whether", "which", "while", "will", "with", "within", "without", "would",
+];
+
+/// Prose words considered per rule.
+const MAX_PROSE_TERMS: usize = 8;
+
+/// Candidates scoring below this are not listed.
+const MIN_SCORE: f64 = 0.15;
+
+/// How likely a candidate is to implement the rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    fn of(score: f64) -> Self {
+        if score >= 0.6 {
+            Confidence::High
+        } else if score >= 0.35 {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Confidence::High => "high",
+            Confidence::Medium => "medium",
+            Confidence::Low => "low",
+        }
+    }
+}
+
+/// A code unit that may implement a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// File relative to the project root.
+    pub file: PathBuf,
+    /// Line the unit starts on, including its doc comment (1-based).
+    pub line: usize,
+    /// e.g. "function `login`"
+    pub symbol: String,
+    /// Between 0 and 1.
+    pub score: f64,
+    /// Rule words found in the unit.
+    pub matched: Vec<String>,
+}
+
+impl Candidate {
+    pub fn confidence(&self) -> Confidence {
+        Confidence::of(self.score)
+    }
+}
+
+/// Candidates for one rule, best first.
+#[derive(Debug, Clone)]
+pub struct RulePlan {
+    pub id: RuleId,
+    pub candidates: Vec<Candidate>,
+}
+
+/// Adoption plan for one implementation.
+#[derive(Debug, Default)]
+pub struct ImplPlan {
+    pub spec: String,
+    pub impl_name: String,
+    /// Rules that already have references and were left out.
+    pub referenced: usize,
+    /// Rules without references, best candidate first; rules without any
+    /// candidate come last.
+    pub rules: Vec<RulePlan>,
+    /// References added by `--annotate`, as (file, line, annotation).
+    pub annotated: Vec<(PathBuf, usize, String)>,
+    /// Files whose language has no known comment syntax, so `--annotate` left
+    /// them alone.
+    pub not_annotated: BTreeSet<PathBuf>,
+}
+
+/// A rule that may describe a code unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSuggestion {
+    pub id: RuleId,
+    /// Between 0 and 1.
+    pub score: f64,
+    /// Rule words found in the unit.
+    pub matched: Vec<String>,
+}
+
+impl RuleSuggestion {
+    pub fn confidence(&self) -> Confidence {
+        Confidence::of(self.score)
+    }
+}
+
+/// Rules suggested for one code unit without references, best first.
+#[derive(Debug, Clone)]
+pub struct UnitSuggestions {
+    /// File relative to the project root.
+    pub file: PathBuf,
+    /// Line the unit starts on, including its doc comment (1-based).
+    pub line: usize,
+    /// e.g. "function `login`"
+    pub symbol: String,
+    pub rules: Vec<RuleSuggestion>,
+}
+
+/// Suggestions for the unmapped code units of one implementation.
+#[derive(Debug, Default)]
+pub struct ImplSuggestions {
+    pub spec: String,
+    pub impl_name: String,
+    /// Code units that already have references and were left out.
+    pub mapped: usize,
+    /// Code units without references that no rule matched.
+    pub unmatched: usize,
+    /// Code units with at least one suggestion, best suggestion first.
+    pub units: Vec<UnitSuggestions>,
+}
+
+/// Rule suggestions for every requested implementation.
+#[derive(Debug, Default)]
+pub struct AnnotateReport {
+    pub impls: Vec<ImplSuggestions>,
+}
+
+impl AnnotateReport {
+    /// Render the suggestions as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Annotation suggestions");
+        for plan in &self.impls {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}/{}", plan.spec, plan.impl_name);
+            let _ = writeln!(out);
+            let _ = writeln!(
+                out,
+                "{} code units without references ({} with suggestions); {} already referenced.",
+                plan.units.len() + plan.unmatched,
+                plan.units.len(),
+                plan.mapped
+            );
+            if plan.units.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Code unit | Rule | Confidence | Matched |");
+            let _ = writeln!(out, "|-----------|------|-----------:|---------|");
+            for unit in &plan.units {
+                for (i, rule) in unit.rules.iter().enumerate() {
+                    let unit_cell = if i == 0 {
+                        format!("{} `{}:{}`", unit.symbol, unit.file.display(), unit.line)
+                    } else {
+                        String::new()
+                    };
+                    let _ = writeln!(
+                        out,
+                        "| {unit_cell} | `{}` | {} ({:.0}%) | {} |",
+                        rule.id,
+                        rule.confidence().label(),
+                        rule.score * 100.0,
+                        rule.matched.join(", ")
+                    );
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Adoption plans for every requested implementation.
+#[derive(Debug, Default)]
+pub struct AdoptReport {
+    pub impls: Vec<ImplPlan>,
+    /// The `--annotate` references, for the caller to apply or preview.
+    pub patch: PatchSet,
+}
+
+impl AdoptReport {
+    /// Render the plan as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Adoption plan");
+        for plan in &self.impls {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}/{}", plan.spec, plan.impl_name);
+            let _ = writeln!(out);
+            let matched = plan
+                .rules
+                .iter()
+                .filter(|r| !r.candidates.is_empty())
+                .count();
+            let _ = writeln!(
+                out,
+                "{} rules without references ({} with candidates); {} already referenced.",
+                plan.rules.len(),
+                matched,
+                plan.referenced
+            );
+            if matched > 0 {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "| Rule | Candidate | Confidence | Matched |");
+                let _ = writeln!(out, "|------|-----------|-----------:|---------|");
+            }
+            for rule in &plan.rules {
+                for (i, candidate) in rule.candidates.iter().enumerate() {
+                    let rule_cell = if i == 0 {
+                        format!("`{}`", rule.id)
+                    } else {
+                        String::new()
+                    };
+                    let _ = writeln!(
+                        out,
+                        "| {rule_cell} | {} `{}:{}` | {} ({:.0}%) | {} |",
+                        candidate.symbol,
+                        candidate.file.display(),
+                        candidate.line,
+                        candidate.confidence().label(),
+                        candidate.score * 100.0,
+                        candidate.matched.join(", ")
+                    );
+                }
+            }
+
+            let unmatched: Vec<String> = plan
+                .rules
+                .iter()
+                .filter(|r| r.candidates.is_empty())
+                .map(|r| format!("`{}`", r.id))
+                .collect();
+            if !unmatched.is_empty() {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "No candidates: {}", unmatched.join(", "));
+            }
+            if !plan.annotated.is_empty() {
+                let _ = writeln!(out);
+                let _ = writeln!(
+                    out,
+                    "Added {} `related` references; review them and change the verb to `impl` where they are right:",
+                    plan.annotated.len()
+                );
+                for (file, line, annotation) in &plan.annotated {
+                    let _ = writeln!(out, "- `{}:{line}` {annotation}", file.display());
+                }
+            }
+            for file in &plan.not_annotated {
+                let _ = writeln!(
+                    out,
+                    "- warning: `{}` was not annotated: unknown comment syntax",
+                    file.display()
+                );
+            }
+        }
+        out
+    }
+}
+
+/// `channel.reset-behavior` or `resetBehavior` -> `["channel", "reset", "behavior"]`
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Words of a rule ID worth matching.
+fn id_terms(id: &RuleId) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in split_words(&id.base) {
+        if word.len() >= 3 && !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+/// Distinctive words of a rule's text, most frequent first.
+fn prose_terms(raw: &str, id_terms: &[String]) -> Vec<String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    // Skip rule markers and inline code spans that name other rules.
+    for word in raw
+        .split(|c: char| c.is_whitespace())
+        .filter(|w| !w.contains('[') && !w.contains('`'))
+        .flat_map(split_words)
+    {
+        if word.len() >= 4
+            && word.chars().all(|c| c.is_ascii_alphabetic())
+            && !STOP_WORDS.contains(&word.as_str())
+            && !id_terms.contains(&word)
+        {
+            *counts.entry(word).or_default() += 1;
+        }
+    }
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms
+        .into_iter()
+        .take(MAX_PROSE_TERMS)
+        .map(|(w, _)| w)
+        .collect()
+}
+
+/// Whether `term` and `word` are the same word up to a suffix
+/// (`valid`/`validate`/`validation`).
+fn same_word(term: &str, word: &str) -> bool {
+    if term == word {
+        return true;
+    }
+    let common = term
+        .bytes()
+        .zip(word.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let shorter = term.len().min(word.len());
+    shorter >= 4 && (common == shorter || common >= 5)
+}
+
+fn contains_word(words: &BTreeSet<String>, term: &str) -> bool {
+    words.iter().any(|w| same_word(term, w))
+}
+
+/// What a code unit says about itself.
+#[derive(Debug, Default)]
+struct UnitText {
+    name: BTreeSet<String>,
+    doc: BTreeSet<String>,
+    path: BTreeSet<String>,
+}
+
+/// Comment lines at the top of a unit, which include its doc comment.
+fn leading_comment(source: &str, unit: &CodeUnit) -> String {
+    let mut doc = String::new();
+    for line in source
+        .lines()
+        .skip(unit.start_line.saturating_sub(1))
+        .take(unit.end_line + 1 - unit.start_line)
+    {
+        let trimmed = line.trim_start();
+        let is_comment = ["//", "#", "/*", "*", "--", ";", "%"]
+            .iter()
+            .any(|p| trimmed.starts_with(p));
+        if !is_comment {
+            break;
+        }
+        doc.push_str(trimmed);
+        doc.push('\n');
+    }
+    doc
+}
+
+fn unit_text(relative: &Path, source: &str, unit: &CodeUnit) -> UnitText {
+    let mut path_words: BTreeSet<String> = BTreeSet::new();
+    for component in relative.with_extension("").components() {
+        path_words.extend(split_words(&component.as_os_str().to_string_lossy()));
+    }
+    UnitText {
+        name: unit
+            .name
+            .as_deref()
+            .map(split_words)
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        doc: split_words(&leading_comment(source, unit))
+            .into_iter()
+            .collect(),
+        path: path_words,
+    }
+}
+
+/// Score how well a unit matches a rule, from 0 to 1, with the matched words.
+///
+/// Words of the rule ID weigh most: in the symbol name fully, in the doc
+/// comment somewhat less, and in the file path least. Words of the rule text
+/// only count when they appear in the name or doc comment.
+fn score(id_terms: &[String], prose_terms: &[String], text: &UnitText) -> (f64, Vec<String>) {
+    let mut matched = Vec::new();
+    let mut id_score = 0.0;
+    for term in id_terms {
+        let weight = if contains_word(&text.name, term) {
+            1.0
+        } else if contains_word(&text.doc, term) {
+            0.7
+        } else if contains_word(&text.path, term) {
+            0.5
+        } else {
+            continue;
+        };
+        id_score += weight;
+        matched.push(term.clone());
+    }
+    let mut prose_hits = 0;
+    for term in prose_terms {
+        if contains_word(&text.name, term) || contains_word(&text.doc, term) {
+            prose_hits += 1;
+            matched.push(term.clone());
+        }
+    }
+    let id_part = if id_terms.is_empty() {
+        0.0
+    } else {
+        id_score / id_terms.len() as f64
+    };
+    let prose_part = if prose_terms.is_empty() {
+        0.0
+    } else {
+        prose_hits as f64 / prose_terms.len() as f64
+    };
+    (0.8 * id_part + 0.2 * prose_part, matched)
+}
+
+/// e.g. "function `login`"
+fn describe(unit: &CodeUnit) -> String {
+    match &unit.name {
+        Some(name) => format!("{} `{name}`", unit.kind),
+        None => unit.kind.to_string(),
+    }
+}
+
+/// `file` relative to the project root, which it may be under either as
+/// given or canonicalized.
+pub(crate) fn relative_to(
+    project_root: &Path,
+    canonical_root: Option<&Path>,
+    file: &Path,
+) -> PathBuf {
+    file.strip_prefix(project_root)
+        .ok()
+        .or_else(|| file.strip_prefix(canonical_root?).ok())
+        .unwrap_or(file)
+        .to_path_buf()
+}
+
+/// Every code unit of an implementation, by absolute file, with what it says
+/// about itself.
+fn impl_units<'a>(
+    data: &'a crate::data::DashboardData,
+    key: &crate::data::ImplKey,
+    relative: impl Fn(&Path) -> PathBuf,
+) -> Vec<(&'a Path, &'a CodeUnit, UnitText)> {
+    let mut units = Vec::new();
+    if let Some(files) = data.code_units_by_impl.get(key) {
+        for (file, file_units) in files {
+            let source = match data.search_files.get(file) {
+                Some(source) => source.clone(),
+                None => std::fs::read_to_string(file).unwrap_or_default(),
+            };
+            let rel = relative(file);
+            for unit in file_units {
+                units.push((file.as_path(), unit, unit_text(&rel, &source, unit)));
+            }
+        }
+    }
+    units
+}
+
+/// Error for `--spec`/`--impl` filters that match no implementation.
+fn no_matching_impl(
+    data: &crate::data::DashboardData,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+) -> eyre::Report {
+    let available: Vec<String> = data
+        .forward_by_impl
+        .keys()
+        .map(|(s, i)| format!("{s}/{i}"))
+        .collect();
+    eyre::eyre!(
+        "No implementation matches{}{}. Available: {}",
+        spec.map(|s| format!(" --spec {s}")).unwrap_or_default(),
+        impl_name
+            .map(|i| format!(" --impl {i}"))
+            .unwrap_or_default(),
+        available.join(", ")
+    )
+}
+
+/// Insert `annotations` (1-based line, comment) above their lines, indented
+/// like the line they annotate.
+fn insert_annotations(source: &str, annotations: &[(usize, String)]) -> String {
+    let mut by_line: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    for (line, text) in annotations {
+        by_line.entry(*line).or_default().push(text);
+    }
+    let mut out = String::with_capacity(source.len());
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        if let Some(texts) = by_line.get(&(index + 1)) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            for text in texts {
+                let _ = writeln!(out, "{indent}{text}");
+            }
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Build adoption plans for the implementations matching `spec` and
+/// `impl_name` (all of them when `None`), listing up to `limit` candidates per
+/// rule. With `annotate`, plan a `related` reference above the best
+/// candidate of each rule in [`AdoptReport::patch`]; nothing is written.
+// r[impl cli.adopt]
+pub async fn adopt(
+    project_root: &Path,
+    config: &Config,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+    limit: usize,
+    annotate: bool,
+) -> Result<AdoptReport> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let canonical_root = project_root.canonicalize().ok();
+    let relative = |file: &Path| relative_to(project_root, canonical_root.as_deref(), file);
+
+    let mut report = AdoptReport {
+        impls: Vec::new(),
+        patch: PatchSet::new(project_root),
+    };
+    for (key, forward) in &data.forward_by_impl {
+        if spec.is_some_and(|s| s != key.0) || impl_name.is_some_and(|i| i != key.1) {
+            continue;
+        }
+        let prefix = data
+            .config
+            .specs
+            .iter()
+            .find(|s| s.name == key.0)
+            .map(|s| s.prefix.clone())
+            .unwrap_or_else(|| "r".to_string());
+
+        let units = impl_units(&data, key, relative);
+
+        let mut plan = ImplPlan {
+            spec: key.0.clone(),
+            impl_name: key.1.clone(),
+            ..Default::default()
+        };
+        // Best candidate of each rule, by absolute file, for --annotate.
+        let mut best: BTreeMap<&Path, Vec<(usize, &RuleId)>> = BTreeMap::new();
+        for rule in &forward.rules {
+            let has_refs = !rule.impl_refs.is_empty()
+                || !rule.verify_refs.is_empty()
+                || !rule.depends_refs.is_empty()
+                || !rule.docs_refs.is_empty();
+            if has_refs {
+                plan.referenced += 1;
+                continue;
+            }
+            let id_terms = id_terms(&rule.id);
+            let prose_terms = prose_terms(&rule.raw, &id_terms);
+            let mut scored: Vec<(&Path, Candidate)> = units
+                .iter()
+                .filter_map(|(file, unit, text)| {
+                    let (score, matched) = score(&id_terms, &prose_terms, text);
+                    (score >= MIN_SCORE).then(|| {
+                        (
+                            *file,
+                            Candidate {
+                                file: relative(file),
+                                line: unit.start_line,
+                                symbol: describe(unit),
+                                score,
+                                matched,
+                            },
+                        )
+                    })
+                })
+                .collect();
+            scored.sort_by(|a, b| {
+                b.1.score
+                    .total_cmp(&a.1.score)
+                    .then_with(|| a.1.file.cmp(&b.1.file))
+                    .then_with(|| a.1.line.cmp(&b.1.line))
+            });
+            scored.truncate(limit);
+            if let Some((file, top)) = scored.first() {
+                best.entry(file).or_default().push((top.line, &rule.id));
+            }
+            plan.rules.push(RulePlan {
+                id: rule.id.clone(),
+                candidates: scored.into_iter().map(|(_, c)| c).collect(),
+            });
+        }
+        plan.rules.sort_by(|a, b| {
+            let top = |r: &RulePlan| r.candidates.first().map_or(-1.0, |c| c.score);
+            top(b).total_cmp(&top(a))
+        });
+
+        if annotate {
+            for (file, annotations) in best {
+                let rel = relative(file);
+                let language = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+                let Some(annotations) = annotations
+                    .into_iter()
+                    .map(|(line, id)| {
+                        annotation_snippet(config, &prefix, RefVerb::Related, id, language)
+                            .map(|comment| (line, comment))
+                    })
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    plan.not_annotated.insert(rel);
+                    continue;
+                };
+                report
+                    .patch
+                    .modify(&rel, |source| insert_annotations(source, &annotations))?;
+                for (line, text) in annotations {
+                    plan.annotated.push((rel.clone(), line, text));
+                }
+            }
+        }
+        report.impls.push(plan);
+    }
+
+    if report.impls.is_empty() {
+        return Err(no_matching_impl(&data, spec, impl_name));
+    }
+    Ok(report)
+}
+
+/// Suggest rules for every code unit without references in the
+/// implementations matching `spec` and `impl_name` (all of them when `None`),
+/// listing up to `limit` rules per unit. Every rule of the spec is a
+/// candidate, including rules that are already referenced elsewhere.
+// r[impl cli.annotate]
+pub async fn suggest_rules(
+    project_root: &Path,
+    config: &Config,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+    limit: usize,
+) -> Result<AnnotateReport> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let canonical_root = project_root.canonicalize().ok();
+    let relative = |file: &Path| relative_to(project_root, canonical_root.as_deref(), file);
+
+    let mut report = AnnotateReport::default();
+    for (key, forward) in &data.forward_by_impl {
+        if spec.is_some_and(|s| s != key.0) || impl_name.is_some_and(|i| i != key.1) {
+            continue;
+        }
+        let rules: Vec<(&RuleId, Vec<String>, Vec<String>)> = forward
+            .rules
+            .iter()
+            .map(|rule| {
+                let id_terms = id_terms(&rule.id);
+                let prose_terms = prose_terms(&rule.raw, &id_terms);
+                (&rule.id, id_terms, prose_terms)
+            })
+            .collect();
+
+        let mut plan = ImplSuggestions {
+            spec: key.0.clone(),
+            impl_name: key.1.clone(),
+            ..Default::default()
+        };
+        for (file, unit, text) in impl_units(&data, key, relative) {
+            if !unit.req_refs.is_empty() {
+                plan.mapped += 1;
+                continue;
+            }
+            let mut suggestions: Vec<RuleSuggestion> = rules
+                .iter()
+                .filter_map(|(id, id_terms, prose_terms)| {
+                    let (score, matched) = score(id_terms, prose_terms, &text);
+                    (score >= MIN_SCORE).then(|| RuleSuggestion {
+                        id: (*id).clone(),
+                        score,
+                        matched,
+                    })
+                })
+                .collect();
+            if suggestions.is_empty() {
+                plan.unmatched += 1;
+                continue;
+            }
+            suggestions.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+            suggestions.truncate(limit);
+            plan.units.push(UnitSuggestions {
+                file: relative(file),
+                line: unit.start_line,
+                symbol: describe(unit),
+                rules: suggestions,
+            });
+        }
+        plan.units.sort_by(|a, b| {
+            b.rules[0]
+                .score
+                .total_cmp(&a.rules[0].score)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+        report.impls.push(plan);
+    }
+
+    if report.impls.is_empty() {
+        return Err(no_matching_impl(&data, spec, impl_name));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::parse_rule_id;
+
+    #[test]
+    fn splits_identifiers_and_matches_word_forms() {
+        assert_eq!(
+            split_words("session.token-refresh"),
+            ["session", "token", "refresh"]
+        );
+        assert_eq!(split_words("refreshHTTPToken"), ["refresh", "httptoken"]);
+        assert_eq!(split_words("validate_login"), ["validate", "login"]);
+        assert!(same_word("validation", "validate"));
+        assert!(same_word("token", "tokens"));
+        assert!(!same_word("log", "login"));
+        assert!(!same_word("session", "setting"));
+    }
+
+    // r[verify cli.adopt]
+    #[test]
+    fn ranks_units_by_rule_words() {
+        let source = "\
+/// Refresh an expired session token.
+fn refresh_token() {}
+
+fn render_page() {}
+";
+        let path = PathBuf::from("src/session.rs");
+        let units = tracey_core::code_units::extract(&path, source).units;
+        let id = parse_rule_id("session.token.refresh").unwrap();
+        let id_terms = id_terms(&id);
+        let prose_terms = prose_terms(
+            "An expired token MUST be refreshed before the request is retried.",
+            &id_terms,
+        );
+        assert!(prose_terms.contains(&"expired".to_string()));
+
+        let scores: Vec<(Option<String>, f64)> = units
+            .iter()
+            .map(|u| {
+                let text = unit_text(&path, source, u);
+                (u.name.clone(), score(&id_terms, &prose_terms, &text).0)
+            })
+            .collect();
+        let refresh = scores[0].1;
+        let render = scores[1].1;
+        assert_eq!(scores[0].0.as_deref(), Some("refresh_token"));
+        assert_eq!(Confidence::of(refresh), Confidence::High, "{scores:?}");
+        // Only the file path matches.
+        assert!(render < refresh && render > 0.0, "{scores:?}");
+    }
+
+    // r[verify cli.annotate]
+    #[tokio::test]
+    async fn suggests_rules_for_unmapped_units() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[session.token.refresh]\nAn expired token MUST be refreshed.\n\n\
+             r[page.render]\nPages MUST render as HTML.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "/// Refresh an expired session token.\npub fn refresh_token() {}\n\n\
+             // r[impl page.render]\npub fn render_page() {}\n\n\
+             pub fn unrelated_helper() {}\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            r#"specs (
+    {
+        name demo
+        include (spec.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap();
+
+        let report = suggest_rules(root, &config, None, None, 3).await.unwrap();
+        assert_eq!(report.impls.len(), 1);
+        let plan = &report.impls[0];
+        assert_eq!(plan.mapped, 1);
+        assert_eq!(plan.unmatched, 1);
+        assert_eq!(plan.units.len(), 1);
+        let unit = &plan.units[0];
+        assert_eq!(unit.symbol, "function `refresh_token`");
+        assert_eq!(unit.file, PathBuf::from("src/lib.rs"));
+        assert_eq!(unit.rules[0].id.to_string(), "session.token.refresh");
+        assert_eq!(unit.rules[0].confidence(), Confidence::High);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("`session.token.refresh`"), "{markdown}");
+        assert!(
+            suggest_rules(root, &config, Some("nope"), None, 3)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn annotations_go_above_the_unit_with_its_indent() {
+        let source = "mod auth {\n    /// Log in.\n    fn login() {}\n}\n";
+        let annotated = insert_annotations(source, &[(2, "// r[related auth.login]".to_string())]);
+        assert_eq!(
+            annotated,
+            "mod auth {\n    // r[related auth.login]\n    /// Log in.\n    fn login() {}\n}\n"
+        );
+    }
+}
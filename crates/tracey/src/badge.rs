@@ -0,0 +1,378 @@
+//! `tracey badge` implementation.
+//!
+//! Renders coverage as self-contained SVG for READMEs and wiki pages: either
+//! a single shields-style badge with the overall implementation coverage, or
+//! a small table with implementation and verification coverage per spec and
+//! implementation. The same renderer backs the dashboard's `/api/summary.svg`
+//! and `/api/badge/{spec}/{impl}.svg`.
+
+use std::fmt::Write as _;
+
+use tracey_proto::{ImplStatus, StatusResponse};
+
+/// Approximate advance width of an 11px Verdana glyph.
+const CHAR_WIDTH: usize = 7;
+/// Horizontal padding on each side of a cell's text.
+const PAD: usize = 6;
+const ROW_HEIGHT: usize = 20;
+
+/// What `tracey badge` renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// One badge: overall implementation coverage
+    #[default]
+    Flat,
+    /// One row per spec and implementation, with impl and verify coverage
+    Table,
+}
+
+impl std::str::FromStr for Style {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "flat" => Ok(Self::Flat),
+            "table" => Ok(Self::Table),
+            other => Err(eyre::eyre!(
+                "Unsupported badge style `{other}`; expected flat or table"
+            )),
+        }
+    }
+}
+
+/// Which coverage a badge shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    /// Rules with an implementation reference
+    #[default]
+    Impl,
+    /// Rules with a verification reference
+    Verify,
+}
+
+impl Metric {
+    /// `impl` or `verify`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Impl => "impl",
+            Self::Verify => "verify",
+        }
+    }
+
+    fn count(self, status: &ImplStatus) -> usize {
+        match self {
+            Self::Impl => status.covered_rules,
+            Self::Verify => status.verified_rules,
+        }
+    }
+}
+
+impl std::str::FromStr for Metric {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "impl" => Ok(Self::Impl),
+            "verify" => Ok(Self::Verify),
+            other => Err(eyre::eyre!(
+                "Unsupported badge metric `{other}`; expected impl or verify"
+            )),
+        }
+    }
+}
+
+/// Render the status in the given style.
+pub fn render(status: &StatusResponse, style: Style) -> String {
+    match style {
+        Style::Flat => render_flat(status),
+        Style::Table => render_table(status),
+    }
+}
+
+/// Render a single badge with implementation coverage summed over every
+/// non-advisory spec and implementation.
+///
+/// r[impl cli.badge.flat]
+pub fn render_flat(status: &StatusResponse) -> String {
+    let (covered, total) = overall(status, Metric::Impl);
+    shield("spec coverage", covered, total)
+}
+
+/// Render a badge with one metric of one spec and implementation.
+pub fn render_impl(status: &ImplStatus, metric: Metric) -> String {
+    shield(
+        &format!("{}/{} {}", status.spec, status.impl_name, metric.name()),
+        metric.count(status),
+        status.total_rules,
+    )
+}
+
+/// Every badge `tracey badge --output` writes, as paths relative to the
+/// output directory: overall `impl.svg` and `verify.svg` over the
+/// non-advisory specs, then `{spec}/{impl}.svg` and `{spec}/{impl}-verify.svg`
+/// for each implementation.
+///
+/// r[impl cli.badge.dir]
+pub fn render_all(status: &StatusResponse) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    for metric in [Metric::Impl, Metric::Verify] {
+        let (part, total) = overall(status, metric);
+        files.push((
+            format!("{}.svg", metric.name()),
+            shield(&format!("{} coverage", metric.name()), part, total),
+        ));
+    }
+    for s in &status.impls {
+        files.push((
+            format!("{}/{}.svg", s.spec, s.impl_name),
+            render_impl(s, Metric::Impl),
+        ));
+        files.push((
+            format!("{}/{}-verify.svg", s.spec, s.impl_name),
+            render_impl(s, Metric::Verify),
+        ));
+    }
+    files
+}
+
+/// `metric` and total rules summed over every non-advisory spec.
+fn overall(status: &StatusResponse, metric: Metric) -> (usize, usize) {
+    status
+        .impls
+        .iter()
+        .filter(|s| !s.advisory)
+        .fold((0, 0), |(c, t), s| (c + metric.count(s), t + s.total_rules))
+}
+
+/// A shields-style badge: `label` on grey, the percentage on its color.
+fn shield(label: &str, covered: usize, total: usize) -> String {
+    let value = percent_label(covered, total);
+    let label_w = text_width(label);
+    let value_w = text_width(&value);
+    let width = label_w + value_w;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{ROW_HEIGHT}" role="img" aria-label="{}: {value}">"##,
+        escape(label)
+    );
+    let _ = write!(svg, "<title>{}: {value}</title>", escape(label));
+    let _ = write!(
+        svg,
+        r##"<rect width="{label_w}" height="{ROW_HEIGHT}" fill="#555"/><rect x="{label_w}" width="{value_w}" height="{ROW_HEIGHT}" fill="{}"/>"##,
+        color(covered, total)
+    );
+    svg.push_str(r##"<g fill="#fff" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11" text-anchor="middle">"##);
+    push_text(&mut svg, label_w / 2, 14, label);
+    push_text(&mut svg, label_w + value_w / 2, 14, &value);
+    svg.push_str("</g></svg>\n");
+    svg
+}
+
+/// Render a table with one row per spec and implementation.
+///
+/// r[impl cli.badge.table]
+pub fn render_table(status: &StatusResponse) -> String {
+    let header = ["spec", "impl", "impl", "verify"];
+    let rows: Vec<[String; 4]> = status
+        .impls
+        .iter()
+        .map(|s| {
+            let spec = if s.advisory {
+                format!("{} (advisory)", s.spec)
+            } else {
+                s.spec.clone()
+            };
+            [
+                spec,
+                s.impl_name.clone(),
+                percent_label(s.covered_rules, s.total_rules),
+                percent_label(s.verified_rules, s.total_rules),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| text_width(&row[col]))
+                .chain([text_width(header[col])])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let xs: Vec<usize> = widths
+        .iter()
+        .scan(0, |x, w| {
+            let start = *x;
+            *x += w;
+            Some(start)
+        })
+        .collect();
+    let width: usize = widths.iter().sum();
+    let height = ROW_HEIGHT * (rows.len() + 1);
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" role="img" aria-label="spec coverage">"##
+    );
+    svg.push_str("<title>spec coverage</title>");
+    let _ = write!(
+        svg,
+        r##"<rect width="{width}" height="{ROW_HEIGHT}" fill="#555"/>"##
+    );
+    for (i, status) in status.impls.iter().enumerate() {
+        let y = ROW_HEIGHT * (i + 1);
+        let stripe = if i % 2 == 0 { "#f6f8fa" } else { "#fff" };
+        let _ = write!(
+            svg,
+            r##"<rect y="{y}" width="{width}" height="{ROW_HEIGHT}" fill="{stripe}"/>"##
+        );
+        for (col, (covered, total)) in [
+            (2, (status.covered_rules, status.total_rules)),
+            (3, (status.verified_rules, status.total_rules)),
+        ] {
+            let _ = write!(
+                svg,
+                r##"<rect x="{}" y="{y}" width="{}" height="{ROW_HEIGHT}" fill="{}"/>"##,
+                xs[col],
+                widths[col],
+                color(covered, total)
+            );
+        }
+    }
+
+    svg.push_str(r##"<g font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##);
+    svg.push_str(r##"<g fill="#fff">"##);
+    for (col, label) in header.iter().enumerate() {
+        push_text(&mut svg, xs[col] + PAD, 14, label);
+    }
+    svg.push_str("</g>");
+    for (i, row) in rows.iter().enumerate() {
+        let y = ROW_HEIGHT * (i + 1) + 14;
+        svg.push_str(r##"<g fill="#24292f">"##);
+        for (col, cell) in row.iter().take(2).enumerate() {
+            push_text(&mut svg, xs[col] + PAD, y, cell);
+        }
+        svg.push_str(r##"</g><g fill="#fff">"##);
+        for (col, cell) in row.iter().enumerate().skip(2) {
+            push_text(&mut svg, xs[col] + PAD, y, cell);
+        }
+        svg.push_str("</g>");
+    }
+    svg.push_str("</g></svg>\n");
+    svg
+}
+
+/// Percentage of `part` in `total`, rounded down, or `None` for an empty spec.
+fn percent(part: usize, total: usize) -> Option<usize> {
+    (part * 100).checked_div(total)
+}
+
+fn percent_label(part: usize, total: usize) -> String {
+    match percent(part, total) {
+        Some(p) => format!("{p}%"),
+        None => "n/a".to_string(),
+    }
+}
+
+/// Shields.io palette, from red to bright green.
+fn color(part: usize, total: usize) -> &'static str {
+    match percent(part, total) {
+        None => "#9f9f9f",
+        Some(100..) => "#4c1",
+        Some(90..) => "#97ca00",
+        Some(75..) => "#a4a61d",
+        Some(50..) => "#dfb317",
+        Some(25..) => "#fe7d37",
+        Some(_) => "#e05d44",
+    }
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count() * CHAR_WIDTH + 2 * PAD
+}
+
+fn push_text(svg: &mut String, x: usize, y: usize, text: &str) {
+    let _ = write!(svg, r#"<text x="{x}" y="{y}">{}</text>"#, escape(text));
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(spec: &str, covered: usize, verified: usize, advisory: bool) -> ImplStatus {
+        ImplStatus {
+            spec: spec.to_string(),
+            impl_name: "rust".to_string(),
+            total_rules: 10,
+            covered_rules: covered,
+            partial_rules: 0,
+            stale_rules: 0,
+            verified_rules: verified,
+            advisory,
+            documented_rules: None,
+            proven_rules: None,
+        }
+    }
+
+    // r[verify cli.badge.flat]
+    #[test]
+    fn flat_badge_sums_non_advisory_specs() {
+        let svg = render_flat(&StatusResponse {
+            impls: vec![
+                status("core", 10, 5, false),
+                status("ext", 5, 0, false),
+                status("vendor", 0, 0, true),
+            ],
+        });
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("<title>spec coverage: 75%</title>"));
+    }
+
+    // r[verify cli.badge.dir]
+    #[test]
+    fn badge_dir_has_overall_and_per_impl_badges() {
+        let status = StatusResponse {
+            impls: vec![status("core", 10, 5, false), status("vendor", 0, 0, true)],
+        };
+        let files = render_all(&status);
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "impl.svg",
+                "verify.svg",
+                "core/rust.svg",
+                "core/rust-verify.svg",
+                "vendor/rust.svg",
+                "vendor/rust-verify.svg",
+            ]
+        );
+        assert!(files[0].1.contains("<title>impl coverage: 100%</title>"));
+        assert!(files[1].1.contains("<title>verify coverage: 50%</title>"));
+        assert!(files[3].1.contains("<title>core/rust verify: 50%</title>"));
+    }
+
+    // r[verify cli.badge.table]
+    #[test]
+    fn table_has_a_row_per_impl() {
+        let svg = render_table(&StatusResponse {
+            impls: vec![status("a<b", 9, 3, false), status("vendor", 0, 0, true)],
+        });
+        assert!(svg.contains(r#"height="60""#));
+        assert!(svg.contains(">a&lt;b<"));
+        assert!(svg.contains(">vendor (advisory)<"));
+        assert!(svg.contains(">90%<"));
+        assert!(svg.contains(">30%<"));
+    }
+}
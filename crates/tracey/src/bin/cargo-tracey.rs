@@ -0,0 +1,121 @@
+//! `cargo tracey` - run tracey as a cargo subcommand.
+//!
+//! r[impl cli.cargo]
+//!
+//! Cargo runs `cargo-tracey tracey <args>` for `cargo tracey <args>`. This
+//! wrapper asks `cargo metadata` for the workspace root and forwards to the
+//! `tracey` binary installed next to it with `--root` pinned to that root, so
+//! the invocation resolves the same project from any crate in the workspace.
+//! An explicit `--root` is passed through untouched.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+
+/// The part of `cargo metadata` output the wrapper needs.
+#[derive(facet::Facet)]
+struct Metadata {
+    workspace_root: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    // Cargo passes the subcommand name as the first argument.
+    if args.first().is_some_and(|a| a == "tracey") {
+        args.remove(0);
+    }
+
+    let root = if has_root_flag(&args) {
+        None
+    } else {
+        workspace_root()
+    };
+
+    let status = Command::new(tracey_exe())
+        .args(forwarded_args(args, root))
+        .status();
+    match status {
+        Ok(status) => ExitCode::from(status.code().unwrap_or(1).clamp(0, 255) as u8),
+        Err(e) => {
+            eprintln!("error: failed to run tracey: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The cargo workspace root of the current directory, if there is one.
+fn workspace_root() -> Option<PathBuf> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let out = Command::new(cargo)
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    parse_workspace_root(&String::from_utf8_lossy(&out.stdout))
+}
+
+fn parse_workspace_root(metadata: &str) -> Option<PathBuf> {
+    facet_json::from_str::<Metadata>(metadata)
+        .ok()
+        .map(|m| m.workspace_root)
+}
+
+/// The `tracey` binary next to this one, falling back to `PATH`.
+fn tracey_exe() -> PathBuf {
+    let name = format!("tracey{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .map(|exe| exe.with_file_name(&name))
+        .ok()
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| name.into())
+}
+
+fn has_root_flag(args: &[OsString]) -> bool {
+    args.iter()
+        .filter_map(|a| a.to_str())
+        .any(|a| a == "--root" || a.starts_with("--root="))
+}
+
+fn forwarded_args(args: Vec<OsString>, root: Option<PathBuf>) -> Vec<OsString> {
+    let mut forwarded = Vec::with_capacity(args.len() + 2);
+    if let Some(root) = root {
+        forwarded.push(OsStr::new("--root").to_owned());
+        forwarded.push(root.into_os_string());
+    }
+    forwarded.extend(args);
+    forwarded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    // r[verify cli.cargo]
+    #[test]
+    fn pins_root_before_the_subcommand() {
+        let args = forwarded_args(os(&["query", "status"]), Some("/ws".into()));
+        assert_eq!(args, os(&["--root", "/ws", "query", "status"]));
+    }
+
+    #[test]
+    fn explicit_root_is_respected() {
+        assert!(has_root_flag(&os(&["--root", "/x", "query", "status"])));
+        assert!(has_root_flag(&os(&["--root=/x", "web"])));
+        assert!(!has_root_flag(&os(&["query", "status"])));
+    }
+
+    #[test]
+    fn reads_workspace_root_from_cargo_metadata() {
+        let metadata = r#"{"packages":[],"workspace_members":[],"workspace_root":"/home/me/ws","target_directory":"/home/me/ws/target","version":1}"#;
+        assert_eq!(
+            parse_workspace_root(metadata),
+            Some(PathBuf::from("/home/me/ws"))
+        );
+    }
+}
@@ -3,94 +3,211 @@
 //! `tracey export <output>` produces a fully self-contained directory of HTML
 //! files that can be served by any static file host. No daemon or JavaScript
 //! framework is required to view the exported pages.
+//!
+//! `tracey report --out <output>` writes the same site with source pages,
+//! but builds the data in-process instead of asking the daemon, so it runs
+//! in CI without starting one.
 
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
 use eyre::{Result, WrapErr, eyre};
+use facet::Facet;
 use tracey_api::{
-    ApiCodeUnit, ApiConfig, ApiFileData, ApiReverseData, ApiRule, ApiSpecData, ApiSpecForward,
-    OutlineEntry,
+    ApiCodeRef, ApiCodeUnit, ApiConfig, ApiFileData, ApiReverseData, ApiRule, ApiSpecData,
+    ApiSpecForward, OutlineEntry,
 };
+use tracey_proto::{FileRequest, TraceyDaemon};
+
+use crate::daemon::{DaemonClient, Engine, TraceyService};
+
+/// Where an export reads its data from.
+enum Source {
+    /// The project's daemon, started if needed.
+    Daemon(DaemonClient),
+    /// A service over an engine built in this process.
+    Local(TraceyService),
+}
+
+impl Source {
+    async fn config(&self) -> Result<ApiConfig> {
+        match self {
+            Self::Daemon(client) => client
+                .config()
+                .await
+                .map_err(|e| eyre!("config RPC failed: {:?}", e)),
+            Self::Local(service) => Ok(service.config().await),
+        }
+    }
 
+    async fn forward(&self, spec: &str, impl_name: &str) -> Result<Option<ApiSpecForward>> {
+        match self {
+            Self::Daemon(client) => client
+                .forward(spec.to_string(), impl_name.to_string())
+                .await
+                .map_err(|e| eyre!("forward RPC failed for {spec}/{impl_name}: {:?}", e)),
+            Self::Local(service) => Ok(service
+                .forward(spec.to_string(), impl_name.to_string())
+                .await),
+        }
+    }
+
+    async fn reverse(&self, spec: &str, impl_name: &str) -> Result<Option<ApiReverseData>> {
+        match self {
+            Self::Daemon(client) => client
+                .reverse(spec.to_string(), impl_name.to_string())
+                .await
+                .map_err(|e| eyre!("reverse RPC failed for {spec}/{impl_name}: {:?}", e)),
+            Self::Local(service) => Ok(service
+                .reverse(spec.to_string(), impl_name.to_string())
+                .await),
+        }
+    }
+
+    async fn spec_content(&self, spec: &str, impl_name: &str) -> Result<Option<ApiSpecData>> {
+        match self {
+            Self::Daemon(client) => client
+                .spec_content(spec.to_string(), impl_name.to_string())
+                .await
+                .map_err(|e| eyre!("spec_content RPC failed for {spec}/{impl_name}: {:?}", e)),
+            Self::Local(service) => Ok(service
+                .spec_content(spec.to_string(), impl_name.to_string())
+                .await),
+        }
+    }
+
+    async fn file(&self, req: FileRequest) -> Result<Option<ApiFileData>> {
+        let path = req.path.clone();
+        match self {
+            Self::Daemon(client) => client
+                .file(req)
+                .await
+                .map_err(|e| eyre!("file RPC failed for {path}: {:?}", e)),
+            Self::Local(service) => Ok(service.file(req).await),
+        }
+    }
+}
+
+/// `tracey export`: write the site from the project's daemon.
 pub async fn run(
     root: Option<PathBuf>,
     _config_path: PathBuf,
     output: PathBuf,
     include_sources: bool,
+    redirects: bool,
 ) -> Result<()> {
     let project_root = match root {
         Some(r) => r,
         None => crate::find_project_root().wrap_err("finding project root")?,
     };
+    let source = Source::Daemon(crate::daemon::new_client(project_root));
+    export(&source, &output, include_sources, redirects).await
+}
 
-    let client = crate::daemon::new_client(project_root);
-    let config = client
-        .config()
-        .await
-        .map_err(|e| eyre!("config RPC failed: {:?}", e))?;
+/// `tracey report --out`: build the data in this process, without a daemon,
+/// and write the site with a page per source file.
+///
+/// r[impl cli.report.site]
+pub async fn run_local(project_root: PathBuf, config_path: PathBuf, output: PathBuf) -> Result<()> {
+    let engine = Engine::new(project_root, config_path).await?;
+    if let Some(error) = engine.config_error().await {
+        return Err(eyre!("{error}"));
+    }
+    let source = Source::Local(TraceyService::new(std::sync::Arc::new(engine)));
+    export(&source, &output, true, false).await
+}
 
-    std::fs::create_dir_all(&output)
-        .wrap_err_with(|| format!("creating output directory {}", output.display()))?;
-    write_assets(&output).wrap_err("writing static assets")?;
+async fn export(
+    source: &Source,
+    output: &Path,
+    include_sources: bool,
+    redirects: bool,
+) -> Result<()> {
+    let config = source.config().await?;
 
-    let first = config.specs.first().and_then(|s| {
-        s.implementations
-            .first()
-            .map(|i| (s.name.clone(), i.clone()))
-    });
-    write_root_index(&output, first.as_ref()).wrap_err("writing root index.html")?;
+    std::fs::create_dir_all(output)
+        .wrap_err_with(|| format!("creating output directory {}", output.display()))?;
+    write_assets(output).wrap_err("writing static assets")?;
 
+    let mut stats = Vec::new();
+    let mut redirected = HashSet::new();
     for spec_info in &config.specs {
-        for impl_name in &spec_info.implementations {
+        for (impl_index, impl_name) in spec_info.implementations.iter().enumerate() {
             let spec_name = &spec_info.name;
             eprintln!("Exporting {spec_name} × {impl_name}…");
 
-            let forward = client
-                .forward(spec_name.clone(), impl_name.clone())
-                .await
-                .map_err(|e| eyre!("forward RPC failed for {spec_name}/{impl_name}: {:?}", e))?
+            let forward = source
+                .forward(spec_name, impl_name)
+                .await?
                 .ok_or_else(|| eyre!("no forward data for {spec_name}/{impl_name}"))?;
 
-            let reverse = client
-                .reverse(spec_name.clone(), impl_name.clone())
-                .await
-                .map_err(|e| eyre!("reverse RPC failed for {spec_name}/{impl_name}: {:?}", e))?
+            let reverse = source
+                .reverse(spec_name, impl_name)
+                .await?
                 .ok_or_else(|| eyre!("no reverse data for {spec_name}/{impl_name}"))?;
 
-            let spec_content = client
-                .spec_content(spec_name.clone(), impl_name.clone())
-                .await
-                .map_err(|e| {
-                    eyre!(
-                        "spec_content RPC failed for {spec_name}/{impl_name}: {:?}",
-                        e
-                    )
-                })?
+            let spec_content = source
+                .spec_content(spec_name, impl_name)
+                .await?
                 .ok_or_else(|| eyre!("no spec content for {spec_name}/{impl_name}"))?;
+            stats.push(PairStats::of(spec_name, impl_name, &forward, &reverse));
 
             let pair_dir = output.join(spec_name).join(impl_name);
             std::fs::create_dir_all(&pair_dir)
                 .wrap_err_with(|| format!("creating directory {}", pair_dir.display()))?;
 
+            let pages = split_spec_pages(spec_name, &spec_info.prefix, &spec_content);
+            for index in 0..pages.pages.len() {
+                let file = &pages.pages[index].file;
+                std::fs::write(
+                    pair_dir.join(file),
+                    render_spec_page(
+                        spec_name,
+                        impl_name,
+                        &spec_content,
+                        &pages,
+                        index,
+                        &config,
+                        include_sources,
+                    )
+                    .wrap_err_with(|| {
+                        format!("rendering spec page {file} for {spec_name}/{impl_name}")
+                    })?,
+                )
+                .wrap_err_with(|| format!("writing {spec_name}/{impl_name}/{file}"))?;
+            }
             std::fs::write(
-                pair_dir.join("spec.html"),
-                render_spec_page(
+                pair_dir.join(SEARCH_INDEX_FILE),
+                render_search_index(&spec_content, &forward, &pages).wrap_err_with(|| {
+                    format!("rendering search index for {spec_name}/{impl_name}")
+                })?,
+            )
+            .wrap_err_with(|| format!("writing {spec_name}/{impl_name}/{SEARCH_INDEX_FILE}"))?;
+
+            if redirects && impl_index == 0 {
+                write_rule_redirects(
+                    output,
                     spec_name,
                     impl_name,
-                    &spec_content,
-                    &config,
-                    include_sources,
+                    &forward,
+                    &pages,
+                    &mut redirected,
                 )
-                .wrap_err_with(|| format!("rendering spec page for {spec_name}/{impl_name}"))?,
-            )
-            .wrap_err_with(|| format!("writing {spec_name}/{impl_name}/spec.html"))?;
+                .wrap_err_with(|| format!("writing rule redirects for {spec_name}"))?;
+            }
 
             std::fs::write(
                 pair_dir.join("coverage.html"),
-                render_coverage_page(spec_name, impl_name, &forward, &config, include_sources)
-                    .wrap_err_with(|| {
-                        format!("rendering coverage page for {spec_name}/{impl_name}")
-                    })?,
+                render_coverage_page(
+                    spec_name,
+                    impl_name,
+                    &forward,
+                    &pages,
+                    &config,
+                    include_sources,
+                )
+                .wrap_err_with(|| format!("rendering coverage page for {spec_name}/{impl_name}"))?,
             )
             .wrap_err_with(|| format!("writing {spec_name}/{impl_name}/coverage.html"))?;
 
@@ -106,16 +223,12 @@ pub async fn run(
                 let sources_dir = pair_dir.join("sources");
                 for file_entry in &reverse.files {
                     let path = &file_entry.path;
-                    let req = tracey_proto::FileRequest {
+                    let req = FileRequest {
                         spec: spec_name.clone(),
                         impl_name: impl_name.clone(),
                         path: path.clone(),
                     };
-                    if let Some(file_data) = client
-                        .file(req)
-                        .await
-                        .map_err(|e| eyre!("file RPC failed for {path}: {:?}", e))?
-                    {
+                    if let Some(file_data) = source.file(req).await? {
                         let file_html = render_file_page(spec_name, impl_name, &file_data, &config)
                             .wrap_err_with(|| format!("rendering file page for {path}"))?;
                         let out_path = sources_dir.join(format!("{path}.html"));
@@ -132,6 +245,12 @@ pub async fn run(
         }
     }
 
+    std::fs::write(
+        output.join("index.html"),
+        render_index_page(&stats, &config, include_sources),
+    )
+    .wrap_err("writing index.html")?;
+
     eprintln!("\nDone! Static site written to: {}", output.display());
     eprintln!(
         "Serve with:  python3 -m http.server -d {}",
@@ -156,24 +275,54 @@ fn write_assets(output: &Path) -> Result<()> {
     Ok(())
 }
 
-fn write_root_index(output: &Path, first: Option<&(String, String)>) -> Result<()> {
-    let html = if let Some((spec, impl_name)) = first {
-        format!(
-            r#"<!DOCTYPE html>
+/// Write `r/<rule-id>/index.html` for every rule of a spec, redirecting to
+/// the rule on its page of the spec's first implementation. IDs are
+/// unversioned so links survive rule bumps; the first spec to define an ID
+/// keeps it.
+///
+/// r[impl cli.export.redirects]
+fn write_rule_redirects(
+    output: &Path,
+    spec_name: &str,
+    impl_name: &str,
+    forward: &ApiSpecForward,
+    pages: &SpecPages,
+    redirected: &mut HashSet<String>,
+) -> Result<()> {
+    for rule in &forward.rules {
+        if !redirected.insert(rule.id.base.clone()) {
+            continue;
+        }
+        let (page, anchor) = pages.resolve(&format!("r--{}", rule.id));
+        let target = format!("/{spec_name}/{impl_name}/{page}#{anchor}");
+        let dir = output.join("r").join(&rule.id.base);
+        std::fs::create_dir_all(&dir)
+            .wrap_err_with(|| format!("creating directory {}", dir.display()))?;
+        std::fs::write(
+            dir.join("index.html"),
+            render_redirect(&rule.id.base, &target),
+        )
+        .wrap_err_with(|| format!("writing r/{}/index.html", rule.id.base))?;
+    }
+    Ok(())
+}
+
+fn render_redirect(title: &str, target: &str) -> String {
+    let title = html_escape(title);
+    let target = html_escape(target);
+    format!(
+        r#"<!DOCTYPE html>
 <html>
 <head>
   <meta charset="UTF-8">
-  <meta http-equiv="refresh" content="0; url=/{spec}/{impl_name}/spec.html">
-  <title>Tracey</title>
+  <meta http-equiv="refresh" content="0; url={target}">
+  <link rel="canonical" href="{target}">
+  <title>{title}</title>
 </head>
-<body><p>Redirecting to <a href="/{spec}/{impl_name}/spec.html">spec</a>&hellip;</p></body>
-</html>"#
-        )
-    } else {
-        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"UTF-8\"><title>Tracey</title></head>\n<body><p>No specs configured.</p></body>\n</html>\n".to_string()
-    };
-    std::fs::write(output.join("index.html"), html).wrap_err("writing index.html")?;
-    Ok(())
+<body><p>Redirecting to <a href="{target}">{title}</a>&hellip;</p></body>
+</html>
+"#
+    )
 }
 
 // ============================================================================
@@ -310,42 +459,245 @@ fn page_shell(
 // Spec page
 // ============================================================================
 
+/// File name of the first spec page, which every other page links back to.
+const SPEC_INDEX: &str = "spec.html";
+
+/// Script defining `window.TRACEY_SEARCH`, next to the spec pages. A script
+/// rather than plain JSON so it also loads from `file://` URLs.
+const SEARCH_INDEX_FILE: &str = "search-index.js";
+
+/// A spec split into one page per top-level heading.
+struct SpecPages {
+    /// Marker prefix of the spec, for resolving `#r--{id}` dashboard links
+    prefix: String,
+    pages: Vec<SpecPage>,
+    /// Page file holding each element id
+    anchors: HashMap<String, String>,
+}
+
+struct SpecPage {
+    /// File name, relative to the spec/impl directory
+    file: String,
+    title: String,
+    html: String,
+    /// Outline entries on this page, as indices into `ApiSpecData::outline`
+    outline: Range<usize>,
+}
+
+impl SpecPages {
+    /// Page file and element id for a fragment of a dashboard spec link.
+    /// Requirements are linked as `#r--{id}` by the dashboard router but
+    /// rendered with a `{prefix}-{id}` element id.
+    fn resolve(&self, fragment: &str) -> (&str, String) {
+        let anchor = match fragment.strip_prefix("r--") {
+            Some(id) => format!("{}-{id}", self.prefix),
+            None => fragment.to_string(),
+        };
+        let file = self.anchors.get(&anchor).map_or(SPEC_INDEX, String::as_str);
+        (file, anchor)
+    }
+}
+
+/// Split the rendered spec at its top-level headings. Content before the
+/// first of them stays on the first page, which is always `spec.html`.
+///
+/// r[impl cli.export.pages]
+fn split_spec_pages(spec_name: &str, prefix: &str, spec_data: &ApiSpecData) -> SpecPages {
+    let html = spec_data
+        .sections
+        .iter()
+        .map(|s| s.html.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let outline = &spec_data.outline;
+
+    // (outline index, byte offset) of every top-level heading, in order
+    let mut starts: Vec<(usize, usize)> = Vec::new();
+    if let Some(top) = outline.iter().map(|e| e.level).min() {
+        for (i, entry) in outline.iter().enumerate() {
+            if entry.level != top {
+                continue;
+            }
+            let tag = format!(r#"<h{top} id="{}">"#, html_escape(&entry.slug));
+            if let Some(pos) = html.find(&tag)
+                && starts.last().is_none_or(|&(_, last)| pos > last)
+            {
+                starts.push((i, pos));
+            }
+        }
+    }
+    if starts.is_empty() {
+        starts.push((0, 0));
+    }
+
+    let mut pages = Vec::new();
+    for (k, &(entry, start)) in starts.iter().enumerate() {
+        let (next_entry, end) = starts
+            .get(k + 1)
+            .copied()
+            .unwrap_or((outline.len(), html.len()));
+        let (first_entry, start) = if k == 0 { (0, 0) } else { (entry, start) };
+        let title = outline
+            .get(entry)
+            .map_or_else(|| spec_name.to_string(), |e| e.title.clone());
+        let file = if k == 0 {
+            SPEC_INDEX.to_string()
+        } else {
+            format!("spec-{}.html", outline[entry].slug)
+        };
+        pages.push(SpecPage {
+            file,
+            title,
+            html: html[start..end].to_string(),
+            outline: first_entry..next_entry,
+        });
+    }
+
+    let anchors = pages
+        .iter()
+        .flat_map(|page| element_ids(&page.html).map(|id| (id.to_string(), page.file.clone())))
+        .collect();
+    SpecPages {
+        prefix: prefix.to_string(),
+        pages,
+        anchors,
+    }
+}
+
+/// Values of every `id="…"` attribute in `html`.
+fn element_ids(html: &str) -> impl Iterator<Item = &str> {
+    html.match_indices(r#" id=""#).filter_map(|(i, m)| {
+        let rest = &html[i + m.len()..];
+        rest.find('"').map(|end| &rest[..end])
+    })
+}
+
 fn render_spec_page(
     spec_name: &str,
     impl_name: &str,
     spec_data: &ApiSpecData,
+    pages: &SpecPages,
+    index: usize,
     config: &ApiConfig,
     include_sources: bool,
 ) -> Result<String> {
-    let sidebar = render_outline_sidebar(&spec_data.outline);
-    let content = spec_data
-        .sections
-        .iter()
-        .map(|s| rewrite_spec_html(&s.html, include_sources))
-        .collect::<Vec<_>>()
-        .join("\n");
-    let head_extras = spec_data.head_injections.join("\n");
+    let page = &pages.pages[index];
+    let search = r#"<div class="spec-search"><input type="search" class="spec-search-input" placeholder="Search spec…" aria-label="Search spec"><ul class="spec-search-results"></ul></div>"#;
+    let sidebar = format!(
+        "{search}{}",
+        render_outline_sidebar(&spec_data.outline, pages, index)
+    );
+    let content = rewrite_spec_html(
+        &page.html,
+        include_sources,
+        &format!("/{spec_name}/{impl_name}/"),
+        pages,
+    );
+
+    // r[impl cli.export.pager]
+    let link = |rel: &str, target: Option<&SpecPage>| {
+        target.map_or(String::new(), |p| {
+            format!(
+                r#"<a class="spec-pager-{rel}" rel="{rel}" href="{}">{}</a>"#,
+                p.file,
+                html_escape(&p.title)
+            )
+        })
+    };
+    let pager = format!(
+        r#"<nav class="spec-pager">{}{}</nav>"#,
+        link(
+            "prev",
+            index.checked_sub(1).and_then(|i| pages.pages.get(i))
+        ),
+        link("next", pages.pages.get(index + 1)),
+    );
 
+    let mut head_extras = spec_data.head_injections.join("\n");
+    head_extras.push_str(&format!(r#"\n<script src="{SEARCH_INDEX_FILE}"></script>"#));
+
+    let title = if pages.pages.len() > 1 {
+        format!("{} — Spec: {spec_name}", page.title)
+    } else {
+        format!("Spec: {spec_name}")
+    };
     Ok(page_shell(
-        &format!("Spec: {spec_name}"),
+        &title,
         spec_name,
         impl_name,
         "spec",
         config,
         include_sources,
         &sidebar,
-        &format!(r#"<div class="spec-page markdown">{content}</div>"#),
+        &format!(r#"<div class="spec-page markdown">{content}{pager}</div>"#),
         &head_extras,
     ))
 }
 
-fn render_outline_sidebar(outline: &[OutlineEntry]) -> String {
+/// Search index in the shape lunr's builder takes: a `ref` field naming
+/// the unique key, the indexed `fields`, and the documents themselves.
+#[derive(Facet)]
+struct SearchIndex {
+    #[facet(rename = "ref")]
+    reference: String,
+    fields: Vec<String>,
+    documents: Vec<SearchDocument>,
+}
+
+#[derive(Facet)]
+struct SearchDocument {
+    /// Element id on `page`
+    anchor: String,
+    page: String,
+    /// Heading text, or the requirement ID
+    title: String,
+    /// Requirement text; empty for headings
+    body: String,
+}
+
+/// Render the search index for one spec/impl pair: every heading and
+/// every requirement, with the page it is on.
+///
+/// r[impl cli.export.search]
+fn render_search_index(
+    spec_data: &ApiSpecData,
+    forward: &ApiSpecForward,
+    pages: &SpecPages,
+) -> Result<String> {
+    let headings = spec_data.outline.iter().map(|e| SearchDocument {
+        page: pages.resolve(&e.slug).0.to_string(),
+        anchor: e.slug.clone(),
+        title: e.title.clone(),
+        body: String::new(),
+    });
+    let rules = forward.rules.iter().map(|rule| {
+        let (page, anchor) = pages.resolve(&format!("r--{}", rule.id));
+        SearchDocument {
+            page: page.to_string(),
+            anchor,
+            title: rule.id.to_string(),
+            body: rule.raw.trim().to_string(),
+        }
+    });
+    let index = SearchIndex {
+        reference: "anchor".to_string(),
+        fields: vec!["title".to_string(), "body".to_string()],
+        documents: headings.chain(rules).collect(),
+    };
+    let json = facet_json::to_string(&index).wrap_err("serializing search index")?;
+    Ok(format!("window.TRACEY_SEARCH = {json};\n"))
+}
+
+/// Outline tree for the sidebar of spec page `index`. Entries link to the
+/// page they are on, and the current page's entries are marked.
+fn render_outline_sidebar(outline: &[OutlineEntry], pages: &SpecPages, index: usize) -> String {
     if outline.is_empty() {
         return String::new();
     }
     let items = outline
         .iter()
-        .map(|e| {
+        .enumerate()
+        .map(|(i, e)| {
             let depth = e.level;
             let cov = &e.aggregated;
 
@@ -358,7 +710,13 @@ fn render_outline_sidebar(outline: &[OutlineEntry]) -> String {
             } else {
                 ""
             };
-
+            let (file, anchor) = pages.resolve(&e.slug);
+            let href = format!("{file}#{anchor}");
+            let current_class = if pages.pages[index].outline.contains(&i) {
+                " is-current-page"
+            } else {
+                ""
+            };
             // Inline SVG arcs (matches the dashboard CoverageArc component)
             let impl_arc = coverage_arc_svg(
                 cov.impl_count,
@@ -389,13 +747,12 @@ fn render_outline_sidebar(outline: &[OutlineEntry]) -> String {
             };
 
             format!(
-                r##"<li class="toc-item depth-{depth}{status_class}">
-  <a class="toc-row" href="#{slug}"{indent_style}>
+                r##"<li class="toc-item depth-{depth}{status_class}{current_class}">
+  <a class="toc-row" href="{href}"{indent_style}>
     <span class="toc-link">{title}</span>
     {badges}
   </a>
 </li>"##,
-                slug = e.slug,
                 title = html_escape(&e.title),
             )
         })
@@ -436,6 +793,142 @@ fn coverage_arc_svg(count: usize, total: usize, color: &str, title: &str) -> Str
     )
 }
 
+// ============================================================================
+// Index page
+// ============================================================================
+
+/// Coverage numbers of one spec and implementation, for the index page.
+struct PairStats {
+    spec: String,
+    impl_name: String,
+    total: usize,
+    covered: usize,
+    tested: usize,
+    files: usize,
+}
+
+impl PairStats {
+    fn of(spec: &str, impl_name: &str, forward: &ApiSpecForward, reverse: &ApiReverseData) -> Self {
+        Self {
+            spec: spec.to_string(),
+            impl_name: impl_name.to_string(),
+            total: forward.rules.len(),
+            covered: forward
+                .rules
+                .iter()
+                .filter(|r| r.is_implemented() && !r.is_partial())
+                .count(),
+            tested: forward
+                .rules
+                .iter()
+                .filter(|r| !r.verify_refs.is_empty())
+                .count(),
+            files: reverse.files.len(),
+        }
+    }
+}
+
+/// The site's landing page: coverage of every spec and implementation, with
+/// links to their spec, coverage, and source pages.
+///
+/// r[impl cli.export.index]
+fn render_index_page(stats: &[PairStats], config: &ApiConfig, include_sources: bool) -> String {
+    let Some(first) = stats.first() else {
+        return "<!DOCTYPE html>\n<html>\n<head><meta charset=\"UTF-8\"><title>Tracey</title></head>\n<body><p>No specs configured.</p></body>\n</html>\n".to_string();
+    };
+    let sources_header = if include_sources {
+        "<th>Sources</th>"
+    } else {
+        ""
+    };
+    let rows = stats
+        .iter()
+        .map(|s| {
+            let base = format!("/{}/{}", s.spec, s.impl_name);
+            let sources = if include_sources {
+                format!(
+                    r#"<td><a href="{base}/sources.html">{} file{}</a></td>"#,
+                    s.files,
+                    if s.files == 1 { "" } else { "s" }
+                )
+            } else {
+                String::new()
+            };
+            format!(
+                r#"<tr>
+  <td><a href="{base}/{SPEC_INDEX}">{} / {}</a></td>
+  <td>{}</td>
+  <td><a class="stat-value {}" href="{base}/coverage.html">{}</a></td>
+  <td><span class="stat-value {}">{}</span></td>
+  {sources}
+</tr>"#,
+                html_escape(&s.spec),
+                html_escape(&s.impl_name),
+                s.total,
+                stat_class(s.covered, s.total),
+                percent(s.covered, s.total),
+                stat_class(s.tested, s.total),
+                percent(s.tested, s.total),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let total: usize = stats.iter().map(|s| s.total).sum();
+    let covered: usize = stats.iter().map(|s| s.covered).sum();
+    let tested: usize = stats.iter().map(|s| s.tested).sum();
+    let summary = format!(
+        r#"<div class="stats-bar">
+  <div class="stat">
+    <span class="stat-label">Rules</span>
+    <span class="stat-value">{total}</span>
+  </div>
+  <div class="stat">
+    <span class="stat-label">Implemented</span>
+    <span class="stat-value {}">{covered}/{total}</span>
+  </div>
+  <div class="stat">
+    <span class="stat-label">Tested</span>
+    <span class="stat-value {}">{tested}/{total}</span>
+  </div>
+</div>"#,
+        stat_class(covered, total),
+        stat_class(tested, total),
+    );
+    let table = format!(
+        r#"<table class="rules-table">
+  <thead><tr>
+    <th>Spec / implementation</th>
+    <th>Rules</th>
+    <th>Implemented</th>
+    <th>Tested</th>
+    {sources_header}
+  </tr></thead>
+  <tbody>{rows}</tbody>
+</table>"#
+    );
+
+    page_shell(
+        "Coverage report",
+        &first.spec,
+        &first.impl_name,
+        "index",
+        config,
+        include_sources,
+        "",
+        &format!(r#"<div class="padded-page">{summary}{table}</div>"#),
+        "",
+    )
+}
+
+/// `part` as a percentage of `total`, rounded down.
+fn percent(part: usize, total: usize) -> String {
+    match (part * 100).checked_div(total) {
+        Some(p) => format!("{p}%"),
+        None => "n/a".to_string(),
+    }
+}
+
 // ============================================================================
 // Coverage page
 // ============================================================================
@@ -444,6 +937,7 @@ fn render_coverage_page(
     spec_name: &str,
     impl_name: &str,
     forward: &ApiSpecForward,
+    pages: &SpecPages,
     config: &ApiConfig,
     include_sources: bool,
 ) -> Result<String> {
@@ -451,8 +945,9 @@ fn render_coverage_page(
     let covered = forward
         .rules
         .iter()
-        .filter(|r| !r.impl_refs.is_empty())
+        .filter(|r| r.is_implemented() && !r.is_partial())
         .count();
+    let partial = forward.rules.iter().filter(|r| r.is_partial()).count();
     let tested = forward
         .rules
         .iter()
@@ -462,23 +957,51 @@ fn render_coverage_page(
     let impl_class = stat_class(covered, total);
     let test_class = stat_class(tested, total);
 
+    // r[impl coverage.proof]
+    let proof_stat = if forward.rules.iter().any(|r| !r.proof_refs.is_empty()) {
+        let proven = forward.rules.iter().filter(|r| r.formally_verified).count();
+        let proof_class = stat_class(proven, total);
+        format!(
+            r#"
+  <div class="stat">
+    <span class="stat-label">Proven</span>
+    <span class="stat-value {proof_class}">{proven}/{total}</span>
+  </div>"#
+        )
+    } else {
+        String::new()
+    };
+
+    // r[impl coverage.partial]
+    let partial_stat = if partial > 0 {
+        format!(
+            r#"
+  <div class="stat">
+    <span class="stat-label">Partial</span>
+    <span class="stat-value warn">{partial}/{total}</span>
+  </div>"#
+        )
+    } else {
+        String::new()
+    };
+
     let stats = format!(
         r#"<div class="stats-bar">
   <div class="stat">
     <span class="stat-label">Implemented</span>
     <span class="stat-value {impl_class}">{covered}/{total}</span>
-  </div>
+  </div>{partial_stat}
   <div class="stat">
     <span class="stat-label">Tested</span>
     <span class="stat-value {test_class}">{tested}/{total}</span>
-  </div>
+  </div>{proof_stat}
 </div>"#
     );
 
     let rows = forward
         .rules
         .iter()
-        .map(|rule| render_rule_row(spec_name, impl_name, rule, include_sources))
+        .map(|rule| render_rule_row(spec_name, impl_name, rule, pages, include_sources))
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -510,19 +1033,33 @@ fn render_coverage_page(
 fn source_ref_html(
     spec_name: &str,
     impl_name: &str,
-    file: &str,
-    line: usize,
+    code_ref: &ApiCodeRef,
     css_class: &str,
     include_sources: bool,
 ) -> String {
+    let ApiCodeRef { file, line, .. } = code_ref;
     let fname = file.rsplit('/').next().unwrap_or(file);
     let label = format!("{fname}:{line}");
+    // r[impl ref.syntax.note]
+    // r[impl ref.coverage.partial]
+    let note = match (code_ref.partial, code_ref.note.as_deref()) {
+        (true, Some(note)) => Some(format!("partial: {note}")),
+        (true, None) => Some("partial".to_string()),
+        (false, note) => note.map(str::to_string),
+    }
+    .map(|note| {
+        format!(
+            r#" <span class="rule-ref-note">{}</span>"#,
+            html_escape(&note)
+        )
+    })
+    .unwrap_or_default();
     if include_sources {
         format!(
-            r#"<a class="rule-ref {css_class}" href="/{spec_name}/{impl_name}/sources/{file}.html#line-{line}">{label}</a>"#
+            r#"<a class="rule-ref {css_class}" href="/{spec_name}/{impl_name}/sources/{file}.html#line-{line}">{label}</a>{note}"#
         )
     } else {
-        format!(r#"<span class="rule-ref {css_class}">{label}</span>"#)
+        format!(r#"<span class="rule-ref {css_class}">{label}</span>{note}"#)
     }
 }
 
@@ -530,45 +1067,39 @@ fn render_rule_row(
     spec_name: &str,
     impl_name: &str,
     rule: &ApiRule,
+    pages: &SpecPages,
     include_sources: bool,
 ) -> String {
     let id = rule.id.to_string();
+    let (page, anchor) = pages.resolve(&format!("r--{id}"));
 
     let impl_refs = rule
         .impl_refs
         .iter()
-        .map(|r| {
-            source_ref_html(
-                spec_name,
-                impl_name,
-                &r.file,
-                r.line,
-                "impl",
-                include_sources,
-            )
-        })
+        .map(|r| source_ref_html(spec_name, impl_name, r, "impl", include_sources))
         .collect::<Vec<_>>()
         .join("<br>");
 
     let verify_refs = rule
         .verify_refs
         .iter()
-        .map(|r| {
-            source_ref_html(
-                spec_name,
-                impl_name,
-                &r.file,
-                r.line,
-                "verify",
-                include_sources,
-            )
-        })
+        .map(|r| source_ref_html(spec_name, impl_name, r, "verify", include_sources))
         .collect::<Vec<_>>()
         .join("<br>");
+    let proven = if rule.formally_verified {
+        r#" <span class="rule-badge proven" title="Formally verified">proven</span>"#
+    } else {
+        ""
+    };
+    let partial = if rule.is_partial() {
+        r#" <span class="rule-partial" title="Implemented only by coverage=partial references">partial</span>"#
+    } else {
+        ""
+    };
 
     format!(
         r#"<tr>
-  <td><div class="rule-id-row"><a class="rule-id" href="/{spec_name}/{impl_name}/spec.html#r--{id}">{id}</a></div></td>
+  <td><div class="rule-id-row"><a class="rule-id" href="/{spec_name}/{impl_name}/{page}#{anchor}">{id}</a>{proven}{partial}</div></td>
   <td class="rule-refs">{impl_refs}</td>
   <td class="rule-refs">{verify_refs}</td>
 </tr>"#
@@ -813,14 +1344,21 @@ fn split_html_lines(html: &str) -> Vec<String> {
 // URL rewriting — SPA hrefs → static file paths
 // ============================================================================
 
-/// Rewrite daemon SPA links to static file paths and strip edit buttons.
+/// Rewrite daemon SPA links to static file paths and strip daemon-only buttons.
 ///
 /// - `href="/{spec}/{impl}/sources/{file}:{line}"`:
 ///   - `include_sources=true`  → `href="...{file}.html#line-{line}"`
 ///   - `include_sources=false` → demote `<a>` to `<span>` (no dead links)
-/// - `href="/{spec}/{impl}/spec#{id}"` → `href="...spec.html#{id}"`
-/// - `<button class="req-edit">` → stripped entirely
-fn rewrite_spec_html(html: &str, include_sources: bool) -> String {
+/// - `href="{pair_base}spec#{id}"` → `href="{pair_base}{page}#{id}"`, the
+///   page of this spec/impl that holds `id`
+/// - `href="/{spec}/{impl}/spec#{id}"` for other pairs → `href="...spec.html#{id}"`
+/// - `<button class="req-edit">` / `<button class="req-snippet">` → stripped entirely
+fn rewrite_spec_html(
+    html: &str,
+    include_sources: bool,
+    pair_base: &str,
+    pages: &SpecPages,
+) -> String {
     let mut output = String::with_capacity(html.len());
     let mut rest = html;
     let mut open_demoted: usize = 0;
@@ -863,6 +1401,15 @@ fn rewrite_spec_html(html: &str, include_sources: bool) -> String {
                         }
                         continue;
                     }
+                    if let Some(fragment) = href
+                        .strip_prefix(pair_base)
+                        .and_then(|rest| rest.strip_prefix("spec#"))
+                    {
+                        let (file, anchor) = pages.resolve(fragment);
+                        let new_href = format!("{pair_base}{file}#{anchor}");
+                        output.push_str(&replace_href_in_tag(full_tag, href, &new_href));
+                        continue;
+                    }
                     if href.contains("/spec#") {
                         let new_href = href.replacen("/spec#", "/spec.html#", 1);
                         output.push_str(&replace_href_in_tag(full_tag, href, &new_href));
@@ -878,11 +1425,11 @@ fn rewrite_spec_html(html: &str, include_sources: bool) -> String {
             continue;
         }
 
-        // <button …> — strip req-edit buttons
+        // <button …> — strip req-edit / req-snippet buttons (they need the daemon API)
         if rest.starts_with("<button") {
             if let Some(tag_end) = find_open_tag_end(rest) {
                 let open_tag = &rest[..tag_end + 1];
-                if open_tag.contains("req-edit") {
+                if open_tag.contains("req-edit") || open_tag.contains("req-snippet") {
                     let after = &rest[tag_end + 1..];
                     if let Some(close_pos) = after.find("</button>") {
                         rest = &after[close_pos + "</button>".len()..];
@@ -997,6 +1544,49 @@ const ENHANCE_JS: &str = r##"(function () {
       navigator.clipboard.writeText(btn.dataset.reqId).catch(function () {});
     }
   });
+
+  // Sectioned spec pages: send anchors that live on another page there,
+  // and search the embedded index from the sidebar.
+  var index = window.TRACEY_SEARCH;
+  if (!index) return;
+
+  function findDoc(hash) {
+    var id = decodeURIComponent(hash);
+    var rule = id.startsWith("r--") ? id.slice(3) : null;
+    return index.documents.find(function (d) {
+      return d.anchor === id || (rule !== null && d.title === rule && d.body !== "");
+    });
+  }
+  function followAnchor() {
+    var hash = location.hash.slice(1);
+    if (!hash || hash.startsWith("line-") || document.getElementById(hash)) return;
+    var doc = findDoc(hash);
+    if (doc) location.replace(doc.page + "#" + doc.anchor);
+  }
+  window.addEventListener("hashchange", followAnchor);
+  document.addEventListener("DOMContentLoaded", followAnchor);
+
+  document.addEventListener("input", function (e) {
+    if (!e.target.classList.contains("spec-search-input")) return;
+    var results = e.target.parentElement.querySelector(".spec-search-results");
+    var terms = e.target.value.toLowerCase().split(/\s+/).filter(Boolean);
+    results.replaceChildren();
+    if (!terms.length) return;
+    index.documents
+      .filter(function (d) {
+        var text = (d.title + " " + d.body).toLowerCase();
+        return terms.every(function (t) { return text.includes(t); });
+      })
+      .slice(0, 20)
+      .forEach(function (d) {
+        var a = document.createElement("a");
+        a.href = d.page + "#" + d.anchor;
+        a.textContent = d.title;
+        var li = document.createElement("li");
+        li.appendChild(a);
+        results.appendChild(li);
+      });
+  });
 })();
 "##;
 
@@ -1014,6 +1604,50 @@ a.spec-tab { text-decoration: none; display: inline-block; }
   max-width: 900px;
 }
 
+/* Previous / next links at the bottom of a sectioned spec page */
+.spec-pager {
+  display: flex;
+  justify-content: space-between;
+  gap: var(--space-4);
+  margin-top: var(--space-8);
+  padding-top: var(--space-4);
+  border-top: 1px solid var(--border);
+}
+.spec-pager a { text-decoration: none; }
+.spec-pager-prev::before { content: "← "; }
+.spec-pager-next { margin-inline-start: auto; }
+.spec-pager-next::after { content: " →"; }
+
+/* Outline entries on the current page */
+.toc-item.is-current-page > .toc-row { background: var(--hover); }
+
+/* Spec search box in the sidebar */
+.spec-search { padding: var(--space-2); }
+.spec-search-input {
+  width: 100%;
+  padding: var(--space-1-5) var(--space-2);
+  border: 1px solid var(--border);
+  border-radius: 4px;
+  background: var(--bg);
+  color: var(--fg);
+  font: inherit;
+  font-size: var(--text-xs);
+}
+.spec-search-results {
+  list-style: none;
+  margin: var(--space-1) 0 0;
+  padding: 0;
+  font-size: var(--text-xs);
+}
+.spec-search-results a {
+  display: block;
+  padding: var(--space-1) var(--space-2);
+  color: var(--fg-muted);
+  text-decoration: none;
+  overflow-wrap: anywhere;
+}
+.spec-search-results a:hover { color: var(--fg); background: var(--hover); }
+
 /* Padded wrapper for coverage / sources-index pages */
 .padded-page {
   padding: var(--space-4) var(--space-6);
@@ -1062,4 +1696,240 @@ a.spec-tab { text-decoration: none; display: inline-block; }
 .cov-bar-fill.high { background: var(--green); }
 .cov-bar-fill.med  { background: var(--yellow); }
 .cov-bar-fill.low  { background: var(--red); }
+
+/* formally verified rules on the coverage page */
+.rule-badge.proven {
+  margin-inline-start: var(--space-2);
+  padding: 0 var(--space-1);
+  border-radius: 3px;
+  font-size: var(--text-2xs);
+  color: var(--green);
+  border: 1px solid var(--green);
+}
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_api::{OutlineCoverage, SpecSection};
+
+    fn heading(level: u8, slug: &str, title: &str) -> OutlineEntry {
+        OutlineEntry {
+            title: title.to_string(),
+            slug: slug.to_string(),
+            level,
+            coverage: OutlineCoverage::default(),
+            aggregated: OutlineCoverage::default(),
+        }
+    }
+
+    fn spec_data() -> ApiSpecData {
+        let html = r#"<p>Intro</p><h1 id="language">Language</h1><h2 id="language--syntax">Syntax</h2><div class="req-container" id="r-syntax.marker"></div><h1 id="tooling">Tooling</h1><p>See <a href="/s/rust/spec#r--syntax.marker">marker</a>.</p>"#;
+        ApiSpecData {
+            name: "s".to_string(),
+            sections: vec![SpecSection {
+                source_file: "spec.md".to_string(),
+                html: html.to_string(),
+                weight: 0,
+            }],
+            outline: vec![
+                heading(1, "language", "Language"),
+                heading(2, "language--syntax", "Syntax"),
+                heading(1, "tooling", "Tooling"),
+            ],
+            head_injections: vec![],
+        }
+    }
+
+    // r[verify cli.export.pages]
+    #[test]
+    fn splits_spec_at_top_level_headings() {
+        let pages = split_spec_pages("s", "r", &spec_data());
+        let files: Vec<_> = pages.pages.iter().map(|p| p.file.as_str()).collect();
+        assert_eq!(files, ["spec.html", "spec-tooling.html"]);
+        assert!(pages.pages[0].html.starts_with("<p>Intro</p>"));
+        assert_eq!(pages.pages[0].title, "Language");
+        assert_eq!(pages.pages[0].outline, 0..2);
+        assert_eq!(pages.pages[1].outline, 2..3);
+        assert_eq!(
+            pages.resolve("r--syntax.marker"),
+            ("spec.html", "r-syntax.marker".to_string())
+        );
+        assert_eq!(
+            pages.resolve("tooling"),
+            ("spec-tooling.html", "tooling".to_string())
+        );
+
+        let rewritten = rewrite_spec_html(&pages.pages[1].html, false, "/s/rust/", &pages);
+        assert!(rewritten.contains(r#"href="/s/rust/spec.html#r-syntax.marker""#));
+    }
+
+    // r[verify cli.export.pager]
+    #[test]
+    fn spec_pages_link_to_neighbours() {
+        let data = spec_data();
+        let pages = split_spec_pages("s", "r", &data);
+        let config = ApiConfig {
+            project_root: "/tmp".to_string(),
+            specs: vec![],
+            verbs: vec![],
+        };
+        let first = render_spec_page("s", "rust", &data, &pages, 0, &config, false).unwrap();
+        assert!(first.contains(
+            r#"<a class="spec-pager-next" rel="next" href="spec-tooling.html">Tooling</a>"#
+        ));
+        assert!(!first.contains("spec-pager-prev"));
+        assert!(first.contains(r#"<script src="search-index.js"></script>"#));
+        let second = render_spec_page("s", "rust", &data, &pages, 1, &config, false).unwrap();
+        assert!(
+            second
+                .contains(r#"<a class="spec-pager-prev" rel="prev" href="spec.html">Language</a>"#)
+        );
+        assert!(second.contains(r##"href="spec.html#language--syntax""##));
+    }
+
+    // r[verify cli.export.search]
+    #[test]
+    fn search_index_lists_headings_with_their_page() {
+        let data = spec_data();
+        let pages = split_spec_pages("s", "r", &data);
+        let forward = ApiSpecForward {
+            name: "s".to_string(),
+            rules: vec![],
+        };
+        let js = render_search_index(&data, &forward, &pages).unwrap();
+        assert!(js.starts_with(
+            r#"window.TRACEY_SEARCH = {"ref":"anchor","fields":["title","body"],"documents":["#
+        ));
+        assert!(js.contains(
+            r#"{"anchor":"tooling","page":"spec-tooling.html","title":"Tooling","body":""}"#
+        ));
+    }
+
+    // r[verify cli.export.index]
+    #[test]
+    fn index_page_sums_coverage_and_links_each_pair() {
+        let config = ApiConfig {
+            project_root: "/tmp".to_string(),
+            specs: vec![],
+            verbs: vec![],
+        };
+        let stats = [
+            PairStats {
+                spec: "s".to_string(),
+                impl_name: "rust".to_string(),
+                total: 4,
+                covered: 4,
+                tested: 1,
+                files: 1,
+            },
+            PairStats {
+                spec: "s".to_string(),
+                impl_name: "ts".to_string(),
+                total: 4,
+                covered: 2,
+                tested: 0,
+                files: 3,
+            },
+        ];
+        let html = render_index_page(&stats, &config, true);
+        assert!(html.contains(r#"<span class="stat-value warn">6/8</span>"#));
+        assert!(html.contains(r#"<a href="/s/rust/spec.html">s / rust</a>"#));
+        assert!(
+            html.contains(r#"<a class="stat-value good" href="/s/rust/coverage.html">100%</a>"#)
+        );
+        assert!(html.contains(r#"<span class="stat-value bad">25%</span>"#));
+        assert!(html.contains(r#"<a href="/s/rust/sources.html">1 file</a>"#));
+        assert!(html.contains(r#"<a href="/s/ts/sources.html">3 files</a>"#));
+        assert!(!render_index_page(&stats, &config, false).contains("sources.html"));
+        assert!(render_index_page(&[], &config, false).contains("No specs configured."));
+    }
+
+    // r[verify cli.report.site]
+    #[tokio::test]
+    async fn report_site_is_built_without_a_daemon() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("project");
+        let template = crate::templates::find("protocol-spec").unwrap();
+        crate::templates::write(template, &root).unwrap();
+        let out = tmp.path().join("site");
+        run_local(
+            root.clone(),
+            root.join(".config/tracey/config.styx"),
+            out.clone(),
+        )
+        .await
+        .unwrap();
+
+        let index = std::fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains(r#"<a href="/framing/rust/spec.html">framing / rust</a>"#));
+        for page in [
+            "framing/rust/spec.html",
+            "framing/rust/coverage.html",
+            "framing/rust/sources.html",
+            "framing/rust/sources/src/lib.rs.html",
+            "framing/rust/sources/tests/framing.rs.html",
+        ] {
+            assert!(out.join(page).is_file(), "{page} is missing");
+        }
+        let coverage = std::fs::read_to_string(out.join("framing/rust/coverage.html")).unwrap();
+        assert!(coverage.contains(r#"href="/framing/rust/sources/src/lib.rs.html#line-"#));
+    }
+
+    // r[verify cli.export.redirects]
+    #[test]
+    fn rule_redirects_point_at_the_rule_page() {
+        let data = spec_data();
+        let pages = split_spec_pages("s", "r", &data);
+        let rule = |id: &str| ApiRule {
+            id: tracey_core::parse_rule_id(id).unwrap(),
+            raw: String::new(),
+            html: String::new(),
+            status: None,
+            level: None,
+            tags: vec![],
+            owner: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            section: None,
+            section_title: None,
+            section_path: Vec::new(),
+            impl_refs: vec![],
+            verify_refs: vec![],
+            depends_refs: vec![],
+            docs_refs: vec![],
+            is_stale: false,
+            stale_refs: vec![],
+            related: vec![],
+            fanout: None,
+            examples: Vec::new(),
+            waiver: None,
+            proof_refs: Vec::new(),
+            formally_verified: false,
+            partial: None,
+        };
+        let forward = ApiSpecForward {
+            name: "s".to_string(),
+            rules: vec![rule("syntax.marker"), rule("taken.elsewhere")],
+        };
+        let out = tempfile::tempdir().unwrap();
+        let mut redirected = HashSet::from(["taken.elsewhere".to_string()]);
+        write_rule_redirects(out.path(), "s", "rust", &forward, &pages, &mut redirected).unwrap();
+
+        let html = std::fs::read_to_string(out.path().join("r/syntax.marker/index.html")).unwrap();
+        assert!(html.contains(r#"content="0; url=/s/rust/spec.html#r-syntax.marker""#));
+        assert!(redirected.contains("syntax.marker"));
+        assert!(!out.path().join("r/taken.elsewhere").exists());
+    }
+
+    #[test]
+    fn spec_without_headings_is_one_page() {
+        let mut data = spec_data();
+        data.outline.clear();
+        let pages = split_spec_pages("s", "r", &data);
+        assert_eq!(pages.pages.len(), 1);
+        assert_eq!(pages.pages[0].title, "s");
+        assert_eq!(pages.pages[0].html, data.sections[0].html);
+    }
+}
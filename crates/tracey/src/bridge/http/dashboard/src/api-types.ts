@@ -59,7 +59,7 @@ export interface RuleId {
 /**
  * Error codes for validation errors
  */
-export type ValidationErrorCode = "circular_dependency" | "invalid_naming" | "unknown_requirement" | "stale_requirement" | "duplicate_requirement" | "unknown_prefix" | "impl_in_test_file" | "include_unparseable_file";
+export type ValidationErrorCode = "circular_dependency" | "invalid_naming" | "unknown_requirement" | "stale_requirement" | "duplicate_requirement" | "unknown_prefix" | "impl_in_test_file" | "include_unparseable_file" | "tag_threshold" | "coverage_threshold" | "skipped_file" | "ownership_conflict" | "empty_wildcard";
 
 /**
  * Validation results for a spec/implementation pair
@@ -144,6 +144,10 @@ export interface OutlineCoverage {
    * Number of rules with verification refs
    */
   verifyCount: number;
+  /**
+   * Number of rules with refs whose verb has the `docs` role
+   */
+  docsCount?: number;
   /**
    * Total number of rules
    */
@@ -228,6 +232,33 @@ export interface ApiReverseData {
 export interface ApiCodeRef {
   file: string;
   line: number;
+  /**
+   * Last line of the region a `begin` marker claims
+   */
+  endLine?: number;
+  /**
+   * Note trailing the rule ID in the reference, saying how the rule
+   * is satisfied
+   */
+  note?: string;
+  /**
+   * The reference is marked `coverage=partial`
+   */
+  partial?: boolean;
+}
+
+/**
+ * An example or counter-example of a rule
+ */
+export interface ApiRuleExample {
+  /**
+   * `good` or `bad`
+   */
+  kind: string;
+  /**
+   * Markdown of the example, without its marker
+   */
+  markdown: string;
 }
 
 export interface ApiRule {
@@ -242,6 +273,14 @@ export interface ApiRule {
   html: string;
   status?: string;
   level?: string;
+  /**
+   * Tags from the rule's metadata
+   */
+  tags?: string[];
+  /**
+   * Team or person owning the rule
+   */
+  owner?: string;
   sourceFile?: string;
   sourceLine?: number;
   sourceColumn?: number;
@@ -253,9 +292,25 @@ export interface ApiRule {
    * Section title (heading text) that this rule belongs to
    */
   sectionTitle?: string;
+  /**
+   * Titles of the headings enclosing this rule, outermost first
+   */
+  sectionPath?: string[];
   implRefs: ApiCodeRef[];
   verifyRefs: ApiCodeRef[];
   dependsRefs: ApiCodeRef[];
+  /**
+   * References whose verb counts toward documentation coverage
+   */
+  docsRefs?: ApiCodeRef[];
+  /**
+   * Verify references in proof files (also listed in `verify_refs`)
+   */
+  proofRefs?: ApiCodeRef[];
+  /**
+   * True if a proof of this rule was checked by a verification tool
+   */
+  formallyVerified?: boolean;
   /**
    * True if any reference to this rule is stale (points to an older version).
    * A stale rule is not counted as covered.
@@ -265,6 +320,65 @@ export interface ApiRule {
    * Stale references pointing to older versions of this rule.
    */
   staleRefs?: ApiStaleRef[];
+  /**
+   * Other rules of the same spec mentioned in this rule's prose
+   * (implicit `related` edges).
+   */
+  related?: RuleId[];
+  /**
+   * Required implementation sites, for rules configured with a fan-out.
+   */
+  fanout?: ApiFanout;
+  /**
+   * `e[...]` examples and counter-examples written next to the rule
+   */
+  examples?: ApiRuleExample[];
+  /**
+   * Accepted coverage gap from the project's waivers file
+   */
+  waiver?: ApiWaiver;
+  /**
+   * Weight the rule counts toward implementation coverage when all its
+   * impl references are marked `coverage=partial`, from 0 to 1.
+   */
+  partial?: number;
+}
+
+/**
+ * A documented exception to coverage checks for one rule
+ */
+export interface ApiWaiver {
+  reason: string;
+  /**
+   * Last day the waiver applies (`YYYY-MM-DD`)
+   */
+  expires?: string;
+  /**
+   * The expiry date has passed, so the waiver no longer applies
+   */
+  expired: boolean;
+}
+
+/**
+ * Fan-out requirement of a rule and how much of it is met.
+ */
+export interface ApiFanout {
+  /**
+   * Number of implementation sites required.
+   */
+  required: number;
+  /**
+   * Number of required sites that are annotated.
+   */
+  annotated: number;
+  /**
+   * Glob for `per-file` fan-outs; `None` for a plain count.
+   */
+  perFile?: string;
+  /**
+   * Matching files without an impl reference (`per-file` only).
+   */
+  missingFiles?: string[];
 }
 
 /**
@@ -310,6 +424,10 @@ export interface ApiSpecInfo {
    * Available implementations for this spec
    */
   implementations: string[];
+  /**
+   * Coverage of this spec never counts toward thresholds
+   */
+  advisory?: boolean;
 }
 
 /**
@@ -318,6 +436,21 @@ export interface ApiSpecInfo {
 export interface ApiConfig {
   projectRoot: string;
   specs: ApiSpecInfo[];
+  /**
+   * Every reference verb and the coverage role it has, sorted by name
+   */
+  verbs?: ApiVerb[];
+}
+
+/**
+ * A reference verb and how it contributes to coverage
+ */
+export interface ApiVerb {
+  name: string;
+  /**
+   * One of `impl`, `verify`, `depends`, `docs` or `informational`
+   */
+  role: string;
 }
 
 /**
@@ -2,10 +2,12 @@
 import { useCallback, useEffect, useState } from "preact/hooks";
 import type {
   ApiData,
+  CompareData,
   Config,
   FileContent,
   ForwardData,
   HealthData,
+  RefsInData,
   ReverseData,
   SpecContent,
 } from "./types";
@@ -260,3 +262,49 @@ export function useSpec(name: string | null, version: string | null): SpecConten
 
   return spec;
 }
+
+export function useCompare(
+  spec: string,
+  impl: string,
+  others: string[],
+  version: string | null,
+): CompareData | null {
+  const [compare, setCompare] = useState<CompareData | null>(null);
+  const impls = [impl, ...others].join(",");
+
+  useEffect(() => {
+    if (others.length === 0) {
+      setCompare(null);
+      return;
+    }
+    const params = new URLSearchParams({ spec, impls });
+    fetchJson<CompareData>(`/api/compare?${params.toString()}`)
+      .then(setCompare)
+      .catch((e) => {
+        console.error("Failed to load comparison:", e);
+        setCompare(null);
+      });
+  }, [spec, impls, version]);
+
+  return compare;
+}
+
+export function useRefsIn(path: string | null): RefsInData | null {
+  const [refs, setRefs] = useState<RefsInData | null>(null);
+
+  useEffect(() => {
+    if (path === null) {
+      setRefs(null);
+      return;
+    }
+    const params = new URLSearchParams({ path });
+    fetchJson<RefsInData>(`/api/refs-in?${params.toString()}`)
+      .then(setRefs)
+      .catch((e) => {
+        console.error("Failed to load references:", e);
+        setRefs(null);
+      });
+  }, [path]);
+
+  return refs;
+}
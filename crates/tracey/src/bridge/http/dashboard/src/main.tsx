@@ -115,6 +115,8 @@ function SearchResultItem({ result, isSelected, onSelect, onHover }: SearchResul
           : html`
               <${LucideIcon} name="file-text" className="search-result-icon rule" />
               <span class="search-modal-result-id">${result.id}</span>
+              ${result.section &&
+              html`<span class="search-modal-result-section">${result.section}</span>`}
             `}
       </div>
       ${result.kind === "source"
@@ -358,6 +360,12 @@ function Header({
             title="View spec source"
             ><${LucideIcon} name="external-link"
           /></a>`}
+          ${currentSpecInfo?.advisory &&
+          html`<span
+            class="spec-advisory-badge"
+            title="Advisory spec: references are validated, but coverage never counts toward thresholds"
+            >advisory</span
+          >`}
         </div>
 
         ${/* r[impl dashboard.header.nav-tabs] */ null}
@@ -552,7 +560,7 @@ function FilePath({
 }
 
 // File reference component
-function FileRef({ file, line, type, onSelectFile }: FileRefProps) {
+function FileRef({ file, line, type, note, onSelectFile }: FileRefProps) {
   return html`
     <div class="ref-line">
       <${FilePath}
@@ -561,6 +569,7 @@ function FileRef({ file, line, type, onSelectFile }: FileRefProps) {
         type=${type}
         onClick=${() => onSelectFile(file, line)}
       />
+      ${note && html`<span class="rule-ref-note">${note}</span>`}
     </div>
   `;
 }
@@ -1005,7 +1014,7 @@ function SourcesViewRoute() {
 function CoverageViewRoute() {
   const { params, query } = useRoute();
   const { route } = useLocation();
-  const { data } = useApiContext();
+  const { data, version } = useApiContext();
 
   if (!data) return html`<div class="loading">Loading...</div>`;
 
@@ -1014,21 +1023,29 @@ function CoverageViewRoute() {
   const impl = params.impl;
   const filter = query.filter || null;
   const level = query.level || "all";
+  const compare = query.compare || null;
 
   const [search, setSearch] = useState("");
 
   const handleLevelChange = useCallback(
     (newLevel: string) => {
-      route(buildUrl(spec, impl, "coverage", { filter, level: newLevel }));
+      route(buildUrl(spec, impl, "coverage", { filter, level: newLevel, compare }));
     },
-    [route, spec, impl, filter],
+    [route, spec, impl, filter, compare],
   );
 
   const handleFilterChange = useCallback(
     (newFilter: string | null) => {
-      route(buildUrl(spec, impl, "coverage", { filter: newFilter, level }));
+      route(buildUrl(spec, impl, "coverage", { filter: newFilter, level, compare }));
+    },
+    [route, spec, impl, level, compare],
+  );
+
+  const handleCompareChange = useCallback(
+    (newCompare: string | null) => {
+      route(buildUrl(spec, impl, "coverage", { filter, level, compare: newCompare }));
     },
-    [route, spec, impl, level],
+    [route, spec, impl, filter, level],
   );
 
   const handleSelectRule = useCallback(
@@ -1049,6 +1066,11 @@ function CoverageViewRoute() {
     <${CoverageView}
       data=${forward}
       config=${config}
+      spec=${spec}
+      impl=${impl}
+      compare=${compare}
+      version=${version}
+      onCompareChange=${handleCompareChange}
       search=${search}
       onSearchChange=${setSearch}
       level=${level}
@@ -8,6 +8,7 @@
 //   /rapace/rust/sources/src/lib.rs:42    -> sources view, file + line
 //   /rapace/rust/coverage                 -> coverage view
 //   /rapace/rust/coverage?filter=impl     -> coverage view with filter
+//   /rapace/rust/coverage?compare=swift,go   -> rust, swift and go side by side
 
 export { LocationProvider, Route, Router, useLocation, useRoute } from "preact-iso";
 
@@ -21,6 +22,7 @@ export interface UrlParams {
   heading?: string | null;
   filter?: string | null;
   level?: string | null;
+  compare?: string | null;
 }
 
 export function buildUrl(
@@ -62,6 +64,7 @@ export function buildUrl(
   const searchParams = new URLSearchParams();
   if (params.filter) searchParams.set("filter", params.filter);
   if (params.level && params.level !== "all") searchParams.set("level", params.level);
+  if (params.compare) searchParams.set("compare", params.compare);
   const query = searchParams.toString();
   return `${base}/coverage${query ? `?${query}` : ""}`;
 }
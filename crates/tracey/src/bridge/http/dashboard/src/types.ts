@@ -8,6 +8,7 @@ import type {
   ApiSpecData,
   OutlineCoverage,
   OutlineEntry,
+  RuleId,
   SpecSection,
 } from "./api-types";
 import type { ComponentChildren } from "preact";
@@ -33,6 +34,58 @@ export interface HealthData {
   uptimeSecs: number;
 }
 
+// Annotation snippet from /api/snippet
+export interface AnnotationSnippet {
+  ruleId: string;
+  spec: string;
+  snippet: string;
+}
+
+// Implementation comparison from /api/compare
+export interface CompareCoverage {
+  implCount: number;
+  verifyCount: number;
+  isStale: boolean;
+}
+
+export interface CompareRule {
+  id: RuleId;
+  // One entry per implementation, in the order of `impls`
+  coverage: CompareCoverage[];
+}
+
+export interface CompareGap {
+  implName: string;
+  // Rules another compared implementation covers but this one doesn't
+  missing: RuleId[];
+}
+
+export interface CompareData {
+  spec: string;
+  impls: string[];
+  rules: CompareRule[];
+  gaps: CompareGap[];
+}
+
+// Annotations under a directory from /api/refs-in
+export interface RefsInVerb {
+  verb: string;
+  locations: FileRef[];
+}
+
+export interface RefsInRule {
+  prefix: string;
+  id: RuleId;
+  verbs: RefsInVerb[];
+}
+
+export interface RefsInData {
+  path: string;
+  files: number;
+  total: number;
+  rules: RefsInRule[];
+}
+
 // Route types
 export type ViewType = "sources" | "spec" | "coverage";
 
@@ -59,6 +112,7 @@ export interface CoverageRoute {
   impl: string | null;
   filter: string | null;
   level: string | null;
+  compare: string | null;
 }
 
 export type Route = SourcesRoute | SpecRoute | CoverageRoute;
@@ -107,6 +161,8 @@ export interface SearchResult {
   content: string;
   highlighted: string;
   score: number;
+  /** For rules: the heading chain it sits under ("Transport > Flow Control") */
+  section?: string;
 }
 
 export interface SearchResults {
@@ -210,6 +266,11 @@ export interface LucideIconProps {
 export interface CoverageViewProps {
   data: ForwardData;
   config: Config;
+  spec: string;
+  impl: string;
+  compare: string | null;
+  version: string | null;
+  onCompareChange: (compare: string | null) => void;
   search: string;
   onSearchChange: (search: string) => void;
   level: string;
@@ -259,6 +320,7 @@ export interface FileRefProps {
   file: string;
   line: number;
   type: "impl" | "verify";
+  note?: string;
   onSelectFile: (path: string, line?: number | null) => void;
 }
 
@@ -1,5 +1,5 @@
 // Utility functions
-import type { RuleId } from "./api-types";
+import type { ApiRule, RuleId } from "./api-types";
 import type { FileInfo, TreeNodeWithCoverage } from "./types";
 
 export function ruleIdToString(ruleId: RuleId): string {
@@ -62,6 +62,20 @@ export function buildFileTree(files: FileInfo[]): TreeNodeWithCoverage {
 	return root;
 }
 
+// A rule counts as implemented once it has every impl reference it needs,
+// including any configured fan-out.
+export function isImplemented(rule: ApiRule): boolean {
+	if (rule.implRefs.length === 0) return false;
+	return !rule.fanout || rule.fanout.annotated >= rule.fanout.required;
+}
+
+// How much a rule counts toward implementation coverage: partially
+// implemented rules count at their configured weight.
+export function implWeight(rule: ApiRule): number {
+	if (!isImplemented(rule)) return 0;
+	return rule.partial ?? 1;
+}
+
 export function getCoverageBadge(
 	covered: number,
 	total: number,
@@ -1,8 +1,72 @@
 import { useCallback, useEffect, useMemo, useState } from "preact/hooks";
 import { LEVELS } from "../config";
+import { useCompare } from "../hooks";
 import { FileRef, html } from "../main";
-import type { CoverageViewProps } from "../types";
-import { getStatClass, ruleIdToString } from "../utils";
+import type { CompareCoverage, CompareData, CoverageViewProps } from "../types";
+import { getStatClass, implWeight, isImplemented, ruleIdToString } from "../utils";
+
+function compareCell(coverage: CompareCoverage) {
+	if (coverage.isStale) return html`<span class="compare-cell stale">stale</span>`;
+	const impl = coverage.implCount > 0;
+	const verify = coverage.verifyCount > 0;
+	if (!impl && !verify) return html`<span class="compare-cell missing">—</span>`;
+	return html`<span class="compare-cell ${impl ? "impl" : "verify"}">
+		${[impl && "impl", verify && "verify"].filter(Boolean).join(" + ")}
+	</span>`;
+}
+
+// r[impl dashboard.coverage.compare]
+function CompareTable({
+	comparison,
+	search,
+	onSelectRule,
+}: {
+	comparison: CompareData;
+	search: string;
+	onSelectRule: (ruleId: string) => void;
+}) {
+	const q = search.toLowerCase();
+	const rules = q
+		? comparison.rules.filter((r) =>
+				ruleIdToString(r.id).toLowerCase().includes(q),
+			)
+		: comparison.rules;
+	const columnWidth = `${50 / comparison.impls.length}%`;
+
+	return html`
+		<div class="compare-summary">
+			${comparison.gaps.map(
+				(gap) =>
+					html`<span key=${gap.implName}>Missing from <strong>${gap.implName}</strong>: ${gap.missing.length}</span>`,
+			)}
+		</div>
+		<table class="rules-table">
+			<thead>
+				<tr>
+					<th style="width: 50%">Rule</th>
+					${comparison.impls.map(
+						(name) => html`<th key=${name} style="width: ${columnWidth}">${name}</th>`,
+					)}
+				</tr>
+			</thead>
+			<tbody>
+				${rules.map((rule) => {
+					const ruleId = ruleIdToString(rule.id);
+					return html`
+						<tr
+							key=${ruleId}
+							onClick=${() => onSelectRule(ruleId)}
+							style="cursor: pointer;"
+						>
+							<td><span class="rule-id">${ruleId}</span></td>
+							${rule.coverage.map((coverage) => html`<td>${compareCell(coverage)}</td>`)}
+						</tr>
+					`;
+				})}
+			</tbody>
+		</table>
+	`;
+}
 
 // r[impl dashboard.coverage.table]
 // r[impl dashboard.coverage.filter-type]
@@ -12,6 +76,12 @@ import { getStatClass, ruleIdToString } from "../utils";
 // r[impl dashboard.coverage.ref-links]
 export function CoverageView({
 	data,
+	config,
+	spec,
+	impl,
+	compare,
+	version,
+	onCompareChange,
 	search,
 	level,
 	onLevelChange,
@@ -21,6 +91,16 @@ export function CoverageView({
 	onSelectFile,
 }: CoverageViewProps) {
 	const [levelOpen, setLevelOpen] = useState(false);
+	const compared = useMemo(
+		() => (compare ? compare.split(",").filter(Boolean) : []),
+		[compare],
+	);
+	const otherImpls = (
+		config.specs?.find((s) => s.name === spec)?.implementations ?? []
+	).filter((i) => i !== impl && !compared.includes(i));
+	const comparison = useCompare(spec, impl, compared, version);
+	const setCompared = (impls: string[]) =>
+		onCompareChange(impls.length > 0 ? impls.join(",") : null);
 
 	// Close dropdowns when clicking outside
 	useEffect(() => {
@@ -65,7 +145,7 @@ export function CoverageView({
 
 		// Filter by coverage
 		if (filter === "impl") {
-			rules = rules.filter((r) => r.implRefs.length === 0);
+			rules = rules.filter((r) => !isImplemented(r));
 		} else if (filter === "verify") {
 			rules = rules.filter((r) => r.verifyRefs.length === 0);
 		}
@@ -89,13 +169,16 @@ export function CoverageView({
 			rules = rules.filter((r) => inferLevel(r) === level);
 		}
 		const total = rules.length;
-		const impl = rules.filter((r) => r.implRefs.length > 0).length;
+		const impl = rules.filter(isImplemented).length;
+		const partial = rules.filter((r) => isImplemented(r) && r.partial != null).length;
+		const implWeighted = rules.reduce((sum, r) => sum + implWeight(r), 0);
 		const verify = rules.filter((r) => r.verifyRefs.length > 0).length;
 		return {
 			total,
 			impl,
+			partial,
 			verify,
-			implPct: total ? (impl / total) * 100 : 0,
+			implPct: total ? (implWeighted / total) * 100 : 0,
 			verifyPct: total ? (verify / total) * 100 : 0,
 		};
 	}, [allRules, level, inferLevel]);
@@ -125,6 +208,13 @@ export function CoverageView({
         <span class="stat-label">Impl Coverage ${filter === "impl" ? "(filtered)" : ""}</span>
         <span class="stat-value ${getStatClass(stats.implPct)}">${stats.implPct.toFixed(1)}%</span>
       </div>
+      ${
+				stats.partial > 0 &&
+				html`<div class="stat">
+          <span class="stat-label">Partial</span>
+          <span class="stat-value warn">${stats.partial}</span>
+        </div>`
+			}
       <div
         class="stat clickable"
         onClick=${() => onFilterChange(filter === "verify" ? null : "verify")}
@@ -175,11 +265,47 @@ export function CoverageView({
 					)}
         </div>
       </div>
+
+      <div class="compare-controls">
+      ${compared.map(
+				(i) => html`<button
+          key=${i}
+          class="compare-chip"
+          title="Stop comparing with ${i}"
+          onClick=${() => setCompared(compared.filter((c) => c !== i))}
+        >
+          ${i} ×
+        </button>`,
+			)}
+      ${
+				otherImpls.length > 0 &&
+				html`<select
+          class="compare-select"
+          value=""
+          onChange=${(e: Event) => {
+						const added = (e.target as HTMLSelectElement).value;
+						if (added) setCompared([...compared, added]);
+					}}
+        >
+          <option value="">${compared.length > 0 ? "Add to comparison…" : "Compare with…"}</option>
+          ${otherImpls.map((i) => html`<option key=${i} value=${i}>${i}</option>`)}
+        </select>`
+			}
+      </div>
     </div>
     <div class="main">
       <div class="content">
         <div class="content-body">
-          <table class="rules-table">
+          ${
+						compared.length > 0
+							? comparison
+								? html`<${CompareTable}
+                    comparison=${comparison}
+                    search=${search}
+                    onSelectRule=${onSelectRule}
+                  />`
+								: html`<div class="loading">Loading comparison...</div>`
+							: html`<table class="rules-table">
             <thead>
               <tr>
                 <th style="width: 45%">Rule</th>
@@ -200,6 +326,24 @@ export function CoverageView({
                       <div class="rule-id-row">
                         ${mdIcon}
                         <span class="rule-id">${ruleId}</span>
+                        ${
+													rule.partial != null &&
+													isImplemented(rule) &&
+													html`<span class="rule-partial" title="Implemented only by coverage=partial references">partial</span>`
+												}
+                        ${
+													rule.fanout &&
+													html`<span
+                          class="rule-fanout ${rule.fanout.annotated >= rule.fanout.required ? "met" : "short"}"
+                          title=${
+														rule.fanout.missingFiles?.length
+															? `Missing: ${rule.fanout.missingFiles.join(", ")}`
+															: ""
+													}
+                        >
+                          ${rule.fanout.annotated}/${rule.fanout.required}
+                        </span>`
+												}
                       </div>
                       ${
 												rule.html &&
@@ -220,6 +364,7 @@ export function CoverageView({
                                   file=${r.file}
                                   line=${r.line}
                                   type="impl"
+                                  note=${r.partial ? ["partial", r.note].filter(Boolean).join(": ") : r.note}
                                   onSelectFile=${onSelectFile}
                                 />
                               `,
@@ -231,6 +376,7 @@ export function CoverageView({
                                   file=${r.file}
                                   line=${r.line}
                                   type="verify"
+                                  note=${r.note}
                                   onSelectFile=${onSelectFile}
                                 />
                               `,
@@ -244,7 +390,8 @@ export function CoverageView({
 									},
 							)}
             </tbody>
-          </table>
+          </table>`
+					}
         </div>
       </div>
     </div>
@@ -1,8 +1,8 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "preact/hooks";
 import { EDITORS, SIDEBAR_COLLAPSED_STORAGE_KEY } from "../config";
-import { useFile } from "../hooks";
+import { useFile, useRefsIn } from "../hooks";
 import { FilePath, html, LangIcon } from "../main";
-import type { FileContent, SourcesViewProps, TreeNodeWithCoverage } from "../types";
+import type { FileContent, RefsInData, SourcesViewProps, TreeNodeWithCoverage } from "../types";
 import {
   buildFileTree,
   getCoverageBadge,
@@ -22,6 +22,8 @@ interface FileTreeProps {
   node: TreeNodeWithCoverage;
   selectedFile: string | null;
   onSelectFile: (path: string, line?: number | null, context?: string | null) => void;
+  selectedDir?: string | null;
+  onSelectDir?: (path: string) => void;
   depth?: number;
   search?: string;
   parentPath?: string;
@@ -31,6 +33,8 @@ function FileTree({
   node,
   selectedFile,
   onSelectFile,
+  selectedDir = null,
+  onSelectDir,
   depth = 0,
   search = "",
   parentPath = "",
@@ -69,6 +73,8 @@ function FileTree({
               node=${f}
               selectedFile=${selectedFile}
               onSelectFile=${onSelectFile}
+              selectedDir=${selectedDir}
+              onSelectDir=${onSelectDir}
               depth=${depth + 1}
               search=${search}
               parentPath=""
@@ -102,7 +108,7 @@ function FileTree({
   const folderBadge = getCoverageBadge(node.coveredUnits, node.totalUnits);
 
   return html`
-    <div class="tree-folder ${open ? "open" : ""}">
+    <div class="tree-folder ${open ? "open" : ""} ${selectedDir === currentPath ? "selected" : ""}">
       <div class="tree-folder-header" onClick=${() => setOpen(!open)}>
         <div class="tree-folder-left">
           <svg
@@ -116,7 +122,23 @@ function FileTree({
           </svg>
           <span>${node.name}</span>
         </div>
-        <span class="folder-badge ${folderBadge.class}">${folderBadge.text}</span>
+        <div class="tree-folder-right">
+          ${onSelectDir &&
+          html`
+            <button
+              class="tree-folder-refs"
+              type="button"
+              title="List annotations in ${currentPath}"
+              onClick=${(e: Event) => {
+                e.stopPropagation();
+                onSelectDir(currentPath);
+              }}
+            >
+              refs
+            </button>
+          `}
+          <span class="folder-badge ${folderBadge.class}">${folderBadge.text}</span>
+        </div>
       </div>
       <div class="tree-folder-children">
         ${folders.map(
@@ -126,6 +148,8 @@ function FileTree({
               node=${f}
               selectedFile=${selectedFile}
               onSelectFile=${onSelectFile}
+              selectedDir=${selectedDir}
+              onSelectDir=${onSelectDir}
               depth=${depth + 1}
               search=${search}
               parentPath=${currentPath}
@@ -167,6 +191,56 @@ function FileTreeFile({ file, selected, onClick }: FileTreeFileProps) {
   `;
 }
 
+// r[impl dashboard.sources.dir-refs]
+// Annotations under a directory, grouped by rule and verb
+interface DirRefsProps {
+  refs: RefsInData;
+  onSelectFile: (path: string, line?: number | null, context?: string | null) => void;
+  onSelectRule: (ruleId: string) => void;
+}
+
+function DirRefs({ refs, onSelectFile, onSelectRule }: DirRefsProps) {
+  if (refs.rules.length === 0) {
+    return html`<div class="empty-state">No annotations under ${refs.path}</div>`;
+  }
+
+  return html`
+    <div class="dir-refs">
+      ${refs.rules.map((rule) => {
+        const id = ruleIdToString(rule.id);
+        return html`
+          <div key=${`${rule.prefix}:${id}`} class="dir-refs-rule">
+            <div class="dir-refs-rule-header">
+              <a class="dir-refs-rule-id" onClick=${() => onSelectRule(id)}>${rule.prefix}[${id}]</a>
+              <span class="dir-refs-counts">
+                ${rule.verbs.map((v) => `${v.verb} ${v.locations.length}`).join(", ")}
+              </span>
+            </div>
+            ${rule.verbs.map((v) =>
+              v.locations.map(
+                (loc) => html`
+                  <div
+                    key=${`${v.verb}:${loc.file}:${loc.line}`}
+                    class="dir-refs-location"
+                    onClick=${() => onSelectFile(loc.file, loc.line, id)}
+                  >
+                    <span class="dir-refs-verb">${v.verb}</span>
+                    <${FilePath}
+                      file=${loc.file}
+                      line=${loc.line}
+                      type=${v.verb === "verify" ? "verify" : "impl"}
+                    />
+                  </div>
+                `,
+              ),
+            )}
+          </div>
+        `;
+      })}
+    </div>
+  `;
+}
+
 // r[impl dashboard.sources.code-view]
 // r[impl dashboard.sources.line-numbers]
 // r[impl dashboard.sources.line-annotations]
@@ -315,6 +389,16 @@ export function SourcesView({
 
   const fileTree = useMemo(() => buildFileTree(data.files), [data.files]);
   const file = useFile(selectedFile);
+  const [selectedDir, setSelectedDir] = useState<string | null>(null);
+  const dirRefs = useRefsIn(selectedDir);
+
+  const selectFile = useCallback(
+    (path: string, line?: number | null, context?: string | null) => {
+      setSelectedDir(null);
+      onSelectFile(path, line, context);
+    },
+    [onSelectFile],
+  );
 
   // Find the rule data if we have a context
   const contextRule = useMemo(() => {
@@ -425,7 +509,7 @@ export function SourcesView({
                           key=${`impl:${ref.file}:${ref.line}`}
                           class="rule-context-ref ${isActiveRef(ref) ? "active" : ""}"
                           onClick=${() => onSelectFile(ref.file, ref.line, ruleContext)}
-                          title=${ref.file}
+                          title=${ref.note ? `${ref.file} — ${ref.note}` : ref.file}
                         >
                           <${FilePath} file=${ref.file} line=${ref.line} short type="impl" />
                         </div>
@@ -437,7 +521,7 @@ export function SourcesView({
                           key=${`verify:${ref.file}:${ref.line}`}
                           class="rule-context-ref ${isActiveRef(ref) ? "active" : ""}"
                           onClick=${() => onSelectFile(ref.file, ref.line, ruleContext)}
-                          title=${ref.file}
+                          title=${ref.note ? `${ref.file} — ${ref.note}` : ref.file}
                         >
                           <${FilePath} file=${ref.file} line=${ref.line} short type="verify" />
                         </div>
@@ -457,14 +541,36 @@ export function SourcesView({
                 <${FileTree}
                   node=${fileTree}
                   selectedFile=${selectedFile}
-                  onSelectFile=${onSelectFile}
+                  onSelectFile=${selectFile}
+                  selectedDir=${selectedDir}
+                  onSelectDir=${setSelectedDir}
                   search=${search}
                 />
               </div>
             `)}
       </div>
       <div class="content">
-        ${file
+        ${selectedDir
+          ? html`
+              <div class="content-header">
+                <span>${selectedDir}</span>
+                ${dirRefs &&
+                html`<span class="dir-refs-summary"
+                  >${dirRefs.total} annotation(s) in ${dirRefs.files} file(s),
+                  ${dirRefs.rules.length} rule(s)</span
+                >`}
+              </div>
+              <div class="content-body">
+                ${dirRefs
+                  ? html`<${DirRefs}
+                      refs=${dirRefs}
+                      onSelectFile=${selectFile}
+                      onSelectRule=${onSelectRule}
+                    />`
+                  : html`<div class="loading">Loading...</div>`}
+              </div>
+            `
+          : file
           ? html`
               <div class="content-header">${file.path}</div>
               <div class="content-body">
@@ -3,7 +3,7 @@ import { render } from "preact";
 import { EDITORS, SIDEBAR_COLLAPSED_STORAGE_KEY } from "../config";
 import { useSpec } from "../hooks";
 import { CoverageArc, html, showRefsPopup } from "../main";
-import type { OutlineEntry, SpecViewProps, FileContent } from "../types";
+import type { AnnotationSnippet, OutlineEntry, SpecViewProps, FileContent } from "../types";
 import { MarkdownEditor } from "../components/MarkdownEditor";
 import { InlineEditor } from "../components/InlineEditor";
 import { CodeView } from "./sources";
@@ -311,16 +311,29 @@ export function SpecView({
     let total = 0;
     let implCount = 0;
     let verifyCount = 0;
+    let docsCount = 0;
     for (const node of outlineTree) {
       total += node.entry.aggregated.total;
       implCount += node.entry.aggregated.implCount;
       verifyCount += node.entry.aggregated.verifyCount;
+      docsCount += node.entry.aggregated.docsCount ?? 0;
     }
     const implPct = total > 0 ? Math.round((implCount / total) * 100) : 0;
     const verifyPct = total > 0 ? Math.round((verifyCount / total) * 100) : 0;
-    return { total, implCount, verifyCount, implPct, verifyPct };
+    const docsPct = total > 0 ? Math.round((docsCount / total) * 100) : 0;
+    return { total, implCount, verifyCount, docsCount, implPct, verifyPct, docsPct };
   }, [outlineTree]);
 
+  // r[impl dashboard.spec.verb-legend]
+  // Legend tooltips name the verbs that count toward each metric
+  const verbsByRole = useMemo(() => {
+    const byRole: Record<string, string[]> = {};
+    for (const verb of config.verbs ?? []) {
+      (byRole[verb.role] ??= []).push(verb.name);
+    }
+    return byRole;
+  }, [config.verbs]);
+
   // Concatenate all sections' HTML (sections are pre-sorted by weight on server)
   const processedContent = useMemo(() => {
     if (!spec?.sections) return "";
@@ -827,6 +840,47 @@ export function SpecView({
         return;
       }
 
+      // r[impl dashboard.editing.copy.snippet]
+      // Handle Annotate badge clicks - copy a ready-to-paste annotation comment.
+      // The language is taken from the rule's existing implementation reference,
+      // falling back to Rust when the rule has none yet.
+      const snippetBadge = target.closest("button.req-badge.req-snippet") as HTMLElement | null;
+      if (snippetBadge) {
+        e.preventDefault();
+        const reqId = snippetBadge.dataset.reqId;
+        if (reqId) {
+          const container = snippetBadge.closest(".req-container");
+          const implBadge = container?.querySelector(
+            "a.req-badge.req-impl",
+          ) as HTMLElement | null;
+          const implFile = implBadge?.dataset.file ?? "";
+          const dot = implFile.lastIndexOf(".");
+          const lang = dot >= 0 ? implFile.slice(dot + 1) : "rs";
+          const params = new URLSearchParams({ id: reqId, lang });
+          fetch(`/api/snippet?${params}`)
+            .then((res) => {
+              if (!res.ok) throw new Error(`HTTP ${res.status}`);
+              return res.json();
+            })
+            .then((data: AnnotationSnippet) => navigator.clipboard.writeText(data.snippet))
+            .then(() => {
+              const originalText = snippetBadge.innerHTML;
+              snippetBadge.innerHTML =
+                '<svg class="req-copy-icon" width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><polyline points="20 6 9 17 4 12"></polyline></svg> Copied!';
+              snippetBadge.classList.add("req-copy-success");
+              setTimeout(() => {
+                snippetBadge.innerHTML = originalText;
+                snippetBadge.classList.remove("req-copy-success");
+              }, 1500);
+            })
+            .catch((err) => {
+              console.error("Failed to copy annotation:", err);
+              alert("Failed to copy annotation comment");
+            });
+        }
+        return;
+      }
+
       // r[impl dashboard.impl-preview.modal]
       // r[impl dashboard.impl-preview.stay-in-spec]
       // Handle impl/test badge clicks - show preview modal instead of navigating
@@ -1125,8 +1179,10 @@ export function SpecView({
           <div class="sidebar-header">
             <span>Outline</span>
             <span class="outline-legend">
-              <span class="legend-item"><span class="legend-dot legend-dot--impl"></span>${overallCoverage.implPct}%</span>
-              <span class="legend-item"><span class="legend-dot legend-dot--test"></span>${overallCoverage.verifyPct}%</span>
+              <span class="legend-item" title=${(verbsByRole.impl ?? []).join(", ")}><span class="legend-dot legend-dot--impl"></span>${overallCoverage.implPct}%</span>
+              <span class="legend-item" title=${(verbsByRole.verify ?? []).join(", ")}><span class="legend-dot legend-dot--test"></span>${overallCoverage.verifyPct}%</span>
+              ${verbsByRole.docs &&
+              html`<span class="legend-item" title=${verbsByRole.docs.join(", ")}><span class="legend-dot legend-dot--docs"></span>${overallCoverage.docsPct}%</span>`}
             </span>
           </div>
           <div class="sidebar-content">
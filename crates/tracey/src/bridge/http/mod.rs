@@ -125,11 +125,18 @@ pub async fn run(
         .route("/api/file", get(api_file))
         .route("/api/search", get(api_search))
         .route("/api/status", get(api_status))
+        .route("/api/summary.svg", get(api_summary_svg))
+        .route("/api/badge/{spec}/{file}", get(api_badge_svg))
         .route("/api/validate", get(api_validate))
         .route("/api/uncovered", get(api_uncovered))
         .route("/api/untested", get(api_untested))
         .route("/api/unmapped", get(api_unmapped))
         .route("/api/rule", get(api_rule))
+        .route("/api/snippet", get(api_snippet))
+        .route("/api/compare", get(api_compare))
+        .route("/api/refs-in", get(api_refs_in))
+        .route("/api/breakdown", get(api_breakdown))
+        .route("/api/audit", get(api_audit))
         .route("/api/reload", get(api_reload))
         .route("/api/health", get(api_health));
 
@@ -271,6 +278,12 @@ struct CoverageQuery {
     prefix: Option<String>,
 }
 
+/// Query parameters for the per-implementation badge.
+#[derive(Debug, Clone, Deserialize)]
+struct BadgeQuery {
+    metric: Option<String>,
+}
+
 /// Query parameters for unmapped endpoint.
 #[derive(Debug, Clone, Deserialize)]
 struct UnmappedQuery {
@@ -286,6 +299,45 @@ struct RuleQuery {
     id: String,
 }
 
+/// Query parameters for annotation snippet endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct SnippetQuery {
+    id: String,
+    lang: String,
+    verb: Option<String>,
+}
+
+/// Query parameters for implementation comparison endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct CompareQuery {
+    spec: Option<String>,
+    /// Comma-separated implementation names
+    impls: String,
+}
+
+/// Query parameters for directory annotation listing endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct RefsInQuery {
+    #[serde(default)]
+    path: String,
+}
+
+/// Query parameters for coverage breakdown endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct BreakdownQuery {
+    spec: Option<String>,
+    #[serde(rename = "impl")]
+    impl_name: Option<String>,
+    by: Option<String>,
+    depth: Option<usize>,
+}
+
+/// Query parameters for audit log endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct AuditQuery {
+    limit: Option<u32>,
+}
+
 /// Version response.
 #[derive(Debug, Clone, Facet)]
 struct VersionResponse {
@@ -501,6 +553,73 @@ async fn api_status(State(state): State<Arc<AppState>>) -> Response {
     }
 }
 
+/// GET /api/summary.svg - Per-spec coverage table for embedding in READMEs.
+///
+/// r[impl dashboard.api.summary-svg]
+async fn api_summary_svg(State(state): State<Arc<AppState>>) -> Response {
+    let client = state.client.clone();
+    match rpc(client.status().await) {
+        Ok(status) => summary_svg(&status),
+        Err(e) => e,
+    }
+}
+
+/// The coverage table `tracey badge --style table` renders.
+fn summary_svg(status: &tracey_proto::StatusResponse) -> Response {
+    svg(crate::badge::render_table(status))
+}
+
+/// An SVG image response.
+fn svg(body: String) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "image/svg+xml")],
+        body,
+    )
+        .into_response()
+}
+
+/// GET /api/badge/{spec}/{impl}.svg - Coverage badge for one implementation;
+/// `?metric=verify` shows verification coverage instead.
+///
+/// r[impl dashboard.api.badge-svg]
+async fn api_badge_svg(
+    State(state): State<Arc<AppState>>,
+    Path((spec, file)): Path<(String, String)>,
+    Query(query): Query<BadgeQuery>,
+) -> Response {
+    let client = state.client.clone();
+    match rpc(client.status().await) {
+        Ok(status) => badge_svg(&status, &spec, &file, query.metric.as_deref()),
+        Err(e) => e,
+    }
+}
+
+/// The badge `tracey badge --output` writes for the implementation `file`
+/// (`{impl}.svg`) of `spec`.
+fn badge_svg(
+    status: &tracey_proto::StatusResponse,
+    spec: &str,
+    file: &str,
+    metric: Option<&str>,
+) -> Response {
+    let Some(impl_name) = file.strip_suffix(".svg") else {
+        return ApiError::not_found("Badges end in .svg");
+    };
+    let metric: crate::badge::Metric = match metric.map(str::parse).transpose() {
+        Ok(metric) => metric.unwrap_or_default(),
+        Err(e) => return ApiError::bad_request(e.to_string()),
+    };
+    match status
+        .impls
+        .iter()
+        .find(|s| s.spec == spec && s.impl_name == impl_name)
+    {
+        Some(status) => svg(crate::badge::render_impl(status, metric)),
+        None => ApiError::not_found(format!("No implementation {spec}/{impl_name}")),
+    }
+}
+
 /// GET /api/validate - Validate spec/impl for errors.
 async fn api_validate(
     State(state): State<Arc<AppState>>,
@@ -618,6 +737,99 @@ async fn api_rule(State(state): State<Arc<AppState>>, Query(query): Query<RuleQu
     }
 }
 
+/// GET /api/snippet - Get a clipboard-ready annotation comment for a rule.
+async fn api_snippet(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SnippetQuery>,
+) -> Response {
+    let client = state.client.clone();
+    let Some(rule_id) = parse_rule_id(&query.id) else {
+        return ApiError::bad_request("Invalid rule ID");
+    };
+
+    let req = tracey_proto::AnnotationSnippetRequest {
+        rule_id,
+        language: query.lang,
+        verb: query.verb,
+    };
+
+    match client.annotation_snippet(req).await {
+        Ok(snippet) => Json(snippet).into_response(),
+        Err(roam::RoamError::User(message)) => ApiError::bad_request(message),
+        Err(e) => ApiError::rpc_error(format!("{:?}", e)),
+    }
+}
+
+/// GET /api/compare - Compare rule coverage of several implementations.
+async fn api_compare(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CompareQuery>,
+) -> Response {
+    let client = state.client.clone();
+    let req = tracey_proto::CompareRequest {
+        spec: query.spec,
+        impls: query
+            .impls
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect(),
+    };
+
+    match client.compare(req).await {
+        Ok(response) => Json(response).into_response(),
+        Err(roam::RoamError::User(message)) => ApiError::bad_request(message),
+        Err(e) => ApiError::rpc_error(format!("{:?}", e)),
+    }
+}
+
+/// GET /api/refs-in - List every annotation under a directory.
+async fn api_refs_in(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RefsInQuery>,
+) -> Response {
+    let client = state.client.clone();
+    let req = tracey_proto::RefsInRequest { path: query.path };
+    match rpc(client.refs_in(req).await) {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => e,
+    }
+}
+
+/// GET /api/breakdown - Roll up coverage by directory or crate.
+async fn api_breakdown(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BreakdownQuery>,
+) -> Response {
+    let client = state.client.clone();
+    let req = tracey_proto::BreakdownRequest {
+        spec: query.spec,
+        impl_name: query.impl_name,
+        by: query.by,
+        depth: query.depth,
+    };
+
+    match client.breakdown(req).await {
+        Ok(response) => Json(response).into_response(),
+        Err(roam::RoamError::User(message)) => ApiError::bad_request(message),
+        Err(e) => ApiError::rpc_error(format!("{:?}", e)),
+    }
+}
+
+/// GET /api/audit - Most recent changes made through the daemon.
+async fn api_audit(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditQuery>,
+) -> Response {
+    let client = state.client.clone();
+    match client.audit(query.limit.unwrap_or(100)).await {
+        Ok(entries) => Json(entries).into_response(),
+        Err(roam::RoamError::User(message)) => ApiError::rpc_error(message),
+        Err(e) => ApiError::rpc_error(format!("{:?}", e)),
+    }
+}
+
 /// GET /api/reload - Force a rebuild.
 async fn api_reload(State(state): State<Arc<AppState>>) -> Response {
     let client = state.client.clone();
@@ -969,3 +1181,71 @@ async fn handle_vite_ws(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_proto::{ImplStatus, StatusResponse};
+
+    fn status() -> StatusResponse {
+        StatusResponse {
+            impls: vec![ImplStatus {
+                spec: "app".to_string(),
+                impl_name: "rust".to_string(),
+                total_rules: 4,
+                covered_rules: 3,
+                partial_rules: 0,
+                stale_rules: 0,
+                verified_rules: 1,
+                advisory: false,
+                documented_rules: None,
+                proven_rules: None,
+            }],
+        }
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    // r[verify dashboard.api.summary-svg]
+    #[tokio::test]
+    async fn summary_svg_is_the_badge_table() {
+        let response = summary_svg(&status());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        assert_eq!(
+            body(response).await,
+            crate::badge::render(&status(), crate::badge::Style::Table)
+        );
+    }
+
+    // r[verify dashboard.api.badge-svg]
+    #[tokio::test]
+    async fn badge_svg_renders_one_implementation() {
+        let status = status();
+        let response = badge_svg(&status, "app", "rust.svg", None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        assert_eq!(
+            body(response).await,
+            crate::badge::render_impl(&status.impls[0], crate::badge::Metric::Impl)
+        );
+
+        let response = badge_svg(&status, "app", "rust.svg", Some("verify"));
+        assert_eq!(
+            body(response).await,
+            crate::badge::render_impl(&status.impls[0], crate::badge::Metric::Verify)
+        );
+
+        let missing = badge_svg(&status, "app", "go.svg", None);
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let unsuffixed = badge_svg(&status, "app", "rust", None);
+        assert_eq!(unsuffixed.status(), StatusCode::NOT_FOUND);
+        let bad_metric = badge_svg(&status, "app", "rust.svg", Some("bogus"));
+        assert_eq!(bad_metric.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -34,11 +34,106 @@ const SEMANTIC_TOKEN_TYPES: &[SemanticTokenType] = &[
     SemanticTokenType::VARIABLE,  // 2: requirement ID
 ];
 
+// Must match the bits of `tracey_proto::semantic_modifiers`.
 const SEMANTIC_TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
     SemanticTokenModifier::DEFINITION, // 0: for definitions in spec files
     SemanticTokenModifier::DECLARATION, // 1: for valid references
+    SemanticTokenModifier::DEPRECATED, // 2: for deprecated or removed rules
+    SemanticTokenModifier::new("stale"), // 3: for references to an older rule version
+    SemanticTokenModifier::new("unknown"), // 4: for references to missing rules
 ];
 
+/// How long `did_change` waits for typing to pause before sending the
+/// document to the daemon.
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Editor-side settings, sent as `initializationOptions` or through
+/// `workspace/didChangeConfiguration`.
+///
+/// r[impl lsp.settings]
+#[derive(Debug, Clone, PartialEq)]
+struct LspSettings {
+    /// Severity per diagnostic code; `None` hides diagnostics with that code.
+    severity: HashMap<String, Option<DiagnosticSeverity>>,
+    inlay_hints: bool,
+    /// Most completion items returned per request.
+    completion_limit: Option<usize>,
+    /// Extensions of the files the bridge answers for; empty serves them all.
+    file_types: HashSet<String>,
+}
+
+impl Default for LspSettings {
+    fn default() -> Self {
+        Self {
+            severity: HashMap::new(),
+            inlay_hints: true,
+            completion_limit: None,
+            file_types: HashSet::new(),
+        }
+    }
+}
+
+impl LspSettings {
+    /// Read settings from a JSON object, either bare or under a `tracey` key,
+    /// as editors send them. Unknown and malformed fields keep their default.
+    fn from_json(value: &serde_json::Value) -> Self {
+        let value = value.get("tracey").unwrap_or(value);
+        let mut settings = Self::default();
+
+        if let Some(overrides) = value
+            .pointer("/diagnostics/severity")
+            .and_then(|v| v.as_object())
+        {
+            for (code, severity) in overrides {
+                let severity = match severity.as_str() {
+                    Some("error") => Some(DiagnosticSeverity::ERROR),
+                    Some("warning") => Some(DiagnosticSeverity::WARNING),
+                    Some("info") => Some(DiagnosticSeverity::INFORMATION),
+                    Some("hint") => Some(DiagnosticSeverity::HINT),
+                    Some("off") => None,
+                    _ => continue,
+                };
+                settings.severity.insert(code.clone(), severity);
+            }
+        }
+        if let Some(enabled) = value
+            .pointer("/inlayHints/enabled")
+            .and_then(|v| v.as_bool())
+        {
+            settings.inlay_hints = enabled;
+        }
+        settings.completion_limit = value
+            .pointer("/completion/limit")
+            .and_then(|v| v.as_u64())
+            .map(|limit| limit as usize);
+        if let Some(types) = value.get("fileTypes").and_then(|v| v.as_array()) {
+            settings.file_types = types
+                .iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| t.trim_start_matches('.').to_string())
+                .collect();
+        }
+        settings
+    }
+
+    /// Whether the bridge answers requests for this file.
+    fn serves(&self, path: &Path) -> bool {
+        self.file_types.is_empty()
+            || path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| self.file_types.contains(e))
+    }
+
+    /// Severity to publish a diagnostic with, or `None` to drop it.
+    fn severity_for(&self, code: &str, severity: DiagnosticSeverity) -> Option<DiagnosticSeverity> {
+        match self.severity.get(code) {
+            Some(severity) => *severity,
+            None => Some(severity),
+        }
+    }
+}
+
 /// Run the LSP bridge over stdio.
 ///
 /// This function starts an LSP server that connects to the tracey daemon
@@ -171,13 +266,14 @@ async fn run_lsp_server(cli_project_root: PathBuf) -> Result<()> {
 
     let doc_state = Arc::new(Mutex::new(LspDocState {
         documents: HashMap::new(),
+        vfs: VfsQueues::new(CHANGE_DEBOUNCE),
     }));
 
     let project_state = Arc::new(Mutex::new(LspProjectState {
         roots: HashSet::from([project_root.clone()]),
         daemon_clients: HashMap::new(),
         watched_roots: HashSet::new(),
-        files_with_diagnostics: HashMap::new(),
+        published_diagnostics: HashMap::new(),
     }));
 
     let (service, socket) = LspService::new(|client| Backend {
@@ -185,6 +281,7 @@ async fn run_lsp_server(cli_project_root: PathBuf) -> Result<()> {
         default_project_root: project_root.clone(),
         project_state: Arc::clone(&project_state),
         doc_state: Arc::clone(&doc_state),
+        settings: Arc::new(Mutex::new(LspSettings::default())),
     });
     Server::new(replayed_stdin, stdout, socket)
         .serve(service)
@@ -193,11 +290,35 @@ async fn run_lsp_server(cli_project_root: PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// What to publish to move the client from the `previous` diagnostics to
+/// `next`, both keyed by absolute file path.
+///
+/// r[impl lsp.diagnostics.incremental]
+/// Only files whose diagnostics changed are re-sent, so a keystroke in one
+/// file does not republish every file in the workspace; files that no longer
+/// have diagnostics are cleared with an empty list.
+fn diagnostics_to_publish(
+    previous: &HashMap<String, Vec<Diagnostic>>,
+    next: HashMap<String, Vec<Diagnostic>>,
+) -> Vec<(String, Vec<Diagnostic>)> {
+    let mut publish: Vec<_> = previous
+        .keys()
+        .filter(|path| !next.contains_key(*path))
+        .map(|path| (path.clone(), Vec::new()))
+        .collect();
+    publish.extend(
+        next.into_iter()
+            .filter(|(path, diagnostics)| previous.get(path) != Some(diagnostics)),
+    );
+    publish
+}
+
 struct Backend {
     client: Client,
     default_project_root: PathBuf,
     project_state: Arc<Mutex<LspProjectState>>,
     doc_state: Arc<Mutex<LspDocState>>,
+    settings: Arc<Mutex<LspSettings>>,
 }
 
 /// Document-tracking state requiring mutual exclusion.
@@ -206,6 +327,111 @@ struct Backend {
 struct LspDocState {
     /// Document content cache: uri -> content
     documents: HashMap<String, String>,
+    /// VFS notifications on their way to the daemon, keyed by uri
+    vfs: VfsQueues<DaemonClient>,
+}
+
+/// One VFS notification about a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VfsUpdate {
+    Open(String),
+    Change(String),
+    Close,
+}
+
+/// Where VFS notifications are delivered; the daemon, outside of tests.
+trait VfsSink: Clone + Send + Sync + 'static {
+    fn send(&self, path: String, update: VfsUpdate) -> impl Future<Output = ()> + Send;
+}
+
+impl VfsSink for DaemonClient {
+    async fn send(&self, path: String, update: VfsUpdate) {
+        let _ = match update {
+            VfsUpdate::Open(content) => self.vfs_open(path, content).await,
+            VfsUpdate::Change(content) => self.vfs_change(path, content).await,
+            VfsUpdate::Close => self.vfs_close(path).await,
+        };
+    }
+}
+
+/// VFS notifications for each document, delivered in the order the editor
+/// produced them.
+///
+/// Every document has a worker task that sends its notifications one at a
+/// time, each after the previous RPC finished, so a debounced change can
+/// never land after the close or save that follows it. Changes first wait
+/// out the debounce; each edit restarts the timer, so a burst of keystrokes
+/// triggers one daemon rebuild instead of one per key.
+struct VfsQueues<S> {
+    debounce: Duration,
+    /// Worker queues: uri -> (sink, path, update) sender
+    workers: HashMap<String, tokio::sync::mpsc::UnboundedSender<(S, String, VfsUpdate)>>,
+    /// Changes still waiting out the debounce: uri -> timer task
+    pending: HashMap<String, tokio::task::JoinHandle<()>>,
+}
+
+impl<S: VfsSink> VfsQueues<S> {
+    fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            workers: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// The worker queue of a document, started on first use.
+    fn worker(&mut self, uri: &str) -> tokio::sync::mpsc::UnboundedSender<(S, String, VfsUpdate)> {
+        self.workers
+            .entry(uri.to_string())
+            .or_insert_with(|| {
+                let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(S, String, VfsUpdate)>();
+                tokio::spawn(async move {
+                    while let Some((sink, path, update)) = rx.recv().await {
+                        sink.send(path, update).await;
+                    }
+                });
+                tx
+            })
+            .clone()
+    }
+
+    /// Queue a notification behind those already sent for the document,
+    /// dropping a change still waiting out the debounce.
+    ///
+    /// The worker outlives a close, so reopening the document cannot send
+    /// its open ahead of the close still in flight.
+    fn send(&mut self, uri: &str, sink: S, path: String, update: VfsUpdate) {
+        self.cancel(uri);
+        let _ = self.worker(uri).send((sink, path, update));
+    }
+
+    /// Queue a change once typing pauses for the debounce interval.
+    fn schedule_change(&mut self, uri: &str, sink: S, path: String, content: String) {
+        let worker = self.worker(uri);
+        let debounce = self.debounce;
+        // Queueing is synchronous, so aborting the timer either drops the
+        // change or finds it already queued, never half sent.
+        let timer = tokio::spawn(async move {
+            tokio::time::sleep(debounce).await;
+            let _ = worker.send((sink, path, VfsUpdate::Change(content)));
+        });
+        if let Some(previous) = self.pending.insert(uri.to_string(), timer) {
+            previous.abort();
+        }
+    }
+
+    /// Drop a change still waiting out the debounce. Returns whether there
+    /// was one.
+    fn cancel(&mut self, uri: &str) -> bool {
+        match self.pending.remove(uri) {
+            Some(timer) => {
+                let waiting = !timer.is_finished();
+                timer.abort();
+                waiting
+            }
+            None => false,
+        }
+    }
 }
 
 /// Per-project daemon clients and diagnostics bookkeeping.
@@ -216,17 +442,22 @@ struct LspProjectState {
     daemon_clients: HashMap<PathBuf, DaemonClient>,
     /// Roots with an active rebuild watcher task.
     watched_roots: HashSet<PathBuf>,
-    /// Files currently published with non-empty diagnostics, keyed by project root.
-    files_with_diagnostics: HashMap<PathBuf, HashSet<String>>,
+    /// Diagnostics last published to the client, keyed by project root, then
+    /// by absolute file path.
+    published_diagnostics: HashMap<PathBuf, HashMap<String, Vec<Diagnostic>>>,
 }
 
 impl Backend {
     /// Get path and content for a document, for daemon calls.
+    /// Documents of file types the settings turn off are not served.
     fn get_path_and_content(&self, uri: &Url) -> Option<(String, String)> {
+        let path = uri.to_file_path().ok()?;
+        if !self.settings.lock().unwrap().serves(&path) {
+            return None;
+        }
         let state = self.doc_state.lock().unwrap();
         let content = state.documents.get(uri.as_str())?.clone();
-        let path = uri.to_file_path().ok()?.to_string_lossy().into_owned();
-        Some((path, content))
+        Some((path.to_string_lossy().into_owned(), content))
     }
 
     fn offset_to_line_col(content: &str, offset: usize) -> (u32, u32) {
@@ -402,6 +633,8 @@ impl Backend {
             daemon_client,
             project_root,
             Arc::clone(&self.project_state),
+            Arc::clone(&self.doc_state),
+            Arc::clone(&self.settings),
         ));
     }
 
@@ -473,58 +706,40 @@ impl Backend {
         Ok(())
     }
 
-    /// Notify daemon that a file was opened.
-    async fn notify_vfs_open(&self, uri: &Url, content: &str) {
+    /// Queue a VFS notification for a document with its project's daemon.
+    fn send_vfs_update(&self, uri: &Url, update: VfsUpdate) {
         let Some((project_root, daemon_client, should_watch, _)) = self.ensure_project_for_uri(uri)
         else {
             return;
         };
         if let Ok(path) = uri.to_file_path() {
             let path = path.to_string_lossy().into_owned();
-            let content = content.to_string();
-            let bg_client = daemon_client.clone();
-            let bg_path = path.clone();
-            let bg_content = content.clone();
-            tokio::spawn(async move {
-                let _ = bg_client.vfs_open(bg_path, bg_content).await;
-            });
+            self.doc_state.lock().unwrap().vfs.send(
+                uri.as_str(),
+                daemon_client.clone(),
+                path,
+                update,
+            );
         }
         self.spawn_watcher_if_needed(project_root, daemon_client, should_watch);
     }
 
-    /// Notify daemon that a file changed.
-    async fn notify_vfs_change(&self, uri: &Url, content: &str) {
+    /// Notify daemon that a file changed once typing pauses.
+    fn schedule_vfs_change(&self, uri: &Url, content: String) {
         let Some((project_root, daemon_client, should_watch, _)) = self.ensure_project_for_uri(uri)
         else {
             return;
         };
-        if let Ok(path) = uri.to_file_path() {
-            let path = path.to_string_lossy().into_owned();
-            let content = content.to_string();
-            let bg_client = daemon_client.clone();
-            let bg_path = path.clone();
-            let bg_content = content.clone();
-            tokio::spawn(async move {
-                let _ = bg_client.vfs_change(bg_path, bg_content).await;
-            });
-        }
-        self.spawn_watcher_if_needed(project_root, daemon_client, should_watch);
-    }
-
-    /// Notify daemon that a file was closed.
-    async fn notify_vfs_close(&self, uri: &Url) {
-        let Some((project_root, daemon_client, should_watch, _)) = self.ensure_project_for_uri(uri)
-        else {
+        let Ok(path) = uri.to_file_path() else {
             return;
         };
-        if let Ok(path) = uri.to_file_path() {
-            let path = path.to_string_lossy().into_owned();
-            let bg_client = daemon_client.clone();
-            let bg_path = path.clone();
-            tokio::spawn(async move {
-                let _ = bg_client.vfs_close(bg_path).await;
-            });
-        }
+        let path = path.to_string_lossy().into_owned();
+        self.doc_state.lock().unwrap().vfs.schedule_change(
+            uri.as_str(),
+            daemon_client.clone(),
+            path,
+            content,
+        );
         self.spawn_watcher_if_needed(project_root, daemon_client, should_watch);
     }
 
@@ -546,6 +761,7 @@ impl Backend {
             &daemon_client,
             &project_root,
             &self.project_state,
+            &self.settings,
         )
         .await;
     }
@@ -555,7 +771,9 @@ impl Backend {
         daemon_client: &DaemonClient,
         project_root: &std::path::Path,
         project_state: &Arc<Mutex<LspProjectState>>,
+        settings: &Arc<Mutex<LspSettings>>,
     ) {
+        let settings = settings.lock().unwrap().clone();
         let config_error = rpc(daemon_client.health().await)
             .ok()
             .and_then(|h| h.config_error);
@@ -565,28 +783,15 @@ impl Backend {
             Err(_) => return,
         };
 
-        let files_to_clear: Vec<String> = {
-            let mut state = project_state.lock().unwrap();
-            state
-                .files_with_diagnostics
-                .remove(project_root)
-                .unwrap_or_default()
-                .into_iter()
-                .collect()
-        };
+        // Gather the full set first so unchanged files can be skipped below.
+        let mut next: HashMap<String, Vec<Diagnostic>> = HashMap::new();
 
-        for path in files_to_clear {
-            let Ok(uri) = Url::from_file_path(&path) else {
-                continue;
-            };
-            client.publish_diagnostics(uri, vec![], None).await;
-        }
-
-        // Publish config error diagnostic on config file
-        let config_path = project_root.join(".config/tracey/config.styx");
-        if let Ok(uri) = Url::from_file_path(&config_path) {
-            if let Some(error_msg) = config_error {
-                let diagnostic = Diagnostic {
+        // Config error diagnostic on the config file
+        if let Some(error_msg) = config_error {
+            let config_path = project_root.join(".config/tracey/config.styx");
+            next.insert(
+                config_path.to_string_lossy().into_owned(),
+                vec![Diagnostic {
                     range: Range {
                         start: Position {
                             line: 0,
@@ -602,59 +807,109 @@ impl Backend {
                     source: Some("tracey".into()),
                     message: error_msg,
                     ..Default::default()
-                };
-                client
-                    .publish_diagnostics(uri, vec![diagnostic], None)
-                    .await;
-            } else {
-                client.publish_diagnostics(uri, vec![], None).await;
-            }
+                }],
+            );
         }
 
-        // Publish diagnostics for all files in the latest rebuild snapshot.
-        let mut published_paths = HashSet::new();
+        // Diagnostics for all files in the latest rebuild snapshot.
         for file_diag in all_diagnostics {
             let abs_path = project_root.join(&file_diag.path);
-            let abs_path_str = abs_path.to_string_lossy().into_owned();
-            let Ok(uri) = Url::from_file_path(&abs_path) else {
+            if !settings.serves(&abs_path) {
                 continue;
-            };
-
+            }
             let diagnostics: Vec<Diagnostic> = file_diag
                 .diagnostics
                 .into_iter()
-                .map(|d| Diagnostic {
-                    range: Range {
-                        start: Position {
-                            line: d.start_line,
-                            character: d.start_char,
+                .filter_map(|d| {
+                    let severity = settings.severity_for(
+                        &d.code,
+                        match d.severity.as_str() {
+                            "error" => DiagnosticSeverity::ERROR,
+                            "warning" => DiagnosticSeverity::WARNING,
+                            "info" => DiagnosticSeverity::INFORMATION,
+                            _ => DiagnosticSeverity::HINT,
                         },
-                        end: Position {
-                            line: d.end_line,
-                            character: d.end_char,
+                    )?;
+                    Some(Diagnostic {
+                        range: Range {
+                            start: Position {
+                                line: d.start_line,
+                                character: d.start_char,
+                            },
+                            end: Position {
+                                line: d.end_line,
+                                character: d.end_char,
+                            },
                         },
-                    },
-                    severity: Some(match d.severity.as_str() {
-                        "error" => DiagnosticSeverity::ERROR,
-                        "warning" => DiagnosticSeverity::WARNING,
-                        "info" => DiagnosticSeverity::INFORMATION,
-                        _ => DiagnosticSeverity::HINT,
-                    }),
-                    code: Some(NumberOrString::String(d.code)),
-                    source: Some("tracey".into()),
-                    message: d.message,
-                    ..Default::default()
+                        severity: Some(severity),
+                        code: Some(NumberOrString::String(d.code)),
+                        source: Some("tracey".into()),
+                        message: d.message,
+                        ..Default::default()
+                    })
                 })
                 .collect();
+            next.insert(abs_path.to_string_lossy().into_owned(), diagnostics);
+        }
+
+        let previous = {
+            let mut state = project_state.lock().unwrap();
+            state
+                .published_diagnostics
+                .insert(project_root.to_path_buf(), next.clone())
+                .unwrap_or_default()
+        };
 
+        for (path, diagnostics) in diagnostics_to_publish(&previous, next) {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
             client.publish_diagnostics(uri, diagnostics, None).await;
-            published_paths.insert(abs_path_str);
         }
+    }
 
-        let mut state = project_state.lock().unwrap();
-        state
-            .files_with_diagnostics
-            .insert(project_root.to_path_buf(), published_paths);
+    /// Bring the daemon's VFS overlay in line with the documents open in this
+    /// project: report them all by content hash in one `vfs_sync` call, then
+    /// send only the ones the daemon asks for.
+    async fn sync_open_documents(
+        daemon_client: &DaemonClient,
+        project_root: &Path,
+        project_state: &Arc<Mutex<LspProjectState>>,
+        doc_state: &Arc<Mutex<LspDocState>>,
+    ) {
+        let roots = project_state.lock().unwrap().roots.clone();
+        let mut contents = HashMap::new();
+        {
+            let state = doc_state.lock().unwrap();
+            for (uri, content) in &state.documents {
+                let Some(path) = Url::parse(uri).ok().and_then(|u| u.to_file_path().ok()) else {
+                    continue;
+                };
+                if Self::best_root_for_path(&path, &roots).as_deref() != Some(project_root) {
+                    continue;
+                }
+                contents.insert(path.to_string_lossy().into_owned(), content.clone());
+            }
+        }
+        if contents.is_empty() {
+            return;
+        }
+
+        let entries = contents
+            .iter()
+            .map(|(path, content)| VfsSyncEntry {
+                path: path.clone(),
+                hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
+            })
+            .collect();
+        let Ok(response) = daemon_client.vfs_sync(VfsSyncRequest { entries }).await else {
+            return;
+        };
+        for path in response.needed {
+            if let Some(content) = contents.remove(&path) {
+                let _ = daemon_client.vfs_open(path, content).await;
+            }
+        }
     }
 
     async fn watch_daemon_rebuilds(
@@ -662,8 +917,13 @@ impl Backend {
         daemon_client: DaemonClient,
         project_root: PathBuf,
         project_state: Arc<Mutex<LspProjectState>>,
+        doc_state: Arc<Mutex<LspDocState>>,
+        settings: Arc<Mutex<LspSettings>>,
     ) {
         let mut last_version: Option<u64> = None;
+        // Whether the overlay is known to match our open documents. Cleared
+        // whenever the daemon goes away, since a new daemon starts empty.
+        let mut synced = false;
         let (tx, mut rx) = roam::channel::<DataUpdate>();
         let subscribe_client = daemon_client.clone();
         let subscribe_task = tokio::spawn(async move { subscribe_client.subscribe(tx).await });
@@ -684,10 +944,24 @@ impl Backend {
                 };
 
             let Some(next_version) = next_version else {
+                synced = false;
                 tokio::time::sleep(Duration::from_millis(250)).await;
                 continue;
             };
 
+            // A version going backwards means the daemon restarted between
+            // polls.
+            if !synced || last_version.is_some_and(|v| next_version < v) {
+                Self::sync_open_documents(
+                    &daemon_client,
+                    &project_root,
+                    &project_state,
+                    &doc_state,
+                )
+                .await;
+                synced = true;
+            }
+
             if last_version == Some(next_version) {
                 continue;
             }
@@ -697,6 +971,7 @@ impl Backend {
                 &daemon_client,
                 &project_root,
                 &project_state,
+                &settings,
             )
             .await;
         }
@@ -710,7 +985,7 @@ impl Backend {
     async fn clear_workspace_diagnostics_on_startup(&self, project_root: &Path) {
         {
             let mut state = self.project_state.lock().unwrap();
-            state.files_with_diagnostics.remove(project_root);
+            state.published_diagnostics.remove(project_root);
         }
 
         let config_path = project_root.join(".config/tracey/config.styx");
@@ -762,10 +1037,10 @@ impl Backend {
             state.watched_roots.remove(&project_root);
             state.daemon_clients.remove(&project_root);
             let files = state
-                .files_with_diagnostics
+                .published_diagnostics
                 .remove(&project_root)
                 .unwrap_or_default()
-                .into_iter()
+                .into_keys()
                 .collect::<Vec<_>>();
             let should_restore_default = state.roots.is_empty();
             (files, should_restore_default)
@@ -790,6 +1065,21 @@ impl Backend {
     }
 }
 
+/// Append a reference to a hover's list, as a link when the file has a URI
+/// and followed by the reference's note if it has one.
+fn push_hover_ref(markdown: &mut String, project_root: &Path, r: &HoverRef) {
+    let abs_path = project_root.join(&r.file);
+    if let Ok(uri) = Url::from_file_path(&abs_path) {
+        markdown.push_str(&format!("\n- [{}:{}]({}#L{})", r.file, r.line, uri, r.line));
+    } else {
+        markdown.push_str(&format!("\n- {}:{}", r.file, r.line));
+    }
+    // r[impl ref.syntax.note]
+    if let Some(note) = &r.note {
+        markdown.push_str(&format!(" — *{note}*"));
+    }
+}
+
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     /// r[impl lsp.lifecycle.initialize]
@@ -800,6 +1090,9 @@ impl LanguageServer for Backend {
             let mut state = self.project_state.lock().unwrap();
             state.roots = roots.into_iter().collect();
         }
+        if let Some(options) = &params.initialization_options {
+            *self.settings.lock().unwrap() = LspSettings::from_json(options);
+        }
 
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
@@ -826,6 +1119,7 @@ impl LanguageServer for Backend {
                     resolve_provider: Some(false),
                 }),
                 inlay_hint_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
                 rename_provider: Some(OneOf::Right(RenameOptions {
                     prepare_provider: Some(true),
                     work_done_progress_options: Default::default(),
@@ -869,6 +1163,32 @@ impl LanguageServer for Backend {
         Ok(())
     }
 
+    /// Apply new settings, then republish diagnostics and ask the client to
+    /// refetch inlay hints so they take effect without a restart.
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        let settings = LspSettings::from_json(&params.settings);
+        {
+            let mut current = self.settings.lock().unwrap();
+            if *current == settings {
+                return;
+            }
+            *current = settings;
+        }
+        for root in self.active_roots() {
+            let (project_root, daemon_client, should_watch, _) = self.ensure_project_root(root);
+            self.spawn_watcher_if_needed(project_root.clone(), daemon_client.clone(), should_watch);
+            Self::publish_workspace_diagnostics_with(
+                &self.client,
+                &daemon_client,
+                &project_root,
+                &self.project_state,
+                &self.settings,
+            )
+            .await;
+        }
+        let _ = self.client.inlay_hint_refresh().await;
+    }
+
     async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
         for folder in params.event.added {
             if let Ok(path) = folder.uri.to_file_path() {
@@ -890,7 +1210,7 @@ impl LanguageServer for Backend {
             let mut state = self.doc_state.lock().unwrap();
             state.documents.insert(uri.to_string(), content.clone());
         }
-        self.notify_vfs_open(&uri, &content).await;
+        self.send_vfs_update(&uri, VfsUpdate::Open(content));
     }
 
     /// r[impl lsp.diagnostics.on-change]
@@ -902,15 +1222,22 @@ impl LanguageServer for Backend {
                 let mut state = self.doc_state.lock().unwrap();
                 state.documents.insert(uri.to_string(), content.clone());
             }
-            self.notify_vfs_change(&uri, &content).await;
+            self.schedule_vfs_change(&uri, content);
         }
     }
 
     /// r[impl lsp.diagnostics.on-save]
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         let uri = params.text_document.uri.clone();
-        if let Some(content) = params.text {
-            self.notify_vfs_change(&uri, &content).await;
+        // Saving flushes any change still waiting out the debounce.
+        let had_pending = self.doc_state.lock().unwrap().vfs.cancel(uri.as_str());
+        let content = params.text.or_else(|| {
+            had_pending
+                .then(|| self.get_path_and_content(&uri).map(|(_, content)| content))
+                .flatten()
+        });
+        if let Some(content) = content {
+            self.send_vfs_update(&uri, VfsUpdate::Change(content));
         }
 
         if let Some((project_root, _, _, _)) = self.ensure_project_for_uri(&uri)
@@ -926,7 +1253,7 @@ impl LanguageServer for Backend {
             let mut state = self.doc_state.lock().unwrap();
             state.documents.remove(uri.as_str());
         }
-        self.notify_vfs_close(&uri).await;
+        self.send_vfs_update(&uri, VfsUpdate::Close);
     }
 
     /// r[impl lsp.completions.verb]
@@ -955,8 +1282,11 @@ impl LanguageServer for Backend {
             return Ok(None);
         };
 
+        let limit = self.settings.lock().unwrap().completion_limit;
+        let truncated = limit.is_some_and(|limit| completions.len() > limit);
         let items: Vec<CompletionItem> = completions
             .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
             .map(|c| CompletionItem {
                 label: c.label,
                 kind: Some(match c.kind.as_str() {
@@ -973,6 +1303,13 @@ impl LanguageServer for Backend {
 
         if items.is_empty() {
             Ok(None)
+        } else if truncated {
+            // Ask the client to query again as the user types, so matches
+            // past the limit are not lost.
+            Ok(Some(CompletionResponse::List(CompletionList {
+                is_incomplete: true,
+                items,
+            })))
         } else {
             Ok(Some(CompletionResponse::Array(items)))
         }
@@ -1029,12 +1366,7 @@ impl LanguageServer for Backend {
         if !info.impl_refs.is_empty() {
             markdown.push_str("\n\n**Implementations:**");
             for r in &info.impl_refs {
-                let abs_path = project_root.join(&r.file);
-                if let Ok(uri) = Url::from_file_path(&abs_path) {
-                    markdown.push_str(&format!("\n- [{}:{}]({}#L{})", r.file, r.line, uri, r.line));
-                } else {
-                    markdown.push_str(&format!("\n- {}:{}", r.file, r.line));
-                }
+                push_hover_ref(&mut markdown, &project_root, r);
             }
         }
 
@@ -1042,12 +1374,7 @@ impl LanguageServer for Backend {
         if !info.verify_refs.is_empty() {
             markdown.push_str("\n\n**Verifications:**");
             for r in &info.verify_refs {
-                let abs_path = project_root.join(&r.file);
-                if let Ok(uri) = Url::from_file_path(&abs_path) {
-                    markdown.push_str(&format!("\n- [{}:{}]({}#L{})", r.file, r.line, uri, r.line));
-                } else {
-                    markdown.push_str(&format!("\n- {}:{}", r.file, r.line));
-                }
+                push_hover_ref(&mut markdown, &project_root, r);
             }
         }
 
@@ -1063,6 +1390,19 @@ impl LanguageServer for Backend {
             markdown.push_str(diff);
         }
 
+        // r[impl lsp.hover.examples]
+        if !info.examples.is_empty() {
+            markdown.push_str("\n\n**Examples:**");
+            for example in &info.examples {
+                let label = if example.kind == "good" {
+                    "✓ Good"
+                } else {
+                    "✗ Bad"
+                };
+                markdown.push_str(&format!("\n\n*{label}*\n\n{}", example.markdown));
+            }
+        }
+
         Ok(Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
@@ -1513,8 +1853,49 @@ impl LanguageServer for Backend {
         Ok(Some(lsp_lenses))
     }
 
+    async fn folding_range(
+        &self,
+        params: FoldingRangeParams,
+    ) -> LspResult<Option<Vec<FoldingRange>>> {
+        let uri = &params.text_document.uri;
+
+        let Some((path, content)) = self.get_path_and_content(uri) else {
+            return Ok(None);
+        };
+        let Some((_, daemon_client)) = self.project_for_doc_uri(uri) else {
+            return Ok(None);
+        };
+
+        let req = LspDocumentRequest { path, content };
+
+        let Ok(ranges) = rpc(daemon_client.lsp_folding_ranges(req).await) else {
+            return Ok(None);
+        };
+
+        if ranges.is_empty() {
+            return Ok(None);
+        }
+
+        let lsp_ranges: Vec<FoldingRange> = ranges
+            .into_iter()
+            .map(|r| FoldingRange {
+                start_line: r.start_line,
+                start_character: None,
+                end_line: r.end_line,
+                end_character: None,
+                kind: Some(FoldingRangeKind::Region),
+                collapsed_text: r.collapsed_text,
+            })
+            .collect();
+
+        Ok(Some(lsp_ranges))
+    }
+
     async fn inlay_hint(&self, params: InlayHintParams) -> LspResult<Option<Vec<InlayHint>>> {
         let uri = &params.text_document.uri;
+        if !self.settings.lock().unwrap().inlay_hints {
+            return Ok(None);
+        }
 
         let Some((path, content)) = self.get_path_and_content(uri) else {
             return Ok(None);
@@ -1715,6 +2096,126 @@ mod tests {
 
     use super::*;
 
+    /// Records what reached it, taking `delay` per notification like an RPC.
+    #[derive(Clone)]
+    struct RecordingSink {
+        delay: Duration,
+        received: Arc<Mutex<Vec<VfsUpdate>>>,
+    }
+
+    impl RecordingSink {
+        fn new(delay: Duration) -> Self {
+            Self {
+                delay,
+                received: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
+        fn received(&self) -> Vec<VfsUpdate> {
+            self.received.lock().unwrap().clone()
+        }
+    }
+
+    impl VfsSink for RecordingSink {
+        async fn send(&self, _path: String, update: VfsUpdate) {
+            tokio::time::sleep(self.delay).await;
+            self.received.lock().unwrap().push(update);
+        }
+    }
+
+    const URI: &str = "file:///project/src/lib.rs";
+
+    fn change(content: &str) -> VfsUpdate {
+        VfsUpdate::Change(content.to_string())
+    }
+
+    // r[verify lsp.diagnostics.on-change]
+    #[tokio::test]
+    async fn changes_within_the_debounce_window_are_coalesced() {
+        let sink = RecordingSink::new(Duration::ZERO);
+        let mut queues = VfsQueues::new(Duration::from_millis(50));
+        for content in ["a", "ab", "abc"] {
+            queues.schedule_change(URI, sink.clone(), "lib.rs".into(), content.into());
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(sink.received(), [change("abc")]);
+
+        queues.schedule_change(URI, sink.clone(), "lib.rs".into(), "abcd".into());
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(sink.received(), [change("abc"), change("abcd")]);
+    }
+
+    #[tokio::test]
+    async fn closing_drops_a_change_still_waiting_out_the_debounce() {
+        let sink = RecordingSink::new(Duration::ZERO);
+        let mut queues = VfsQueues::new(Duration::from_millis(50));
+        queues.schedule_change(URI, sink.clone(), "lib.rs".into(), "edit".into());
+        assert!(queues.cancel(URI));
+        queues.send(URI, sink.clone(), "lib.rs".into(), VfsUpdate::Close);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(sink.received(), [VfsUpdate::Close]);
+    }
+
+    #[tokio::test]
+    async fn updates_to_a_document_arrive_in_order() {
+        // Each delivery takes longer than the debounce, so the change is still
+        // in flight when the close and the reopen are queued.
+        let sink = RecordingSink::new(Duration::from_millis(100));
+        let mut queues = VfsQueues::new(Duration::from_millis(10));
+        queues.schedule_change(URI, sink.clone(), "lib.rs".into(), "edit".into());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!queues.cancel(URI), "the change already left the debounce");
+        queues.send(URI, sink.clone(), "lib.rs".into(), VfsUpdate::Close);
+        queues.send(
+            URI,
+            sink.clone(),
+            "lib.rs".into(),
+            VfsUpdate::Open("again".into()),
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(
+            sink.received(),
+            [
+                change("edit"),
+                VfsUpdate::Close,
+                VfsUpdate::Open("again".into())
+            ]
+        );
+    }
+
+    // r[verify lsp.diagnostics.incremental]
+    #[test]
+    fn only_changed_diagnostics_are_published() {
+        let diagnostic = |message: &str| Diagnostic {
+            message: message.to_string(),
+            ..Default::default()
+        };
+        let previous = HashMap::from([
+            ("/p/same.rs".to_string(), vec![diagnostic("unchanged")]),
+            ("/p/edited.rs".to_string(), vec![diagnostic("old")]),
+            ("/p/fixed.rs".to_string(), vec![diagnostic("gone")]),
+        ]);
+        let next = HashMap::from([
+            ("/p/same.rs".to_string(), vec![diagnostic("unchanged")]),
+            ("/p/edited.rs".to_string(), vec![diagnostic("new")]),
+            ("/p/broken.rs".to_string(), vec![diagnostic("fresh")]),
+        ]);
+
+        let mut published = diagnostics_to_publish(&previous, next.clone());
+        published.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            published,
+            [
+                ("/p/broken.rs".to_string(), vec![diagnostic("fresh")]),
+                ("/p/edited.rs".to_string(), vec![diagnostic("new")]),
+                ("/p/fixed.rs".to_string(), Vec::new()),
+            ]
+        );
+
+        assert!(diagnostics_to_publish(&next, next.clone()).is_empty());
+    }
+
     #[test]
     fn symbol_uri_from_relative_path_resolves_under_project_root() {
         let project_root = PathBuf::from("/tmp/project");
@@ -1825,4 +2326,43 @@ mod tests {
             Some(PathBuf::from("/preferred/root"))
         );
     }
+
+    // r[verify lsp.settings]
+    #[test]
+    fn settings_read_bare_or_under_tracey_key() {
+        let settings = LspSettings::from_json(&serde_json::json!({
+            "tracey": {
+                "diagnostics": { "severity": { "stale": "error", "orphaned": "off", "x": "loud" } },
+                "inlayHints": { "enabled": false },
+                "completion": { "limit": 20 },
+                "fileTypes": ["rs", ".md"]
+            }
+        }));
+        assert_eq!(
+            settings.severity_for("stale", DiagnosticSeverity::WARNING),
+            Some(DiagnosticSeverity::ERROR)
+        );
+        assert_eq!(
+            settings.severity_for("orphaned", DiagnosticSeverity::HINT),
+            None
+        );
+        assert_eq!(
+            settings.severity_for("x", DiagnosticSeverity::WARNING),
+            Some(DiagnosticSeverity::WARNING)
+        );
+        assert!(!settings.inlay_hints);
+        assert_eq!(settings.completion_limit, Some(20));
+        assert!(settings.serves(Path::new("/p/src/lib.rs")));
+        assert!(settings.serves(Path::new("/p/docs/spec.md")));
+        assert!(!settings.serves(Path::new("/p/web/app.ts")));
+
+        let bare = LspSettings::from_json(&serde_json::json!({ "completion": { "limit": 5 } }));
+        assert_eq!(bare.completion_limit, Some(5));
+        assert!(bare.inlay_hints);
+        assert!(bare.serves(Path::new("/p/web/app.ts")));
+        assert_eq!(
+            LspSettings::from_json(&serde_json::Value::Null),
+            LspSettings::default()
+        );
+    }
 }
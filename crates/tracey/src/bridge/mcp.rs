@@ -116,6 +116,25 @@ pub struct RuleTool {
     pub rule_id: String,
 }
 
+/// r[impl mcp.tool.annotation-snippet]
+///
+/// Format a ready-to-paste annotation comment for a rule
+#[mcp_tool(
+    name = "tracey_annotation_snippet",
+    description = "Get a correctly formatted annotation comment for a rule in a given language (e.g. `// r[impl auth.login]`, `# r[impl auth.login]`, `<!-- r[impl auth.login] -->`). Uses the rule's current version. Requires `cwd` (absolute workspace path)."
+)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct AnnotationSnippetTool {
+    /// Absolute workspace path where Tracey should resolve the project root.
+    pub cwd: String,
+    pub rule_id: String,
+    /// Target language: a file extension ("rs", "py") or language name ("rust", "python")
+    pub language: String,
+    /// Reference verb: impl (default), verify, depends, or related
+    #[serde(default)]
+    pub verb: Option<String>,
+}
+
 /// Display current configuration
 #[mcp_tool(
     name = "tracey_config",
@@ -200,6 +219,7 @@ tool_box!(
         StaleTool,
         UnmappedTool,
         RuleTool,
+        AnnotationSnippetTool,
         ConfigTool,
         ReloadTool,
         ValidateTool,
@@ -623,6 +643,23 @@ impl ServerHandler for TraceyHandler {
                     }
                 }
             }
+            "tracey_annotation_snippet" => {
+                let rule_id = args.get("rule_id").and_then(|v| v.as_str());
+                let language = args.get("language").and_then(|v| v.as_str());
+                let verb = args.get("verb").and_then(|v| v.as_str());
+                match (rule_id, language) {
+                    (Some(id), Some(language)) => {
+                        client.annotation_snippet(id, language, verb).await
+                    }
+                    _ => {
+                        client
+                            .with_config_banner(
+                                "Error: rule_id and language are required".to_string(),
+                            )
+                            .await
+                    }
+                }
+            }
             "tracey_config" => client.config().await,
             "tracey_reload" => client.reload().await,
             "tracey_validate" => {
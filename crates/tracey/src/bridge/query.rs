@@ -19,6 +19,162 @@ pub enum Caller {
     Mcp,
 }
 
+impl Caller {
+    /// Client identity recorded in the daemon's audit log
+    pub fn name(self) -> &'static str {
+        match self {
+            Caller::Cli => "cli",
+            Caller::Mcp => "mcp",
+        }
+    }
+}
+
+/// Render a compare response as a coverage matrix followed by the rules each
+/// implementation is missing
+fn format_compare(response: &CompareResponse) -> String {
+    let covered = |index: usize| {
+        response
+            .rules
+            .iter()
+            .filter(|r| r.coverage[index].impl_count > 0 && !r.coverage[index].is_stale)
+            .count()
+    };
+    let cell = |c: &CompareCoverage| match (c.impl_count > 0, c.verify_count > 0) {
+        _ if c.is_stale => "stale",
+        (true, true) => "impl+verify",
+        (true, false) => "impl",
+        (false, true) => "verify",
+        (false, false) => "-",
+    };
+
+    let counts = response
+        .impls
+        .iter()
+        .enumerate()
+        .map(|(index, name)| format!("{name} covers {}", covered(index)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut output = format!(
+        "{}: {} ({} rules, {counts})\n\n",
+        response.spec,
+        response.impls.join(" vs "),
+        response.rules.len(),
+    );
+
+    output.push_str(&format!(
+        "| Rule | {} |\n|------|",
+        response.impls.join(" | ")
+    ));
+    output.push_str(&"------|".repeat(response.impls.len()));
+    output.push('\n');
+    for rule in &response.rules {
+        let cells = rule.coverage.iter().map(cell).collect::<Vec<_>>();
+        output.push_str(&format!("| {} | {} |\n", rule.id, cells.join(" | ")));
+    }
+
+    for gap in &response.gaps {
+        if !gap.missing.is_empty() {
+            output.push_str(&format!(
+                "\nMissing from {} ({}):\n",
+                gap.impl_name,
+                gap.missing.len()
+            ));
+            for id in &gap.missing {
+                output.push_str(&format!("  - {id}\n"));
+            }
+        }
+    }
+
+    output
+}
+
+/// Render the annotations under a path, grouped by rule and then by verb
+fn format_refs_in(response: &RefsInResponse) -> String {
+    let shown = if response.path.is_empty() {
+        "."
+    } else {
+        response.path.as_str()
+    };
+    let mut output = format!(
+        "{shown}: {} annotation(s) in {} file(s), {} rule(s)\n",
+        response.total,
+        response.files,
+        response.rules.len()
+    );
+
+    for rule in &response.rules {
+        let counts = rule
+            .verbs
+            .iter()
+            .map(|v| format!("{} {}", v.verb, v.locations.len()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("\n{}[{}] {counts}\n", rule.prefix, rule.id));
+        for verb in &rule.verbs {
+            for loc in &verb.locations {
+                output.push_str(&format!("  {:<8}{}:{}\n", verb.verb, loc.file, loc.line));
+            }
+        }
+    }
+
+    output
+}
+
+/// Render a breakdown as a table of groups followed by the groups without spec references
+fn format_breakdown(response: &BreakdownResponse) -> String {
+    let linked = response
+        .groups
+        .iter()
+        .filter(|g| g.impl_count + g.verify_count > 0)
+        .count();
+    let mut output = format!(
+        "{}/{} by {}: {} group(s), {} with spec references\n\n",
+        response.spec,
+        response.impl_name,
+        response.by,
+        response.groups.len(),
+        linked
+    );
+
+    output.push_str(
+        "| Path | Files | Units covered | Impl refs | Verify refs | Rules impl | Rules verified |\n\
+         |------|------:|------:|------:|------:|------:|------:|\n",
+    );
+    for g in &response.groups {
+        let pct = if g.total_units > 0 {
+            g.covered_units as f64 / g.total_units as f64 * 100.0
+        } else {
+            0.0
+        };
+        output.push_str(&format!(
+            "| {} | {} | {}/{} ({:.0}%) | {} | {} | {} | {} |\n",
+            g.path,
+            g.files,
+            g.covered_units,
+            g.total_units,
+            pct,
+            g.impl_count,
+            g.verify_count,
+            g.implemented.len(),
+            g.verified.len()
+        ));
+    }
+
+    let unlinked: Vec<_> = response
+        .groups
+        .iter()
+        .filter(|g| g.impl_count + g.verify_count == 0)
+        .collect();
+    if !unlinked.is_empty() {
+        output.push_str(&format!("\nNo spec references ({}):\n", unlinked.len()));
+        for g in unlinked {
+            output.push_str(&format!("  - {} ({} file(s))\n", g.path, g.files));
+        }
+    }
+
+    output
+}
+
 /// Format config error as a warning banner to prepend to responses
 fn format_config_error_banner(error: &str) -> String {
     format!(
@@ -206,14 +362,31 @@ impl QueryClient {
                     let total = impl_status.total_rules;
                     let covered = impl_status.covered_rules;
                     let stale = impl_status.stale_rules;
-                    let uncovered = total.saturating_sub(covered + stale);
+                    let partial = impl_status.partial_rules;
+                    let uncovered = total.saturating_sub(covered + partial + stale);
                     let verified = impl_status.verified_rules;
 
                     output.push_str(&format!(
-                        "{}/{}: {} of {} requirements are covered.",
-                        impl_status.spec, impl_status.impl_name, covered, total
+                        "{}/{}{}: {} of {} requirements are covered.",
+                        impl_status.spec,
+                        impl_status.impl_name,
+                        if impl_status.advisory {
+                            " (advisory)"
+                        } else {
+                            ""
+                        },
+                        covered,
+                        total
                     ));
 
+                    // r[impl coverage.partial]
+                    if partial > 0 {
+                        output.push_str(&format!(
+                            " {} are only partially implemented (coverage=partial).",
+                            partial
+                        ));
+                    }
+
                     if stale > 0 {
                         output.push_str(&format!(
                             " {} are stale — the spec has been updated since the code was last \
@@ -231,9 +404,29 @@ impl QueryClient {
                     }
 
                     output.push_str(&format!(
-                        " {} of {} have a verification reference.\n",
+                        " {} of {} have a verification reference.",
                         verified, total
                     ));
+
+                    // r[impl mcp.tool.status.docs]
+                    if let Some(documented) = impl_status.documented_rules {
+                        output.push_str(&format!(
+                            " {} of {} have a documentation reference.",
+                            documented, total
+                        ));
+                    }
+                    if let Some(proven) = impl_status.proven_rules {
+                        output
+                            .push_str(&format!(" {} of {} are formally verified.", proven, total));
+                    }
+                    output.push('\n');
+                }
+
+                if status.impls.iter().any(|s| s.advisory) {
+                    output.push_str(
+                        "\nAdvisory specs are tracked for reference: their references are \
+                         validated, but their coverage never counts toward thresholds.\n",
+                    );
                 }
 
                 output.push_str("\n---\n");
@@ -293,11 +486,17 @@ impl QueryClient {
                     if !section.rules.is_empty() {
                         output.push_str(&format!("## {}\n", section.section));
                         for rule in &section.rules {
-                            output.push_str(&format!("  - {}\n", rule.id));
+                            match &rule.text {
+                                Some(shortfall) => {
+                                    output.push_str(&format!("  - {} ({shortfall})\n", rule.id))
+                                }
+                                None => output.push_str(&format!("  - {}\n", rule.id)),
+                            }
                         }
                         output.push('\n');
                     }
                 }
+                push_waived(&mut output, &response.waived);
 
                 output.push_str("---\n");
                 output.push_str(&self.hint(
@@ -344,11 +543,17 @@ impl QueryClient {
                     if !section.rules.is_empty() {
                         output.push_str(&format!("## {}\n", section.section));
                         for rule in &section.rules {
-                            output.push_str(&format!("  - {}\n", rule.id));
+                            match &rule.text {
+                                Some(note) => {
+                                    output.push_str(&format!("  - {} ({note})\n", rule.id))
+                                }
+                                None => output.push_str(&format!("  - {}\n", rule.id)),
+                            }
                         }
                         output.push('\n');
                     }
                 }
+                push_waived(&mut output, &response.waived);
 
                 output.push_str("---\n");
                 output.push_str(&self.hint(
@@ -549,6 +754,96 @@ impl QueryClient {
         self.with_config_banner(output).await
     }
 
+    /// Format a clipboard-ready annotation comment for a rule
+    pub async fn annotation_snippet(
+        &self,
+        rule_id: &str,
+        language: &str,
+        verb: Option<&str>,
+    ) -> String {
+        let Some(rule_id) = parse_rule_id(rule_id) else {
+            return "Error: invalid rule ID".to_string();
+        };
+
+        let req = AnnotationSnippetRequest {
+            rule_id,
+            language: language.to_string(),
+            verb: verb.map(String::from),
+        };
+
+        let output = match self.client.annotation_snippet(req).await {
+            Ok(snippet) => format!(
+                "{}\n\n---\nPlace this comment directly above the code that {} `{}` ({}).\n",
+                snippet.snippet,
+                match verb.unwrap_or("impl") {
+                    "verify" => "verifies",
+                    "depends" => "depends on",
+                    "related" => "relates to",
+                    _ => "implements",
+                },
+                snippet.rule_id,
+                snippet.spec
+            ),
+            Err(roam::RoamError::User(message)) => format!("Error: {message}"),
+            Err(e) => format!("Error: {e:?}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    /// Compare rule coverage of several implementations side by side
+    pub async fn compare(&self, spec: Option<&str>, impls: &[String]) -> String {
+        let req = CompareRequest {
+            spec: spec.map(String::from),
+            impls: impls.to_vec(),
+        };
+
+        let output = match self.client.compare(req).await {
+            Ok(response) => format_compare(&response),
+            Err(roam::RoamError::User(message)) => format!("Error: {message}"),
+            Err(e) => format!("Error: {e:?}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    /// List every annotation under a path, grouped by rule and verb
+    pub async fn refs_in(&self, path: &str) -> String {
+        let req = RefsInRequest {
+            path: path.to_string(),
+        };
+
+        let output = match self.client.refs_in(req).await {
+            Ok(response) => format_refs_in(&response),
+            Err(e) => format!("Error: {e:?}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    /// Coverage rolled up by directory or crate
+    pub async fn breakdown(
+        &self,
+        spec: Option<&str>,
+        impl_name: Option<&str>,
+        by: Option<&str>,
+        depth: Option<usize>,
+    ) -> String {
+        let req = BreakdownRequest {
+            spec: spec.map(String::from),
+            impl_name: impl_name.map(String::from),
+            by: by.map(String::from),
+            depth,
+        };
+
+        let output = match self.client.breakdown(req).await {
+            Ok(response) => format_breakdown(&response),
+            Err(e) => format!("Error: {e:?}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
     /// Display current configuration
     pub async fn config(&self) -> String {
         let output = match self.client.config().await {
@@ -682,6 +977,9 @@ impl QueryClient {
                                         .iter()
                                         .filter(|e| {
                                             e.code != ValidationErrorCode::UnknownRequirement
+                                                && e.code != ValidationErrorCode::SkippedFile
+                                                && e.code != ValidationErrorCode::OwnershipConflict
+                                                && e.code != ValidationErrorCode::EmptyWildcard
                                         })
                                         .count(),
                                 };
@@ -749,6 +1047,7 @@ impl QueryClient {
             spec,
             impl_name,
             pattern: pattern.to_string(),
+            client: Some(self.caller.name().to_string()),
         };
 
         let output = match self.client.config_add_exclude(req).await {
@@ -769,6 +1068,7 @@ impl QueryClient {
             spec,
             impl_name,
             pattern: pattern.to_string(),
+            client: Some(self.caller.name().to_string()),
         };
 
         let output = match self.client.config_add_include(req).await {
@@ -780,6 +1080,23 @@ impl QueryClient {
     }
 }
 
+/// The "Waived" section of uncovered/untested output: accepted gaps, with
+/// the waiver's reason, that are not counted above.
+fn push_waived(output: &mut String, waived: &[RuleRef]) {
+    if waived.is_empty() {
+        return;
+    }
+    output.push_str(&format!("## Waived ({})\n", waived.len()));
+    for rule in waived {
+        output.push_str(&format!(
+            "  - {}: {}\n",
+            rule.id,
+            rule.text.as_deref().unwrap_or_default()
+        ));
+    }
+    output.push('\n');
+}
+
 /// Format a single rule's information for display.
 fn format_rule_info(info: &RuleInfo) -> String {
     let mut output = format!("# {}\n\n{}\n\n", info.id, info.raw);
@@ -790,6 +1107,19 @@ fn format_rule_info(info: &RuleInfo) -> String {
         output.push_str(&format!("Defined in: {}:{}\n\n", file, line));
     }
 
+    if !info.section_path.is_empty() {
+        output.push_str(&format!("Section: {}\n\n", info.section_path.join(" > ")));
+    }
+
+    if let Some(owner) = &info.owner {
+        output.push_str(&format!("Owner: {owner}\n\n"));
+    }
+
+    if !info.related.is_empty() {
+        let related: Vec<_> = info.related.iter().map(ToString::to_string).collect();
+        output.push_str(&format!("Related: {}\n\n", related.join(", ")));
+    }
+
     if let Some(diff) = &info.version_diff {
         output.push_str(&format!("## Changes from previous version\n\n{diff}\n\n"));
     }
@@ -799,13 +1129,13 @@ fn format_rule_info(info: &RuleInfo) -> String {
         if !cov.impl_refs.is_empty() {
             output.push_str("Impl references:\n");
             for r in &cov.impl_refs {
-                output.push_str(&format!("  - {}:{}\n", r.file, r.line));
+                output.push_str(&format!("  - {}:{}{}\n", r.file, r.line, note_suffix(r)));
             }
         }
         if !cov.verify_refs.is_empty() {
             output.push_str("Verify references:\n");
             for r in &cov.verify_refs {
-                output.push_str(&format!("  - {}:{}\n", r.file, r.line));
+                output.push_str(&format!("  - {}:{}{}\n", r.file, r.line, note_suffix(r)));
             }
         }
     }
@@ -813,6 +1143,20 @@ fn format_rule_info(info: &RuleInfo) -> String {
     output
 }
 
+/// ` — note` for a reference carrying a note or marked partial, and
+/// nothing otherwise.
+///
+/// r[impl ref.syntax.note]
+/// r[impl ref.coverage.partial]
+fn note_suffix(code_ref: &ApiCodeRef) -> String {
+    match (code_ref.partial, code_ref.note.as_deref()) {
+        (true, Some(note)) => format!(" — partial: {note}"),
+        (true, None) => " — partial".to_string(),
+        (false, Some(note)) => format!(" — {note}"),
+        (false, None) => String::new(),
+    }
+}
+
 /// Format a validation result for display.
 fn format_validation_result(result: &tracey_proto::ValidationResult) -> String {
     if result.errors.is_empty() {
@@ -821,10 +1165,17 @@ fn format_validation_result(result: &tracey_proto::ValidationResult) -> String {
             result.spec, result.impl_name
         )
     } else {
-        let mut output = format!(
-            "✗ {}/{}: {} error(s) found\n",
-            result.spec, result.impl_name, result.error_count
-        );
+        let mut output = if result.error_count == 0 {
+            format!(
+                "✓ {}/{}: No validation errors found, {} warning(s)\n",
+                result.spec, result.impl_name, result.warning_count
+            )
+        } else {
+            format!(
+                "✗ {}/{}: {} error(s) found\n",
+                result.spec, result.impl_name, result.error_count
+            )
+        };
 
         for error in &result.errors {
             let location = match (&error.file, error.line) {
@@ -880,11 +1231,16 @@ fn format_validation_result(result: &tracey_proto::ValidationResult) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{format_rule_info, format_validation_result, validate_spec_impl_selection};
+    use super::{
+        format_breakdown, format_compare, format_refs_in, format_rule_info,
+        format_validation_result, validate_spec_impl_selection,
+    };
     use tracey_api::{ApiConfig, ApiSpecInfo};
     use tracey_core::parse_rule_id;
     use tracey_proto::{
-        ApiCodeRef, RuleCoverage, RuleInfo, ValidationError, ValidationErrorCode, ValidationResult,
+        ApiCodeRef, BreakdownGroup, BreakdownResponse, CompareCoverage, CompareGap,
+        CompareResponse, CompareRule, RefsInResponse, RefsInRule, RefsInVerb, RuleCoverage,
+        RuleInfo, ValidationError, ValidationErrorCode, ValidationResult,
     };
 
     fn sample_config() -> ApiConfig {
@@ -897,6 +1253,7 @@ mod tests {
                     source: None,
                     source_url: None,
                     implementations: vec!["rust".to_string(), "typescript".to_string()],
+                    advisory: false,
                 },
                 ApiSpecInfo {
                     name: "other".to_string(),
@@ -904,8 +1261,10 @@ mod tests {
                     source: None,
                     source_url: None,
                     implementations: vec!["rust".to_string()],
+                    advisory: false,
                 },
             ],
+            verbs: Vec::new(),
         }
     }
 
@@ -982,16 +1341,22 @@ mod tests {
             html: format!("<p>Rule text for {}</p>", base),
             source_file: Some("docs/spec.md".to_string()),
             source_line: Some(10),
+            section_path: vec![],
+            owner: None,
             coverage: vec![RuleCoverage {
                 spec: "test-spec".to_string(),
                 impl_name: "main".to_string(),
                 impl_refs: vec![ApiCodeRef {
                     file: "src/lib.rs".to_string(),
                     line: 42,
+                    end_line: None,
+                    note: None,
+                    partial: false,
                 }],
                 verify_refs: vec![],
             }],
             version_diff: None,
+            related: vec![],
         }
     }
 
@@ -1034,6 +1399,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn format_rule_info_lists_related_rules() {
+        let mut info = make_rule_info("foo.bar", 1);
+        info.related = vec![parse_rule_id("foo.baz").unwrap()];
+        let output = format_rule_info(&info);
+        assert!(output.contains("Related: foo.baz\n"), "output:\n{}", output);
+    }
+
+    #[test]
+    fn format_rule_info_shows_section_path() {
+        let mut info = make_rule_info("foo.bar", 1);
+        info.section_path = vec!["Transport".to_string(), "Flow Control".to_string()];
+        let output = format_rule_info(&info);
+        assert!(
+            output.contains("Section: Transport > Flow Control\n"),
+            "output:\n{}",
+            output
+        );
+    }
+
+    // r[verify markdown.syntax.owner]
+    #[test]
+    fn format_rule_info_shows_owner() {
+        let mut info = make_rule_info("foo.bar", 1);
+        info.owner = Some("@identity".to_string());
+        let output = format_rule_info(&info);
+        assert!(output.contains("Owner: @identity\n"), "output:\n{}", output);
+    }
+
     #[test]
     fn format_rule_info_no_coverage() {
         let mut info = make_rule_info("lonely.rule", 1);
@@ -1052,4 +1446,164 @@ mod tests {
             output
         );
     }
+
+    // r[verify cli.compare]
+    #[test]
+    fn format_compare_shows_matrix_and_missing_rules() {
+        let side = |impl_count, verify_count, is_stale| CompareCoverage {
+            impl_count,
+            verify_count,
+            is_stale,
+        };
+        let gap = |impl_name: &str, missing: &[&str]| CompareGap {
+            impl_name: impl_name.to_string(),
+            missing: missing
+                .iter()
+                .map(|id| parse_rule_id(id).unwrap())
+                .collect(),
+        };
+        let response = CompareResponse {
+            spec: "proto".to_string(),
+            impls: vec!["rust".to_string(), "go".to_string(), "swift".to_string()],
+            rules: vec![
+                CompareRule {
+                    id: parse_rule_id("frame.header").unwrap(),
+                    coverage: vec![side(1, 1, false), side(1, 0, false), side(0, 0, false)],
+                },
+                CompareRule {
+                    id: parse_rule_id("frame.body").unwrap(),
+                    coverage: vec![side(2, 0, false), side(0, 0, false), side(1, 0, false)],
+                },
+                CompareRule {
+                    id: parse_rule_id("frame.trailer").unwrap(),
+                    coverage: vec![side(0, 0, false), side(1, 0, true), side(0, 0, false)],
+                },
+            ],
+            gaps: vec![
+                gap("rust", &[]),
+                gap("go", &["frame.body"]),
+                gap("swift", &["frame.header"]),
+            ],
+        };
+
+        let output = format_compare(&response);
+        assert!(
+            output.starts_with(
+                "proto: rust vs go vs swift (3 rules, rust covers 2, go covers 1, swift covers 1)\n"
+            ),
+            "output:\n{output}"
+        );
+        assert!(
+            output.contains("| Rule | rust | go | swift |\n|------|------|------|------|\n"),
+            "output:\n{output}"
+        );
+        assert!(
+            output.contains("| frame.header | impl+verify | impl | - |\n"),
+            "output:\n{output}"
+        );
+        assert!(
+            output.contains("| frame.body | impl | - | impl |\n"),
+            "output:\n{output}"
+        );
+        assert!(
+            output.contains("| frame.trailer | - | stale | - |\n"),
+            "output:\n{output}"
+        );
+        assert!(
+            output.ends_with(
+                "\nMissing from go (1):\n  - frame.body\n\nMissing from swift (1):\n  - frame.header\n"
+            ),
+            "output:\n{output}"
+        );
+        assert!(!output.contains("Missing from rust"), "output:\n{output}");
+    }
+
+    // r[verify cli.refs]
+    #[test]
+    fn format_refs_in_groups_by_rule_then_verb() {
+        let at = |file: &str, line| ApiCodeRef {
+            file: file.to_string(),
+            line,
+            end_line: None,
+            note: None,
+            partial: false,
+        };
+        let response = RefsInResponse {
+            path: "src/auth".to_string(),
+            files: 2,
+            total: 3,
+            rules: vec![RefsInRule {
+                prefix: "r".to_string(),
+                id: parse_rule_id("auth.login").unwrap(),
+                verbs: vec![
+                    RefsInVerb {
+                        verb: "impl".to_string(),
+                        locations: vec![at("src/auth/login.rs", 4), at("src/auth/mod.rs", 9)],
+                    },
+                    RefsInVerb {
+                        verb: "verify".to_string(),
+                        locations: vec![at("src/auth/login.rs", 30)],
+                    },
+                ],
+            }],
+        };
+
+        assert_eq!(
+            format_refs_in(&response),
+            "src/auth: 3 annotation(s) in 2 file(s), 1 rule(s)\n\
+             \n\
+             r[auth.login] impl 2, verify 1\n  \
+             impl    src/auth/login.rs:4\n  \
+             impl    src/auth/mod.rs:9\n  \
+             verify  src/auth/login.rs:30\n"
+        );
+
+        let everything = RefsInResponse {
+            path: String::new(),
+            files: 0,
+            total: 0,
+            rules: Vec::new(),
+        };
+        assert_eq!(
+            format_refs_in(&everything),
+            ".: 0 annotation(s) in 0 file(s), 0 rule(s)\n"
+        );
+    }
+
+    // r[verify cli.breakdown]
+    #[test]
+    fn format_breakdown_lists_groups_then_unlinked_ones() {
+        let group = |path: &str, files, covered_units, impl_count, verify_count| BreakdownGroup {
+            path: path.to_string(),
+            files,
+            total_units: 4,
+            covered_units,
+            impl_count,
+            verify_count,
+            implemented: (0..impl_count)
+                .map(|i| parse_rule_id(&format!("auth.r{i}")).unwrap())
+                .collect(),
+            verified: (0..verify_count)
+                .map(|i| parse_rule_id(&format!("auth.r{i}")).unwrap())
+                .collect(),
+        };
+        let response = BreakdownResponse {
+            spec: "ship".to_string(),
+            impl_name: "rust".to_string(),
+            by: "dir".to_string(),
+            groups: vec![group("src/auth", 2, 3, 2, 1), group("src/util", 1, 0, 0, 0)],
+        };
+
+        assert_eq!(
+            format_breakdown(&response),
+            "ship/rust by dir: 2 group(s), 1 with spec references\n\n\
+             | Path | Files | Units covered | Impl refs | Verify refs | Rules impl | Rules verified |\n\
+             |------|------:|------:|------:|------:|------:|------:|\n\
+             | src/auth | 2 | 3/4 (75%) | 2 | 1 | 2 | 1 |\n\
+             | src/util | 1 | 0/4 (0%) | 0 | 0 | 0 | 0 |\n\
+             \n\
+             No spec references (1):\n\
+             \x20 - src/util (1 file(s))\n"
+        );
+    }
 }
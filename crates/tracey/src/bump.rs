@@ -1,4 +1,4 @@
-//! `tracey pre-commit` and `tracey bump` implementation.
+//! `tracey pre-commit`, `tracey bump` and `tracey install-hooks` implementation.
 //!
 //! These commands work directly on the git index (staged files) and do not
 //! require the daemon. They detect spec rules whose text was modified without
@@ -9,8 +9,10 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use marq::{RenderOptions, render};
+use tracey_core::markdown::edit;
 
 use crate::config::Config;
+use crate::patch::PatchSet;
 
 /// A rule whose text changed in the staged index but whose version was not bumped.
 #[derive(Debug)]
@@ -71,9 +73,35 @@ pub fn git_cat_file(project_root: &Path, revision: &str, path: &str) -> Result<O
         .wrap_err_with(|| format!("content of {spec} is not valid UTF-8"))
 }
 
+/// Files git considers renamed by `git diff <args>`, as a map from the new
+/// path to the old one. Renames are found by similarity, so a file that was
+/// moved and lightly edited in the same change still counts, regardless of
+/// the user's `diff.renames` setting.
+pub fn git_renames(project_root: &Path, args: &[&str]) -> Result<HashMap<String, String>> {
+    let mut full = vec!["diff", "--name-status", "--find-renames"];
+    full.extend_from_slice(args);
+    Ok(parse_renames(&git_capture(project_root, &full)?))
+}
+
+/// Parse `git diff --name-status` output (`R087\told\tnew`) into new → old.
+fn parse_renames(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            if !fields.next()?.starts_with('R') {
+                return None;
+            }
+            let old = fields.next()?;
+            let new = fields.next()?;
+            Some((new.to_string(), old.to_string()))
+        })
+        .collect()
+}
+
 /// Parse a spec file's contents (markdown or `.sdoc`) and return a map from
 /// rule **base** ID → `ReqDefinition`.
-async fn parse_spec_rules(
+pub(crate) async fn parse_spec_rules(
     content: &str,
     path: &str,
 ) -> Result<HashMap<String, marq::ReqDefinition>> {
@@ -85,7 +113,7 @@ async fn parse_spec_rules(
             .collect());
     }
 
-    let doc = render(content, &RenderOptions::default())
+    let doc = render(&crate::examples::strip(content), &RenderOptions::default())
         .await
         .map_err(|e| eyre::eyre!("failed to parse spec: {e}"))?;
 
@@ -131,6 +159,11 @@ pub async fn detect_changed_rules(
         .flat_map(|s| s.include.iter().map(String::as_str))
         .collect();
 
+    // r[impl history.renames]
+    // A moved spec file is compared against its content at the old path, so
+    // editing a rule while moving its file still requires a bump.
+    let renamed_from = git_renames(project_root, &["--cached", "HEAD"])?;
+
     let mut changed_rules = Vec::new();
 
     for staged_file in staged_output.lines() {
@@ -148,7 +181,10 @@ pub async fn detect_changed_rules(
             continue;
         }
 
-        let old_content = git_cat_file(project_root, "HEAD", staged_file)?;
+        let head_path = renamed_from
+            .get(staged_file)
+            .map_or(staged_file, String::as_str);
+        let old_content = git_cat_file(project_root, "HEAD", head_path)?;
         let new_content = match git_cat_file(project_root, "", staged_file)? {
             Some(c) => c,
             None => continue, // deleted — nothing to check
@@ -181,6 +217,16 @@ pub async fn detect_changed_rules(
     Ok(changed_rules)
 }
 
+/// Load the config for `tracey pre-commit` and `tracey bump`. A missing
+/// config means there are no specs to check, but one that fails to load is
+/// an error: falling back to no specs would let every change through.
+pub fn load_config(config_path: &Path) -> Result<Config> {
+    if !config_path.exists() {
+        return Ok(Config::default());
+    }
+    crate::load_config(&config_path.to_path_buf())
+}
+
 /// Check staged spec changes and exit non-zero if any rule text changed without
 /// a version bump. Intended to be called from a git pre-commit hook.
 ///
@@ -206,77 +252,187 @@ pub async fn pre_commit(project_root: &Path, config: &Config) -> Result<bool> {
     Ok(false)
 }
 
-/// Bump the version of every staged rule whose text changed, then re-stage the
-/// affected files.
+/// Version bumps planned by [`plan_bump`].
+#[derive(Debug)]
+pub struct BumpPlan {
+    /// The rules with their new versions.
+    pub bumped: Vec<marq::RuleId>,
+    /// The rewritten spec files, computed from their staged contents.
+    pub patch: PatchSet,
+}
+
+/// Plan a version bump for every staged rule whose text changed, without
+/// writing anything.
 ///
-/// Edits are applied last-to-first within each file so that earlier byte
-/// offsets are not invalidated by preceding edits.
-pub async fn bump(project_root: &Path, config: &Config) -> Result<Vec<marq::RuleId>> {
+/// Markers are rewritten with [`tracey_core::markdown::edit`], so only the
+/// rule ID token of each bumped marker changes.
+pub async fn plan_bump(project_root: &Path, config: &Config) -> Result<BumpPlan> {
     let changes = detect_changed_rules(project_root, config).await?;
 
-    if changes.is_empty() {
-        return Ok(vec![]);
-    }
-
     // Group changes by file.
     let mut by_file: HashMap<PathBuf, Vec<usize>> = HashMap::new();
     for (i, change) in changes.iter().enumerate() {
         by_file.entry(change.file.clone()).or_default().push(i);
     }
 
-    let mut bumped_ids = Vec::new();
+    let mut plan = BumpPlan {
+        bumped: Vec::new(),
+        patch: PatchSet::new(project_root),
+    };
 
     for (file, indices) in &by_file {
         let file_str = file.to_string_lossy();
         let content = git_cat_file(project_root, "", &file_str)?
             .ok_or_else(|| eyre::eyre!("file disappeared from index: {}", file.display()))?;
 
-        let mut bytes = content.into_bytes();
-
-        // Sort indices so we apply edits from last byte offset to first.
-        let mut sorted_indices = indices.clone();
-        sorted_indices.sort_by(|&a, &b| {
-            changes[b]
-                .marker_span
-                .offset
-                .cmp(&changes[a].marker_span.offset)
-        });
-
-        for &idx in &sorted_indices {
+        let markers = edit::find_rule_markers(&content);
+        let mut edits = Vec::new();
+        for &idx in indices {
             let change = &changes[idx];
             let new_version = change.rule_id.version + 1;
 
-            // Extract the prefix (chars before `[`) from the current marker bytes.
-            let span = change.marker_span;
-            let marker_bytes = &bytes[span.offset..span.offset + span.length];
-            let marker_str =
-                std::str::from_utf8(marker_bytes).wrap_err("marker is not valid UTF-8")?;
-            let bracket = marker_str
-                .find('[')
-                .ok_or_else(|| eyre::eyre!("malformed marker: {}", marker_str))?;
-            let prefix = &marker_str[..bracket];
-
-            // Build the new marker, e.g. `r[auth.login+2]`.
-            let new_marker = format!("{}[{}+{}]", prefix, change.rule_id.base, new_version);
-
-            let start = span.offset;
-            let end = start + span.length;
-            bytes.splice(start..end, new_marker.into_bytes());
-
-            bumped_ids.push(marq::RuleId {
+            let marker = markers
+                .iter()
+                .find(|m| m.span.start == change.marker_span.offset)
+                .or_else(|| markers.iter().find(|m| m.id.base == change.rule_id.base))
+                .ok_or_else(|| {
+                    eyre::eyre!(
+                        "marker for {} not found in {}",
+                        change.rule_id,
+                        file.display()
+                    )
+                })?;
+            edits.push(marker.set_version(new_version));
+
+            plan.bumped.push(marq::RuleId {
                 base: change.rule_id.base.clone(),
                 version: new_version,
             });
         }
+        let bumped = edit::apply_edits(&content, edits)
+            .wrap_err_with(|| format!("failed to edit {}", file.display()))?;
+        plan.patch.set(file, content, bumped);
+    }
 
-        // Write the modified content back and re-stage.
-        let full_path = project_root.join(file.as_path());
-        std::fs::write(&full_path, &bytes)
-            .wrap_err_with(|| format!("failed to write {}", full_path.display()))?;
+    Ok(plan)
+}
 
+/// Re-stage the files of an applied bump.
+pub fn stage_bump(project_root: &Path, plan: &BumpPlan) -> Result<()> {
+    for file in plan.patch.files() {
+        let file_str = file.to_string_lossy();
         git_capture(project_root, &["update-index", "--add", "--", &file_str])
             .wrap_err_with(|| format!("failed to re-stage {}", file.display()))?;
     }
+    Ok(())
+}
+
+/// Bump the version of every staged rule whose text changed, then re-stage the
+/// affected files.
+///
+/// The spec files are written together through [`PatchSet::apply`], which
+/// refuses to overwrite a file whose working copy differs from what is staged.
+pub async fn bump(project_root: &Path, config: &Config) -> Result<Vec<marq::RuleId>> {
+    let plan = plan_bump(project_root, config).await?;
+    plan.patch.apply()?;
+    stage_bump(project_root, &plan)?;
+    Ok(plan.bumped)
+}
+
+/// A git hook that `tracey install-hooks` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Runs `tracey pre-commit`.
+    PreCommit,
+    /// Runs `tracey prepare-commit-msg`.
+    PrepareCommitMsg,
+}
+
+impl Hook {
+    /// File name of the hook in the hooks directory.
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreCommit => "pre-commit",
+            Hook::PrepareCommitMsg => "prepare-commit-msg",
+        }
+    }
+
+    /// Contents of the hook script. Hooks run from the top of the work tree,
+    /// so a project root below it is passed as a relative `--root`.
+    fn script(self, root: &str) -> String {
+        let root = if root.is_empty() {
+            String::new()
+        } else {
+            format!("--root {} ", shell_quote(root))
+        };
+        let args = match self {
+            Hook::PreCommit => "",
+            Hook::PrepareCommitMsg => " \"$@\"",
+        };
+        format!(
+            "#!/bin/sh\n# Installed by `tracey install-hooks`.\nexec tracey {root}{}{args}\n",
+            self.name()
+        )
+    }
+}
+
+/// Quote `s` as a single `sh` word.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// What `install_hooks` did with one hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutcome {
+    /// The hook was written.
+    Installed(PathBuf),
+    /// The hook already runs tracey; left as is.
+    AlreadyInstalled(PathBuf),
+    /// A different hook exists and was left alone.
+    Skipped(PathBuf),
+}
+
+/// Write git hooks that run tracey into the repository containing
+/// `project_root`, honoring `core.hooksPath`.
+///
+/// An existing hook that doesn't already run tracey is only replaced when
+/// `force` is set.
+pub fn install_hooks(project_root: &Path, hooks: &[Hook], force: bool) -> Result<Vec<HookOutcome>> {
+    let hooks_dir = git_capture(project_root, &["rev-parse", "--git-path", "hooks"])?;
+    let hooks_dir = project_root.join(hooks_dir.trim());
+    let prefix = git_capture(project_root, &["rev-parse", "--show-prefix"])?;
+    let root = prefix.trim().trim_end_matches('/');
+
+    std::fs::create_dir_all(&hooks_dir)
+        .wrap_err_with(|| format!("failed to create {}", hooks_dir.display()))?;
+
+    let mut outcomes = Vec::new();
+    for &hook in hooks {
+        let path = hooks_dir.join(hook.name());
+        if let Ok(existing) = std::fs::read_to_string(&path) {
+            if existing
+                .lines()
+                .any(|line| line.contains("tracey") && line.contains(hook.name()))
+            {
+                outcomes.push(HookOutcome::AlreadyInstalled(path));
+                continue;
+            }
+            if !force {
+                outcomes.push(HookOutcome::Skipped(path));
+                continue;
+            }
+        }
+
+        std::fs::write(&path, hook.script(root))
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .wrap_err_with(|| format!("failed to make {} executable", path.display()))?;
+        }
+        outcomes.push(HookOutcome::Installed(path));
+    }
 
-    Ok(bumped_ids)
+    Ok(outcomes)
 }
@@ -0,0 +1,327 @@
+//! `tracey check --changed-since` implementation.
+//!
+//! A fast check for pull requests in large repositories: only the source
+//! files changed since a git revision (committed, staged, unstaged or
+//! untracked) are extracted, so the report covers the rules those files
+//! reference and the rules whose spec text changed, not the whole project.
+//! Each implementation's include patterns are narrowed to the exact paths of
+//! its changed files before the build; specs are always read in full, since they are small.
+
+use eyre::{Result, WrapErr};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::bump::git_capture;
+use crate::config::Config;
+use crate::spec_diff::SpecDiff;
+
+/// References from changed files to one rule, in one implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferencedRule {
+    pub spec: String,
+    pub impl_name: String,
+    pub id: String,
+    pub impl_refs: usize,
+    pub verify_refs: usize,
+}
+
+/// An error located in a changed file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Problem {
+    /// Path relative to the project root.
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// What changed since a revision, as far as the specs are concerned.
+#[derive(Debug)]
+pub struct CheckReport {
+    /// Revision the working tree was compared against.
+    pub base: String,
+    /// Changed files some implementation scans, relative to the project root.
+    pub scanned: Vec<String>,
+    /// Rules the changed files reference.
+    pub rules: Vec<ReferencedRule>,
+    /// Rules added, removed or changed in the specs.
+    pub spec_changes: SpecDiff,
+    /// Validation errors in the changed files.
+    pub problems: Vec<Problem>,
+}
+
+impl CheckReport {
+    /// Render the report as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Changes since `{}`", self.base);
+        let _ = writeln!(out);
+        if self.scanned.is_empty() {
+            let _ = writeln!(out, "No changed file is scanned by an implementation.");
+        } else {
+            let _ = writeln!(out, "Scanned {} changed file(s):", self.scanned.len());
+            for file in &self.scanned {
+                let _ = writeln!(out, "- {file}");
+            }
+        }
+
+        if !self.rules.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## Rules referenced by changed files");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Rule | Implementation | impl | verify |");
+            let _ = writeln!(out, "|------|----------------|-----:|-------:|");
+            for rule in &self.rules {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {}/{} | {} | {} |",
+                    rule.id, rule.spec, rule.impl_name, rule.impl_refs, rule.verify_refs
+                );
+            }
+        }
+
+        if !self.spec_changes.specs.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## Spec changes");
+            let _ = writeln!(out);
+            out.push_str(&self.spec_changes.to_markdown().replace("\n## ", "\n### "));
+        }
+
+        let _ = writeln!(out);
+        if self.problems.is_empty() {
+            let _ = writeln!(out, "No problems in the changed files.");
+        } else {
+            let _ = writeln!(out, "## Problems");
+            let _ = writeln!(out);
+            for problem in &self.problems {
+                match problem.line {
+                    Some(line) => {
+                        let _ = writeln!(out, "- {}:{line}: {}", problem.file, problem.message);
+                    }
+                    None => {
+                        let _ = writeln!(out, "- {}: {}", problem.file, problem.message);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Files changed between `base` and the working tree, plus untracked ones,
+/// relative to the project root.
+fn changed_files(project_root: &Path, base: &str) -> Result<BTreeSet<String>> {
+    git_capture(
+        project_root,
+        &["rev-parse", "--verify", &format!("{base}^{{commit}}")],
+    )
+    .wrap_err_with(|| format!("unknown revision `{base}`"))?;
+    let diff = git_capture(
+        project_root,
+        &["diff", "--name-only", "--relative", "--no-renames", base],
+    )?;
+    let untracked = git_capture(
+        project_root,
+        &["ls-files", "--others", "--exclude-standard"],
+    )?;
+    Ok(diff
+        .lines()
+        .chain(untracked.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Include pattern for implementations whose only changed files are tests:
+/// an empty include means `**/*.rs`, and this path matches nothing.
+const NO_SOURCES: &str = ".tracey-no-changed-sources";
+
+/// `config` with each implementation narrowed to the files of `changed` it
+/// scans, and implementations that scan none of them dropped. Returns the
+/// narrowed config and the changed files it scans.
+fn narrow(
+    project_root: &Path,
+    config: &Config,
+    changed: &BTreeSet<String>,
+) -> Result<(Config, BTreeSet<String>)> {
+    let selection = crate::files::files(project_root, config, None, None)?;
+    let mut narrowed = config.clone();
+    let mut scanned = BTreeSet::new();
+    for spec in &mut narrowed.specs {
+        let mut impls = Vec::new();
+        for mut impl_config in std::mem::take(&mut spec.impls) {
+            let Some(files) = selection
+                .impls
+                .iter()
+                .find(|f| f.spec == spec.name && f.impl_name == impl_config.name)
+            else {
+                continue;
+            };
+            let selected: Vec<(String, bool)> = files
+                .selected
+                .iter()
+                .map(|(path, test)| (path.to_string_lossy().replace('\\', "/"), *test))
+                .filter(|(path, _)| changed.contains(path))
+                .collect();
+            if selected.is_empty() {
+                continue;
+            }
+            scanned.extend(selected.iter().map(|(path, _)| path.clone()));
+            // Patterns without `*` are exact paths.
+            let (tests, sources): (Vec<_>, Vec<_>) =
+                selected.into_iter().partition(|(_, test)| *test);
+            impl_config.include = sources.into_iter().map(|(path, _)| path).collect();
+            if impl_config.include.is_empty() {
+                impl_config.include.push(NO_SOURCES.to_string());
+            }
+            impl_config.test_include = tests.into_iter().map(|(path, _)| path).collect();
+            impl_config.exclude = Vec::new();
+            impls.push(impl_config);
+        }
+        spec.impls = impls;
+    }
+    Ok((narrowed, scanned))
+}
+
+/// Check the changes since `base`, extracting only the changed files.
+// r[impl cli.check.changed-since]
+pub async fn check_changed(
+    project_root: &Path,
+    config: &Config,
+    base: &str,
+) -> Result<CheckReport> {
+    let changed = changed_files(project_root, base)?;
+    let (narrowed, scanned) = narrow(project_root, config, &changed)?;
+
+    let old = crate::spec_diff::manifest(project_root, config, Some(base)).await?;
+    let new = crate::spec_diff::manifest(project_root, config, None).await?;
+    let spec_changes = crate::spec_diff::diff(&old, &new);
+
+    let mut rules = Vec::new();
+    let mut problems = BTreeSet::new();
+    if !scanned.is_empty() {
+        let data = crate::data::build_dashboard_data(project_root, &narrowed, 0, true).await?;
+        for ((spec, impl_name), forward) in &data.forward_by_impl {
+            for rule in &forward.rules {
+                if rule.impl_refs.is_empty() && rule.verify_refs.is_empty() {
+                    continue;
+                }
+                rules.push(ReferencedRule {
+                    spec: spec.clone(),
+                    impl_name: impl_name.clone(),
+                    id: rule.id.to_string(),
+                    impl_refs: rule.impl_refs.len(),
+                    verify_refs: rule.verify_refs.len(),
+                });
+            }
+        }
+        // Coverage and threshold errors need the whole project, so only
+        // errors located in a changed file are reported.
+        for result in data.validation_by_impl.values() {
+            for error in &result.errors[..result.error_count] {
+                if let Some(file) = &error.file
+                    && scanned.contains(file)
+                {
+                    problems.insert(Problem {
+                        file: file.clone(),
+                        line: error.line,
+                        message: error.message.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    // Group each rule's implementations together.
+    let mut by_rule: BTreeMap<String, Vec<ReferencedRule>> = BTreeMap::new();
+    for rule in rules {
+        by_rule.entry(rule.id.clone()).or_default().push(rule);
+    }
+    Ok(CheckReport {
+        base: base.to_string(),
+        scanned: scanned.into_iter().collect(),
+        rules: by_rule.into_values().flatten().collect(),
+        spec_changes,
+        problems: problems.into_iter().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(root: &Path, args: &[&str]) {
+        git_capture(root, args).unwrap();
+    }
+
+    // r[verify cli.check.changed-since]
+    #[tokio::test]
+    async fn only_changed_files_are_extracted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+        git(root, &["config", "user.email", "t@example.com"]);
+        git(root, &["config", "user.name", "t"]);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MUST log out.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/login.rs"),
+            "// r[impl auth.login]\nfn a() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/logout.rs"),
+            "// r[impl auth.logout]\nfn b() {}\n",
+        )
+        .unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "base"]);
+
+        std::fs::write(
+            root.join("src/logout.rs"),
+            "// r[impl auth.logout]\n// r[verify auth.lgout]\nfn b() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MUST log out.\n\n\
+             r[auth.expire]\nSessions MUST expire.\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            r#"specs (
+    {
+        name demo
+        include (spec.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap();
+
+        let report = check_changed(root, &config, "HEAD").await.unwrap();
+        assert_eq!(report.scanned, ["src/logout.rs"]);
+        let ids: Vec<&str> = report.rules.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["auth.logout"]);
+        assert_eq!(report.spec_changes.specs[0].added, ["auth.expire"]);
+        assert_eq!(report.problems.len(), 1, "{:?}", report.problems);
+        assert_eq!(report.problems[0].file, "src/logout.rs");
+        assert_eq!(report.problems[0].line, Some(2));
+
+        let markdown = report.to_markdown();
+        assert!(
+            markdown.contains("| `auth.logout` | demo/rust | 1 | 0 |"),
+            "{markdown}"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! `tracey prepare-commit-msg` implementation.
+//!
+//! Summarizes how the staged changes move coverage and appends the summary
+//! to the commit message as a `Tracey-Coverage:` trailer, so coverage
+//! progress shows up in `git log` and can be mined later with
+//! `git log --format='%(trailers:key=Tracey-Coverage)'`. Like `tracey bump`,
+//! this works directly on git and does not require the daemon.
+
+use eyre::{Result, WrapErr};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use tracey_core::{RefVerb, ReqReference, Reqs, RuleId, is_supported_extension};
+
+use crate::bump::{git_capture, git_cat_file};
+use crate::config::Config;
+
+/// Trailer key written to commit messages.
+pub const TRAILER_KEY: &str = "Tracey-Coverage";
+
+/// Verbs in the order they are reported.
+const VERBS: [RefVerb; 5] = [
+    RefVerb::Define,
+    RefVerb::Impl,
+    RefVerb::Verify,
+    RefVerb::Depends,
+    RefVerb::Related,
+];
+
+/// Coverage impact of the staged changes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CoverageDelta {
+    /// Rules that had no `impl` reference at HEAD and have one once staged.
+    pub covered: Vec<RuleId>,
+    /// Rules that lose their last `impl` reference.
+    pub uncovered: Vec<RuleId>,
+    /// References added, per verb, in canonical verb order.
+    pub added: Vec<(RefVerb, usize)>,
+    /// References removed, per verb, in canonical verb order.
+    pub removed: Vec<(RefVerb, usize)>,
+}
+
+impl CoverageDelta {
+    pub fn is_empty(&self) -> bool {
+        self.covered.is_empty()
+            && self.uncovered.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+    }
+
+    /// Trailer value, e.g. `+2 rules covered, 1 verify added`, or `None`
+    /// when nothing changed.
+    pub fn trailer(&self) -> Option<String> {
+        let rules = |n: usize| if n == 1 { "rule" } else { "rules" };
+        let mut parts = Vec::new();
+        if !self.covered.is_empty() {
+            let n = self.covered.len();
+            parts.push(format!("+{n} {} covered", rules(n)));
+        }
+        if !self.uncovered.is_empty() {
+            let n = self.uncovered.len();
+            parts.push(format!("{n} {} uncovered", rules(n)));
+        }
+        for (verb, n) in &self.added {
+            parts.push(format!("{n} {} added", verb.as_str()));
+        }
+        for (verb, n) in &self.removed {
+            parts.push(format!("{n} {} removed", verb.as_str()));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Compare references before and after a change.
+///
+/// `unchanged` are references in files the change does not touch; `before`
+/// and `after` are the references in the touched files on either side. Only
+/// references accepted by `relevant` are counted. A rule is covered when it
+/// has at least one `impl` reference, of any version.
+fn diff_refs(
+    unchanged: &[ReqReference],
+    before: &[ReqReference],
+    after: &[ReqReference],
+    relevant: impl Fn(&ReqReference) -> bool,
+) -> CoverageDelta {
+    let covered = |refs: &[&[ReqReference]]| -> BTreeSet<(String, String)> {
+        refs.iter()
+            .flat_map(|r| r.iter())
+            .filter(|r| r.verb == RefVerb::Impl && relevant(r))
+            .map(|r| (r.prefix.clone(), r.req_id.base.clone()))
+            .collect()
+    };
+    let covered_before = covered(&[unchanged, before]);
+    let covered_after = covered(&[unchanged, after]);
+    let rule_id = |(_, base): &(String, String)| RuleId {
+        base: base.clone(),
+        version: 1,
+    };
+
+    // Match references across both sides by (prefix, verb, rule base), so
+    // moving a reference or bumping its version is not a change; whatever is
+    // left over on either side was added or removed.
+    let key = |r: &ReqReference| (r.prefix.clone(), r.verb.order(), r.req_id.base.clone());
+    let mut counts: BTreeMap<_, isize> = BTreeMap::new();
+    for r in after.iter().filter(|r| relevant(r)) {
+        *counts.entry(key(r)).or_default() += 1;
+    }
+    for r in before.iter().filter(|r| relevant(r)) {
+        *counts.entry(key(r)).or_default() -= 1;
+    }
+    let per_verb = |sign: isize| -> Vec<(RefVerb, usize)> {
+        VERBS
+            .iter()
+            .map(|verb| {
+                let n = counts
+                    .iter()
+                    .filter(|((_, v, _), n)| *v == verb.order() && n.signum() == sign)
+                    .map(|(_, n)| n.unsigned_abs())
+                    .sum();
+                (verb.clone(), n)
+            })
+            .filter(|(_, n)| *n > 0)
+            .collect()
+    };
+
+    CoverageDelta {
+        covered: covered_after
+            .difference(&covered_before)
+            .map(rule_id)
+            .collect(),
+        uncovered: covered_before
+            .difference(&covered_after)
+            .map(rule_id)
+            .collect(),
+        added: per_verb(1),
+        removed: per_verb(-1),
+    }
+}
+
+/// `git diff --name-only` with the given extra arguments, as paths relative
+/// to the project root.
+fn changed_paths(project_root: &Path, args: &[&str]) -> Result<BTreeSet<String>> {
+    let mut full = vec!["diff", "--name-only", "--relative", "--no-renames"];
+    full.extend_from_slice(args);
+    Ok(git_capture(project_root, &full)?
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+fn extract(path: &str, content: Option<String>) -> Vec<ReqReference> {
+    content
+        .map(|c| Reqs::extract_from_content(Path::new(path), &c).references)
+        .unwrap_or_default()
+}
+
+/// Compute the coverage impact of the staged changes against HEAD.
+///
+/// Files outside the staged set contribute their staged (index) contents to
+/// both sides, so unstaged edits do not leak into the summary. Only
+/// references to rules defined in a configured spec are counted.
+// r[impl cli.prepare-commit-msg]
+pub async fn staged_coverage(project_root: &Path, config: &Config) -> Result<CoverageDelta> {
+    let has_head = git_capture(project_root, &["rev-parse", "--verify", "HEAD^{commit}"]).is_ok();
+    let staged = if has_head {
+        changed_paths(project_root, &["--cached", "HEAD"])?
+    } else {
+        // Initial commit: everything in the index is new.
+        git_capture(project_root, &["ls-files", "--cached"])?
+            .lines()
+            .map(String::from)
+            .collect()
+    };
+    let unstaged = changed_paths(project_root, &[])?;
+
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let prefix_of: BTreeMap<&str, &str> = data
+        .config
+        .specs
+        .iter()
+        .map(|s| (s.name.as_str(), s.prefix.as_str()))
+        .collect();
+    let defined: BTreeSet<(&str, &str)> = data
+        .forward_by_impl
+        .iter()
+        .filter_map(|((spec, _), forward)| Some((*prefix_of.get(spec.as_str())?, forward)))
+        .flat_map(|(prefix, forward)| {
+            forward
+                .rules
+                .iter()
+                .map(move |r| (prefix, r.id.base.as_str()))
+        })
+        .collect();
+    let relevant =
+        |r: &ReqReference| defined.contains(&(r.prefix.as_str(), r.req_id.base.as_str()));
+
+    let canonical_root = project_root.canonicalize().ok();
+    let relative = |file: &PathBuf| -> Option<String> {
+        let rel = file
+            .strip_prefix(project_root)
+            .ok()
+            .or_else(|| file.strip_prefix(canonical_root.as_ref()?).ok())
+            .unwrap_or(file);
+        rel.to_str().map(String::from)
+    };
+
+    // Scanned files outside the staged set: same on both sides.
+    let mut scanned = BTreeSet::new();
+    let mut unchanged = Vec::new();
+    for (file, reqs) in &data.source_reqs_by_file {
+        let Some(path) = relative(file) else {
+            continue;
+        };
+        if staged.contains(&path) {
+            scanned.insert(path);
+        } else if unstaged.contains(&path) {
+            unchanged.extend(extract(&path, git_cat_file(project_root, "", &path)?));
+        } else {
+            unchanged.extend(reqs.references.iter().cloned());
+        }
+    }
+
+    // Staged files: HEAD on one side, the index on the other. Files deleted
+    // from the working tree are no longer scanned but still count.
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for path in &staged {
+        let deleted = !project_root.join(path).exists();
+        let counts = scanned.contains(path)
+            || (deleted
+                && Path::new(path)
+                    .extension()
+                    .is_some_and(is_supported_extension));
+        if !counts {
+            continue;
+        }
+        if has_head {
+            before.extend(extract(path, git_cat_file(project_root, "HEAD", path)?));
+        }
+        after.extend(extract(path, git_cat_file(project_root, "", path)?));
+    }
+
+    Ok(diff_refs(&unchanged, &before, &after, relevant))
+}
+
+/// Add (or replace) the `Tracey-Coverage:` trailer in the commit message
+/// file, using `git interpret-trailers` so the trailer block is formatted
+/// the way git expects.
+pub fn write_trailer(project_root: &Path, message_file: &Path, value: &str) -> Result<()> {
+    let trailer = format!("{TRAILER_KEY}: {value}");
+    let message_file = message_file.to_str().ok_or_else(|| {
+        eyre::eyre!(
+            "commit message path {} is not valid UTF-8",
+            message_file.display()
+        )
+    })?;
+    git_capture(
+        project_root,
+        &[
+            "interpret-trailers",
+            "--in-place",
+            "--if-exists",
+            "replace",
+            "--trailer",
+            &trailer,
+            message_file,
+        ],
+    )
+    .wrap_err("failed to add coverage trailer")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refs(source: &str) -> Vec<ReqReference> {
+        Reqs::extract_from_content(Path::new("lib.rs"), source).references
+    }
+
+    #[test]
+    fn counts_newly_covered_rules_and_references() {
+        let unchanged = refs("// r[impl auth.login]\n");
+        let before = refs("// r[verify auth.login]\n");
+        let after = refs(
+            "// r[impl auth.logout]\n// r[impl auth.login]\n// r[verify auth.login]\n// r[verify auth.logout]\n",
+        );
+        let delta = diff_refs(&unchanged, &before, &after, |_| true);
+        assert_eq!(
+            delta.covered,
+            [RuleId {
+                base: "auth.logout".to_string(),
+                version: 1
+            }]
+        );
+        assert!(delta.uncovered.is_empty());
+        assert_eq!(delta.added, [(RefVerb::Impl, 2), (RefVerb::Verify, 1)]);
+        assert_eq!(
+            delta.trailer().as_deref(),
+            Some("+1 rule covered, 2 impl added, 1 verify added")
+        );
+    }
+
+    #[test]
+    fn reports_uncovered_rules_and_removals() {
+        let before = refs("// r[impl auth.login]\n// r[impl data.format]\n// r[impl other.rule]\n");
+        let after = refs("// r[impl data.format+2]\n");
+        let delta = diff_refs(&[], &before, &after, |r| r.req_id.base != "other.rule");
+        assert_eq!(delta.uncovered.len(), 1);
+        assert_eq!(delta.removed, [(RefVerb::Impl, 1)]);
+        assert_eq!(
+            delta.trailer().as_deref(),
+            Some("1 rule uncovered, 1 impl removed")
+        );
+    }
+
+    #[test]
+    fn moved_references_are_not_a_change() {
+        let before = refs("// r[impl auth.login]\n\nfn a() {}\n");
+        let after = refs("fn a() {}\n\n// r[impl auth.login]\n");
+        let delta = diff_refs(&[], &before, &after, |_| true);
+        assert!(delta.is_empty());
+        assert_eq!(delta.trailer(), None);
+    }
+}
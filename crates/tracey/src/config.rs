@@ -5,5 +5,1693 @@
 //!
 //! Config lives at `.config/tracey/config.styx` relative to the project root.
 
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
 // Re-export from tracey-config crate so build.rs can access the types
 pub use tracey_config::*;
+pub use tracey_core::{Enforcement, StringReferences, TestImpls, VerbRole, VerbRoles};
+
+/// How `spec` counts toward coverage thresholds. Invalid values were already
+/// reported when the config was validated and fall back to the default.
+pub fn enforcement(spec: &SpecConfig) -> Enforcement {
+    spec.enforcement
+        .as_deref()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// How `impl` references in `spec`'s test files count. Invalid values were
+/// already reported when the config was validated and fall back to the
+/// default.
+pub fn test_impls(spec: &SpecConfig) -> TestImpls {
+    spec.test_impls
+        .as_deref()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Which implementation of a spec owns a source file that several of its
+/// implementations include.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Ownership {
+    /// The implementation listed first in the config (the default).
+    #[default]
+    First,
+    /// The implementation whose matching pattern names the deepest
+    /// directory; ties go to the one listed first.
+    Specific,
+}
+
+impl std::str::FromStr for Ownership {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "first" => Ok(Self::First),
+            "specific" => Ok(Self::Specific),
+            _ => Err(format!("expected `first` or `specific`, got `{value}`")),
+        }
+    }
+}
+
+/// How files shared by several implementations of `spec` are assigned.
+/// Invalid values were already reported when the config was validated and
+/// fall back to the default.
+pub fn ownership(spec: &SpecConfig) -> Ownership {
+    spec.ownership
+        .as_deref()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Which string literals are scanned for references in `spec`'s
+/// implementations. Unknown kinds were already reported when the config was
+/// validated and are skipped.
+pub fn string_references(spec: &SpecConfig) -> StringReferences {
+    let enabled = |kind: &str| spec.strings.iter().any(|s| s == kind);
+    StringReferences {
+        doc_attributes: enabled("doc-attributes"),
+        test_names: enabled("test-names"),
+    }
+}
+
+/// Weight, from 0 to 1, of a rule of `spec` implemented only by
+/// `coverage=partial` references. Out-of-range values were already
+/// reported when the config was validated and are clamped.
+pub fn partial_weight(spec: &SpecConfig) -> f64 {
+    f64::from(spec.partial_weight.unwrap_or(50).min(100)) / 100.0
+}
+
+/// Coverage roles of all verbs, with the config's `verbs` block applied.
+/// Invalid roles were already reported when the config was validated and
+/// are skipped.
+pub fn verb_roles(config: &Config) -> VerbRoles {
+    config
+        .verbs
+        .iter()
+        .filter_map(|(verb, role)| Some((verb, role.parse().ok()?)))
+        .fold(VerbRoles::default(), |roles, (verb, role)| {
+            roles.with(verb.clone(), role)
+        })
+}
+
+/// Formatters run by `tracey fmt-check`, keyed by file extension: the
+/// built-in ones with the config's `formatters` block applied.
+pub fn formatter_commands(config: &Config) -> BTreeMap<String, String> {
+    let mut commands: BTreeMap<String, String> = [
+        ("rs", "rustfmt --edition 2024"),
+        ("go", "gofmt"),
+        ("js", "prettier --stdin-filepath {path}"),
+        ("jsx", "prettier --stdin-filepath {path}"),
+        ("ts", "prettier --stdin-filepath {path}"),
+        ("tsx", "prettier --stdin-filepath {path}"),
+    ]
+    .into_iter()
+    .map(|(ext, command)| (ext.to_string(), command.to_string()))
+    .collect();
+    for (ext, command) in &config.formatters {
+        if command.trim().is_empty() {
+            commands.remove(ext);
+        } else {
+            commands.insert(ext.clone(), command.clone());
+        }
+    }
+    commands
+}
+
+/// Comment markers of the file types declared in the config's `comments`
+/// block, keyed by file extension. Invalid entries were already reported
+/// when the config was validated and are skipped.
+pub fn comment_delimiters(config: &Config) -> BTreeMap<String, tracey_core::CommentDelimiters> {
+    config
+        .comments
+        .iter()
+        .filter(|(ext, _)| !tracey_core::is_supported_extension(std::ffi::OsStr::new(ext)))
+        .map(|(ext, comment)| {
+            let block = match (&comment.block_start, &comment.block_end) {
+                (Some(start), Some(end)) => vec![(start.clone(), end.clone())],
+                _ => Vec::new(),
+            };
+            let delimiters = tracey_core::CommentDelimiters {
+                line: comment.line.clone(),
+                block,
+            };
+            (ext.clone(), delimiters)
+        })
+        .collect()
+}
+
+/// Annotation comment for `language`, a file extension or language name,
+/// written with the config's `comments` markers when the file type is
+/// declared there. `None` if tracey would not scan such a file.
+pub fn annotation_snippet(
+    config: &Config,
+    prefix: &str,
+    verb: tracey_core::RefVerb,
+    rule_id: &tracey_core::RuleId,
+    language: &str,
+) -> Option<String> {
+    let ext = language.trim().trim_start_matches('.');
+    match comment_delimiters(config).get(ext) {
+        Some(delimiters) => tracey_core::format_annotation_with(prefix, verb, rule_id, delimiters),
+        None => tracey_core::format_annotation(prefix, verb, rule_id, language),
+    }
+}
+
+/// One step of the path to a config value, e.g. `specs`, `0`, `impls`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl From<&str> for PathSegment {
+    fn from(key: &str) -> Self {
+        PathSegment::Key(key.to_string())
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
+}
+
+/// A problem found by [`problems`], with the path of the value it is about,
+/// so diagnostics can point into the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub message: String,
+    pub path: Vec<PathSegment>,
+    /// The config still loads; the problem is only reported.
+    pub warning: bool,
+}
+
+impl Problem {
+    fn new(message: String, path: Vec<PathSegment>) -> Self {
+        Self {
+            message,
+            path,
+            warning: false,
+        }
+    }
+
+    fn warning(message: String, path: Vec<PathSegment>) -> Self {
+        Self {
+            message,
+            path,
+            warning: true,
+        }
+    }
+}
+
+/// One place a spec's rules are read from, resolved from an entry of its
+/// `include` list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecSource {
+    /// A file, or every file in a directory, named without wildcards.
+    Path(PathBuf),
+    /// Files below `base` whose path relative to it matches `glob`.
+    Glob { base: PathBuf, glob: String },
+}
+
+impl SpecSource {
+    /// Resolve one `include` pattern; `None` when it is not a valid glob.
+    pub fn parse(pattern: &str) -> Option<Self> {
+        globset::Glob::new(pattern).ok()?;
+        let (base, suffix) = crate::data::split_glob_prefix(pattern);
+        Some(if suffix.is_empty() {
+            SpecSource::Path(PathBuf::from(base))
+        } else {
+            SpecSource::Glob {
+                base: PathBuf::from(base),
+                glob: suffix.to_string(),
+            }
+        })
+    }
+
+    /// The files this source names under `project_root`, honoring
+    /// `.gitignore`. `None` when the path or the directory the glob starts
+    /// in does not exist.
+    pub fn files(&self, project_root: &Path) -> Option<Vec<PathBuf>> {
+        let (base, glob) = match self {
+            SpecSource::Path(path) => (project_root.join(path), "**/*"),
+            SpecSource::Glob { base, glob } => (project_root.join(base), glob.as_str()),
+        };
+        if !base.exists() {
+            return None;
+        }
+        if base.is_file() {
+            return Some(vec![base]);
+        }
+        let matcher = globset::Glob::new(glob).ok()?.compile_matcher();
+        let walker = ignore::WalkBuilder::new(&base)
+            .follow_links(true)
+            .hidden(false)
+            .git_ignore(true)
+            .build();
+        Some(
+            walker
+                .flatten()
+                .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+                .filter(|entry| {
+                    entry
+                        .path()
+                        .strip_prefix(&base)
+                        .is_ok_and(|rel| matcher.is_match(rel))
+                })
+                .map(|entry| entry.into_path())
+                .collect(),
+        )
+    }
+}
+
+/// Where `spec`'s rules are read from. Without `include` patterns that is
+/// every file in the project, as scans read it; patterns that are not valid
+/// globs are left out (`tracey config check` reports them).
+pub fn spec_sources(spec: &SpecConfig) -> Vec<SpecSource> {
+    if spec.include.is_empty() {
+        return vec![SpecSource::Glob {
+            base: PathBuf::new(),
+            glob: "**/*".to_string(),
+        }];
+    }
+    spec.include
+        .iter()
+        .filter_map(|pattern| SpecSource::parse(pattern))
+        .collect()
+}
+
+/// Check a parsed config for problems the schema cannot express.
+///
+/// All problems are collected and reported together, so a broken config can
+/// be fixed in one pass instead of one reload per mistake. Warnings are
+/// logged and do not stop the config from loading.
+// r[impl config.validate]
+pub fn validate(config: &Config) -> eyre::Result<()> {
+    let (warnings, errors): (Vec<Problem>, Vec<Problem>) =
+        problems(config).into_iter().partition(|p| p.warning);
+    for warning in warnings {
+        tracing::warn!("{}", warning.message);
+    }
+    let problems: Vec<String> = errors.into_iter().map(|p| p.message).collect();
+    match problems.as_slice() {
+        [] => Ok(()),
+        [problem] => Err(eyre::eyre!("{problem}")),
+        _ => Err(eyre::eyre!(
+            "{} problems found:\n{}",
+            problems.len(),
+            problems
+                .iter()
+                .map(|p| format!("- {p}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+/// Every problem [`validate`] reports, in config order.
+pub fn problems(config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut spec_names = HashSet::new();
+
+    for (i, spec) in config.specs.iter().enumerate() {
+        let at = |rest: &[PathSegment]| {
+            let mut path = vec!["specs".into(), i.into()];
+            path.extend_from_slice(rest);
+            path
+        };
+        if !spec_names.insert(spec.name.as_str()) {
+            problems.push(Problem::new(
+                format!(
+                    "Spec name '{}' is used more than once. Spec names must be unique.",
+                    spec.name
+                ),
+                at(&["name".into()]),
+            ));
+        }
+
+        if let Some(prefix) = &spec.prefix {
+            // r[impl config.spec.prefix+2]
+            problems.push(Problem::new(
+                format!(
+                    "Spec '{}' uses deprecated `prefix {}` in config. \
+                     Remove the `prefix` field from this spec config. Tracey now infers prefixes \
+                     directly from requirement markers in spec files (for example `r[...]`).",
+                    spec.name, prefix
+                ),
+                at(&["prefix".into()]),
+            ));
+        }
+
+        if spec.include.is_empty() {
+            problems.push(Problem::warning(
+                format!(
+                    "Spec '{}' has no `include` patterns, so every spec file in the project is \
+                     read for its rules. Add glob patterns for its spec files, \
+                     e.g. `include (docs/spec/**/*.md)`.",
+                    spec.name
+                ),
+                at(&[]),
+            ));
+        }
+
+        if spec.impls.is_empty() {
+            problems.push(Problem::warning(
+                format!(
+                    "Spec '{}' has no implementations defined, so none of its rules can be \
+                     covered. Add at least one impl block to its `impls` list, \
+                     e.g. `impls ({{ name main include (src/**/*.rs) }})`.",
+                    spec.name
+                ),
+                at(&[]),
+            ));
+        }
+
+        for (rule, value) in &spec.fanout {
+            if let Err(err) = value.parse::<Fanout>() {
+                problems.push(Problem::new(
+                    format!(
+                        "Spec '{}' has an invalid fanout for '{rule}': {err}",
+                        spec.name
+                    ),
+                    at(&["fanout".into(), rule.as_str().into()]),
+                ));
+            }
+        }
+
+        if let Some(value) = spec.threshold
+            && value > 100
+        {
+            problems.push(Problem::new(
+                format!(
+                    "Spec '{}' has threshold {value}; it must be a percentage from 0 to 100.",
+                    spec.name
+                ),
+                at(&["threshold".into()]),
+            ));
+        }
+        for (prefix, value) in &spec.thresholds {
+            if *value > 100 {
+                problems.push(Problem::new(
+                    format!(
+                        "Spec '{}' has threshold for '{prefix}' {value}; it must be a \
+                         percentage from 0 to 100.",
+                        spec.name
+                    ),
+                    at(&["thresholds".into(), prefix.as_str().into()]),
+                ));
+            }
+        }
+        if let Some(value) = spec.partial_weight
+            && value > 100
+        {
+            problems.push(Problem::new(
+                format!(
+                    "Spec '{}' has partial_weight {value}; it must be a percentage from 0 to 100.",
+                    spec.name
+                ),
+                at(&["partial_weight".into()]),
+            ));
+        }
+
+        if let Some(sandbox) = &spec.sandbox {
+            for (j, scheme) in sandbox.url_schemes.iter().enumerate() {
+                let shaped = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+                if !shaped {
+                    problems.push(Problem::new(
+                        format!(
+                            "Spec '{}' allows an invalid URL scheme '{scheme}' in its sandbox. \
+                             List schemes without the colon, e.g. `url_schemes (https)`.",
+                            spec.name
+                        ),
+                        at(&["sandbox".into(), "url_schemes".into(), j.into()]),
+                    ));
+                }
+            }
+        }
+
+        if let Some(value) = &spec.enforcement
+            && let Err(err) = value.parse::<Enforcement>()
+        {
+            problems.push(Problem::new(
+                format!("Spec '{}' has an invalid enforcement: {err}", spec.name),
+                at(&["enforcement".into()]),
+            ));
+        }
+
+        if let Some(value) = &spec.test_impls
+            && let Err(err) = value.parse::<TestImpls>()
+        {
+            problems.push(Problem::new(
+                format!("Spec '{}' has an invalid test_impls: {err}", spec.name),
+                at(&["test_impls".into()]),
+            ));
+        }
+
+        if let Some(value) = &spec.ownership
+            && let Err(err) = value.parse::<Ownership>()
+        {
+            problems.push(Problem::new(
+                format!("Spec '{}' has an invalid ownership: {err}", spec.name),
+                at(&["ownership".into()]),
+            ));
+        }
+
+        for (k, kind) in spec.strings.iter().enumerate() {
+            if !matches!(kind.as_str(), "doc-attributes" | "test-names") {
+                problems.push(Problem::new(
+                    format!(
+                        "Spec '{}' has an invalid strings entry: expected \
+                         `doc-attributes` or `test-names`, got `{kind}`",
+                        spec.name
+                    ),
+                    at(&["strings".into(), k.into()]),
+                ));
+            }
+        }
+
+        let mut impl_names = HashSet::new();
+        for (j, impl_config) in spec.impls.iter().enumerate() {
+            if !impl_names.insert(impl_config.name.as_str()) {
+                problems.push(Problem::new(
+                    format!(
+                        "Spec '{}' defines implementation '{}' more than once. \
+                         Implementation names must be unique within a spec.",
+                        spec.name, impl_config.name
+                    ),
+                    at(&["impls".into(), j.into(), "name".into()]),
+                ));
+            }
+        }
+    }
+
+    for (verb, role) in &config.verbs {
+        let path = vec!["verbs".into(), verb.as_str().into()];
+        let shaped = verb.starts_with(|c: char| c.is_ascii_lowercase())
+            && verb.chars().all(|c| c.is_ascii_lowercase() || c == '-');
+        if !shaped {
+            problems.push(Problem::new(
+                format!(
+                    "Verb '{verb}' must be lowercase letters and dashes, starting with a letter."
+                ),
+                path.clone(),
+            ));
+        }
+        if let Err(err) = role.parse::<VerbRole>() {
+            problems.push(Problem::new(
+                format!("Verb '{verb}' has an invalid role: {err}"),
+                path,
+            ));
+        }
+    }
+
+    for ext in config.formatters.keys() {
+        if ext.starts_with('.') || ext.contains('/') {
+            problems.push(Problem::new(
+                format!("Formatter key '{ext}' must be a bare file extension, like `rs`."),
+                vec!["formatters".into(), ext.as_str().into()],
+            ));
+        }
+    }
+
+    for (ext, comment) in &config.comments {
+        let path = vec!["comments".into(), ext.as_str().into()];
+        if ext.starts_with('.') || ext.contains('/') {
+            problems.push(Problem::new(
+                format!("Comment syntax key '{ext}' must be a bare file extension, like `sql`."),
+                path,
+            ));
+        } else if tracey_core::is_supported_extension(std::ffi::OsStr::new(ext)) {
+            problems.push(Problem::new(
+                format!("Files with extension '{ext}' are already parsed by tracey; remove it from `comments`."),
+                path,
+            ));
+        } else if comment.block_start.is_some() != comment.block_end.is_some() {
+            problems.push(Problem::new(
+                format!("Comment syntax for '{ext}' must set both block_start and block_end."),
+                path,
+            ));
+        } else if comment.line.is_empty() && comment.block_start.is_none() {
+            problems.push(Problem::new(
+                format!("Comment syntax for '{ext}' sets neither line nor block markers."),
+                path,
+            ));
+        } else if comment.line.iter().any(|m| m.trim().is_empty())
+            || [&comment.block_start, &comment.block_end]
+                .into_iter()
+                .flatten()
+                .any(|m| m.trim().is_empty())
+        {
+            problems.push(Problem::new(
+                format!("Comment syntax for '{ext}' has an empty marker."),
+                path,
+            ));
+        }
+    }
+
+    for (name, command) in &config.policies {
+        if command.trim().is_empty() {
+            problems.push(Problem::new(
+                format!("Policy '{name}' has an empty command."),
+                vec!["policies".into(), name.as_str().into()],
+            ));
+        }
+    }
+
+    if let Some(lints) = &config.spec_lints {
+        for (name, level) in &lints.levels {
+            let at = vec!["spec_lints".into(), "levels".into(), name.as_str().into()];
+            if crate::spec_lint::Lint::from_name(name).is_none() {
+                let known: Vec<&str> = crate::spec_lint::Lint::ALL
+                    .iter()
+                    .map(|lint| lint.name())
+                    .collect();
+                problems.push(Problem::new(
+                    format!(
+                        "Unknown spec lint '{name}'; expected one of {}.",
+                        known.join(", ")
+                    ),
+                    at,
+                ));
+            } else if let Err(e) = crate::spec_lint::parse_level(level) {
+                problems.push(Problem::new(format!("Spec lint '{name}': {e}."), at));
+            }
+        }
+        if let Some(min) = lints.min_similarity
+            && min > 100
+        {
+            problems.push(Problem::new(
+                format!(
+                    "Spec lint min_similarity is {min}; it must be a percentage from 0 to 100."
+                ),
+                vec!["spec_lints".into(), "min_similarity".into()],
+            ));
+        }
+    }
+
+    for (tag, threshold) in &config.tags {
+        for (key, value) in [
+            ("min_impl", threshold.min_impl),
+            ("min_verify", threshold.min_verify),
+        ] {
+            if let Some(value) = value
+                && value > 100
+            {
+                problems.push(Problem::new(
+                    format!(
+                        "Tag '{tag}' has {key} {value}; it must be a percentage from 0 to 100."
+                    ),
+                    vec!["tags".into(), tag.as_str().into(), key.into()],
+                ));
+            }
+        }
+    }
+
+    if let Some(webhook) = &config.webhook
+        && !(webhook.url.starts_with("http://") || webhook.url.starts_with("https://"))
+    {
+        problems.push(Problem::new(
+            format!(
+                "Webhook url '{}' must be an http:// or https:// URL.",
+                webhook.url
+            ),
+            vec!["webhook".into(), "url".into()],
+        ));
+    }
+
+    if let Some(cache) = &config.remote_cache {
+        if !(cache.url.starts_with("http://") || cache.url.starts_with("https://")) {
+            problems.push(Problem::new(
+                format!(
+                    "Remote cache url '{}' must be an http:// or https:// URL.",
+                    cache.url
+                ),
+                vec!["remote_cache".into(), "url".into()],
+            ));
+        }
+        if cache.token_env.is_some() && cache.s3_region.is_some() {
+            problems.push(Problem::new(
+                "Remote cache sets both token_env and s3_region; pick one way to authenticate."
+                    .to_string(),
+                vec!["remote_cache".into(), "token_env".into()],
+            ));
+        }
+    }
+
+    problems
+}
+
+/// How many implementation sites a fan-out rule requires.
+#[derive(Debug, Clone)]
+pub enum Fanout {
+    /// At least this many impl references.
+    Count(usize),
+    /// An impl reference in every scanned file matching the glob.
+    PerFile(globset::GlobMatcher),
+}
+
+impl std::str::FromStr for Fanout {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(pattern) = value.strip_prefix("per-file:") {
+            let glob = globset::Glob::new(pattern)
+                .map_err(|err| format!("bad glob `{pattern}`: {err}"))?;
+            return Ok(Self::PerFile(glob.compile_matcher()));
+        }
+        match value.parse::<usize>() {
+            Ok(count) if count > 0 => Ok(Self::Count(count)),
+            _ => Err(format!(
+                "expected a positive count or `per-file:<glob>`, got `{value}`"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Fanout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count(count) => write!(f, "{count}"),
+            Self::PerFile(glob) => write!(f, "per-file:{}", glob.glob()),
+        }
+    }
+}
+
+/// The config at `path` as `tracey config show` prints it: as written, or
+/// when `resolved`, validated and with `defaults` folded into each
+/// implementation.
+// r[impl cli.config.show]
+pub fn show(path: &PathBuf, resolved: bool) -> eyre::Result<String> {
+    use eyre::WrapErr as _;
+
+    let config = if resolved {
+        crate::load_config(path)?
+    } else {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read config file: {}", path.display()))?;
+        facet_styx::from_str::<Config>(&content)
+            .wrap_err_with(|| format!("Failed to parse config file: {}", path.display()))?
+    };
+    Ok(to_styx(&config))
+}
+
+/// Render a config back to styx, omitting unset and empty fields.
+pub fn to_styx(config: &Config) -> String {
+    let mut out = String::new();
+    if let Some(root) = &config.root {
+        let _ = writeln!(out, "root {}", styx_scalar(root));
+    }
+    if let Some(defaults) = &config.defaults {
+        let _ = writeln!(out, "defaults {{");
+        write_patterns(&mut out, 1, "include", &defaults.include);
+        write_patterns(&mut out, 1, "exclude", &defaults.exclude);
+        write_patterns(&mut out, 1, "test_include", &defaults.test_include);
+        let _ = writeln!(out, "}}");
+    }
+    if let Some(retention) = &config.retention {
+        let _ = writeln!(out, "retention {{");
+        if let Some(mb) = retention.max_log_mb {
+            let _ = writeln!(out, "  max_log_mb {mb}");
+        }
+        if let Some(days) = retention.max_log_age_days {
+            let _ = writeln!(out, "  max_log_age_days {days}");
+        }
+        if let Some(entries) = retention.max_history_entries {
+            let _ = writeln!(out, "  max_history_entries {entries}");
+        }
+        if let Some(entries) = retention.max_audit_entries {
+            let _ = writeln!(out, "  max_audit_entries {entries}");
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if let Some(limits) = &config.limits {
+        let _ = writeln!(out, "limits {{");
+        for (key, value) in [
+            ("max_file_kb", limits.max_file_kb),
+            ("max_files", limits.max_files),
+            ("file_timeout_ms", limits.file_timeout_ms),
+            ("budget_secs", limits.budget_secs),
+        ] {
+            if let Some(value) = value {
+                let _ = writeln!(out, "  {key} {value}");
+            }
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if !config.verbs.is_empty() {
+        let _ = writeln!(out, "verbs {{");
+        for (verb, role) in &config.verbs {
+            let _ = writeln!(out, "  {} {}", styx_scalar(verb), styx_scalar(role));
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if !config.formatters.is_empty() {
+        let _ = writeln!(out, "formatters {{");
+        for (ext, command) in &config.formatters {
+            let _ = writeln!(out, "  {} {}", styx_scalar(ext), styx_scalar(command));
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if !config.comments.is_empty() {
+        let _ = writeln!(out, "comments {{");
+        for (ext, comment) in &config.comments {
+            let _ = writeln!(out, "  {} {{", styx_scalar(ext));
+            write_patterns(&mut out, 2, "line", &comment.line);
+            if let Some(start) = &comment.block_start {
+                let _ = writeln!(out, "    block_start {}", styx_scalar(start));
+            }
+            if let Some(end) = &comment.block_end {
+                let _ = writeln!(out, "    block_end {}", styx_scalar(end));
+            }
+            let _ = writeln!(out, "  }}");
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if !config.policies.is_empty() {
+        let _ = writeln!(out, "policies {{");
+        for (name, command) in &config.policies {
+            let _ = writeln!(out, "  {} {}", styx_scalar(name), styx_scalar(command));
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if let Some(path) = &config.owners_file {
+        let _ = writeln!(out, "owners_file {}", styx_scalar(path));
+    }
+    if let Some(lints) = &config.spec_lints {
+        let _ = writeln!(out, "spec_lints {{");
+        if !lints.levels.is_empty() {
+            let _ = writeln!(out, "  levels {{");
+            for (name, level) in &lints.levels {
+                let _ = writeln!(out, "    {} {}", styx_scalar(name), styx_scalar(level));
+            }
+            let _ = writeln!(out, "  }}");
+        }
+        if let Some(max) = lints.max_sentences {
+            let _ = writeln!(out, "  max_sentences {max}");
+        }
+        if let Some(min) = lints.min_similarity {
+            let _ = writeln!(out, "  min_similarity {min}");
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if !config.tags.is_empty() {
+        let _ = writeln!(out, "tags {{");
+        for (tag, threshold) in &config.tags {
+            let _ = writeln!(out, "  {} {{", styx_scalar(tag));
+            if let Some(min) = threshold.min_impl {
+                let _ = writeln!(out, "    min_impl {min}");
+            }
+            if let Some(min) = threshold.min_verify {
+                let _ = writeln!(out, "    min_verify {min}");
+            }
+            let _ = writeln!(out, "  }}");
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if let Some(webhook) = &config.webhook {
+        let _ = writeln!(out, "webhook {{");
+        let _ = writeln!(out, "  url {}", styx_scalar(&webhook.url));
+        if let Some(var) = &webhook.secret_env {
+            let _ = writeln!(out, "  secret_env {}", styx_scalar(var));
+        }
+        let _ = writeln!(out, "}}");
+    }
+    if let Some(cache) = &config.remote_cache {
+        let _ = writeln!(out, "remote_cache {{");
+        let _ = writeln!(out, "  url {}", styx_scalar(&cache.url));
+        if let Some(var) = &cache.token_env {
+            let _ = writeln!(out, "  token_env {}", styx_scalar(var));
+        }
+        if let Some(region) = &cache.s3_region {
+            let _ = writeln!(out, "  s3_region {}", styx_scalar(region));
+        }
+        if cache.read_only {
+            let _ = writeln!(out, "  read_only true");
+        }
+        let _ = writeln!(out, "}}");
+    }
+    let _ = writeln!(out, "specs (");
+    for spec in &config.specs {
+        let _ = writeln!(out, "  {{");
+        let _ = writeln!(out, "    name {}", styx_scalar(&spec.name));
+        if let Some(url) = &spec.source_url {
+            let _ = writeln!(out, "    source_url {}", styx_scalar(url));
+        }
+        write_patterns(&mut out, 2, "include", &spec.include);
+        if let Some(enforcement) = &spec.enforcement {
+            let _ = writeln!(out, "    enforcement {}", styx_scalar(enforcement));
+        }
+        if let Some(test_impls) = &spec.test_impls {
+            let _ = writeln!(out, "    test_impls {}", styx_scalar(test_impls));
+        }
+        if let Some(ownership) = &spec.ownership {
+            let _ = writeln!(out, "    ownership {}", styx_scalar(ownership));
+        }
+        write_patterns(&mut out, 2, "shared", &spec.shared);
+        write_patterns(&mut out, 2, "strings", &spec.strings);
+        if !spec.fanout.is_empty() {
+            let _ = writeln!(out, "    fanout {{");
+            for (rule, value) in &spec.fanout {
+                let _ = writeln!(out, "      {} {}", styx_scalar(rule), styx_scalar(value));
+            }
+            let _ = writeln!(out, "    }}");
+        }
+        if let Some(threshold) = spec.threshold {
+            let _ = writeln!(out, "    threshold {threshold}");
+        }
+        if !spec.thresholds.is_empty() {
+            let _ = writeln!(out, "    thresholds {{");
+            for (prefix, value) in &spec.thresholds {
+                let _ = writeln!(out, "      {} {value}", styx_scalar(prefix));
+            }
+            let _ = writeln!(out, "    }}");
+        }
+        if let Some(weight) = spec.partial_weight {
+            let _ = writeln!(out, "    partial_weight {weight}");
+        }
+        if let Some(sandbox) = &spec.sandbox {
+            let _ = writeln!(out, "    sandbox {{");
+            if let Some(kb) = sandbox.max_file_kb {
+                let _ = writeln!(out, "      max_file_kb {kb}");
+            }
+            if let Some(rules) = sandbox.max_rules {
+                let _ = writeln!(out, "      max_rules {rules}");
+            }
+            write_patterns(&mut out, 3, "url_schemes", &sandbox.url_schemes);
+            let _ = writeln!(out, "    }}");
+        }
+        let _ = writeln!(out, "    impls (");
+        for impl_config in &spec.impls {
+            let _ = writeln!(out, "      {{");
+            let _ = writeln!(out, "        name {}", styx_scalar(&impl_config.name));
+            write_patterns(&mut out, 4, "include", &impl_config.include);
+            write_patterns(&mut out, 4, "exclude", &impl_config.exclude);
+            write_patterns(&mut out, 4, "test_include", &impl_config.test_include);
+            write_patterns(&mut out, 4, "proof_include", &impl_config.proof_include);
+            let _ = writeln!(out, "      }}");
+        }
+        let _ = writeln!(out, "    )");
+        let _ = writeln!(out, "  }}");
+    }
+    let _ = writeln!(out, ")");
+    out
+}
+
+fn write_patterns(out: &mut String, depth: usize, key: &str, patterns: &[String]) {
+    if patterns.is_empty() {
+        return;
+    }
+    let items: Vec<_> = patterns.iter().map(|p| styx_scalar(p)).collect();
+    let _ = writeln!(out, "{}{key} ({})", "  ".repeat(depth), items.join(" "));
+}
+
+/// Quote a scalar if it would not parse back as a bare word.
+pub(crate) fn styx_scalar(value: &str) -> String {
+    let bare = !value.is_empty()
+        && !value.starts_with("//")
+        && !value.chars().any(|c| {
+            c.is_whitespace() || matches!(c, '(' | ')' | '{' | '}' | ',' | '"' | '@' | '>')
+        });
+    if bare {
+        value.to_string()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::RefVerb;
+
+    fn parse(source: &str) -> Config {
+        facet_styx::from_str(source).expect("valid styx")
+    }
+
+    #[test]
+    fn valid_config_passes() {
+        let config = parse(
+            r#"
+specs (
+  {
+    name app
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+        );
+        assert!(validate(&config).is_ok());
+    }
+
+    // r[verify config.validate]
+    #[test]
+    fn all_problems_are_reported_together() {
+        let config = parse(
+            r#"
+specs (
+  {
+    name app
+    include (spec.md)
+    impls (
+      {
+        name rust
+      }
+      {
+        name rust
+      }
+    )
+  }
+  {
+    name app
+  }
+)
+"#,
+        );
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(message.starts_with("2 problems found:"), "{message}");
+        assert!(message.contains("Spec name 'app' is used more than once"));
+        assert!(message.contains("defines implementation 'rust' more than once"));
+        assert!(
+            !message.contains("has no implementations defined"),
+            "{message}"
+        );
+        assert!(!message.contains("has no `include` patterns"), "{message}");
+        let warnings: Vec<_> = problems(&config)
+            .into_iter()
+            .filter(|p| p.warning)
+            .map(|p| p.message)
+            .collect();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings[0].contains("has no `include` patterns"));
+        assert!(warnings[1].contains("has no implementations defined"));
+    }
+
+    // r[verify config.validate]
+    #[test]
+    fn missing_include_is_only_a_warning() {
+        let config = parse(
+            r#"
+specs (
+  {
+    name app
+    impls ({name rust})
+  }
+)
+"#,
+        );
+        validate(&config).unwrap();
+        let problems = problems(&config);
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].warning);
+        assert!(problems[0].message.contains("has no `include` patterns"));
+        assert_eq!(
+            spec_sources(&config.specs[0]),
+            vec![SpecSource::Glob {
+                base: PathBuf::new(),
+                glob: "**/*".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn spec_sources_resolve_paths_and_globs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("docs/spec/nested")).unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        std::fs::write(root.join("docs/spec/a.md"), "").unwrap();
+        std::fs::write(root.join("docs/spec/nested/b.md"), "").unwrap();
+        std::fs::write(root.join("docs/spec/notes.txt"), "").unwrap();
+
+        let sources: Vec<_> = ["README.md", "docs/spec/**/*.md", "missing/*.md", "bad[.md"]
+            .into_iter()
+            .map(SpecSource::parse)
+            .collect();
+        assert_eq!(
+            sources[0],
+            Some(SpecSource::Path(PathBuf::from("README.md")))
+        );
+        assert_eq!(
+            sources[1],
+            Some(SpecSource::Glob {
+                base: PathBuf::from("docs/spec"),
+                glob: "**/*.md".to_string()
+            })
+        );
+        assert_eq!(sources[3], None);
+
+        let files = |source: &Option<SpecSource>| {
+            source.as_ref().unwrap().files(root).map(|files| {
+                let mut files: Vec<_> = files
+                    .iter()
+                    .map(|f| f.strip_prefix(root).unwrap().to_path_buf())
+                    .collect();
+                files.sort();
+                files
+            })
+        };
+        assert_eq!(files(&sources[0]), Some(vec![PathBuf::from("README.md")]));
+        assert_eq!(
+            files(&sources[1]),
+            Some(vec![
+                PathBuf::from("docs/spec/a.md"),
+                PathBuf::from("docs/spec/nested/b.md")
+            ])
+        );
+        assert_eq!(files(&sources[2]), None);
+    }
+
+    // r[verify config.defaults.inherit]
+    #[test]
+    fn impls_inherit_defaults_unless_overridden() {
+        let config = parse(
+            r#"
+defaults {
+  include (src/**/*.rs)
+  exclude (target/**)
+  test_include (tests/**/*.rs)
+}
+specs (
+  {
+    name app
+    include (spec.md)
+    impls (
+      {
+        name rust
+      }
+      {
+        name web
+        include (web/**/*.ts)
+      }
+    )
+  }
+)
+"#,
+        )
+        .resolve_defaults();
+        assert!(config.defaults.is_none());
+        let impls = &config.specs[0].impls;
+        assert_eq!(impls[0].include, ["src/**/*.rs"]);
+        assert_eq!(impls[0].exclude, ["target/**"]);
+        assert_eq!(impls[0].test_include, ["tests/**/*.rs"]);
+        assert_eq!(impls[1].include, ["web/**/*.ts"]);
+        assert_eq!(impls[1].exclude, ["target/**"]);
+    }
+
+    // r[verify config.defaults]
+    #[test]
+    fn defaults_block_parses_and_round_trips() {
+        let config = parse(
+            r#"
+defaults {
+  include (src/**/*.rs)
+  exclude (target/**)
+  test_include (tests/**/*.rs)
+}
+specs ()
+"#,
+        );
+        let reparsed = parse(&to_styx(&config));
+        let defaults = reparsed.defaults.expect("defaults block");
+        assert_eq!(defaults.include, ["src/**/*.rs"]);
+        assert_eq!(defaults.exclude, ["target/**"]);
+        assert_eq!(defaults.test_include, ["tests/**/*.rs"]);
+    }
+
+    // r[verify cli.config.show]
+    #[test]
+    fn show_prints_the_config_as_written_or_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.styx");
+        std::fs::write(
+            &path,
+            r#"
+defaults {
+  include (src/**/*.rs)
+}
+specs (
+  {
+    name app
+    include (spec.md)
+    impls ({name rust})
+  }
+)
+"#,
+        )
+        .unwrap();
+
+        let written = show(&path, false).unwrap();
+        assert!(written.contains("defaults {"), "{written}");
+        assert!(
+            !parse(&written).specs[0].impls[0]
+                .include
+                .iter()
+                .any(|p| p == "src/**/*.rs")
+        );
+
+        let resolved = show(&path, true).unwrap();
+        assert!(!resolved.contains("defaults {"), "{resolved}");
+        assert_eq!(parse(&resolved).specs[0].impls[0].include, ["src/**/*.rs"]);
+
+        std::fs::write(
+            &path,
+            "specs ({name app, include (spec.md), fanout {a.b 0}})\n",
+        )
+        .unwrap();
+        assert!(show(&path, false).is_ok(), "as written is not validated");
+        assert!(show(&path, true).is_err());
+    }
+
+    #[test]
+    fn to_styx_round_trips() {
+        let source = r#"
+specs (
+  {
+    name app
+    include (spec.md "docs/my spec.md")
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#;
+        let config = parse(source);
+        let rendered = to_styx(&config);
+        assert!(
+            rendered.contains(r#"include (spec.md "docs/my spec.md")"#),
+            "{rendered}"
+        );
+        let reparsed = parse(&rendered);
+        assert_eq!(reparsed.specs[0].include, config.specs[0].include);
+        assert_eq!(reparsed.specs[0].impls[0].include, ["src/**/*.rs"]);
+    }
+
+    // r[verify config.spec.fanout]
+    #[test]
+    fn fanout_values_parse_and_validate() {
+        let config = parse(
+            r#"
+specs (
+  {
+    name app
+    include (spec.md)
+    fanout {
+      api.endpoint 3
+      api.handler "per-file:src/handlers/*.rs"
+      api.broken "per-file"
+    }
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+        );
+        let fanout = &config.specs[0].fanout;
+        assert!(matches!(
+            fanout["api.endpoint"].parse(),
+            Ok(Fanout::Count(3))
+        ));
+        let Ok(Fanout::PerFile(glob)) = fanout["api.handler"].parse() else {
+            panic!("expected per-file fanout");
+        };
+        assert!(glob.is_match("src/handlers/users.rs"));
+        assert!(!glob.is_match("src/main.rs"));
+        assert!("0".parse::<Fanout>().is_err());
+
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("invalid fanout for 'api.broken'"),
+            "{message}"
+        );
+
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(&reparsed.specs[0].fanout, fanout);
+    }
+
+    // r[verify config.spec.enforcement]
+    #[test]
+    fn enforcement_parses_and_validates() {
+        let source = |value: &str| {
+            format!(
+                "specs (\n  {{\n    name rfc\n    include (rfc.md)\n    enforcement {value}\n    impls (\n      {{\n        name rust\n        include (src/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source("advisory"));
+        validate(&config).unwrap();
+        assert_eq!(enforcement(&config.specs[0]), Enforcement::Advisory);
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(reparsed.specs[0].enforcement.as_deref(), Some("advisory"));
+
+        let config = parse(&source("optional"));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Spec 'rfc' has an invalid enforcement"),
+            "{message}"
+        );
+        assert_eq!(enforcement(&config.specs[0]), Enforcement::Enforced);
+    }
+
+    // r[verify config.spec.partial-weight]
+    #[test]
+    fn partial_weight_parses_and_validates() {
+        let source = |field: &str| {
+            format!(
+                "specs (\n  {{\n    name rfc\n    include (rfc.md)\n    {field}\n    impls (\n      {{\n        name rust\n        include (src/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source(""));
+        validate(&config).unwrap();
+        assert_eq!(partial_weight(&config.specs[0]), 0.5);
+
+        let config = parse(&source("partial_weight 25"));
+        validate(&config).unwrap();
+        assert_eq!(partial_weight(&config.specs[0]), 0.25);
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(reparsed.specs[0].partial_weight, Some(25));
+
+        let config = parse(&source("partial_weight 150"));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Spec 'rfc' has partial_weight 150"),
+            "{message}"
+        );
+        assert_eq!(partial_weight(&config.specs[0]), 1.0);
+    }
+
+    // r[verify config.spec.test-impls]
+    #[test]
+    fn test_impls_parses_and_validates() {
+        let source = |value: &str| {
+            format!(
+                "specs (\n  {{\n    name rfc\n    include (rfc.md)\n    test_impls {value}\n    impls (\n      {{\n        name rust\n        include (src/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source("verify"));
+        validate(&config).unwrap();
+        assert_eq!(test_impls(&config.specs[0]), TestImpls::Verify);
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(reparsed.specs[0].test_impls.as_deref(), Some("verify"));
+
+        let config = parse(&source("ignore"));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Spec 'rfc' has an invalid test_impls"),
+            "{message}"
+        );
+        assert_eq!(test_impls(&config.specs[0]), TestImpls::Impl);
+    }
+
+    // r[verify config.spec.strings]
+    #[test]
+    fn strings_parse_and_validate() {
+        let source = |value: &str| {
+            format!(
+                "specs (\n  {{\n    name api\n    include (api.md)\n    strings ({value})\n    impls (\n      {{\n        name web\n        include (src/**/*.ts)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source("doc-attributes test-names"));
+        validate(&config).unwrap();
+        let strings = string_references(&config.specs[0]);
+        assert!(strings.doc_attributes && strings.test_names);
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(reparsed.specs[0].strings, ["doc-attributes", "test-names"]);
+
+        let config = parse(&source("test-names literals"));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Spec 'api' has an invalid strings entry"),
+            "{message}"
+        );
+        let strings = string_references(&config.specs[0]);
+        assert!(!strings.doc_attributes && strings.test_names);
+    }
+
+    // r[verify config.spec.ownership]
+    // r[verify config.spec.shared]
+    #[test]
+    fn ownership_parses_and_validates() {
+        let source = |value: &str| {
+            format!(
+                "specs (\n  {{\n    name api\n    include (api.md)\n    ownership {value}\n    shared (common/**/*.rs)\n    impls (\n      {{\n        name server\n        include (src/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source("specific"));
+        validate(&config).unwrap();
+        assert_eq!(ownership(&config.specs[0]), Ownership::Specific);
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(reparsed.specs[0].ownership.as_deref(), Some("specific"));
+        assert_eq!(reparsed.specs[0].shared, vec!["common/**/*.rs".to_string()]);
+
+        let config = parse(&source("last"));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Spec 'api' has an invalid ownership"),
+            "{message}"
+        );
+        assert_eq!(ownership(&config.specs[0]), Ownership::First);
+    }
+
+    // r[verify config.spec.threshold]
+    // r[verify config.spec.thresholds]
+    #[test]
+    fn spec_thresholds_parse_and_validate() {
+        let source = |block: &str| {
+            format!(
+                "specs (\n  {{\n    name api\n    include (api.md)\n{block}    impls (\n      {{\n        name server\n        include (src/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source(
+            "    threshold 85\n    thresholds {\n      channel. 100\n      \"auth.\" 50\n    }\n",
+        ));
+        validate(&config).unwrap();
+        assert_eq!(config.specs[0].threshold, Some(85));
+        assert_eq!(config.specs[0].thresholds["channel."], 100);
+        let reparsed = parse(&to_styx(&config));
+        assert_eq!(reparsed.specs[0].threshold, Some(85));
+        assert_eq!(reparsed.specs[0].thresholds, config.specs[0].thresholds);
+
+        let config = parse(&source(
+            "    threshold 120\n    thresholds {\n      channel. 101\n    }\n",
+        ));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Spec 'api' has threshold 120"),
+            "{message}"
+        );
+        assert!(
+            message.contains("Spec 'api' has threshold for 'channel.' 101"),
+            "{message}"
+        );
+    }
+
+    // r[verify config.spec.sandbox]
+    #[test]
+    fn sandbox_parses_and_validates() {
+        let source = |block: &str| {
+            format!(
+                "specs (\n  {{\n    name vendor\n    include (vendor/*.md)\n    {block}\n    impls (\n      {{\n        name rust\n        include (src/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+            )
+        };
+        let config = parse(&source("sandbox {}"));
+        validate(&config).unwrap();
+        assert!(config.specs[0].sandbox.is_some());
+        assert!(parse(&source("")).specs[0].sandbox.is_none());
+
+        let config = parse(&source(
+            "sandbox {\n      max_file_kb 64\n      max_rules 10\n      url_schemes (https)\n    }",
+        ));
+        validate(&config).unwrap();
+        let reparsed = parse(&to_styx(&config));
+        let sandbox = reparsed.specs[0].sandbox.as_ref().unwrap();
+        assert_eq!(sandbox.max_file_kb, Some(64));
+        assert_eq!(sandbox.max_rules, Some(10));
+        assert_eq!(sandbox.url_schemes, vec!["https".to_string()]);
+
+        let config = parse(&source("sandbox {\n      url_schemes (https:)\n    }"));
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(message.contains("invalid URL scheme 'https:'"), "{message}");
+    }
+
+    // r[verify config.retention]
+    #[test]
+    fn retention_limits_parse() {
+        let config = parse(
+            r#"
+retention {
+  max_log_mb 20
+  max_log_age_days 3
+  max_history_entries 500
+  max_audit_entries 2000
+}
+"#,
+        );
+        let retention = config.retention.clone().expect("retention block");
+        assert_eq!(retention.max_log_mb, Some(20));
+        assert_eq!(retention.max_log_age_days, Some(3));
+        assert_eq!(retention.max_history_entries, Some(500));
+        assert_eq!(retention.max_audit_entries, Some(2000));
+        let reparsed = parse(&to_styx(&config)).retention.unwrap();
+        assert_eq!(reparsed.max_history_entries, Some(500));
+        assert_eq!(reparsed.max_audit_entries, Some(2000));
+    }
+
+    // r[verify config.limits]
+    #[test]
+    fn extraction_limits_parse_and_round_trip() {
+        let config = parse(
+            r#"
+limits {
+  max_file_kb 256
+  max_files 1000
+  file_timeout_ms 500
+  budget_secs 60
+}
+"#,
+        );
+        let reparsed = parse(&to_styx(&config));
+        let limits = reparsed.limits.expect("limits block");
+        assert_eq!(limits.max_file_kb, Some(256));
+        assert_eq!(limits.max_files, Some(1000));
+        assert_eq!(limits.file_timeout_ms, Some(500));
+        assert_eq!(limits.budget_secs, Some(60));
+    }
+
+    // r[verify config.webhook]
+    #[test]
+    fn webhook_parses_and_round_trips() {
+        let config = parse(
+            r#"
+webhook {
+  url "https://hooks.example.com/tracey"
+  secret_env TRACEY_WEBHOOK_SECRET
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let reparsed = parse(&to_styx(&config));
+        let webhook = reparsed.webhook.expect("webhook block");
+        assert_eq!(webhook.url, "https://hooks.example.com/tracey");
+        assert_eq!(webhook.secret_env.as_deref(), Some("TRACEY_WEBHOOK_SECRET"));
+
+        let config = parse("webhook {\n  url ftp://example.com\n}\nspecs ()\n");
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("must be an http:// or https:// URL"),
+            "{message}"
+        );
+    }
+
+    // r[verify config.remote-cache]
+    #[test]
+    fn remote_cache_parses_and_validates() {
+        let config = parse(
+            r#"
+remote_cache {
+  url "https://bucket.s3.example.com/tracey"
+  s3_region eu-west-1
+  read_only true
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let reparsed = parse(&to_styx(&config));
+        let cache = reparsed.remote_cache.expect("remote_cache block");
+        assert_eq!(cache.url, "https://bucket.s3.example.com/tracey");
+        assert_eq!(cache.s3_region.as_deref(), Some("eu-west-1"));
+        assert_eq!(cache.token_env, None);
+        assert!(cache.read_only);
+
+        let config = parse(
+            "remote_cache {\n  url s3://bucket\n  token_env T\n  s3_region us-east-1\n}\nspecs ()\n",
+        );
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("must be an http:// or https:// URL"),
+            "{message}"
+        );
+        assert!(
+            message.contains("both token_env and s3_region"),
+            "{message}"
+        );
+    }
+
+    // r[verify config.formatters]
+    #[test]
+    fn formatters_override_builtins_and_round_trip() {
+        let config = parse(
+            r#"
+formatters {
+  rs "rustfmt --edition 2021"
+  go ""
+  zig "zig fmt --stdin"
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let commands = formatter_commands(&parse(&to_styx(&config)));
+        assert_eq!(commands["rs"], "rustfmt --edition 2021");
+        assert_eq!(commands["zig"], "zig fmt --stdin");
+        assert_eq!(commands["ts"], "prettier --stdin-filepath {path}");
+        assert!(!commands.contains_key("go"));
+
+        let config = parse("formatters {\n  .rs rustfmt\n}\nspecs ()\n");
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Formatter key '.rs' must be a bare file extension"),
+            "{message}"
+        );
+    }
+
+    // r[verify config.comments]
+    #[test]
+    fn comment_syntaxes_validate_and_round_trip() {
+        let config = parse(
+            r#"
+comments {
+  sql { line (--) }
+  html { block_start "<!--", block_end "-->" }
+  tex { line (%) }
+  proto { line ("//") }
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let delimiters = comment_delimiters(&parse(&to_styx(&config)));
+        assert_eq!(delimiters["sql"].line, ["--"]);
+        assert_eq!(
+            delimiters["html"].block,
+            [("<!--".to_string(), "-->".to_string())]
+        );
+        assert_eq!(delimiters["tex"].line, ["%"]);
+        assert_eq!(delimiters["proto"].line, ["//"]);
+
+        let id = tracey_core::parse_rule_id("a.b").unwrap();
+        let snippet =
+            |language| annotation_snippet(&config, "r", tracey_core::RefVerb::Impl, &id, language);
+        assert_eq!(snippet("sql").as_deref(), Some("-- r[impl a.b]"));
+        assert_eq!(snippet(".html").as_deref(), Some("<!-- r[impl a.b] -->"));
+        assert_eq!(snippet("rust").as_deref(), Some("// r[impl a.b]"));
+        assert_eq!(snippet("yaml"), None);
+
+        for (source, expected) in [
+            (
+                "comments {\n  rs { line (#) }\n}\nspecs ()\n",
+                "already parsed by tracey",
+            ),
+            (
+                "comments {\n  html { block_start \"<!--\" }\n}\nspecs ()\n",
+                "must set both block_start and block_end",
+            ),
+            (
+                "comments {\n  sql {}\n}\nspecs ()\n",
+                "sets neither line nor block",
+            ),
+        ] {
+            let message = validate(&parse(source)).unwrap_err().to_string();
+            assert!(message.contains(expected), "{message}");
+        }
+    }
+
+    // r[verify config.policies]
+    #[test]
+    fn policies_validate_and_round_trip() {
+        let config = parse(
+            r#"
+policies {
+  no-uncovered-must "python3 scripts/gate.py --level must"
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let config = parse(&to_styx(&config));
+        assert_eq!(
+            config.policies["no-uncovered-must"],
+            "python3 scripts/gate.py --level must"
+        );
+
+        let config = parse("policies {\n  empty \"\"\n}\nspecs ()\n");
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Policy 'empty' has an empty command"),
+            "{message}"
+        );
+    }
+
+    // r[verify config.tags]
+    #[test]
+    fn tag_thresholds_validate_and_round_trip() {
+        let config = parse(
+            r#"
+tags {
+  security {
+    min_impl 100
+    min_verify 80
+  }
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let config = parse(&to_styx(&config));
+        assert_eq!(config.tags["security"].min_impl, Some(100));
+        assert_eq!(config.tags["security"].min_verify, Some(80));
+
+        let config = parse("tags {\n  perf {\n    min_impl 150\n  }\n}\nspecs ()\n");
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(message.contains("Tag 'perf' has min_impl 150"), "{message}");
+    }
+
+    // r[verify config.verbs]
+    #[test]
+    fn verbs_parse_validate_and_round_trip() {
+        let config = parse(
+            r#"
+verbs {
+  documents docs
+  related informational
+}
+specs ()
+"#,
+        );
+        validate(&config).unwrap();
+        let roles = verb_roles(&parse(&to_styx(&config)));
+        assert_eq!(roles.custom_verbs(), ["documents"]);
+        assert_eq!(roles.role(&RefVerb::Related), VerbRole::Informational);
+        assert_eq!(roles.role(&RefVerb::Define), VerbRole::Impl);
+
+        let config = parse("verbs {\n  Docs docs\n  tests coverage\n}\nspecs ()\n");
+        let message = validate(&config).unwrap_err().to_string();
+        assert!(
+            message.contains("Verb 'Docs' must be lowercase"),
+            "{message}"
+        );
+        assert!(
+            message.contains("Verb 'tests' has an invalid role"),
+            "{message}"
+        );
+    }
+}
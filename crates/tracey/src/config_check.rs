@@ -0,0 +1,417 @@
+//! `tracey config check` implementation.
+//!
+//! Most config mistakes otherwise surface as an error deep inside whichever
+//! command first trips over them: a glob that does not compile, an include
+//! that matches nothing, a spec whose files define no rules. This module runs
+//! every such check up front, without building the dashboard, and points each
+//! problem at the value in the config file that causes it.
+
+use ariadne::{Color, Config as ReportConfig, Label, Report, ReportKind, Source};
+use facet_styx::RenderError as _;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use styx_tree::{Payload, Value};
+
+use crate::config::{Config, PathSegment, SpecSource, problems, spec_sources};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Commands fail or misbehave with this config.
+    Error,
+    /// The config works but probably does not do what was meant.
+    Warning,
+}
+
+/// A problem in the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Byte range in the config file of the value at fault.
+    pub span: Range<usize>,
+    /// What is wrong with that value, shown under it.
+    pub label: &'static str,
+}
+
+/// Outcome of `tracey config check`.
+#[derive(Debug, Default)]
+pub struct ConfigCheck {
+    /// The parser's own diagnostic, already rendered, when the file is not
+    /// valid Styx or does not match the config schema. No other check runs
+    /// in that case.
+    pub parse_error: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ConfigCheck {
+    pub fn errors(&self) -> usize {
+        usize::from(self.parse_error.is_some())
+            + self
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count()
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Warning)
+            .count()
+    }
+
+    /// Render every diagnostic with the config source around it, followed by
+    /// a summary line.
+    pub fn render(&self, filename: &str, source: &str, color: bool) -> String {
+        let mut out = String::new();
+        if let Some(error) = &self.parse_error {
+            out.push_str(error);
+        }
+        for diagnostic in &self.diagnostics {
+            let (kind, label_color) = match diagnostic.severity {
+                Severity::Error => (ReportKind::Error, Color::Red),
+                Severity::Warning => (ReportKind::Warning, Color::Yellow),
+            };
+            let mut buf = Vec::new();
+            let _ = Report::build(kind, (filename, diagnostic.span.clone()))
+                .with_config(ReportConfig::default().with_color(color))
+                .with_message(&diagnostic.message)
+                .with_label(
+                    Label::new((filename, diagnostic.span.clone()))
+                        .with_message(diagnostic.label)
+                        .with_color(label_color),
+                )
+                .finish()
+                .write((filename, Source::from(source)), &mut buf);
+            out.push_str(&String::from_utf8_lossy(&buf));
+        }
+        match (self.errors(), self.warnings()) {
+            (0, 0) => out.push_str(&format!("{filename}: no problems found.\n")),
+            (errors, warnings) => out.push_str(&format!(
+                "{filename}: {errors} error(s), {warnings} warning(s).\n"
+            )),
+        }
+        out
+    }
+}
+
+/// Byte range of the value at `path` in the parsed config, or of the
+/// innermost value on the way to it that exists. Object keys cover the whole
+/// `key value` entry.
+fn locate(tree: &Value, path: &[PathSegment]) -> Range<usize> {
+    let range = |span: Option<styx_tree::Span>| span.map(|s| s.start as usize..s.end as usize);
+    let mut current = tree;
+    let mut found = range(tree.span).unwrap_or(0..0);
+    for segment in path {
+        let next = match (segment, &current.payload) {
+            (PathSegment::Key(key), Some(Payload::Object(object))) => object
+                .entries
+                .iter()
+                .find(|e| e.key.scalar_text() == Some(key.as_str()))
+                .map(|e| {
+                    let span = match (range(e.key.span), range(e.value.span)) {
+                        (Some(k), Some(v)) => Some(k.start..v.end.max(k.end)),
+                        (k, v) => k.or(v),
+                    };
+                    (&e.value, span)
+                }),
+            (PathSegment::Index(i), Some(Payload::Sequence(seq))) => {
+                seq.items.get(*i).map(|item| (item, range(item.span)))
+            }
+            _ => None,
+        };
+        let Some((value, span)) = next else {
+            break;
+        };
+        current = value;
+        if let Some(span) = span {
+            found = span;
+        }
+    }
+    found
+}
+
+/// Files matched by one include pattern, resolved the way scans resolve it.
+/// `None` when the directory the pattern starts in does not exist.
+pub(crate) fn matching_files(project_root: &Path, pattern: &str) -> Option<Vec<PathBuf>> {
+    SpecSource::parse(pattern)?.files(project_root)
+}
+
+/// Check the config in `source`, resolving include patterns against
+/// `project_root`.
+// r[impl cli.config.check]
+pub fn check(project_root: &Path, filename: &str, source: &str) -> ConfigCheck {
+    let mut report = ConfigCheck::default();
+    let config: Config = match facet_styx::from_str(source) {
+        Ok(config) => config,
+        Err(err) => {
+            report.parse_error = Some(err.render(filename, source));
+            return report;
+        }
+    };
+    let tree = styx_tree::parse(source).unwrap_or_else(|_| Value::unit());
+    let mut push = |severity, message: String, label, path: Vec<PathSegment>| {
+        report.diagnostics.push(Diagnostic {
+            severity,
+            message,
+            span: locate(&tree, &path),
+            label,
+        });
+    };
+
+    for problem in problems(&config.clone().resolve_defaults()) {
+        let severity = if problem.warning {
+            Severity::Warning
+        } else {
+            Severity::Error
+        };
+        push(severity, problem.message, "here", problem.path);
+    }
+
+    // Every pattern as written, so defaults are reported where they are set.
+    let mut patterns: Vec<(Vec<PathSegment>, &str, bool)> = Vec::new();
+    if let Some(defaults) = &config.defaults {
+        for (field, list, include) in [
+            ("include", &defaults.include, true),
+            ("exclude", &defaults.exclude, false),
+            ("test_include", &defaults.test_include, true),
+        ] {
+            for (k, pattern) in list.iter().enumerate() {
+                patterns.push((
+                    vec!["defaults".into(), field.into(), k.into()],
+                    pattern,
+                    include,
+                ));
+            }
+        }
+    }
+    for (i, spec) in config.specs.iter().enumerate() {
+        for (k, pattern) in spec.include.iter().enumerate() {
+            patterns.push((
+                vec!["specs".into(), i.into(), "include".into(), k.into()],
+                pattern,
+                true,
+            ));
+        }
+        for (k, pattern) in spec.shared.iter().enumerate() {
+            patterns.push((
+                vec!["specs".into(), i.into(), "shared".into(), k.into()],
+                pattern,
+                true,
+            ));
+        }
+        for (j, impl_config) in spec.impls.iter().enumerate() {
+            for (field, list, include) in [
+                ("include", &impl_config.include, true),
+                ("exclude", &impl_config.exclude, false),
+                ("test_include", &impl_config.test_include, true),
+                ("proof_include", &impl_config.proof_include, true),
+            ] {
+                for (k, pattern) in list.iter().enumerate() {
+                    let path = vec![
+                        "specs".into(),
+                        i.into(),
+                        "impls".into(),
+                        j.into(),
+                        field.into(),
+                        k.into(),
+                    ];
+                    patterns.push((path, pattern, include));
+                }
+            }
+        }
+    }
+    for (path, pattern, include) in patterns {
+        if let Err(err) = globset::Glob::new(pattern) {
+            push(
+                Severity::Error,
+                format!("`{pattern}` is not a valid glob: {}", err.kind()),
+                "does not compile",
+                path,
+            );
+            continue;
+        }
+        if include && matching_files(project_root, pattern).is_none_or(|files| files.is_empty()) {
+            push(
+                Severity::Warning,
+                format!("`{pattern}` matches no files"),
+                "matches nothing",
+                path,
+            );
+        }
+    }
+
+    // Prefixes inferred from each spec's markdown, and the rule IDs defined
+    // under each prefix, to find the problems a scan would stop at.
+    let mut defined: BTreeMap<(String, String), Vec<usize>> = BTreeMap::new();
+    for (i, spec) in config.specs.iter().enumerate() {
+        let files: BTreeSet<PathBuf> = spec_sources(spec)
+            .iter()
+            .filter_map(|source| source.files(project_root))
+            .flatten()
+            .filter(|file| file.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        let mut prefixes = BTreeSet::new();
+        let mut ids = BTreeSet::new();
+        for file in &files {
+            let Ok(content) = std::fs::read_to_string(file) else {
+                continue;
+            };
+            for marker in tracey_core::markdown::edit::find_rule_markers(&content) {
+                ids.insert((marker.prefix.clone(), marker.id.base.clone()));
+                prefixes.insert(marker.prefix);
+            }
+        }
+        let at = vec!["specs".into(), i.into(), "include".into()];
+        match prefixes.len() {
+            0 => push(
+                Severity::Error,
+                format!(
+                    "Spec '{}' includes no rule definitions, so its marker prefix cannot be inferred",
+                    spec.name
+                ),
+                "no rules in these files",
+                at,
+            ),
+            1 => {}
+            _ => push(
+                Severity::Error,
+                format!(
+                    "Spec '{}' uses more than one marker prefix ({}); use a single prefix per spec",
+                    spec.name,
+                    prefixes.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+                "mixes prefixes",
+                at,
+            ),
+        }
+        for key in ids {
+            defined.entry(key).or_default().push(i);
+        }
+    }
+    let mut clashes: BTreeMap<Vec<usize>, Vec<String>> = BTreeMap::new();
+    for ((prefix, id), specs) in defined {
+        if specs.len() > 1 {
+            clashes
+                .entry(specs)
+                .or_default()
+                .push(format!("{prefix}[{id}]"));
+        }
+    }
+    for (specs, rules) in clashes {
+        let names: Vec<String> = specs
+            .iter()
+            .map(|&i| format!("'{}'", config.specs[i].name))
+            .collect();
+        let shown: Vec<&str> = rules.iter().take(3).map(String::as_str).collect();
+        push(
+            Severity::Warning,
+            format!(
+                "Specs {} define {} of the same rules under the same prefix ({}{}); \
+                 references to them count for every one of these specs",
+                names.join(" and "),
+                rules.len(),
+                shown.join(", "),
+                if rules.len() > shown.len() {
+                    ", ..."
+                } else {
+                    ""
+                }
+            ),
+            "shares rule IDs",
+            vec!["specs".into(), specs[specs.len() - 1].into(), "name".into()],
+        );
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"specs (
+  {
+    name tracey
+    include (docs/*.md)
+    impls (
+      {
+        name main
+        include (src/**/*.rs)
+        exclude ("src/[gen.rs")
+      }
+      {
+        name main
+        include (lib/**/*.rs)
+      }
+    )
+  }
+  {
+    name vendored
+    include (vendor/*.md)
+    impls ({ name main, include (src/**/*.rs) })
+  }
+)
+"#;
+
+    // r[verify cli.config.check]
+    #[test]
+    fn reports_every_problem_at_its_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for (file, content) in [
+            ("docs/spec.md", "r[auth.login]\nLog in.\n"),
+            ("vendor/spec.md", "r[auth.login]\nAlso log in.\n"),
+            ("src/lib.rs", ""),
+        ] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::write(root.join(file), content).unwrap();
+        }
+
+        let report = check(root, "config.styx", CONFIG);
+        assert!(report.parse_error.is_none(), "{:?}", report.parse_error);
+        let at = |d: &Diagnostic| &CONFIG[d.span.clone()];
+        let found: Vec<(Severity, &str)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.severity, at(d)))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Error, "name main"),
+                (Severity::Error, "\"src/[gen.rs\""),
+                (Severity::Warning, "lib/**/*.rs"),
+                (Severity::Warning, "name vendored"),
+            ],
+            "{:#?}",
+            report.diagnostics
+        );
+        assert!(report.diagnostics[0].message.contains("more than once"));
+        assert!(report.diagnostics[3].message.contains("r[auth.login]"));
+
+        let text = report.render("config.styx", CONFIG, false);
+        assert!(text.contains("config.styx:9:"), "{text}");
+        assert!(
+            text.ends_with("config.styx: 2 error(s), 2 warning(s).\n"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn parse_errors_stop_the_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = check(
+            dir.path(),
+            "config.styx",
+            "specs (\n  { name x nmae y }\n)\n",
+        );
+        assert!(report.parse_error.is_some());
+        assert!(report.diagnostics.is_empty());
+        assert_eq!(report.errors(), 1);
+    }
+}
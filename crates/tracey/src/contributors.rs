@@ -0,0 +1,344 @@
+//! `tracey contributors` implementation.
+//!
+//! Attributes every implementation and verification reference to the author
+//! of the line it sits on, using `git blame`, to show who does the
+//! traceability work and where verification of a spec rests on a single
+//! person. Like `tracey review`, this works directly on git and does not
+//! require the daemon.
+
+use eyre::Result;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracey_core::RuleId;
+
+use crate::bump::git_capture;
+use crate::config::{Config, VerbRole, verb_roles};
+
+/// Name git blame gives lines that are not committed yet.
+const UNCOMMITTED: &str = "Not Committed Yet";
+
+/// The author of a line, as recorded by git.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Author {
+    pub name: String,
+    /// Email without angle brackets; empty for uncommitted lines.
+    pub email: String,
+}
+
+impl std::fmt::Display for Author {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.email.is_empty() {
+            f.write_str(&self.name)
+        } else {
+            write!(f, "{} <{}>", self.name, self.email)
+        }
+    }
+}
+
+/// References written by one author.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorStats {
+    pub author: Author,
+    /// Implementation references.
+    pub impl_refs: usize,
+    /// Verification references.
+    pub verify_refs: usize,
+    /// Distinct rules the author references.
+    pub rules: usize,
+    /// Rules whose every verification reference was written by this author.
+    pub sole_verifier: Vec<RuleId>,
+}
+
+/// A spec whose verification references all come from one author.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SingleVerifier {
+    pub spec: String,
+    pub author: Author,
+    /// Number of verification references in the spec.
+    pub verify_refs: usize,
+}
+
+/// Who covers what.
+#[derive(Debug, Default)]
+pub struct ContributorsReport {
+    /// Authors, most references first.
+    pub authors: Vec<AuthorStats>,
+    /// Specs whose verification rests on a single author.
+    pub single_verifier_specs: Vec<SingleVerifier>,
+}
+
+impl ContributorsReport {
+    /// Render the report as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Contributors");
+        let _ = writeln!(out);
+        if self.authors.is_empty() {
+            let _ = writeln!(out, "No implementation or verification references found.");
+            return out;
+        }
+
+        let _ = writeln!(out, "| Author | impl | verify | Rules | Sole verifier |");
+        let _ = writeln!(out, "|--------|-----:|-------:|------:|--------------:|");
+        for stats in &self.authors {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                stats.author,
+                stats.impl_refs,
+                stats.verify_refs,
+                stats.rules,
+                stats.sole_verifier.len()
+            );
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## Bus factor");
+        let _ = writeln!(out);
+        if self.single_verifier_specs.is_empty() {
+            let _ = writeln!(out, "No spec relies on a single author for verification.");
+        }
+        for single in &self.single_verifier_specs {
+            let _ = writeln!(
+                out,
+                "- Every `verify` reference in spec `{}` ({}) was written by {}.",
+                single.spec, single.verify_refs, single.author
+            );
+        }
+        out
+    }
+}
+
+/// Parse `git blame --line-porcelain` output into the author of each line,
+/// keyed by 1-based line number.
+fn parse_blame(output: &str) -> HashMap<usize, Author> {
+    let mut authors = HashMap::new();
+    let mut line = 0;
+    let mut name = String::new();
+    let mut email = String::new();
+    for row in output.lines() {
+        if row.starts_with('\t') {
+            let email = if name == UNCOMMITTED {
+                String::new()
+            } else {
+                email.clone()
+            };
+            authors.insert(
+                line,
+                Author {
+                    name: name.clone(),
+                    email,
+                },
+            );
+        } else if let Some(value) = row.strip_prefix("author ") {
+            name = value.to_string();
+        } else if let Some(value) = row.strip_prefix("author-mail ") {
+            email = value
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string();
+        } else if let Some(final_line) = row.split(' ').nth(2)
+            && row.split(' ').next().is_some_and(|sha| sha.len() >= 40)
+        {
+            line = final_line.parse().unwrap_or(0);
+        }
+    }
+    authors
+}
+
+/// Blame every reference to a configured spec and tally it per author.
+// r[impl cli.contributors]
+pub async fn contributors(project_root: &Path, config: &Config) -> Result<ContributorsReport> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let roles = verb_roles(config);
+    let spec_of: BTreeMap<&str, &str> = data
+        .config
+        .specs
+        .iter()
+        .map(|s| (s.prefix.as_str(), s.name.as_str()))
+        .collect();
+
+    let uncommitted = Author {
+        name: UNCOMMITTED.to_string(),
+        email: String::new(),
+    };
+    let canonical_root = project_root.canonicalize().ok();
+
+    #[derive(Default)]
+    struct Tally {
+        impl_refs: usize,
+        verify_refs: usize,
+        rules: BTreeSet<(String, String)>,
+    }
+    let mut tallies: BTreeMap<Author, Tally> = BTreeMap::new();
+    // Authors of verification references, per rule and per spec.
+    let mut verifiers: BTreeMap<(String, String), (RuleId, BTreeSet<Author>)> = BTreeMap::new();
+    let mut spec_verifiers: BTreeMap<&str, (usize, BTreeSet<Author>)> = BTreeMap::new();
+
+    for (file, reqs) in &data.source_reqs_by_file {
+        let refs: Vec<_> = reqs
+            .references
+            .iter()
+            .filter(|r| spec_of.contains_key(r.prefix.as_str()))
+            .filter(|r| matches!(roles.role(&r.verb), VerbRole::Impl | VerbRole::Verify))
+            .collect();
+        if refs.is_empty() {
+            continue;
+        }
+
+        let relative = file
+            .strip_prefix(project_root)
+            .ok()
+            .or_else(|| file.strip_prefix(canonical_root.as_ref()?).ok())
+            .unwrap_or(file);
+        // Untracked files have no history; all their lines are uncommitted.
+        let blame = relative
+            .to_str()
+            .and_then(|path| {
+                git_capture(
+                    project_root,
+                    &["blame", "--line-porcelain", "-w", "-M", "--", path],
+                )
+                .ok()
+            })
+            .map(|out| parse_blame(&out))
+            .unwrap_or_default();
+
+        for r in refs {
+            let author = blame.get(&r.line).unwrap_or(&uncommitted).clone();
+            let rule = (r.prefix.clone(), r.req_id.base.clone());
+            let tally = tallies.entry(author.clone()).or_default();
+            tally.rules.insert(rule.clone());
+            if roles.role(&r.verb) == VerbRole::Impl {
+                tally.impl_refs += 1;
+                continue;
+            }
+            tally.verify_refs += 1;
+            verifiers
+                .entry(rule)
+                .or_insert_with(|| (r.req_id.clone(), BTreeSet::new()))
+                .1
+                .insert(author.clone());
+            let spec = spec_verifiers
+                .entry(spec_of[r.prefix.as_str()])
+                .or_default();
+            spec.0 += 1;
+            spec.1.insert(author);
+        }
+    }
+
+    let mut sole: BTreeMap<&Author, Vec<RuleId>> = BTreeMap::new();
+    for (id, authors) in verifiers.values() {
+        if let [author] = Vec::from_iter(authors).as_slice() {
+            sole.entry(author).or_default().push(id.clone());
+        }
+    }
+
+    let mut authors: Vec<AuthorStats> = tallies
+        .iter()
+        .map(|(author, tally)| AuthorStats {
+            author: author.clone(),
+            impl_refs: tally.impl_refs,
+            verify_refs: tally.verify_refs,
+            rules: tally.rules.len(),
+            sole_verifier: sole.remove(author).unwrap_or_default(),
+        })
+        .collect();
+    authors.sort_by(|a, b| {
+        (b.impl_refs + b.verify_refs)
+            .cmp(&(a.impl_refs + a.verify_refs))
+            .then_with(|| a.author.cmp(&b.author))
+    });
+
+    let single_verifier_specs = spec_verifiers
+        .into_iter()
+        .filter_map(|(spec, (verify_refs, authors))| {
+            let [author] = Vec::from_iter(authors).try_into().ok()?;
+            Some(SingleVerifier {
+                spec: spec.to_string(),
+                author,
+                verify_refs,
+            })
+        })
+        .collect();
+
+    Ok(ContributorsReport {
+        authors,
+        single_verifier_specs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(name: &str, email: &str) -> Author {
+        Author {
+            name: name.to_string(),
+            email: email.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_line_porcelain() {
+        let output = "\
+1111111111111111111111111111111111111111 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1700000000
+summary initial
+filename src/lib.rs
+\t// r[impl auth.login]
+1111111111111111111111111111111111111111 2 2
+author Alice
+author-mail <alice@example.com>
+filename src/lib.rs
+\tfn login() {}
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+filename src/lib.rs
+\t// r[verify auth.login]
+";
+        let blame = parse_blame(output);
+        assert_eq!(blame[&1], author("Alice", "alice@example.com"));
+        assert_eq!(blame[&2], author("Alice", "alice@example.com"));
+        assert_eq!(blame[&3], author(UNCOMMITTED, ""));
+    }
+
+    // r[verify cli.contributors]
+    #[test]
+    fn markdown_lists_authors_and_bus_factor() {
+        let alice = author("Alice", "alice@example.com");
+        let report = ContributorsReport {
+            authors: vec![AuthorStats {
+                author: alice.clone(),
+                impl_refs: 3,
+                verify_refs: 2,
+                rules: 4,
+                sole_verifier: vec![tracey_core::parse_rule_id("auth.login").unwrap()],
+            }],
+            single_verifier_specs: vec![SingleVerifier {
+                spec: "api".to_string(),
+                author: alice,
+                verify_refs: 2,
+            }],
+        };
+        let md = report.to_markdown();
+        assert!(
+            md.contains("| Alice <alice@example.com> | 3 | 2 | 4 | 1 |"),
+            "{md}"
+        );
+        assert!(
+            md.contains(
+                "- Every `verify` reference in spec `api` (2) was written by Alice <alice@example.com>."
+            ),
+            "{md}"
+        );
+
+        let empty = ContributorsReport::default().to_markdown();
+        assert!(empty.contains("No implementation or verification references found."));
+    }
+}
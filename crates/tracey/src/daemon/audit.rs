@@ -0,0 +1,108 @@
+//! Audit trail of mutations made through the daemon.
+//!
+//! r[impl daemon.audit.record]
+//!
+//! Every RPC that writes to the project (inline edits from the dashboard,
+//! include/exclude patterns added to the config) appends one [`AuditEntry`]
+//! to `.tracey/audit.jsonl`, with the client that asked for it and the hash
+//! of the file before and after. The file is only ever appended to, except
+//! when retention trims it to its newest entries; the `audit` RPC reads it
+//! back for review.
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracey_proto::AuditEntry;
+
+/// Audit log, relative to the project root.
+pub const AUDIT_FILE: &str = ".tracey/audit.jsonl";
+
+/// Client recorded when the caller did not say who it is.
+const UNKNOWN_CLIENT: &str = "unknown";
+
+/// Held while appending or trimming, so a trim never drops a new entry.
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Path of the audit log for a project.
+pub fn audit_path(project_root: &Path) -> PathBuf {
+    project_root.join(AUDIT_FILE)
+}
+
+/// Blake3 hex digest of a file's content, as recorded in the log.
+pub fn content_hash(content: &[u8]) -> String {
+    blake3::hash(content).to_hex().to_string()
+}
+
+/// Build an entry for a change to `path` made just now.
+pub fn entry(
+    project_root: &Path,
+    client: Option<&str>,
+    action: &str,
+    path: &Path,
+    before: &[u8],
+    after: &[u8],
+    detail: Option<String>,
+) -> AuditEntry {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    AuditEntry {
+        timestamp,
+        client: client
+            .filter(|c| !c.trim().is_empty())
+            .unwrap_or(UNKNOWN_CLIENT)
+            .to_string(),
+        action: action.to_string(),
+        path: path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .display()
+            .to_string(),
+        before_hash: content_hash(before),
+        after_hash: content_hash(after),
+        detail,
+    }
+}
+
+/// Append an entry to the project's audit log.
+pub fn record(project_root: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    let path = audit_path(project_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let line = facet_json::to_string(entry).map_err(std::io::Error::other)?;
+    let _lock = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{line}")
+}
+
+/// Keep only the newest `max` entries of the audit log. Returns the bytes
+/// removed, or `None` if there was nothing to trim.
+// r[impl daemon.state.retention]
+pub fn trim(project_root: &Path, max: usize) -> std::io::Result<Option<u64>> {
+    let _lock = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    super::retention::keep_newest_lines(&audit_path(project_root), max)
+}
+
+/// The last `limit` entries of the audit log, newest first. A missing log is
+/// empty; lines that don't parse are skipped.
+// r[impl daemon.audit.query]
+pub fn load(project_root: &Path, limit: usize) -> std::io::Result<Vec<AuditEntry>> {
+    let content = match std::fs::read_to_string(audit_path(project_root)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| facet_json::from_str(line).ok())
+        .take(limit)
+        .collect())
+}
@@ -19,7 +19,8 @@ use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
 use crate::data::{
-    BuildCache, DashboardData, FileOverlay, build_dashboard_data_with_overlay_and_cache,
+    BuildCache, DashboardData, FileOverlay, ImplKey, RebuildScope,
+    build_dashboard_data_with_overlay_and_cache,
 };
 use crate::search::{self, SearchIndex, SearchResult};
 
@@ -61,16 +62,28 @@ pub struct Engine {
     rebuild_notify: Arc<Notify>,
 }
 
+/// Result of one rebuild pass.
+#[derive(Debug, Clone)]
+pub struct RebuildOutcome {
+    pub version: u64,
+    pub elapsed: Duration,
+    /// Implementations whose forward or reverse data differs from the
+    /// previous build
+    pub changed: Vec<ImplKey>,
+}
+
 #[derive(Default)]
 struct RebuildCoalesceState {
     in_progress: bool,
     pending_full_rebuild: bool,
     pending_changed_files: BTreeSet<PathBuf>,
+    /// Union of the scopes of pending requests, if any are pending
+    pending_scope: Option<RebuildScope>,
     generation: u64,
     next_ticket: u64,
     pending_ticket: u64,
     applied_ticket: u64,
-    last_result: Option<std::result::Result<(u64, Duration), String>>,
+    last_result: Option<std::result::Result<RebuildOutcome, String>>,
 }
 
 impl Engine {
@@ -93,8 +106,8 @@ impl Engine {
             (Config::default(), Some(err))
         } else {
             match tokio::fs::read_to_string(&config_path).await {
-                Ok(content) => match facet_styx::from_str(&content) {
-                    Ok(config) => (config, None),
+                Ok(content) => match facet_styx::from_str::<Config>(&content) {
+                    Ok(config) => (config.resolve_defaults(), None),
                     Err(e) => {
                         // Config has errors - use empty config and record error
                         let err = Self::format_config_error(&config_path, e);
@@ -129,6 +142,7 @@ impl Engine {
             &overlay,
             &mut build_cache,
             &[],
+            None,
         )
         .await
         {
@@ -147,6 +161,7 @@ impl Engine {
                     &overlay,
                     &mut build_cache,
                     &[],
+                    None,
                 )
                 .await?
             }
@@ -236,7 +251,7 @@ impl Engine {
         debug!("VFS: opened {}", path.display());
         // Trigger rebuild
         drop(vfs);
-        self.schedule_rebuild_with_changes(&[path]).await;
+        self.schedule_rebuild_for_path(path).await;
     }
 
     /// Update a file in the VFS overlay (from LSP didChange).
@@ -248,7 +263,7 @@ impl Engine {
         debug!("VFS: changed {}", path.display());
         // Trigger rebuild
         drop(vfs);
-        self.schedule_rebuild_with_changes(&[path]).await;
+        self.schedule_rebuild_for_path(path).await;
     }
 
     /// Remove a file from the VFS overlay (from LSP didClose).
@@ -260,7 +275,29 @@ impl Engine {
         debug!("VFS: closed {}", path.display());
         // Trigger rebuild
         drop(vfs);
-        self.schedule_rebuild_with_changes(&[path]).await;
+        self.schedule_rebuild_for_path(path).await;
+    }
+
+    /// Compare the client's open files against the VFS overlay by content
+    /// hash, returning the paths that are missing or out of date.
+    ///
+    /// Overlay entries the client does not mention are left alone: they may
+    /// belong to another client of the same daemon.
+    ///
+    /// r[impl daemon.vfs.sync]
+    pub async fn vfs_sync(&self, entries: Vec<(PathBuf, String)>) -> Vec<PathBuf> {
+        let vfs = self.vfs.read().await;
+        let needed: Vec<PathBuf> = entries
+            .into_iter()
+            .filter(|(path, hash)| {
+                vfs.get(path).is_none_or(|content| {
+                    blake3::hash(content.as_bytes()).to_hex().as_str() != hash
+                })
+            })
+            .map(|(path, _)| path)
+            .collect();
+        debug!("VFS: sync needs {} file(s)", needed.len());
+        needed
     }
 
     /// Force a rebuild of the dashboard data.
@@ -273,11 +310,48 @@ impl Engine {
     }
 
     pub async fn rebuild_with_changes(&self, changed_files: &[PathBuf]) -> Result<(u64, Duration)> {
-        self.run_coalesced_rebuild(changed_files, true).await
+        let outcome = self
+            .run_coalesced_rebuild(changed_files, RebuildScope::default(), true)
+            .await?;
+        Ok((outcome.version, outcome.elapsed))
+    }
+
+    /// Rebuild only the implementations in `scope`, carrying the others over
+    /// from the current data. `changed_files` narrows the rescan of the
+    /// implementations in scope, like the file watcher does; when empty they
+    /// are rescanned in full.
+    pub async fn rebuild_scope(
+        &self,
+        scope: RebuildScope,
+        changed_files: &[PathBuf],
+    ) -> Result<RebuildOutcome> {
+        self.run_coalesced_rebuild(changed_files, scope, true).await
     }
 
     pub async fn schedule_rebuild_with_changes(&self, changed_files: &[PathBuf]) {
-        if let Err(e) = self.run_coalesced_rebuild(changed_files, false).await {
+        self.schedule_scoped_rebuild(changed_files, RebuildScope::default())
+            .await;
+    }
+
+    /// Rebuild after an editor change to `path`, limited to the specs and
+    /// implementations that include it, like a file watcher rebuild.
+    ///
+    /// r[impl daemon.reload.vfs-scope]
+    async fn schedule_rebuild_for_path(&self, path: PathBuf) {
+        let scope = match path.strip_prefix(&self.project_root) {
+            Ok(relative) => {
+                RebuildScope::for_paths(&*self.config.read().await, &[relative.to_path_buf()])
+            }
+            Err(_) => RebuildScope::default(),
+        };
+        self.schedule_scoped_rebuild(&[path], scope).await;
+    }
+
+    pub async fn schedule_scoped_rebuild(&self, changed_files: &[PathBuf], scope: RebuildScope) {
+        if let Err(e) = self
+            .run_coalesced_rebuild(changed_files, scope, false)
+            .await
+        {
             error!("Scheduled rebuild failed: {}", e);
         }
     }
@@ -285,12 +359,17 @@ impl Engine {
     async fn run_coalesced_rebuild(
         &self,
         changed_files: &[PathBuf],
+        scope: RebuildScope,
         wait_for_completion: bool,
-    ) -> Result<(u64, Duration)> {
+    ) -> Result<RebuildOutcome> {
         let waiter_ticket = {
             let mut state = self.rebuild_state.lock().await;
             state.next_ticket = state.next_ticket.saturating_add(1);
             let request_ticket = state.next_ticket;
+            state.pending_scope = Some(match state.pending_scope.take() {
+                Some(pending) => pending.union(scope),
+                None => scope,
+            });
 
             if changed_files.is_empty() {
                 state.pending_full_rebuild = true;
@@ -305,7 +384,7 @@ impl Engine {
                 if wait_for_completion {
                     Some(request_ticket)
                 } else {
-                    return Ok((self.version(), Duration::ZERO));
+                    return Ok(self.unchanged_outcome());
                 }
             } else {
                 state.in_progress = true;
@@ -320,11 +399,11 @@ impl Engine {
                     if state.applied_ticket >= target_ticket {
                         if let Some(result) = &state.last_result {
                             return match result {
-                                Ok(ok) => Ok(*ok),
+                                Ok(ok) => Ok(ok.clone()),
                                 Err(e) => Err(eyre::eyre!(e.clone())),
                             };
                         }
-                        return Ok((self.version(), Duration::ZERO));
+                        return Ok(self.unchanged_outcome());
                     }
                 }
                 self.rebuild_notify.notified().await;
@@ -332,30 +411,32 @@ impl Engine {
         }
 
         loop {
-            let (changed_batch, batch_ticket) = {
+            let (changed_batch, batch_scope, batch_ticket) = {
                 let mut state = self.rebuild_state.lock().await;
                 let pending_full = state.pending_full_rebuild;
                 state.pending_full_rebuild = false;
                 let ticket = state.pending_ticket;
+                let scope = state.pending_scope.take().unwrap_or_default();
 
                 if pending_full {
                     state.pending_changed_files.clear();
-                    (Vec::new(), ticket)
+                    (Vec::new(), scope, ticket)
                 } else {
                     (
                         std::mem::take(&mut state.pending_changed_files)
                             .into_iter()
                             .collect::<Vec<_>>(),
+                        scope,
                         ticket,
                     )
                 }
             };
 
-            let result = self.rebuild_once(&changed_batch).await;
+            let result = self.rebuild_once(&changed_batch, batch_scope).await;
             let should_continue = {
                 let mut state = self.rebuild_state.lock().await;
                 state.last_result = Some(match &result {
-                    Ok(v) => Ok(*v),
+                    Ok(v) => Ok(v.clone()),
                     Err(e) => Err(e.to_string()),
                 });
                 state.generation = state.generation.saturating_add(1);
@@ -376,13 +457,25 @@ impl Engine {
         }
     }
 
-    async fn rebuild_once(&self, changed_files: &[PathBuf]) -> Result<(u64, Duration)> {
+    fn unchanged_outcome(&self) -> RebuildOutcome {
+        RebuildOutcome {
+            version: self.version(),
+            elapsed: Duration::ZERO,
+            changed: Vec::new(),
+        }
+    }
+
+    async fn rebuild_once(
+        &self,
+        changed_files: &[PathBuf],
+        scope: RebuildScope,
+    ) -> Result<RebuildOutcome> {
         let start = Instant::now();
 
         // Reload config - record errors but continue with current config
         let (config, new_config_error) = match tokio::fs::read_to_string(&self.config_path).await {
-            Ok(content) => match facet_styx::from_str(&content) {
-                Ok(config) => (Some(config), None),
+            Ok(content) => match facet_styx::from_str::<Config>(&content) {
+                Ok(config) => (Some(config.resolve_defaults()), None),
                 Err(e) => {
                     let error_msg = Self::format_config_error(&self.config_path, e);
                     warn!("{}", error_msg);
@@ -408,11 +501,21 @@ impl Engine {
             }
         };
 
-        // Use new config if valid, otherwise keep the current one
-        let config = match config {
-            Some(cfg) => cfg,
-            None => self.config.read().await.clone(),
+        // Use new config if valid, otherwise keep the current one. Coverage
+        // carried over by a scoped rebuild was computed with the current
+        // config, so a config change widens the scope to everything.
+        let current_config = self.config.read().await.clone();
+        let scope = match &config {
+            Some(cfg)
+                if facet_json::to_string(cfg).ok()
+                    != facet_json::to_string(&current_config).ok() =>
+            {
+                RebuildScope::default()
+            }
+            _ => scope,
         };
+        let config = config.unwrap_or(current_config);
+        let previous = self.data().await;
 
         // Get current VFS overlay
         let overlay = self.vfs.read().await.clone();
@@ -431,6 +534,7 @@ impl Engine {
             &overlay,
             &mut build_cache,
             changed_files,
+            (!scope.is_everything()).then_some((previous.as_ref(), &scope)),
         )
         .await;
         let new_data = match build_result {
@@ -443,9 +547,13 @@ impl Engine {
                 );
                 let mut err = self.config_error.write().await;
                 *err = Some(semantic_error);
-                return Ok((self.version(), start.elapsed()));
+                return Ok(RebuildOutcome {
+                    elapsed: start.elapsed(),
+                    ..self.unchanged_outcome()
+                });
             }
         };
+        let changed = changed_impls(&previous, &new_data);
 
         // Acquire write lock and update (blocks all reads)
         {
@@ -478,11 +586,17 @@ impl Engine {
 
         let elapsed = start.elapsed();
         info!(
-            "Rebuild completed in {:?} (version {})",
-            elapsed, new_version
+            "Rebuild completed in {:?} (version {}, {} implementation(s) changed)",
+            elapsed,
+            new_version,
+            changed.len()
         );
 
-        Ok((new_version, elapsed))
+        Ok(RebuildOutcome {
+            version: new_version,
+            elapsed,
+            changed,
+        })
     }
 
     /// Get the project root path.
@@ -576,3 +690,27 @@ impl Engine {
         })
     }
 }
+
+/// Implementations whose forward or reverse data differs between two builds,
+/// including ones that only exist in one of them.
+fn changed_impls(old: &DashboardData, new: &DashboardData) -> Vec<ImplKey> {
+    let json = |data: &DashboardData, key: &ImplKey| {
+        (
+            data.forward_by_impl
+                .get(key)
+                .and_then(|f| facet_json::to_string(f).ok()),
+            data.reverse_by_impl
+                .get(key)
+                .and_then(|r| facet_json::to_string(r).ok()),
+        )
+    };
+    let keys: BTreeSet<&ImplKey> = old
+        .forward_by_impl
+        .keys()
+        .chain(new.forward_by_impl.keys())
+        .collect();
+    keys.into_iter()
+        .filter(|key| json(old, key) != json(new, key))
+        .cloned()
+        .collect()
+}
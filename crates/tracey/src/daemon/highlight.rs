@@ -0,0 +1,210 @@
+//! Syntax highlighting for the `file` RPC.
+//!
+//! r[impl daemon.highlight.parallel]
+//!
+//! Highlighters are checked out of a small pool and run on the blocking
+//! thread pool, so several files can be highlighted at once without holding
+//! a lock across the work. Output is kept in a byte-bounded LRU cache keyed
+//! by a Blake3 hash of the language and content; unchanged files are served
+//! from the cache.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of idle highlighters kept around for reuse.
+const MAX_IDLE_HIGHLIGHTERS: usize = 4;
+
+/// Files larger than this are not highlighted at all.
+const MAX_HIGHLIGHT_INPUT_BYTES: usize = 2 * 1024 * 1024;
+
+/// Total size budget for cached highlighted output.
+const MAX_CACHE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Pool of highlighters plus a cache of their output.
+pub struct HighlightPool {
+    idle: Mutex<Vec<arborium::Highlighter>>,
+    cache: Mutex<HighlightCache>,
+}
+
+impl Default for HighlightPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HighlightPool {
+    pub fn new() -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            cache: Mutex::new(HighlightCache::new(MAX_CACHE_BYTES)),
+        }
+    }
+
+    /// Highlight `content` as `lang`, returning HTML.
+    ///
+    /// Returns `None` if the input is too large to highlight or the
+    /// highlighter fails; callers fall back to escaped plain text.
+    pub async fn highlight(&self, lang: &'static str, content: &str) -> Option<Arc<str>> {
+        if content.len() > MAX_HIGHLIGHT_INPUT_BYTES {
+            return None;
+        }
+
+        let key = cache_key(lang, content);
+        if let Some(html) = self.cache.lock().unwrap().get(&key) {
+            return Some(html);
+        }
+
+        let mut hl = self.idle.lock().unwrap().pop().unwrap_or_default();
+        let owned = content.to_string();
+        let (hl, result) = tokio::task::spawn_blocking(move || {
+            let result = hl.highlight(lang, &owned);
+            (hl, result)
+        })
+        .await
+        .ok()?;
+
+        {
+            let mut idle = self.idle.lock().unwrap();
+            if idle.len() < MAX_IDLE_HIGHLIGHTERS {
+                idle.push(hl);
+            }
+        }
+
+        let html: Arc<str> = result.ok()?.into();
+        self.cache.lock().unwrap().insert(key, html.clone());
+        Some(html)
+    }
+}
+
+fn cache_key(lang: &str, content: &str) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(lang.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(content.as_bytes());
+    hasher.finalize()
+}
+
+struct CacheEntry {
+    html: Arc<str>,
+    last_used: u64,
+}
+
+/// Least-recently-used cache bounded by the total size of its values.
+struct HighlightCache {
+    entries: HashMap<blake3::Hash, CacheEntry>,
+    bytes: usize,
+    max_bytes: usize,
+    tick: u64,
+}
+
+impl HighlightCache {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            bytes: 0,
+            max_bytes,
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &blake3::Hash) -> Option<Arc<str>> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.tick;
+        Some(entry.html.clone())
+    }
+
+    fn insert(&mut self, key: blake3::Hash, html: Arc<str>) {
+        if html.len() > self.max_bytes {
+            return;
+        }
+        self.tick += 1;
+        if let Some(old) = self.entries.insert(
+            key,
+            CacheEntry {
+                html: html.clone(),
+                last_used: self.tick,
+            },
+        ) {
+            self.bytes -= old.html.len();
+        }
+        self.bytes += html.len();
+
+        // The cache holds at most a few hundred entries, so a linear scan
+        // for the oldest one is cheaper than maintaining a separate order.
+        while self.bytes > self.max_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| *k)
+            else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.bytes -= evicted.html.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> blake3::Hash {
+        cache_key("rust", s)
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let mut cache = HighlightCache::new(10);
+        cache.insert(key("a"), "aaaa".into());
+        cache.insert(key("b"), "bbbb".into());
+        // Touch `a` so `b` becomes the oldest entry.
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), "cccc".into());
+
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+        assert_eq!(cache.bytes, 8);
+    }
+
+    #[test]
+    fn cache_skips_oversized_values_and_replaces_in_place() {
+        let mut cache = HighlightCache::new(4);
+        cache.insert(key("big"), "too large".into());
+        assert!(cache.get(&key("big")).is_none());
+
+        cache.insert(key("a"), "aa".into());
+        cache.insert(key("a"), "aaa".into());
+        assert_eq!(cache.bytes, 3);
+        assert_eq!(cache.get(&key("a")).as_deref(), Some("aaa"));
+    }
+
+    #[test]
+    fn key_depends_on_language() {
+        assert_ne!(cache_key("rust", "x"), cache_key("go", "x"));
+    }
+
+    // r[verify daemon.highlight.parallel]
+    #[tokio::test]
+    async fn concurrent_highlights_share_cache() {
+        let pool = Arc::new(HighlightPool::new());
+        let src = "fn main() {}\n";
+        let tasks: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move { pool.highlight("rust", src).await })
+            })
+            .collect();
+        let mut outputs = Vec::new();
+        for task in tasks {
+            outputs.push(task.await.unwrap().expect("rust is highlightable"));
+        }
+        assert!(outputs.windows(2).all(|w| w[0] == w[1]));
+        assert_eq!(pool.cache.lock().unwrap().entries.len(), 1);
+        assert!(pool.idle.lock().unwrap().len() <= MAX_IDLE_HIGHLIGHTERS);
+    }
+}
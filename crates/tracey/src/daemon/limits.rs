@@ -0,0 +1,367 @@
+//! Concurrency limits and backpressure for daemon connections.
+//!
+//! r[impl daemon.limits.connections]
+//! r[impl daemon.limits.per-connection]
+//! r[impl daemon.limits.fair]
+//!
+//! Every call is classified by method name. Editor calls (`lsp_*` and the
+//! `vfs_*` overlay sync) are *interactive*; everything else the bridges send
+//! (MCP tools, HTTP dashboard data, CLI queries) is *batch*. Each class draws
+//! from its own global pool, so a flood of batch queries can never take the
+//! slots the editor needs. On top of that, each connection may only have a
+//! handful of calls in flight; further calls from the same connection wait in
+//! line behind it instead of in front of everyone else.
+//!
+//! Control calls (`health`, `version`, `shutdown`) and the long-lived
+//! `subscribe` stream bypass the limits, so a saturated daemon can still be
+//! inspected and stopped.
+//!
+//! A connection arriving when every slot is taken is answered by [`Refused`],
+//! which fails its calls with a "too many connections" error before the
+//! daemon hangs up.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use roam::{Handler, ReplySink, RequestCall, RoamError, SelfRef};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracey_proto::{QueueDepth, RequestLoad, tracey_daemon_service_descriptor};
+
+/// Maximum number of simultaneous client connections.
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Maximum number of calls a single connection may have running at once.
+pub const MAX_IN_FLIGHT_PER_CONNECTION: usize = 8;
+
+/// Global cap on running interactive (editor) calls.
+pub const MAX_INTERACTIVE_IN_FLIGHT: usize = 16;
+
+/// Global cap on running batch calls.
+pub const MAX_BATCH_IN_FLIGHT: usize = 8;
+
+/// How a call is scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallClass {
+    /// Latency-sensitive editor requests.
+    Interactive,
+    /// Everything else: MCP tools, dashboard data, CLI queries.
+    Batch,
+    /// Cheap or long-lived calls that are never queued.
+    Control,
+}
+
+impl CallClass {
+    /// Classify a `TraceyDaemon` method by name.
+    pub fn of(method_name: &str) -> Self {
+        match method_name {
+            "health" | "version" | "shutdown" | "subscribe" => CallClass::Control,
+            name if name.starts_with("lsp_") || name.starts_with("vfs_") => CallClass::Interactive,
+            _ => CallClass::Batch,
+        }
+    }
+}
+
+/// Counters for one class of calls.
+#[derive(Default)]
+struct Counters {
+    in_flight: AtomicU64,
+    queued: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> QueueDepth {
+        QueueDepth {
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            queued: self.queued.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Decrements a counter when dropped, so cancelled calls are accounted for.
+struct CountGuard<'a>(&'a AtomicU64);
+
+impl<'a> CountGuard<'a> {
+    fn new(counter: &'a AtomicU64) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for CountGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Daemon-wide limits, shared by all connections.
+pub struct Limiter {
+    max_connections: usize,
+    connections: Arc<Semaphore>,
+    interactive: Arc<Semaphore>,
+    batch: Arc<Semaphore>,
+    interactive_counters: Counters,
+    batch_counters: Counters,
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new(
+            MAX_CONNECTIONS,
+            MAX_INTERACTIVE_IN_FLIGHT,
+            MAX_BATCH_IN_FLIGHT,
+        )
+    }
+}
+
+impl Limiter {
+    /// Create a limiter with explicit caps.
+    pub fn new(connections: usize, interactive: usize, batch: usize) -> Self {
+        Self {
+            max_connections: connections,
+            connections: Arc::new(Semaphore::new(connections)),
+            interactive: Arc::new(Semaphore::new(interactive)),
+            batch: Arc::new(Semaphore::new(batch)),
+            interactive_counters: Counters::default(),
+            batch_counters: Counters::default(),
+        }
+    }
+
+    /// Reserve a connection slot, or `None` if the daemon is at capacity.
+    /// The slot is released when the returned permit is dropped.
+    pub fn try_connect(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.connections).try_acquire_owned().ok()
+    }
+
+    /// Current connection count and queue depths.
+    pub fn load(&self) -> RequestLoad {
+        RequestLoad {
+            connections: (self.max_connections - self.connections.available_permits()) as u64,
+            interactive: self.interactive_counters.snapshot(),
+            batch: self.batch_counters.snapshot(),
+        }
+    }
+
+    /// Run `fut` once both the connection's and the class's pool have room.
+    pub async fn run<F: Future>(
+        &self,
+        class: CallClass,
+        connection: &Semaphore,
+        fut: F,
+    ) -> F::Output {
+        let (pool, counters) = match class {
+            CallClass::Control => return fut.await,
+            CallClass::Interactive => (&self.interactive, &self.interactive_counters),
+            CallClass::Batch => (&self.batch, &self.batch_counters),
+        };
+
+        let queued = CountGuard::new(&counters.queued);
+        // Wait on the connection first, so a busy connection queues behind
+        // itself rather than holding a place in the global line.
+        let _conn = connection.acquire().await.expect("semaphore never closed");
+        let _global = pool.acquire().await.expect("semaphore never closed");
+        drop(queued);
+
+        let _running = CountGuard::new(&counters.in_flight);
+        fut.await
+    }
+}
+
+/// Wraps a connection's dispatcher to apply the [`Limiter`] to every call.
+pub struct Limited<H> {
+    inner: H,
+    limiter: Arc<Limiter>,
+    connection: Semaphore,
+}
+
+impl<H> Limited<H> {
+    /// Wrap `inner` for a single connection.
+    pub fn new(inner: H, limiter: Arc<Limiter>) -> Self {
+        Self {
+            inner,
+            limiter,
+            connection: Semaphore::new(MAX_IN_FLIGHT_PER_CONNECTION),
+        }
+    }
+}
+
+impl<H, R> Handler<R> for Limited<H>
+where
+    H: Handler<R>,
+    R: ReplySink,
+{
+    async fn handle(&self, call: SelfRef<RequestCall<'static>>, reply: R) {
+        let class = tracey_daemon_service_descriptor()
+            .by_id(call.method_id)
+            .map_or(CallClass::Batch, |m| CallClass::of(m.method_name));
+        self.limiter
+            .run(class, &self.connection, self.inner.handle(call, reply))
+            .await;
+    }
+}
+
+/// Answers every call on a connection the daemon has no room for.
+///
+/// Each call fails with a `User` error naming the limit; methods whose error
+/// type is not a string still fail instead of hanging.
+pub struct Refused;
+
+impl Refused {
+    /// The error message sent to refused connections.
+    pub fn message() -> String {
+        format!("too many connections ({MAX_CONNECTIONS} open); try again later")
+    }
+}
+
+impl<R: ReplySink> Handler<R> for Refused {
+    async fn handle(&self, _call: SelfRef<RequestCall<'static>>, reply: R) {
+        reply.send_error(RoamError::User(Self::message())).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn classifies_methods() {
+        assert_eq!(CallClass::of("lsp_hover"), CallClass::Interactive);
+        assert_eq!(CallClass::of("vfs_change"), CallClass::Interactive);
+        assert_eq!(CallClass::of("status"), CallClass::Batch);
+        assert_eq!(CallClass::of("search"), CallClass::Batch);
+        assert_eq!(CallClass::of("health"), CallClass::Control);
+        assert_eq!(CallClass::of("subscribe"), CallClass::Control);
+    }
+
+    #[test]
+    fn every_daemon_method_is_classified_by_its_rust_name() {
+        let names: Vec<_> = tracey_daemon_service_descriptor()
+            .methods
+            .iter()
+            .map(|m| m.method_name)
+            .collect();
+        assert!(names.contains(&"lsp_hover"));
+        assert!(names.contains(&"health"));
+        assert!(names.contains(&"subscribe"));
+    }
+
+    // r[verify daemon.limits.connections]
+    #[test]
+    fn connection_cap_is_enforced() {
+        let limiter = Limiter::new(2, 1, 1);
+        let a = limiter.try_connect();
+        let b = limiter.try_connect();
+        assert!(a.is_some() && b.is_some());
+        assert!(limiter.try_connect().is_none());
+        drop(a);
+        assert!(limiter.try_connect().is_some());
+    }
+
+    // r[verify daemon.limits.connections]
+    #[tokio::test]
+    async fn refused_connections_are_told_why() {
+        use tracey_proto::{CompareRequest, TraceyDaemonClient};
+
+        let (client_link, server_link) = roam_core::memory_link_pair(16);
+        let (server, client) = tokio::try_join!(
+            roam::acceptor(server_link).establish::<TraceyDaemonClient>(Refused),
+            roam::initiator(client_link).establish::<TraceyDaemonClient>(()),
+        )
+        .unwrap();
+        let (_server, _server_session) = server;
+        let (client, _client_session) = client;
+
+        let request = CompareRequest {
+            spec: None,
+            impls: vec!["rust".to_string(), "go".to_string()],
+        };
+        match client.compare(request).await {
+            Err(RoamError::User(message)) => assert!(message.contains("too many connections")),
+            other => panic!("expected a refusal, got {other:?}"),
+        }
+        assert!(client.status().await.is_err());
+    }
+
+    // r[verify daemon.limits.fair]
+    // r[verify daemon.limits.health]
+    #[tokio::test]
+    async fn batch_saturation_does_not_block_interactive() {
+        let limiter = Arc::new(Limiter::new(4, 1, 1));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let busy = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                let conn = Semaphore::new(4);
+                limiter
+                    .run(CallClass::Batch, &conn, async {
+                        let _ = release_rx.await;
+                    })
+                    .await;
+            })
+        };
+        let queued = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                let conn = Semaphore::new(4);
+                limiter.run(CallClass::Batch, &conn, async {}).await;
+            })
+        };
+
+        // Wait until the second batch call is parked behind the first.
+        while limiter.load().batch.queued == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let load = limiter.load();
+        assert_eq!(load.batch.in_flight, 1);
+        assert_eq!(load.batch.queued, 1);
+
+        let conn = Semaphore::new(4);
+        let answer = tokio::time::timeout(
+            Duration::from_secs(5),
+            limiter.run(CallClass::Interactive, &conn, async { 42 }),
+        )
+        .await
+        .expect("interactive call was blocked by batch calls");
+        assert_eq!(answer, 42);
+
+        release_tx.send(()).unwrap();
+        busy.await.unwrap();
+        queued.await.unwrap();
+        let load = limiter.load();
+        assert_eq!((load.batch.in_flight, load.batch.queued), (0, 0));
+    }
+
+    // r[verify daemon.limits.per-connection]
+    #[tokio::test]
+    async fn per_connection_cap_queues_excess_calls() {
+        let limiter = Arc::new(Limiter::new(4, 8, 8));
+        let conn = Arc::new(Semaphore::new(1));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let first = {
+            let (limiter, conn) = (Arc::clone(&limiter), Arc::clone(&conn));
+            tokio::spawn(async move {
+                limiter
+                    .run(CallClass::Batch, &conn, async {
+                        let _ = release_rx.await;
+                    })
+                    .await;
+            })
+        };
+        while limiter.load().batch.in_flight == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(50),
+            limiter.run(CallClass::Batch, &conn, async {}),
+        )
+        .await;
+        assert!(blocked.is_err(), "second call should wait for the first");
+
+        release_tx.send(()).unwrap();
+        first.await.unwrap();
+        limiter.run(CallClass::Batch, &conn, async {}).await;
+    }
+}
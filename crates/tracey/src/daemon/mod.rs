@@ -12,6 +12,11 @@
 //! `$XDG_STATE_HOME/tracey/<hash>` (or platform equivalent), where `<hash>`
 //! is a truncated Blake3 hash of the canonical project root path. A
 //! `project-root` metadata file inside each state dir enables reverse lookups.
+//! The paths are computed in [`tracey_client::state`], shared with clients.
+//! Logs are pruned periodically by [`retention`] according to config limits.
+//! Coverage changes are pushed to a configured URL by [`webhook`].
+//! Connections and in-flight requests are capped by [`limits`].
+//! Changes made through the daemon are logged by [`audit`].
 //!
 //! ## Socket Location
 //!
@@ -26,10 +31,14 @@
 //! - Daemon exits after idle timeout (no connections for N minutes)
 //! - Stale socket files are cleaned up on connect failure
 
-pub mod client;
+pub mod audit;
 pub mod engine;
+pub mod highlight;
+pub mod limits;
+pub mod retention;
 pub mod service;
 pub mod watcher;
+pub mod webhook;
 
 use eyre::{Result, WrapErr};
 use roam_stream::LocalLinkAcceptor;
@@ -39,150 +48,23 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
+use crate::data::RebuildScope;
+use limits::Limited;
 use service::TraceyDaemonDispatcher;
 use watcher::{WatcherEvent, WatcherManager, WatcherState};
 
-pub use client::{DaemonClient, DaemonConnector, new_client};
 pub use engine::Engine;
 pub use service::TraceyService;
+pub use tracey_client::state::{
+    ensure_state_dir, is_pid_alive, local_endpoint, pid_file_path, read_pid_file_at,
+    state_base_dir, state_dir,
+};
+pub use tracey_client::{DaemonClient, DaemonConnector, new_client};
 pub use watcher::WatcherState as DaemonWatcherState;
 
 /// Default idle timeout in seconds (10 minutes)
 const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
 
-/// Socket file name within the state directory (Unix only)
-#[cfg(unix)]
-const SOCKET_FILENAME: &str = "daemon.sock";
-
-/// Return the base directory that contains all per-project state directories.
-///
-/// Resolved via `dirs::state_dir()` with platform-specific fallbacks
-/// (`~/.local/state` on Linux, `~/Library/Application Support` on macOS
-/// via `dirs::data_local_dir()`). Returns `{state_home}/tracey/`.
-pub fn state_base_dir() -> PathBuf {
-    let base = dirs::state_dir()
-        .or_else(dirs::data_local_dir)
-        .unwrap_or_else(|| {
-            dirs::home_dir()
-                .expect("could not determine home directory")
-                .join(".local/state")
-        });
-
-    base.join("tracey")
-}
-
-fn state_dir_inner(project_root: &Path) -> (PathBuf, PathBuf) {
-    let canonical =
-        std::fs::canonicalize(project_root).unwrap_or_else(|_| project_root.to_path_buf());
-    let hash = blake3::hash(canonical.as_os_str().as_encoded_bytes());
-    let short_hash = &hash.to_hex()[..16];
-
-    (state_base_dir().join(short_hash), canonical)
-}
-
-/// Compute the per-project state directory path.
-///
-/// Returns `{state_home}/tracey/{hash}` where `hash` is the first 16 hex
-/// characters of the Blake3 hash of the canonical project root path.
-pub fn state_dir(project_root: &Path) -> PathBuf {
-    state_dir_inner(project_root).0
-}
-
-/// Create the per-project state directory and write a `project-root` metadata
-/// file for reverse lookups. Returns the directory path.
-pub fn ensure_state_dir(project_root: &Path) -> Result<PathBuf> {
-    let (dir, canonical) = state_dir_inner(project_root);
-    std::fs::create_dir_all(&dir)?;
-
-    let meta_path = dir.join("project-root");
-    std::fs::write(&meta_path, canonical.as_os_str().as_encoded_bytes())?;
-
-    Ok(dir)
-}
-
-/// Get the local IPC endpoint for a workspace.
-///
-/// On Unix, this returns a string representing a path to `<state_dir>/daemon.sock`.
-/// On Windows, this returns a string representing a named pipe path like `\\.\pipe\tracey-{hash}`.
-///
-/// r[impl daemon.roam.unix-socket]
-#[cfg(unix)]
-pub fn local_endpoint(project_root: &Path) -> String {
-    state_dir(project_root)
-        .join(SOCKET_FILENAME)
-        .to_string_lossy()
-        .into_owned()
-}
-
-/// Get the local IPC endpoint for a workspace.
-///
-/// On Unix, this returns a string representing a path to `<state_dir>/daemon.sock`.
-/// On Windows, this returns a string representing a named pipe path like `\\.\pipe\tracey-{hash}`.
-#[cfg(windows)]
-pub fn local_endpoint(project_root: &Path) -> String {
-    let dir = state_dir(project_root);
-    let hash = dir
-        .file_name()
-        .and_then(|n| n.to_str())
-        .expect("state_dir hash");
-    format!(r"\\.\pipe\tracey-{hash}").into()
-}
-
-/// Path to the daemon PID file within the state directory.
-pub fn pid_file_path(project_root: &Path) -> PathBuf {
-    state_dir(project_root).join("daemon.pid")
-}
-
-/// Check whether a process with the given PID is alive.
-#[cfg(unix)]
-pub fn is_pid_alive(pid: u32) -> bool {
-    // Signal 0 doesn't send a signal; it just checks whether the process exists.
-    unsafe extern "C" {
-        fn kill(pid: i32, sig: i32) -> i32;
-    }
-    unsafe { kill(pid as i32, 0) == 0 }
-}
-
-/// Check whether a process with the given PID is alive.
-#[cfg(not(unix))]
-pub fn is_pid_alive(_pid: u32) -> bool {
-    true // best-effort on non-Unix; rely on socket connect to detect dead daemon
-}
-
-/// Read a PID file at the given path and return `(pid, protocol_version)` if it
-/// parses correctly. Returns `None` if the file doesn't exist or is malformed.
-pub fn read_pid_file_at(path: &Path) -> Option<(u32, u32)> {
-    let content = match std::fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
-        Err(e) => {
-            warn!("Failed to read PID file {}: {e}", path.display());
-            return None;
-        }
-    };
-
-    let mut pid = None;
-    let mut version = None;
-    for line in content.lines() {
-        if let Some(v) = line.strip_prefix("pid=") {
-            pid = v.parse().ok();
-        } else if let Some(v) = line.strip_prefix("version=") {
-            version = v.parse().ok();
-        }
-    }
-
-    match (pid, version) {
-        (Some(p), Some(v)) => Some((p, v)),
-        _ => {
-            warn!(
-                "PID file {} has unexpected format, ignoring it",
-                path.display()
-            );
-            None
-        }
-    }
-}
-
 /// RAII guard that writes the PID file on creation and removes it on drop.
 struct PidFile {
     path: PathBuf,
@@ -332,7 +214,8 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
                     let mut exclude_patterns: Vec<String> = Vec::new();
 
                     // Get patterns from the raw config file if available
-                    if let Ok(config) = crate::load_config(&config_path_for_rebuild) {
+                    let config = crate::load_config(&config_path_for_rebuild).ok();
+                    if let Some(config) = &config {
                         for spec in &config.specs {
                             for pattern in &spec.include {
                                 include_patterns.push(pattern.clone());
@@ -438,18 +321,54 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
                         );
                     }
 
+                    // r[impl daemon.reload.watcher-scope]
+                    // Only recompute the implementations (or specs) whose
+                    // files changed.
+                    let scope = config
+                        .as_ref()
+                        .map(|config| RebuildScope::for_paths(config, &relative_paths))
+                        .unwrap_or_default();
                     let changed_abs: Vec<PathBuf> = relative_paths
                         .iter()
                         .map(|p| project_root_for_rebuild.join(p))
                         .collect();
                     engine_for_rebuild
-                        .schedule_rebuild_with_changes(&changed_abs)
+                        .schedule_scoped_rebuild(&changed_abs, scope)
                         .await;
                 }
             }
         }
     });
 
+    // r[impl daemon.state.retention]
+    // Periodically prune logs in the state directory and the project's
+    // records. Limits are re-read from config on every pass so edits take
+    // effect without a restart.
+    let state_dir_for_prune = state_dir(&project_root);
+    let project_root_for_prune = project_root.clone();
+    let config_path_for_prune = config_path.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(retention::PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = crate::load_config(&config_path_for_prune).ok();
+            let limits =
+                retention::RetentionLimits::from_config(config.and_then(|c| c.retention).as_ref());
+            match retention::prune(&state_dir_for_prune, &project_root_for_prune, &limits) {
+                Ok(freed) if freed.files > 0 => info!(
+                    "Pruned {} file(s), freed {}",
+                    freed.files,
+                    retention::format_bytes(freed.bytes)
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to prune state directory: {e}"),
+            }
+        }
+    });
+
+    // Notify the configured webhook, if any, of every rebuild.
+    tokio::spawn(webhook::run(Arc::clone(&engine), config_path.clone()));
+
     // Bind local IPC listener
     // Note: on Windows, accept() takes &mut self (to swap server instances)
     #[cfg(unix)]
@@ -489,12 +408,25 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
                 last_activity.store(start_time.elapsed().as_secs(), Ordering::Relaxed);
                 info!("New connection accepted");
 
+                // r[impl daemon.limits.connections]
+                let limiter = service.limiter();
+                let Some(connection_slot) = limiter.try_connect() else {
+                    warn!(
+                        "Refusing connection: {} connections already open",
+                        limits::MAX_CONNECTIONS
+                    );
+                    tokio::spawn(refuse(stream));
+                    continue;
+                };
+
                 let service = service.clone();
                 let last_activity = Arc::clone(&last_activity);
 
                 tokio::spawn(async move {
-                    // Create dispatcher (wraps service with generated dispatch + tracing)
-                    let dispatcher = TraceyDaemonDispatcher::new(service);
+                    let _connection_slot = connection_slot;
+                    // Create dispatcher (wraps service with generated dispatch + tracing),
+                    // limiting how many of its calls run at once
+                    let dispatcher = Limited::new(TraceyDaemonDispatcher::new(service), limiter);
                     let (session_task_tx, session_task_rx) =
                         tokio::sync::oneshot::channel::<tokio::task::JoinHandle<()>>();
 
@@ -543,6 +475,39 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
     }
 }
 
+/// How long a refused connection is kept open for its calls to be answered.
+const REFUSED_LINGER: Duration = Duration::from_secs(5);
+
+/// Answer a connection the daemon has no slot for with "too many
+/// connections" errors until the client hangs up or [`REFUSED_LINGER`]
+/// passes.
+///
+/// r[impl daemon.limits.connections]
+async fn refuse(stream: roam_stream::LocalLink) {
+    let (session_task_tx, session_task_rx) =
+        tokio::sync::oneshot::channel::<tokio::task::JoinHandle<()>>();
+    match roam::acceptor(stream)
+        .spawn_fn(move |fut| {
+            let _ = session_task_tx.send(tokio::spawn(fut));
+        })
+        .establish::<tracey_proto::TraceyDaemonClient>(limits::Refused)
+        .await
+    {
+        Ok((_client_guard, _session_handle)) => {
+            if let Ok(session_task) = session_task_rx.await {
+                let abort = session_task.abort_handle();
+                if tokio::time::timeout(REFUSED_LINGER, session_task)
+                    .await
+                    .is_err()
+                {
+                    abort.abort();
+                }
+            }
+        }
+        Err(e) => debug!("Refused connection closed during setup: {}", e),
+    }
+}
+
 /// Build a gitignore matcher for the project.
 ///
 /// r[impl server.watch.respect-gitignore]
@@ -0,0 +1,675 @@
+//! Size-aware retention for the per-project state directory.
+//!
+//! r[impl daemon.state.retention]
+//!
+//! The state directory holds runtime files (socket, PID file, lock file,
+//! `project-root`) and logs: the daemon appends to `daemon.log` and every
+//! bridge process writes its own `{bridge}-{pid}.log`. Runtime files are never
+//! touched here; logs are pruned by age and total size, and can be cleared on
+//! demand with `tracey cache clear`.
+//!
+//! Some kinds live in the project's `.tracey` directory instead, such as the
+//! proof index that `tracey proofs import-kani` writes. Those are reported and
+//! cleared alongside the state directory. Append-only records like the
+//! coverage history are only cleared when asked for by name, and are pruned
+//! to their newest entries when a limit is configured.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use crate::config::RetentionConfig;
+
+/// Default cap on the total size of log files, in megabytes.
+pub const DEFAULT_MAX_LOG_MB: u64 = 50;
+
+/// Default age after which logs of exited bridges are removed, in days.
+pub const DEFAULT_MAX_LOG_AGE_DAYS: u64 = 7;
+
+/// How often the daemon prunes its state directory.
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Name of the daemon log within the state directory.
+const DAEMON_LOG: &str = "daemon.log";
+
+/// A category of files in the state directory that can be cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    /// Daemon and bridge logs.
+    Logs,
+    /// Imported proof results (`.tracey/proofs.json`). Tracey cannot
+    /// rebuild them: they come back only by importing the Kani output again.
+    Proofs,
+    /// Coverage snapshots appended by `tracey history`
+    /// (`.tracey/history.jsonl`).
+    History,
+    /// The daemon's record of the writes it made (`.tracey/audit.jsonl`).
+    Audit,
+}
+
+impl CacheKind {
+    /// Every kind, in display order.
+    pub const ALL: &[CacheKind] = &[
+        CacheKind::Logs,
+        CacheKind::Proofs,
+        CacheKind::History,
+        CacheKind::Audit,
+    ];
+
+    /// The file holding this kind, relative to the project root, for kinds
+    /// kept in the project rather than the state directory.
+    fn project_file(self) -> Option<&'static str> {
+        match self {
+            CacheKind::Logs => None,
+            CacheKind::Proofs => Some(crate::proofs::PROOFS_FILE),
+            CacheKind::History => Some(crate::history::HISTORY_FILE),
+            CacheKind::Audit => Some(super::audit::AUDIT_FILE),
+        }
+    }
+
+    /// Whether a file in the state directory belongs to this kind.
+    fn matches(self, file_name: &str) -> bool {
+        match self {
+            CacheKind::Logs => file_name.ends_with(".log"),
+            CacheKind::Proofs | CacheKind::History | CacheKind::Audit => false,
+        }
+    }
+
+    /// Whether `tracey cache clear` without `--kind` clears this kind.
+    /// Records that can't be recreated are only cleared by name.
+    pub fn cleared_by_default(self) -> bool {
+        match self {
+            CacheKind::Logs => true,
+            CacheKind::Proofs | CacheKind::History | CacheKind::Audit => false,
+        }
+    }
+}
+
+impl fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheKind::Logs => f.write_str("logs"),
+            CacheKind::Proofs => f.write_str("proofs"),
+            CacheKind::History => f.write_str("history"),
+            CacheKind::Audit => f.write_str("audit"),
+        }
+    }
+}
+
+impl FromStr for CacheKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CacheKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| {
+                let available: Vec<_> = CacheKind::ALL.iter().map(|k| k.to_string()).collect();
+                format!(
+                    "unknown cache kind `{s}` (available: {})",
+                    available.join(", ")
+                )
+            })
+    }
+}
+
+/// Retention limits resolved from config, with defaults filled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionLimits {
+    /// Total size budget for log files, in bytes.
+    pub max_log_bytes: u64,
+    /// Logs of exited bridges older than this are removed.
+    pub max_log_age: Duration,
+    /// Number of newest history snapshots to keep, or `None` to keep all.
+    pub max_history_entries: Option<usize>,
+    /// Number of newest audit entries to keep, or `None` to keep all.
+    pub max_audit_entries: Option<usize>,
+}
+
+impl Default for RetentionLimits {
+    fn default() -> Self {
+        Self::from_config(None)
+    }
+}
+
+impl RetentionLimits {
+    /// Resolve limits from the `retention` config block, if any.
+    pub fn from_config(config: Option<&RetentionConfig>) -> Self {
+        let max_log_mb = config
+            .and_then(|c| c.max_log_mb)
+            .unwrap_or(DEFAULT_MAX_LOG_MB);
+        let max_log_age_days = config
+            .and_then(|c| c.max_log_age_days)
+            .unwrap_or(DEFAULT_MAX_LOG_AGE_DAYS);
+        Self {
+            max_log_bytes: max_log_mb.saturating_mul(1024 * 1024),
+            max_log_age: Duration::from_secs(max_log_age_days.saturating_mul(24 * 60 * 60)),
+            max_history_entries: entry_limit(config.and_then(|c| c.max_history_entries)),
+            max_audit_entries: entry_limit(config.and_then(|c| c.max_audit_entries)),
+        }
+    }
+}
+
+fn entry_limit(limit: Option<u64>) -> Option<usize> {
+    limit.map(|n| usize::try_from(n).unwrap_or(usize::MAX))
+}
+
+/// File count and total size of a group of files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl Usage {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+/// Disk usage of a state directory and project files, broken down by kind.
+#[derive(Debug, Clone, Default)]
+pub struct StateStats {
+    /// Usage per clearable kind.
+    pub kinds: Vec<(CacheKind, Usage)>,
+    /// Runtime files (socket, PID file, locks) that are never cleared.
+    pub runtime: Usage,
+}
+
+impl StateStats {
+    /// Total usage across all files.
+    pub fn total(&self) -> Usage {
+        let mut total = self.runtime;
+        for (_, usage) in &self.kinds {
+            total.files += usage.files;
+            total.bytes += usage.bytes;
+        }
+        total
+    }
+}
+
+/// A file in the state directory or the project.
+struct Entry {
+    path: PathBuf,
+    name: String,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// The file of a project-level kind, if it exists.
+fn project_entry(project_root: &Path, kind: CacheKind) -> io::Result<Option<Entry>> {
+    let Some(file) = kind.project_file() else {
+        return Ok(None);
+    };
+    let path = project_root.join(file);
+    let metadata = match std::fs::metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    Ok(Some(Entry {
+        name: file.to_string(),
+        bytes: metadata.len(),
+        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        path,
+    }))
+}
+
+fn entries(dir: &Path) -> io::Result<Vec<Entry>> {
+    let read_dir = match std::fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut entries = Vec::new();
+    for entry in read_dir {
+        let entry = entry?;
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            continue;
+        }
+        entries.push(Entry {
+            path: entry.path(),
+            name: entry.file_name().to_string_lossy().into_owned(),
+            bytes: metadata.len(),
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        });
+    }
+    Ok(entries)
+}
+
+/// Whether a log file may still be written to by a running process.
+///
+/// `daemon.log` is always treated as live; bridge logs are named
+/// `{bridge}-{pid}.log` and are live while that process is.
+fn is_live_log(name: &str) -> bool {
+    if name == DAEMON_LOG {
+        return true;
+    }
+    name.strip_suffix(".log")
+        .and_then(|stem| stem.rsplit_once('-'))
+        .and_then(|(_, pid)| pid.parse::<u32>().ok())
+        .is_some_and(super::is_pid_alive)
+}
+
+/// Empty a log file. Live logs are truncated in place so processes holding
+/// them open (in append mode) keep writing to the same file; others are removed.
+fn discard_log(entry: &Entry) -> io::Result<()> {
+    if is_live_log(&entry.name) {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&entry.path)?
+            .set_len(0)
+    } else {
+        std::fs::remove_file(&entry.path)
+    }
+}
+
+/// Measure disk usage of a state directory and the project's cache files.
+pub fn stats(dir: &Path, project_root: &Path) -> io::Result<StateStats> {
+    let mut stats = StateStats {
+        kinds: CacheKind::ALL
+            .iter()
+            .map(|&kind| (kind, Usage::default()))
+            .collect(),
+        runtime: Usage::default(),
+    };
+    for entry in entries(dir)? {
+        match stats
+            .kinds
+            .iter_mut()
+            .find(|(kind, _)| kind.matches(&entry.name))
+        {
+            Some((_, usage)) => usage.add(entry.bytes),
+            None => stats.runtime.add(entry.bytes),
+        }
+    }
+    for (kind, usage) in &mut stats.kinds {
+        if let Some(entry) = project_entry(project_root, *kind)? {
+            usage.add(entry.bytes);
+        }
+    }
+    Ok(stats)
+}
+
+/// Clear files of the given kind (or every kind cleared by default when
+/// `None`). Returns how much was freed.
+pub fn clear(dir: &Path, project_root: &Path, kind: Option<CacheKind>) -> io::Result<Usage> {
+    let kinds: Vec<CacheKind> = match kind {
+        Some(kind) => vec![kind],
+        None => CacheKind::ALL
+            .iter()
+            .copied()
+            .filter(|k| k.cleared_by_default())
+            .collect(),
+    };
+    let mut freed = Usage::default();
+    for entry in entries(dir)? {
+        if kinds.iter().any(|k| k.matches(&entry.name)) {
+            discard_log(&entry)?;
+            freed.add(entry.bytes);
+        }
+    }
+    for kind in kinds {
+        if let Some(entry) = project_entry(project_root, kind)? {
+            std::fs::remove_file(&entry.path)?;
+            freed.add(entry.bytes);
+        }
+    }
+    Ok(freed)
+}
+
+/// Enforce retention limits on a state directory and the project's records.
+/// Returns how much was freed; a record trimmed in place counts as one file.
+///
+/// Logs of exited bridges are removed once older than the age limit. If logs
+/// still exceed the size budget, the oldest logs are discarded first until
+/// they fit. Records over their entry limit keep only their newest entries.
+pub fn prune(dir: &Path, project_root: &Path, limits: &RetentionLimits) -> io::Result<Usage> {
+    let now = SystemTime::now();
+    let mut logs: Vec<Entry> = entries(dir)?
+        .into_iter()
+        .filter(|e| CacheKind::Logs.matches(&e.name))
+        .collect();
+    logs.sort_by_key(|e| e.modified);
+
+    let mut freed = Usage::default();
+    let mut kept = Vec::new();
+    for entry in logs {
+        let age = now.duration_since(entry.modified).unwrap_or_default();
+        if age > limits.max_log_age && !is_live_log(&entry.name) {
+            std::fs::remove_file(&entry.path)?;
+            freed.add(entry.bytes);
+        } else {
+            kept.push(entry);
+        }
+    }
+
+    let mut total: u64 = kept.iter().map(|e| e.bytes).sum();
+    for entry in &kept {
+        if total <= limits.max_log_bytes {
+            break;
+        }
+        discard_log(entry)?;
+        freed.add(entry.bytes);
+        total -= entry.bytes;
+    }
+
+    if let Some(max) = limits.max_history_entries
+        && let Some(bytes) =
+            keep_newest_lines(&project_root.join(crate::history::HISTORY_FILE), max)?
+    {
+        freed.add(bytes);
+    }
+    if let Some(max) = limits.max_audit_entries
+        && let Some(bytes) = super::audit::trim(project_root, max)?
+    {
+        freed.add(bytes);
+    }
+
+    Ok(freed)
+}
+
+/// Drop all but the last `max` lines of an append-only JSONL record. Returns
+/// the bytes removed, or `None` if the file is missing or already fits.
+pub(crate) fn keep_newest_lines(path: &Path, max: usize) -> io::Result<Option<u64>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() <= max {
+        return Ok(None);
+    }
+    let mut kept = lines[lines.len() - max..].join("\n");
+    if !kept.is_empty() {
+        kept.push('\n');
+    }
+    // Write a sibling and rename it over the record, so a crash never leaves
+    // it half-written.
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, &kept)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(Some((content.len() - kept.len()) as u64))
+}
+
+/// Format a byte count for display (e.g. `1.5 MB`).
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, bytes: usize) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, vec![b'x'; bytes]).unwrap();
+        path
+    }
+
+    fn set_age(path: &Path, age: Duration) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    // A PID that cannot belong to a running process.
+    const DEAD_PID: u32 = u32::MAX / 2;
+
+    fn write_proofs(project: &Path, bytes: usize) -> PathBuf {
+        std::fs::create_dir_all(project.join(".tracey")).unwrap();
+        write(project, crate::proofs::PROOFS_FILE, bytes)
+    }
+
+    fn audit_entries(project: &Path, entries: usize) -> PathBuf {
+        use crate::daemon::audit;
+        for i in 0..entries {
+            let path = project.join("spec.md");
+            let entry = audit::entry(project, None, "test", &path, b"", b"", Some(i.to_string()));
+            audit::record(project, &entry).unwrap();
+        }
+        audit::audit_path(project)
+    }
+
+    fn write_history(project: &Path, entries: usize) -> PathBuf {
+        std::fs::create_dir_all(project.join(".tracey")).unwrap();
+        let path = project.join(crate::history::HISTORY_FILE);
+        let lines: String = (0..entries).map(|i| format!("{{\"n\":{i}}}\n")).collect();
+        std::fs::write(&path, lines).unwrap();
+        path
+    }
+
+    // r[verify daemon.cli.cache]
+    #[test]
+    fn stats_separates_kinds_from_runtime_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        write(dir.path(), "daemon.log", 100);
+        write(dir.path(), &format!("mcp-{DEAD_PID}.log"), 50);
+        write(dir.path(), "daemon.pid", 10);
+        write(dir.path(), "project-root", 5);
+        write_proofs(project.path(), 20);
+
+        let stats = stats(dir.path(), project.path()).unwrap();
+        assert_eq!(
+            stats.kinds,
+            vec![
+                (
+                    CacheKind::Logs,
+                    Usage {
+                        files: 2,
+                        bytes: 150
+                    }
+                ),
+                (
+                    CacheKind::Proofs,
+                    Usage {
+                        files: 1,
+                        bytes: 20
+                    }
+                ),
+                (CacheKind::History, Usage::default()),
+                (CacheKind::Audit, Usage::default()),
+            ]
+        );
+        assert_eq!(
+            stats.runtime,
+            Usage {
+                files: 2,
+                bytes: 15
+            }
+        );
+        assert_eq!(stats.total().bytes, 185);
+    }
+
+    // r[verify daemon.state.retention]
+    // r[verify daemon.cli.cache]
+    #[test]
+    fn clear_truncates_live_logs_and_removes_others() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let daemon_log = write(dir.path(), "daemon.log", 100);
+        let bridge_log = write(dir.path(), &format!("lsp-{DEAD_PID}.log"), 50);
+        let pid = write(dir.path(), "daemon.pid", 10);
+        let proofs = write_proofs(project.path(), 20);
+
+        let freed = clear(dir.path(), project.path(), Some(CacheKind::Logs)).unwrap();
+        assert_eq!(freed.bytes, 150);
+        assert_eq!(std::fs::metadata(&daemon_log).unwrap().len(), 0);
+        assert!(!bridge_log.exists());
+        assert!(pid.exists());
+        assert!(proofs.exists(), "only logs were asked for");
+    }
+
+    // r[verify daemon.cli.cache]
+    #[test]
+    fn clear_without_kind_clears_only_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let bridge_log = write(dir.path(), &format!("lsp-{DEAD_PID}.log"), 50);
+        let pid = write(dir.path(), "daemon.pid", 10);
+        let proofs = write_proofs(project.path(), 20);
+        let history = write_history(project.path(), 3);
+        let audit = audit_entries(project.path(), 1);
+
+        let freed = clear(dir.path(), project.path(), None).unwrap();
+        assert_eq!(
+            freed,
+            Usage {
+                files: 1,
+                bytes: 50
+            }
+        );
+        assert!(!bridge_log.exists());
+        assert!(proofs.exists(), "proof results are only cleared by name");
+        assert!(pid.exists());
+        assert!(history.exists(), "history is only cleared by name");
+        assert!(audit.exists(), "the audit log is only cleared by name");
+
+        let freed = clear(dir.path(), project.path(), Some(CacheKind::History)).unwrap();
+        assert_eq!(freed.files, 1);
+        assert!(!history.exists());
+        let freed = clear(dir.path(), project.path(), Some(CacheKind::Proofs)).unwrap();
+        assert_eq!(freed.files, 1);
+        assert!(!proofs.exists());
+    }
+
+    // r[verify daemon.state.retention]
+    #[test]
+    fn prune_removes_old_logs_then_enforces_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = write(dir.path(), &format!("mcp-{DEAD_PID}.log"), 10);
+        set_age(&old, Duration::from_secs(10 * 24 * 60 * 60));
+        let older = write(dir.path(), &format!("lsp-{}.log", DEAD_PID + 1), 600);
+        set_age(&older, Duration::from_secs(60 * 60));
+        let newer = write(dir.path(), "daemon.log", 600);
+
+        let limits = RetentionLimits {
+            max_log_bytes: 1000,
+            max_log_age: Duration::from_secs(7 * 24 * 60 * 60),
+            max_history_entries: None,
+            max_audit_entries: None,
+        };
+        let project = tempfile::tempdir().unwrap();
+        let freed = prune(dir.path(), project.path(), &limits).unwrap();
+
+        assert_eq!(freed.files, 2);
+        assert!(!old.exists());
+        assert!(!older.exists());
+        assert_eq!(std::fs::metadata(&newer).unwrap().len(), 600);
+    }
+
+    // r[verify daemon.state.retention]
+    // r[verify config.retention]
+    #[test]
+    fn prune_keeps_the_newest_history_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let history = write_history(project.path(), 5);
+
+        let unlimited = RetentionLimits::default();
+        assert_eq!(
+            prune(dir.path(), project.path(), &unlimited).unwrap(),
+            Usage::default()
+        );
+
+        let limits = RetentionLimits {
+            max_history_entries: Some(2),
+            ..unlimited
+        };
+        let freed = prune(dir.path(), project.path(), &limits).unwrap();
+        assert_eq!(freed.files, 1);
+        assert_eq!(
+            std::fs::read_to_string(&history).unwrap(),
+            "{\"n\":3}\n{\"n\":4}\n"
+        );
+        assert_eq!(
+            prune(dir.path(), project.path(), &limits).unwrap(),
+            Usage::default()
+        );
+    }
+
+    // r[verify daemon.state.retention]
+    // r[verify daemon.cli.cache]
+    #[test]
+    fn audit_log_is_trimmed_and_cleared_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let audit = audit_entries(project.path(), 4);
+
+        let limits = RetentionLimits {
+            max_audit_entries: Some(1),
+            ..RetentionLimits::default()
+        };
+        assert_eq!(prune(dir.path(), project.path(), &limits).unwrap().files, 1);
+        let kept = crate::daemon::audit::load(project.path(), 10).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].detail.as_deref(), Some("3"));
+
+        let stats = stats(dir.path(), project.path()).unwrap();
+        assert!(stats.kinds.contains(&(
+            CacheKind::Audit,
+            Usage {
+                files: 1,
+                bytes: std::fs::metadata(&audit).unwrap().len()
+            }
+        )));
+        clear(dir.path(), project.path(), Some(CacheKind::Audit)).unwrap();
+        assert!(!audit.exists());
+    }
+
+    // r[verify daemon.cli.cache]
+    #[test]
+    fn parse_kind() {
+        assert_eq!("logs".parse::<CacheKind>(), Ok(CacheKind::Logs));
+        assert_eq!("proofs".parse::<CacheKind>(), Ok(CacheKind::Proofs));
+        assert_eq!("history".parse::<CacheKind>(), Ok(CacheKind::History));
+        assert_eq!("audit".parse::<CacheKind>(), Ok(CacheKind::Audit));
+        assert!("index".parse::<CacheKind>().is_err());
+        let err = "sessions".parse::<CacheKind>().unwrap_err();
+        assert!(
+            err.contains("available: logs, proofs, history, audit"),
+            "{err}"
+        );
+    }
+
+    // r[verify config.retention]
+    #[test]
+    fn limits_default_and_override() {
+        let defaults = RetentionLimits::default();
+        assert_eq!(defaults.max_log_bytes, DEFAULT_MAX_LOG_MB * 1024 * 1024);
+        assert_eq!(defaults.max_history_entries, None);
+
+        let limits = RetentionLimits::from_config(Some(&RetentionConfig {
+            max_log_mb: Some(1),
+            max_history_entries: Some(100),
+            ..Default::default()
+        }));
+        assert_eq!(limits.max_log_bytes, 1024 * 1024);
+        assert_eq!(limits.max_history_entries, Some(100));
+        assert_eq!(limits.max_audit_entries, None);
+        assert_eq!(
+            limits.max_log_age,
+            Duration::from_secs(DEFAULT_MAX_LOG_AGE_DAYS * 24 * 60 * 60)
+        );
+    }
+}
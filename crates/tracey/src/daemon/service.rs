@@ -3,13 +3,16 @@
 //! Implements the roam RPC service by delegating to the Engine.
 
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Instant;
-use tracey_core::{RuleId, RuleIdMatch, classify_reference_for_rule, parse_rule_id};
+use tracey_core::{RefVerb, RuleId, RuleIdMatch, classify_reference_for_rule, parse_rule_id};
 use tracey_proto::*;
 
 use super::engine::Engine;
+use super::highlight::HighlightPool;
+use super::limits::Limiter;
 use super::watcher::WatcherState;
+use crate::data::{DashboardData, RebuildScope};
 use crate::rule_suggestions::suggest_similar_rule_ids;
 use crate::server::QueryEngine;
 use roam::Tx;
@@ -22,17 +25,53 @@ struct HistoricalRuleText {
     text: String,
 }
 
+/// Build the update message for a data version.
+///
+/// Flattens all impl deltas into a single summary; shared by `subscribe`
+/// streams and the outbound webhook.
+pub(crate) fn data_update(data: &DashboardData) -> DataUpdate {
+    let delta = if data.delta.is_empty() {
+        None
+    } else {
+        let mut newly_covered = Vec::new();
+        let mut newly_uncovered = Vec::new();
+
+        for impl_delta in data.delta.by_impl.values() {
+            for change in &impl_delta.newly_covered {
+                newly_covered.push(CoverageChange {
+                    rule_id: change.rule_id.clone(),
+                    file: change.file.clone(),
+                    line: change.line,
+                });
+            }
+            newly_uncovered.extend(impl_delta.newly_uncovered.iter().cloned());
+        }
+
+        Some(DeltaSummary {
+            newly_covered,
+            newly_uncovered,
+        })
+    };
+
+    DataUpdate {
+        version: data.version,
+        delta,
+    }
+}
+
 /// Inner service state shared via Arc.
 struct TraceyServiceInner {
     engine: Arc<Engine>,
-    /// Syntax highlighter for source files
-    highlighter: Mutex<arborium::Highlighter>,
+    /// Syntax highlighters and cached output for source files
+    highlighter: HighlightPool,
     /// Watcher state for health monitoring
     watcher_state: Option<Arc<WatcherState>>,
     /// Start time for uptime calculation
     start_time: Instant,
     /// Shutdown signal sender
     shutdown_tx: tokio::sync::watch::Sender<bool>,
+    /// Connection and request limits, reported through health
+    limiter: Arc<Limiter>,
 }
 
 /// Service implementation wrapping the Engine.
@@ -50,10 +89,11 @@ impl TraceyService {
         Self {
             inner: Arc::new(TraceyServiceInner {
                 engine,
-                highlighter: Mutex::new(arborium::Highlighter::new()),
+                highlighter: HighlightPool::new(),
                 watcher_state: None,
                 start_time: Instant::now(),
                 shutdown_tx,
+                limiter: Arc::new(Limiter::default()),
             }),
         }
     }
@@ -68,15 +108,21 @@ impl TraceyService {
         let service = Self {
             inner: Arc::new(TraceyServiceInner {
                 engine,
-                highlighter: Mutex::new(arborium::Highlighter::new()),
+                highlighter: HighlightPool::new(),
                 watcher_state: Some(watcher_state),
                 start_time: Instant::now(),
                 shutdown_tx,
+                limiter: Arc::new(Limiter::default()),
             }),
         };
         (service, shutdown_rx)
     }
 
+    /// Limits shared by every connection to this service.
+    pub fn limiter(&self) -> Arc<Limiter> {
+        Arc::clone(&self.inner.limiter)
+    }
+
     /// Set the watcher state (for lazy initialization).
     ///
     /// Note: This requires exclusive access to the inner state. If the Arc
@@ -115,6 +161,35 @@ impl TraceyService {
 
         (spec_name, impl_name)
     }
+
+    /// Append a change to the audit log. The change itself has already been
+    /// made, so a log that can't be written is reported but not fatal.
+    fn audit(
+        &self,
+        client: Option<&str>,
+        action: &str,
+        path: &Path,
+        before: &[u8],
+        after: &[u8],
+        detail: Option<String>,
+    ) {
+        let project_root = self.inner.engine.project_root();
+        let entry = super::audit::entry(project_root, client, action, path, before, after, detail);
+        if let Err(e) = super::audit::record(project_root, &entry) {
+            tracing::warn!("Failed to write audit log entry for {action}: {e}");
+        }
+    }
+}
+
+/// Waived rules as rule references whose text is the waiver's reason.
+fn waived_refs(waived: Vec<crate::server::WaivedRule>) -> Vec<tracey_proto::RuleRef> {
+    waived
+        .into_iter()
+        .map(|w| tracey_proto::RuleRef {
+            text: Some(w.describe()),
+            id: w.id,
+        })
+        .collect()
 }
 
 /// Escape HTML special characters.
@@ -225,17 +300,38 @@ impl TraceyDaemon for TraceyService {
         let data = self.inner.engine.data().await;
         let query = QueryEngine::new(&data);
         let stats = query.status();
+        let advisory = |spec: &str| {
+            data.config
+                .specs
+                .iter()
+                .any(|s| s.name == spec && s.advisory)
+        };
+
+        let has_docs = data.config.verbs.iter().any(|verb| verb.role == "docs");
+        let config = self.inner.engine.config().await;
+        let has_proofs = |spec: &str, impl_name: &str| {
+            config.specs.iter().any(|s| {
+                s.name == spec
+                    && s.impls
+                        .iter()
+                        .any(|i| i.name == impl_name && !i.proof_include.is_empty())
+            })
+        };
 
         StatusResponse {
             impls: stats
                 .into_iter()
                 .map(|(spec, impl_name, s)| ImplStatus {
+                    advisory: advisory(&spec),
+                    proven_rules: has_proofs(&spec, &impl_name).then_some(s.proven_covered),
                     spec,
                     impl_name,
                     total_rules: s.total_rules,
                     covered_rules: s.impl_covered,
+                    partial_rules: s.partial_covered,
                     stale_rules: s.stale_covered,
                     verified_rules: s.verify_covered,
+                    documented_rules: has_docs.then_some(s.docs_covered),
                 })
                 .collect(),
         }
@@ -265,11 +361,12 @@ impl TraceyDaemon for TraceyService {
                             .into_iter()
                             .map(|r| tracey_proto::RuleRef {
                                 id: r.id,
-                                text: None, // RuleRef in server.rs doesn't have text
+                                text: r.note,
                             })
                             .collect(),
                     })
                     .collect(),
+                waived: waived_refs(result.waived),
             }
         } else {
             UncoveredResponse {
@@ -278,6 +375,7 @@ impl TraceyDaemon for TraceyService {
                 total_rules: 0,
                 uncovered_count: 0,
                 by_section: vec![],
+                waived: vec![],
             }
         }
     }
@@ -305,11 +403,12 @@ impl TraceyDaemon for TraceyService {
                             .into_iter()
                             .map(|r| tracey_proto::RuleRef {
                                 id: r.id,
-                                text: None,
+                                text: r.note,
                             })
                             .collect(),
                     })
                     .collect(),
+                waived: waived_refs(result.waived),
             }
         } else {
             UntestedResponse {
@@ -318,6 +417,7 @@ impl TraceyDaemon for TraceyService {
                 total_rules: 0,
                 untested_count: 0,
                 by_section: vec![],
+                waived: vec![],
             }
         }
     }
@@ -450,6 +550,8 @@ impl TraceyDaemon for TraceyService {
             html: info.html,
             source_file: info.source_file,
             source_line: info.source_line,
+            section_path: info.section_path,
+            owner: info.owner,
             coverage: info
                 .coverage
                 .into_iter()
@@ -461,6 +563,170 @@ impl TraceyDaemon for TraceyService {
                 })
                 .collect(),
             version_diff,
+            related: info.related,
+        })
+    }
+
+    /// Format an annotation comment for a rule
+    async fn annotation_snippet(
+        &self,
+        req: AnnotationSnippetRequest,
+    ) -> Result<AnnotationSnippet, String> {
+        let verb = match req.verb.as_deref() {
+            None => RefVerb::Impl,
+            Some(raw) => RefVerb::parse(raw).ok_or_else(|| {
+                format!("unknown verb '{raw}' (expected impl, verify, depends, or related)")
+            })?,
+        };
+
+        let data = self.inner.engine.data().await;
+        let (spec, rule) = find_rule_in_data(&data, &req.rule_id)
+            .ok_or_else(|| format!("rule not found: {}", req.rule_id))?;
+        let prefix = data
+            .config
+            .specs
+            .iter()
+            .find(|s| &s.name == spec)
+            .map(|s| s.prefix.clone())
+            .ok_or_else(|| format!("spec not found: {spec}"))?;
+
+        let config = self.inner.engine.config().await;
+        let snippet =
+            crate::config::annotation_snippet(&config, &prefix, verb, &rule.id, &req.language)
+                .ok_or_else(|| format!("unsupported language: {}", req.language))?;
+
+        Ok(AnnotationSnippet {
+            rule_id: rule.id.clone(),
+            spec: spec.clone(),
+            snippet,
+        })
+    }
+
+    /// Compare rule coverage of several implementations
+    async fn compare(&self, req: CompareRequest) -> Result<CompareResponse, String> {
+        if req.impls.len() < 2 {
+            return Err(format!(
+                "compare needs at least two implementations, got {}",
+                req.impls.len()
+            ));
+        }
+        let data = self.inner.engine.data().await;
+        let query = QueryEngine::new(&data);
+
+        let (spec, _) = self.resolve_spec_impl(req.spec.as_deref(), None, &data.config);
+        let known_impls = data
+            .config
+            .specs
+            .iter()
+            .find(|s| s.name == spec)
+            .map(|s| s.implementations.clone())
+            .ok_or_else(|| format!("spec not found: {spec}"))?;
+        for impl_name in &req.impls {
+            if !known_impls.contains(impl_name) {
+                return Err(format!(
+                    "unknown implementation '{impl_name}' for spec '{spec}' (available: {})",
+                    known_impls.join(", ")
+                ));
+            }
+        }
+
+        let result = query
+            .compare(&spec, &req.impls)
+            .ok_or_else(|| format!("no coverage data for spec '{spec}'"))?;
+        let coverage = |side: crate::server::CompareSide| CompareCoverage {
+            impl_count: side.impl_count,
+            verify_count: side.verify_count,
+            is_stale: side.is_stale,
+        };
+
+        Ok(CompareResponse {
+            gaps: result
+                .impls
+                .iter()
+                .enumerate()
+                .map(|(index, impl_name)| CompareGap {
+                    impl_name: impl_name.clone(),
+                    missing: result.missing(index).cloned().collect(),
+                })
+                .collect(),
+            rules: result
+                .rows
+                .iter()
+                .map(|row| CompareRule {
+                    id: row.id.clone(),
+                    coverage: row.coverage.iter().copied().map(coverage).collect(),
+                })
+                .collect(),
+            spec: result.spec,
+            impls: result.impls,
+        })
+    }
+
+    /// List every annotation under a path
+    async fn refs_in(&self, req: RefsInRequest) -> RefsInResponse {
+        let data = self.inner.engine.data().await;
+        let result = QueryEngine::new(&data).refs_in(&req.path);
+        RefsInResponse {
+            total: result.total(),
+            files: result.files,
+            rules: result
+                .rules
+                .into_iter()
+                .map(|rule| RefsInRule {
+                    prefix: rule.prefix,
+                    id: rule.id,
+                    verbs: rule
+                        .verbs
+                        .into_iter()
+                        .map(|(verb, locations)| RefsInVerb {
+                            verb: verb.as_str().to_string(),
+                            locations,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            path: result.path,
+        }
+    }
+
+    /// Roll up forward coverage by directory or crate
+    async fn breakdown(&self, req: BreakdownRequest) -> Result<BreakdownResponse, String> {
+        let data = self.inner.engine.data().await;
+        let query = QueryEngine::new(&data);
+
+        let (spec, impl_name) =
+            self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
+        let by = match req.by.as_deref().unwrap_or("dir") {
+            "dir" => crate::server::BreakdownBy::Dir { depth: req.depth },
+            "crate" => crate::server::BreakdownBy::Crate,
+            other => {
+                return Err(format!(
+                    "unknown grouping '{other}' (expected dir or crate)"
+                ));
+            }
+        };
+
+        let groups = query
+            .breakdown(&spec, &impl_name, by)
+            .ok_or_else(|| format!("no coverage data for {spec}/{impl_name}"))?;
+
+        Ok(BreakdownResponse {
+            by: req.by.unwrap_or_else(|| "dir".to_string()),
+            groups: groups
+                .into_iter()
+                .map(|group| BreakdownGroup {
+                    path: group.path,
+                    files: group.files,
+                    total_units: group.total_units,
+                    covered_units: group.covered_units,
+                    impl_count: group.impl_count,
+                    verify_count: group.verify_count,
+                    implemented: group.implemented,
+                    verified: group.verified,
+                })
+                .collect(),
+            spec,
+            impl_name,
         })
     }
 
@@ -494,6 +760,22 @@ impl TraceyDaemon for TraceyService {
             .await;
     }
 
+    /// VFS: batch sync of open files
+    async fn vfs_sync(&self, req: VfsSyncRequest) -> VfsSyncResponse {
+        let entries = req
+            .entries
+            .into_iter()
+            .map(|e| (std::path::PathBuf::from(e.path), e.hash))
+            .collect();
+        let needed = self.inner.engine.vfs_sync(entries).await;
+        VfsSyncResponse {
+            needed: needed
+                .into_iter()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect(),
+        }
+    }
+
     /// Force a rebuild
     async fn reload(&self) -> ReloadResponse {
         match self.inner.engine.rebuild().await {
@@ -511,6 +793,60 @@ impl TraceyDaemon for TraceyService {
         }
     }
 
+    /// Rebuild only the implementations in scope
+    ///
+    /// r[impl daemon.reload.scope]
+    async fn reload_scope(&self, req: ReloadScopeRequest) -> Result<ReloadScopeResponse, String> {
+        let data = self.inner.engine.data().await;
+        if let Some(spec) = &req.spec
+            && !data.config.specs.iter().any(|s| &s.name == spec)
+        {
+            let available: Vec<_> = data.config.specs.iter().map(|s| s.name.as_str()).collect();
+            return Err(format!(
+                "unknown spec '{spec}' (available: {})",
+                available.join(", ")
+            ));
+        }
+        if let Some(impl_name) = &req.impl_name {
+            let known: Vec<&str> = data
+                .config
+                .specs
+                .iter()
+                .filter(|s| req.spec.as_ref().is_none_or(|spec| &s.name == spec))
+                .flat_map(|s| s.implementations.iter().map(String::as_str))
+                .collect();
+            if !known.contains(&impl_name.as_str()) {
+                return Err(format!(
+                    "unknown implementation '{impl_name}' (available: {})",
+                    known.join(", ")
+                ));
+            }
+        }
+        drop(data);
+
+        let project_root = self.inner.engine.project_root();
+        let paths: Vec<PathBuf> = req.paths.iter().map(|p| project_root.join(p)).collect();
+        let scope = RebuildScope {
+            spec: req.spec,
+            impl_name: req.impl_name,
+        };
+        let outcome = self
+            .inner
+            .engine
+            .rebuild_scope(scope, &paths)
+            .await
+            .map_err(|e| format!("reload failed: {e}"))?;
+        Ok(ReloadScopeResponse {
+            version: outcome.version,
+            rebuild_time_ms: outcome.elapsed.as_millis() as u64,
+            changed: outcome
+                .changed
+                .into_iter()
+                .map(|(spec, impl_name)| ReloadedImpl { spec, impl_name })
+                .collect(),
+        })
+    }
+
     /// Get current version
     async fn version(&self) -> u64 {
         self.inner.engine.version()
@@ -557,6 +893,8 @@ impl TraceyDaemon for TraceyService {
             watcher_event_count,
             watched_directories,
             uptime_secs,
+            // r[impl daemon.limits.health]
+            load: self.inner.limiter.load(),
         }
     }
 
@@ -580,39 +918,7 @@ impl TraceyDaemon for TraceyService {
             }
 
             // Build the update message (clone to avoid holding the guard across await)
-            let update = {
-                let data = rx.borrow_and_update();
-
-                // Convert server::Delta to proto::DeltaSummary
-                // Flatten all impl deltas into a single summary
-                let delta = if data.delta.is_empty() {
-                    None
-                } else {
-                    let mut newly_covered = Vec::new();
-                    let mut newly_uncovered = Vec::new();
-
-                    for impl_delta in data.delta.by_impl.values() {
-                        for change in &impl_delta.newly_covered {
-                            newly_covered.push(CoverageChange {
-                                rule_id: change.rule_id.clone(),
-                                file: change.file.clone(),
-                                line: change.line,
-                            });
-                        }
-                        newly_uncovered.extend(impl_delta.newly_uncovered.iter().cloned());
-                    }
-
-                    Some(DeltaSummary {
-                        newly_covered,
-                        newly_uncovered,
-                    })
-                };
-
-                DataUpdate {
-                    version: data.version,
-                    delta,
-                }
-            }; // Guard dropped here before the await
+            let update = data_update(&rx.borrow_and_update());
 
             // Send the update - if this fails, the client disconnected
             if updates.send(update).await.is_err() {
@@ -671,17 +977,16 @@ impl TraceyDaemon for TraceyService {
 
         // Syntax highlight the content
         let html = if let Some(lang) = arborium_language(&relative) {
-            let mut hl = self.inner.highlighter.lock().unwrap();
-            match hl.highlight(lang, &content) {
-                Ok(highlighted) => highlighted,
-                Err(_) => html_escape(&content),
+            match self.inner.highlighter.highlight(lang, &content).await {
+                Some(highlighted) => highlighted.to_string(),
+                None => html_escape(&content),
             }
         } else {
             html_escape(&content)
         };
 
         // Convert code units to API format
-        let api_units: Vec<ApiCodeUnit> = units
+        let mut api_units: Vec<ApiCodeUnit> = units
             .iter()
             .map(|u| ApiCodeUnit {
                 kind: format!("{:?}", u.kind).to_lowercase(),
@@ -691,6 +996,20 @@ impl TraceyDaemon for TraceyService {
                 rule_refs: u.req_refs.iter().map(|r| r.to_string()).collect(),
             })
             .collect();
+        // r[impl ref.syntax.region]
+        // Regions are shown as units of their own, so the file view
+        // highlights the whole block a `begin` marker claims.
+        if let Some(reqs) = data.source_reqs_by_file.get(&full_path) {
+            api_units.extend(reqs.references.iter().filter_map(|r| {
+                Some(ApiCodeUnit {
+                    kind: "region".to_string(),
+                    name: None,
+                    start_line: r.line,
+                    end_line: r.end_line?,
+                    rule_refs: vec![r.req_id.to_string()],
+                })
+            }));
+        }
 
         Some(ApiFileData {
             path: relative,
@@ -721,6 +1040,7 @@ impl TraceyDaemon for TraceyService {
             &spec,
             &impl_name,
             forward,
+            data.spec_sandboxes.get(&spec),
         )
         .await
         .ok()
@@ -762,6 +1082,7 @@ impl TraceyDaemon for TraceyService {
                 content: Some(r.content),
                 highlighted: Some(highlighted),
                 score: r.score,
+                section: r.section,
             });
         }
 
@@ -801,24 +1122,15 @@ impl TraceyDaemon for TraceyService {
             });
         }
 
-        // Validate range
-        if req.start > req.end || req.end > content.len() {
-            return Err(UpdateError {
-                message: format!(
-                    "Invalid range: {}..{} (file length: {})",
-                    req.start,
-                    req.end,
-                    content.len()
-                ),
-            });
-        }
-
         // Replace the range
-        let mut new_content =
-            String::with_capacity(content.len() - (req.end - req.start) + req.content.len());
-        new_content.push_str(&content[..req.start]);
-        new_content.push_str(&req.content);
-        new_content.push_str(&content[req.end..]);
+        let range = req.start..req.end;
+        let edit = tracey_core::markdown::edit::TextEdit::new(range.clone(), req.content);
+        let new_content =
+            tracey_core::markdown::edit::apply_edits(&content, vec![edit]).map_err(|e| {
+                UpdateError {
+                    message: e.to_string(),
+                }
+            })?;
 
         // Write back
         if let Err(e) = std::fs::write(&full_path, &new_content) {
@@ -827,9 +1139,24 @@ impl TraceyDaemon for TraceyService {
             });
         }
 
+        self.audit(
+            req.client.as_deref(),
+            "update_file_range",
+            &full_path,
+            content.as_bytes(),
+            new_content.as_bytes(),
+            Some(format!("bytes {}..{}", range.start, range.end)),
+        );
+
         Ok(())
     }
 
+    /// Most recent audit log entries, newest first
+    async fn audit(&self, limit: u32) -> Result<Vec<AuditEntry>, String> {
+        super::audit::load(self.inner.engine.project_root(), limit as usize)
+            .map_err(|e| format!("Failed to read audit log: {e}"))
+    }
+
     /// Check if a path is a test file
     async fn is_test_file(&self, path: String) -> bool {
         let data = self.inner.engine.data().await;
@@ -886,6 +1213,7 @@ impl TraceyDaemon for TraceyService {
             .map(|r| HoverRef {
                 file: r.file.clone(),
                 line: r.line,
+                note: r.note.clone(),
             })
             .collect();
         let verify_refs: Vec<HoverRef> = rule
@@ -894,6 +1222,7 @@ impl TraceyDaemon for TraceyService {
             .map(|r| HoverRef {
                 file: r.file.clone(),
                 line: r.line,
+                note: r.note.clone(),
             })
             .collect();
 
@@ -955,6 +1284,7 @@ impl TraceyDaemon for TraceyService {
             range_end_line: end_line,
             range_end_char: end_char,
             version_diff,
+            examples: rule.examples.clone(),
         })
     }
 
@@ -1272,39 +1602,50 @@ impl TraceyDaemon for TraceyService {
     ///
     /// r[impl lsp.semantic-tokens.prefix]
     /// r[impl lsp.semantic-tokens.verb]
+    /// r[impl lsp.semantic-tokens.rule-state]
     async fn lsp_semantic_tokens(&self, req: LspDocumentRequest) -> Vec<LspSemanticToken> {
+        use tracey_proto::semantic_modifiers::*;
+
         let path = PathBuf::from(&req.path);
         let data = self.inner.engine.data().await;
 
-        // Build set of known rule IDs
-        let known_rules: std::collections::HashSet<_> = data
-            .forward_by_impl
-            .values()
-            .flat_map(|f| f.rules.iter().map(|r| r.id.clone()))
-            .collect();
-
         let mut tokens = Vec::new();
 
         // For markdown spec files, tokenize requirement definitions
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) = marq::render(&crate::examples::strip(&req.content), &options).await {
                 for def in &doc.reqs {
                     // Use marker_span for semantic tokens (only color the marker)
                     let (start_line, start_char, _, _) =
                         span_to_range(&req.content, def.marker_span.offset, def.marker_span.length);
 
-                    // Definitions are always the DEFINITION modifier
+                    let deprecated = def
+                        .metadata
+                        .status
+                        .is_some_and(|s| is_retired_status(s.as_str()));
                     tokens.push(LspSemanticToken {
                         line: start_line,
                         start_char,
                         length: def.marker_span.length as u32,
                         token_type: 2, // variable (req_id)
-                        modifiers: 1,  // DEFINITION modifier
+                        modifiers: DEFINITION | if deprecated { DEPRECATED } else { 0 },
                     });
                 }
             }
         } else if let Some(reqs) = lookup_source_reqs(&data, &path) {
+            // Rules of each configured spec, by prefix
+            let mut rules_by_prefix: std::collections::HashMap<&str, Vec<&ApiRule>> =
+                std::collections::HashMap::new();
+            for spec in &data.config.specs {
+                let rules = rules_by_prefix.entry(spec.prefix.as_str()).or_default();
+                for ((spec_name, _), forward) in &data.forward_by_impl {
+                    if spec_name == &spec.name {
+                        rules.extend(&forward.rules);
+                    }
+                }
+            }
+
             // For source files, tokenize references from build data
             for reference in &reqs.references {
                 let (start_line, start_char, _, _) =
@@ -1312,21 +1653,38 @@ impl TraceyDaemon for TraceyService {
 
                 // Token for the entire reference
                 // Token type 0 = namespace (prefix), 1 = keyword (verb), 2 = variable (req_id)
-                let is_valid = known_rules.contains(&reference.req_id);
-                let modifier = if reference.verb == tracey_core::RefVerb::Define {
-                    1 // DEFINITION modifier
-                } else if is_valid {
-                    2 // DECLARATION modifier
-                } else {
-                    0
+                // References with a prefix no spec uses get no state modifier.
+                let mut modifiers = match rules_by_prefix.get(reference.prefix.as_str()) {
+                    None => 0,
+                    Some(rules) => {
+                        let matched = |kind| {
+                            rules.iter().find(|r| {
+                                classify_reference_for_rule(&r.id, &reference.req_id) == kind
+                            })
+                        };
+                        let (state, rule) = match matched(RuleIdMatch::Exact) {
+                            Some(rule) => (DECLARATION, Some(rule)),
+                            None => match matched(RuleIdMatch::Stale) {
+                                Some(rule) => (STALE, Some(rule)),
+                                None => (UNKNOWN, None),
+                            },
+                        };
+                        let deprecated = rule
+                            .and_then(|r| r.status.as_deref())
+                            .is_some_and(is_retired_status);
+                        state | if deprecated { DEPRECATED } else { 0 }
+                    }
                 };
+                if reference.verb == tracey_core::RefVerb::Define {
+                    modifiers = modifiers & !DECLARATION | DEFINITION;
+                }
 
                 tokens.push(LspSemanticToken {
                     line: start_line,
                     start_char,
                     length: reference.span.length as u32,
                     token_type: 2, // variable (req_id)
-                    modifiers: modifier,
+                    modifiers,
                 });
             }
         }
@@ -1348,7 +1706,7 @@ impl TraceyDaemon for TraceyService {
         // For markdown spec files, show code lenses for requirement definitions
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) = marq::render(&crate::examples::strip(&req.content), &options).await {
                 for def in &doc.reqs {
                     // Use marker_span for code lens positioning
                     let (start_line, start_char, _, end_char) =
@@ -1419,6 +1777,60 @@ impl TraceyDaemon for TraceyService {
         lenses
     }
 
+    /// Get folding ranges
+    ///
+    /// In spec files each rule's body folds under its marker; in source files
+    /// each code unit carrying requirement references folds under its first
+    /// line. Ranges are computed from the sent content, so they follow unsaved
+    /// edits.
+    ///
+    /// r[impl lsp.folding.rules]
+    /// r[impl lsp.folding.annotated-units]
+    async fn lsp_folding_ranges(&self, req: LspDocumentRequest) -> Vec<LspFoldingRange> {
+        let path = PathBuf::from(&req.path);
+        let mut ranges = Vec::new();
+
+        if path.extension().is_some_and(|ext| ext == "md") {
+            let options = marq::RenderOptions::default();
+            if let Ok(doc) = marq::render(&crate::examples::strip(&req.content), &options).await {
+                for def in &doc.reqs {
+                    let (start_line, _, _, _) =
+                        span_to_range(&req.content, def.span.offset, def.span.length);
+                    // Trailing blank lines belong to the gap before the next block.
+                    let body = req
+                        .content
+                        .get(def.span.offset..def.span.offset + def.span.length)
+                        .unwrap_or_default()
+                        .trim_end();
+                    let end_line = start_line + body.matches('\n').count() as u32;
+                    if end_line > start_line {
+                        ranges.push(LspFoldingRange {
+                            start_line,
+                            end_line,
+                            // The marker line stays visible and already names the rule.
+                            collapsed_text: None,
+                        });
+                    }
+                }
+            }
+        } else {
+            let units = tracey_core::code_units::extract(&path, &req.content);
+            for unit in &units.units {
+                if unit.req_refs.is_empty() || unit.end_line <= unit.start_line {
+                    continue;
+                }
+                let ids: Vec<String> = unit.req_refs.iter().map(|id| id.to_string()).collect();
+                ranges.push(LspFoldingRange {
+                    start_line: unit.start_line.saturating_sub(1) as u32,
+                    end_line: unit.end_line.saturating_sub(1) as u32,
+                    collapsed_text: Some(ids.join(", ")),
+                });
+            }
+        }
+
+        ranges
+    }
+
     /// Get inlay hints for a range
     ///
     /// r[impl lsp.inlay.coverage-status]
@@ -1432,7 +1844,7 @@ impl TraceyDaemon for TraceyService {
         // For markdown spec files, show hints for requirement definitions
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) = marq::render(&crate::examples::strip(&req.content), &options).await {
                 for def in &doc.reqs {
                     // Use marker_span for inlay hint positioning (after the marker)
                     let (line, _, _, end_char) =
@@ -1680,7 +2092,7 @@ impl TraceyDaemon for TraceyService {
         // For markdown files, highlight all definitions of the same rule (typically just one)
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) = marq::render(&crate::examples::strip(&req.content), &options).await {
                 return doc
                     .reqs
                     .iter()
@@ -1760,9 +2172,19 @@ impl TraceyDaemon for TraceyService {
         }
 
         // Save config
+        let before = std::fs::read(&config_path).unwrap_or_default();
         if let Err(e) = save_config(&config_path, &config) {
             return Err(format!("Error saving config: {}", e));
         }
+        let after = std::fs::read(&config_path).unwrap_or_default();
+        self.audit(
+            req.client.as_deref(),
+            "config_add_exclude",
+            &config_path,
+            &before,
+            &after,
+            Some(format!("{spec_name}/{impl_name}: exclude {}", req.pattern)),
+        );
 
         Ok(())
     }
@@ -1803,9 +2225,19 @@ impl TraceyDaemon for TraceyService {
         }
 
         // Save config
+        let before = std::fs::read(&config_path).unwrap_or_default();
         if let Err(e) = save_config(&config_path, &config) {
             return Err(format!("Error saving config: {}", e));
         }
+        let after = std::fs::read(&config_path).unwrap_or_default();
+        self.audit(
+            req.client.as_deref(),
+            "config_add_include",
+            &config_path,
+            &before,
+            &after,
+            Some(format!("{spec_name}/{impl_name}: include {}", req.pattern)),
+        );
 
         Ok(())
     }
@@ -1829,6 +2261,11 @@ struct RuleAtPosition {
 
 /// Look up build-data reqs for a source file path.
 /// Returns `None` if the file was not part of the build scan.
+/// Whether a rule status marks the rule as on its way out.
+fn is_retired_status(status: &str) -> bool {
+    matches!(status, "deprecated" | "removed")
+}
+
 fn lookup_source_reqs<'a>(
     data: &'a crate::data::DashboardData,
     path: &Path,
@@ -1855,7 +2292,9 @@ async fn find_rule_at_position(
 
         // Parse markdown to find requirement definitions first.
         let options = marq::RenderOptions::default();
-        let doc = marq::render(content, &options).await.ok()?;
+        let doc = marq::render(&crate::examples::strip(content), &options)
+            .await
+            .ok()?;
         if let Some(rule) = doc.reqs.iter().find_map(|r| {
             let start = r.span.offset;
             let end = r.span.offset + r.span.length;
@@ -2017,9 +2456,29 @@ fn run_git_capture(project_root: &Path, args: &[&str]) -> Option<String> {
     String::from_utf8(output.stdout).ok()
 }
 
+/// Pair each commit of `git log --name-only --format='commit %H'` output
+/// with the path the file had in it.
+fn commit_paths(log: &str) -> Vec<(&str, &str)> {
+    let mut pairs = Vec::new();
+    let mut commit = None;
+    for line in log.lines().filter(|line| !line.is_empty()) {
+        match line.strip_prefix("commit ") {
+            Some(hash) => commit = Some(hash),
+            None => {
+                if let Some(hash) = commit.take() {
+                    pairs.push((hash, line));
+                }
+            }
+        }
+    }
+    pairs
+}
+
 async fn find_rule_text_in_markdown(content: &str, rule_id: &RuleId) -> Option<String> {
     let options = marq::RenderOptions::default();
-    let doc = marq::render(content, &options).await.ok()?;
+    let doc = marq::render(&crate::examples::strip(content), &options)
+        .await
+        .ok()?;
     let rule_id = rule_id.to_string();
     doc.reqs
         .iter()
@@ -2033,10 +2492,23 @@ async fn load_previous_rule_text_from_git(
     previous_rule_id: &RuleId,
 ) -> Option<HistoricalRuleText> {
     // r[impl validation.stale.diff]
-    let commits = run_git_capture(project_root, &["log", "--format=%H", "--", source_file])?;
-
-    for commit in commits.lines() {
-        let show_arg = format!("{commit}:{source_file}");
+    // r[impl history.renames]
+    // `--follow` keeps walking past a move; `--name-only` tells us the
+    // file's path in each commit, which is what `git show` needs.
+    let log = run_git_capture(
+        project_root,
+        &[
+            "log",
+            "--follow",
+            "--name-only",
+            "--format=commit %H",
+            "--",
+            source_file,
+        ],
+    )?;
+
+    for (commit, path) in commit_paths(&log) {
+        let show_arg = format!("{commit}:{path}");
         let content = run_git_capture(project_root, &["show", &show_arg]);
         let Some(content) = content else {
             continue;
@@ -0,0 +1,266 @@
+//! Outbound coverage webhook.
+//!
+//! r[impl daemon.webhook.post]
+//!
+//! When the config has a `webhook` block, the daemon POSTs the same
+//! `DataUpdate` that `subscribe` streams (version plus `DeltaSummary`) to the
+//! configured URL after every rebuild. Like `check-links`, delivery shells
+//! out to `curl` so no TLS stack has to be linked into tracey.
+
+use eyre::{Result, WrapErr};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracey_proto::DataUpdate;
+use tracing::{debug, warn};
+
+use super::engine::Engine;
+use super::service::data_update;
+use crate::config::WebhookConfig;
+
+/// Header carrying the hex HMAC-SHA256 of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Tracey-Signature-256";
+
+/// Per-delivery timeout passed to curl, in seconds.
+const TIMEOUT_SECS: &str = "10";
+
+/// Deliver every data update to the configured webhook until the engine
+/// shuts down. The config is re-read on every update so adding, changing or
+/// removing the webhook takes effect without a restart.
+pub async fn run(engine: Arc<Engine>, config_path: PathBuf) {
+    let mut rx = engine.subscribe();
+    while rx.changed().await.is_ok() {
+        let update = data_update(&rx.borrow_and_update());
+        let Some(webhook) = crate::load_config(&config_path)
+            .ok()
+            .and_then(|config| config.webhook)
+        else {
+            continue;
+        };
+        match deliver(&webhook, &update).await {
+            Ok(()) => debug!("Delivered version {} to {}", update.version, webhook.url),
+            Err(e) => warn!("Webhook delivery to {} failed: {e:#}", webhook.url),
+        }
+    }
+}
+
+/// POST one update to the webhook, signing it when a secret is configured.
+async fn deliver(webhook: &WebhookConfig, update: &DataUpdate) -> Result<()> {
+    let body = facet_json::to_string(update).wrap_err("failed to serialize update")?;
+
+    let mut args = vec![
+        "--silent".to_string(),
+        "--show-error".to_string(),
+        "--fail".to_string(),
+        // The URL is only meant to be a web endpoint; keep curl from reading
+        // `file://` or talking to other services, directly or via redirects.
+        "--proto".to_string(),
+        "=http,https".to_string(),
+        "--proto-redir".to_string(),
+        "=http,https".to_string(),
+        "--max-time".to_string(),
+        TIMEOUT_SECS.to_string(),
+        "--user-agent".to_string(),
+        concat!("tracey/", env!("CARGO_PKG_VERSION")).to_string(),
+        "--header".to_string(),
+        "Content-Type: application/json".to_string(),
+        "--header".to_string(),
+        "X-Tracey-Event: coverage".to_string(),
+    ];
+    // r[impl daemon.webhook.signature]
+    if let Some(var) = &webhook.secret_env {
+        // Never fall back to an unsigned request: receivers that verify
+        // signatures would reject it anyway.
+        let secret =
+            std::env::var(var).wrap_err_with(|| format!("secret variable `{var}` is not set"))?;
+        args.push("--header".to_string());
+        args.push(format!(
+            "{SIGNATURE_HEADER}: {}",
+            signature(secret.as_bytes(), body.as_bytes())
+        ));
+    }
+    args.extend([
+        "--data-binary".to_string(),
+        "@-".to_string(),
+        "--output".to_string(),
+        if cfg!(windows) { "NUL" } else { "/dev/null" }.to_string(),
+        // The URL comes from the config; never let curl read it as an option.
+        "--".to_string(),
+        webhook.url.clone(),
+    ]);
+
+    let mut child = tokio::process::Command::new("curl")
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err("failed to run curl; is it installed?")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        eyre::bail!(
+            "curl exit {}: {}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Value of the signature header for `body`: `sha256=` followed by the
+/// lowercase hex HMAC-SHA256 of the body keyed with `secret`.
+pub fn signature(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    let mut out = String::from("sha256=");
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify daemon.webhook.signature]
+    #[test]
+    fn signature_matches_rfc4231_vectors() {
+        // Test case 2: short key
+        assert_eq!(
+            signature(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: key longer than the block size is hashed first
+        assert_eq!(
+            signature(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "sha256=60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    /// Accept one HTTP request on a local port and answer it with `status`.
+    /// Returns the URL to post to and the raw request once it arrived.
+    fn listen(status: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    fn update() -> DataUpdate {
+        DataUpdate {
+            version: 7,
+            delta: None,
+        }
+    }
+
+    // r[verify daemon.webhook.post]
+    // r[verify daemon.webhook.signature]
+    #[tokio::test]
+    async fn posts_the_signed_update_to_a_local_listener() {
+        const SECRET_VAR: &str = "TRACEY_TEST_WEBHOOK_SECRET_POST";
+        // SAFETY: only this test reads or writes this variable.
+        unsafe { std::env::set_var(SECRET_VAR, "hunter2") };
+        let (url, request) = listen("200 OK");
+        let webhook = WebhookConfig {
+            url,
+            secret_env: Some(SECRET_VAR.to_string()),
+        };
+
+        deliver(&webhook, &update()).await.unwrap();
+
+        let request = request.join().unwrap();
+        let body = facet_json::to_string(&update()).unwrap();
+        assert!(request.starts_with("POST /hook HTTP/1.1\r\n"), "{request}");
+        assert!(
+            request.contains("Content-Type: application/json\r\n"),
+            "{request}"
+        );
+        assert!(
+            request.contains(&format!(
+                "{SIGNATURE_HEADER}: {}\r\n",
+                signature(b"hunter2", body.as_bytes())
+            )),
+            "{request}"
+        );
+        assert!(request.ends_with(&format!("\r\n\r\n{body}")), "{request}");
+    }
+
+    // r[verify daemon.webhook.post]
+    #[tokio::test]
+    async fn a_rejected_delivery_is_an_error() {
+        let (url, request) = listen("500 Internal Server Error");
+        let webhook = WebhookConfig {
+            url,
+            secret_env: None,
+        };
+
+        let err = deliver(&webhook, &update()).await.unwrap_err();
+        assert!(err.to_string().contains("curl exit"), "{err}");
+        assert!(!request.join().unwrap().contains(SIGNATURE_HEADER));
+    }
+
+    #[tokio::test]
+    async fn only_http_urls_are_fetched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("target");
+        std::fs::write(&file, "").unwrap();
+        let webhook = WebhookConfig {
+            url: format!("file://{}", file.display()),
+            secret_env: None,
+        };
+
+        let err = deliver(&webhook, &update()).await.unwrap_err();
+        assert!(err.to_string().contains("curl exit"), "{err}");
+    }
+
+    // r[verify daemon.webhook.signature]
+    #[tokio::test]
+    async fn a_missing_secret_is_never_sent_unsigned() {
+        let webhook = WebhookConfig {
+            url: "http://127.0.0.1:9/never".to_string(),
+            secret_env: Some("TRACEY_TEST_WEBHOOK_SECRET_UNSET".to_string()),
+        };
+        let err = deliver(&webhook, &update()).await.unwrap_err();
+        assert!(err.to_string().contains("is not set"), "{err}");
+    }
+}
@@ -10,17 +10,19 @@ use owo_colors::OwoColorize;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracey_core::code_units::CodeUnit;
 use tracey_core::{
-    ParseWarning, RefVerb, ReqDefinition, ReqReference, Reqs, RuleId, RuleIdMatch,
-    classify_reference_for_rule, parse_rule_id,
+    CommentDelimiters, ParseWarning, RefVerb, ReqDefinition, ReqReference, Reqs, RuleId,
+    RuleIdMatch, StringReferences, classify_reference_for_rule, find_rule_id_mentions,
+    is_rule_pattern, parse_rule_id, rule_pattern_matches,
 };
 use tracey_core::{SUPPORTED_EXTENSIONS, is_supported_extension};
 use tracing::info;
@@ -31,8 +33,12 @@ use marq::{
     RenderOptions, ReqHandler, parse_frontmatter, render,
 };
 
-use crate::config::Config;
+use crate::config::{
+    Config, Fanout, Ownership, SpecConfig, TagThreshold, TestImpls, VerbRole, VerbRoles,
+};
+use crate::remote_cache::RemoteCache;
 use crate::rule_suggestions::suggest_similar_rule_ids;
+use crate::sandbox::Sandbox;
 use crate::search;
 
 // ============================================================================
@@ -41,9 +47,10 @@ use crate::search;
 
 // Re-export API types from tracey-api crate
 pub use tracey_api::{
-    ApiCodeRef, ApiCodeUnit, ApiConfig, ApiFileData, ApiFileEntry, ApiForwardData, ApiReverseData,
-    ApiRule, ApiSpecData, ApiSpecForward, ApiSpecInfo, ApiStaleRef, GitStatus, OutlineCoverage,
-    OutlineEntry, SpecSection, ValidationError, ValidationErrorCode, ValidationResult,
+    ApiCodeRef, ApiCodeUnit, ApiConfig, ApiFanout, ApiFileData, ApiFileEntry, ApiForwardData,
+    ApiReverseData, ApiRule, ApiRuleExample, ApiSpecData, ApiSpecForward, ApiSpecInfo, ApiStaleRef,
+    ApiVerb, ApiWaiver, GitStatus, OutlineCoverage, OutlineEntry, SpecSection, ValidationError,
+    ValidationErrorCode, ValidationResult,
 };
 use tracey_proto::{LspDiagnostic, LspFileDiagnostics};
 
@@ -67,6 +74,8 @@ pub struct DashboardData {
     pub specs_content_by_impl: BTreeMap<ImplKey, ApiSpecData>,
     /// Spec include patterns by spec name
     pub spec_includes_by_name: BTreeMap<String, Vec<String>>,
+    /// Sandbox limits of specs whose sources are untrusted, by spec name
+    pub spec_sandboxes: BTreeMap<String, Sandbox>,
     /// Source files for full-text index construction
     pub search_files: BTreeMap<PathBuf, String>,
     /// Parsed requirement references and warnings by source file, captured during rebuild.
@@ -87,14 +96,124 @@ pub struct DashboardData {
     /// Files matched by test_include patterns (only verify allowed)
     /// r[impl config.impl.test_include]
     pub test_files: std::collections::HashSet<PathBuf>,
+    /// Files of each implementation that could not be parsed, kept so a
+    /// scoped rebuild can carry them over for implementations it skips
+    pub parse_failures_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, String>>,
+    /// Files of each implementation skipped for exceeding an extraction
+    /// limit, with the reason
+    pub skipped_files_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, String>>,
+    /// Files each implementation includes but another implementation of the
+    /// same spec owns, with the owner
+    pub ownership_conflicts_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, String>>,
+}
+
+/// Which implementations a rebuild recomputes. Implementations outside the
+/// scope keep the coverage computed by the previous build.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RebuildScope {
+    /// Only implementations of this spec
+    pub spec: Option<String>,
+    /// Only implementations with this name
+    pub impl_name: Option<String>,
+}
+
+impl RebuildScope {
+    /// Whether the scope covers every implementation
+    pub fn is_everything(&self) -> bool {
+        self.spec.is_none() && self.impl_name.is_none()
+    }
+
+    pub fn includes(&self, (spec, impl_name): &ImplKey) -> bool {
+        self.spec.as_ref().is_none_or(|s| s == spec)
+            && self.impl_name.as_ref().is_none_or(|i| i == impl_name)
+    }
+
+    /// The narrowest scope covering both `self` and `other`
+    pub fn union(self, other: RebuildScope) -> RebuildScope {
+        RebuildScope {
+            spec: self.spec.filter(|s| other.spec.as_ref() == Some(s)),
+            impl_name: self
+                .impl_name
+                .filter(|i| other.impl_name.as_ref() == Some(i)),
+        }
+    }
+
+    /// The narrowest scope covering the implementations and specs that
+    /// include any of `paths` (relative to the project root). A path no spec
+    /// or implementation includes widens the scope to everything.
+    pub fn for_paths(config: &Config, paths: &[PathBuf]) -> RebuildScope {
+        let matches = |patterns: &[String], path: &Path| {
+            patterns.iter().any(|p| {
+                globset::Glob::new(p)
+                    .map(|g| g.compile_matcher().is_match(path))
+                    .unwrap_or(false)
+            })
+        };
+        let mut scope: Option<RebuildScope> = None;
+        for path in paths {
+            let mut path_scope: Option<RebuildScope> = None;
+            let mut add = |found: RebuildScope| {
+                path_scope = Some(match path_scope.take() {
+                    Some(current) => current.union(found),
+                    None => found,
+                });
+            };
+            for spec in &config.specs {
+                if matches(&spec.include, path) {
+                    add(RebuildScope {
+                        spec: Some(spec.name.clone()),
+                        impl_name: None,
+                    });
+                }
+                for impl_config in &spec.impls {
+                    let include: &[String] = if impl_config.include.is_empty() {
+                        &["**/*.rs".to_string()]
+                    } else {
+                        &impl_config.include
+                    };
+                    if matches(include, path)
+                        || matches(&impl_config.test_include, path)
+                        || matches(&impl_config.proof_include, path)
+                    {
+                        add(RebuildScope {
+                            spec: Some(spec.name.clone()),
+                            impl_name: Some(impl_config.name.clone()),
+                        });
+                    }
+                }
+            }
+            let Some(path_scope) = path_scope else {
+                return RebuildScope::default();
+            };
+            scope = Some(match scope {
+                Some(current) => current.union(path_scope),
+                None => path_scope,
+            });
+        }
+        scope.unwrap_or_default()
+    }
 }
 
 #[derive(Default)]
 pub struct BuildCache {
     source_files: HashMap<PathBuf, CachedSourceFile>,
+    /// Custom verbs `source_files` were parsed with
+    custom_verbs: Vec<String>,
+    /// Comment markers of configured file types `source_files` were parsed with
+    comments: BTreeMap<String, CommentDelimiters>,
     impl_scan_paths: HashMap<ImplScanKey, CachedScanPaths>,
     spec_scan_paths: HashMap<SpecScanKey, CachedScanPaths>,
     markdown_files: HashMap<PathBuf, CachedMarkdownFile>,
+    /// Shared store consulted before parsing, when configured
+    remote: Option<RemoteCache>,
+}
+
+impl BuildCache {
+    /// Comment markers declared in the config for `path`'s file type.
+    fn comments_for(&self, path: &Path) -> Option<&CommentDelimiters> {
+        let ext = path.extension()?.to_str()?;
+        self.comments.get(ext)
+    }
 }
 
 #[derive(Clone)]
@@ -110,8 +229,13 @@ struct CachedSourceFile {
 
 #[derive(Default)]
 struct CacheStats {
+    /// Files used from the cache without reading their metadata, because the
+    /// build was told which paths changed and they were not among them
+    unchanged_hits: usize,
     metadata_hits: usize,
     hash_hits: usize,
+    /// Misses answered by the remote cache instead of parsing
+    remote_hits: usize,
     misses: usize,
     reparsed: usize,
 }
@@ -168,6 +292,14 @@ struct RuleCoverage {
     status: &'static str, // "covered", "partial", "stale", "uncovered"
     impl_refs: Vec<ApiCodeRef>,
     verify_refs: Vec<ApiCodeRef>,
+    /// Whether the rule has every impl reference it needs (see `ApiRule::is_implemented`)
+    implemented: bool,
+    /// Whether a reference with a `docs`-role verb points at the rule
+    documented: bool,
+    /// Rules mentioned in this rule's prose
+    related: Vec<RuleId>,
+    /// Examples and counter-examples written next to the rule
+    examples: Vec<ApiRuleExample>,
 }
 
 /// Custom rule handler that renders rules with coverage status and refs
@@ -431,17 +563,25 @@ impl ReqHandler for TraceyRuleHandler {
                 source_file
             );
 
+            // r[impl dashboard.editing.copy.snippet]
+            // Annotation snippet badge - copies a ready-to-paste comment
+            let snippet_badge_html = format!(
+                r#"<button class="req-badge req-snippet" data-req-id="{}" title="Copy annotation comment"><svg class="req-copy-icon" width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><polyline points="16 18 22 12 16 6"></polyline><polyline points="8 6 2 12 8 18"></polyline></svg> Annotate</button>"#,
+                &rule_id
+            );
+
             // Render the opening of the req container
             Ok(format!(
                 r#"<div class="req-container req-{status}" id="{anchor}" data-br="{br_start}-{br_end}">
 <div class="req-badges-left">{badges}</div>
-<div class="req-badges-right">{edit_badge}</div>
+<div class="req-badges-right">{snippet_badge}{edit_badge}</div>
 <div class="req-content">"#,
                 status = status,
                 anchor = rule.anchor_id,
                 br_start = rule.span.offset,
                 br_end = rule.span.offset + rule.span.length,
                 badges = badges_html,
+                snippet_badge = snippet_badge_html,
                 edit_badge = edit_badge_html,
             ))
         })
@@ -449,11 +589,50 @@ impl ReqHandler for TraceyRuleHandler {
 
     fn end<'a>(
         &'a self,
-        _rule: &'a ReqDefinition,
+        rule: &'a ReqDefinition,
     ) -> Pin<Box<dyn Future<Output = marq::Result<String>> + Send + 'a>> {
         Box::pin(async move {
-            // Close the rule container
-            Ok("</div>\n</div>".to_string())
+            // r[impl markdown.related.links]
+            // Link rules mentioned in the prose, list examples, then close the
+            // rule container
+            let coverage = self.coverage.get(&rule.id.to_string());
+            let related = coverage.map(|c| c.related.as_slice()).unwrap_or_default();
+            let examples = coverage.map(|c| c.examples.as_slice()).unwrap_or_default();
+            let mut html = String::new();
+            if !related.is_empty() {
+                let links = related
+                    .iter()
+                    .map(|id| {
+                        format!(
+                            r#"<a class="rule-ref" href="/{}/{}/spec#r--{id}" data-rule="{id}">{id}</a>"#,
+                            self.spec_name, self.impl_name
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                html.push_str(&format!(
+                    r#"<div class="req-related">Related: {links}</div>"#
+                ));
+            }
+            // r[impl markdown.examples.html]
+            if !examples.is_empty() {
+                let opts = RenderOptions::new().with_default_handler(ArboriumHandler::new());
+                html.push_str(r#"<details class="req-examples"><summary>Examples</summary>"#);
+                for example in examples {
+                    let (class, label) = match example.kind.as_str() {
+                        "good" => ("req-example-good", "✓ Good"),
+                        _ => ("req-example-bad", "✗ Bad"),
+                    };
+                    let doc = render(&example.markdown, &opts).await?;
+                    html.push_str(&format!(
+                        r#"<div class="req-example {class}"><div class="req-example-label">{label}</div>{}</div>"#,
+                        doc.html
+                    ));
+                }
+                html.push_str("</details>");
+            }
+            html.push_str("</div>\n</div>");
+            Ok(html)
         })
     }
 }
@@ -560,7 +739,7 @@ async fn read_file_with_overlay(path: &Path, overlay: &FileOverlay) -> std::io::
         return Ok(content.clone());
     }
     // Fall back to disk (async)
-    tokio::fs::read_to_string(path).await
+    Ok(tracey_core::decode_source(tokio::fs::read(path).await?).0)
 }
 
 fn file_modified_nanos(modified: SystemTime) -> Option<u128> {
@@ -595,12 +774,294 @@ fn extract_marker_prefix_from_content(
     Some(prefix.to_string())
 }
 
+/// Decides which implementation of a spec counts the references in a file
+/// that several of its implementations include.
+// r[impl config.spec.ownership]
+struct FileOwnership {
+    /// The spec's implementations, in config order
+    impls: Vec<ImplPatterns>,
+    shared: Option<globset::GlobSet>,
+    ownership: Ownership,
+    project_root: PathBuf,
+    /// Files passed over since the last `take_conflicts`, with their owner
+    conflicts: Vec<(PathBuf, String)>,
+}
+
+/// The patterns of one implementation, as `FileOwnership` matches them.
+struct ImplPatterns {
+    name: String,
+    roots: Vec<ScanRootPattern>,
+    /// Depth of the directory each of `roots` names
+    depths: Vec<usize>,
+    exclude: Vec<String>,
+}
+
+impl FileOwnership {
+    fn new(project_root: &Path, spec: &SpecConfig) -> Self {
+        let impls = spec
+            .impls
+            .iter()
+            .map(|impl_config| {
+                let mut include = if impl_config.include.is_empty() {
+                    vec!["**/*.rs".to_string()]
+                } else {
+                    impl_config.include.clone()
+                };
+                include.extend(impl_config.test_include.iter().cloned());
+                include.extend(impl_config.proof_include.iter().cloned());
+                let (roots, depths) = include
+                    .iter()
+                    .flat_map(|pattern| {
+                        let (base, suffix) = split_glob_prefix(pattern);
+                        let depth =
+                            Path::new(base).components().count() + usize::from(suffix.is_empty());
+                        build_scan_roots(project_root, std::slice::from_ref(pattern))
+                            .0
+                            .into_iter()
+                            .map(move |root| (root, depth))
+                    })
+                    .unzip();
+                ImplPatterns {
+                    name: impl_config.name.clone(),
+                    roots,
+                    depths,
+                    exclude: impl_config.exclude.clone(),
+                }
+            })
+            .collect();
+        let shared = (!spec.shared.is_empty()).then(|| {
+            let mut builder = globset::GlobSetBuilder::new();
+            for pattern in &spec.shared {
+                if let Ok(glob) = globset::Glob::new(pattern) {
+                    builder.add(glob);
+                }
+            }
+            builder
+                .build()
+                .unwrap_or_else(|_| globset::GlobSet::empty())
+        });
+        Self {
+            impls,
+            shared,
+            ownership: crate::config::ownership(spec),
+            project_root: project_root.to_path_buf(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// The implementation owning `path`, or `None` when no more than one
+    /// implementation includes it or it is shared.
+    // r[impl config.spec.shared]
+    fn owner(&self, path: &Path) -> Option<&str> {
+        if self.impls.len() < 2 {
+            return None;
+        }
+        if let Some(shared) = &self.shared
+            && path
+                .strip_prefix(&self.project_root)
+                .is_ok_and(|relative| shared.is_match(relative))
+        {
+            return None;
+        }
+        let mut matching = self
+            .impls
+            .iter()
+            .filter_map(|patterns| {
+                let depth = patterns
+                    .roots
+                    .iter()
+                    .zip(&patterns.depths)
+                    .filter(|(root, _)| path_matches_root_pattern(path, root))
+                    .map(|(_, depth)| *depth)
+                    .max()?;
+                (!path_matches_excludes(path, &patterns.roots, &patterns.exclude))
+                    .then_some((patterns.name.as_str(), depth))
+            })
+            .collect::<Vec<_>>();
+        if matching.len() < 2 {
+            return None;
+        }
+        if self.ownership == Ownership::Specific {
+            // Stable, so ties keep config order.
+            matching.sort_by_key(|(_, depth)| std::cmp::Reverse(*depth));
+        }
+        matching.first().map(|(name, _)| *name)
+    }
+
+    /// Whether `impl_name` counts the references in `path`. A file another
+    /// implementation owns is recorded as a conflict.
+    fn claims(&mut self, impl_name: &str, path: &Path) -> bool {
+        match self.owner(path) {
+            Some(owner) if owner != impl_name => {
+                let owner = owner.to_string();
+                self.conflicts.push((path.to_path_buf(), owner));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn take_conflicts(&mut self) -> Vec<(PathBuf, String)> {
+        std::mem::take(&mut self.conflicts)
+    }
+}
+
+/// Resource limits for one build's source extraction, from the config's
+/// `limits` block. A file over a limit is skipped and recorded in `skipped`
+/// rather than failing or stalling the build.
+// r[impl config.limits]
+struct ExtractionLimits {
+    max_file_bytes: Option<u64>,
+    /// Files that may still be extracted, when `max_files` is set
+    files_left: Option<u64>,
+    file_timeout: Option<Duration>,
+    /// When extraction must stop, and the budget in seconds
+    deadline: Option<(Instant, u64)>,
+    /// Files skipped since the last `take_skipped`, with the reason
+    skipped: Vec<(PathBuf, String)>,
+}
+
+impl ExtractionLimits {
+    fn new(config: Option<&crate::config::LimitsConfig>, start: Instant) -> Self {
+        let config = config.cloned().unwrap_or_default();
+        Self {
+            max_file_bytes: config.max_file_kb.map(|kb| kb.saturating_mul(1024)),
+            files_left: config.max_files,
+            file_timeout: config.file_timeout_ms.map(Duration::from_millis),
+            deadline: config
+                .budget_secs
+                .map(|secs| (start + Duration::from_secs(secs), secs)),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Count `path` against the file limit, or return why it must be
+    /// skipped without being read.
+    fn admit(&mut self) -> Option<String> {
+        if let Some((deadline, secs)) = self.deadline
+            && Instant::now() >= deadline
+        {
+            return Some(format!("the extraction budget of {secs}s ran out"));
+        }
+        match &mut self.files_left {
+            Some(0) => Some("the max_files limit was reached".to_string()),
+            Some(left) => {
+                *left -= 1;
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn check_size(&self, len: u64) -> std::io::Result<()> {
+        match self.max_file_bytes {
+            Some(max) if len > max => Err(std::io::Error::new(
+                std::io::ErrorKind::FileTooLarge,
+                format!(
+                    "{} KB is over the max_file_kb limit of {} KB",
+                    len.div_ceil(1024),
+                    max / 1024
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn take_skipped(&mut self) -> Vec<(PathBuf, String)> {
+        std::mem::take(&mut self.skipped)
+    }
+}
+
+/// Extract references and code units from `content`. With a timeout, parsing
+/// runs on a blocking thread and is abandoned once the timeout passes.
+async fn extract_source(
+    path: &Path,
+    content: String,
+    custom_verbs: &[String],
+    comments: Option<&CommentDelimiters>,
+    timeout: Option<Duration>,
+) -> std::io::Result<(String, Reqs, Vec<CodeUnit>)> {
+    let path = path.to_path_buf();
+    let custom_verbs = custom_verbs.to_vec();
+    let comments = comments.cloned();
+    let extract = move || {
+        // r[impl config.comments]
+        // File types declared in the config have no parser, hence no code units.
+        if let Some(comments) = &comments {
+            let reqs = Reqs::extract_from_content_with_delimiters(
+                &path,
+                &content,
+                &custom_verbs,
+                comments,
+            );
+            return (content, reqs, Vec::new());
+        }
+        let reqs = Reqs::extract_from_content_with_verbs(&path, &content, &custom_verbs);
+        let mut code_units = tracey_core::code_units::extract(&path, &content).units;
+        tracey_core::code_units::attribute_regions(&mut code_units, &reqs.references);
+        (content, reqs, code_units)
+    };
+    let Some(timeout) = timeout else {
+        return Ok(extract());
+    };
+    match tokio::time::timeout(timeout, tokio::task::spawn_blocking(extract)).await {
+        Ok(Ok(extracted)) => Ok(extracted),
+        Ok(Err(err)) => Err(std::io::Error::other(err)),
+        Err(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            format!(
+                "parsing took longer than the file_timeout_ms limit of {} ms",
+                timeout.as_millis()
+            ),
+        )),
+    }
+}
+
+/// Parse a file read from disk, through the remote cache when there is one.
+async fn extract_source_shared(
+    path: &Path,
+    content: String,
+    cache: &BuildCache,
+    stats: &mut CacheStats,
+    timeout: Option<Duration>,
+) -> std::io::Result<(String, Reqs, Vec<CodeUnit>)> {
+    let comments = cache.comments_for(path);
+    // Remote cache keys don't cover configured comment markers.
+    let Some(remote) = cache.remote.as_ref().filter(|_| comments.is_none()) else {
+        stats.reparsed += 1;
+        return extract_source(path, content, &cache.custom_verbs, comments, timeout).await;
+    };
+    let key = RemoteCache::key(path, &content, &cache.custom_verbs);
+    if let Some((reqs, code_units)) = remote.get(&key, path).await {
+        stats.remote_hits += 1;
+        return Ok((content, reqs, code_units));
+    }
+    stats.reparsed += 1;
+    let (content, reqs, code_units) =
+        extract_source(path, content, &cache.custom_verbs, None, timeout).await?;
+    remote.put(&key, &reqs, &code_units).await;
+    Ok((content, reqs, code_units))
+}
+
+/// Read and extract `path`, or take it from the cache. With `unchanged`,
+/// the caller knows the file has not changed since it was cached, so a cached
+/// entry is used without touching the file.
 async fn get_cached_source_file(
     path: &Path,
     overlay: &FileOverlay,
     cache: &mut BuildCache,
     stats: &mut CacheStats,
+    limits: &ExtractionLimits,
+    unchanged: bool,
 ) -> std::io::Result<CachedSourceFile> {
+    if unchanged
+        && !overlay.contains_key(path)
+        && let Some(entry) = cache.source_files.get(path)
+    {
+        stats.unchanged_hits += 1;
+        return Ok(entry.clone());
+    }
+
     let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
     let overlay_content = overlay
@@ -609,6 +1070,7 @@ async fn get_cached_source_file(
         .cloned();
 
     if let Some(content) = overlay_content {
+        limits.check_size(content.len() as u64)?;
         let content_hash = compute_content_hash(&content);
         if let Some(entry) = cache.source_files.get(&canonical)
             && entry.content_hash == content_hash
@@ -617,8 +1079,14 @@ async fn get_cached_source_file(
             return Ok(entry.clone());
         }
 
-        let reqs = Reqs::extract_from_content(&canonical, &content);
-        let code_units = tracey_core::code_units::extract(&canonical, &content).units;
+        let (content, reqs, code_units) = extract_source(
+            &canonical,
+            content,
+            &cache.custom_verbs,
+            cache.comments_for(&canonical),
+            limits.file_timeout,
+        )
+        .await?;
         let parsed = CachedSourceFile {
             content_hash,
             file_len: content.len() as u64,
@@ -636,6 +1104,7 @@ async fn get_cached_source_file(
 
     let metadata = tokio::fs::metadata(&canonical).await?;
     let file_len = metadata.len();
+    limits.check_size(file_len)?;
     let modified_nanos = metadata.modified().ok().and_then(file_modified_nanos);
 
     if let Some(entry) = cache.source_files.get(&canonical)
@@ -646,7 +1115,8 @@ async fn get_cached_source_file(
         return Ok(entry.clone());
     }
 
-    let content = read_file_with_overlay(&canonical, overlay).await?;
+    // r[impl ref.encoding.fallback]
+    let (content, encoding) = tracey_core::decode_source(tokio::fs::read(&canonical).await?);
     let content_hash = compute_content_hash(&content);
 
     if let Some(entry) = cache.source_files.get(&canonical)
@@ -660,19 +1130,20 @@ async fn get_cached_source_file(
         return Ok(updated);
     }
 
-    let reqs = Reqs::extract_from_content(&canonical, &content);
-    let code_units = tracey_core::code_units::extract(&canonical, &content).units;
+    let (content, reqs, code_units) =
+        extract_source_shared(&canonical, content, cache, stats, limits.file_timeout).await?;
+    let mut parse_warnings: Vec<_> = encoding.warning(&canonical).into_iter().collect();
+    parse_warnings.extend(reqs.warnings);
     let parsed = CachedSourceFile {
         content_hash,
         file_len,
         modified_nanos,
         content,
         refs: reqs.references,
-        parse_warnings: reqs.warnings,
+        parse_warnings,
         code_units,
     };
     stats.misses += 1;
-    stats.reparsed += 1;
     cache.source_files.insert(canonical, parsed.clone());
     Ok(parsed)
 }
@@ -688,7 +1159,7 @@ struct ScanRootPattern {
 /// The directory prefix is the longest path before any wildcard characters,
 /// so that the walker can start from a narrowed root instead of scanning
 /// the entire project tree.
-fn split_glob_prefix(pattern: &str) -> (&str, &str) {
+pub(crate) fn split_glob_prefix(pattern: &str) -> (&str, &str) {
     if let Some(wildcard_pos) = pattern.find("**").or_else(|| pattern.find('*')) {
         let base = pattern[..wildcard_pos].trim_end_matches('/');
         let suffix = &pattern[wildcard_pos..];
@@ -796,18 +1267,111 @@ fn path_matches_any_root(path: &Path, roots: &[ScanRootPattern]) -> bool {
 }
 
 fn path_matches_excludes(path: &Path, roots: &[ScanRootPattern], exclude: &[String]) -> bool {
-    roots.iter().any(|r| {
-        let Ok(relative) = path.strip_prefix(&r.root) else {
-            return false;
-        };
-        exclude.iter().any(|pattern| {
-            globset::Glob::new(pattern)
-                .map(|g| g.compile_matcher().is_match(relative))
-                .unwrap_or(false)
-        })
+    matching_exclude(path, roots, exclude).is_some()
+}
+
+/// The first exclude pattern that matches `path`, relative to any scan root.
+fn matching_exclude<'a>(
+    path: &Path,
+    roots: &[ScanRootPattern],
+    exclude: &'a [String],
+) -> Option<&'a str> {
+    roots.iter().find_map(|r| {
+        let relative = path.strip_prefix(&r.root).ok()?;
+        exclude
+            .iter()
+            .find(|pattern| {
+                globset::Glob::new(pattern)
+                    .map(|g| g.compile_matcher().is_match(relative))
+                    .unwrap_or(false)
+            })
+            .map(String::as_str)
     })
 }
 
+/// Why a path reached by an implementation's include patterns is not scanned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Ignored by git, so the walker never visits it. May be a directory.
+    Gitignored,
+    /// Matched this `exclude` pattern.
+    Excluded(String),
+    /// Has no extension, or one tracey cannot parse.
+    UnsupportedExtension,
+}
+
+/// Resolve an implementation's include/exclude patterns the way a scan does,
+/// but keep every path the include patterns reach along with the reason it is
+/// skipped (`None` for files that are scanned). Also returns warnings about
+/// the patterns themselves, such as include paths that don't exist.
+///
+/// r[impl cli.files]
+pub fn explain_impl_scan(
+    project_root: &Path,
+    include: &[String],
+    exclude: &[String],
+    configured_extensions: &[String],
+) -> (BTreeMap<PathBuf, Option<SkipReason>>, Vec<String>) {
+    let (roots, warnings) = build_scan_roots(project_root, include);
+    let mut out = BTreeMap::new();
+
+    for root_pattern in &roots {
+        let walker = ignore::WalkBuilder::new(&root_pattern.root)
+            .follow_links(true)
+            .hidden(false)
+            .git_ignore(true)
+            .build();
+        for entry in walker.flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_some_and(|ft| ft.is_file())
+                || !path_matches_root_pattern(path, root_pattern)
+            {
+                continue;
+            }
+            let reason = if let Some(pattern) = matching_exclude(path, &roots, exclude) {
+                Some(SkipReason::Excluded(pattern.to_string()))
+            } else if path.extension().is_none_or(|ext| {
+                !is_supported_extension(ext)
+                    && !configured_extensions.iter().any(|c| ext == c.as_str())
+            }) {
+                Some(SkipReason::UnsupportedExtension)
+            } else {
+                None
+            };
+            out.insert(path.to_path_buf(), reason);
+        }
+    }
+
+    // `--directory` collapses wholly ignored trees (like `target/`) to one
+    // entry, so this stays cheap where walking them would not.
+    let ignored = crate::bump::git_capture(
+        project_root,
+        &[
+            "ls-files",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+        ],
+    )
+    .unwrap_or_default();
+    for line in ignored.lines() {
+        let path = project_root.join(line.trim_end_matches('/'));
+        let reached = if line.ends_with('/') {
+            roots
+                .iter()
+                .any(|r| path.starts_with(&r.root) || r.root.starts_with(&path))
+        } else {
+            path_matches_any_root(&path, &roots)
+        };
+        if reached {
+            out.insert(path, Some(SkipReason::Gitignored));
+        }
+    }
+
+    (out, warnings)
+}
+
 fn full_walk_for_roots(
     roots: &[ScanRootPattern],
     include_supported_ext_only: bool,
@@ -919,6 +1483,29 @@ fn get_cached_impl_scan_paths(
     (entry.files.clone(), warnings, did_full_walk)
 }
 
+/// Files an implementation was last scanned with, from the scan cache and
+/// without walking the tree. `None` if either include list was never scanned.
+fn cached_impl_files(
+    project_root: &Path,
+    include: &[String],
+    test_include: &[String],
+    exclude: &[String],
+    cache: &BuildCache,
+) -> Option<BTreeSet<PathBuf>> {
+    let scanned = |include: &[String]| {
+        cache.impl_scan_paths.get(&ImplScanKey {
+            project_root: project_root.to_path_buf(),
+            include: include.to_vec(),
+            exclude: exclude.to_vec(),
+        })
+    };
+    let mut files = scanned(include)?.files.clone();
+    if !test_include.is_empty() {
+        files.extend(scanned(test_include)?.files.iter().cloned());
+    }
+    Some(files)
+}
+
 fn get_cached_spec_scan_paths(
     project_root: &Path,
     include: &[String],
@@ -1004,7 +1591,8 @@ async fn extract_markdown_rules_cached(
         compute_relative_path(project_root, &canonical)
     };
 
-    let doc = render(&content, &RenderOptions::default())
+    let (markdown, mut examples) = crate::examples::extract(&content);
+    let doc = render(&markdown, &RenderOptions::default())
         .await
         .map_err(|e| eyre::eyre!("Failed to process {}: {}", canonical.display(), e))?;
 
@@ -1019,21 +1607,7 @@ async fn extract_markdown_rules_cached(
 
     let mut extracted = Vec::new();
     if !doc.reqs.is_empty() {
-        use marq::DocElement;
-        let mut rule_sections: HashMap<String, (Option<String>, Option<String>)> = HashMap::new();
-        let mut current_section: Option<(String, String)> = None;
-        for element in &doc.elements {
-            match element {
-                DocElement::Heading(h) => current_section = Some((h.id.clone(), h.title.clone())),
-                DocElement::Req(r) => {
-                    if let Some((slug, title)) = &current_section {
-                        rule_sections
-                            .insert(r.id.to_string(), (Some(slug.clone()), Some(title.clone())));
-                    }
-                }
-                DocElement::Paragraph(_) => {}
-            }
-        }
+        let mut rule_sections = crate::rule_sections(&doc.elements);
 
         for req in doc.reqs {
             let column = Some(compute_column_for_content(&content, req.span.offset));
@@ -1045,16 +1619,33 @@ async fn extract_markdown_rules_cached(
                         req.line
                     )
                 })?;
-            let (section, section_title) = rule_sections
+            let section = rule_sections
                 .remove(&req.id.to_string())
-                .unwrap_or((None, None));
+                .unwrap_or_default();
+            let (own, rest) = examples.into_iter().partition(|e| e.rule == req.id.base);
+            examples = rest;
+            let owner = crate::marker_owner(&content, req.marker_span);
+            let fanout = crate::marker_fanout(&content, req.marker_span).map_err(|e| {
+                eyre::eyre!(
+                    "Invalid fanout for {} in {} at line {}: {e}",
+                    req.id,
+                    relative_display,
+                    req.line
+                )
+            })?;
+            let params = crate::marker_params(&content, req.marker_span);
             extracted.push(crate::ExtractedRule {
                 def: req,
                 source_file: relative_display.clone(),
                 prefix,
                 column,
-                section,
-                section_title,
+                section: section.slug,
+                section_title: section.title,
+                section_path: section.path,
+                examples: own,
+                owner,
+                fanout,
+                params,
             });
         }
     }
@@ -1157,6 +1748,7 @@ async fn extract_sdoc_rules_cached(
     Ok(extracted)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn load_rules_from_includes_cached(
     project_root: &Path,
     include_patterns: &[String],
@@ -1165,6 +1757,7 @@ async fn load_rules_from_includes_cached(
     quiet: bool,
     changed_files: &[PathBuf],
     stats: &mut CacheStats,
+    sandbox: Option<&Sandbox>,
 ) -> Result<(Vec<crate::ExtractedRule>, Vec<PathBuf>, bool)> {
     let (mut spec_paths, _warnings, did_full_walk) =
         get_cached_spec_scan_paths(project_root, include_patterns, changed_files, cache);
@@ -1185,6 +1778,13 @@ async fn load_rules_from_includes_cached(
     let mut seen_ids: BTreeSet<String> = BTreeSet::new();
     let collected_paths: Vec<PathBuf> = spec_paths.into_iter().collect();
     for path in &collected_paths {
+        if let Some(sandbox) = sandbox {
+            let len = match overlay.get(path) {
+                Some(content) => content.len() as u64,
+                None => tokio::fs::metadata(path).await.map_or(0, |m| m.len()),
+            };
+            sandbox.check_file_size(&compute_relative_path(project_root, path), len)?;
+        }
         let extracted = if path
             .extension()
             .and_then(|e| e.to_str())
@@ -1207,9 +1807,15 @@ async fn load_rules_from_includes_cached(
             all_rules.push(rule);
         }
     }
+    if let Some(sandbox) = sandbox {
+        for rule in &mut all_rules {
+            rule.def.html = sandbox.sanitize_html(&rule.def.html);
+        }
+    }
     Ok((all_rules, collected_paths, did_full_walk))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn scan_impl_files(
     project_root: &Path,
     include: &[String],
@@ -1218,6 +1824,10 @@ async fn scan_impl_files(
     cache: &mut BuildCache,
     changed_files: &[PathBuf],
     stats: &mut CacheStats,
+    limits: &mut ExtractionLimits,
+    ownership: &mut FileOwnership,
+    impl_name: &str,
+    strings: StringReferences,
 ) -> (
     Vec<ReqReference>,
     Vec<ParseWarning>,
@@ -1238,6 +1848,20 @@ async fn scan_impl_files(
             files.insert(overlay_path.clone());
         }
     }
+    files.retain(|path| ownership.claims(impl_name, path));
+    // r[impl daemon.reload.incremental]
+    // Scan paths are canonical. When the watcher or an editor reported which
+    // paths changed, the others are taken from the cache as extracted last
+    // time, like the scan paths themselves.
+    //
+    // Files go through the build cache one at a time rather than through
+    // `Reqs::extract_streaming`: streaming would read every file from disk,
+    // bypassing the cache, editor overlays, the remote cache and the
+    // per-file size and time limits.
+    let changed: HashSet<PathBuf> = changed_files
+        .iter()
+        .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+        .collect();
     let mut refs = Vec::new();
     let mut parse_warnings = Vec::new();
     let mut parse_failures = Vec::new();
@@ -1247,6 +1871,7 @@ async fn scan_impl_files(
     for path in files {
         match path.extension() {
             Some(ext) if is_supported_extension(ext) => {}
+            Some(_) if cache.comments_for(&path).is_some() => {}
             Some(ext) => {
                 parse_failures.push((
                     path.clone(),
@@ -1260,8 +1885,26 @@ async fn scan_impl_files(
             }
         }
 
-        match get_cached_source_file(&path, overlay, cache, stats).await {
-            Ok(parsed) => {
+        if let Some(reason) = limits.admit() {
+            limits.skipped.push((path, reason));
+            continue;
+        }
+        let unchanged = !changed.is_empty() && !changed.contains(&path);
+        match get_cached_source_file(&path, overlay, cache, stats, limits, unchanged).await {
+            Ok(mut parsed) => {
+                // r[impl config.spec.strings]
+                // Only some specs opt in, so string literals are scanned per
+                // implementation rather than cached with the file.
+                if !strings.is_empty() {
+                    let extra = Reqs::extract_from_strings(
+                        &path,
+                        &parsed.content,
+                        &cache.custom_verbs,
+                        strings,
+                    );
+                    parsed.refs.extend(extra.references);
+                    parsed.parse_warnings.extend(extra.warnings);
+                }
                 reqs_by_file.insert(
                     path.clone(),
                     Reqs {
@@ -1276,6 +1919,14 @@ async fn scan_impl_files(
                 }
                 file_contents.insert(path, parsed.content);
             }
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::FileTooLarge | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                limits.skipped.push((path, err.to_string()));
+            }
             Err(err) => {
                 parse_failures.push((path, format!("failed to read/parse file: {err}")));
             }
@@ -1293,6 +1944,73 @@ async fn scan_impl_files(
     )
 }
 
+/// An implementation's proof files and the harnesses proven in them.
+#[derive(Clone, Default)]
+struct ProofFiles {
+    patterns: Option<globset::GlobSet>,
+    /// Names of proven harnesses, from the imported proof results.
+    proven: Arc<HashSet<String>>,
+}
+
+/// Glob set of `patterns`, skipping invalid ones; `None` when there are none.
+fn glob_set(patterns: &[String]) -> Option<globset::GlobSet> {
+    if patterns.is_empty() {
+        return None;
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = globset::Glob::new(pattern) {
+            builder.add(glob);
+        }
+    }
+    builder.build().ok()
+}
+
+impl ProofFiles {
+    fn new(proof_include: &[String], proven: Arc<HashSet<String>>) -> Self {
+        match glob_set(proof_include) {
+            Some(patterns) => Self {
+                patterns: Some(patterns),
+                proven,
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Whether `relative_path` (relative to the project root) is a proof file.
+    // r[impl config.impl.proof_include]
+    fn includes(&self, relative_path: &str) -> bool {
+        self.patterns
+            .as_ref()
+            .is_some_and(|set| set.is_match(relative_path))
+    }
+}
+
+/// Test files whose `impl` references only count as verification, for a
+/// spec with `test_impls verify`. Matches nothing otherwise.
+#[derive(Clone, Default)]
+struct TestImplFiles {
+    patterns: Option<globset::GlobSet>,
+}
+
+impl TestImplFiles {
+    fn new(test_impls: TestImpls, test_include: &[String]) -> Self {
+        match test_impls {
+            TestImpls::Impl => Self::default(),
+            TestImpls::Verify => Self {
+                patterns: glob_set(test_include),
+            },
+        }
+    }
+
+    /// Whether `relative_path` (relative to the project root) is such a file.
+    fn includes(&self, relative_path: &str) -> bool {
+        self.patterns
+            .as_ref()
+            .is_some_and(|set| set.is_match(relative_path))
+    }
+}
+
 struct ImplComputedOutput {
     impl_name: String,
     api_rules: Vec<ApiRule>,
@@ -1379,8 +2097,8 @@ fn unknown_rule_message_with_context(
     }
 }
 
-// r[impl ref.syntax.req-id]
-fn is_valid_rule_id(id: &RuleId) -> bool {
+// r[impl ref.syntax.req-id+3]
+pub(crate) fn is_valid_rule_id(id: &RuleId) -> bool {
     let base_id = &id.base;
     for segment in base_id.split('.') {
         if segment.is_empty() {
@@ -1396,62 +2114,176 @@ fn is_valid_rule_id(id: &RuleId) -> bool {
     true
 }
 
-fn detect_circular_dependencies(forward_data: &ApiSpecForward) -> Vec<Vec<RuleId>> {
-    use std::collections::{HashMap, HashSet};
-
-    let mut graph: HashMap<RuleId, Vec<RuleId>> = HashMap::new();
-    for rule in &forward_data.rules {
-        graph.entry(rule.id.clone()).or_default();
-    }
-
-    let mut cycles = Vec::new();
-    let mut visited = HashSet::new();
-    let mut rec_stack = HashSet::new();
-    let mut path = Vec::new();
-
-    fn dfs(
-        node: &RuleId,
-        graph: &HashMap<RuleId, Vec<RuleId>>,
-        visited: &mut HashSet<RuleId>,
-        rec_stack: &mut HashSet<RuleId>,
-        path: &mut Vec<RuleId>,
-        cycles: &mut Vec<Vec<RuleId>>,
-    ) {
-        visited.insert(node.clone());
-        rec_stack.insert(node.clone());
-        path.push(node.clone());
-
-        if let Some(neighbors) = graph.get(node) {
-            for neighbor in neighbors {
-                if !visited.contains(neighbor) {
-                    dfs(neighbor, graph, visited, rec_stack, path, cycles);
-                } else if rec_stack.contains(neighbor) {
-                    let cycle_start = path.iter().position(|n| n == neighbor).unwrap_or(0);
-                    let mut cycle: Vec<RuleId> = path[cycle_start..].to_vec();
-                    cycle.push(neighbor.clone());
-                    cycles.push(cycle);
-                }
+/// Record rules mentioned in another rule's prose as implicit `related` edges.
+///
+/// Mentions are matched by base ID against the rules of the same spec and
+/// resolved to the current version of the mentioned rule.
+// r[impl markdown.related.prose]
+fn link_prose_mentions(rules: &mut [ApiRule]) {
+    let by_base: HashMap<String, RuleId> = rules
+        .iter()
+        .map(|r| (r.id.base.clone(), r.id.clone()))
+        .collect();
+    for rule in rules.iter_mut() {
+        let mut related: Vec<RuleId> = Vec::new();
+        for mention in find_rule_id_mentions(&rule.raw) {
+            if mention.base == rule.id.base {
+                continue;
+            }
+            if let Some(id) = by_base.get(&mention.base)
+                && !related.contains(id)
+            {
+                related.push(id.clone());
             }
         }
-
-        path.pop();
-        rec_stack.remove(node);
+        rule.related = related;
     }
+}
 
-    for node in graph.keys().cloned().collect::<Vec<_>>() {
-        if !visited.contains(&node) {
-            dfs(
-                &node,
-                &graph,
-                &mut visited,
-                &mut rec_stack,
-                &mut path,
-                &mut cycles,
-            );
+/// Check each configured tag's coverage minimums against the rules carrying
+/// that tag. Draft, removed and waived rules are left out, as in release
+/// checks.
+// r[impl validation.tag-threshold]
+fn tag_threshold_errors(
+    forward_data: &ApiSpecForward,
+    tags: &BTreeMap<String, TagThreshold>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    for (tag, threshold) in tags {
+        let rules: Vec<&ApiRule> = forward_data
+            .rules
+            .iter()
+            .filter(|rule| rule.tags.iter().any(|t| t == tag))
+            .filter(|rule| !matches!(rule.status.as_deref(), Some("draft" | "removed")))
+            .filter(|rule| !rule.is_waived())
+            .collect();
+        if rules.is_empty() {
+            continue;
+        }
+
+        let total = rules.len();
+        let mut breakdown = Vec::new();
+        let mut uncovered = BTreeSet::new();
+        let unimplemented = rules.iter().filter(|rule| !rule.is_implemented());
+        let unverified = rules.iter().filter(|rule| rule.verify_refs.is_empty());
+        for (kind, min, missing) in [
+            (
+                "implemented",
+                threshold.min_impl,
+                unimplemented.collect::<Vec<_>>(),
+            ),
+            ("verified", threshold.min_verify, unverified.collect()),
+        ] {
+            let Some(min) = min else { continue };
+            let count = total - missing.len();
+            // Integer comparison so 2 of 3 does not round up to a 67% minimum.
+            if count * 100 < total * min as usize {
+                breakdown.push(format!(
+                    "{count} of {total} {kind} ({}%, minimum {min}%)",
+                    count * 100 / total
+                ));
+                uncovered.extend(missing.iter().map(|rule| rule.id.clone()));
+            }
+        }
+        if breakdown.is_empty() {
+            continue;
         }
+
+        errors.push(ValidationError {
+            code: ValidationErrorCode::TagThreshold,
+            message: format!(
+                "Tag '{tag}' is below its coverage minimum: {}",
+                breakdown.join(", ")
+            ),
+            file: None,
+            line: None,
+            column: None,
+            related_rules: uncovered.into_iter().collect(),
+            reference_rule_id: None,
+            reference_text: None,
+        });
     }
+    errors
+}
+
+/// Check a spec's overall threshold and its per-prefix thresholds. Each one
+/// that is not met becomes its own error, so a failing section is named even
+/// when the spec as a whole passes. Waived rules are left out.
+// r[impl validation.spec-threshold]
+fn spec_threshold_errors(
+    forward_data: &ApiSpecForward,
+    spec_config: &SpecConfig,
+) -> Vec<ValidationError> {
+    let rules: Vec<&ApiRule> = forward_data
+        .rules
+        .iter()
+        .filter(|rule| !matches!(rule.status.as_deref(), Some("draft" | "removed")))
+        .filter(|rule| !rule.is_waived())
+        .collect();
+
+    let overall = spec_config.threshold.map(|min| (None, min));
+    let sections = spec_config
+        .thresholds
+        .iter()
+        .map(|(prefix, min)| (Some(prefix.as_str()), *min));
+
+    let mut errors = Vec::new();
+    for (prefix, min) in overall.into_iter().chain(sections) {
+        let scoped: Vec<&ApiRule> = rules
+            .iter()
+            .copied()
+            .filter(|rule| prefix.is_none_or(|p| rule.id.base.starts_with(p)))
+            .collect();
+        if scoped.is_empty() {
+            continue;
+        }
 
-    cycles
+        let total = scoped.len();
+        let missing: Vec<RuleId> = scoped
+            .iter()
+            .filter(|rule| !rule.is_implemented())
+            .map(|rule| rule.id.clone())
+            .collect();
+        let count = total - missing.len();
+        // r[impl coverage.partial]
+        let partial = scoped.iter().filter(|rule| rule.is_partial()).count();
+        let weighted: f64 = scoped.iter().map(|rule| rule.impl_weight()).sum();
+        // Without partial rules, integer comparison so 2 of 3 does not round
+        // up to a 67% threshold.
+        let met = if partial == 0 {
+            count * 100 >= total * min as usize
+        } else {
+            weighted * 100.0 >= (total * min as usize) as f64
+        };
+        if met {
+            continue;
+        }
+        let partial_note = if partial > 0 {
+            format!(", {partial} of them partially")
+        } else {
+            String::new()
+        };
+
+        let scope = match prefix {
+            Some(prefix) => format!("Rules under '{prefix}' are"),
+            None => format!("Spec '{}' is", spec_config.name),
+        };
+        errors.push(ValidationError {
+            code: ValidationErrorCode::CoverageThreshold,
+            message: format!(
+                "{scope} below the coverage threshold: {count} of {total} implemented\
+                 {partial_note} ({}%, threshold {min}%)",
+                (weighted * 100.0 / total as f64) as usize
+            ),
+            file: None,
+            line: None,
+            column: None,
+            related_rules: missing,
+            reference_rule_id: None,
+            reference_text: None,
+        });
+    }
+    errors
 }
 
 fn span_to_range(content: &str, offset: usize, length: usize) -> (u32, u32, u32, u32) {
@@ -1496,6 +2328,7 @@ enum SourceDiagnosticIssueCode {
     UnknownPrefix,
     Stale { current_rule_id: RuleId },
     UnknownRequirement,
+    EmptyWildcard,
     ImplInTestFile,
     ParseWarning,
 }
@@ -1589,16 +2422,15 @@ fn collect_source_diagnostic_issues(
             .get(reference.prefix.as_str())
             .map(Vec::as_slice)
             .unwrap_or(&[]);
-        match classify_reference_against_known_rules(&reference.req_id, known_for_prefix) {
-            KnownRuleMatch::Exact => {}
-            KnownRuleMatch::Stale(current_rule_id) => {
-                let message = stale_diagnostic_message_short(
-                    &reference.req_id,
-                    ctx.rules_by_id.get(&current_rule_id),
-                );
+        // r[impl ref.syntax.patterns]
+        if is_rule_pattern(&reference.req_id) {
+            if !known_for_prefix
+                .iter()
+                .any(|rule| rule_pattern_matches(&reference.req_id.base, &rule.base))
+            {
                 diagnostics.push(SourceDiagnosticIssue {
-                    code: SourceDiagnosticIssueCode::Stale { current_rule_id },
-                    message,
+                    code: SourceDiagnosticIssueCode::EmptyWildcard,
+                    message: format!("Wildcard '{}' matches no rules", reference.req_id),
                     line: reference.line,
                     start_line,
                     start_char,
@@ -1608,30 +2440,52 @@ fn collect_source_diagnostic_issues(
                     reference_text: None,
                 });
             }
-            KnownRuleMatch::Missing => {
-                let message = unknown_rule_message_with_context(
-                    &reference.prefix,
-                    &reference.verb,
-                    &reference.req_id,
-                    known_for_prefix,
-                );
-                diagnostics.push(SourceDiagnosticIssue {
-                    code: SourceDiagnosticIssueCode::UnknownRequirement,
-                    message,
-                    line: reference.line,
-                    start_line,
-                    start_char,
-                    end_line,
-                    end_char,
-                    reference_rule_id: Some(reference.req_id.clone()),
-                    reference_text: Some(format!(
-                        "{}[{} {}]",
-                        reference.prefix, reference.verb, reference.req_id
-                    )),
-                });
+        } else {
+            match classify_reference_against_known_rules(&reference.req_id, known_for_prefix) {
+                KnownRuleMatch::Exact => {}
+                KnownRuleMatch::Stale(current_rule_id) => {
+                    let message = stale_diagnostic_message_short(
+                        &reference.req_id,
+                        ctx.rules_by_id.get(&current_rule_id),
+                    );
+                    diagnostics.push(SourceDiagnosticIssue {
+                        code: SourceDiagnosticIssueCode::Stale { current_rule_id },
+                        message,
+                        line: reference.line,
+                        start_line,
+                        start_char,
+                        end_line,
+                        end_char,
+                        reference_rule_id: Some(reference.req_id.clone()),
+                        reference_text: None,
+                    });
+                }
+                KnownRuleMatch::Missing => {
+                    let message = unknown_rule_message_with_context(
+                        &reference.prefix,
+                        &reference.verb,
+                        &reference.req_id,
+                        known_for_prefix,
+                    );
+                    diagnostics.push(SourceDiagnosticIssue {
+                        code: SourceDiagnosticIssueCode::UnknownRequirement,
+                        message,
+                        line: reference.line,
+                        start_line,
+                        start_char,
+                        end_line,
+                        end_char,
+                        reference_rule_id: Some(reference.req_id.clone()),
+                        reference_text: Some(format!(
+                            "{}[{} {}]",
+                            reference.prefix, reference.verb, reference.req_id
+                        )),
+                    });
+                }
             }
         }
 
+        // r[impl config.impl.test_include.verify-only]
         if is_test && reference.verb == RefVerb::Impl {
             diagnostics.push(SourceDiagnosticIssue {
                 code: SourceDiagnosticIssueCode::ImplInTestFile,
@@ -1655,6 +2509,9 @@ fn collect_source_diagnostic_issues(
                 format!("Unknown verb: '{}'", verb)
             }
             tracey_core::WarningKind::MalformedReference => "Malformed reference".to_string(),
+            tracey_core::WarningKind::NonUtf8Encoding(encoding) => {
+                format!("File is not valid UTF-8; decoded as {encoding}")
+            }
         };
 
         diagnostics.push(SourceDiagnosticIssue {
@@ -1678,6 +2535,7 @@ fn source_issue_to_lsp(issue: SourceDiagnosticIssue) -> LspDiagnostic {
         SourceDiagnosticIssueCode::UnknownPrefix => ("hint", "unknown-prefix"),
         SourceDiagnosticIssueCode::Stale { .. } => ("warning", "stale"),
         SourceDiagnosticIssueCode::UnknownRequirement => ("warning", "orphaned"),
+        SourceDiagnosticIssueCode::EmptyWildcard => ("warning", "empty-wildcard"),
         SourceDiagnosticIssueCode::ImplInTestFile => ("warning", "impl-in-test"),
         SourceDiagnosticIssueCode::ParseWarning => ("warning", "parse-warning"),
     };
@@ -1703,13 +2561,20 @@ fn compute_validation_by_impl(
     file_contents: &BTreeMap<PathBuf, String>,
     test_files: &std::collections::HashSet<PathBuf>,
     include_parse_failures_by_impl: &BTreeMap<ImplKey, BTreeMap<PathBuf, String>>,
+    skipped_files_by_impl: &BTreeMap<ImplKey, BTreeMap<PathBuf, String>>,
+    ownership_conflicts_by_impl: &BTreeMap<ImplKey, BTreeMap<PathBuf, String>>,
+    code_units_by_impl: &BTreeMap<ImplKey, BTreeMap<PathBuf, Vec<CodeUnit>>>,
+    tags: &BTreeMap<String, TagThreshold>,
+    spec_configs: &[SpecConfig],
 ) -> BTreeMap<ImplKey, ValidationResult> {
     let mut out = BTreeMap::new();
+    let no_units = BTreeMap::new();
     let source_ctx = build_source_diagnostic_context(config, forward_by_impl);
 
     for (impl_key, forward_data) in forward_by_impl {
         let (spec, impl_name) = impl_key;
         let mut errors = Vec::new();
+        let mut warnings = Vec::new();
 
         let mut seen_ids: HashMap<RuleId, (&Option<String>, Option<usize>)> = HashMap::new();
         let mut seen_bases: HashMap<String, (&RuleId, &Option<String>, Option<usize>)> =
@@ -1814,6 +2679,9 @@ fn compute_validation_by_impl(
                         SourceDiagnosticIssueCode::UnknownRequirement => {
                             (ValidationErrorCode::UnknownRequirement, Vec::new())
                         }
+                        SourceDiagnosticIssueCode::EmptyWildcard => {
+                            (ValidationErrorCode::EmptyWildcard, Vec::new())
+                        }
                         SourceDiagnosticIssueCode::ImplInTestFile => (
                             ValidationErrorCode::ImplInTestFile,
                             issue
@@ -1823,7 +2691,7 @@ fn compute_validation_by_impl(
                         ),
                         SourceDiagnosticIssueCode::ParseWarning => continue,
                     };
-                    errors.push(ValidationError {
+                    let error = ValidationError {
                         code,
                         message: issue.message,
                         file: Some(file_entry.path.clone()),
@@ -1832,7 +2700,12 @@ fn compute_validation_by_impl(
                         related_rules,
                         reference_rule_id: issue.reference_rule_id,
                         reference_text: issue.reference_text,
-                    });
+                    };
+                    if code == ValidationErrorCode::EmptyWildcard {
+                        warnings.push(error);
+                    } else {
+                        errors.push(error);
+                    }
                 }
             }
         }
@@ -1868,7 +2741,9 @@ fn compute_validation_by_impl(
             }
         }
 
-        for cycle in detect_circular_dependencies(forward_data) {
+        let units = code_units_by_impl.get(impl_key).unwrap_or(&no_units);
+        let rules: Vec<&ApiRule> = forward_data.rules.iter().collect();
+        for (cycle, at) in crate::graph::depends_cycles(&rules, units) {
             errors.push(ValidationError {
                 code: ValidationErrorCode::CircularDependency,
                 message: format!(
@@ -1879,8 +2754,8 @@ fn compute_validation_by_impl(
                         .collect::<Vec<_>>()
                         .join(" -> ")
                 ),
-                file: None,
-                line: None,
+                file: Some(at.file.clone()),
+                line: Some(at.line),
                 column: None,
                 related_rules: cycle,
                 reference_rule_id: None,
@@ -1888,14 +2763,71 @@ fn compute_validation_by_impl(
             });
         }
 
+        let advisory = config
+            .specs
+            .iter()
+            .any(|info| &info.name == spec && info.advisory);
+        if !advisory {
+            errors.extend(tag_threshold_errors(forward_data, tags));
+            if let Some(spec_config) = spec_configs.iter().find(|s| &s.name == spec) {
+                errors.extend(spec_threshold_errors(forward_data, spec_config));
+            }
+        }
+
         let error_count = errors.len();
+
+        let mut warning_count = warnings.len();
+        errors.extend(warnings);
+        // r[impl config.limits.skipped]
+        for (path, reason) in skipped_files_by_impl.get(impl_key).into_iter().flatten() {
+            let rel_path = path
+                .strip_prefix(abs_root)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| compute_relative_path(abs_root, path));
+            errors.push(ValidationError {
+                code: ValidationErrorCode::SkippedFile,
+                message: format!("Skipped '{rel_path}': {reason}. Its references are not counted."),
+                file: Some(rel_path),
+                line: None,
+                column: None,
+                related_rules: Vec::new(),
+                reference_rule_id: None,
+                reference_text: None,
+            });
+            warning_count += 1;
+        }
+        // r[impl validation.ownership]
+        for (path, owner) in ownership_conflicts_by_impl
+            .get(impl_key)
+            .into_iter()
+            .flatten()
+        {
+            let rel_path = path
+                .strip_prefix(abs_root)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| compute_relative_path(abs_root, path));
+            errors.push(ValidationError {
+                code: ValidationErrorCode::OwnershipConflict,
+                message: format!(
+                    "'{rel_path}' is also included by implementation '{owner}', which owns it, so its references count for '{owner}' only. To count them for every implementation, list the file under the spec's `shared` patterns; otherwise narrow the include patterns."
+                ),
+                file: Some(rel_path),
+                line: None,
+                column: None,
+                related_rules: Vec::new(),
+                reference_rule_id: None,
+                reference_text: None,
+            });
+            warning_count += 1;
+        }
+
         out.insert(
             impl_key.clone(),
             ValidationResult {
                 spec: spec.clone(),
                 impl_name: impl_name.clone(),
                 errors,
-                warning_count: 0,
+                warning_count,
                 error_count,
             },
         );
@@ -2073,7 +3005,7 @@ async fn compute_spec_file_diagnostics(
 
         // Coverage diagnostics: parse the markdown to get requirement definitions
         let options = RenderOptions::default();
-        if let Ok(doc) = render(content, &options).await {
+        if let Ok(doc) = render(&crate::examples::strip(content), &options).await {
             for def in &doc.reqs {
                 let (start_line, start_char, end_line, end_char) =
                     span_to_range(content, def.marker_span.offset, def.marker_span.length);
@@ -2106,6 +3038,21 @@ async fn compute_spec_file_diagnostics(
                                 end_line,
                                 end_char,
                             });
+                        } else if let Some(fanout) =
+                            rule.fanout.as_ref().filter(|f| !f.is_satisfied())
+                        {
+                            diagnostics.push(LspDiagnostic {
+                                severity: "hint".to_string(),
+                                code: "fanout-shortfall".to_string(),
+                                message: format!(
+                                    "Requirement fan-out not met: {}",
+                                    fanout.summary()
+                                ),
+                                start_line,
+                                start_char,
+                                end_line,
+                                end_char,
+                            });
                         } else if verify_count == 0 {
                             diagnostics.push(LspDiagnostic {
                                 severity: "hint".to_string(),
@@ -2231,6 +3178,7 @@ async fn compute_spec_file_diagnostics(
     out
 }
 
+#[allow(clippy::too_many_arguments)]
 fn compute_impl_output(
     abs_root: &Path,
     _spec_name: &str,
@@ -2239,6 +3187,12 @@ fn compute_impl_output(
     extracted_rules: &[crate::ExtractedRule],
     refs: Vec<ReqReference>,
     impl_code_units: BTreeMap<PathBuf, Vec<CodeUnit>>,
+    fanouts: &BTreeMap<String, Fanout>,
+    verb_roles: &VerbRoles,
+    scanned_files: &[String],
+    proofs: &ProofFiles,
+    test_impls: &TestImplFiles,
+    partial_weight: f64,
 ) -> ImplComputedOutput {
     let impl_start = Instant::now();
     let forward_start = Instant::now();
@@ -2248,9 +3202,15 @@ fn compute_impl_output(
         code_ref: ApiCodeRef,
         relative_file: String,
         line: usize,
+        in_proof: bool,
+        in_test: bool,
     }
     let mut indexed_refs: Vec<IndexedRef> = Vec::new();
     let mut refs_by_base: HashMap<String, Vec<usize>> = HashMap::new();
+    let rule_ids: Vec<RuleId> = extracted_rules
+        .iter()
+        .filter_map(|extracted| parse_rule_id(&extracted.def.id.to_string()))
+        .collect();
     for r in &refs {
         if r.prefix != inferred_prefix {
             continue;
@@ -2261,23 +3221,62 @@ fn compute_impl_output(
         } else {
             compute_relative_path(abs_root, &canonical_ref)
         };
-        let idx = indexed_refs.len();
-        indexed_refs.push(IndexedRef {
-            verb: r.verb,
-            req_id: r.req_id.clone(),
-            code_ref: ApiCodeRef {
-                file: relative_display.clone(),
+        // r[impl ref.syntax.patterns]
+        // A wildcard stands for every rule it matches in the manifest, at
+        // that rule's current version.
+        let targets: Vec<RuleId> = if is_rule_pattern(&r.req_id) {
+            rule_ids
+                .iter()
+                .filter(|id| rule_pattern_matches(&r.req_id.base, &id.base))
+                .cloned()
+                .collect()
+        } else {
+            vec![r.req_id.clone()]
+        };
+        for req_id in targets {
+            let idx = indexed_refs.len();
+            refs_by_base
+                .entry(req_id.base.clone())
+                .or_default()
+                .push(idx);
+            indexed_refs.push(IndexedRef {
+                verb: r.verb.clone(),
+                req_id,
+                code_ref: ApiCodeRef {
+                    file: relative_display.clone(),
+                    line: r.line,
+                    end_line: r.end_line,
+                    note: r.note.clone(),
+                    // r[impl ref.coverage.partial]
+                    partial: r.partial,
+                },
+                in_proof: proofs.includes(&relative_display),
+                in_test: test_impls.includes(&relative_display),
+                relative_file: relative_display.clone(),
                 line: r.line,
-            },
-            relative_file: relative_display,
-            line: r.line,
-        });
-        refs_by_base
-            .entry(r.req_id.base.clone())
-            .or_default()
-            .push(idx);
+            });
+        }
     }
 
+    // Rules referenced by proof harnesses a tool has checked.
+    let proven_ids: Vec<&RuleId> = impl_code_units
+        .iter()
+        .filter(|(path, _)| {
+            let relative = path
+                .strip_prefix(abs_root)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| compute_relative_path(abs_root, path));
+            proofs.includes(&relative)
+        })
+        .flat_map(|(_, units)| units)
+        .filter(|unit| {
+            unit.name
+                .as_ref()
+                .is_some_and(|name| proofs.proven.contains(name))
+        })
+        .flat_map(|unit| &unit.req_refs)
+        .collect();
+
     let mut api_rules = Vec::new();
     for extracted in extracted_rules {
         let Some(rule_id) = parse_rule_id(&extracted.def.id.to_string()) else {
@@ -2286,45 +3285,69 @@ fn compute_impl_output(
         let mut impl_refs = Vec::new();
         let mut verify_refs = Vec::new();
         let mut depends_refs = Vec::new();
+        let mut docs_refs = Vec::new();
+        let mut proof_refs = Vec::new();
         let mut stale_refs = Vec::new();
 
         let candidate_idxs = refs_by_base
             .get(&rule_id.base)
             .map(Vec::as_slice)
             .unwrap_or(&[]);
+        // r[impl config.verbs.roles]
         for idx in candidate_idxs {
             let entry = &indexed_refs[*idx];
+            let role = match verb_roles.role(&entry.verb) {
+                // r[impl coverage.test-impls]
+                VerbRole::Impl if entry.in_test => VerbRole::Verify,
+                role => role,
+            };
             match classify_reference_for_rule(&rule_id, &entry.req_id) {
-                RuleIdMatch::Exact => match entry.verb {
-                    RefVerb::Impl | RefVerb::Define => impl_refs.push(entry.code_ref.clone()),
-                    RefVerb::Verify => verify_refs.push(entry.code_ref.clone()),
-                    RefVerb::Depends | RefVerb::Related => {
-                        depends_refs.push(entry.code_ref.clone())
-                    }
-                },
-                RuleIdMatch::Stale => match entry.verb {
-                    RefVerb::Impl | RefVerb::Define => {
-                        impl_refs.push(entry.code_ref.clone());
-                        stale_refs.push(ApiStaleRef {
-                            file: entry.relative_file.clone(),
-                            line: entry.line,
-                            reference_id: entry.req_id.clone(),
-                        });
-                    }
-                    RefVerb::Verify => {
+                RuleIdMatch::Exact => match role {
+                    VerbRole::Impl => impl_refs.push(entry.code_ref.clone()),
+                    // r[impl coverage.proof]
+                    VerbRole::Verify => {
+                        if entry.in_proof {
+                            proof_refs.push(entry.code_ref.clone());
+                        }
                         verify_refs.push(entry.code_ref.clone());
-                        stale_refs.push(ApiStaleRef {
-                            file: entry.relative_file.clone(),
-                            line: entry.line,
-                            reference_id: entry.req_id.clone(),
-                        });
                     }
-                    RefVerb::Depends | RefVerb::Related => {}
+                    VerbRole::Depends => depends_refs.push(entry.code_ref.clone()),
+                    VerbRole::Docs => docs_refs.push(entry.code_ref.clone()),
+                    VerbRole::Informational => {}
                 },
+                RuleIdMatch::Stale => {
+                    let bucket = match role {
+                        VerbRole::Impl => &mut impl_refs,
+                        VerbRole::Verify => &mut verify_refs,
+                        VerbRole::Docs => &mut docs_refs,
+                        VerbRole::Depends | VerbRole::Informational => continue,
+                    };
+                    bucket.push(entry.code_ref.clone());
+                    stale_refs.push(ApiStaleRef {
+                        file: entry.relative_file.clone(),
+                        line: entry.line,
+                        reference_id: entry.req_id.clone(),
+                    });
+                }
                 RuleIdMatch::NoMatch => {}
             }
         }
 
+        let fanout = fanouts
+            .get(&rule_id.base)
+            .map(|fanout| compute_fanout(fanout, &impl_refs, scanned_files));
+        // r[impl ref.coverage.partial]
+        // r[impl coverage.partial]
+        let partial = (!impl_refs.is_empty() && impl_refs.iter().all(|r| r.partial))
+            .then_some(partial_weight);
+        let formally_verified = !proof_refs.is_empty()
+            && proven_ids.iter().any(|id| {
+                matches!(
+                    classify_reference_for_rule(&rule_id, id),
+                    RuleIdMatch::Exact
+                ) || (is_rule_pattern(id) && rule_pattern_matches(&id.base, &rule_id.base))
+            });
+
         api_rules.push(ApiRule {
             id: rule_id,
             raw: extracted.def.raw.clone(),
@@ -2335,24 +3358,44 @@ fn compute_impl_output(
                 .status
                 .map(|s| s.as_str().to_string()),
             level: extracted.def.metadata.level.map(|l| l.as_str().to_string()),
+            tags: extracted.def.metadata.tags.clone(),
+            owner: extracted.owner.clone(),
             source_file: Some(extracted.source_file.clone()),
             source_line: Some(extracted.def.line),
             source_column: extracted.column,
             section: extracted.section.clone(),
             section_title: extracted.section_title.clone(),
+            section_path: extracted.section_path.clone(),
             impl_refs,
             verify_refs,
             depends_refs,
+            docs_refs,
+            proof_refs,
+            formally_verified,
             is_stale: !stale_refs.is_empty(),
             stale_refs,
+            related: Vec::new(),
+            fanout,
+            examples: extracted
+                .examples
+                .iter()
+                .map(|e| ApiRuleExample {
+                    kind: e.kind().to_string(),
+                    markdown: e.markdown.clone(),
+                })
+                .collect(),
+            waiver: None,
+            partial,
         });
     }
     api_rules.sort_by(|a, b| a.id.cmp(&b.id));
+    link_prose_mentions(&mut api_rules);
     let all_search_rules = api_rules
         .iter()
         .map(|r| search::RuleEntry {
             id: r.id.to_string(),
             raw: r.raw.clone(),
+            section: r.section_label(),
         })
         .collect::<Vec<_>>();
     let forward_elapsed_ms = forward_start.elapsed().as_millis();
@@ -2400,6 +3443,37 @@ fn compute_impl_output(
     }
 }
 
+/// Measure a rule's impl references against its fan-out requirement.
+// r[impl coverage.fanout]
+fn compute_fanout(
+    fanout: &Fanout,
+    impl_refs: &[ApiCodeRef],
+    scanned_files: &[String],
+) -> ApiFanout {
+    match fanout {
+        Fanout::Count(required) => ApiFanout {
+            required: *required,
+            annotated: impl_refs.len().min(*required),
+            per_file: None,
+            missing_files: Vec::new(),
+        },
+        Fanout::PerFile(glob) => {
+            let annotated_files: BTreeSet<&str> =
+                impl_refs.iter().map(|r| r.file.as_str()).collect();
+            let (annotated, missing_files): (Vec<&String>, Vec<&String>) = scanned_files
+                .iter()
+                .filter(|file| glob.is_match(file.as_str()))
+                .partition(|file| annotated_files.contains(file.as_str()));
+            ApiFanout {
+                required: annotated.len() + missing_files.len(),
+                annotated: annotated.len(),
+                per_file: Some(glob.glob().to_string()),
+                missing_files: missing_files.into_iter().cloned().collect(),
+            }
+        }
+    }
+}
+
 pub async fn build_dashboard_data(
     project_root: &Path,
     config: &Config,
@@ -2422,6 +3496,7 @@ pub async fn build_dashboard_data(
         &FileOverlay::new(),
         &mut cache,
         &[],
+        None,
     )
     .await
 }
@@ -2449,10 +3524,16 @@ pub async fn build_dashboard_data_with_overlay(
         overlay,
         &mut cache,
         &[],
+        None,
     )
     .await
 }
 
+/// Build dashboard data, reusing the per-file parse cache.
+///
+/// With `reuse`, implementations outside the scope are not scanned or
+/// recomputed: their coverage, file contents and references are carried over
+/// from the previous build.
 #[allow(clippy::too_many_arguments)]
 pub async fn build_dashboard_data_with_overlay_and_cache(
     project_root: &Path,
@@ -2463,6 +3544,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     overlay: &FileOverlay,
     cache: &mut BuildCache,
     changed_files: &[PathBuf],
+    reuse: Option<(&DashboardData, &RebuildScope)>,
 ) -> Result<DashboardData> {
     let build_start = Instant::now();
     let abs_root = project_root
@@ -2473,6 +3555,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     let mut api_config = ApiConfig {
         project_root: abs_root.display().to_string(),
         specs: Vec::new(),
+        verbs: Vec::new(),
     };
 
     let mut forward_by_impl: BTreeMap<ImplKey, ApiSpecForward> = BTreeMap::new();
@@ -2481,6 +3564,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         BTreeMap::new();
     let specs_content_by_impl: BTreeMap<ImplKey, ApiSpecData> = BTreeMap::new();
     let mut spec_includes_by_name: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut spec_sandboxes: BTreeMap<String, Sandbox> = BTreeMap::new();
     let mut all_file_contents: BTreeMap<PathBuf, String> = BTreeMap::new();
     let mut all_spec_file_contents: BTreeMap<PathBuf, String> = BTreeMap::new();
     let mut all_source_reqs_by_file: BTreeMap<PathBuf, Reqs> = BTreeMap::new();
@@ -2492,8 +3576,45 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     let mut include_parse_failures: BTreeMap<PathBuf, String> = BTreeMap::new();
     let mut include_parse_failures_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, String>> =
         BTreeMap::new();
+    let mut skipped_files_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, String>> = BTreeMap::new();
+    let mut ownership_conflicts_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, String>> =
+        BTreeMap::new();
+    let mut limits = ExtractionLimits::new(config.limits.as_ref(), build_start);
     let total_impls: usize = config.specs.iter().map(|s| s.impls.len()).sum();
 
+    crate::config::validate(config)?;
+
+    // r[impl config.verbs.custom]
+    // Files parsed before a custom verb was declared (or after it was
+    // removed) hold the wrong references, so the source cache is dropped.
+    let verb_roles = Arc::new(crate::config::verb_roles(config));
+    let proven: Arc<HashSet<String>> = Arc::new(
+        crate::proofs::load(project_root)
+            .map(|results| results.proven())
+            .unwrap_or_default(),
+    );
+    let custom_verbs = verb_roles.custom_verbs();
+    if cache.custom_verbs != custom_verbs {
+        cache.source_files.clear();
+        cache.custom_verbs = custom_verbs;
+    }
+    // r[impl config.comments]
+    let comments = crate::config::comment_delimiters(config);
+    if cache.comments != comments {
+        cache.source_files.clear();
+        cache.comments = comments;
+    }
+    // r[impl cache.remote]
+    cache.remote = config.remote_cache.clone().map(RemoteCache::new);
+    api_config.verbs = verb_roles
+        .iter()
+        .into_iter()
+        .map(|(name, role)| ApiVerb {
+            name: name.to_string(),
+            role: role.as_str().to_string(),
+        })
+        .collect();
+
     info!(
         "dashboard build start version={} specs={} impls={} overlay_files={}",
         version,
@@ -2547,40 +3668,13 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         test_files_start.elapsed().as_millis()
     );
 
+    let owners = crate::owners::OwnersFile::load(project_root, config)?;
+
     for spec_config in &config.specs {
         let spec_start = Instant::now();
         let spec_name = &spec_config.name;
         let include_patterns: Vec<String> = spec_config.include.to_vec();
 
-        if let Some(prefix) = &spec_config.prefix {
-            // r[impl config.spec.prefix+2]
-            return Err(eyre::eyre!(
-                "Spec '{}' uses deprecated `prefix {}` in config.\n\n\
-                 Remove the `prefix` field from this spec config. Tracey now infers prefixes \
-                 directly from requirement markers in spec files (for example `r[...]`).",
-                spec_name,
-                prefix
-            ));
-        }
-
-        // Validate that spec has at least one implementation
-        if spec_config.impls.is_empty() {
-            return Err(eyre::eyre!(
-                "Spec '{}' has no implementations defined.\n\n\
-                Add at least one impl block to your config:\n\n\
-                spec {{\n    \
-                    name \"{}\"\n    \
-                    include \"docs/spec/**/*.md\"\n\n    \
-                    impl {{\n        \
-                        name \"main\"\n        \
-                        include \"src/**/*.rs\"\n    \
-                    }}\n\
-                }}",
-                spec_name,
-                spec_name
-            ));
-        }
-
         // Extract requirements directly from markdown files (shared across impls)
         if !quiet {
             eprintln!(
@@ -2589,7 +3683,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 include_patterns
             );
         }
-        let (extracted_rules, spec_file_paths, spec_walk_full_scan) =
+        let sandbox = spec_config.sandbox.as_ref().map(Sandbox::from_config);
+        let (mut extracted_rules, spec_file_paths, spec_walk_full_scan) =
             load_rules_from_includes_cached(
                 project_root,
                 &include_patterns,
@@ -2598,8 +3693,14 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 quiet,
                 changed_files,
                 &mut cache_stats,
+                sandbox.as_ref(),
             )
             .await?;
+        if let Some(sandbox) = sandbox {
+            sandbox.check_rule_count(spec_name, extracted_rules.len())?;
+            spec_sandboxes.insert(spec_name.clone(), sandbox);
+        }
+        owners.assign(&mut extracted_rules);
         total_extracted_rules += extracted_rules.len();
 
         // Collect spec file contents for workspace diagnostics
@@ -2647,8 +3748,23 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             source: Some(include_patterns.join(", ")),
             source_url: spec_config.source_url.clone(),
             implementations: spec_config.impls.iter().map(|i| i.name.clone()).collect(),
+            advisory: crate::config::enforcement(spec_config).is_advisory(),
         });
         spec_includes_by_name.insert(spec_name.clone(), include_patterns.clone());
+        // Invalid config entries were already reported when the config was
+        // validated. A `fanout=` on the rule's marker wins over the config.
+        let mut fanouts: BTreeMap<String, Fanout> = spec_config
+            .fanout
+            .iter()
+            .filter_map(|(rule, value)| Some((rule.clone(), value.parse().ok()?)))
+            .collect();
+        for rule in &extracted_rules {
+            if let Some(fanout) = &rule.fanout {
+                fanouts.insert(rule.def.id.base.clone(), fanout.clone());
+            }
+        }
+        let fanouts = Arc::new(fanouts);
+        let partial_weight = crate::config::partial_weight(spec_config);
 
         // Build data for each implementation
         struct ImplComputeTaskMeta {
@@ -2660,20 +3776,84 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         }
         let mut impl_compute_tasks = Vec::new();
         let mut impl_compute_meta = Vec::new();
+        let mut ownership = FileOwnership::new(project_root, spec_config);
 
         for impl_config in &spec_config.impls {
             let scan_start = Instant::now();
             let impl_name = impl_config.name.clone();
-            if !quiet {
-                eprintln!("   {} {} implementation", "Scanning".green(), impl_name);
-            }
-            let include: Vec<String> = if impl_config.include.is_empty() {
+            let mut include: Vec<String> = if impl_config.include.is_empty() {
                 vec!["**/*.rs".to_string()]
             } else {
                 impl_config.include.to_vec()
             };
+            // Proof files are scanned with the implementation's sources.
+            include.extend(impl_config.proof_include.iter().cloned());
             let exclude: Vec<String> = impl_config.exclude.to_vec();
             let impl_key: ImplKey = (spec_name.clone(), impl_name.clone());
+            let proof_files = ProofFiles::new(&impl_config.proof_include, proven.clone());
+            let test_impl_files = TestImplFiles::new(
+                crate::config::test_impls(spec_config),
+                &impl_config.test_include,
+            );
+            let strings = crate::config::string_references(spec_config);
+
+            // r[impl daemon.reload.scope]
+            if let Some((previous, scope)) = reuse
+                && !scope.includes(&impl_key)
+                && let Some(forward) = previous.forward_by_impl.get(&impl_key)
+                && let Some(reverse) = previous.reverse_by_impl.get(&impl_key)
+                && let Some(files) = cached_impl_files(
+                    project_root,
+                    &include,
+                    &impl_config.test_include,
+                    &exclude,
+                    cache,
+                )
+            {
+                for path in files.iter().chain(overlay.keys()) {
+                    if let Some(content) = previous.search_files.get(path) {
+                        all_file_contents.insert(path.clone(), content.clone());
+                    }
+                    if let Some(reqs) = previous.source_reqs_by_file.get(path) {
+                        all_source_reqs_by_file
+                            .entry(path.clone())
+                            .or_insert_with(|| reqs.clone());
+                    }
+                }
+                if let Some(failures) = previous.parse_failures_by_impl.get(&impl_key) {
+                    for (path, reason) in failures {
+                        include_parse_failures
+                            .entry(path.clone())
+                            .or_insert_with(|| reason.clone());
+                    }
+                    include_parse_failures_by_impl.insert(impl_key.clone(), failures.clone());
+                }
+                if let Some(skipped) = previous.skipped_files_by_impl.get(&impl_key) {
+                    skipped_files_by_impl.insert(impl_key.clone(), skipped.clone());
+                }
+                if let Some(conflicts) = previous.ownership_conflicts_by_impl.get(&impl_key) {
+                    ownership_conflicts_by_impl.insert(impl_key.clone(), conflicts.clone());
+                }
+                all_search_rules.extend(forward.rules.iter().map(|r| search::RuleEntry {
+                    id: r.id.to_string(),
+                    raw: r.raw.clone(),
+                    section: r.section_label(),
+                }));
+                if let Some(units) = previous.code_units_by_impl.get(&impl_key) {
+                    code_units_by_impl.insert(impl_key.clone(), units.clone());
+                }
+                forward_by_impl.insert(impl_key.clone(), forward.clone());
+                reverse_by_impl.insert(impl_key, reverse.clone());
+                info!(
+                    "dashboard build impl reused spec={} impl={}",
+                    spec_name, impl_name
+                );
+                continue;
+            }
+
+            if !quiet {
+                eprintln!("   {} {} implementation", "Scanning".green(), impl_name);
+            }
             let (
                 mut refs,
                 mut parse_warnings,
@@ -2691,6 +3871,10 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 cache,
                 changed_files,
                 &mut cache_stats,
+                &mut limits,
+                &mut ownership,
+                &impl_name,
+                strings,
             )
             .await;
             for (path, reason) in parse_failures {
@@ -2724,6 +3908,10 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                     cache,
                     changed_files,
                     &mut cache_stats,
+                    &mut limits,
+                    &mut ownership,
+                    &impl_name,
+                    strings,
                 )
                 .await;
                 refs.extend(test_refs);
@@ -2751,6 +3939,34 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 impl_walk_full_scan = impl_walk_full_scan || test_walk_full_scan;
             }
 
+            // r[impl config.limits.skipped]
+            let skipped = limits.take_skipped();
+            if !skipped.is_empty() {
+                if !quiet {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "Skipped {} {} file(s) that exceeded extraction limits",
+                            skipped.len(),
+                            impl_name
+                        )
+                        .yellow()
+                    );
+                }
+                skipped_files_by_impl
+                    .entry(impl_key.clone())
+                    .or_default()
+                    .extend(skipped);
+            }
+
+            let conflicts = ownership.take_conflicts();
+            if !conflicts.is_empty() {
+                ownership_conflicts_by_impl
+                    .entry(impl_key.clone())
+                    .or_default()
+                    .extend(conflicts);
+            }
+
             let warning_count = scan_warnings.len();
             let scan_elapsed_ms = scan_start.elapsed().as_millis();
 
@@ -2760,6 +3976,16 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 }
             }
             total_source_refs += refs.len();
+            let scanned_files: Vec<String> = impl_file_contents
+                .keys()
+                .map(|path| {
+                    let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                    match canonical.strip_prefix(&abs_root) {
+                        Ok(rel) => rel.display().to_string(),
+                        Err(_) => compute_relative_path(&abs_root, &canonical),
+                    }
+                })
+                .collect();
             for (path, content) in impl_file_contents {
                 all_file_contents.insert(path, content);
             }
@@ -2780,6 +4006,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             let inferred_prefix_cloned = inferred_prefix.clone();
             let extracted_rules_cloned = extracted_rules.clone();
             let impl_name_cloned = impl_name.clone();
+            let fanouts_cloned = fanouts.clone();
+            let verb_roles_cloned = verb_roles.clone();
             impl_compute_tasks.push(tokio::task::spawn_blocking(move || {
                 compute_impl_output(
                     &abs_root_cloned,
@@ -2789,6 +4017,12 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                     &extracted_rules_cloned,
                     refs,
                     impl_code_units,
+                    &fanouts_cloned,
+                    &verb_roles_cloned,
+                    &scanned_files,
+                    &proof_files,
+                    &test_impl_files,
+                    partial_weight,
                 )
             }));
             impl_compute_meta.push(ImplComputeTaskMeta {
@@ -2864,6 +4098,21 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         all_search_rules.len()
     );
 
+    // r[impl coverage.waivers]
+    // Applied to every implementation, including ones a scoped rebuild
+    // carried over, so an edited waivers file takes effect everywhere.
+    if !forward_by_impl.is_empty() {
+        let waivers = crate::waivers::load(project_root)?;
+        let today = crate::waivers::today();
+        for rule in forward_by_impl.values_mut().flat_map(|f| &mut f.rules) {
+            rule.waiver = waivers.get(&rule.id.base).map(|w| ApiWaiver {
+                reason: w.reason.clone(),
+                expires: w.expires.clone(),
+                expired: w.is_expired(today),
+            });
+        }
+    }
+
     // Compute content hash for change detection (hash all forward/reverse data)
     let mut content_hash: u64 = 0;
     for (key, forward) in &forward_by_impl {
@@ -2885,6 +4134,11 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         &all_file_contents,
         &test_files,
         &include_parse_failures_by_impl,
+        &skipped_files_by_impl,
+        &ownership_conflicts_by_impl,
+        &code_units_by_impl,
+        &config.tags,
+        &config.specs,
     );
     let workspace_diagnostics = compute_workspace_diagnostics(
         &abs_root,
@@ -2901,7 +4155,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
 
     let elapsed = build_start.elapsed();
     info!(
-        "dashboard build done version={} specs={} impls={} rules={} refs={} code_files={} code_units={} cache_metadata_hits={} cache_hash_hits={} cache_misses={} reparsed_files={} cache_entries={} elapsed_ms={}",
+        "dashboard build done version={} specs={} impls={} rules={} refs={} code_files={} code_units={} cache_unchanged_hits={} cache_metadata_hits={} cache_hash_hits={} cache_remote_hits={} cache_misses={} reparsed_files={} cache_entries={} elapsed_ms={}",
         version,
         api_config.specs.len(),
         forward_by_impl.len(),
@@ -2909,8 +4163,10 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         total_source_refs,
         total_code_files,
         total_code_units,
+        cache_stats.unchanged_hits,
         cache_stats.metadata_hits,
         cache_stats.hash_hits,
+        cache_stats.remote_hits,
         cache_stats.misses,
         cache_stats.reparsed,
         cache.source_files.len(),
@@ -2924,6 +4180,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         code_units_by_impl,
         specs_content_by_impl,
         spec_includes_by_name,
+        spec_sandboxes,
         search_files: all_file_contents,
         source_reqs_by_file: all_source_reqs_by_file,
         search_rules: all_search_rules,
@@ -2933,6 +4190,9 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         content_hash,
         delta: crate::server::Delta::default(),
         test_files,
+        parse_failures_by_impl: include_parse_failures_by_impl,
+        skipped_files_by_impl,
+        ownership_conflicts_by_impl,
     })
 }
 
@@ -2946,6 +4206,7 @@ fn simple_hash(s: &str) -> u64 {
     hash
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn load_spec_content(
     root: &Path,
     patterns: &[&str],
@@ -2954,6 +4215,7 @@ async fn load_spec_content(
     coverage: &BTreeMap<String, RuleCoverage>,
     specs_content: &mut BTreeMap<String, ApiSpecData>,
     overlay: &FileOverlay,
+    sandbox: Option<&Sandbox>,
 ) -> Result<()> {
     use ignore::WalkBuilder;
 
@@ -3012,6 +4274,9 @@ async fn load_spec_content(
         }
 
         if let Ok(content) = read_file_with_overlay(path, overlay).await {
+            if let Some(sandbox) = sandbox {
+                sandbox.check_file_size(&relative.to_string_lossy(), content.len() as u64)?;
+            }
             // Parse frontmatter to get weight
             let weight = match parse_frontmatter(&content) {
                 Ok((fm, _)) => fm.weight,
@@ -3045,7 +4310,12 @@ async fn load_spec_content(
     *current_source_file.lock().unwrap() = first_source_file.clone();
     let absolute_source_path = root.join(&first_source_file).display().to_string();
     let opts = opts.with_source_path(&absolute_source_path);
-    let doc = render(&combined_markdown, &opts).await?;
+    let mut doc = render(&crate::examples::strip(&combined_markdown), &opts).await?;
+    if let Some(sandbox) = sandbox {
+        // Head injections are scripts and styles for diagram renderers.
+        doc.html = sandbox.sanitize_html(&doc.html);
+        doc.head_injections.clear();
+    }
 
     // Create a single section with all content
     // (Frontend concatenates sections anyway, this just simplifies tracking)
@@ -3085,11 +4355,12 @@ pub async fn render_spec_content_for_impl(
     spec_name: &str,
     impl_name: &str,
     forward: &ApiSpecForward,
+    sandbox: Option<&Sandbox>,
 ) -> Result<ApiSpecData> {
     let mut coverage: BTreeMap<String, RuleCoverage> = BTreeMap::new();
     for rule in &forward.rules {
         let rule_id_string = rule.id.to_string();
-        let has_impl = !rule.impl_refs.is_empty();
+        let has_impl = rule.is_implemented();
         let has_verify = !rule.verify_refs.is_empty();
         let has_stale = rule.is_stale;
         let status = if has_stale {
@@ -3107,6 +4378,10 @@ pub async fn render_spec_content_for_impl(
                 status,
                 impl_refs: rule.impl_refs.clone(),
                 verify_refs: rule.verify_refs.clone(),
+                implemented: has_impl,
+                documented: !rule.docs_refs.is_empty(),
+                related: rule.related.clone(),
+                examples: rule.examples.clone(),
             },
         );
     }
@@ -3121,6 +4396,7 @@ pub async fn render_spec_content_for_impl(
         &coverage,
         &mut map,
         &FileOverlay::new(),
+        sandbox,
     )
     .await?;
     map.remove(spec_name)
@@ -3154,8 +4430,9 @@ fn build_outline(
             DocElement::Req(r) => {
                 if let Some(idx) = current_heading_idx {
                     let cov = coverage.get(&r.id.to_string());
-                    let has_impl = cov.is_some_and(|c| !c.impl_refs.is_empty());
+                    let has_impl = cov.is_some_and(|c| c.implemented);
                     let has_verify = cov.is_some_and(|c| !c.verify_refs.is_empty());
+                    let has_docs = cov.is_some_and(|c| c.documented);
 
                     entries[idx].coverage.total += 1;
                     if has_impl {
@@ -3164,6 +4441,9 @@ fn build_outline(
                     if has_verify {
                         entries[idx].coverage.verify_count += 1;
                     }
+                    if has_docs {
+                        entries[idx].coverage.docs_count += 1;
+                    }
                 }
             }
             DocElement::Paragraph(_) => {
@@ -3197,6 +4477,7 @@ fn build_outline(
                 entries[i].aggregated.total += child_agg.total;
                 entries[i].aggregated.impl_count += child_agg.impl_count;
                 entries[i].aggregated.verify_count += child_agg.verify_count;
+                entries[i].aggregated.docs_count += child_agg.docs_count;
             }
             j += 1;
         }
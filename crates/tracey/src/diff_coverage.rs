@@ -0,0 +1,511 @@
+//! `tracey diff-coverage` implementation.
+//!
+//! Computes coverage at two git revisions and reports the rules whose
+//! coverage or verification changed between them. Each revision's files are
+//! read with `git show` into a temporary directory, so the working tree and
+//! the index are never touched; only the config, the specs and the files an
+//! implementation's patterns select are written out.
+
+use eyre::{Result, WrapErr, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use tracey_api::ApiRule;
+
+use crate::bump::git_capture;
+use crate::config::Config;
+
+/// How thoroughly a rule is verified, from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verification {
+    Unverified,
+    /// Has a `verify` reference.
+    Tested,
+    /// Has a `verify` reference in a proof file.
+    Proven,
+    /// Has a proof a verification tool checked.
+    Checked,
+}
+
+impl Verification {
+    fn label(self) -> &'static str {
+        match self {
+            Verification::Unverified => "unverified",
+            Verification::Tested => "tested",
+            Verification::Proven => "proven",
+            Verification::Checked => "checked",
+        }
+    }
+}
+
+/// Coverage of one rule in one implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleStatus {
+    pub implemented: bool,
+    pub verification: Verification,
+}
+
+impl RuleStatus {
+    fn of(rule: &ApiRule) -> Self {
+        let verification = if rule.formally_verified {
+            Verification::Checked
+        } else if !rule.proof_refs.is_empty() {
+            Verification::Proven
+        } else if !rule.verify_refs.is_empty() {
+            Verification::Tested
+        } else {
+            Verification::Unverified
+        };
+        RuleStatus {
+            implemented: rule.is_implemented(),
+            verification,
+        }
+    }
+}
+
+/// A rule whose coverage differs between the two revisions. `None` means
+/// the rule does not exist at that revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleChange {
+    pub spec: String,
+    pub impl_name: String,
+    /// Rule ID without its version, so bumping a rule is not a change.
+    pub id: String,
+    pub before: Option<RuleStatus>,
+    pub after: Option<RuleStatus>,
+}
+
+impl RuleChange {
+    fn implemented(status: Option<RuleStatus>) -> bool {
+        status.is_some_and(|s| s.implemented)
+    }
+
+    fn verification(status: Option<RuleStatus>) -> Verification {
+        status.map_or(Verification::Unverified, |s| s.verification)
+    }
+
+    pub fn gained_coverage(&self) -> bool {
+        !Self::implemented(self.before) && Self::implemented(self.after)
+    }
+
+    pub fn lost_coverage(&self) -> bool {
+        Self::implemented(self.before) && !Self::implemented(self.after)
+    }
+
+    pub fn verification_changed(&self) -> bool {
+        Self::verification(self.before) != Self::verification(self.after)
+    }
+
+    fn describe(&self) -> String {
+        let mut line = format!("`{}` ({}/{})", self.id, self.spec, self.impl_name);
+        match (self.before, self.after) {
+            (None, Some(_)) => line.push_str(", new rule"),
+            (Some(_), None) => line.push_str(", rule removed"),
+            _ => {}
+        }
+        line
+    }
+}
+
+/// Rule counts of one implementation at one revision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub rules: usize,
+    pub implemented: usize,
+    pub verified: usize,
+}
+
+/// Coverage changes between two revisions.
+#[derive(Debug)]
+pub struct CoverageDiff {
+    pub from: String,
+    pub to: String,
+    /// Totals before and after, by `(spec, impl)`.
+    pub totals: BTreeMap<(String, String), (Option<Totals>, Option<Totals>)>,
+    pub changes: Vec<RuleChange>,
+}
+
+impl CoverageDiff {
+    /// Render the diff as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Coverage from `{}` to `{}`", self.from, self.to);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Implementation | Implemented | Verified |");
+        let _ = writeln!(out, "|----------------|-------------|----------|");
+        let fraction = |totals: Option<Totals>, n: fn(&Totals) -> usize| {
+            totals.map_or("-".to_string(), |t| format!("{}/{}", n(&t), t.rules))
+        };
+        for ((spec, impl_name), (before, after)) in &self.totals {
+            let _ = writeln!(
+                out,
+                "| {spec}/{impl_name} | {} → {} | {} → {} |",
+                fraction(*before, |t| t.implemented),
+                fraction(*after, |t| t.implemented),
+                fraction(*before, |t| t.verified),
+                fraction(*after, |t| t.verified),
+            );
+        }
+
+        let sections = [
+            (
+                "Gained coverage",
+                RuleChange::gained_coverage as fn(&RuleChange) -> bool,
+            ),
+            ("Lost coverage", RuleChange::lost_coverage),
+        ];
+        for (title, filter) in sections {
+            let changes: Vec<&RuleChange> = self.changes.iter().filter(|c| filter(c)).collect();
+            if changes.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {title}");
+            let _ = writeln!(out);
+            for change in changes {
+                let _ = writeln!(out, "- {}", change.describe());
+            }
+        }
+
+        let verification: Vec<&RuleChange> = self
+            .changes
+            .iter()
+            .filter(|c| c.verification_changed())
+            .collect();
+        if !verification.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## Verification changed");
+            let _ = writeln!(out);
+            for change in verification {
+                let _ = writeln!(
+                    out,
+                    "- {}: {} → {}",
+                    change.describe(),
+                    RuleChange::verification(change.before).label(),
+                    RuleChange::verification(change.after).label()
+                );
+            }
+        }
+
+        if self.changes.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "No rule changed coverage.");
+        }
+        out
+    }
+}
+
+/// A revision's files written out to a temporary directory, removed on drop.
+struct Checkout {
+    dir: PathBuf,
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Files the scan of a config may read: its specs, its implementations'
+/// sources, tests and proofs, and `.config/tracey`.
+struct Selection {
+    /// Patterns without `*`, which are exact files or directories, as in
+    /// the scan.
+    exact: Vec<String>,
+    globs: globset::GlobSet,
+}
+
+impl Selection {
+    fn new(config: &Config) -> Self {
+        let mut patterns = vec![".config/tracey".to_string()];
+        for spec in &config.specs {
+            patterns.extend(spec.include.iter().cloned());
+            for impl_config in &spec.impls {
+                if impl_config.include.is_empty() {
+                    patterns.push("**/*.rs".to_string());
+                }
+                patterns.extend(impl_config.include.iter().cloned());
+                patterns.extend(impl_config.test_include.iter().cloned());
+                patterns.extend(impl_config.proof_include.iter().cloned());
+            }
+        }
+        let mut exact = Vec::new();
+        let mut globs = globset::GlobSetBuilder::new();
+        for pattern in patterns {
+            if !pattern.contains('*') {
+                exact.push(pattern.trim_end_matches('/').to_string());
+            } else if let Ok(glob) = globset::Glob::new(&pattern) {
+                globs.add(glob);
+            }
+        }
+        Selection {
+            exact,
+            globs: globs.build().unwrap_or_else(|_| globset::GlobSet::empty()),
+        }
+    }
+
+    fn selects(&self, file: &str) -> bool {
+        self.globs.is_match(file)
+            || self.exact.iter().any(|pattern| {
+                file == pattern
+                    || file
+                        .strip_prefix(pattern.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+    }
+}
+
+/// Contents of `path` at `commit`, relative to the project root.
+fn show(project_root: &Path, commit: &str, path: &str) -> Result<Vec<u8>> {
+    let out = std::process::Command::new("git")
+        .args(["show", &format!("{commit}:./{path}")])
+        .current_dir(project_root)
+        .output()
+        .wrap_err("failed to run git show")?;
+    if !out.status.success() {
+        bail!(
+            "git show {commit}:{path} failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(out.stdout)
+}
+
+/// Write the files of `revision` that the scan needs to a temporary
+/// directory, and load the config as of that revision (the current one when
+/// the revision has none).
+fn checkout(
+    project_root: &Path,
+    config_path: &Path,
+    config: &Config,
+    revision: &str,
+) -> Result<(Checkout, Config)> {
+    let commit = git_capture(
+        project_root,
+        &["rev-parse", "--verify", &format!("{revision}^{{commit}}")],
+    )
+    .wrap_err_with(|| format!("unknown revision `{revision}`"))?;
+    let commit = commit.trim();
+    let checkout = Checkout {
+        dir: std::env::temp_dir().join(format!(
+            "tracey-diff-coverage-{}-{commit}",
+            std::process::id()
+        )),
+    };
+    let _ = std::fs::remove_dir_all(&checkout.dir);
+
+    let files = git_capture(project_root, &["ls-tree", "-r", "--name-only", commit])?;
+    let files: BTreeSet<&str> = files.lines().map(str::trim).collect();
+    let config_rel = config_path
+        .strip_prefix(project_root)
+        .unwrap_or(config_path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let config = if files.contains(config_rel.as_str()) {
+        let text = show(project_root, commit, &config_rel)?;
+        let text = String::from_utf8(text)
+            .wrap_err_with(|| format!("{config_rel} at {revision} is not valid UTF-8"))?;
+        facet_styx::from_str::<Config>(&text)
+            .map(Config::resolve_defaults)
+            .wrap_err_with(|| format!("failed to parse {config_rel} at {revision}"))?
+    } else {
+        config.clone()
+    };
+
+    let selection = Selection::new(&config);
+    for file in files {
+        if file.is_empty() || !selection.selects(file) {
+            continue;
+        }
+        let path = checkout.dir.join(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, show(project_root, commit, file)?)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok((checkout, config))
+}
+
+/// Coverage of every rule at `revision`, by `(spec, impl)` and base rule ID.
+async fn coverage(
+    project_root: &Path,
+    config_path: &Path,
+    config: &Config,
+    revision: &str,
+) -> Result<BTreeMap<(String, String), BTreeMap<String, RuleStatus>>> {
+    let (checkout, config) = checkout(project_root, config_path, config, revision)?;
+    let data = crate::data::build_dashboard_data(&checkout.dir, &config, 0, true)
+        .await
+        .wrap_err_with(|| format!("failed to compute coverage at {revision}"))?;
+    Ok(data
+        .forward_by_impl
+        .iter()
+        .map(|(key, forward)| {
+            let rules = forward
+                .rules
+                .iter()
+                .map(|rule| (rule.id.base.clone(), RuleStatus::of(rule)))
+                .collect();
+            (key.clone(), rules)
+        })
+        .collect())
+}
+
+fn totals(rules: &BTreeMap<String, RuleStatus>) -> Totals {
+    Totals {
+        rules: rules.len(),
+        implemented: rules.values().filter(|s| s.implemented).count(),
+        verified: rules
+            .values()
+            .filter(|s| s.verification > Verification::Unverified)
+            .count(),
+    }
+}
+
+/// Compare coverage at `from` and `to` without touching the working tree.
+// r[impl cli.diff-coverage]
+pub async fn diff_coverage(
+    project_root: &Path,
+    config_path: &Path,
+    config: &Config,
+    from: &str,
+    to: &str,
+) -> Result<CoverageDiff> {
+    let before = coverage(project_root, config_path, config, from).await?;
+    let after = coverage(project_root, config_path, config, to).await?;
+    let empty = BTreeMap::new();
+
+    let keys: BTreeSet<&(String, String)> = before.keys().chain(after.keys()).collect();
+    let mut totals_by_impl = BTreeMap::new();
+    let mut changes = Vec::new();
+    for key in keys {
+        let old = before.get(key);
+        let new = after.get(key);
+        totals_by_impl.insert(key.clone(), (old.map(totals), new.map(totals)));
+        let (old, new) = (old.unwrap_or(&empty), new.unwrap_or(&empty));
+        let ids: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        for id in ids {
+            let change = RuleChange {
+                spec: key.0.clone(),
+                impl_name: key.1.clone(),
+                id: id.clone(),
+                before: old.get(id).copied(),
+                after: new.get(id).copied(),
+            };
+            if change.gained_coverage() || change.lost_coverage() || change.verification_changed() {
+                changes.push(change);
+            }
+        }
+    }
+    Ok(CoverageDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        totals: totals_by_impl,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(root: &Path, args: &[&str]) {
+        git_capture(root, args).unwrap();
+    }
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn selects_files_the_scan_reads() {
+        let config: Config = facet_styx::from_str(
+            "specs (\n  {\n    name demo\n    include (docs)\n    impls (\n      {\n        name rust\n        include (src/**/*.rs)\n      }\n    )\n  }\n)\n",
+        )
+        .unwrap();
+        let selection = Selection::new(&config);
+        assert!(selection.selects("docs/spec.md"));
+        assert!(!selection.selects("docs2/spec.md"));
+        assert!(selection.selects("src/a/b.rs"));
+        assert!(!selection.selects("tests/b.rs"));
+        assert!(selection.selects(".config/tracey/config.styx"));
+    }
+
+    // r[verify cli.diff-coverage]
+    #[tokio::test]
+    async fn reports_rules_whose_coverage_changed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+        git(root, &["config", "user.email", "t@example.com"]);
+        git(root, &["config", "user.name", "t"]);
+        write(
+            root,
+            ".config/tracey/config.styx",
+            "specs (\n  {\n    name demo\n    include (spec.md)\n    impls (\n      {\n        name rust\n        include (src/**/*.rs)\n        test_include (tests/**/*.rs)\n      }\n    )\n  }\n)\n",
+        );
+        write(
+            root,
+            "spec.md",
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MUST log out.\n",
+        );
+        write(root, "src/lib.rs", "// r[impl auth.logout]\nfn b() {}\n");
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "base"]);
+
+        write(root, "src/lib.rs", "// r[impl auth.login]\nfn a() {}\n");
+        write(
+            root,
+            "tests/login.rs",
+            "// r[verify auth.login]\n#[test]\nfn t() {}\n",
+        );
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "login"]);
+        // Uncommitted changes are not part of either revision.
+        write(root, "src/lib.rs", "fn nothing() {}\n");
+
+        let config_path = root.join(".config/tracey/config.styx");
+        let config = crate::load_config(&config_path).unwrap();
+        let diff = diff_coverage(root, &config_path, &config, "HEAD~1", "HEAD")
+            .await
+            .unwrap();
+
+        let summary: Vec<(&str, bool, bool, bool)> = diff
+            .changes
+            .iter()
+            .map(|c| {
+                (
+                    c.id.as_str(),
+                    c.gained_coverage(),
+                    c.lost_coverage(),
+                    c.verification_changed(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("auth.login", true, false, true),
+                ("auth.logout", false, true, false),
+            ]
+        );
+        let markdown = diff.to_markdown();
+        assert!(
+            markdown.contains("| demo/rust | 1/2 → 1/2 | 0/2 → 1/2 |"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("- `auth.login` (demo/rust): unverified → tested"),
+            "{markdown}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "fn nothing() {}\n"
+        );
+    }
+}
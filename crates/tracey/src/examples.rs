@@ -0,0 +1,224 @@
+//! Example and counter-example blocks in spec markdown.
+//!
+//! A paragraph starting with `e[rule.id good]` or `e[rule.id bad]` shows a
+//! concrete case that follows or breaks a rule defined in the same file; a
+//! fenced code block right after that paragraph is part of the example.
+//! marq would read the marker as a rule definition, so examples are cut out
+//! of the markdown before it is rendered. They are replaced with whitespace,
+//! which keeps every byte offset and line number of the rest of the file.
+
+use std::borrow::Cow;
+
+/// An example attached to a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleExample {
+    /// Base ID of the rule the example illustrates.
+    pub rule: String,
+    /// `true` for `good`, `false` for `bad`.
+    pub good: bool,
+    /// Markdown of the example, without its marker.
+    pub markdown: String,
+    /// 1-based line of the marker.
+    pub line: usize,
+}
+
+impl RuleExample {
+    /// `good` or `bad`, as written in the marker.
+    pub fn kind(&self) -> &'static str {
+        if self.good { "good" } else { "bad" }
+    }
+}
+
+/// Parse `e[rule.id good]` at the start of `text`, returning the rule's base
+/// ID, whether it is a good example, and the text after the marker.
+fn parse_marker(text: &str) -> Option<(String, bool, &str)> {
+    let inner = text.strip_prefix("e[")?;
+    let end = inner.find(']')?;
+    let mut words = inner[..end].split_whitespace();
+    let id = tracey_core::parse_rule_id(words.next()?)?;
+    let good = match words.next()? {
+        "good" => true,
+        "bad" => false,
+        _ => return None,
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    Some((id.base, good, &inner[end + 1..]))
+}
+
+/// The fence a line opens (its character and length), if it opens one.
+fn fence_open(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() >= 4 {
+        return None;
+    }
+    let ch = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = trimmed.chars().take_while(|c| *c == ch).count();
+    (len >= 3).then_some((ch, len))
+}
+
+fn closes_fence(line: &str, (ch, len): (char, usize)) -> bool {
+    let trimmed = line.trim();
+    trimmed.chars().take_while(|c| *c == ch).count() >= len && trimmed.chars().all(|c| c == ch)
+}
+
+/// Split example blocks out of `markdown`. The returned markdown has every
+/// example replaced by whitespace and its rule markers narrowed to what marq
+/// parses (see [`crate::marq_markers`]); it is borrowed when nothing
+/// changed.
+// r[impl markdown.examples]
+pub fn extract(markdown: &str) -> (Cow<'_, str>, Vec<RuleExample>) {
+    let lines: Vec<(usize, &str)> = markdown
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .collect();
+    let is_blank = |i: usize| lines.get(i).is_none_or(|(_, l)| l.trim().is_empty());
+
+    let mut examples = Vec::new();
+    let mut regions = Vec::new();
+    let mut fence = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].1;
+        if let Some(open) = fence {
+            if closes_fence(line, open) {
+                fence = None;
+            }
+            i += 1;
+            continue;
+        }
+        if let Some(open) = fence_open(line) {
+            fence = Some(open);
+            i += 1;
+            continue;
+        }
+        let trimmed = line.trim_start_matches(' ');
+        let marker = (i == 0 || is_blank(i - 1))
+            .then(|| parse_marker(trimmed))
+            .flatten()
+            .filter(|_| line.len() - trimmed.len() < 4);
+        let Some((rule, good, rest)) = marker else {
+            i += 1;
+            continue;
+        };
+
+        // The marker's paragraph…
+        let start = i;
+        let mut body = rest.trim_start().to_string();
+        i += 1;
+        while !is_blank(i) && fence_open(lines[i].1).is_none() {
+            body.push_str(lines[i].1);
+            i += 1;
+        }
+        // …and a fenced code block right after it.
+        let mut next = i;
+        while next < lines.len() && is_blank(next) {
+            next += 1;
+        }
+        if let Some(open) = lines.get(next).and_then(|(_, l)| fence_open(l)) {
+            let mut end = next + 1;
+            while end < lines.len() && !closes_fence(lines[end].1, open) {
+                end += 1;
+            }
+            i = (end + 1).min(lines.len());
+            let code: String = lines[next..i].iter().map(|(_, l)| *l).collect();
+            body = format!("{}\n\n{}", body.trim_end(), code);
+        }
+
+        examples.push(RuleExample {
+            rule,
+            good,
+            markdown: body.trim().to_string(),
+            line: start + 1,
+        });
+        let end = lines.get(i).map_or(markdown.len(), |(offset, _)| *offset);
+        regions.push(lines[start].0..end);
+    }
+
+    if regions.is_empty() {
+        return (crate::marq_markers(markdown), examples);
+    }
+    let mut out = String::with_capacity(markdown.len());
+    let mut last = 0;
+    for region in regions {
+        out.push_str(&markdown[last..region.start]);
+        for ch in markdown[region.clone()].chars() {
+            if ch == '\n' {
+                out.push('\n');
+            } else {
+                out.extend(std::iter::repeat_n(' ', ch.len_utf8()));
+            }
+        }
+        last = region.end;
+    }
+    out.push_str(&markdown[last..]);
+    if let Cow::Owned(blanked) = crate::marq_markers(&out) {
+        out = blanked;
+    }
+    (Cow::Owned(out), examples)
+}
+
+/// `markdown` with its example blocks blanked out, ready for marq.
+pub fn strip(markdown: &str) -> Cow<'_, str> {
+    extract(markdown).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify markdown.examples]
+    #[tokio::test]
+    async fn examples_are_cut_out_and_attached_to_their_rule() {
+        let markdown = "\
+r[auth.login]
+Passwords MUST be compared in constant time.
+
+e[auth.login good] Use a constant-time comparison — always:
+
+```rust
+constant_time_eq(a, b)
+```
+
+e[auth.login bad]
+Comparing with `==` leaks timing.
+
+r[auth.logout]
+Logout MUST clear the session.
+
+```
+e[auth.login good] inside a fence stays put
+```
+";
+        let (stripped, examples) = extract(markdown);
+        assert_eq!(stripped.len(), markdown.len());
+        assert_eq!(stripped.lines().count(), markdown.lines().count());
+        assert!(!stripped.contains("constant_time_eq"));
+        assert!(stripped.contains("e[auth.login good] inside a fence stays put"));
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].rule, "auth.login");
+        assert_eq!(examples[0].kind(), "good");
+        assert_eq!(examples[0].line, 4);
+        assert_eq!(
+            examples[0].markdown,
+            "Use a constant-time comparison — always:\n\n```rust\nconstant_time_eq(a, b)\n```"
+        );
+        assert_eq!(examples[1].kind(), "bad");
+        assert_eq!(examples[1].markdown, "Comparing with `==` leaks timing.");
+
+        let doc = marq::render(&stripped, &marq::RenderOptions::default())
+            .await
+            .unwrap();
+        let ids: Vec<_> = doc.reqs.iter().map(|r| r.id.to_string()).collect();
+        assert_eq!(ids, ["auth.login", "auth.logout"]);
+        assert_eq!(doc.reqs[1].line, 13);
+
+        assert!(matches!(strip("r[a.b]\nText.\n"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,188 @@
+//! `tracey extract` implementation.
+//!
+//! Extracts the requirement references of a single piece of source, read
+//! from a file or from stdin, and prints them as JSON. Editor plugins and
+//! hooks that work on unsaved or patched content use this instead of a full
+//! scan; nothing is read from the rest of the project except the config for
+//! custom verbs.
+
+use eyre::{Result, bail};
+use facet::Facet;
+use std::path::{Path, PathBuf};
+
+use tracey_core::{Reqs, SUPPORTED_EXTENSIONS, WarningKind};
+
+/// A reference found in the source.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExtractedReference {
+    pub prefix: String,
+    pub verb: String,
+    /// Rule ID in canonical form, with its `+N` version suffix if any.
+    pub rule: String,
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+    /// Byte offset of the reference in the source.
+    pub offset: usize,
+    /// Length of the reference in bytes.
+    pub length: usize,
+}
+
+/// Something that looks like a reference but could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExtractedWarning {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub length: usize,
+    pub message: String,
+}
+
+/// Everything `tracey extract` prints.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Extracted {
+    pub references: Vec<ExtractedReference>,
+    pub warnings: Vec<ExtractedWarning>,
+}
+
+/// File extension for a language given by name (`rust`) or by extension
+/// (`rs`, `.rs`).
+pub fn extension_for(lang: &str) -> Result<&'static str> {
+    let lower = lang.trim().trim_start_matches('.').to_ascii_lowercase();
+    let ext = match lower.as_str() {
+        "rust" => "rs",
+        "python" => "py",
+        "typescript" => "ts",
+        "javascript" => "js",
+        "golang" => "go",
+        "kotlin" => "kt",
+        "csharp" | "c#" => "cs",
+        "c++" => "cpp",
+        "objective-c" | "objc" => "m",
+        "ruby" => "rb",
+        "perl" => "pl",
+        "haskell" => "hs",
+        "elixir" => "ex",
+        "erlang" => "erl",
+        "clojure" => "clj",
+        "fsharp" | "f#" => "fs",
+        "julia" => "jl",
+        "ocaml" => "ml",
+        "powershell" => "ps1",
+        "shell" => "sh",
+        other => match SUPPORTED_EXTENSIONS.iter().find(|ext| **ext == other) {
+            Some(ext) => ext,
+            None => bail!("Unsupported language `{lang}`"),
+        },
+    };
+    Ok(ext)
+}
+
+/// 1-based character column of byte `offset` in `content`.
+fn column_at(content: &str, offset: usize) -> usize {
+    let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    content[line_start..offset].chars().count() + 1
+}
+
+/// The path to parse the source as: `path` if given (its extension picks the
+/// language unless `lang` overrides it), otherwise a placeholder file name
+/// with the extension of `lang`.
+pub fn source_path(path: Option<&Path>, lang: Option<&str>) -> Result<PathBuf> {
+    match (path, lang) {
+        (Some(path), None) => Ok(path.to_path_buf()),
+        (Some(path), Some(lang)) => Ok(path.with_extension(extension_for(lang)?)),
+        (None, Some(lang)) => Ok(PathBuf::from(format!("stdin.{}", extension_for(lang)?))),
+        (None, None) => bail!("Pass --lang or a file name so the language is known"),
+    }
+}
+
+/// Extract the references of `content`, parsed as the source file `path`.
+// r[impl cli.extract]
+pub fn extract(path: &Path, content: &str, custom_verbs: &[String]) -> Extracted {
+    let reqs = Reqs::extract_from_content_with_verbs(path, content, custom_verbs);
+    let references = reqs
+        .references
+        .iter()
+        .map(|r| ExtractedReference {
+            prefix: r.prefix.clone(),
+            verb: r.verb.to_string(),
+            rule: r.req_id.to_string(),
+            line: r.line,
+            column: column_at(content, r.span.offset),
+            offset: r.span.offset,
+            length: r.span.length,
+        })
+        .collect();
+    let warnings = reqs
+        .warnings
+        .iter()
+        .map(|w| ExtractedWarning {
+            line: w.line,
+            column: column_at(content, w.span.offset),
+            offset: w.span.offset,
+            length: w.span.length,
+            message: match &w.kind {
+                WarningKind::UnknownVerb(verb) => format!("Unknown verb: '{verb}'"),
+                WarningKind::MalformedReference => "Malformed reference".to_string(),
+                WarningKind::NonUtf8Encoding(encoding) => {
+                    format!("File is not valid UTF-8; decoded as {encoding}")
+                }
+            },
+        })
+        .collect();
+    Extracted {
+        references,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.extract]
+    #[test]
+    fn extracts_references_with_positions() {
+        let source = "fn a() {}\n\n    // r[impl auth.login+2]\n// r[documents auth.guide]\n";
+        let path = source_path(None, Some("rust")).unwrap();
+        assert_eq!(path, PathBuf::from("stdin.rs"));
+
+        let extracted = extract(&path, source, &[]);
+        assert_eq!(
+            extracted.references,
+            vec![ExtractedReference {
+                prefix: "r".to_string(),
+                verb: "impl".to_string(),
+                rule: "auth.login+2".to_string(),
+                line: 3,
+                column: 8,
+                offset: 18,
+                length: 20,
+            }]
+        );
+
+        // Custom verbs are only recognized when configured.
+        let extracted = extract(&path, source, &["documents".to_string()]);
+        assert_eq!(extracted.references.len(), 2);
+        assert_eq!(extracted.references[1].verb, "documents");
+
+        let json = facet_json::to_string(&extracted).unwrap();
+        assert!(json.contains("\"rule\":\"auth.guide\""), "{json}");
+    }
+
+    #[test]
+    fn language_comes_from_lang_or_file_name() {
+        assert_eq!(extension_for("Python").unwrap(), "py");
+        assert_eq!(extension_for(".tsx").unwrap(), "tsx");
+        assert!(extension_for("fortran").is_err());
+        assert_eq!(
+            source_path(Some(Path::new("src/lib.txt")), Some("rs")).unwrap(),
+            PathBuf::from("src/lib.rs")
+        );
+        assert!(source_path(None, None).is_err());
+    }
+}
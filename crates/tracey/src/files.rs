@@ -0,0 +1,277 @@
+//! `tracey files` implementation.
+//!
+//! Shows which files each implementation's include/exclude patterns select,
+//! and why the other files they reach are skipped (ignored by git, matched an
+//! exclude pattern, or have an extension tracey cannot parse). This answers
+//! "why is my annotation not picked up" without guessing at the walker.
+
+use eyre::{Result, bail};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::data::{SkipReason, explain_impl_scan};
+
+/// The files one implementation scans and skips.
+#[derive(Debug, Default)]
+pub struct ImplFiles {
+    pub spec: String,
+    pub impl_name: String,
+    pub include: Vec<String>,
+    pub test_include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Scanned files relative to the project root, and whether they are
+    /// test files (matched by `test_include`).
+    pub selected: Vec<(PathBuf, bool)>,
+    /// Skipped paths relative to the project root. Paths ignored by git may
+    /// be directories, shown with a trailing `/`.
+    pub skipped: Vec<(String, SkipReason)>,
+    /// Problems with the patterns themselves.
+    pub warnings: Vec<String>,
+}
+
+/// File selection for every requested implementation.
+#[derive(Debug, Default)]
+pub struct FilesReport {
+    pub impls: Vec<ImplFiles>,
+}
+
+impl FilesReport {
+    /// Render the report as plain text.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (i, files) in self.impls.iter().enumerate() {
+            if i > 0 {
+                let _ = writeln!(out);
+            }
+            let _ = writeln!(out, "{}/{}", files.spec, files.impl_name);
+            for (label, patterns) in [
+                ("include", &files.include),
+                ("test_include", &files.test_include),
+                ("exclude", &files.exclude),
+            ] {
+                if !patterns.is_empty() {
+                    let _ = writeln!(out, "  {label}: {}", patterns.join(", "));
+                }
+            }
+            for warning in &files.warnings {
+                let _ = writeln!(out, "  {}", warning.replace('\n', "\n  "));
+            }
+
+            let _ = writeln!(out, "  selected ({}):", files.selected.len());
+            for (path, test) in &files.selected {
+                let suffix = if *test { " (test)" } else { "" };
+                let _ = writeln!(out, "    {}{suffix}", path.display());
+            }
+            if !files.skipped.is_empty() {
+                let _ = writeln!(out, "  skipped ({}):", files.skipped.len());
+                let width = files
+                    .skipped
+                    .iter()
+                    .map(|(p, _)| p.len())
+                    .max()
+                    .unwrap_or(0);
+                for (path, reason) in &files.skipped {
+                    let reason = match reason {
+                        SkipReason::Gitignored => "ignored by git".to_string(),
+                        SkipReason::Excluded(pattern) => format!("excluded by `{pattern}`"),
+                        SkipReason::UnsupportedExtension => "unsupported extension".to_string(),
+                    };
+                    let _ = writeln!(out, "    {path:width$}  {reason}");
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Explain file selection for the implementations matching `spec` and
+/// `impl_name` (all of them when `None`).
+pub fn files(
+    project_root: &Path,
+    config: &Config,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+) -> Result<FilesReport> {
+    let mut report = FilesReport::default();
+    let configured_extensions: Vec<String> = config.comments.keys().cloned().collect();
+    for spec_config in &config.specs {
+        if spec.is_some_and(|s| s != spec_config.name) {
+            continue;
+        }
+        for impl_config in &spec_config.impls {
+            if impl_name.is_some_and(|i| i != impl_config.name) {
+                continue;
+            }
+            // Same default as the scan itself.
+            let include: Vec<String> = if impl_config.include.is_empty() {
+                vec!["**/*.rs".to_string()]
+            } else {
+                impl_config.include.to_vec()
+            };
+            report.impls.push(explain(
+                project_root,
+                &spec_config.name,
+                &impl_config.name,
+                include,
+                impl_config.test_include.to_vec(),
+                impl_config.exclude.to_vec(),
+                &configured_extensions,
+            ));
+        }
+    }
+
+    if report.impls.is_empty() {
+        let available: Vec<String> = config
+            .specs
+            .iter()
+            .flat_map(|s| {
+                s.impls
+                    .iter()
+                    .map(move |i| format!("{}/{}", s.name, i.name))
+            })
+            .collect();
+        bail!(
+            "No implementation matches{}{}. Available: {}",
+            spec.map(|s| format!(" --spec {s}")).unwrap_or_default(),
+            impl_name
+                .map(|i| format!(" --impl {i}"))
+                .unwrap_or_default(),
+            available.join(", ")
+        );
+    }
+    Ok(report)
+}
+
+fn explain(
+    project_root: &Path,
+    spec: &str,
+    impl_name: &str,
+    include: Vec<String>,
+    test_include: Vec<String>,
+    exclude: Vec<String>,
+    configured_extensions: &[String],
+) -> ImplFiles {
+    let (mut paths, mut warnings) =
+        explain_impl_scan(project_root, &include, &exclude, configured_extensions);
+    // Files selected only through test_include.
+    let mut tests: BTreeSet<PathBuf> = BTreeSet::new();
+    if !test_include.is_empty() {
+        let (test_paths, test_warnings) =
+            explain_impl_scan(project_root, &test_include, &exclude, configured_extensions);
+        warnings.extend(test_warnings);
+        for (path, reason) in test_paths {
+            match (paths.get(&path), reason) {
+                (Some(None), _) | (Some(Some(_)), Some(_)) => {}
+                (_, None) => {
+                    tests.insert(path.clone());
+                    paths.insert(path, None);
+                }
+                (None, reason) => {
+                    paths.insert(path, reason);
+                }
+            }
+        }
+    }
+
+    let relative = |path: &Path| {
+        path.strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_path_buf()
+    };
+    let mut files = ImplFiles {
+        spec: spec.to_string(),
+        impl_name: impl_name.to_string(),
+        include,
+        test_include,
+        exclude,
+        warnings,
+        ..Default::default()
+    };
+    for (path, reason) in paths {
+        match reason {
+            None => {
+                files
+                    .selected
+                    .push((relative(&path), tests.contains(&path)));
+            }
+            Some(reason) => {
+                let mut shown = relative(&path).display().to_string();
+                if path.is_dir() {
+                    shown.push('/');
+                }
+                files.skipped.push((shown, reason));
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    // r[verify cli.files]
+    #[test]
+    fn explains_why_files_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(root)
+            .status()
+            .unwrap();
+        for file in [
+            "src/lib.rs",
+            "src/generated.rs",
+            "src/notes.txt",
+            "tests/it.rs",
+            "src/gen/out.rs",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "gen/\n").unwrap();
+
+        let files = explain(
+            root,
+            "api",
+            "rust",
+            vec!["src/**/*".to_string()],
+            vec!["tests/**/*.rs".to_string()],
+            vec!["**/generated.rs".to_string()],
+            &[],
+        );
+        assert_eq!(
+            files.selected,
+            vec![
+                (PathBuf::from("src/lib.rs"), false),
+                (PathBuf::from("tests/it.rs"), true)
+            ]
+        );
+        assert_eq!(
+            files.skipped,
+            vec![
+                ("src/gen/".to_string(), SkipReason::Gitignored),
+                (
+                    "src/generated.rs".to_string(),
+                    SkipReason::Excluded("**/generated.rs".to_string())
+                ),
+                (
+                    "src/notes.txt".to_string(),
+                    SkipReason::UnsupportedExtension
+                ),
+            ]
+        );
+
+        let text = FilesReport { impls: vec![files] }.to_text();
+        assert!(text.contains("    tests/it.rs (test)\n"), "{text}");
+        assert!(
+            text.contains("    src/gen/          ignored by git\n"),
+            "{text}"
+        );
+    }
+}
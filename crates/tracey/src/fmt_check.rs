@@ -0,0 +1,287 @@
+//! `tracey fmt-check` implementation.
+//!
+//! Formats every annotated source file in memory with the formatter
+//! configured for its extension, then checks that each requirement reference
+//! is still attributed to the same code unit. Formatters move comments
+//! around (joining lines, reflowing attributes), and a reference that drifts
+//! off the function it annotates silently changes what the reverse coverage
+//! view reports. Files on disk are never modified.
+
+use eyre::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config::{Config, formatter_commands};
+
+/// A reference whose code unit would change if the file were formatted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// File relative to the project root.
+    pub file: PathBuf,
+    /// The referenced rule, in canonical form.
+    pub rule: String,
+    /// Code units the rule is attributed to now.
+    pub before: Vec<String>,
+    /// Code units the rule would be attributed to after formatting.
+    pub after: Vec<String>,
+}
+
+/// Outcome of `tracey fmt-check`.
+#[derive(Debug, Default)]
+pub struct FmtCheckReport {
+    /// Annotated files that were run through a formatter.
+    pub checked: usize,
+    /// How many of those the formatter would change.
+    pub reformatted: usize,
+    pub drifts: Vec<Drift>,
+    /// Formatter programs that could not be started.
+    pub missing: BTreeSet<String>,
+    /// Files the formatter rejected, with its first line of error output.
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+impl FmtCheckReport {
+    /// Whether formatting would move no reference.
+    pub fn is_clean(&self) -> bool {
+        self.drifts.is_empty()
+    }
+
+    /// Render the report as plain text.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for drift in &self.drifts {
+            out.push_str(&format!(
+                "{}: r[{}] moves from {} to {}\n",
+                drift.file.display(),
+                drift.rule,
+                describe(&drift.before),
+                describe(&drift.after)
+            ));
+        }
+        for (file, error) in &self.failures {
+            out.push_str(&format!(
+                "warning: formatter failed on {}: {error}\n",
+                file.display()
+            ));
+        }
+        for program in &self.missing {
+            out.push_str(&format!(
+                "warning: `{program}` not found; files it formats were skipped\n"
+            ));
+        }
+        out.push_str(&format!(
+            "Checked {} annotated files; {} would be reformatted, {} references would drift.\n",
+            self.checked,
+            self.reformatted,
+            self.drifts.len()
+        ));
+        out
+    }
+}
+
+fn describe(units: &[String]) -> String {
+    if units.is_empty() {
+        "no code unit".to_string()
+    } else {
+        units.join(", ")
+    }
+}
+
+/// Rules referenced by each code unit in `source`, as rule → unit labels.
+fn attribution(path: &Path, source: &str) -> BTreeMap<String, Vec<String>> {
+    let mut by_rule: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for unit in tracey_core::code_units::extract(path, source).units {
+        let label = match &unit.name {
+            Some(name) => format!("{} `{name}`", unit.kind),
+            None => unit.kind.to_string(),
+        };
+        for rule in &unit.req_refs {
+            by_rule
+                .entry(rule.to_string())
+                .or_default()
+                .push(label.clone());
+        }
+    }
+    for units in by_rule.values_mut() {
+        units.sort();
+    }
+    by_rule
+}
+
+/// Compare reference attribution before and after formatting.
+fn drifts(file: &Path, before: &str, after: &str) -> Vec<Drift> {
+    let before_units = attribution(file, before);
+    let after_units = attribution(file, after);
+    let rules: BTreeSet<&String> = before_units.keys().chain(after_units.keys()).collect();
+    rules
+        .into_iter()
+        .filter(|rule| before_units.get(*rule) != after_units.get(*rule))
+        .map(|rule| Drift {
+            file: file.to_path_buf(),
+            rule: rule.clone(),
+            before: before_units.get(rule).cloned().unwrap_or_default(),
+            after: after_units.get(rule).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Why a formatter produced no output.
+enum FormatError {
+    /// The program could not be started.
+    Missing(String),
+    /// The program ran and failed.
+    Failed(String),
+}
+
+/// Pipe `source` through `command` and return the formatted text.
+fn format_source(
+    project_root: &Path,
+    command: &str,
+    relative: &Path,
+    source: &str,
+) -> std::result::Result<String, FormatError> {
+    let path = relative.to_string_lossy();
+    let mut args = command
+        .split_whitespace()
+        .map(|arg| arg.replace("{path}", &path));
+    let program = args.next().unwrap_or_default();
+    let mut child = Command::new(&program)
+        .args(args)
+        .current_dir(project_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| FormatError::Missing(program.clone()))?;
+
+    // Write from a separate thread so a formatter that streams output before
+    // reading all of its input cannot deadlock against us.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = source.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .map_err(|e| FormatError::Failed(e.to_string()))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let first = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(FormatError::Failed(format!("`{program}` {}", first.trim())));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| FormatError::Failed(format!("`{program}` printed invalid UTF-8")))
+}
+
+/// Run the configured formatters over every annotated file and report
+/// references that would move to a different code unit.
+// r[impl cli.fmt-check]
+pub async fn fmt_check(project_root: &Path, config: &Config) -> Result<FmtCheckReport> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let commands = formatter_commands(config);
+    let canonical_root = project_root.canonicalize().ok();
+
+    let mut report = FmtCheckReport::default();
+    for (file, reqs) in &data.source_reqs_by_file {
+        if reqs.references.is_empty() {
+            continue;
+        }
+        let Some(command) = file
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| commands.get(ext))
+        else {
+            continue;
+        };
+        let program = command.split_whitespace().next().unwrap_or_default();
+        if report.missing.contains(program) {
+            continue;
+        }
+        let relative = file
+            .strip_prefix(project_root)
+            .ok()
+            .or_else(|| file.strip_prefix(canonical_root.as_ref()?).ok())
+            .unwrap_or(file);
+        let Ok(source) = std::fs::read_to_string(file) else {
+            continue;
+        };
+
+        match format_source(project_root, command, relative, &source) {
+            Ok(formatted) => {
+                report.checked += 1;
+                if formatted != source {
+                    report.reformatted += 1;
+                    report.drifts.extend(drifts(relative, &source, &formatted));
+                }
+            }
+            Err(FormatError::Missing(program)) => {
+                report.missing.insert(program);
+            }
+            Err(FormatError::Failed(error)) => {
+                report.failures.push((relative.to_path_buf(), error));
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.fmt-check]
+    #[test]
+    fn detects_reference_moving_to_another_unit() {
+        let before = "\
+// r[impl auth.login]
+fn login() {}
+
+fn logout() {}
+";
+        // A formatter that drops the blank line keeps attribution...
+        let same = "// r[impl auth.login]\nfn login() {}\nfn logout() {}\n";
+        assert!(drifts(Path::new("src/lib.rs"), before, same).is_empty());
+
+        // ...while one that moves the comment below the function does not.
+        let moved = "fn login() {}\n// r[impl auth.login]\nfn logout() {}\n";
+        let found = drifts(Path::new("src/lib.rs"), before, moved);
+        assert_eq!(
+            found,
+            vec![Drift {
+                file: PathBuf::from("src/lib.rs"),
+                rule: "auth.login".to_string(),
+                before: vec!["function `login`".to_string()],
+                after: vec!["function `logout`".to_string()],
+            }]
+        );
+
+        let report = FmtCheckReport {
+            checked: 1,
+            reformatted: 1,
+            drifts: found,
+            ..Default::default()
+        };
+        assert!(!report.is_clean());
+        assert!(
+            report.to_text().contains(
+                "src/lib.rs: r[auth.login] moves from function `login` to function `logout`"
+            ),
+            "{}",
+            report.to_text()
+        );
+    }
+
+    #[test]
+    fn missing_formatter_is_reported_not_fatal() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = format_source(
+            dir.path(),
+            "tracey-no-such-formatter --check",
+            Path::new("a.rs"),
+            "fn main() {}\n",
+        );
+        assert!(matches!(result, Err(FormatError::Missing(p)) if p == "tracey-no-such-formatter"));
+    }
+}
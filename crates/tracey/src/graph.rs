@@ -0,0 +1,732 @@
+//! `tracey graph` implementation.
+//!
+//! Builds a graph of the rules of one spec, grouped by the headings of the
+//! spec, with an edge for every rule another rule's prose mentions
+//! (`related`) and for every rule the code implementing a rule declares a
+//! `depends` reference on. The graph is printed as Graphviz DOT or as a
+//! Mermaid flowchart, with rules that are not implemented highlighted.
+
+use eyre::{Result, bail};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use tracey_api::{ApiCodeRef, ApiRule, OutlineEntry};
+use tracey_core::RuleId;
+use tracey_core::code_units::CodeUnit;
+
+use crate::config::Config;
+
+/// Fill and border of rules that are not implemented.
+const UNCOVERED_FILL: &str = "#f8d7da";
+const UNCOVERED_STROKE: &str = "#c0392b";
+
+/// What `tracey graph` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl std::str::FromStr for Format {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            other => Err(eyre::eyre!(
+                "Unsupported graph format `{other}`; expected dot or mermaid"
+            )),
+        }
+    }
+}
+
+/// Why one rule points at another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeKind {
+    /// Code implementing the source rule has a `depends` reference on the target.
+    Depends,
+    /// The source rule's prose mentions the target.
+    Related,
+}
+
+impl EdgeKind {
+    fn label(self) -> &'static str {
+        match self {
+            EdgeKind::Depends => "depends",
+            EdgeKind::Related => "related",
+        }
+    }
+}
+
+/// A heading of the spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub slug: String,
+    pub title: String,
+    /// Index of the enclosing heading in [`RuleGraph::sections`].
+    pub parent: Option<usize>,
+}
+
+/// A rule of the spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub id: RuleId,
+    /// Index of the heading the rule sits under in [`RuleGraph::sections`].
+    pub section: Option<usize>,
+    /// Whether the rule is implemented.
+    pub covered: bool,
+}
+
+/// A step of [`RuleGraph::walk`].
+enum Visit {
+    /// A rule, by index in [`RuleGraph::nodes`].
+    Node(usize),
+    /// Entering a heading, by index in [`RuleGraph::sections`].
+    Open(usize),
+    /// Leaving the heading last entered.
+    Close,
+}
+
+/// The rules of one spec and how they point at each other.
+#[derive(Debug, Default)]
+pub struct RuleGraph {
+    pub spec: String,
+    /// Headings in document order.
+    pub sections: Vec<Section>,
+    /// Rules in document order.
+    pub nodes: Vec<Node>,
+    pub edges: BTreeSet<(RuleId, RuleId, EdgeKind)>,
+}
+
+/// Whether `base` is `prefix` or nested below it, segment-wise.
+fn matches_prefix(base: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('.');
+    base == prefix
+        || base
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// The innermost code unit containing a reference, as (file, unit index).
+fn unit_of<'a>(
+    units: &'a BTreeMap<PathBuf, Vec<CodeUnit>>,
+    files: &mut HashMap<String, Option<&'a Path>>,
+    code_ref: &ApiCodeRef,
+) -> Option<(&'a Path, usize)> {
+    // Code units are keyed by absolute path, references by project-relative path.
+    let file = *files.entry(code_ref.file.clone()).or_insert_with(|| {
+        units
+            .keys()
+            .find(|p| p.ends_with(&code_ref.file))
+            .map(PathBuf::as_path)
+    });
+    let file = file?;
+    let index = units[file]
+        .iter()
+        .enumerate()
+        .filter(|(_, u)| u.start_line <= code_ref.line && code_ref.line <= u.end_line)
+        .min_by_key(|(_, u)| u.end_line - u.start_line)?
+        .0;
+    Some((file, index))
+}
+
+/// A rule `from` depending on a rule `to`: a code unit implementing `from`
+/// carries the `depends` reference `at` to `to`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DependsEdge<'a> {
+    pub from: &'a RuleId,
+    pub to: &'a RuleId,
+    pub at: &'a ApiCodeRef,
+}
+
+/// Every `depends` edge between `rules`, ordered by source and target rule,
+/// with the first reference that makes each one.
+pub(crate) fn depends_edges<'a>(
+    rules: &[&'a ApiRule],
+    units: &BTreeMap<PathBuf, Vec<CodeUnit>>,
+) -> Vec<DependsEdge<'a>> {
+    let mut files = HashMap::new();
+    let mut implementing: HashMap<(&Path, usize), Vec<&RuleId>> = HashMap::new();
+    let mut depending: HashMap<(&Path, usize), Vec<(&RuleId, &ApiCodeRef)>> = HashMap::new();
+    for rule in rules {
+        for code_ref in &rule.impl_refs {
+            if let Some(unit) = unit_of(units, &mut files, code_ref) {
+                implementing.entry(unit).or_default().push(&rule.id);
+            }
+        }
+        for code_ref in &rule.depends_refs {
+            if let Some(unit) = unit_of(units, &mut files, code_ref) {
+                depending
+                    .entry(unit)
+                    .or_default()
+                    .push((&rule.id, code_ref));
+            }
+        }
+    }
+    let mut edges: BTreeMap<(&RuleId, &RuleId), &ApiCodeRef> = BTreeMap::new();
+    for (unit, targets) in &depending {
+        for &from in implementing.get(unit).into_iter().flatten() {
+            for &(to, at) in targets {
+                if from == to {
+                    continue;
+                }
+                let first = edges.entry((from, to)).or_insert(at);
+                if (&at.file, at.line) < (&first.file, first.line) {
+                    *first = at;
+                }
+            }
+        }
+    }
+    edges
+        .into_iter()
+        .map(|((from, to), at)| DependsEdge { from, to, at })
+        .collect()
+}
+
+/// Cycles among the `depends` edges of `rules`. Each cycle lists its rules
+/// with the first one repeated at the end, and comes with the `depends`
+/// reference that makes its first edge.
+// r[impl validation.circular-deps]
+pub(crate) fn depends_cycles<'a>(
+    rules: &[&'a ApiRule],
+    units: &BTreeMap<PathBuf, Vec<CodeUnit>>,
+) -> Vec<(Vec<RuleId>, &'a ApiCodeRef)> {
+    let mut graph: BTreeMap<&RuleId, Vec<(&RuleId, &ApiCodeRef)>> = BTreeMap::new();
+    for rule in rules {
+        graph.entry(&rule.id).or_default();
+    }
+    for edge in depends_edges(rules, units) {
+        graph.entry(edge.from).or_default().push((edge.to, edge.at));
+    }
+
+    struct Search<'g, 'a> {
+        graph: &'g BTreeMap<&'a RuleId, Vec<(&'a RuleId, &'a ApiCodeRef)>>,
+        visited: BTreeSet<&'a RuleId>,
+        /// Rules on the current path, and the references between them.
+        path: Vec<&'a RuleId>,
+        edges: Vec<&'a ApiCodeRef>,
+        cycles: Vec<(Vec<RuleId>, &'a ApiCodeRef)>,
+    }
+
+    impl<'a> Search<'_, 'a> {
+        fn dfs(&mut self, node: &'a RuleId) {
+            self.visited.insert(node);
+            self.path.push(node);
+            for &(neighbor, at) in self.graph.get(node).into_iter().flatten() {
+                if let Some(start) = self.path.iter().position(|n| *n == neighbor) {
+                    let mut cycle: Vec<RuleId> =
+                        self.path[start..].iter().map(|n| (*n).clone()).collect();
+                    cycle.push(neighbor.clone());
+                    let first = self.edges.get(start).copied().unwrap_or(at);
+                    self.cycles.push((cycle, first));
+                } else if !self.visited.contains(neighbor) {
+                    self.edges.push(at);
+                    self.dfs(neighbor);
+                    self.edges.pop();
+                }
+            }
+            self.path.pop();
+        }
+    }
+
+    let mut search = Search {
+        graph: &graph,
+        visited: BTreeSet::new(),
+        path: Vec::new(),
+        edges: Vec::new(),
+        cycles: Vec::new(),
+    };
+    for &node in graph.keys() {
+        if !search.visited.contains(node) {
+            search.dfs(node);
+        }
+    }
+    search.cycles
+}
+
+/// Build the graph of `rules`, keeping the rules under `prefix` if given.
+pub fn build(
+    spec: &str,
+    rules: &[ApiRule],
+    outline: &[OutlineEntry],
+    units: &BTreeMap<PathBuf, Vec<CodeUnit>>,
+    prefix: Option<&str>,
+) -> RuleGraph {
+    let mut graph = RuleGraph {
+        spec: spec.to_string(),
+        ..Default::default()
+    };
+
+    // Nest headings by level.
+    let mut open: Vec<(u8, usize)> = Vec::new();
+    let mut section_index: HashMap<&str, usize> = HashMap::new();
+    for entry in outline {
+        while open.last().is_some_and(|(level, _)| *level >= entry.level) {
+            open.pop();
+        }
+        let index = graph.sections.len();
+        graph.sections.push(Section {
+            slug: entry.slug.clone(),
+            title: entry.title.clone(),
+            parent: open.last().map(|(_, i)| *i),
+        });
+        section_index.insert(entry.slug.as_str(), index);
+        open.push((entry.level, index));
+    }
+
+    let kept: Vec<&ApiRule> = rules
+        .iter()
+        .filter(|r| prefix.is_none_or(|p| matches_prefix(&r.id.base, p)))
+        .collect();
+    let kept_ids: BTreeSet<&RuleId> = kept.iter().map(|r| &r.id).collect();
+    for rule in &kept {
+        graph.nodes.push(Node {
+            id: rule.id.clone(),
+            section: rule
+                .section
+                .as_deref()
+                .and_then(|slug| section_index.get(slug).copied()),
+            covered: rule.is_implemented(),
+        });
+        for related in &rule.related {
+            if kept_ids.contains(related) {
+                graph
+                    .edges
+                    .insert((rule.id.clone(), related.clone(), EdgeKind::Related));
+            }
+        }
+    }
+
+    for edge in depends_edges(&kept, units) {
+        graph
+            .edges
+            .insert((edge.from.clone(), edge.to.clone(), EdgeKind::Depends));
+    }
+
+    graph
+}
+
+impl RuleGraph {
+    /// Render the graph in the given format.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Dot => self.to_dot(),
+            Format::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Headings that contain a rule, directly or through a nested heading.
+    fn populated_sections(&self) -> Vec<bool> {
+        let mut populated = vec![false; self.sections.len()];
+        for node in &self.nodes {
+            let mut section = node.section;
+            while let Some(index) = section {
+                if populated[index] {
+                    break;
+                }
+                populated[index] = true;
+                section = self.sections[index].parent;
+            }
+        }
+        populated
+    }
+
+    /// Emit the rules and nested headings under `parent` (the top level when
+    /// `None`), depth-first in document order.
+    fn walk(
+        &self,
+        out: &mut String,
+        parent: Option<usize>,
+        indent: &str,
+        populated: &[bool],
+        emit: &dyn Fn(&mut String, &str, Visit),
+    ) {
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.section == parent {
+                emit(out, indent, Visit::Node(index));
+            }
+        }
+        for (index, section) in self.sections.iter().enumerate() {
+            if section.parent == parent && populated[index] {
+                emit(out, indent, Visit::Open(index));
+                self.walk(out, Some(index), &format!("{indent}  "), populated, emit);
+                emit(out, indent, Visit::Close);
+            }
+        }
+    }
+
+    /// Render the graph as Graphviz DOT.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph {} {{", dot_quote(&self.spec));
+        let _ = writeln!(out, "  rankdir=LR;");
+        let _ = writeln!(
+            out,
+            "  node [shape=box, style=\"rounded,filled\", fillcolor=white];"
+        );
+
+        let populated = self.populated_sections();
+        self.walk(&mut out, None, "  ", &populated, &|out, indent, visit| {
+            match visit {
+                Visit::Node(index) => {
+                    let node = &self.nodes[index];
+                    let id = dot_quote(&node.id);
+                    if node.covered {
+                        let _ = writeln!(out, "{indent}{id};");
+                    } else {
+                        let _ = writeln!(
+                            out,
+                            "{indent}{id} [fillcolor=\"{UNCOVERED_FILL}\", color=\"{UNCOVERED_STROKE}\"];"
+                        );
+                    }
+                }
+                Visit::Open(index) => {
+                    let _ = writeln!(out, "{indent}subgraph \"cluster_{index}\" {{");
+                    let _ = writeln!(
+                        out,
+                        "{indent}  label={};",
+                        dot_quote(&self.sections[index].title)
+                    );
+                }
+                Visit::Close => {
+                    let _ = writeln!(out, "{indent}}}");
+                }
+            }
+        });
+
+        for (from, to, kind) in &self.edges {
+            let style = match kind {
+                EdgeKind::Depends => "",
+                EdgeKind::Related => ", style=dashed",
+            };
+            let _ = writeln!(
+                out,
+                "  {} -> {} [label=\"{}\"{style}];",
+                dot_quote(from),
+                dot_quote(to),
+                kind.label()
+            );
+        }
+        let _ = writeln!(out, "}}");
+        out
+    }
+
+    /// Render the graph as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "flowchart LR");
+
+        let populated = self.populated_sections();
+        self.walk(&mut out, None, "  ", &populated, &|out, indent, visit| {
+            let _ = match visit {
+                Visit::Node(index) => writeln!(
+                    out,
+                    "{indent}n{index}[\"{}\"]",
+                    mermaid_escape(&self.nodes[index].id)
+                ),
+                Visit::Open(index) => writeln!(
+                    out,
+                    "{indent}subgraph s{index}[\"{}\"]",
+                    mermaid_escape(&self.sections[index].title)
+                ),
+                Visit::Close => writeln!(out, "{indent}end"),
+            };
+        });
+
+        let index_of: HashMap<&RuleId, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (&n.id, i))
+            .collect();
+        for (from, to, kind) in &self.edges {
+            let arrow = match kind {
+                EdgeKind::Depends => "-->",
+                EdgeKind::Related => "-.->",
+            };
+            let _ = writeln!(
+                out,
+                "  n{} {arrow}|{}| n{}",
+                index_of[from],
+                kind.label(),
+                index_of[to]
+            );
+        }
+
+        let uncovered: Vec<String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.covered)
+            .map(|(i, _)| format!("n{i}"))
+            .collect();
+        if !uncovered.is_empty() {
+            let _ = writeln!(
+                out,
+                "  classDef uncovered fill:{UNCOVERED_FILL},stroke:{UNCOVERED_STROKE}"
+            );
+            let _ = writeln!(out, "  class {} uncovered", uncovered.join(","));
+        }
+        out
+    }
+}
+
+fn dot_quote(s: impl std::fmt::Display) -> String {
+    let s = s.to_string();
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn mermaid_escape(s: impl std::fmt::Display) -> String {
+    let s = s.to_string();
+    s.replace('"', "#quot;")
+}
+
+/// Build the rule graph of `spec` (default: the first spec) as covered by
+/// `impl_name` (default: its first implementation).
+// r[impl cli.graph]
+pub async fn graph(
+    project_root: &Path,
+    config: &Config,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+    prefix: Option<&str>,
+) -> Result<RuleGraph> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let Some(key) = data
+        .forward_by_impl
+        .keys()
+        .find(|(s, i)| spec.is_none_or(|spec| spec == s) && impl_name.is_none_or(|name| name == i))
+        .cloned()
+    else {
+        let available: Vec<String> = data
+            .forward_by_impl
+            .keys()
+            .map(|(s, i)| format!("{s}/{i}"))
+            .collect();
+        bail!(
+            "No implementation matches{}{}. Available: {}",
+            spec.map(|s| format!(" --spec {s}")).unwrap_or_default(),
+            impl_name
+                .map(|i| format!(" --impl {i}"))
+                .unwrap_or_default(),
+            available.join(", ")
+        );
+    };
+
+    let forward = &data.forward_by_impl[&key];
+    // Spec content (and with it the heading outline) is rendered on demand.
+    let outline = match data.spec_includes_by_name.get(&key.0) {
+        Some(include) => {
+            crate::data::render_spec_content_for_impl(
+                project_root,
+                include,
+                &key.0,
+                &key.1,
+                forward,
+                data.spec_sandboxes.get(&key.0),
+            )
+            .await?
+            .outline
+        }
+        None => Vec::new(),
+    };
+    let no_units = BTreeMap::new();
+    let units = data.code_units_by_impl.get(&key).unwrap_or(&no_units);
+    Ok(build(&key.0, &forward.rules, &outline, units, prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_api::OutlineCoverage;
+    use tracey_core::code_units::CodeUnitKind;
+
+    fn id(s: &str) -> RuleId {
+        tracey_core::parse_rule_id(s).unwrap()
+    }
+
+    fn code_ref(line: usize) -> ApiCodeRef {
+        ApiCodeRef {
+            file: "src/lib.rs".to_string(),
+            line,
+            end_line: None,
+            note: None,
+            partial: false,
+        }
+    }
+
+    fn rule(base: &str, section: &str) -> ApiRule {
+        ApiRule {
+            id: id(base),
+            raw: String::new(),
+            html: String::new(),
+            status: None,
+            level: None,
+            tags: Vec::new(),
+            owner: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            section: Some(section.to_string()),
+            section_title: None,
+            section_path: Vec::new(),
+            impl_refs: Vec::new(),
+            verify_refs: Vec::new(),
+            depends_refs: Vec::new(),
+            docs_refs: Vec::new(),
+            is_stale: false,
+            stale_refs: Vec::new(),
+            related: Vec::new(),
+            fanout: None,
+            examples: Vec::new(),
+            waiver: None,
+            proof_refs: Vec::new(),
+            formally_verified: false,
+            partial: None,
+        }
+    }
+
+    fn heading(title: &str, level: u8) -> OutlineEntry {
+        let coverage = OutlineCoverage {
+            impl_count: 0,
+            verify_count: 0,
+            docs_count: 0,
+            total: 0,
+        };
+        OutlineEntry {
+            title: title.to_string(),
+            slug: title.to_lowercase(),
+            level,
+            coverage: coverage.clone(),
+            aggregated: coverage,
+        }
+    }
+
+    fn sample() -> RuleGraph {
+        // `auth.login` is implemented by a function that also depends on
+        // `auth.hash`; `auth.logout` mentions `auth.login` in its prose.
+        let mut login = rule("auth.login", "login");
+        login.impl_refs.push(code_ref(1));
+        let mut hash = rule("auth.hash", "login");
+        hash.depends_refs.push(code_ref(2));
+        let mut logout = rule("auth.logout", "auth");
+        logout.related.push(id("auth.login"));
+        let other = rule("net.retry", "net");
+
+        let units = BTreeMap::from([(
+            PathBuf::from("/project/src/lib.rs"),
+            vec![CodeUnit {
+                kind: CodeUnitKind::Function,
+                name: Some("login".to_string()),
+                file: PathBuf::from("/project/src/lib.rs"),
+                start_line: 1,
+                end_line: 5,
+                start_byte: 0,
+                end_byte: 0,
+                req_refs: vec![id("auth.login"), id("auth.hash")],
+            }],
+        )]);
+        build(
+            "api",
+            &[login, hash, logout, other],
+            &[heading("Auth", 1), heading("Login", 2), heading("Net", 1)],
+            &units,
+            Some("auth"),
+        )
+    }
+
+    #[test]
+    fn builds_edges_and_filters_by_prefix() {
+        let graph = sample();
+        let ids: Vec<String> = graph.nodes.iter().map(|n| n.id.to_string()).collect();
+        assert_eq!(ids, ["auth.login", "auth.hash", "auth.logout"]);
+        assert_eq!(
+            graph.edges,
+            BTreeSet::from([
+                (id("auth.login"), id("auth.hash"), EdgeKind::Depends),
+                (id("auth.logout"), id("auth.login"), EdgeKind::Related),
+            ])
+        );
+        assert_eq!(graph.sections[1].parent, Some(0));
+        assert!(matches_prefix("auth.login", "auth."));
+        assert!(!matches_prefix("authz.login", "auth"));
+    }
+
+    // r[verify validation.circular-deps]
+    #[test]
+    fn finds_depends_cycles_with_their_reference() {
+        // `a.one` depends on `a.two` (line 3) and `a.two` back on `a.one`
+        // (line 13); `a.three` depends on `a.one` without closing a cycle.
+        let unit = |name: &str, start_line: usize| CodeUnit {
+            kind: CodeUnitKind::Function,
+            name: Some(name.to_string()),
+            file: PathBuf::from("/project/src/lib.rs"),
+            start_line,
+            end_line: start_line + 5,
+            start_byte: 0,
+            end_byte: 0,
+            req_refs: Vec::new(),
+        };
+        let units = BTreeMap::from([(
+            PathBuf::from("/project/src/lib.rs"),
+            vec![unit("one", 1), unit("two", 11), unit("three", 21)],
+        )]);
+        let mut one = rule("a.one", "a");
+        one.impl_refs.push(code_ref(2));
+        one.depends_refs.push(code_ref(13));
+        one.depends_refs.push(code_ref(23));
+        let mut two = rule("a.two", "a");
+        two.impl_refs.push(code_ref(12));
+        two.depends_refs.push(code_ref(3));
+        let mut three = rule("a.three", "a");
+        three.impl_refs.push(code_ref(22));
+
+        let rules = [&one, &two, &three];
+        let cycles = depends_cycles(&rules, &units);
+        assert_eq!(cycles.len(), 1, "{cycles:?}");
+        let (cycle, at) = &cycles[0];
+        assert_eq!(cycle, &[id("a.one"), id("a.two"), id("a.one")]);
+        assert_eq!((at.file.as_str(), at.line), ("src/lib.rs", 3));
+
+        two.depends_refs.clear();
+        assert!(depends_cycles(&[&one, &two, &three], &units).is_empty());
+    }
+
+    // r[verify cli.graph]
+    #[test]
+    fn renders_dot_and_mermaid() {
+        let graph = sample();
+
+        let dot = graph.to_dot();
+        assert!(
+            dot.contains(
+                "  subgraph \"cluster_0\" {\n    label=\"Auth\";\n    \"auth.logout\" [fillcolor=\"#f8d7da\", color=\"#c0392b\"];\n    subgraph \"cluster_1\" {\n      label=\"Login\";\n      \"auth.login\";\n"
+            ),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("  \"auth.login\" -> \"auth.hash\" [label=\"depends\"];"),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("  \"auth.logout\" -> \"auth.login\" [label=\"related\", style=dashed];"),
+            "{dot}"
+        );
+        // Headings without kept rules are left out.
+        assert!(!dot.contains("Net"), "{dot}");
+
+        let mermaid = graph.render(Format::Mermaid);
+        assert!(
+            mermaid.contains("  subgraph s0[\"Auth\"]\n    n2[\"auth.logout\"]\n    subgraph s1[\"Login\"]\n      n0[\"auth.login\"]\n"),
+            "{mermaid}"
+        );
+        assert!(mermaid.contains("  n0 -->|depends| n1\n"), "{mermaid}");
+        assert!(mermaid.contains("  n2 -.->|related| n0\n"), "{mermaid}");
+        assert!(mermaid.contains("  class n1,n2 uncovered\n"), "{mermaid}");
+    }
+}
@@ -0,0 +1,281 @@
+//! `tracey history` and `tracey trend` implementation.
+//!
+//! `tracey history` appends a snapshot of the coverage of every spec and
+//! implementation to `.tracey/history.jsonl`, one JSON object per line, so a
+//! CI job can record one per build. `tracey trend` reads the file back and
+//! shows how coverage moved over the last few commits.
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::bump::git_capture;
+use crate::config::Config;
+use crate::server::QueryEngine;
+
+/// History file, relative to the project root.
+pub const HISTORY_FILE: &str = ".tracey/history.jsonl";
+
+/// Sparkline glyphs from 0% to 100%.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Coverage of every spec/impl pair at one point in time.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct Snapshot {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// `HEAD` when the snapshot was taken, if the project is a git checkout.
+    #[facet(default)]
+    pub commit: Option<String>,
+    pub impls: Vec<ImplSnapshot>,
+}
+
+/// Coverage of one spec/impl pair.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct ImplSnapshot {
+    pub spec: String,
+    pub impl_name: String,
+    pub total_rules: usize,
+    pub covered_rules: usize,
+    pub verified_rules: usize,
+}
+
+impl ImplSnapshot {
+    fn impl_percent(&self) -> f64 {
+        percent(self.covered_rules, self.total_rules)
+    }
+
+    fn verify_percent(&self) -> f64 {
+        percent(self.verified_rules, self.total_rules)
+    }
+}
+
+fn percent(n: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        n as f64 * 100.0 / total as f64
+    }
+}
+
+/// Path of the history file for a project.
+pub fn history_path(project_root: &Path) -> PathBuf {
+    project_root.join(HISTORY_FILE)
+}
+
+/// Measure the current coverage of the project.
+pub async fn snapshot(project_root: &Path, config: &Config) -> Result<Snapshot> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let impls = QueryEngine::new(&data)
+        .status()
+        .into_iter()
+        .map(|(spec, impl_name, stats)| ImplSnapshot {
+            spec,
+            impl_name,
+            total_rules: stats.total_rules,
+            covered_rules: stats.impl_covered,
+            verified_rules: stats.verify_covered,
+        })
+        .collect();
+    let commit = git_capture(project_root, &["rev-parse", "HEAD"])
+        .ok()
+        .map(|out| out.trim().to_string());
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    Ok(Snapshot {
+        timestamp,
+        commit,
+        impls,
+    })
+}
+
+/// Append a snapshot to the project's history file.
+// r[impl cli.history]
+pub fn append(project_root: &Path, snapshot: &Snapshot) -> Result<()> {
+    let path = history_path(project_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+    }
+    let line = facet_json::to_string(snapshot).wrap_err("failed to serialize snapshot")?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .wrap_err_with(|| format!("failed to open {}", path.display()))?;
+    writeln!(file, "{line}").wrap_err_with(|| format!("failed to write {}", path.display()))
+}
+
+/// Read every snapshot in the project's history file, oldest first.
+/// A missing file is an empty history; lines that don't parse are skipped.
+pub fn load(project_root: &Path) -> Result<Vec<Snapshot>> {
+    let path = history_path(project_root);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| facet_json::from_str(line).ok())
+        .collect())
+}
+
+/// The snapshots covering the last `commits` commits, plus the one before
+/// them as the baseline. Snapshots of the same commit count once (the last
+/// one wins); snapshots without a commit each count as their own.
+fn window(history: &[Snapshot], commits: usize) -> Vec<&Snapshot> {
+    let mut points: Vec<&Snapshot> = Vec::new();
+    for snapshot in history {
+        match points.last_mut() {
+            Some(last) if last.commit.is_some() && last.commit == snapshot.commit => {
+                *last = snapshot;
+            }
+            _ => points.push(snapshot),
+        }
+    }
+    let start = points.len().saturating_sub(commits + 1);
+    points.split_off(start)
+}
+
+fn sparkline(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|v| SPARKS[((v / 100.0 * 7.0).round() as usize).min(7)])
+        .collect()
+}
+
+fn delta(now: f64, then: Option<f64>) -> String {
+    match then {
+        Some(then) => format!("{:+.1}", now - then),
+        None => "new".to_string(),
+    }
+}
+
+/// Render coverage over the last `commits` commits as markdown.
+// r[impl cli.trend]
+pub fn render_trend(history: &[Snapshot], commits: usize) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Coverage trend");
+    let _ = writeln!(out);
+
+    let points = window(history, commits);
+    let (Some(baseline), Some(latest)) = (points.first(), points.last()) else {
+        let _ = writeln!(
+            out,
+            "No history yet. Run `tracey history` to record a snapshot in `{HISTORY_FILE}`."
+        );
+        return out;
+    };
+
+    let _ = writeln!(
+        out,
+        "| Spec | Impl | Coverage | Trend | Δ | Verified | Trend | Δ |"
+    );
+    let _ = writeln!(
+        out,
+        "|------|------|---------:|-------|--:|---------:|-------|--:|"
+    );
+    for current in &latest.impls {
+        let series: Vec<&ImplSnapshot> = points
+            .iter()
+            .filter_map(|p| {
+                p.impls
+                    .iter()
+                    .find(|i| i.spec == current.spec && i.impl_name == current.impl_name)
+            })
+            .collect();
+        let before = baseline
+            .impls
+            .iter()
+            .find(|i| i.spec == current.spec && i.impl_name == current.impl_name);
+        let impl_series: Vec<f64> = series.iter().map(|i| i.impl_percent()).collect();
+        let verify_series: Vec<f64> = series.iter().map(|i| i.verify_percent()).collect();
+        let _ = writeln!(
+            out,
+            "| {} | {} | {:.1}% | {} | {} | {:.1}% | {} | {} |",
+            current.spec,
+            current.impl_name,
+            current.impl_percent(),
+            sparkline(&impl_series),
+            delta(current.impl_percent(), before.map(|b| b.impl_percent())),
+            current.verify_percent(),
+            sparkline(&verify_series),
+            delta(current.verify_percent(), before.map(|b| b.verify_percent())),
+        );
+    }
+
+    let _ = writeln!(out);
+    let since = match &baseline.commit {
+        Some(commit) => format!("commit `{}`", &commit[..commit.len().min(8)]),
+        None => "the first snapshot shown".to_string(),
+    };
+    let _ = writeln!(
+        out,
+        "{} snapshot{}; deltas are percentage points since {since}.",
+        points.len(),
+        if points.len() == 1 { "" } else { "s" }
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(commit: &str, covered: usize, verified: usize) -> Snapshot {
+        Snapshot {
+            timestamp: 0,
+            commit: Some(commit.to_string()),
+            impls: vec![ImplSnapshot {
+                spec: "api".to_string(),
+                impl_name: "rust".to_string(),
+                total_rules: 10,
+                covered_rules: covered,
+                verified_rules: verified,
+            }],
+        }
+    }
+
+    // r[verify cli.history]
+    #[test]
+    fn appends_and_loads_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load(dir.path()).unwrap().is_empty());
+
+        append(dir.path(), &snap("aaaa", 1, 0)).unwrap();
+        append(dir.path(), &snap("bbbb", 2, 1)).unwrap();
+
+        let history = load(dir.path()).unwrap();
+        assert_eq!(history, vec![snap("aaaa", 1, 0), snap("bbbb", 2, 1)]);
+    }
+
+    // r[verify cli.trend]
+    #[test]
+    fn trend_compares_against_n_commits_back() {
+        let history = vec![
+            snap("c1", 1, 0),
+            snap("c2", 4, 1),
+            snap("c3", 5, 2),
+            // Re-run on the same commit replaces the earlier snapshot.
+            snap("c3", 6, 2),
+        ];
+        let md = render_trend(&history, 1);
+        assert!(
+            md.contains("| api | rust | 60.0% | ▄▅ | +20.0 | 20.0% | ▂▂ | +10.0 |"),
+            "{md}"
+        );
+        assert!(md.contains("since commit `c2`"), "{md}");
+
+        let md = render_trend(&history, 10);
+        assert!(md.contains("| 60.0% | ▂▄▅ | +50.0 |"), "{md}");
+
+        assert!(render_trend(&[], 10).contains("No history yet"));
+    }
+}
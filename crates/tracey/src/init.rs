@@ -0,0 +1,565 @@
+//! `tracey init` implementation.
+//!
+//! Writes a first `.config/tracey/config.styx` by looking at the repository
+//! instead of asking the user to write one by hand: markdown files with
+//! requirement markers become spec includes (one spec per marker prefix),
+//! and the source files tracey can scan become one implementation per
+//! language, with test directories in `test_include` and vendored code
+//! under the included directories excluded. The source files are then
+//! stream-extracted once so the config can say how many references each
+//! implementation already has, reporting progress as each file is done.
+
+use eyre::{Result, WrapErr, bail};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::config::styx_scalar;
+use crate::config_check::{self, ConfigCheck};
+
+/// Where `tracey init` writes the config, relative to the project root.
+pub const CONFIG_PATH: &str = ".config/tracey/config.styx";
+
+/// Directory names holding tests.
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__"];
+
+/// Directory names holding code the project does not own.
+const VENDORED_DIRS: &[&str] = &["vendor", "third_party", "node_modules", "target"];
+
+/// Implementation names for extensions that belong to the same language.
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("rust", &["rs"]),
+    ("typescript", &["ts", "tsx"]),
+    ("javascript", &["js", "jsx"]),
+    ("python", &["py"]),
+    ("c", &["c", "h"]),
+    ("cpp", &["cpp", "hpp", "cc", "cxx"]),
+    ("objc", &["m", "mm"]),
+    ("kotlin", &["kt", "kts"]),
+    ("csharp", &["cs"]),
+    ("ruby", &["rb"]),
+    ("r", &["r", "R"]),
+    ("shell", &["sh", "bash", "zsh"]),
+    ("perl", &["pl", "pm"]),
+    ("haskell", &["hs", "lhs"]),
+    ("elixir", &["ex", "exs"]),
+    ("erlang", &["erl", "hrl"]),
+    ("clojure", &["clj", "cljs", "cljc", "edn"]),
+    ("fsharp", &["fs", "fsi", "fsx"]),
+    ("ocaml", &["ml", "mli"]),
+];
+
+fn language(ext: &str) -> &str {
+    LANGUAGES
+        .iter()
+        .find(|(_, exts)| exts.contains(&ext))
+        .map_or(ext, |(name, _)| name)
+}
+
+/// Markdown files defining rules under one marker prefix.
+#[derive(Debug, Default, PartialEq)]
+pub struct DetectedSpec {
+    pub prefix: String,
+    /// Relative to the project root.
+    pub files: Vec<PathBuf>,
+}
+
+/// Source files of one language.
+#[derive(Debug, Default, PartialEq)]
+pub struct DetectedImpl {
+    pub language: String,
+    pub files: usize,
+    pub test_files: usize,
+    /// Requirement references already in those files.
+    pub references: usize,
+    pub include: Vec<String>,
+    pub test_include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+/// What `tracey init` found in the repository.
+#[derive(Debug, Default)]
+pub struct Detection {
+    pub project: String,
+    pub specs: Vec<DetectedSpec>,
+    /// Most files first.
+    pub impls: Vec<DetectedImpl>,
+}
+
+/// Prefix of the requirement marker starting this line, e.g. `r` for
+/// `r[auth.login]`.
+fn marker_prefix(line: &str) -> Option<&str> {
+    let bracket = line.find('[')?;
+    let prefix = &line[..bracket];
+    let id = line[bracket + 1..].split(']').next()?;
+    let valid_prefix = !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    let valid_id = id.contains('.')
+        && id
+            .split([' ', '+'])
+            .next()
+            .is_some_and(|id| id.chars().all(|c| c.is_alphanumeric() || "._-".contains(c)));
+    (valid_prefix && valid_id && line[bracket..].contains(']')).then_some(prefix)
+}
+
+/// Marker prefixes used by rule definitions in a markdown file, ignoring
+/// fenced code blocks.
+fn marker_prefixes(content: &str) -> BTreeSet<String> {
+    let mut prefixes = BTreeSet::new();
+    let mut fenced = false;
+    for line in content.lines() {
+        let line = line.trim_start();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            fenced = !fenced;
+        } else if !fenced && let Some(prefix) = marker_prefix(line) {
+            prefixes.insert(prefix.to_string());
+        }
+    }
+    prefixes
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Glob for every file with one of `exts` under `dir`, e.g. `src/**/*.rs`.
+fn glob(dir: &str, exts: &BTreeSet<String>) -> String {
+    let exts = if exts.len() == 1 {
+        exts.iter().next().cloned().unwrap_or_default()
+    } else {
+        format!("{{{}}}", exts.iter().cloned().collect::<Vec<_>>().join(","))
+    };
+    if dir.is_empty() {
+        format!("*.{exts}")
+    } else {
+        format!("{dir}/**/*.{exts}")
+    }
+}
+
+/// Directories holding the spec files, without those nested in another one.
+fn spec_includes(files: &[PathBuf]) -> Vec<String> {
+    let mut dirs: BTreeSet<String> = BTreeSet::new();
+    let mut loose = Vec::new();
+    for file in files {
+        match file.parent().map(slash_path).filter(|d| !d.is_empty()) {
+            Some(dir) => {
+                dirs.insert(dir);
+            }
+            None => loose.push(slash_path(file)),
+        }
+    }
+    let outer: Vec<&String> = dirs
+        .iter()
+        .filter(|dir| {
+            !dirs
+                .iter()
+                .any(|other| other != *dir && dir.starts_with(&format!("{other}/")))
+        })
+        .collect();
+    outer
+        .into_iter()
+        .map(|dir| format!("{dir}/**/*.md"))
+        .chain(loose)
+        .collect()
+}
+
+#[derive(Default)]
+struct LanguageFiles {
+    files: usize,
+    test_files: usize,
+    /// Every source and test file outside vendored directories.
+    paths: Vec<PathBuf>,
+    /// Directory glob prefix, and the extensions seen under it.
+    include: BTreeMap<String, BTreeSet<String>>,
+    test_include: BTreeMap<String, BTreeSet<String>>,
+    exclude: BTreeSet<String>,
+}
+
+/// Count the references already in each language's files, streaming the
+/// extraction so `progress` hears of every file done, with the total.
+fn count_references(
+    languages: &BTreeMap<String, LanguageFiles>,
+    progress: &(dyn Fn(usize, usize) + Sync),
+) -> Result<BTreeMap<String, usize>> {
+    let total = languages.values().map(|files| files.paths.len()).sum();
+    let scanned = AtomicUsize::new(0);
+    let mut counts = BTreeMap::new();
+    for (language, files) in languages {
+        let references = AtomicUsize::new(0);
+        tracey_core::Reqs::extract_streaming(
+            tracey_core::PathSources::new(files.paths.iter().cloned()),
+            |file| {
+                references.fetch_add(file.reqs.references.len(), Ordering::Relaxed);
+                progress(scanned.fetch_add(1, Ordering::Relaxed) + 1, total);
+            },
+        )
+        .wrap_err_with(|| format!("failed to scan the {language} files"))?;
+        counts.insert(language.clone(), references.into_inner());
+    }
+    Ok(counts)
+}
+
+/// Look at the repository's markdown and source files, honoring `.gitignore`.
+/// `progress` is called with the number of source files scanned so far and
+/// their total.
+pub fn detect(project_root: &Path, progress: &(dyn Fn(usize, usize) + Sync)) -> Result<Detection> {
+    let mut spec_files: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut languages: BTreeMap<String, LanguageFiles> = BTreeMap::new();
+
+    let walker = ignore::WalkBuilder::new(project_root)
+        .hidden(true)
+        .git_ignore(true)
+        .build();
+    for entry in walker {
+        let entry = entry.wrap_err("failed to walk the project")?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(project_root) else {
+            continue;
+        };
+        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+
+        if ext == "md" {
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            for prefix in marker_prefixes(&content) {
+                spec_files
+                    .entry(prefix)
+                    .or_default()
+                    .push(relative.to_path_buf());
+            }
+            continue;
+        }
+        if !tracey_core::is_supported_extension(std::ffi::OsStr::new(ext)) {
+            continue;
+        }
+
+        let dirs: Vec<String> = relative
+            .parent()
+            .into_iter()
+            .flat_map(|p| p.components())
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let files = languages.entry(language(ext).to_string()).or_default();
+        if let Some(vendored) = dirs
+            .iter()
+            .position(|d| VENDORED_DIRS.contains(&d.as_str()))
+        {
+            // Top-level vendored directories are left out of every include.
+            if vendored > 0 {
+                files
+                    .exclude
+                    .insert(format!("{}/**", dirs[..=vendored].join("/")));
+            }
+            continue;
+        }
+        files.paths.push(path.to_path_buf());
+        let top = dirs.first().cloned().unwrap_or_default();
+        match dirs.iter().position(|d| TEST_DIRS.contains(&d.as_str())) {
+            Some(0) => {
+                files.test_files += 1;
+                files
+                    .test_include
+                    .entry(top)
+                    .or_default()
+                    .insert(ext.to_string());
+            }
+            Some(i) => {
+                files.test_files += 1;
+                files
+                    .test_include
+                    .entry(format!("{top}/**/{}", dirs[i]))
+                    .or_default()
+                    .insert(ext.to_string());
+                files
+                    .include
+                    .entry(top)
+                    .or_default()
+                    .insert(ext.to_string());
+            }
+            None => {
+                files.files += 1;
+                files
+                    .include
+                    .entry(top)
+                    .or_default()
+                    .insert(ext.to_string());
+            }
+        }
+    }
+
+    languages.retain(|_, files| !files.include.is_empty());
+    let references = count_references(&languages, progress)?;
+    let mut impls: Vec<DetectedImpl> = languages
+        .into_iter()
+        .map(|(language, files)| DetectedImpl {
+            references: references[&language],
+            language,
+            files: files.files,
+            test_files: files.test_files,
+            include: files.include.iter().map(|(d, e)| glob(d, e)).collect(),
+            test_include: files.test_include.iter().map(|(d, e)| glob(d, e)).collect(),
+            exclude: files.exclude.into_iter().collect(),
+        })
+        .collect();
+    impls.sort_by_key(|i| Reverse(i.files + i.test_files));
+
+    let project = project_root
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "my-project".to_string());
+    Ok(Detection {
+        project,
+        specs: spec_files
+            .into_iter()
+            .map(|(prefix, files)| DetectedSpec { prefix, files })
+            .collect(),
+        impls,
+    })
+}
+
+fn write_list(out: &mut String, indent: &str, key: &str, patterns: &[String]) {
+    if patterns.is_empty() {
+        return;
+    }
+    let items: Vec<_> = patterns.iter().map(|p| styx_scalar(p)).collect();
+    let _ = writeln!(out, "{indent}{key} ({})", items.join(" "));
+}
+
+impl Detection {
+    /// Render the proposed config, with comments saying where each part
+    /// came from.
+    pub fn to_styx(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "@schema {{id crate:tracey-config@1, cli tracey}}");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "// Generated by `tracey init`. Check it with `tracey config check`;"
+        );
+        let _ = writeln!(
+            out,
+            "// see https://tracey.bearcove.eu/guide/configuration/ for every field."
+        );
+        let _ = writeln!(out, "specs (");
+
+        let placeholder = [DetectedSpec {
+            prefix: "r".to_string(),
+            files: Vec::new(),
+        }];
+        let specs: &[DetectedSpec] = if self.specs.is_empty() {
+            &placeholder
+        } else {
+            &self.specs
+        };
+        for spec in specs {
+            let name = if specs.len() == 1 {
+                self.project.clone()
+            } else {
+                format!("{}-{}", self.project, spec.prefix)
+            };
+            let _ = writeln!(out, "    {{");
+            let _ = writeln!(out, "        name {}", styx_scalar(&name));
+            if spec.files.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "        // No markdown file defines rules yet: write them here as"
+                );
+                let _ = writeln!(
+                    out,
+                    "        // `r[area.rule-name]` followed by the rule text."
+                );
+                write_list(
+                    &mut out,
+                    "        ",
+                    "include",
+                    &["docs/spec/**/*.md".to_string()],
+                );
+            } else {
+                let _ = writeln!(
+                    out,
+                    "        // {} markdown file(s) with `{}[...]` rule markers",
+                    spec.files.len(),
+                    spec.prefix
+                );
+                write_list(&mut out, "        ", "include", &spec_includes(&spec.files));
+            }
+            let _ = writeln!(out, "        impls (");
+            if self.impls.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "            // No source files found: point this at your code."
+                );
+                let _ = writeln!(out, "            {{");
+                let _ = writeln!(out, "                name main");
+                write_list(
+                    &mut out,
+                    "                ",
+                    "include",
+                    &["src/**/*.rs".to_string()],
+                );
+                let _ = writeln!(out, "            }}");
+            }
+            for imp in &self.impls {
+                let _ = writeln!(out, "            {{");
+                let _ = writeln!(
+                    out,
+                    "                // {} source and {} test file(s), {} reference(s) so far",
+                    imp.files, imp.test_files, imp.references
+                );
+                let _ = writeln!(out, "                name {}", styx_scalar(&imp.language));
+                write_list(&mut out, "                ", "include", &imp.include);
+                write_list(&mut out, "                ", "exclude", &imp.exclude);
+                write_list(
+                    &mut out,
+                    "                ",
+                    "test_include",
+                    &imp.test_include,
+                );
+                let _ = writeln!(out, "            }}");
+            }
+            let _ = writeln!(out, "        )");
+            let _ = writeln!(out, "    }}");
+        }
+        let _ = writeln!(out, ")");
+        out
+    }
+}
+
+/// Outcome of `tracey init`.
+#[derive(Debug)]
+pub struct Init {
+    pub detection: Detection,
+    pub source: String,
+    /// Findings of `tracey config check` on the proposed config.
+    pub check: ConfigCheck,
+}
+
+/// Propose a config for the project and check it; see [`detect`] for
+/// `progress`.
+// r[impl cli.init]
+pub fn propose(project_root: &Path, progress: &(dyn Fn(usize, usize) + Sync)) -> Result<Init> {
+    let detection = detect(project_root, progress)?;
+    let source = detection.to_styx();
+    let check = config_check::check(project_root, CONFIG_PATH, &source);
+    Ok(Init {
+        detection,
+        source,
+        check,
+    })
+}
+
+/// Write the proposed config, refusing to replace an existing one unless
+/// `force` is set. Returns the path written.
+pub fn write(project_root: &Path, init: &Init, force: bool) -> Result<PathBuf> {
+    let path = project_root.join(CONFIG_PATH);
+    if path.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            path.display()
+        );
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .wrap_err_with(|| format!("failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&path, &init.source)
+        .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn detects_rule_markers_outside_code_blocks() {
+        assert_eq!(marker_prefix("r[auth.login]"), Some("r"));
+        assert_eq!(marker_prefix("h2[frame.size+2 level=must]"), Some("h2"));
+        assert_eq!(marker_prefix("[link](url)"), None);
+        assert_eq!(marker_prefix("see[^note]"), None);
+        let prefixes = marker_prefixes("r[a.b]\nText.\n\n```\nx[c.d]\n```\n");
+        assert_eq!(prefixes, BTreeSet::from(["r".to_string()]));
+    }
+
+    // r[verify cli.init]
+    #[tokio::test]
+    async fn proposes_a_config_that_loads_and_validates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write_file(
+            root,
+            "docs/spec/auth.md",
+            "# Auth\n\nr[auth.login]\nUsers MUST log in.\n",
+        );
+        write_file(root, "docs/guide.md", "# Guide\n\nNo rules here.\n");
+        write_file(
+            root,
+            "src/lib.rs",
+            "// r[impl auth.login]\npub fn login() {}\n",
+        );
+        write_file(root, "src/vendor/dep.rs", "pub fn dep() {}\n");
+        write_file(
+            root,
+            "tests/login.rs",
+            "// r[verify auth.login]\n#[test]\nfn login() {}\n",
+        );
+        write_file(root, "web/app.ts", "export const x = 1;\n");
+        write_file(root, "web/view.tsx", "export const y = 2;\n");
+
+        let scanned = AtomicUsize::new(0);
+        let init = propose(root, &|done, total| {
+            assert!(done <= total);
+            scanned.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+        assert_eq!(scanned.into_inner(), 4);
+        assert_eq!(init.detection.specs.len(), 1);
+        assert_eq!(
+            init.detection.specs[0].files,
+            vec![PathBuf::from("docs/spec/auth.md")]
+        );
+        let rust = &init.detection.impls[0];
+        assert_eq!(rust.language, "rust");
+        assert_eq!(rust.include, vec!["src/**/*.rs"]);
+        assert_eq!(rust.exclude, vec!["src/vendor/**"]);
+        assert_eq!(rust.test_include, vec!["tests/**/*.rs"]);
+        assert_eq!((rust.files, rust.test_files, rust.references), (1, 1, 2));
+        assert!(
+            init.source
+                .contains("// 1 source and 1 test file(s), 2 reference(s) so far")
+        );
+        assert_eq!(init.detection.impls[1].include, vec!["web/**/*.{ts,tsx}"]);
+        assert_eq!(init.check.errors(), 0, "{}", init.source);
+
+        let path = write(root, &init, false).unwrap();
+        assert!(write(root, &init, false).is_err());
+        let config = crate::load_config(&path).unwrap();
+        assert_eq!(config.specs[0].include, vec!["docs/spec/**/*.md"]);
+        let data = crate::data::build_dashboard_data(root, &config, 0, true)
+            .await
+            .unwrap();
+        let key = (config.specs[0].name.clone(), "rust".to_string());
+        let rule = &data.forward_by_impl[&key].rules[0];
+        assert!(!rule.impl_refs.is_empty() && !rule.verify_refs.is_empty());
+    }
+}
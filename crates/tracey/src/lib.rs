@@ -3,16 +3,49 @@
 //! This library exposes the core functionality of tracey for testing
 //! and embedding purposes.
 
+pub mod about;
+pub mod adopt;
+pub mod badge;
 pub mod bridge;
 pub mod bump;
+pub mod check;
+pub mod commit_msg;
 pub mod config;
+pub mod config_check;
+pub mod contributors;
 pub mod daemon;
 pub mod data;
+pub mod diff_coverage;
+pub mod examples;
+pub mod extract;
+pub mod files;
+pub mod fmt_check;
+pub mod graph;
+pub mod history;
+pub mod init;
+pub mod links;
+pub mod output;
+pub mod owners;
+pub mod params;
+pub mod patch;
+pub mod policy;
+pub mod proofs;
+pub mod release;
+pub mod remote_cache;
+pub mod rename;
+pub mod review;
 pub(crate) mod rule_suggestions;
+pub mod sandbox;
+pub mod scaffold;
 pub mod sdoc;
 pub mod search;
+pub mod selftest;
 pub mod server;
+pub mod spec_diff;
+pub mod spec_lint;
+pub mod templates;
 pub mod vite;
+pub mod waivers;
 
 use config::Config;
 use eyre::{Result, WrapErr};
@@ -35,6 +68,63 @@ pub struct ExtractedRule {
     pub section: Option<String>,
     /// Section title (heading text) that this rule belongs to
     pub section_title: Option<String>,
+    /// Titles of the headings enclosing this rule, outermost first
+    pub section_path: Vec<String>,
+    /// `e[...]` examples of this rule from the file that defines it
+    pub examples: Vec<examples::RuleExample>,
+    /// Team or person owning the rule, from an `owner=` attribute on its
+    /// marker or the config's owners file
+    pub owner: Option<String>,
+    /// Fan-out from a `fanout=` attribute on its marker; the spec config's
+    /// `fanout` map applies when this is `None`
+    pub fanout: Option<config::Fanout>,
+    /// Parameters from the other attributes on its marker (`size=16384`)
+    pub params: std::collections::BTreeMap<String, String>,
+}
+
+/// Heading a rule sits under, as found while walking a rendered document
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RuleSection {
+    pub slug: Option<String>,
+    pub title: Option<String>,
+    /// Enclosing heading titles, outermost first
+    pub path: Vec<String>,
+}
+
+/// Map each rule ID in a document to the heading it sits under and the chain
+/// of headings above that one (e.g. `["Transport", "Flow Control"]`).
+// r[impl markdown.sections]
+pub(crate) fn rule_sections(
+    elements: &[marq::DocElement],
+) -> std::collections::HashMap<String, RuleSection> {
+    use marq::DocElement;
+    let mut sections = std::collections::HashMap::new();
+    // (level, slug, title) of each open heading, outermost first
+    let mut open: Vec<(u8, String, String)> = Vec::new();
+    for element in elements {
+        match element {
+            DocElement::Heading(h) => {
+                while open.last().is_some_and(|(level, _, _)| *level >= h.level) {
+                    open.pop();
+                }
+                open.push((h.level, h.id.clone(), h.title.clone()));
+            }
+            DocElement::Req(r) => {
+                if let Some((_, slug, title)) = open.last() {
+                    sections.insert(
+                        r.id.to_string(),
+                        RuleSection {
+                            slug: Some(slug.clone()),
+                            title: Some(title.clone()),
+                            path: open.iter().map(|(_, _, title)| title.clone()).collect(),
+                        },
+                    );
+                }
+            }
+            DocElement::Paragraph(_) => {}
+        }
+    }
+    sections
 }
 
 /// Compute 1-indexed column from byte offset in content
@@ -58,6 +148,132 @@ fn extract_marker_prefix(content: &str, marker_span: marq::SourceSpan) -> Option
     Some(prefix.to_string())
 }
 
+/// Marker attributes marq reads itself. A marker carrying any other
+/// attribute, or a quoted value, is not a rule to marq.
+const MARQ_ATTRIBUTES: &[&str] = &["status", "level", "since", "until", "tags"];
+
+/// The rule marker marq reported at `marker_span`, with its attributes as
+/// tracey parses them (quoted values, continuation lines, and keys marq does
+/// not know).
+// r[impl markdown.syntax.attributes]
+pub(crate) fn rule_marker_at(
+    content: &str,
+    marker_span: marq::SourceSpan,
+) -> Option<tracey_core::markdown::edit::RuleMarker> {
+    let rest = content.get(marker_span.offset..)?;
+    // A marker never reaches past the end of its paragraph.
+    let end = rest.find("\n\n").map_or(rest.len(), |at| at + 1);
+    tracey_core::markdown::edit::find_rule_markers(&rest[..end])
+        .into_iter()
+        .next()
+}
+
+/// Attributes of the rule marker at `marker_span` that marq does not read,
+/// by key.
+pub(crate) fn marker_attributes(
+    content: &str,
+    marker_span: marq::SourceSpan,
+) -> std::collections::BTreeMap<String, String> {
+    rule_marker_at(content, marker_span)
+        .map(|marker| marker.attributes)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|attr| !MARQ_ATTRIBUTES.contains(&attr.key.as_str()))
+        .map(|attr| (attr.key, attr.value))
+        .collect()
+}
+
+/// Marker attributes tracey itself gives a meaning to.
+const TRACEY_ATTRIBUTES: &[&str] = &["owner", "fanout"];
+
+/// Parameters declared on the rule marker at `marker_span`: the attributes
+/// neither marq nor tracey give a meaning to, such as `size=16384`.
+// r[impl markdown.syntax.params]
+pub(crate) fn marker_params(
+    content: &str,
+    marker_span: marq::SourceSpan,
+) -> std::collections::BTreeMap<String, String> {
+    let mut params = marker_attributes(content, marker_span);
+    params.retain(|key, _| !TRACEY_ATTRIBUTES.contains(&key.as_str()));
+    params
+}
+
+/// Value of the `owner=` attribute on the rule marker at `marker_span`.
+// r[impl markdown.syntax.owner]
+pub(crate) fn marker_owner(content: &str, marker_span: marq::SourceSpan) -> Option<String> {
+    marker_attributes(content, marker_span).remove("owner")
+}
+
+/// Fan-out set by the `fanout=` attribute on the rule marker at
+/// `marker_span`, overriding the spec config's `fanout` map.
+// r[impl markdown.syntax.fanout]
+pub(crate) fn marker_fanout(
+    content: &str,
+    marker_span: marq::SourceSpan,
+) -> Result<Option<config::Fanout>, String> {
+    marker_attributes(content, marker_span)
+        .remove("fanout")
+        .map(|value| value.parse())
+        .transpose()
+}
+
+/// `markdown` with its rule markers rewritten into the subset marq parses,
+/// keeping every byte offset and line break: attributes marq does not know
+/// are blanked out, and quoted values of the ones it does are unquoted in
+/// place. Attributes tracey reads itself are read back from the original
+/// source with [`rule_marker_at`].
+// r[impl markdown.syntax.attributes]
+pub(crate) fn marq_markers(markdown: &str) -> std::borrow::Cow<'_, str> {
+    let mut rewritten: Option<String> = None;
+    for marker in tracey_core::markdown::edit::find_rule_markers(markdown) {
+        let original = &markdown[marker.span.clone()];
+        let mut text = original.to_string();
+        for attr in &marker.attributes {
+            let known = MARQ_ATTRIBUTES.contains(&attr.key.as_str());
+            if known && !attr.is_quoted(markdown) {
+                continue;
+            }
+            let len = attr.span.len();
+            let mut token = String::new();
+            if known {
+                token = format!("{}=", attr.key);
+                token.extend(
+                    attr.value
+                        .chars()
+                        .filter(|c| !c.is_whitespace() && *c != ']'),
+                );
+            }
+            while token.len() < len {
+                token.push(' ');
+            }
+            let start = attr.span.start - marker.span.start;
+            text.replace_range(start..start + len, &token);
+        }
+        // marq joins the lines of a marker with a space, except after two
+        // trailing spaces, which markdown reads as a hard line break. Move
+        // trailing whitespace past the line break, where it is indentation.
+        if text.contains('\n') {
+            text = text
+                .split_inclusive('\n')
+                .map(|line| {
+                    let body = line.trim_end_matches(['\n', '\r']);
+                    let kept = body.trim_end_matches([' ', '\t']);
+                    format!("{kept}{}{}", &line[body.len()..], &body[kept.len()..])
+                })
+                .collect();
+        }
+        if text != original {
+            rewritten
+                .get_or_insert_with(|| markdown.to_string())
+                .replace_range(marker.span.clone(), &text);
+        }
+    }
+    rewritten.map_or(
+        std::borrow::Cow::Borrowed(markdown),
+        std::borrow::Cow::Owned,
+    )
+}
+
 /// Load rules from markdown files matching a glob pattern.
 ///
 /// marq implements markdown rule extraction:
@@ -184,7 +400,8 @@ pub async fn load_rules_from_glob(
             continue;
         }
 
-        let doc = render(&content, &RenderOptions::default())
+        let (markdown, mut examples) = examples::extract(&content);
+        let doc = render(&markdown, &RenderOptions::default())
             .await
             .map_err(|e| eyre::eyre!("Failed to process {}: {}", path.display(), e))?;
 
@@ -213,29 +430,7 @@ pub async fn load_rules_from_glob(
                 seen_ids.insert(req_id);
             }
 
-            // Build a mapping from rule ID to section info by processing elements in order
-            use marq::DocElement;
-            use std::collections::HashMap;
-            let mut rule_sections: HashMap<String, (Option<String>, Option<String>)> =
-                HashMap::new();
-            let mut current_section: Option<(String, String)> = None; // (slug, title)
-
-            for element in &doc.elements {
-                match element {
-                    DocElement::Heading(h) => {
-                        current_section = Some((h.id.clone(), h.title.clone()));
-                    }
-                    DocElement::Req(r) => {
-                        if let Some((slug, title)) = &current_section {
-                            rule_sections.insert(
-                                r.id.to_string(),
-                                (Some(slug.clone()), Some(title.clone())),
-                            );
-                        }
-                    }
-                    DocElement::Paragraph(_) => {}
-                }
-            }
+            let mut rule_sections = rule_sections(&doc.elements);
 
             // Add requirements with their source file, computed column, and section
             for req in doc.reqs {
@@ -247,16 +442,33 @@ pub async fn load_rules_from_glob(
                         req.line
                     )
                 })?;
-                let (section, section_title) = rule_sections
+                let section = rule_sections
                     .remove(&req.id.to_string())
-                    .unwrap_or((None, None));
+                    .unwrap_or_default();
+                let (own, rest) = examples.into_iter().partition(|e| e.rule == req.id.base);
+                examples = rest;
+                let owner = marker_owner(&content, req.marker_span);
+                let fanout = marker_fanout(&content, req.marker_span).map_err(|e| {
+                    eyre::eyre!(
+                        "Invalid fanout for {} in {} at line {}: {e}",
+                        req.id,
+                        display_path,
+                        req.line
+                    )
+                })?;
+                let params = marker_params(&content, req.marker_span);
                 rules.push(ExtractedRule {
                     def: req,
                     source_file: display_path.clone(),
                     prefix,
                     column,
-                    section,
-                    section_title,
+                    section: section.slug,
+                    section_title: section.title,
+                    section_path: section.path,
+                    examples: own,
+                    owner,
+                    fanout,
+                    params,
                 });
             }
         }
@@ -299,18 +511,67 @@ pub async fn load_rules_from_globs(
     Ok(all_rules)
 }
 
-/// Walk upward from `start` looking for a directory containing a `Cargo.toml`.
+/// Marker file that pins the project root to the directory containing it.
+pub const ROOT_MARKER: &str = ".tracey-root";
+
+/// Where a project keeps its tracey config, relative to its root.
+const CONFIG_PATH: &str = ".config/tracey/config.styx";
+
+/// Find the project root for `start` by walking upward through its ancestors.
+///
+/// In order of precedence:
+/// 1. the nearest directory containing a `.tracey-root` marker,
+/// 2. the nearest directory containing `.config/tracey/config.styx`, or the
+///    root named by that config's `root` key,
+/// 3. the outermost directory whose `Cargo.toml` declares a `[workspace]`,
+/// 4. the nearest directory containing a `Cargo.toml`.
+///
 /// Falls back to `start` itself if none is found.
+// r[impl daemon.root.detect]
 pub fn find_project_root_from(start: &std::path::Path) -> PathBuf {
-    let mut current = start.to_path_buf();
-    loop {
-        if current.join("Cargo.toml").exists() {
-            return current;
+    let mut outermost_workspace = None;
+    let mut nearest_manifest = None;
+
+    for dir in start.ancestors() {
+        // r[impl daemon.root.marker]
+        if dir.join(ROOT_MARKER).is_file() {
+            return dir.to_path_buf();
         }
-        if !current.pop() {
-            return start.to_path_buf();
+        // r[impl config.root]
+        if dir.join(CONFIG_PATH).is_file() {
+            return configured_root(dir).unwrap_or_else(|| dir.to_path_buf());
+        }
+        let manifest = dir.join("Cargo.toml");
+        if manifest.is_file() {
+            nearest_manifest.get_or_insert(dir);
+            if is_cargo_workspace(&manifest) {
+                outermost_workspace = Some(dir);
+            }
         }
     }
+
+    outermost_workspace
+        .or(nearest_manifest)
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or_else(|| start.to_path_buf())
+}
+
+/// The root pinned by the `root` key of `dir`'s tracey config, if any.
+fn configured_root(dir: &std::path::Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(dir.join(CONFIG_PATH)).ok()?;
+    let config: Config = facet_styx::from_str(&content).ok()?;
+    let root = dir.join(config.root?);
+    Some(root.canonicalize().unwrap_or(root))
+}
+
+/// Whether the manifest at `path` declares a Cargo workspace.
+fn is_cargo_workspace(path: &std::path::Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| {
+        content.lines().any(|line| {
+            let line = line.trim();
+            line == "[workspace]" || line.starts_with("[workspace.")
+        })
+    })
 }
 
 pub fn find_project_root() -> Result<PathBuf> {
@@ -344,6 +605,9 @@ pub fn load_config(path: &PathBuf) -> Result<Config> {
 
     let config: Config = facet_styx::from_str(&content)
         .wrap_err_with(|| format!("Failed to parse config file: {}", path.display()))?;
+    let config = config.resolve_defaults();
+    config::validate(&config)
+        .wrap_err_with(|| format!("Invalid config file: {}", path.display()))?;
 
     Ok(config)
 }
@@ -357,7 +621,212 @@ pub fn load_config_or_default(path: &PathBuf) -> Config {
     }
 
     match std::fs::read_to_string(path) {
-        Ok(content) => facet_styx::from_str(&content).unwrap_or_default(),
+        Ok(content) => facet_styx::from_str::<Config>(&content)
+            .map(Config::resolve_defaults)
+            .unwrap_or_default(),
         Err(_) => Config::default(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write(path: &std::path::Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    // r[verify daemon.root.detect]
+    #[test]
+    fn project_root_prefers_outermost_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        write(
+            &root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            &root.join("crates/a/Cargo.toml"),
+            "[package]\nname = \"a\"\n",
+        );
+        let src = root.join("crates/a/src");
+        fs::create_dir_all(&src).unwrap();
+
+        assert_eq!(find_project_root_from(&src), root);
+    }
+
+    // r[verify daemon.root.detect]
+    #[test]
+    fn project_root_falls_back_to_nearest_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        write(&root.join("a/Cargo.toml"), "[package]\nname = \"a\"\n");
+        let src = root.join("a/src");
+        fs::create_dir_all(&src).unwrap();
+
+        assert_eq!(find_project_root_from(&src), root.join("a"));
+    }
+
+    // r[verify daemon.root.marker]
+    #[test]
+    fn project_root_marker_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        write(&root.join("Cargo.toml"), "[workspace]\n");
+        write(
+            &root.join("crates/a/Cargo.toml"),
+            "[package]\nname = \"a\"\n",
+        );
+        write(&root.join("crates/a").join(ROOT_MARKER), "");
+
+        assert_eq!(
+            find_project_root_from(&root.join("crates/a")),
+            root.join("crates/a")
+        );
+    }
+
+    // r[verify config.root]
+    #[test]
+    fn project_root_pinned_by_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        write(&root.join("Cargo.toml"), "[workspace]\n");
+        write(&root.join("nested/Cargo.toml"), "[package]\nname = \"n\"\n");
+        write(
+            &root.join("nested/.config/tracey/config.styx"),
+            "root .\nspecs ()\n",
+        );
+        let src = root.join("nested/src");
+        fs::create_dir_all(&src).unwrap();
+
+        assert_eq!(find_project_root_from(&src), root.join("nested"));
+    }
+
+    // r[verify daemon.root.detect]
+    #[test]
+    fn project_root_prefers_nearest_config_over_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        write(&root.join("Cargo.toml"), "[workspace]\n");
+        write(&root.join(".config/tracey/config.styx"), "specs ()\n");
+        write(
+            &root.join("vendor/inner/Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            &root.join("vendor/inner/crates/b/Cargo.toml"),
+            "[package]\nname = \"b\"\n",
+        );
+        write(
+            &root.join("vendor/inner/.config/tracey/config.styx"),
+            "specs ()\n",
+        );
+        let inner_src = root.join("vendor/inner/crates/b/src");
+        fs::create_dir_all(&inner_src).unwrap();
+        let outer_src = root.join("src");
+        fs::create_dir_all(&outer_src).unwrap();
+
+        // The nested workspace keeps its own config...
+        assert_eq!(
+            find_project_root_from(&inner_src),
+            root.join("vendor/inner")
+        );
+        // ...and the outer one is found from outside it.
+        assert_eq!(find_project_root_from(&outer_src), root);
+
+        // Without a config of its own, a nested crate belongs to the
+        // nearest configured project rather than an unconfigured outer
+        // workspace.
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        write(&root.join("Cargo.toml"), "[workspace]\n");
+        write(&root.join("app/Cargo.toml"), "[workspace]\n");
+        write(&root.join("app/.config/tracey/config.styx"), "specs ()\n");
+        write(
+            &root.join("app/lib/Cargo.toml"),
+            "[package]\nname = \"l\"\n",
+        );
+        let src = root.join("app/lib/src");
+        fs::create_dir_all(&src).unwrap();
+        assert_eq!(find_project_root_from(&src), root.join("app"));
+    }
+    // r[verify markdown.syntax.attributes]
+    #[tokio::test]
+    async fn marker_attributes_may_be_quoted_and_span_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let spec = "# Spec\n\n\
+             r[net.retry status=\"draft\" tags=\"wire, retry\"\n    owner=\"Network team\"]\n\
+             Clients SHOULD retry.\n\n\
+             > r[net.backoff owner=\"a ] b\"]\n\
+             > Clients MUST back off.\n\n\
+             r[net.timeout owner=ops\n    status=stable]\n\
+             Clients MAY time out.\n";
+        write(&root.join("spec.md"), spec);
+
+        let markdown = marq_markers(spec);
+        assert_eq!(markdown.len(), spec.len());
+        assert_eq!(markdown.lines().count(), spec.lines().count());
+
+        let rules = load_rules_from_glob(root, "spec.md", true).await.unwrap();
+        let retry_text = rules[0].def.raw.trim().to_string();
+        let rules: Vec<_> = rules
+            .iter()
+            .map(|r| {
+                (
+                    r.def.id.base.as_str(),
+                    r.def.metadata.status.map(|s| s.as_str()),
+                    r.def.metadata.tags.clone(),
+                    r.owner.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                (
+                    "net.retry",
+                    Some("draft"),
+                    vec!["wire".to_string(), "retry".to_string()],
+                    Some("Network team"),
+                ),
+                ("net.backoff", None, vec![], Some("a ] b")),
+                ("net.timeout", Some("stable"), vec![], Some("ops")),
+            ]
+        );
+        assert_eq!(retry_text, "Clients SHOULD retry.");
+    }
+    // r[verify markdown.syntax.fanout]
+    #[tokio::test]
+    async fn marker_fanout_is_parsed_and_checked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write(
+            &root.join("spec.md"),
+            "r[api.auth fanout=\"per-file:src/api/*.rs\"]\nEndpoints MUST check auth.\n\n\
+             r[api.log]\nRequests MUST be logged.\n",
+        );
+        let rules = load_rules_from_glob(root, "spec.md", true).await.unwrap();
+        let fanouts: Vec<_> = rules
+            .iter()
+            .map(|r| r.fanout.as_ref().map(ToString::to_string))
+            .collect();
+        assert_eq!(fanouts, [Some("per-file:src/api/*.rs".to_string()), None]);
+
+        write(
+            &root.join("spec.md"),
+            "r[api.auth fanout=0]\nEndpoints MUST check auth.\n",
+        );
+        let err = load_rules_from_glob(root, "spec.md", true)
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.starts_with("Invalid fanout for api.auth in spec.md at line 1"),
+            "{err}"
+        );
+    }
+}
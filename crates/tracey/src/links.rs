@@ -0,0 +1,364 @@
+//! `tracey check-links` implementation.
+//!
+//! Collects every external `http(s)` link in the configured spec files and the
+//! config file (e.g. each spec's `source_url`), and checks that it still resolves. Links with a
+//! fragment are also checked for a matching anchor in the fetched HTML.
+//! Fetching shells out to `curl`, the same way versioning shells out to `git`,
+//! so no TLS stack has to be linked into tracey.
+
+use eyre::{Result, WrapErr};
+use futures_util::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::config::Config;
+
+/// Number of links fetched concurrently.
+const CONCURRENCY: usize = 8;
+
+/// Per-request timeout passed to curl, in seconds.
+const TIMEOUT_SECS: &str = "20";
+
+/// Marker separating the response body from curl's `--write-out` trailer.
+const STATUS_MARKER: &str = "\n__tracey_link_status__ ";
+
+/// An external link and where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// The URL as written.
+    pub url: String,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+/// A link that did not resolve.
+#[derive(Debug)]
+pub struct DeadLink {
+    pub link: Link,
+    /// Why the link is considered dead (e.g. `HTTP 404`).
+    pub reason: String,
+}
+
+/// Result of checking all links.
+#[derive(Debug, Default)]
+pub struct LinkReport {
+    /// Number of distinct URLs checked.
+    pub checked: usize,
+    /// Dead links grouped by the file they appear in (relative to the
+    /// project root).
+    pub dead: BTreeMap<String, Vec<DeadLink>>,
+}
+
+impl LinkReport {
+    pub fn dead_count(&self) -> usize {
+        self.dead.values().map(Vec::len).sum()
+    }
+
+    /// Render dead links grouped by file.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (file, links) in &self.dead {
+            let _ = writeln!(out, "{file}");
+            for dead in links {
+                let _ = writeln!(
+                    out,
+                    "  line {}: {} ({})",
+                    dead.link.line, dead.link.url, dead.reason
+                );
+            }
+            let _ = writeln!(out);
+        }
+        let dead = self.dead_count();
+        if dead == 0 {
+            let _ = writeln!(out, "All {} link(s) resolve.", self.checked);
+        } else {
+            let _ = writeln!(
+                out,
+                "{dead} dead link(s) found; {} distinct URL(s) checked.",
+                self.checked
+            );
+        }
+        out
+    }
+}
+
+/// Extract external links from markdown.
+///
+/// Finds `http://` and `https://` URLs in inline links, autolinks, and bare
+/// text. Fenced code blocks and inline code spans are skipped, since URLs
+/// there are usually examples rather than references.
+pub fn extract_links(markdown: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+    for (idx, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start().trim_start_matches('>').trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        for (segment_idx, segment) in line.split('`').enumerate() {
+            // Odd segments are inside inline code spans.
+            if segment_idx % 2 == 1 {
+                continue;
+            }
+            let mut rest = segment;
+            while let Some(start) = [rest.find("http://"), rest.find("https://")]
+                .into_iter()
+                .flatten()
+                .min()
+            {
+                let candidate = &rest[start..];
+                let end = candidate
+                    .find(|c: char| {
+                        c.is_whitespace() || matches!(c, ')' | '>' | ']' | '"' | '\'' | '<')
+                    })
+                    .unwrap_or(candidate.len());
+                let url = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+                if url.len() > "https://".len() {
+                    links.push(Link {
+                        url: url.to_string(),
+                        line: idx + 1,
+                    });
+                }
+                rest = &candidate[end..];
+            }
+        }
+    }
+    links
+}
+
+/// Whether `html` declares an element with the given id or anchor name.
+fn has_anchor(html: &str, fragment: &str) -> bool {
+    let decoded = urlencoding::decode(fragment)
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| fragment.to_string());
+    ["id", "name"].iter().any(|attr| {
+        html.contains(&format!("{attr}=\"{decoded}\""))
+            || html.contains(&format!("{attr}='{decoded}'"))
+    })
+}
+
+/// Fetch `url` with curl and decide whether it resolves.
+///
+/// Returns `None` when the link is alive, or the reason it is dead.
+async fn check_url(url: &str) -> Result<Option<String>> {
+    let (target, fragment) = match url.split_once('#') {
+        Some((target, fragment)) if !fragment.is_empty() => (target, Some(fragment)),
+        Some((target, _)) => (target, None),
+        None => (url, None),
+    };
+    let write_out = format!("{STATUS_MARKER}%{{http_code}} %{{content_type}}");
+    let output = tokio::process::Command::new("curl")
+        .args([
+            "--silent",
+            "--location",
+            "--max-time",
+            TIMEOUT_SECS,
+            "--user-agent",
+            concat!("tracey/", env!("CARGO_PKG_VERSION")),
+            "--output",
+            "-",
+            "--write-out",
+            &write_out,
+            target,
+        ])
+        .output()
+        .await
+        .wrap_err("failed to run curl; is it installed?")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some((body, trailer)) = stdout.rsplit_once(STATUS_MARKER) else {
+        return Ok(Some(format!(
+            "request failed (curl exit {})",
+            output.status.code().unwrap_or(-1)
+        )));
+    };
+    let (status, content_type) = trailer.split_once(' ').unwrap_or((trailer, ""));
+    match status.trim() {
+        "000" => {
+            return Ok(Some(format!(
+                "request failed (curl exit {})",
+                output.status.code().unwrap_or(-1)
+            )));
+        }
+        "200" => {}
+        other => return Ok(Some(format!("HTTP {other}"))),
+    }
+
+    // Only HTML can be searched for anchors; anything else passes on status.
+    if let Some(fragment) = fragment
+        && content_type.contains("html")
+        && !has_anchor(body, fragment)
+    {
+        return Ok(Some(format!("anchor `#{fragment}` not found")));
+    }
+    Ok(None)
+}
+
+/// Collect links from every spec file and the config file, keyed by the file
+/// they appear in.
+fn collect_links(
+    project_root: &Path,
+    config: &Config,
+    config_path: &Path,
+) -> Result<BTreeMap<String, Vec<Link>>> {
+    let mut by_file: BTreeMap<String, Vec<Link>> = BTreeMap::new();
+
+    // URLs in the config file, such as each spec's `source_url`.
+    if let Ok(content) = std::fs::read_to_string(config_path) {
+        let links = extract_links(&content);
+        if !links.is_empty() {
+            let display = config_path
+                .strip_prefix(project_root)
+                .unwrap_or(config_path);
+            by_file.insert(display.display().to_string(), links);
+        }
+    }
+
+    let mut globs = globset::GlobSetBuilder::new();
+    for pattern in config.specs.iter().flat_map(|s| &s.include) {
+        globs.add(globset::Glob::new(pattern).wrap_err_with(|| format!("bad glob `{pattern}`"))?);
+    }
+    let globs = globs.build()?;
+
+    for entry in ignore::WalkBuilder::new(project_root).build().flatten() {
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(project_root) else {
+            continue;
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) || !globs.is_match(relative) {
+            continue;
+        }
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        let links = extract_links(&content);
+        if !links.is_empty() {
+            by_file
+                .entry(relative.display().to_string())
+                .or_default()
+                .extend(links);
+        }
+    }
+    Ok(by_file)
+}
+
+/// Check every external link referenced by the configured specs.
+///
+/// Each distinct URL is fetched once; dead links are reported at every place
+/// they appear.
+// r[impl cli.check-links]
+pub async fn check_links(
+    project_root: &Path,
+    config: &Config,
+    config_path: &Path,
+) -> Result<LinkReport> {
+    let by_file = collect_links(project_root, config, config_path)?;
+
+    let mut urls: Vec<&str> = by_file
+        .values()
+        .flatten()
+        .map(|link| link.url.as_str())
+        .collect();
+    urls.sort_unstable();
+    urls.dedup();
+
+    let results: Vec<(&str, Result<Option<String>>)> = stream::iter(urls)
+        .map(|url| async move { (url, check_url(url).await) })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+    let checked = results.len();
+    let mut failures = HashMap::new();
+    for (url, result) in results {
+        if let Some(reason) = result? {
+            failures.insert(url.to_string(), reason);
+        }
+    }
+
+    let mut dead = BTreeMap::new();
+    for (file, links) in by_file {
+        let file_dead: Vec<DeadLink> = links
+            .into_iter()
+            .filter_map(|link| {
+                let reason = failures.get(&link.url)?.clone();
+                Some(DeadLink { link, reason })
+            })
+            .collect();
+        if !file_dead.is_empty() {
+            dead.insert(file, file_dead);
+        }
+    }
+
+    Ok(LinkReport { checked, dead })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(markdown: &str) -> Vec<(String, usize)> {
+        extract_links(markdown)
+            .into_iter()
+            .map(|l| (l.url, l.line))
+            .collect()
+    }
+
+    // r[verify cli.check-links]
+    #[test]
+    fn extracts_inline_auto_and_bare_links() {
+        let md = "See [the RFC](https://example.com/rfc#section-2).\n\
+                  Mirror: <http://mirror.example.com/spec>, or https://example.org/a.\n";
+        assert_eq!(
+            urls(md),
+            [
+                ("https://example.com/rfc#section-2".to_string(), 1),
+                ("http://mirror.example.com/spec".to_string(), 2),
+                ("https://example.org/a".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_code() {
+        let md = "Use `https://inline.example.com` here.\n\
+                  ```styx\n\
+                  source_url https://fenced.example.com\n\
+                  ```\n\
+                  After https://kept.example.com\n";
+        assert_eq!(urls(md), [("https://kept.example.com".to_string(), 5)]);
+    }
+
+    #[test]
+    fn anchors_match_id_or_name() {
+        let html = r#"<h2 id="r--auth.login">x</h2><a name='legacy'></a>"#;
+        assert!(has_anchor(html, "r--auth.login"));
+        assert!(has_anchor(html, "legacy"));
+        assert!(!has_anchor(html, "r--auth.logout"));
+    }
+
+    #[test]
+    fn report_groups_by_file() {
+        let mut report = LinkReport {
+            checked: 3,
+            ..Default::default()
+        };
+        report.dead.insert(
+            "docs/spec.md".to_string(),
+            vec![DeadLink {
+                link: Link {
+                    url: "https://example.com/gone".to_string(),
+                    line: 4,
+                },
+                reason: "HTTP 404".to_string(),
+            }],
+        );
+        let text = report.to_text();
+        assert!(text.starts_with("docs/spec.md\n  line 4: https://example.com/gone (HTTP 404)\n"));
+        assert!(text.contains("1 dead link(s) found; 3 distinct URL(s) checked."));
+    }
+}
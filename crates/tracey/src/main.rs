@@ -17,6 +17,10 @@ use tracey::{bridge, daemon, find_project_root};
 /// CLI arguments
 #[derive(Debug, facet::Facet)]
 struct Args {
+    /// Pin the project root for any command, overriding root detection
+    #[facet(args::named, default)]
+    root: Option<PathBuf>,
+
     /// Subcommand to run
     #[facet(args::subcommand)]
     command: Command,
@@ -26,6 +30,18 @@ struct Args {
     builtins: FigueBuiltins,
 }
 
+impl Args {
+    /// Hand the global `--root` to the command, replacing its own root.
+    // r[impl cli.root]
+    fn pin_root(&mut self) {
+        if let Some(root) = self.root.take()
+            && let Some(slot) = self.command.root_mut()
+        {
+            *slot = Some(root);
+        }
+    }
+}
+
 /// Subcommands
 #[derive(Debug, facet::Facet)]
 #[repr(u8)]
@@ -119,6 +135,26 @@ enum Command {
         root: Option<PathBuf>,
     },
 
+    /// Print the requirement references of one source file, or of stdin, as JSON
+    Extract {
+        /// File to read; with --stdin, only names the content so its
+        /// language is known
+        #[facet(args::positional, default)]
+        file: Option<PathBuf>,
+
+        /// Read the source from stdin instead of the file
+        #[facet(args::named, default)]
+        stdin: bool,
+
+        /// Language of the source, by name or extension (default: from the file name)
+        #[facet(args::named, default)]
+        lang: Option<String>,
+
+        /// Path to config file, for custom verbs (used if it exists)
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
     /// Manage the bundled AI skill
     Skill {
         /// Skill action to perform
@@ -152,6 +188,55 @@ enum Command {
         query: QueryCommand,
     },
 
+    /// Compare rule coverage of implementations of the same spec side by side
+    Compare {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Spec to compare (optional if only one exists)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Implementations to compare (at least two, e.g. --impl rust --impl go --impl swift)
+        #[facet(rename = "impl", args::named, default)]
+        impls: Vec<String>,
+    },
+
+    /// List every annotation under a file or directory, grouped by rule and verb
+    Refs {
+        /// File or directory, relative to the project root (default: everything)
+        #[facet(args::positional, default)]
+        path: Option<String>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+    },
+
+    /// Roll up forward coverage by the directory or crate containing each file
+    Breakdown {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Spec name (optional if only one exists)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Implementation name (optional if only one exists)
+        #[facet(rename = "impl", args::named, default)]
+        impl_name: Option<String>,
+
+        /// Group by `dir` (default) or `crate`
+        #[facet(args::named, default)]
+        by: Option<String>,
+
+        /// With `--by dir`, group by the first N components of each directory
+        #[facet(args::named, default)]
+        depth: Option<usize>,
+    },
+
     /// Check staged spec changes and fail if any rule text changed without a version bump.
     /// Designed to be installed as a git pre-commit hook.
     PreCommit {
@@ -166,6 +251,289 @@ enum Command {
 
     /// Bump version numbers of staged rules whose text changed, then re-stage the files.
     Bump {
+        /// Print the changes as a unified diff instead of writing them
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+
+        /// Write the changes to this `.patch` file instead of applying them
+        #[facet(args::named, default)]
+        patch: Option<PathBuf>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Rename a rule in its spec and in every reference to it
+    Rename {
+        /// Current rule ID
+        #[facet(args::positional)]
+        old: String,
+
+        /// New rule ID
+        #[facet(args::positional)]
+        new: String,
+
+        /// Spec defining the rule (needed when several specs define it)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Print the changes as a unified diff instead of writing them
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+
+        /// Write the changes to this `.patch` file instead of applying them
+        #[facet(args::named, default)]
+        patch: Option<PathBuf>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Split a rule into new draft rules
+    ///
+    /// Every reference to the split rule is left with a TODO note naming the
+    /// new rules.
+    Split {
+        /// Rule to split
+        #[facet(args::positional)]
+        rule: String,
+
+        /// New rule IDs, comma-separated
+        #[facet(args::named)]
+        into: String,
+
+        /// Spec defining the rule (needed when several specs define it)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Print the changes as a unified diff instead of writing them
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+
+        /// Write the changes to this `.patch` file instead of applying them
+        #[facet(args::named, default)]
+        patch: Option<PathBuf>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Merge rules into one, leaving a TODO note on every reference to them
+    Merge {
+        /// Rules to merge
+        #[facet(args::positional)]
+        rules: Vec<String>,
+
+        /// Rule to merge them into (new, or one of the merged rules)
+        #[facet(args::named)]
+        into: String,
+
+        /// Spec defining the rules (needed when several specs define them)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Print the changes as a unified diff instead of writing them
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+
+        /// Write the changes to this `.patch` file instead of applying them
+        #[facet(args::named, default)]
+        patch: Option<PathBuf>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Install a git pre-commit hook that runs `tracey pre-commit`
+    ///
+    /// With `--commit-msg`, a prepare-commit-msg hook running
+    /// `tracey prepare-commit-msg` is installed too.
+    InstallHooks {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Also install a prepare-commit-msg hook that adds a coverage trailer
+        #[facet(args::named, default)]
+        commit_msg: bool,
+
+        /// Replace existing hooks that don't run tracey
+        #[facet(args::named, default)]
+        force: bool,
+    },
+
+    /// Append the coverage impact of the staged changes to a commit message
+    ///
+    /// The impact is summarized in a `Tracey-Coverage:` trailer. Designed to
+    /// be installed as a git prepare-commit-msg hook.
+    PrepareCommitMsg {
+        /// Commit message file, as passed to the hook by git
+        #[facet(args::positional)]
+        message_file: PathBuf,
+
+        /// Source of the message, as passed to the hook by git
+        #[facet(args::positional, default)]
+        source: Option<String>,
+
+        /// Commit object name, as passed to the hook by git
+        #[facet(args::positional, default)]
+        commit: Option<String>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Check that the spec is ready to release as of a release tag
+    ///
+    /// Reports coverage of the spec as of the tag, plus verification of the
+    /// rules added since the previous release.
+    ReleaseCheck {
+        /// Release tag whose spec contents are pinned (e.g. v2.0)
+        #[facet(args::named)]
+        tag: String,
+
+        /// Previous release tag (default: the tag preceding --tag)
+        #[facet(args::named, default)]
+        since: Option<String>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// List the files each implementation scans, and why other files are skipped
+    Files {
+        /// Only this spec (default: all specs)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Only this implementation (default: all implementations)
+        #[facet(rename = "impl", args::named, default)]
+        impl_name: Option<String>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Check that formatting annotated files would not move references to other code
+    FmtCheck {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Run the config's policy commands against the current coverage
+    Policy {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Show who wrote the impl and verify references, per author (from git blame).
+    Contributors {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Check spec markdown for style problems
+    ///
+    /// Severities come from the config's `spec_lints` block.
+    LintSpec {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Only lint this spec
+        #[facet(args::named, default)]
+        spec: Option<String>,
+    },
+
+    /// Show coverage grouped by rule owner
+    ///
+    /// With `--min`, fails when an owner's rules fall below that percentage.
+    Owners {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Only report the rules of this owner
+        #[facet(args::named, default)]
+        owner: Option<String>,
+
+        /// Exit non-zero if an owner's rules are less than this percentage implemented
+        #[facet(args::named, default)]
+        min: Option<f64>,
+    },
+
+    /// Print a reviewer checklist of spec-relevant changes since a git revision
+    ///
+    /// The working tree, including uncommitted changes, is compared against
+    /// the revision.
+    Review {
+        /// Revision to diff the working tree against (e.g. main, HEAD~3)
+        #[facet(args::named)]
+        diff: String,
+
+        /// Output format: markdown (default) or github (job summary and
+        /// error annotations for GitHub Actions)
+        #[facet(args::named, default)]
+        format: Option<String>,
+
         /// Project root directory (default: current directory)
         #[facet(args::positional, default)]
         root: Option<PathBuf>,
@@ -175,6 +543,330 @@ enum Command {
         config: PathBuf,
     },
 
+    /// Check only the files changed since a git revision
+    ///
+    /// Reports the rules they reference, spec changes, and errors in those
+    /// files.
+    Check {
+        /// Revision to compare the working tree against (e.g. main, HEAD~3)
+        #[facet(args::named)]
+        changed_since: String,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Compare coverage between two git revisions
+    ///
+    /// Both revisions are read from git, so the working tree is left alone.
+    DiffCoverage {
+        /// Revision before the change (e.g. main)
+        #[facet(args::named)]
+        from: String,
+
+        /// Revision after the change (default: HEAD)
+        #[facet(args::named, default)]
+        to: Option<String>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Check that external links in spec files and the config still resolve
+    CheckLinks {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Generate a stub implementation and test for a rule
+    ///
+    /// The stubs are pre-annotated with `impl` and `verify` references.
+    Scaffold {
+        /// Rule to scaffold (e.g. channel.reset.behavior)
+        #[facet(args::named)]
+        rule: String,
+
+        /// Language of the stub: rust, python, or typescript (default: inferred from --out)
+        #[facet(args::named, default)]
+        lang: Option<String>,
+
+        /// File to write (default: print to stdout)
+        #[facet(args::named, default)]
+        out: Option<PathBuf>,
+
+        /// Overwrite --out if it already exists
+        #[facet(args::named, default)]
+        force: bool,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Generate constants from the parameters rules declare on their markers
+    ///
+    /// A marker such as `r[limits.max-frame size=16384]` becomes
+    /// `LIMITS_MAX_FRAME_SIZE`, preceded by a `define` reference to its rule.
+    Params {
+        /// Output format: rust or json (default: inferred from --out, else rust)
+        #[facet(args::named, default)]
+        format: Option<String>,
+
+        /// Only include this spec's rules
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// File to write (default: print to stdout)
+        #[facet(args::named, default)]
+        out: Option<PathBuf>,
+
+        /// Exit non-zero if --out is not up to date, instead of writing it
+        #[facet(args::named, default)]
+        check: bool,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Write a starting config by scanning the project for specs and sources
+    Init {
+        /// Replace an existing config
+        #[facet(args::named, default)]
+        force: bool,
+
+        /// Print the proposed config instead of writing it
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+    },
+
+    /// Create an example project from a template to explore the workflow
+    New {
+        /// Template to use (see --list)
+        #[facet(args::named, default)]
+        template: Option<String>,
+
+        /// List the available templates
+        #[facet(args::named, default)]
+        list: bool,
+
+        /// Directory to create the project in (default: the template name)
+        #[facet(args::positional, default)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Suggest where each unreferenced rule is implemented
+    ///
+    /// Meant for adopting tracey on an existing codebase.
+    Adopt {
+        /// Only this spec (default: all specs)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Only this implementation (default: all implementations)
+        #[facet(rename = "impl", args::named, default)]
+        impl_name: Option<String>,
+
+        /// Candidates to list per rule (default: 3)
+        #[facet(args::named, default)]
+        limit: Option<usize>,
+
+        /// Add a `related` reference above the best candidate of each rule
+        #[facet(args::named, default)]
+        annotate: bool,
+
+        /// Print the changes as a unified diff instead of writing them
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+
+        /// Write the changes to this `.patch` file instead of applying them
+        #[facet(args::named, default)]
+        patch: Option<PathBuf>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Suggest rules for each code unit that has no reference
+    Annotate {
+        /// Only this spec (default: all specs)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Only this implementation (default: all implementations)
+        #[facet(rename = "impl", args::named, default)]
+        impl_name: Option<String>,
+
+        /// Rules to list per code unit (default: 3)
+        #[facet(args::named, default)]
+        limit: Option<usize>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Print a coverage report, or a summary of the tracey setup for bug reports
+    Report {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Summarize versions, config, extraction timing, and daemon state,
+        /// with paths redacted
+        #[facet(args::named, default)]
+        about: bool,
+
+        /// Write a static HTML coverage site to this directory, without a daemon
+        #[facet(args::named, default)]
+        out: Option<PathBuf>,
+
+        /// Print the coverage report of one implementation in this format:
+        /// text, json, markdown, html or junit
+        #[facet(args::named, default)]
+        format: Option<String>,
+
+        /// Spec to report with --format (default: the first spec)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Implementation to report with --format (default: the spec's first
+        /// implementation)
+        #[facet(rename = "impl", args::named, default)]
+        impl_name: Option<String>,
+    },
+
+    /// Append a snapshot of current coverage to .tracey/history.jsonl
+    History {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Show coverage over the recorded history
+    Trend {
+        /// Number of recent commits to compare against (default: 10)
+        #[facet(args::named, default)]
+        commits: Option<usize>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+    },
+
+    /// Print the graph of rules linked by depends and related references
+    Graph {
+        /// Output format: dot (Graphviz) or mermaid
+        #[facet(args::named, default)]
+        format: Option<String>,
+
+        /// Only rules whose ID starts with this prefix (e.g. auth or auth.login)
+        #[facet(args::named, default)]
+        prefix: Option<String>,
+
+        /// Spec to graph (default: the first spec)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Implementation whose coverage is highlighted (default: the first)
+        #[facet(rename = "impl", args::named, default)]
+        impl_name: Option<String>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Render spec coverage as an SVG badge or summary table for READMEs
+    Badge {
+        /// Badge style: flat (one badge) or table (one row per spec/impl)
+        #[facet(args::named, default)]
+        style: Option<String>,
+
+        /// File to write (default: print to stdout)
+        #[facet(args::named, default)]
+        out: Option<PathBuf>,
+
+        /// Directory to write overall and per-spec badges to, instead of one
+        /// badge
+        #[facet(args::named, default)]
+        output: Option<PathBuf>,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+    },
+
+    /// Report panics or slowdowns of the lexer and markdown processor
+    ///
+    /// Both are run over pathological inputs: fixed edge cases plus randomly
+    /// generated ones.
+    Selftest {
+        /// Seed for randomly generated inputs (to reproduce a failure)
+        #[facet(args::named, default)]
+        seed: Option<u64>,
+
+        /// Number of randomly generated inputs
+        #[facet(args::named, default)]
+        iterations: Option<usize>,
+
+        /// Time budget per input, in milliseconds
+        #[facet(rename = "budget-ms", args::named, default)]
+        budget_ms: Option<u64>,
+
+        /// Print each input before running it
+        #[facet(args::named, default)]
+        verbose: bool,
+    },
+
     /// Remove orphaned state directories whose projects no longer exist on disk
     Gc {
         /// Show what would be removed without deleting anything
@@ -182,39 +874,238 @@ enum Command {
         dry_run: bool,
     },
 
-    /// Export a static, deployable site from the current spec coverage data.
-    Export {
-        /// Output directory (will be created; existing contents overwritten)
-        #[facet(args::positional)]
-        output: PathBuf,
+    /// Inspect or clear logs and caches kept for the project
+    Cache {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Cache action to perform
+        #[facet(args::subcommand)]
+        action: CacheAction,
+    },
+
+    /// Inspect the tracey config
+    Config {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Config action to perform
+        #[facet(args::subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Snapshot spec rules as JSON, or report how they changed
+    Spec {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Spec action to perform
+        #[facet(args::subcommand)]
+        action: SpecAction,
+    },
+
+    /// Import formal verification results
+    Proofs {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Proofs action to perform
+        #[facet(args::subcommand)]
+        action: ProofsAction,
+    },
+
+    /// Export a static, deployable site from the current spec coverage data.
+    Export {
+        /// Output directory (will be created; existing contents overwritten)
+        #[facet(args::positional)]
+        output: PathBuf,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Also export individual source file pages (includes full source code).
+        #[facet(args::named, default)]
+        sources: bool,
+
+        /// Also write `r/<rule-id>/index.html` redirect pages for short rule links.
+        #[facet(args::named, default)]
+        redirects: bool,
+    },
+}
+
+impl Command {
+    /// The project root argument of this command, if it takes one.
+    fn root_mut(&mut self) -> Option<&mut Option<PathBuf>> {
+        match self {
+            Command::Web { root, .. }
+            | Command::Mcp { root, .. }
+            | Command::Lsp { root, .. }
+            | Command::Daemon { root, .. }
+            | Command::Logs { root, .. }
+            | Command::Status { root, .. }
+            | Command::Kill { root }
+            | Command::Query { root, .. }
+            | Command::Compare { root, .. }
+            | Command::Refs { root, .. }
+            | Command::Breakdown { root, .. }
+            | Command::PreCommit { root, .. }
+            | Command::Bump { root, .. }
+            | Command::Rename { root, .. }
+            | Command::Split { root, .. }
+            | Command::Merge { root, .. }
+            | Command::InstallHooks { root, .. }
+            | Command::PrepareCommitMsg { root, .. }
+            | Command::ReleaseCheck { root, .. }
+            | Command::Review { root, .. }
+            | Command::Check { root, .. }
+            | Command::DiffCoverage { root, .. }
+            | Command::Contributors { root, .. }
+            | Command::Owners { root, .. }
+            | Command::LintSpec { root, .. }
+            | Command::Files { root, .. }
+            | Command::FmtCheck { root, .. }
+            | Command::Policy { root, .. }
+            | Command::CheckLinks { root, .. }
+            | Command::Scaffold { root, .. }
+            | Command::Params { root, .. }
+            | Command::Init { root, .. }
+            | Command::Adopt { root, .. }
+            | Command::Annotate { root, .. }
+            | Command::Report { root, .. }
+            | Command::Badge { root, .. }
+            | Command::History { root, .. }
+            | Command::Trend { root, .. }
+            | Command::Graph { root, .. }
+            | Command::Cache { root, .. }
+            | Command::Config { root, .. }
+            | Command::Spec { root, .. }
+            | Command::Proofs { root, .. }
+            | Command::Export { root, .. } => Some(root),
+            Command::Skill { .. }
+            | Command::Extract { .. }
+            | Command::Ai { .. }
+            | Command::Selftest { .. }
+            | Command::New { .. }
+            | Command::Gc { .. } => None,
+        }
+    }
+}
+
+/// Skill subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum SkillAction {
+    /// Install the bundled Tracey skill for Claude and/or Codex
+    Install {
+        /// Install only for Claude Code
+        #[facet(args::named, default)]
+        claude: bool,
+
+        /// Install only for Codex CLI
+        #[facet(args::named, default)]
+        codex: bool,
+    },
+}
+
+/// Cache subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum CacheAction {
+    /// Show disk usage of logs and caches and the configured limits
+    Stats,
+
+    /// Clear cached files (default: logs only)
+    ///
+    /// Proof results, the coverage history and the audit log can't be
+    /// recreated, so they are only cleared when named with `--kind`.
+    Clear {
+        /// Only clear this kind of file (logs, proofs, history, audit)
+        #[facet(args::named, default)]
+        kind: Option<String>,
+    },
+}
+
+/// Config subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum ConfigAction {
+    /// Print the config
+    Show {
+        /// Print the effective config, with `defaults` folded into each impl
+        #[facet(args::named, default)]
+        resolved: bool,
+    },
+
+    /// Check the config for problems, pointing at the values that cause them
+    Check,
+}
+
+/// Spec subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum SpecAction {
+    /// Print every spec's rules (ID, version, level, status, text) as JSON
+    Manifest {
+        /// Read the specs as of this git revision instead of the working tree
+        #[facet(args::named, default)]
+        git: Option<String>,
+    },
+
+    /// Report added, removed, reworded, re-leveled, re-statused and
+    /// re-versioned rules, and changed source URLs
+    Diff {
+        /// Old manifest, as written by `tracey spec manifest`
+        #[facet(args::positional, default)]
+        old: Option<PathBuf>,
 
-        /// Project root directory (default: current directory)
+        /// New manifest (default: the working tree)
         #[facet(args::positional, default)]
-        root: Option<PathBuf>,
+        new: Option<PathBuf>,
 
-        /// Path to config file
-        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
-        config: PathBuf,
+        /// Compare the specs at this git revision with the working tree
+        #[facet(args::named, default)]
+        git: Option<String>,
 
-        /// Also export individual source file pages (includes full source code).
+        /// Output format: markdown (default), text or json
         #[facet(args::named, default)]
-        sources: bool,
+        format: Option<String>,
+
+        /// Output as JSON (same as `--format json`)
+        #[facet(args::named, default)]
+        json: bool,
     },
 }
 
-/// Skill subcommands
+/// Proofs subcommands
 #[derive(Debug, facet::Facet)]
 #[repr(u8)]
-enum SkillAction {
-    /// Install the bundled Tracey skill for Claude and/or Codex
-    Install {
-        /// Install only for Claude Code
-        #[facet(args::named, default)]
-        claude: bool,
-
-        /// Install only for Codex CLI
-        #[facet(args::named, default)]
-        codex: bool,
+enum ProofsAction {
+    /// Record which harnesses Kani proved, from its JSON output
+    ImportKani {
+        /// Kani results file
+        #[facet(args::positional)]
+        results: PathBuf,
     },
 }
 
@@ -342,7 +1233,8 @@ async fn main() -> Result<()> {
                 .version(cli_version_text())
         })
         .build();
-    let args: Args = args::Driver::new(config).run().unwrap();
+    let mut args: Args = args::Driver::new(config).run().unwrap();
+    args.pin_root();
 
     match args.command {
         // r[impl cli.web]
@@ -422,53 +1314,686 @@ async fn main() -> Result<()> {
 
             daemon::run(project_root, config_path).await
         }
-        // r[impl daemon.cli.logs]
-        Command::Logs {
+        // r[impl daemon.cli.logs]
+        Command::Logs {
+            root,
+            follow,
+            lines,
+        } => show_logs(root, follow, lines.unwrap_or(50)),
+        // r[impl daemon.cli.status]
+        Command::Status { root, json } => show_status(root, json).await,
+        // r[impl daemon.cli.kill]
+        Command::Kill { root } => kill_daemon(root).await,
+
+        // r[impl cli.skill.install]
+        Command::Extract {
+            file,
+            stdin,
+            lang,
+            config,
+        } => {
+            let path = tracey::extract::source_path(file.as_deref(), lang.as_deref())?;
+            let content = if stdin {
+                std::io::read_to_string(std::io::stdin()).wrap_err("failed to read stdin")?
+            } else {
+                let Some(file) = &file else {
+                    eyre::bail!("Pass a file to read, or --stdin");
+                };
+                std::fs::read_to_string(file)
+                    .wrap_err_with(|| format!("failed to read {}", file.display()))?
+            };
+            // Custom verbs come from the project config when there is one.
+            let config_path = find_project_root().unwrap_or_default().join(&config);
+            let custom_verbs = if config_path.is_file() {
+                tracey::config::verb_roles(&tracey::load_config(&config_path)?).custom_verbs()
+            } else {
+                Vec::new()
+            };
+            let extracted = tracey::extract::extract(&path, &content, &custom_verbs);
+            println!(
+                "{}",
+                facet_json::to_string_pretty(&extracted).expect("JSON serialization failed")
+            );
+            Ok(())
+        }
+
+        Command::Skill { action } => match action {
+            SkillAction::Install { claude, codex } => install_skill(codex, claude),
+        },
+
+        // r[impl cli.mcp.register]
+        // r[impl cli.skill.install]
+        Command::Ai { claude, codex } => setup_ai_clients(codex, claude),
+
+        // r[impl cli.pre-commit]
+        Command::PreCommit { root, config } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::bump::load_config(&config_path)?;
+            let passed = tracey::bump::pre_commit(&project_root, &cfg).await?;
+            if !passed {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        // r[impl cli.bump]
+        Command::Bump {
+            dry_run,
+            patch,
+            root,
+            config,
+        } => {
+            let mode = tracey::patch::Mode::from_flags(dry_run, patch)?;
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::bump::load_config(&config_path)?;
+            let plan = tracey::bump::plan_bump(&project_root, &cfg).await?;
+            if plan.bumped.is_empty() {
+                println!("No staged rule changes require a version bump.");
+                return Ok(());
+            }
+            let applied = finish_patch(&plan.patch, &mode)?;
+            if applied {
+                tracey::bump::stage_bump(&project_root, &plan)?;
+            }
+            println!(
+                "{} {} rule(s):",
+                if applied { "Bumped" } else { "Would bump" },
+                plan.bumped.len()
+            );
+            for id in &plan.bumped {
+                println!("  {id}");
+            }
+            if applied {
+                println!();
+                println!("Affected spec files have been re-staged. Review and commit.");
+            }
+            Ok(())
+        }
+
+        Command::Rename {
+            old,
+            new,
+            spec,
+            dry_run,
+            patch,
+            root,
+            config,
+        } => {
+            let mode = tracey::patch::Mode::from_flags(dry_run, patch)?;
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let plan =
+                tracey::rename::plan_rename(&project_root, &cfg, &old, &new, spec.as_deref())
+                    .await?;
+            let applied = finish_patch(&plan.patch, &mode)?;
+            println!(
+                "{} {old} to {new} in {} and {} reference(s).",
+                if applied { "Renamed" } else { "Would rename" },
+                plan.spec_file.display(),
+                plan.references.len()
+            );
+            if !plan.skipped.is_empty() {
+                println!("Left for manual review:");
+                for (file, line) in &plan.skipped {
+                    println!("  {}:{line}", file.display());
+                }
+            }
+            Ok(())
+        }
+
+        Command::Split {
+            rule,
+            into,
+            spec,
+            dry_run,
+            patch,
+            root,
+            config,
+        } => {
+            let mode = tracey::patch::Mode::from_flags(dry_run, patch)?;
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let into: Vec<String> = into
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+            let plan =
+                tracey::rename::plan_split(&project_root, &cfg, &rule, &into, spec.as_deref())
+                    .await?;
+            print!("{}", plan.to_markdown());
+            finish_patch(&plan.patch, &mode)?;
+            Ok(())
+        }
+
+        Command::Merge {
+            rules,
+            into,
+            spec,
+            dry_run,
+            patch,
+            root,
+            config,
+        } => {
+            let mode = tracey::patch::Mode::from_flags(dry_run, patch)?;
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let plan =
+                tracey::rename::plan_merge(&project_root, &cfg, &rules, &into, spec.as_deref())
+                    .await?;
+            print!("{}", plan.to_markdown());
+            finish_patch(&plan.patch, &mode)?;
+            Ok(())
+        }
+
+        // r[impl cli.install-hooks]
+        Command::InstallHooks {
+            root,
+            commit_msg,
+            force,
+        } => {
+            use tracey::bump::{Hook, HookOutcome};
+
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let mut hooks = vec![Hook::PreCommit];
+            if commit_msg {
+                hooks.push(Hook::PrepareCommitMsg);
+            }
+            let mut skipped = false;
+            for outcome in tracey::bump::install_hooks(&project_root, &hooks, force)? {
+                match outcome {
+                    HookOutcome::Installed(path) => {
+                        println!("{} {}", "Installed".green(), path.display())
+                    }
+                    HookOutcome::AlreadyInstalled(path) => {
+                        println!("Already installed: {}", path.display())
+                    }
+                    HookOutcome::Skipped(path) => {
+                        skipped = true;
+                        eprintln!(
+                            "{}: {} already exists and does not run tracey; not replacing it",
+                            "warning".yellow(),
+                            path.display()
+                        );
+                    }
+                }
+            }
+            if skipped {
+                eprintln!("Hint: rerun with --force to replace it, or call tracey from it.");
+            }
+            Ok(())
+        }
+
+        Command::PrepareCommitMsg {
+            message_file,
+            source,
+            commit,
+            root,
+            config,
+        } => {
+            // Merges, squashes, and amended or reused commits (`-c`, `-C`,
+            // `--amend`, which pass a commit) describe changes other than the
+            // staged diff against HEAD; leave their messages alone.
+            if commit.is_some() || matches!(source.as_deref(), Some("merge" | "squash")) {
+                return Ok(());
+            }
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let delta = tracey::commit_msg::staged_coverage(&project_root, &cfg).await?;
+            if let Some(trailer) = delta.trailer() {
+                tracey::commit_msg::write_trailer(&project_root, &message_file, &trailer)?;
+            }
+            Ok(())
+        }
+
+        // r[impl cli.release-check]
+        Command::ReleaseCheck {
+            tag,
+            since,
+            root,
+            config,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report =
+                tracey::release::release_check(&project_root, &cfg, &tag, since.as_deref()).await?;
+            print!("{}", report.to_markdown());
+            if !report.is_ready() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::Review {
+            diff,
+            format,
+            root,
+            config,
+        } => {
+            let format: tracey::review::Format = match format {
+                Some(format) => format.parse()?,
+                None => tracey::review::Format::default(),
+            };
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::review::review(&project_root, &cfg, &diff).await?;
+            match format {
+                tracey::review::Format::Markdown => print!("{}", report.to_markdown()),
+                // r[impl cli.review.github]
+                tracey::review::Format::Github => {
+                    print!("{}", report.to_github());
+                    match std::env::var_os("GITHUB_STEP_SUMMARY") {
+                        Some(summary) => {
+                            use std::io::Write as _;
+                            std::fs::OpenOptions::new()
+                                .create(true)
+                                .append(true)
+                                .open(&summary)
+                                .and_then(|mut file| {
+                                    file.write_all(report.to_markdown().as_bytes())
+                                })
+                                .wrap_err("failed to write the job summary")?;
+                        }
+                        None => print!("{}", report.to_markdown()),
+                    }
+                    if !report.problems.is_empty() {
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        Command::Check {
+            changed_since,
+            root,
+            config,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::check::check_changed(&project_root, &cfg, &changed_since).await?;
+            print!("{}", report.to_markdown());
+            if !report.problems.is_empty() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::DiffCoverage {
+            from,
+            to,
+            root,
+            config,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let to = to.as_deref().unwrap_or("HEAD");
+            let diff =
+                tracey::diff_coverage::diff_coverage(&project_root, &config_path, &cfg, &from, to)
+                    .await?;
+            print!("{}", diff.to_markdown());
+            Ok(())
+        }
+
+        Command::Files {
+            spec,
+            impl_name,
+            root,
+            config,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report =
+                tracey::files::files(&project_root, &cfg, spec.as_deref(), impl_name.as_deref())?;
+            print!("{}", report.to_text());
+            Ok(())
+        }
+
+        Command::FmtCheck { root, config } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::fmt_check::fmt_check(&project_root, &cfg).await?;
+            print!("{}", report.to_text());
+            if !report.is_clean() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::Policy { root, config } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let run = tracey::policy::run(&project_root, &cfg).await?;
+            print!("{}", run.to_text());
+            if !run.is_passing() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::Contributors { root, config } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::contributors::contributors(&project_root, &cfg).await?;
+            print!("{}", report.to_markdown());
+            Ok(())
+        }
+
+        Command::LintSpec { root, config, spec } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::spec_lint::lint_spec(&project_root, &cfg, spec.as_deref())?;
+            let color = std::env::var_os("NO_COLOR").is_none();
+            print!("{}", report.render(color));
+            if report.errors() > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::Owners {
+            root,
+            config,
+            owner,
+            min,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::owners::owners(&project_root, &cfg, owner.as_deref(), min).await?;
+            print!("{}", report.to_markdown());
+            if !report.failing().is_empty() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::CheckLinks { root, config } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let report = tracey::links::check_links(&project_root, &cfg, &config_path).await?;
+            print!("{}", report.to_text());
+            if report.dead_count() > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Command::Init {
+            force,
+            dry_run,
+            root,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let interactive = std::io::IsTerminal::is_terminal(&std::io::stderr());
+            let init = tracey::init::propose(&project_root, &|done, total| {
+                if interactive {
+                    eprint!("\rScanning source files: {done}/{total}");
+                    if done == total {
+                        eprintln!();
+                    }
+                }
+            })?;
+            let color = std::env::var_os("NO_COLOR").is_none();
+            if dry_run {
+                print!("{}", init.source);
+                eprint!(
+                    "{}",
+                    init.check
+                        .render(tracey::init::CONFIG_PATH, &init.source, color)
+                );
+                return Ok(());
+            }
+            let path = tracey::init::write(&project_root, &init, force)?;
+            print!(
+                "{}",
+                init.check
+                    .render(tracey::init::CONFIG_PATH, &init.source, color)
+            );
+            println!(
+                "Wrote {} ({} spec(s), {} implementation(s)). Next:",
+                path.display(),
+                init.detection.specs.len(),
+                init.detection.impls.len()
+            );
+            println!("  tracey config check");
+            println!("  tracey query status");
+            Ok(())
+        }
+
+        Command::New {
+            template,
+            list,
+            dir,
+        } => {
+            let template = match template {
+                Some(name) if !list => tracey::templates::find(&name)?,
+                _ => {
+                    print!("{}", tracey::templates::list());
+                    return Ok(());
+                }
+            };
+            let dir = dir.unwrap_or_else(|| PathBuf::from(template.name));
+            let files = tracey::templates::write(template, &dir)?;
+            println!(
+                "Created the {} example in {} ({} files). Next:",
+                template.name,
+                dir.display(),
+                files.len()
+            );
+            println!("  cd {}", dir.display());
+            println!("  tracey query status");
+            println!("  tracey web --open");
+            Ok(())
+        }
+
+        Command::Adopt {
+            spec,
+            impl_name,
+            limit,
+            annotate,
+            dry_run,
+            patch,
             root,
-            follow,
-            lines,
-        } => show_logs(root, follow, lines.unwrap_or(50)),
-        // r[impl daemon.cli.status]
-        Command::Status { root, json } => show_status(root, json).await,
-        // r[impl daemon.cli.kill]
-        Command::Kill { root } => kill_daemon(root).await,
+            config,
+        } => {
+            let mode = tracey::patch::Mode::from_flags(dry_run, patch)?;
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let report = tracey::adopt::adopt(
+                &project_root,
+                &cfg,
+                spec.as_deref(),
+                impl_name.as_deref(),
+                limit.unwrap_or(3),
+                annotate,
+            )
+            .await?;
+            print!("{}", report.to_markdown());
+            finish_patch(&report.patch, &mode)?;
+            Ok(())
+        }
 
-        // r[impl cli.skill.install]
-        Command::Skill { action } => match action {
-            SkillAction::Install { claude, codex } => install_skill(codex, claude),
-        },
+        Command::Annotate {
+            spec,
+            impl_name,
+            limit,
+            root,
+            config,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let report = tracey::adopt::suggest_rules(
+                &project_root,
+                &cfg,
+                spec.as_deref(),
+                impl_name.as_deref(),
+                limit.unwrap_or(3),
+            )
+            .await?;
+            print!("{}", report.to_markdown());
+            Ok(())
+        }
 
-        // r[impl cli.mcp.register]
-        // r[impl cli.skill.install]
-        Command::Ai { claude, codex } => setup_ai_clients(codex, claude),
+        Command::Scaffold {
+            rule,
+            lang,
+            out,
+            force,
+            root,
+            config,
+        } => {
+            let lang: tracey::scaffold::Lang = match (lang, &out) {
+                (Some(lang), _) => lang.parse()?,
+                (None, Some(out)) => tracey::scaffold::Lang::from_path(out).ok_or_else(|| {
+                    eyre::eyre!(
+                        "Cannot infer the language of {}; pass --lang",
+                        out.display()
+                    )
+                })?,
+                (None, None) => tracey::scaffold::Lang::Rust,
+            };
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let found = tracey::scaffold::find_rule(&project_root, &cfg, &rule).await?;
+            let source = tracey::scaffold::render(&found, lang);
+            match out {
+                Some(out) => {
+                    tracey::scaffold::write_stub(&out, &source, force)?;
+                    println!("Wrote stub for {} to {}", found.id, out.display());
+                }
+                None => print!("{source}"),
+            }
+            Ok(())
+        }
 
-        // r[impl cli.pre-commit]
-        Command::PreCommit { root, config } => {
+        Command::Params {
+            format,
+            spec,
+            out,
+            check,
+            root,
+            config,
+        } => {
+            let format: tracey::params::Format = match (format, &out) {
+                (Some(format), _) => format.parse()?,
+                (None, Some(out)) => tracey::params::Format::from_path(out).ok_or_else(|| {
+                    eyre::eyre!(
+                        "Cannot infer the format of {}; pass --format",
+                        out.display()
+                    )
+                })?,
+                (None, None) => tracey::params::Format::Rust,
+            };
             let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
-            let config_path = project_root.join(&config);
-            let cfg = load_bump_config(&config_path);
-            let passed = tracey::bump::pre_commit(&project_root, &cfg).await?;
-            if !passed {
-                std::process::exit(1);
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let rules = tracey::params::collect(&project_root, &cfg, spec.as_deref()).await?;
+            let source = tracey::params::render(&rules, format)?;
+            match (out, check) {
+                (Some(out), true) => {
+                    if !tracey::params::is_up_to_date(&out, &source) {
+                        eprintln!(
+                            "{} is out of date; run `tracey params --out {}`",
+                            out.display(),
+                            out.display()
+                        );
+                        std::process::exit(1);
+                    }
+                }
+                (None, true) => eyre::bail!("--check needs --out"),
+                (Some(out), false) => {
+                    std::fs::write(&out, &source)
+                        .wrap_err_with(|| format!("failed to write {}", out.display()))?;
+                    println!("Wrote rule parameters to {}", out.display());
+                }
+                (None, false) => print!("{source}"),
             }
             Ok(())
         }
 
-        // r[impl cli.bump]
-        Command::Bump { root, config } => {
+        Command::Report {
+            root,
+            config,
+            about,
+            out,
+            format,
+            spec,
+            impl_name,
+        } => {
             let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
-            let config_path = project_root.join(&config);
-            let cfg = load_bump_config(&config_path);
-            let bumped = tracey::bump::bump(&project_root, &cfg).await?;
-            if bumped.is_empty() {
-                println!("No staged rule changes require a version bump.");
-            } else {
-                println!("Bumped {} rule(s):", bumped.len());
-                for id in &bumped {
-                    println!("  {id}");
+            if let Some(format) = format {
+                if about || out.is_some() {
+                    return Err(eyre::eyre!(
+                        "--format cannot be combined with --about or --out"
+                    ));
                 }
-                println!();
-                println!("Affected spec files have been re-staged. Review and commit.");
+                let format: tracey::output::OutputFormat = format.parse()?;
+                let cfg = tracey::load_config(&project_root.join(&config))?;
+                let (report, sections) = tracey::output::coverage_report(
+                    &project_root,
+                    &cfg,
+                    spec.as_deref(),
+                    impl_name.as_deref(),
+                )
+                .await?;
+                print!(
+                    "{}",
+                    tracey::output::render_report(&report, &sections, format, false)
+                );
+                return Ok(());
+            }
+            if let Some(out) = out {
+                if about {
+                    return Err(eyre::eyre!("--about and --out cannot be combined"));
+                }
+                let config_path = project_root.join(&config);
+                tracey::load_config(&config_path)?;
+                return bridge::export::run_local(project_root, config_path, out).await;
+            }
+            if !about {
+                return Err(eyre::eyre!(
+                    "Nothing to report. Run `tracey report --about` for a summary of this setup, \
+                     `tracey report --format FORMAT` for a coverage report, \
+                     or `tracey report --out DIR` for a static coverage site."
+                ));
+            }
+            let report = tracey::about::gather(&project_root, &project_root.join(&config)).await;
+            print!("{}", report.to_text(&project_root));
+            Ok(())
+        }
+
+        Command::Selftest {
+            seed,
+            iterations,
+            budget_ms,
+            verbose,
+        } => {
+            let defaults = tracey::selftest::SelftestOptions::default();
+            let options = tracey::selftest::SelftestOptions {
+                seed: seed.unwrap_or(defaults.seed),
+                iterations: iterations.unwrap_or(defaults.iterations),
+                budget: budget_ms.map_or(defaults.budget, std::time::Duration::from_millis),
+                verbose,
+                ..defaults
+            };
+            let report = tracey::selftest::run(&options).await;
+            print!("{}", report.to_text());
+            if !report.passed() {
+                std::process::exit(1);
             }
             Ok(())
         }
@@ -537,14 +2062,233 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
+        // r[impl cli.compare]
+        Command::Compare { root, spec, impls } => {
+            if impls.len() < 2 {
+                return Err(eyre!(
+                    "compare needs at least two implementations, e.g. --impl rust --impl go"
+                ));
+            }
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let query_client =
+                bridge::query::QueryClient::new(project_root, bridge::query::Caller::Cli);
+            init_tracing(TracingConfig {
+                log_file: None,
+                enable_console: true,
+                console_ansi: true,
+                default_filter: "tracey=info",
+            })?;
+            println!("{}", query_client.compare(spec.as_deref(), &impls).await);
+            Ok(())
+        }
+
+        // r[impl cli.refs]
+        Command::Refs { path, root } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let query_client =
+                bridge::query::QueryClient::new(project_root, bridge::query::Caller::Cli);
+            init_tracing(TracingConfig {
+                log_file: None,
+                enable_console: true,
+                console_ansi: true,
+                default_filter: "tracey=info",
+            })?;
+            println!(
+                "{}",
+                query_client.refs_in(path.as_deref().unwrap_or("")).await
+            );
+            Ok(())
+        }
+
+        // r[impl cli.breakdown]
+        Command::Breakdown {
+            root,
+            spec,
+            impl_name,
+            by,
+            depth,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let query_client =
+                bridge::query::QueryClient::new(project_root, bridge::query::Caller::Cli);
+            init_tracing(TracingConfig {
+                log_file: None,
+                enable_console: true,
+                console_ansi: true,
+                default_filter: "tracey=info",
+            })?;
+            println!(
+                "{}",
+                query_client
+                    .breakdown(spec.as_deref(), impl_name.as_deref(), by.as_deref(), depth)
+                    .await
+            );
+            Ok(())
+        }
+
+        // r[impl cli.badge.flat]
+        // r[impl cli.badge.table]
+        Command::History { root, config } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = tracey::load_config(&config_path)?;
+            let snapshot = tracey::history::snapshot(&project_root, &cfg).await?;
+            tracey::history::append(&project_root, &snapshot)?;
+            println!(
+                "Recorded coverage of {} spec/impl pairs in {}",
+                snapshot.impls.len(),
+                tracey::history::HISTORY_FILE
+            );
+            Ok(())
+        }
+
+        Command::Trend { commits, root } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let history = tracey::history::load(&project_root)?;
+            print!(
+                "{}",
+                tracey::history::render_trend(&history, commits.unwrap_or(10))
+            );
+            Ok(())
+        }
+
+        Command::Graph {
+            format,
+            prefix,
+            spec,
+            impl_name,
+            root,
+            config,
+        } => {
+            let format: tracey::graph::Format = match format {
+                Some(format) => format.parse()?,
+                None => tracey::graph::Format::default(),
+            };
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            let graph = tracey::graph::graph(
+                &project_root,
+                &cfg,
+                spec.as_deref(),
+                impl_name.as_deref(),
+                prefix.as_deref(),
+            )
+            .await?;
+            print!("{}", graph.render(format));
+            Ok(())
+        }
+
+        Command::Badge {
+            style,
+            out,
+            output,
+            root,
+        } => {
+            if output.is_some() && (out.is_some() || style.is_some()) {
+                eyre::bail!(
+                    "--output writes every badge; it cannot be combined with --out or --style"
+                );
+            }
+            let style: tracey::badge::Style = match style {
+                Some(style) => style.parse()?,
+                None => tracey::badge::Style::default(),
+            };
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let status = daemon::new_client(project_root)
+                .status()
+                .await
+                .map_err(|e| eyre!("status RPC failed: {:?}", e))?;
+            if let Some(output) = output {
+                let files = tracey::badge::render_all(&status);
+                for (name, svg) in &files {
+                    let path = output.join(name);
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .wrap_err_with(|| format!("creating {}", parent.display()))?;
+                    }
+                    std::fs::write(&path, svg)
+                        .wrap_err_with(|| format!("writing {}", path.display()))?;
+                }
+                println!("Wrote {} badges to {}", files.len(), output.display());
+                return Ok(());
+            }
+            let svg = tracey::badge::render(&status, style);
+            match out {
+                Some(out) => std::fs::write(&out, svg)
+                    .wrap_err_with(|| format!("writing {}", out.display()))?,
+                None => print!("{svg}"),
+            }
+            Ok(())
+        }
+
         Command::Gc { dry_run } => run_gc(dry_run),
 
+        // r[impl daemon.cli.cache]
+        Command::Cache {
+            root,
+            config,
+            action,
+        } => {
+            let project_root = match root {
+                Some(r) => r,
+                None => find_project_root()?,
+            };
+            run_cache(&project_root, &project_root.join(&config), action)
+        }
+
+        Command::Config {
+            root,
+            config,
+            action,
+        } => {
+            let project_root = match root {
+                Some(r) => r,
+                None => find_project_root()?,
+            };
+            run_config(&project_root, &config, action)
+        }
+
+        Command::Spec {
+            root,
+            config,
+            action,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = tracey::load_config(&project_root.join(&config))?;
+            run_spec(&project_root, &cfg, action).await
+        }
+
+        Command::Proofs { root, action } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            match action {
+                ProofsAction::ImportKani { results } => {
+                    let imported = tracey::proofs::import_kani(&project_root, &results)?;
+                    let proven = imported.harnesses.iter().filter(|h| h.proven).count();
+                    println!(
+                        "Imported {} Kani harnesses ({} proven) into {}",
+                        imported.harnesses.len(),
+                        proven,
+                        tracey::proofs::PROOFS_FILE
+                    );
+                    // A running daemon only reads proof results on rebuild.
+                    if daemon::is_running(&project_root).await {
+                        daemon::new_client(project_root)
+                            .reload()
+                            .await
+                            .map_err(|e| eyre!("reload RPC failed: {:?}", e))?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+
         Command::Export {
             output,
             root,
             config,
             sources,
-        } => bridge::export::run(root, config, output, sources).await,
+            redirects,
+        } => bridge::export::run(root, config, output, sources, redirects).await,
     }
 }
 
@@ -794,7 +2538,34 @@ async fn query_json(qc: &bridge::query::QueryClient, query: QueryCommand) -> (St
 
 #[cfg(test)]
 mod tests {
-    use super::ValidationDeny;
+    use super::{Args, Command, ValidationDeny};
+    use std::path::PathBuf;
+
+    fn parse(argv: &[&str]) -> Args {
+        let config = figue::builder::<Args>()
+            .unwrap()
+            .cli(|cli| cli.args(argv.iter().map(|a| a.to_string())))
+            .build();
+        figue::Driver::new(config).run().unwrap()
+    }
+
+    // r[verify cli.root]
+    #[test]
+    fn global_root_overrides_the_positional_root() {
+        let mut args = parse(&["--root", "/pinned", "files", "/positional"]);
+        args.pin_root();
+        let Command::Files { root, .. } = args.command else {
+            panic!("expected files command");
+        };
+        assert_eq!(root, Some(PathBuf::from("/pinned")));
+
+        let mut args = parse(&["files", "/positional"]);
+        args.pin_root();
+        let Command::Files { root, .. } = args.command else {
+            panic!("expected files command");
+        };
+        assert_eq!(root, Some(PathBuf::from("/positional")));
+    }
 
     #[test]
     fn parse_validation_deny_accepts_warnings_variants() {
@@ -1067,6 +2838,15 @@ async fn show_status(root: Option<PathBuf>, json: bool) -> Result<()> {
                 }
                 println!("  File events: {}", health.watcher_event_count);
                 println!("  Watched dirs: {}", health.watched_directories.len());
+                println!("  Connections: {}", health.load.connections);
+                println!(
+                    "  Editor requests: {} running, {} queued",
+                    health.load.interactive.in_flight, health.load.interactive.queued
+                );
+                println!(
+                    "  Batch requests: {} running, {} queued",
+                    health.load.batch.in_flight, health.load.batch.queued
+                );
             }
         }
         Ok(Err(e)) => {
@@ -1221,23 +3001,6 @@ fn setup_ai_clients(codex: bool, claude: bool) -> Result<()> {
     Ok(())
 }
 
-/// Load config for pre-commit / bump: silently no-op if missing, warn if unparseable.
-fn load_bump_config(config_path: &std::path::Path) -> tracey::config::Config {
-    if !config_path.exists() {
-        return tracey::config::Config::default();
-    }
-    match tracey::load_config(&config_path.to_path_buf()) {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            eprintln!(
-                "warning: failed to parse tracey config at {}: {e:?}",
-                config_path.display()
-            );
-            tracey::config::Config::default()
-        }
-    }
-}
-
 fn install_skill_to(skill_dir: &Path) -> Result<()> {
     let refs_dir = skill_dir.join("references");
     std::fs::create_dir_all(&refs_dir)
@@ -1456,7 +3219,179 @@ async fn kill_daemon(root: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Print the config as written, or as tracey sees it after resolving
+/// defaults, or check it.
+fn run_config(project_root: &Path, config: &Path, action: ConfigAction) -> Result<()> {
+    let config_path = &project_root.join(config);
+    match action {
+        ConfigAction::Show { resolved } => {
+            print!("{}", tracey::config::show(config_path, resolved)?);
+            Ok(())
+        }
+
+        ConfigAction::Check => {
+            let source = std::fs::read_to_string(config_path).wrap_err_with(|| {
+                format!("Failed to read config file: {}", config_path.display())
+            })?;
+            let filename = config.display().to_string();
+            let report = tracey::config_check::check(project_root, &filename, &source);
+            let color = std::env::var_os("NO_COLOR").is_none();
+            print!("{}", report.render(&filename, &source, color));
+            if report.errors() > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn run_spec(
+    project_root: &Path,
+    config: &tracey::config::Config,
+    action: SpecAction,
+) -> Result<()> {
+    use tracey::spec_diff::{self, SpecManifest};
+
+    let read_manifest = |path: &Path| -> Result<SpecManifest> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read manifest: {}", path.display()))?;
+        facet_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Failed to parse manifest {}: {e}", path.display()))
+    };
+
+    match action {
+        SpecAction::Manifest { git } => {
+            let manifest = spec_diff::manifest(project_root, config, git.as_deref()).await?;
+            println!(
+                "{}",
+                facet_json::to_string_pretty(&manifest).expect("JSON serialization failed")
+            );
+            Ok(())
+        }
+
+        SpecAction::Diff {
+            old,
+            new,
+            git,
+            format,
+            json,
+        } => {
+            let format = match (format, json) {
+                (Some(format), _) => format.parse()?,
+                (None, true) => spec_diff::Format::Json,
+                (None, false) => spec_diff::Format::default(),
+            };
+            let (old, new) = match (old, new, git) {
+                (None, None, Some(revision)) => (
+                    spec_diff::manifest(project_root, config, Some(&revision)).await?,
+                    spec_diff::manifest(project_root, config, None).await?,
+                ),
+                (Some(old), None, None) => (
+                    read_manifest(&old)?,
+                    spec_diff::manifest(project_root, config, None).await?,
+                ),
+                (Some(old), Some(new), None) => (read_manifest(&old)?, read_manifest(&new)?),
+                _ => eyre::bail!("pass one or two manifests, or --git <revision>"),
+            };
+            print!("{}", spec_diff::diff(&old, &new).render(format));
+            Ok(())
+        }
+    }
+}
+
+/// Show or clear the contents of a project's state directory.
+fn run_cache(project_root: &Path, config_path: &PathBuf, action: CacheAction) -> Result<()> {
+    use daemon::retention::{self, CacheKind, RetentionLimits};
+
+    let dir = daemon::state_dir(project_root);
+    match action {
+        CacheAction::Stats => {
+            let stats = retention::stats(&dir, project_root)
+                .wrap_err_with(|| format!("Failed to read state directory: {}", dir.display()))?;
+            println!("State directory: {}", dir.display());
+            let rows = stats
+                .kinds
+                .iter()
+                .map(|(kind, usage)| (kind.to_string(), *usage))
+                .chain([
+                    ("runtime".to_string(), stats.runtime),
+                    ("total".to_string(), stats.total()),
+                ]);
+            for (label, usage) in rows {
+                println!(
+                    "  {label:<8} {:>4} file(s) {:>10}",
+                    usage.files,
+                    retention::format_bytes(usage.bytes)
+                );
+            }
+
+            let config = tracey::load_config(config_path).ok();
+            let limits = RetentionLimits::from_config(config.and_then(|c| c.retention).as_ref());
+            println!(
+                "\nLogs are capped at {} in total; logs of exited bridges are removed after {} day(s).",
+                retention::format_bytes(limits.max_log_bytes),
+                limits.max_log_age.as_secs() / (24 * 60 * 60)
+            );
+            match limits.max_history_entries {
+                Some(max) => println!("History keeps the newest {max} snapshot(s)."),
+                None => println!("History is kept in full."),
+            }
+            match limits.max_audit_entries {
+                Some(max) => println!("The audit log keeps the newest {max} entries."),
+                None => println!("The audit log is kept in full."),
+            }
+        }
+        CacheAction::Clear { kind } => {
+            let kind = kind
+                .map(|k| k.parse::<CacheKind>())
+                .transpose()
+                .map_err(|e| eyre!(e))?;
+            let freed = retention::clear(&dir, project_root, kind)
+                .wrap_err_with(|| format!("Failed to clear state directory: {}", dir.display()))?;
+            println!(
+                "{}: Cleared {} file(s), freed {}",
+                "Success".green(),
+                freed.files,
+                retention::format_bytes(freed.bytes)
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Remove orphaned state directories whose projects no longer exist on disk.
+/// Apply, print, or export the edits of an edit-producing command,
+/// returning whether the files were changed.
+// r[impl cli.patch]
+fn finish_patch(patch: &tracey::patch::PatchSet, mode: &tracey::patch::Mode) -> Result<bool> {
+    use tracey::patch::Mode;
+
+    if patch.is_empty() {
+        return Ok(false);
+    }
+    let files = patch.files().count();
+    match mode {
+        Mode::Apply => {
+            patch.apply()?;
+            eprintln!("Changed {files} file(s).");
+            Ok(true)
+        }
+        Mode::DryRun => {
+            print!("{}", patch.to_diff());
+            eprintln!("Dry run: {files} file(s) would change; nothing was written.");
+            Ok(false)
+        }
+        Mode::Export(out) => {
+            patch.write_patch(out)?;
+            eprintln!(
+                "Wrote changes to {files} file(s) to {}; apply them with `git apply`.",
+                out.display()
+            );
+            Ok(false)
+        }
+    }
+}
+
 fn run_gc(dry_run: bool) -> Result<()> {
     let base = daemon::state_base_dir();
 
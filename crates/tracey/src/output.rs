@@ -1,38 +1,143 @@
 //! Output formatting for coverage reports
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use eyre::{Result, WrapErr};
 use facet::Facet;
 use owo_colors::OwoColorize;
-use tracey_core::{CoverageReport, RefVerb};
+use tracey_core::{CoverageReport, RefVerb, Reqs, RuleId, parse_rule_id};
+
+use crate::config::{self, Config};
 
 /// Output format
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum OutputFormat {
     #[default]
     Text,
     Json,
     Markdown,
     Html,
+    /// JUnit XML, one test case per rule, for CI test report ingestion
+    Junit,
 }
 
-impl OutputFormat {
-    pub fn from_str(s: &str) -> Option<Self> {
+impl std::str::FromStr for OutputFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
-            "text" => Some(Self::Text),
-            "json" => Some(Self::Json),
-            "markdown" | "md" => Some(Self::Markdown),
-            "html" => Some(Self::Html),
-            _ => None,
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            "junit" => Ok(Self::Junit),
+            other => Err(eyre::eyre!(
+                "Unsupported format `{other}`; expected text, json, markdown, html or junit"
+            )),
+        }
+    }
+}
+
+/// Compute the coverage report of one implementation in-process, without a
+/// daemon. Without `spec` the first spec is used, and without `impl_name`
+/// its first implementation, as in `tracey query`.
+///
+/// Also returns the section each rule is defined in, by heading title.
+// r[impl cli.report.format]
+pub async fn coverage_report(
+    project_root: &Path,
+    config: &Config,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+) -> Result<(CoverageReport, HashMap<RuleId, String>)> {
+    let spec_config = match spec {
+        Some(name) => config.specs.iter().find(|s| s.name == name),
+        None => config.specs.first(),
+    }
+    .ok_or_else(|| eyre::eyre!("Spec '{}' is not configured", spec.unwrap_or_default()))?;
+    let impl_config = match impl_name {
+        Some(name) => spec_config.impls.iter().find(|i| i.name == name),
+        None => spec_config.impls.first(),
+    }
+    .ok_or_else(|| {
+        eyre::eyre!(
+            "Spec '{}' has no implementation '{}'",
+            spec_config.name,
+            impl_name.unwrap_or_default()
+        )
+    })?;
+
+    let patterns: Vec<&str> = spec_config.include.iter().map(String::as_str).collect();
+    let rules = crate::load_rules_from_globs(project_root, &patterns, true)
+        .await
+        .wrap_err_with(|| format!("failed to load spec '{}'", spec_config.name))?;
+    let mut known_rule_ids = HashSet::new();
+    let mut sections = HashMap::new();
+    let mut prefixes = HashSet::new();
+    for rule in rules {
+        let Some(id) = parse_rule_id(&rule.def.id.to_string()) else {
+            continue;
+        };
+        let section = rule
+            .section_title
+            .or(rule.section)
+            .unwrap_or_else(|| spec_config.name.clone());
+        sections.insert(id.clone(), section);
+        known_rule_ids.insert(id);
+        prefixes.insert(rule.prefix);
+    }
+
+    let files = crate::files::files(
+        project_root,
+        config,
+        Some(&spec_config.name),
+        Some(&impl_config.name),
+    )?;
+    let roles = config::verb_roles(config);
+    let custom_verbs = roles.custom_verbs();
+    let mut reqs = Reqs::default();
+    let mut test_files = HashSet::new();
+    for (path, is_test) in files.impls.iter().flat_map(|f| &f.selected) {
+        let Ok(content) = std::fs::read_to_string(project_root.join(path)) else {
+            continue;
+        };
+        let extracted = Reqs::extract_from_content_with_verbs(path, &content, &custom_verbs);
+        reqs.references.extend(
+            extracted
+                .references
+                .into_iter()
+                .filter(|r| prefixes.contains(&r.prefix)),
+        );
+        if *is_test {
+            test_files.insert(path.clone());
         }
     }
+
+    let report =
+        CoverageReport::compute_with_roles(&spec_config.name, &known_rule_ids, &reqs, &roles)
+            .with_partial_weight(config::partial_weight(spec_config))
+            .with_enforcement(config::enforcement(spec_config))
+            .with_test_impls(config::test_impls(spec_config), |path| {
+                test_files.contains(path)
+            });
+    Ok((report, sections))
 }
 
-/// Render a coverage report in the specified format
-pub fn render_report(report: &CoverageReport, format: OutputFormat, verbose: bool) -> String {
+/// Render a coverage report in the specified format. `sections` maps rules
+/// to the section they are defined in, which JUnit output groups them by.
+pub fn render_report(
+    report: &CoverageReport,
+    sections: &HashMap<RuleId, String>,
+    format: OutputFormat,
+    verbose: bool,
+) -> String {
     match format {
         OutputFormat::Text => render_text(report, verbose),
         OutputFormat::Json => render_json(report),
         OutputFormat::Markdown => render_markdown(report, verbose),
         OutputFormat::Html => render_html(report, verbose),
+        OutputFormat::Junit => render_junit(report, sections),
     }
 }
 
@@ -108,7 +213,7 @@ fn render_text(report: &CoverageReport, verbose: bool) -> String {
                 r.file.display(),
                 r.line,
                 r.verb.as_str().dimmed(),
-                r.rule_id.yellow()
+                r.req_id.yellow()
             ));
         }
         output.push('\n');
@@ -145,14 +250,14 @@ fn render_text(report: &CoverageReport, verbose: bool) -> String {
                     RefVerb::Impl => "+",
                     RefVerb::Verify => "✓",
                     RefVerb::Depends => "→",
-                    RefVerb::Related => "~",
+                    RefVerb::Related | RefVerb::Custom(_) => "~",
                 };
                 let verb_color = match verb {
                     RefVerb::Define => verb.as_str().blue().to_string(),
                     RefVerb::Impl => verb.as_str().green().to_string(),
                     RefVerb::Verify => verb.as_str().cyan().to_string(),
                     RefVerb::Depends => verb.as_str().magenta().to_string(),
-                    RefVerb::Related => verb.as_str().dimmed().to_string(),
+                    RefVerb::Related | RefVerb::Custom(_) => verb.as_str().dimmed().to_string(),
                 };
 
                 output.push_str(&format!(
@@ -266,7 +371,7 @@ fn render_markdown(report: &CoverageReport, verbose: bool) -> String {
                 r.file.display(),
                 r.line,
                 r.verb.as_str(),
-                r.rule_id
+                r.req_id
             ));
         }
         output.push('\n');
@@ -344,7 +449,7 @@ fn render_html(report: &CoverageReport, verbose: bool) -> String {
                 r.file.display(),
                 r.line,
                 r.verb.as_str(),
-                r.rule_id
+                r.req_id
             ));
         }
         output.push_str("</ul>\n");
@@ -380,3 +485,199 @@ fn render_html(report: &CoverageReport, verbose: bool) -> String {
     output.push_str("</body>\n</html>\n");
     output
 }
+
+/// Render the report as JUnit XML: each rule is a test case that passes when
+/// it has both an implementation and a verification reference. Test cases are
+/// grouped into one suite per spec section.
+// r[impl cli.report.junit]
+fn render_junit(report: &CoverageReport, sections: &HashMap<RuleId, String>) -> String {
+    let implemented = report.references_by_verb.get(&RefVerb::Impl);
+    let verified = report.references_by_verb.get(&RefVerb::Verify);
+    let has =
+        |refs: Option<&HashMap<RuleId, _>>, rule_id| refs.is_some_and(|r| r.contains_key(rule_id));
+
+    let mut by_section: BTreeMap<&str, Vec<(&RuleId, Option<&str>)>> = BTreeMap::new();
+    let all_rules = report
+        .covered_rules
+        .iter()
+        .chain(&report.stale_rules)
+        .chain(&report.uncovered_rules);
+    for rule_id in all_rules {
+        let failure = if report.uncovered_rules.contains(rule_id) {
+            Some("rule has no references")
+        } else if report.stale_rules.contains(rule_id) {
+            Some("rule is only referenced at an older version")
+        } else if !has(implemented, rule_id) {
+            Some("rule has no impl reference")
+        } else if !has(verified, rule_id) {
+            Some("rule has no verify reference")
+        } else {
+            None
+        };
+        let section = sections
+            .get(rule_id)
+            .map_or(report.spec_name.as_str(), String::as_str);
+        by_section
+            .entry(section)
+            .or_default()
+            .push((rule_id, failure));
+    }
+
+    let failures = by_section
+        .values()
+        .flatten()
+        .filter(|(_, failure)| failure.is_some())
+        .count();
+
+    let mut output = String::new();
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        xml_escape(&report.spec_name),
+        report.total_rules,
+        failures
+    ));
+    for (section, mut cases) in by_section {
+        cases.sort();
+        let section_failures = cases.iter().filter(|(_, f)| f.is_some()).count();
+        output.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            xml_escape(section),
+            cases.len(),
+            section_failures
+        ));
+        let classname = format!("{}.{}", report.spec_name, section);
+        for (rule_id, failure) in cases {
+            let name = rule_id.to_string();
+            match failure {
+                None => output.push_str(&format!(
+                    "    <testcase classname=\"{}\" name=\"{}\"/>\n",
+                    xml_escape(&classname),
+                    xml_escape(&name)
+                )),
+                Some(message) => output.push_str(&format!(
+                    "    <testcase classname=\"{}\" name=\"{}\">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                    xml_escape(&classname),
+                    xml_escape(&name),
+                    message
+                )),
+            }
+        }
+        output.push_str("  </testsuite>\n");
+    }
+    output.push_str("</testsuites>\n");
+    output
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quick_xml::events::{BytesStart, Event};
+
+    /// Test suites of a JUnit document by name, with the names of their test
+    /// cases and the message of any failure.
+    fn parse_junit(xml: &str) -> BTreeMap<String, Vec<(String, Option<String>)>> {
+        let attribute = |e: &BytesStart, key: &str| {
+            e.try_get_attribute(key)
+                .unwrap()
+                .unwrap()
+                .unescape_value()
+                .unwrap()
+                .into_owned()
+        };
+        let mut reader = quick_xml::Reader::from_str(xml);
+        let mut suites: BTreeMap<String, Vec<(String, Option<String>)>> = BTreeMap::new();
+        let mut suite = String::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                    b"testsuite" => {
+                        suite = attribute(&e, "name");
+                        suites.entry(suite.clone()).or_default();
+                    }
+                    b"testcase" => {
+                        let case = (attribute(&e, "name"), None);
+                        suites.get_mut(&suite).unwrap().push(case);
+                    }
+                    b"failure" => {
+                        let case = suites.get_mut(&suite).unwrap().last_mut().unwrap();
+                        case.1 = Some(attribute(&e, "message"));
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        suites
+    }
+
+    // r[verify cli.report.format]
+    // r[verify cli.report.junit]
+    #[tokio::test]
+    async fn junit_report_has_a_suite_per_section() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Sessions & tokens\n\n\
+             r[auth.login]\nUsers MUST log in.\n\n\
+             r[auth.logout]\nUsers MUST log out.\n\n\
+             # Storage\n\n\
+             r[store.put+2]\nValues MUST be stored.\n\n\
+             r[store.get]\nValues MUST be read back.\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn login() {}\n\n\
+             // r[verify auth.login]\n#[test]\nfn login_works() {}\n\n\
+             // r[impl auth.logout]\nfn logout() {}\n\n\
+             // r[impl store.put]\nfn put() {}\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            "specs ({name app, include (spec.md), impls ({name rust, include (src/**/*.rs)})})",
+        )
+        .unwrap();
+
+        let (report, sections) = coverage_report(root, &config, None, None).await.unwrap();
+        let xml = render_report(&report, &sections, OutputFormat::Junit, false);
+        let suites = parse_junit(&xml);
+        let case =
+            |name: &str, failure: Option<&str>| (name.to_string(), failure.map(str::to_string));
+        assert_eq!(
+            suites["Sessions & tokens"],
+            [
+                case("auth.login", None),
+                case("auth.logout", Some("rule has no verify reference")),
+            ]
+        );
+        assert_eq!(
+            suites["Storage"],
+            [
+                case("store.get", Some("rule has no references")),
+                case(
+                    "store.put+2",
+                    Some("rule is only referenced at an older version")
+                ),
+            ]
+        );
+        assert_eq!(suites.len(), 2);
+
+        assert!(
+            coverage_report(root, &config, None, Some("go"))
+                .await
+                .is_err()
+        );
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
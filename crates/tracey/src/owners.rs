@@ -0,0 +1,343 @@
+//! `tracey owners` implementation.
+//!
+//! A rule's owner comes from the `owner=` attribute on its marker
+//! (`r[auth.login owner=@identity]`) or, failing that, from the config's
+//! `owners_file`: a CODEOWNERS-style list of rule ID patterns and owners in
+//! which the last matching line wins. The report groups coverage by owner so
+//! each team can see, and be held to, the state of its own rules.
+
+use eyre::{Result, WrapErr};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracey_api::ApiRule;
+use tracey_core::rule_pattern_matches;
+
+use crate::ExtractedRule;
+use crate::config::Config;
+
+/// Name the report gives rules without an owner.
+const UNOWNED: &str = "(unowned)";
+
+/// A parsed owners file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnersFile {
+    /// `(pattern, owner)` in file order.
+    entries: Vec<(String, String)>,
+}
+
+impl OwnersFile {
+    /// Parse an owners file. Each non-blank line that is not a `#` comment is
+    /// a rule ID or wildcard pattern (`auth.*`) followed by one owner.
+    // r[impl config.owners-file]
+    pub fn parse(content: &str) -> std::result::Result<Self, String> {
+        let mut entries = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some(pattern), Some(owner), None) => {
+                    entries.push((pattern.to_string(), owner.to_string()));
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: expected `<rule pattern> <owner>`, got `{line}`",
+                        index + 1
+                    ));
+                }
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Load the config's owners file, or an empty one if none is configured.
+    pub fn load(project_root: &Path, config: &Config) -> Result<Self> {
+        let Some(path) = &config.owners_file else {
+            return Ok(Self::default());
+        };
+        let content = std::fs::read_to_string(project_root.join(path))
+            .wrap_err_with(|| format!("failed to read owners file {path}"))?;
+        Self::parse(&content).map_err(|e| eyre::eyre!("invalid owners file {path}: {e}"))
+    }
+
+    /// Owner of the rule with base ID `base`: the last line whose pattern
+    /// matches it.
+    pub fn owner_of(&self, base: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern == base || rule_pattern_matches(pattern, base))
+            .map(|(_, owner)| owner.as_str())
+    }
+
+    /// Give each rule without an `owner=` attribute its owner from the file.
+    pub fn assign(&self, rules: &mut [ExtractedRule]) {
+        for rule in rules.iter_mut().filter(|r| r.owner.is_none()) {
+            rule.owner = self.owner_of(&rule.def.id.base).map(str::to_string);
+        }
+    }
+}
+
+/// Coverage of the rules one owner is responsible for. A rule counts once
+/// for each implementation of its spec.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnerStats {
+    /// `None` for rules nobody owns.
+    pub owner: Option<String>,
+    pub total: usize,
+    /// Implemented rules, partially implemented ones at their weight.
+    pub implemented: f64,
+    pub verified: usize,
+}
+
+impl OwnerStats {
+    fn add(&mut self, rule: &ApiRule) {
+        self.total += 1;
+        if !rule.is_stale {
+            self.implemented += rule.impl_weight();
+        }
+        if !rule.verify_refs.is_empty() {
+            self.verified += 1;
+        }
+    }
+
+    /// Share of the owner's rules that are implemented, in percent.
+    pub fn impl_percent(&self) -> f64 {
+        self.implemented * 100.0 / self.total as f64
+    }
+
+    /// Share of the owner's rules that are verified, in percent.
+    pub fn verify_percent(&self) -> f64 {
+        self.verified as f64 * 100.0 / self.total as f64
+    }
+}
+
+/// Coverage grouped by owner.
+#[derive(Debug, Default)]
+pub struct OwnersReport {
+    /// Owners by name, with unowned rules last.
+    pub owners: Vec<OwnerStats>,
+    /// Implementation coverage every owner must reach, in percent.
+    pub min: Option<f64>,
+}
+
+impl OwnersReport {
+    /// Owners whose implementation coverage is below the minimum. Unowned
+    /// rules are never held to it.
+    pub fn failing(&self) -> Vec<&OwnerStats> {
+        let Some(min) = self.min else {
+            return Vec::new();
+        };
+        self.owners
+            .iter()
+            .filter(|stats| stats.owner.is_some() && stats.impl_percent() < min)
+            .collect()
+    }
+
+    /// Render the report as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Owners");
+        let _ = writeln!(out);
+        if self.owners.is_empty() {
+            let _ = writeln!(out, "No rules found.");
+            return out;
+        }
+
+        let _ = writeln!(out, "| Owner | Rules | Implemented | Verified |");
+        let _ = writeln!(out, "|-------|------:|------------:|---------:|");
+        for stats in &self.owners {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {:.0}% | {:.0}% |",
+                stats.owner.as_deref().unwrap_or(UNOWNED),
+                stats.total,
+                stats.impl_percent(),
+                stats.verify_percent()
+            );
+        }
+
+        if let Some(min) = self.min {
+            let _ = writeln!(out);
+            let failing = self.failing();
+            if failing.is_empty() {
+                let _ = writeln!(out, "Every owner is at or above {min}% implemented.");
+            }
+            for stats in failing {
+                let _ = writeln!(
+                    out,
+                    "- {} is below {min}% implemented ({:.0}%).",
+                    stats.owner.as_deref().unwrap_or(UNOWNED),
+                    stats.impl_percent()
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Group `rules` by owner, keeping only `only` when given. Draft, removed
+/// and waived rules are left out, as in release checks.
+pub fn group<'a>(
+    rules: impl IntoIterator<Item = &'a ApiRule>,
+    only: Option<&str>,
+) -> Vec<OwnerStats> {
+    let mut by_owner: BTreeMap<Option<&str>, OwnerStats> = BTreeMap::new();
+    for rule in rules {
+        if matches!(rule.status.as_deref(), Some("draft" | "removed")) || rule.is_waived() {
+            continue;
+        }
+        let owner = rule.owner.as_deref();
+        if only.is_some_and(|only| owner != Some(only)) {
+            continue;
+        }
+        by_owner
+            .entry(owner)
+            .or_insert_with(|| OwnerStats {
+                owner: owner.map(str::to_string),
+                ..OwnerStats::default()
+            })
+            .add(rule);
+    }
+    let mut owners: Vec<OwnerStats> = by_owner.into_values().collect();
+    owners.sort_by_key(|stats| stats.owner.is_none());
+    owners
+}
+
+/// Report coverage by owner across every spec and implementation.
+// r[impl cli.owners]
+pub async fn owners(
+    project_root: &Path,
+    config: &Config,
+    only: Option<&str>,
+    min: Option<f64>,
+) -> Result<OwnersReport> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let rules = data.forward_by_impl.values().flat_map(|f| &f.rules);
+    Ok(OwnersReport {
+        owners: group(rules, only),
+        min,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify config.owners-file]
+    #[test]
+    fn last_matching_line_wins() {
+        let file = OwnersFile::parse(
+            "# Rule owners\n\
+             *            @platform\n\
+             auth.*       @identity\n\
+             auth.audit   @security\n",
+        )
+        .unwrap();
+        assert_eq!(file.owner_of("auth.login"), Some("@identity"));
+        assert_eq!(file.owner_of("auth.audit"), Some("@security"));
+        assert_eq!(file.owner_of("channel.open"), Some("@platform"));
+        assert_eq!(OwnersFile::default().owner_of("auth.login"), None);
+
+        let err = OwnersFile::parse("auth.* @identity @security\n").unwrap_err();
+        assert!(err.starts_with("line 1:"), "{err}");
+    }
+
+    // r[verify cli.owners]
+    // r[verify markdown.syntax.owner]
+    #[tokio::test]
+    async fn reports_coverage_per_owner() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\n\
+             r[auth.login owner=@identity]\nUsers MUST log in.\n\n\
+             r[auth.logout owner=@identity]\nUsers MUST log out.\n\n\
+             r[auth.future owner=@identity status=draft]\nUsers MAY fly.\n\n\
+             r[billing.charge]\nCards MUST be charged.\n\n\
+             r[misc.note]\nNotes MAY be kept.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("OWNERS"),
+            "auth.* @platform\nbilling.* @billing\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\n// r[verify auth.login]\nfn a() {}\n\
+             // r[impl billing.charge]\nfn b() {}\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            r#"owners_file OWNERS
+specs (
+    {
+        name app
+        include (spec.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap();
+
+        let report = owners(root, &config, None, Some(75.0)).await.unwrap();
+        let rows: Vec<_> = report
+            .owners
+            .iter()
+            .map(|s| {
+                (
+                    s.owner.as_deref(),
+                    s.total,
+                    s.impl_percent(),
+                    s.verify_percent(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (Some("@billing"), 1, 100.0, 0.0),
+                (Some("@identity"), 2, 50.0, 50.0),
+                (None, 1, 0.0, 0.0),
+            ]
+        );
+        let failing: Vec<_> = report.failing().iter().map(|s| s.owner.clone()).collect();
+        assert_eq!(failing, [Some("@identity".to_string())]);
+        let markdown = report.to_markdown();
+        assert!(
+            markdown.contains("| @identity | 2 | 50% | 50% |"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("| (unowned) | 1 | 0% | 0% |"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("- @identity is below 75% implemented (50%)."),
+            "{markdown}"
+        );
+
+        let report = owners(root, &config, Some("@billing"), Some(75.0))
+            .await
+            .unwrap();
+        assert_eq!(report.owners.len(), 1);
+        assert!(report.failing().is_empty());
+        assert!(
+            report
+                .to_markdown()
+                .contains("Every owner is at or above 75% implemented.")
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! `tracey params` implementation.
+//!
+//! A rule can declare machine-readable parameters as extra attributes on its
+//! marker (`r[limits.max-frame size=16384]`). `tracey params` turns them into
+//! constants for the code to use. Each Rust constant carries a `define`
+//! reference to its rule, so a constant and the number in the spec it comes
+//! from are tied together, and `--check` fails CI when the generated file
+//! has fallen behind the spec.
+
+use eyre::{Result, WrapErr};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracey_core::{RuleId, parse_rule_id};
+
+use crate::config::Config;
+
+/// Output formats of `tracey params`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Rust,
+    Json,
+}
+
+impl Format {
+    /// Infer the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Ok(Self::Rust),
+            "json" => Ok(Self::Json),
+            other => Err(eyre::eyre!(
+                "Unsupported format `{other}`; expected rust or json"
+            )),
+        }
+    }
+}
+
+/// The parameters one rule declares.
+#[derive(Debug, Clone)]
+pub struct RuleParams {
+    /// Marker prefix (e.g. `r`).
+    pub prefix: String,
+    /// Current ID, including version.
+    pub id: RuleId,
+    pub params: BTreeMap<String, String>,
+}
+
+/// A parameter value, typed by what it parses as.
+#[derive(Debug, Clone, PartialEq)]
+enum Value<'a> {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    Bool(bool),
+    Str(&'a str),
+}
+
+impl<'a> Value<'a> {
+    fn parse(value: &'a str) -> Self {
+        if let Ok(n) = value.parse() {
+            return Self::Unsigned(n);
+        }
+        if let Ok(n) = value.parse() {
+            return Self::Signed(n);
+        }
+        // `f64::from_str` also takes `inf` and `NaN`, which stay strings.
+        let numeric = value
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'));
+        if numeric
+            && let Ok(n) = value.parse::<f64>()
+            && n.is_finite()
+        {
+            return Self::Float(n);
+        }
+        match value {
+            "true" => Self::Bool(true),
+            "false" => Self::Bool(false),
+            _ => Self::Str(value),
+        }
+    }
+}
+
+/// Collect the parameters of every rule in the configured specs, or only in
+/// `spec` when given, ordered by rule ID.
+pub async fn collect(
+    project_root: &Path,
+    config: &Config,
+    spec: Option<&str>,
+) -> Result<Vec<RuleParams>> {
+    if let Some(name) = spec
+        && !config.specs.iter().any(|s| s.name == name)
+    {
+        eyre::bail!("Spec '{name}' is not configured");
+    }
+    let mut found = Vec::new();
+    for spec_config in config
+        .specs
+        .iter()
+        .filter(|s| spec.is_none_or(|name| s.name == name))
+    {
+        let patterns: Vec<&str> = spec_config.include.iter().map(String::as_str).collect();
+        let rules = crate::load_rules_from_globs(project_root, &patterns, true)
+            .await
+            .wrap_err_with(|| format!("failed to load spec '{}'", spec_config.name))?;
+        found.extend(
+            rules
+                .into_iter()
+                .filter(|rule| !rule.params.is_empty())
+                .filter_map(|rule| {
+                    Some(RuleParams {
+                        id: parse_rule_id(&rule.def.id.to_string())?,
+                        prefix: rule.prefix,
+                        params: rule.params,
+                    })
+                }),
+        );
+    }
+    found.sort_by(|a, b| a.id.base.cmp(&b.id.base));
+    Ok(found)
+}
+
+/// `limits.max-frame` and `size` -> `LIMITS_MAX_FRAME_SIZE`
+fn constant_name(base: &str, key: &str) -> String {
+    let words: Vec<String> = [base, key]
+        .iter()
+        .flat_map(|part| part.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let name = words.join("_");
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Render `rules` in `format`.
+// r[impl cli.params]
+pub fn render(rules: &[RuleParams], format: Format) -> Result<String> {
+    match format {
+        Format::Rust => render_rust(rules),
+        Format::Json => Ok(render_json(rules)),
+    }
+}
+
+fn render_rust(rules: &[RuleParams]) -> Result<String> {
+    let mut out = String::from("// Generated by `tracey params` from the spec; do not edit.\n");
+    let mut names: BTreeMap<String, &RuleId> = BTreeMap::new();
+    for rule in rules {
+        for (key, value) in &rule.params {
+            let name = constant_name(&rule.id.base, key);
+            if let Some(other) = names.insert(name.clone(), &rule.id) {
+                eyre::bail!(
+                    "`{key}` of {} and a parameter of {other} both become `{name}`",
+                    rule.id
+                );
+            }
+            let (ty, literal) = match Value::parse(value) {
+                Value::Unsigned(n) => ("u64", n.to_string()),
+                Value::Signed(n) => ("i64", n.to_string()),
+                Value::Float(n) => ("f64", format!("{n:?}")),
+                Value::Bool(b) => ("bool", b.to_string()),
+                Value::Str(s) => ("&str", format!("{s:?}")),
+            };
+            let _ = writeln!(out);
+            let _ = writeln!(out, "/// {}[define {}]", rule.prefix, rule.id);
+            let _ = writeln!(out, "pub const {name}: {ty} = {literal};");
+        }
+    }
+    Ok(out)
+}
+
+fn render_json(rules: &[RuleParams]) -> String {
+    let object: serde_json::Map<String, serde_json::Value> = rules
+        .iter()
+        .map(|rule| {
+            let params = rule
+                .params
+                .iter()
+                .map(|(key, value)| {
+                    let value = match Value::parse(value) {
+                        Value::Unsigned(n) => n.into(),
+                        Value::Signed(n) => n.into(),
+                        Value::Float(n) => n.into(),
+                        Value::Bool(b) => b.into(),
+                        Value::Str(s) => s.into(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect();
+            (rule.id.base.clone(), serde_json::Value::Object(params))
+        })
+        .collect();
+    let mut out = serde_json::to_string_pretty(&object).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// Whether the file at `path` already holds `expected`.
+pub fn is_up_to_date(path: &Path, expected: &str) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|current| current == expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify markdown.syntax.params]
+    // r[verify cli.params]
+    #[tokio::test]
+    async fn marker_parameters_become_constants() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Limits\n\n\
+             r[limits.max-frame+2 size=16384 owner=@wire status=stable]\n\
+             Frames MUST NOT exceed the size limit.\n\n\
+             r[limits.retry backoff=1.5 offset=-3 jitter=true\n    \
+             reason=\"flaky links, mostly\"]\n\
+             Retries MUST back off.\n\n\
+             r[limits.plain]\nNo parameters here.\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            "specs ({name app, include (spec.md), impls ({name rust, include (src/**/*.rs)})})",
+        )
+        .unwrap();
+
+        let rules = collect(root, &config, None).await.unwrap();
+        let ids: Vec<String> = rules.iter().map(|r| r.id.to_string()).collect();
+        assert_eq!(ids, ["limits.max-frame+2", "limits.retry"]);
+        // `owner=` is tracey's own attribute, not a parameter.
+        assert_eq!(rules[0].params.keys().collect::<Vec<_>>(), ["size"]);
+
+        let rust = render(&rules, Format::Rust).unwrap();
+        assert_eq!(
+            rust,
+            "// Generated by `tracey params` from the spec; do not edit.\n\
+             \n\
+             /// r[define limits.max-frame+2]\n\
+             pub const LIMITS_MAX_FRAME_SIZE: u64 = 16384;\n\
+             \n\
+             /// r[define limits.retry]\n\
+             pub const LIMITS_RETRY_BACKOFF: f64 = 1.5;\n\
+             \n\
+             /// r[define limits.retry]\n\
+             pub const LIMITS_RETRY_JITTER: bool = true;\n\
+             \n\
+             /// r[define limits.retry]\n\
+             pub const LIMITS_RETRY_OFFSET: i64 = -3;\n\
+             \n\
+             /// r[define limits.retry]\n\
+             pub const LIMITS_RETRY_REASON: &str = \"flaky links, mostly\";\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&rules, Format::Json).unwrap()).unwrap();
+        assert_eq!(json["limits.max-frame"]["size"], 16384);
+        assert_eq!(json["limits.retry"]["backoff"], 1.5);
+        assert_eq!(json["limits.retry"]["offset"], -3);
+        assert_eq!(json["limits.retry"]["jitter"], true);
+        assert_eq!(json["limits.retry"]["reason"], "flaky links, mostly");
+
+        let out = root.join("params.rs");
+        assert!(!is_up_to_date(&out, &rust));
+        std::fs::write(&out, &rust).unwrap();
+        assert!(is_up_to_date(&out, &rust));
+
+        assert!(collect(root, &config, Some("other")).await.is_err());
+    }
+
+    #[test]
+    fn values_are_typed_conservatively() {
+        assert_eq!(Value::parse("inf"), Value::Str("inf"));
+        assert_eq!(Value::parse("NaN"), Value::Str("NaN"));
+        assert_eq!(Value::parse("1e3"), Value::Float(1000.0));
+        assert_eq!(Value::parse("1e400"), Value::Str("1e400"));
+        assert_eq!(Value::parse("0x10"), Value::Str("0x10"));
+        assert_eq!(constant_name("v2.3d", "max-len"), "V2_3D_MAX_LEN");
+        assert_eq!(constant_name("3d.mesh", "max"), "_3D_MESH_MAX");
+    }
+}
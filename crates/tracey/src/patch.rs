@@ -0,0 +1,415 @@
+//! Multi-file edits shared by the commands that rewrite sources.
+//!
+//! Commands such as `tracey adopt --annotate` and `tracey bump` first collect
+//! their edits into a [`PatchSet`] instead of writing files one by one. The
+//! set can then be previewed as a unified diff, exported as a `.patch` file
+//! for `git apply`, or applied. Applying is all or nothing: every file is
+//! checked against the content the edits were computed from, new contents
+//! are staged next to their files, and only then swapped in, so a failure
+//! part way leaves the tree as it was.
+
+use eyre::{Result, WrapErr, bail};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Unchanged lines shown around each change in a diff.
+const CONTEXT: usize = 3;
+
+/// Above this many line pairs, the changed middle of a file is diffed as one
+/// replaced block instead of computing the longest common subsequence.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// New contents for one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// What the edit was computed from; the file must still hold this when
+    /// the set is applied.
+    pub original: String,
+    pub updated: String,
+}
+
+/// Edits to files under one project root, keyed by path relative to it.
+#[derive(Debug, Clone, Default)]
+pub struct PatchSet {
+    root: PathBuf,
+    changes: BTreeMap<PathBuf, FileChange>,
+}
+
+/// What an edit-producing command does with its patch set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Write the changes to the files.
+    Apply,
+    /// Print the diff and leave the files alone.
+    DryRun,
+    /// Write the diff to a `.patch` file and leave the files alone.
+    Export(PathBuf),
+}
+
+impl Mode {
+    /// Mode for the `--dry-run` and `--patch FILE` flags.
+    pub fn from_flags(dry_run: bool, patch: Option<PathBuf>) -> Result<Self> {
+        match (dry_run, patch) {
+            (true, Some(_)) => bail!("--dry-run and --patch cannot be combined"),
+            (true, None) => Ok(Mode::DryRun),
+            (false, Some(path)) => Ok(Mode::Export(path)),
+            (false, None) => Ok(Mode::Apply),
+        }
+    }
+}
+
+impl PatchSet {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            changes: BTreeMap::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changed files, relative to the root, in path order.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.changes.keys().map(PathBuf::as_path)
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&FileChange> {
+        self.changes.get(path)
+    }
+
+    /// Record that `path` goes from `original` to `updated`. A later call for
+    /// the same file keeps the first original, so edits can be layered.
+    pub fn set(&mut self, path: impl Into<PathBuf>, original: String, updated: String) {
+        let path = path.into();
+        let original = match self.changes.remove(&path) {
+            Some(previous) => previous.original,
+            None => original,
+        };
+        if original != updated {
+            self.changes.insert(path, FileChange { original, updated });
+        }
+    }
+
+    /// Edit `path` with `edit`, starting from its pending contents if it was
+    /// already changed, or from disk otherwise.
+    pub fn modify(
+        &mut self,
+        path: impl Into<PathBuf>,
+        edit: impl FnOnce(&str) -> String,
+    ) -> Result<()> {
+        let path = path.into();
+        let current = match self.changes.get(&path) {
+            Some(change) => change.updated.clone(),
+            None => {
+                let full = self.root.join(&path);
+                std::fs::read_to_string(&full)
+                    .wrap_err_with(|| format!("failed to read {}", full.display()))?
+            }
+        };
+        let updated = edit(&current);
+        self.set(path, current, updated);
+        Ok(())
+    }
+
+    /// The changes as a unified diff with `a/` and `b/` path prefixes, as
+    /// `git apply` and `patch -p1` expect.
+    pub fn to_diff(&self) -> String {
+        let mut out = String::new();
+        for (path, change) in &self.changes {
+            let name = path.to_string_lossy().replace('\\', "/");
+            let _ = writeln!(out, "diff --git a/{name} b/{name}");
+            let _ = writeln!(out, "--- a/{name}");
+            let _ = writeln!(out, "+++ b/{name}");
+            out.push_str(&unified_hunks(&change.original, &change.updated));
+        }
+        out
+    }
+
+    /// Write the diff to `out`.
+    pub fn write_patch(&self, out: &Path) -> Result<()> {
+        std::fs::write(out, self.to_diff())
+            .wrap_err_with(|| format!("failed to write {}", out.display()))
+    }
+
+    /// Write every change, or none of them.
+    ///
+    /// Fails without touching anything if a file no longer holds the content
+    /// its edit was computed from. New contents are written to temporary
+    /// files next to their targets first, then renamed over them; if a rename
+    /// fails, the files already replaced get their original contents back.
+    pub fn apply(&self) -> Result<()> {
+        for (path, change) in &self.changes {
+            let full = self.root.join(path);
+            let current = std::fs::read_to_string(&full)
+                .wrap_err_with(|| format!("failed to read {}", full.display()))?;
+            if current != change.original {
+                bail!(
+                    "{} changed since the edits were planned; no files were written",
+                    path.display()
+                );
+            }
+        }
+
+        let mut staged: Vec<(PathBuf, PathBuf, &FileChange)> = Vec::new();
+        for (path, change) in &self.changes {
+            let full = self.root.join(path);
+            let temp = temp_path(&full);
+            if let Err(e) = stage(&full, &temp, &change.updated) {
+                let _ = std::fs::remove_file(&temp);
+                for (_, temp, _) in &staged {
+                    let _ = std::fs::remove_file(temp);
+                }
+                return Err(e.wrap_err("no files were written"));
+            }
+            staged.push((full, temp, change));
+        }
+
+        for (i, (full, temp, _)) in staged.iter().enumerate() {
+            if let Err(e) = std::fs::rename(temp, full) {
+                for (done, _, change) in &staged[..i] {
+                    let _ = std::fs::write(done, &change.original);
+                }
+                for (_, temp, _) in &staged[i..] {
+                    let _ = std::fs::remove_file(temp);
+                }
+                return Err(e).wrap_err_with(|| {
+                    format!(
+                        "failed to replace {}; the files already written were restored",
+                        full.display()
+                    )
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Hidden sibling of `path` that holds its new contents until the swap.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.tracey-tmp"))
+}
+
+/// Write `contents` to `temp` with the permissions of `target`.
+fn stage(target: &Path, temp: &Path, contents: &str) -> Result<()> {
+    std::fs::write(temp, contents)
+        .wrap_err_with(|| format!("failed to write {}", temp.display()))?;
+    let permissions = std::fs::metadata(target)
+        .wrap_err_with(|| format!("failed to read {}", target.display()))?
+        .permissions();
+    std::fs::set_permissions(temp, permissions)
+        .wrap_err_with(|| format!("failed to set permissions on {}", temp.display()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Line-level edit script from `old` to `new`.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(Op, &str)> = old[..prefix].iter().map(|l| (Op::Equal, *l)).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        ops.extend(old_mid.iter().map(|l| (Op::Delete, *l)));
+        ops.extend(new_mid.iter().map(|l| (Op::Insert, *l)));
+    } else {
+        // lcs[i][j]: longest common subsequence of old_mid[i..] and new_mid[j..].
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * (m + 1) + j] = if old_mid[i] == new_mid[j] {
+                    lcs[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * (m + 1) + j].max(lcs[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push((Op::Equal, old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[(i + 1) * (m + 1) + j] >= lcs[i * (m + 1) + j + 1]) {
+                ops.push((Op::Delete, old_mid[i]));
+                i += 1;
+            } else {
+                ops.push((Op::Insert, new_mid[j]));
+                j += 1;
+            }
+        }
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|l| (Op::Equal, *l)));
+    ops
+}
+
+/// `start,count` of a hunk side; an empty side names the line before it.
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+/// The `@@` hunks of a unified diff from `old` to `new`.
+fn unified_hunks(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let mut out = String::new();
+    // Lines of each side before ops[i].
+    let (mut old_pos, mut new_pos) = (0, 0);
+    let mut i = 0;
+    while i < ops.len() {
+        let Some(first) = (i..ops.len()).find(|&k| ops[k].0 != Op::Equal) else {
+            break;
+        };
+        let start = first.saturating_sub(CONTEXT).max(i);
+        for (op, _) in &ops[i..start] {
+            debug_assert_eq!(*op, Op::Equal);
+            old_pos += 1;
+            new_pos += 1;
+        }
+
+        // Extend the hunk while the next change is close enough that the
+        // context between them would overlap.
+        let mut last = first;
+        let mut k = first + 1;
+        while k < ops.len() && k - last <= 2 * CONTEXT + 1 {
+            if ops[k].0 != Op::Equal {
+                last = k;
+            }
+            k += 1;
+        }
+        let end = (last + 1 + CONTEXT).min(ops.len());
+
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_count = hunk.iter().filter(|(op, _)| *op != Op::Delete).count();
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            hunk_range(old_pos, old_count),
+            hunk_range(new_pos, new_count)
+        );
+        for (op, line) in hunk {
+            out.push(match op {
+                Op::Equal => ' ',
+                Op::Delete => '-',
+                Op::Insert => '+',
+            });
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        old_pos += old_count;
+        new_pos += new_count;
+        i = end;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_has_context_and_merges_close_changes() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\no\np\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\nI\nj\nk\nl\nm\nn\no\np\nadded";
+        assert_eq!(
+            unified_hunks(old, new),
+            "\
+@@ -1,12 +1,12 @@
+ a
+-b
++B
+ c
+ d
+ e
+ f
+ g
+ h
+-i
++I
+ j
+ k
+ l
+@@ -14,3 +14,4 @@
+ n
+ o
+ p
++added
+\\ No newline at end of file
+"
+        );
+        assert_eq!(unified_hunks("", "x\n"), "@@ -0,0 +1 @@\n+x\n");
+    }
+
+    // r[verify cli.patch]
+    #[test]
+    fn apply_is_all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.md"), "one\n").unwrap();
+        std::fs::write(root.join("b.md"), "two\n").unwrap();
+
+        let mut patch = PatchSet::new(root);
+        patch.modify("a.md", |s| s.replace("one", "ONE")).unwrap();
+        patch.modify("b.md", |s| s.replace("two", "TWO")).unwrap();
+        patch.modify("a.md", |s| format!("{s}more\n")).unwrap();
+        assert_eq!(patch.get(Path::new("a.md")).unwrap().original, "one\n");
+        assert!(patch.to_diff().starts_with(
+            "diff --git a/a.md b/a.md\n--- a/a.md\n+++ b/a.md\n@@ -1 +1,2 @@\n-one\n+ONE\n+more\n"
+        ));
+
+        // A file edited after planning aborts the whole set.
+        std::fs::write(root.join("b.md"), "changed\n").unwrap();
+        let err = patch.apply().unwrap_err();
+        assert!(err.to_string().contains("b.md changed"), "{err}");
+        assert_eq!(std::fs::read_to_string(root.join("a.md")).unwrap(), "one\n");
+
+        std::fs::write(root.join("b.md"), "two\n").unwrap();
+        patch.apply().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.md")).unwrap(),
+            "ONE\nmore\n"
+        );
+        assert_eq!(std::fs::read_to_string(root.join("b.md")).unwrap(), "TWO\n");
+        let leftovers: Vec<_> = std::fs::read_dir(root)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tracey-tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        assert_eq!(Mode::from_flags(false, None).unwrap(), Mode::Apply);
+        assert!(Mode::from_flags(true, Some("x.patch".into())).is_err());
+    }
+}
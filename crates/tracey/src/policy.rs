@@ -0,0 +1,342 @@
+//! `tracey policy` implementation.
+//!
+//! Policies are project-specific coverage gates kept out of tracey itself.
+//! Each one is a command from the config's `policies` block that receives
+//! the coverage report as JSON on stdin — stats per spec and implementation,
+//! per requirement level and per tag, the change since the last snapshot in
+//! `.tracey/history.jsonl`, and every rule with the files covering it — and
+//! prints its verdict as `{"pass": bool, "messages": [...]}`.
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::bump::git_capture;
+use crate::config::Config;
+use crate::history;
+use tracey_api::ApiRule;
+
+/// What every policy command receives on stdin.
+#[derive(Debug, Clone, Facet)]
+pub struct PolicyReport {
+    /// `HEAD`, if the project is a git checkout.
+    #[facet(default)]
+    pub commit: Option<String>,
+    /// Commit of the history snapshot the deltas are measured against.
+    #[facet(default)]
+    pub baseline_commit: Option<String>,
+    pub impls: Vec<PolicyImpl>,
+}
+
+/// Coverage of one spec/impl pair.
+#[derive(Debug, Clone, Facet)]
+pub struct PolicyImpl {
+    pub spec: String,
+    pub impl_name: String,
+    pub stats: PolicyStats,
+    /// Keyed by requirement level; rules without one are left out.
+    pub by_level: BTreeMap<String, PolicyStats>,
+    pub by_tag: BTreeMap<String, PolicyStats>,
+    /// Change since the baseline snapshot, if it recorded this pair.
+    #[facet(default)]
+    pub delta: Option<PolicyDelta>,
+    pub rules: Vec<PolicyRule>,
+}
+
+/// Rule counts over a set of rules.
+#[derive(Debug, Clone, Default, PartialEq, Facet)]
+pub struct PolicyStats {
+    pub total: usize,
+    pub implemented: usize,
+    pub verified: usize,
+}
+
+impl PolicyStats {
+    fn add(&mut self, rule: &ApiRule) {
+        self.total += 1;
+        if !rule.is_stale && rule.is_implemented() {
+            self.implemented += 1;
+        }
+        if !rule.verify_refs.is_empty() {
+            self.verified += 1;
+        }
+    }
+}
+
+/// Change in rule counts since the baseline snapshot.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct PolicyDelta {
+    pub total: i64,
+    pub implemented: i64,
+    pub verified: i64,
+}
+
+/// One rule as seen by a policy.
+#[derive(Debug, Clone, Facet)]
+pub struct PolicyRule {
+    pub id: String,
+    #[facet(default)]
+    pub level: Option<String>,
+    #[facet(default)]
+    pub status: Option<String>,
+    pub tags: Vec<String>,
+    #[facet(default)]
+    pub source_file: Option<String>,
+    #[facet(default)]
+    pub source_line: Option<usize>,
+    pub implemented: bool,
+    pub verified: bool,
+    pub waived: bool,
+    pub stale: bool,
+    /// Files holding an implementation reference, relative to the project root.
+    pub impl_files: Vec<String>,
+    /// Files holding a verification reference, relative to the project root.
+    pub verify_files: Vec<String>,
+}
+
+/// What a policy command prints on stdout.
+#[derive(Debug, Facet)]
+struct Verdict {
+    pass: bool,
+    #[facet(default)]
+    messages: Vec<String>,
+}
+
+/// Result of one policy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyOutcome {
+    pub name: String,
+    pub pass: bool,
+    pub messages: Vec<String>,
+}
+
+/// Outcome of `tracey policy`.
+#[derive(Debug, Default)]
+pub struct PolicyRun {
+    pub outcomes: Vec<PolicyOutcome>,
+}
+
+impl PolicyRun {
+    /// Whether every policy passed.
+    pub fn is_passing(&self) -> bool {
+        self.outcomes.iter().all(|o| o.pass)
+    }
+
+    /// Render the outcomes as plain text.
+    pub fn to_text(&self) -> String {
+        if self.outcomes.is_empty() {
+            return "No policies configured.\n".to_string();
+        }
+        let mut out = String::new();
+        for outcome in &self.outcomes {
+            let mark = if outcome.pass { "✓" } else { "✗" };
+            let _ = writeln!(out, "{mark} {}", outcome.name);
+            for message in &outcome.messages {
+                let _ = writeln!(out, "    {message}");
+            }
+        }
+        let failed = self.outcomes.iter().filter(|o| !o.pass).count();
+        let _ = writeln!(
+            out,
+            "{} of {} policies passed.",
+            self.outcomes.len() - failed,
+            self.outcomes.len()
+        );
+        out
+    }
+}
+
+fn files(refs: &[tracey_api::ApiCodeRef]) -> Vec<String> {
+    let mut files: Vec<String> = refs.iter().map(|r| r.file.clone()).collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn policy_rule(rule: &ApiRule) -> PolicyRule {
+    PolicyRule {
+        id: rule.id.to_string(),
+        level: rule.level.clone(),
+        status: rule.status.clone(),
+        tags: rule.tags.clone(),
+        source_file: rule.source_file.clone(),
+        source_line: rule.source_line,
+        implemented: rule.is_implemented(),
+        verified: !rule.verify_refs.is_empty(),
+        waived: rule.is_waived(),
+        stale: rule.is_stale,
+        impl_files: files(&rule.impl_refs),
+        verify_files: files(&rule.verify_refs),
+    }
+}
+
+/// Build the report policies are evaluated against.
+pub async fn report(project_root: &Path, config: &Config) -> Result<PolicyReport> {
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let baseline = history::load(project_root)?.pop();
+
+    let impls = data
+        .forward_by_impl
+        .iter()
+        .map(|((spec, impl_name), forward)| {
+            let mut stats = PolicyStats::default();
+            let mut by_level: BTreeMap<String, PolicyStats> = BTreeMap::new();
+            let mut by_tag: BTreeMap<String, PolicyStats> = BTreeMap::new();
+            for rule in &forward.rules {
+                stats.add(rule);
+                if let Some(level) = &rule.level {
+                    by_level.entry(level.clone()).or_default().add(rule);
+                }
+                for tag in &rule.tags {
+                    by_tag.entry(tag.clone()).or_default().add(rule);
+                }
+            }
+            let delta = baseline
+                .as_ref()
+                .and_then(|b| {
+                    b.impls
+                        .iter()
+                        .find(|i| &i.spec == spec && &i.impl_name == impl_name)
+                })
+                .map(|before| PolicyDelta {
+                    total: stats.total as i64 - before.total_rules as i64,
+                    implemented: stats.implemented as i64 - before.covered_rules as i64,
+                    verified: stats.verified as i64 - before.verified_rules as i64,
+                });
+            PolicyImpl {
+                spec: spec.clone(),
+                impl_name: impl_name.clone(),
+                stats,
+                by_level,
+                by_tag,
+                delta,
+                rules: forward.rules.iter().map(policy_rule).collect(),
+            }
+        })
+        .collect();
+
+    let commit = git_capture(project_root, &["rev-parse", "HEAD"])
+        .ok()
+        .map(|out| out.trim().to_string());
+    Ok(PolicyReport {
+        commit,
+        baseline_commit: baseline.and_then(|b| b.commit),
+        impls,
+    })
+}
+
+/// Run one policy command over the serialized report. A command that can't
+/// be started, exits non-zero or prints no verdict fails the policy.
+fn evaluate(project_root: &Path, name: &str, command: &str, input: &str) -> PolicyOutcome {
+    let fail = |message: String| PolicyOutcome {
+        name: name.to_string(),
+        pass: false,
+        messages: vec![message],
+    };
+    let mut args = command.split_whitespace();
+    let program = args.next().unwrap_or_default();
+    let mut child = match Command::new(program)
+        .args(args)
+        .current_dir(project_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return fail(format!("failed to run `{program}`: {e}")),
+    };
+
+    // Write from a separate thread so a policy that prints before reading all
+    // of its input cannot deadlock against us.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = match child.wait_with_output() {
+        Ok(output) => output,
+        Err(e) => return fail(format!("`{program}` failed: {e}")),
+    };
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let first = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return fail(format!(
+            "`{program}` exited with {} {}",
+            output.status,
+            first.trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match facet_json::from_str::<Verdict>(stdout.trim()) {
+        Ok(verdict) => PolicyOutcome {
+            name: name.to_string(),
+            pass: verdict.pass,
+            messages: verdict.messages,
+        },
+        Err(e) => fail(format!("`{program}` printed no verdict: {e}")),
+    }
+}
+
+/// Evaluate every configured policy against the project's current coverage.
+// r[impl cli.policy]
+pub async fn run(project_root: &Path, config: &Config) -> Result<PolicyRun> {
+    if config.policies.is_empty() {
+        return Ok(PolicyRun::default());
+    }
+    let report = report(project_root, config).await?;
+    let input = facet_json::to_string(&report).wrap_err("failed to serialize policy report")?;
+    let outcomes = config
+        .policies
+        .iter()
+        .map(|(name, command)| evaluate(project_root, name, command, &input))
+        .collect();
+    Ok(PolicyRun { outcomes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.policy]
+    #[test]
+    fn policies_receive_the_report_and_return_a_verdict() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("gate.sh"),
+            "cat > report.json\necho '{\"pass\": false, \"messages\": [\"2 MUST rules uncovered\"]}'\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.sh"), "echo oops >&2\nexit 3\n").unwrap();
+
+        let outcome = evaluate(dir.path(), "gate", "sh gate.sh", "{\"impls\": []}");
+        assert_eq!(
+            outcome,
+            PolicyOutcome {
+                name: "gate".to_string(),
+                pass: false,
+                messages: vec!["2 MUST rules uncovered".to_string()],
+            }
+        );
+        let received = std::fs::read_to_string(dir.path().join("report.json")).unwrap();
+        assert_eq!(received, "{\"impls\": []}");
+
+        let outcome = evaluate(dir.path(), "broken", "sh broken.sh", "{}");
+        assert!(!outcome.pass);
+        assert!(
+            outcome.messages[0].contains("oops"),
+            "{:?}",
+            outcome.messages
+        );
+
+        let run = PolicyRun {
+            outcomes: vec![outcome],
+        };
+        assert!(!run.is_passing());
+        assert!(run.to_text().contains("✗ broken"), "{}", run.to_text());
+    }
+}
@@ -0,0 +1,226 @@
+//! Formal verification results.
+//!
+//! Verify references in files matched by an implementation's `proof_include`
+//! patterns are proofs: Kani harnesses, Creusot or Prusti contracts, TLA+
+//! specs. A proof only shows up as "formally verified" once a tool has
+//! checked it; `tracey proofs import-kani` records which harnesses Kani
+//! proved in `.tracey/proofs.json`, and every build reads that file back.
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Proof results file, relative to the project root.
+pub const PROOFS_FILE: &str = ".tracey/proofs.json";
+
+/// The results of one verification run.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct ProofResults {
+    /// Tool that produced the results, e.g. `kani`.
+    pub tool: String,
+    /// Seconds since the Unix epoch.
+    pub imported_at: u64,
+    pub harnesses: Vec<HarnessResult>,
+}
+
+/// Outcome of one proof harness.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct HarnessResult {
+    /// Harness name as the tool reports it, e.g. `proofs::check_decode`.
+    pub name: String,
+    pub proven: bool,
+}
+
+impl ProofResults {
+    /// Names of the proven harnesses, without their module path, to match
+    /// against function names in proof files.
+    pub fn proven(&self) -> HashSet<String> {
+        self.harnesses
+            .iter()
+            .filter(|h| h.proven)
+            .map(|h| short_name(&h.name).to_string())
+            .collect()
+    }
+}
+
+/// `check_decode` for `proofs::check_decode`.
+fn short_name(name: &str) -> &str {
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn proofs_path(project_root: &Path) -> PathBuf {
+    project_root.join(PROOFS_FILE)
+}
+
+/// Read the results of Kani's JSON output. Every object with a harness name
+/// (`harness`, `harness_name`, `pretty_name`, or `name`) and a status
+/// (`status` or `result`) is a harness result, wherever it is nested; a
+/// harness is proven when its status starts with `success`, `verified`, or
+/// `pass`, ignoring case.
+// r[impl cli.proofs.import-kani]
+pub fn parse_kani(json: &str) -> Result<Vec<HarnessResult>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).wrap_err("Kani results are not valid JSON")?;
+    let mut harnesses = Vec::new();
+    let mut stack = vec![&value];
+    while let Some(value) = stack.pop() {
+        match value {
+            serde_json::Value::Array(items) => stack.extend(items.iter().rev()),
+            serde_json::Value::Object(fields) => {
+                let field = |keys: &[&str]| {
+                    keys.iter()
+                        .find_map(|k| fields.get(*k).and_then(|v| v.as_str()))
+                };
+                let name = field(&["harness", "harness_name", "pretty_name", "name"]);
+                let status = field(&["status", "result"]);
+                if let (Some(name), Some(status)) = (name, status) {
+                    let status = status.to_ascii_lowercase();
+                    harnesses.push(HarnessResult {
+                        name: name.to_string(),
+                        proven: ["success", "verified", "pass"]
+                            .iter()
+                            .any(|ok| status.starts_with(ok)),
+                    });
+                } else {
+                    stack.extend(fields.values().rev());
+                }
+            }
+            _ => {}
+        }
+    }
+    if harnesses.is_empty() {
+        eyre::bail!("No harness results found; expected objects with a harness name and a status");
+    }
+    Ok(harnesses)
+}
+
+/// Import Kani results from `results` and write them to the project's proof
+/// results file, replacing earlier results.
+pub fn import_kani(project_root: &Path, results: &Path) -> Result<ProofResults> {
+    let json = std::fs::read_to_string(results)
+        .wrap_err_with(|| format!("failed to read {}", results.display()))?;
+    let imported = ProofResults {
+        tool: "kani".to_string(),
+        imported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        harnesses: parse_kani(&json)?,
+    };
+    let path = proofs_path(project_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+    }
+    let content = facet_json::to_string(&imported).wrap_err("failed to serialize results")?;
+    std::fs::write(&path, content)
+        .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    Ok(imported)
+}
+
+/// The project's proof results, or `None` when none were imported or the
+/// file doesn't parse.
+pub fn load(project_root: &Path) -> Option<ProofResults> {
+    let content = std::fs::read_to_string(proofs_path(project_root)).ok()?;
+    facet_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.proofs.import-kani]
+    #[test]
+    fn kani_results_are_found_wherever_they_are_nested() {
+        let json = r#"{
+            "kani_version": "0.56.0",
+            "harnesses": [
+                {"pretty_name": "proofs::check_decode", "status": "Success", "checks": []},
+                {"pretty_name": "proofs::check_encode", "status": "FAILURE"},
+                {"summary": {"harness": "check_pong", "result": "VERIFIED"}}
+            ]
+        }"#;
+        let harnesses = parse_kani(json).unwrap();
+        let results = ProofResults {
+            tool: "kani".to_string(),
+            imported_at: 0,
+            harnesses,
+        };
+        assert_eq!(results.harnesses.len(), 3);
+        let mut proven: Vec<String> = results.proven().into_iter().collect();
+        proven.sort();
+        assert_eq!(proven, ["check_decode", "check_pong"]);
+        assert!(parse_kani(r#"{"harnesses": []}"#).is_err());
+        assert!(parse_kani("not json").is_err());
+    }
+
+    // r[verify coverage.proof]
+    // r[verify config.impl.proof_include]
+    #[tokio::test]
+    async fn proven_harnesses_make_rules_formally_verified() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write(
+            "docs/spec.md",
+            "r[codec.decode]\nDecoding MUST succeed.\n\nr[codec.encode]\nEncoding MUST succeed.\n\nr[codec.len]\nLengths MUST fit.\n",
+        );
+        write(
+            "src/lib.rs",
+            "// r[impl codec.decode]\npub fn decode() {}\n\n// r[impl codec.encode]\npub fn encode() {}\n",
+        );
+        write(
+            "proofs/codec.rs",
+            "// r[verify codec.decode]\n#[kani::proof]\nfn check_decode() {}\n\n// r[verify codec.encode]\n#[kani::proof]\nfn check_encode() {}\n",
+        );
+        write(
+            "kani.json",
+            r#"[{"harness": "proofs::check_decode", "status": "SUCCESS"}, {"harness": "proofs::check_encode", "status": "FAILURE"}]"#,
+        );
+        let config_path = root.join(".config/tracey/config.styx");
+        write(
+            ".config/tracey/config.styx",
+            "specs (\n    {\n        name codec\n        include (docs/**/*.md)\n        impls (\n            {\n                name rust\n                include (src/**/*.rs)\n                proof_include (proofs/**/*.rs)\n            }\n        )\n    }\n)\n",
+        );
+        let config = crate::load_config(&config_path).unwrap();
+
+        let rules = |data: &crate::data::DashboardData| {
+            data.forward_by_impl[&("codec".to_string(), "rust".to_string())]
+                .rules
+                .iter()
+                .map(|r| (r.id.base.clone(), r.proof_refs.len(), r.formally_verified))
+                .collect::<Vec<_>>()
+        };
+
+        let data = crate::data::build_dashboard_data(root, &config, 0, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            rules(&data),
+            [
+                ("codec.decode".to_string(), 1, false),
+                ("codec.encode".to_string(), 1, false),
+                ("codec.len".to_string(), 0, false),
+            ]
+        );
+
+        let imported = import_kani(root, &root.join("kani.json")).unwrap();
+        assert_eq!(load(root), Some(imported));
+        let data = crate::data::build_dashboard_data(root, &config, 0, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            rules(&data),
+            [
+                ("codec.decode".to_string(), 1, true),
+                ("codec.encode".to_string(), 1, false),
+                ("codec.len".to_string(), 0, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,510 @@
+//! `tracey release-check` implementation.
+//!
+//! Pins each spec to its contents at a release tag, measures the current code
+//! against that pinned rule set, and reports whether the release is ready.
+//! Like `tracey bump`, this works directly on git and does not require the
+//! daemon.
+
+use eyre::{Result, WrapErr};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracey_core::RefVerb;
+
+use crate::bump::{git_capture, git_cat_file, git_renames, parse_spec_rules};
+use crate::config::Config;
+
+/// Release readiness of every configured spec at a given tag.
+#[derive(Debug)]
+pub struct ReleaseReport {
+    /// Tag whose spec contents were pinned.
+    pub tag: String,
+    /// Previous release tag that "new" rules are measured against, if any.
+    pub previous_tag: Option<String>,
+    /// Per-spec results, in config order.
+    pub specs: Vec<SpecReadiness>,
+}
+
+/// Release readiness of one spec.
+#[derive(Debug)]
+pub struct SpecReadiness {
+    /// Spec name from config.
+    pub name: String,
+    /// Advisory specs are reported but never block a release.
+    pub advisory: bool,
+    /// Rules defined at the tag that count for coverage.
+    pub total: usize,
+    /// Pinned rules with at least one implementation reference.
+    pub implemented: usize,
+    /// Pinned rules with at least one verification reference.
+    pub verified: usize,
+    /// Pinned rules added since the previous release.
+    pub added: usize,
+    /// Pinned rules with no implementation reference.
+    pub unimplemented: Vec<marq::RuleId>,
+    /// Rules added since the previous release with no verification reference.
+    pub unverified_new: Vec<marq::RuleId>,
+    /// Gaps accepted by a waiver, with the waiver's reason; they don't block.
+    pub waived: Vec<(marq::RuleId, String)>,
+}
+
+impl SpecReadiness {
+    fn is_ready(&self) -> bool {
+        self.unimplemented.is_empty() && self.unverified_new.is_empty()
+    }
+
+    fn status(&self) -> &'static str {
+        match (self.is_ready(), self.advisory) {
+            (true, _) => "ready",
+            (false, true) => "advisory",
+            (false, false) => "blocked",
+        }
+    }
+}
+
+impl ReleaseReport {
+    /// A release is ready when every pinned rule is implemented and every rule
+    /// added since the previous release is verified. Advisory specs are not
+    /// considered.
+    // r[impl coverage.enforcement]
+    pub fn is_ready(&self) -> bool {
+        self.specs.iter().all(|s| s.advisory || s.is_ready())
+    }
+
+    /// Render the report as a markdown summary suitable for a release checklist.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let verdict = if self.is_ready() {
+            "✅ Ready"
+        } else {
+            "❌ Not ready"
+        };
+        let _ = writeln!(out, "# Release readiness: {}", self.tag);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "**Verdict:** {verdict}");
+        let _ = writeln!(out);
+        match &self.previous_tag {
+            Some(prev) => {
+                let _ = writeln!(out, "New rules are counted since `{prev}`.");
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "No previous release tag found; every rule counts as new."
+                );
+            }
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "| Spec | Rules | Implemented | Verified | New since last release | Status |"
+        );
+        let _ = writeln!(
+            out,
+            "|------|-------|-------------|----------|------------------------|--------|"
+        );
+        for spec in &self.specs {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} |",
+                spec.name,
+                spec.total,
+                spec.implemented,
+                spec.verified,
+                spec.added,
+                spec.status()
+            );
+        }
+
+        for spec in &self.specs {
+            if spec.is_ready() && spec.waived.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}", spec.name);
+            if spec.advisory {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "Advisory spec; these gaps do not block the release.");
+            }
+            if !spec.unimplemented.is_empty() {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "Rules without an implementation:");
+                let _ = writeln!(out);
+                for id in &spec.unimplemented {
+                    let _ = writeln!(out, "- `{id}`");
+                }
+            }
+            if !spec.unverified_new.is_empty() {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "New rules without verification:");
+                let _ = writeln!(out);
+                for id in &spec.unverified_new {
+                    let _ = writeln!(out, "- `{id}`");
+                }
+            }
+            // r[impl coverage.waivers.report]
+            if !spec.waived.is_empty() {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "Waived (accepted gaps that do not block):");
+                let _ = writeln!(out);
+                for (id, reason) in &spec.waived {
+                    let _ = writeln!(out, "- `{id}`: {reason}");
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// The release tag preceding `tag`, if there is one.
+fn previous_tag(project_root: &Path, tag: &str) -> Option<String> {
+    git_capture(
+        project_root,
+        &["describe", "--tags", "--abbrev=0", &format!("{tag}^")],
+    )
+    .ok()
+    .map(|s| s.trim().to_string())
+    .filter(|s| !s.is_empty())
+}
+
+/// Load the rules of every spec file matching `include` as of `revision`,
+/// keyed by base ID.
+pub(crate) async fn rules_at_revision(
+    project_root: &Path,
+    revision: &str,
+    include: &[String],
+) -> Result<BTreeMap<String, marq::ReqDefinition>> {
+    let mut rules = BTreeMap::new();
+    for (file, content) in spec_files_at_revision(project_root, revision, include)? {
+        rules.extend(parse_spec_rules(&content, &file).await?);
+    }
+    Ok(rules)
+}
+
+/// Read every spec file matching `include` as of `revision`, as
+/// `(path, content)` pairs.
+///
+/// A file counts when either its path at `revision` or the path it has since
+/// been moved to matches, so moving a spec does not make every rule in it
+/// look new.
+// r[impl history.renames]
+pub(crate) fn spec_files_at_revision(
+    project_root: &Path,
+    revision: &str,
+    include: &[String],
+) -> Result<Vec<(String, String)>> {
+    let files = git_capture(project_root, &["ls-tree", "-r", "--name-only", revision])?;
+    let renamed_to: HashMap<String, String> = git_renames(project_root, &["--relative", revision])?
+        .into_iter()
+        .map(|(new, old)| (old, new))
+        .collect();
+    let matchers: Vec<_> = include
+        .iter()
+        .filter_map(|p| globset::Glob::new(p).ok())
+        .map(|g| g.compile_matcher())
+        .collect();
+
+    let mut contents = Vec::new();
+    for file in files.lines().map(str::trim) {
+        let current = renamed_to.get(file).map_or(file, String::as_str);
+        if file.is_empty()
+            || !matchers
+                .iter()
+                .any(|m| m.is_match(file) || m.is_match(current))
+        {
+            continue;
+        }
+        // `./` makes the path relative to the project root rather than the
+        // repository root, matching `ls-tree` output above.
+        let Some(content) = git_cat_file(project_root, revision, &format!("./{file}"))? else {
+            continue;
+        };
+        contents.push((file.to_string(), content));
+    }
+    Ok(contents)
+}
+
+/// Run the release check for `tag`, comparing against `since` (or the tag
+/// preceding `tag` when not given).
+///
+/// References are taken from the current working tree. A reference counts for
+/// a pinned rule when it uses the spec's prefix, names the same base ID, and
+/// is at least the pinned version.
+// r[impl cli.release-check]
+pub async fn release_check(
+    project_root: &Path,
+    config: &Config,
+    tag: &str,
+    since: Option<&str>,
+) -> Result<ReleaseReport> {
+    git_capture(
+        project_root,
+        &["rev-parse", "--verify", &format!("{tag}^{{commit}}")],
+    )
+    .wrap_err_with(|| format!("unknown release tag `{tag}`"))?;
+    let previous_tag = match since {
+        Some(since) => Some(since.to_string()),
+        None => previous_tag(project_root, tag),
+    };
+
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let waivers = crate::waivers::load(project_root)?;
+    let today = crate::waivers::today();
+
+    let mut specs = Vec::new();
+    for spec in &config.specs {
+        let prefix = data
+            .config
+            .specs
+            .iter()
+            .find(|s| s.name == spec.name)
+            .map(|s| s.prefix.as_str())
+            .unwrap_or("r");
+
+        let pinned = rules_at_revision(project_root, tag, &spec.include).await?;
+        let before: HashSet<String> = match &previous_tag {
+            Some(prev) => rules_at_revision(project_root, prev, &spec.include)
+                .await?
+                .into_keys()
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        let mut readiness = SpecReadiness {
+            name: spec.name.clone(),
+            advisory: crate::config::enforcement(spec).is_advisory(),
+            total: 0,
+            implemented: 0,
+            verified: 0,
+            added: 0,
+            unimplemented: Vec::new(),
+            unverified_new: Vec::new(),
+            waived: Vec::new(),
+        };
+
+        for (base, def) in &pinned {
+            if !def.metadata.counts_for_coverage() {
+                continue;
+            }
+            let has_ref = |verb: RefVerb| {
+                data.source_reqs_by_file.values().any(|reqs| {
+                    reqs.references.iter().any(|r| {
+                        r.verb == verb
+                            && r.prefix == prefix
+                            && r.req_id.base == *base
+                            && r.req_id.version >= def.id.version
+                    })
+                })
+            };
+            let implemented = has_ref(RefVerb::Impl);
+            let verified = has_ref(RefVerb::Verify);
+            let is_new = !before.contains(base);
+
+            readiness.total += 1;
+            readiness.implemented += usize::from(implemented);
+            readiness.verified += usize::from(verified);
+            readiness.added += usize::from(is_new);
+            let gap = !implemented || (is_new && !verified);
+            if gap && waivers.is_waived(base, today) {
+                let reason = waivers.get(base).map(|w| w.describe()).unwrap_or_default();
+                readiness.waived.push((def.id.clone(), reason));
+                continue;
+            }
+            if !implemented {
+                readiness.unimplemented.push(def.id.clone());
+            }
+            if is_new && !verified {
+                readiness.unverified_new.push(def.id.clone());
+            }
+        }
+        specs.push(readiness);
+    }
+
+    Ok(ReleaseReport {
+        tag: tag.to_string(),
+        previous_tag,
+        specs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(base: &str) -> marq::RuleId {
+        marq::RuleId {
+            base: base.to_string(),
+            version: 1,
+        }
+    }
+
+    fn spec(unimplemented: Vec<marq::RuleId>, unverified_new: Vec<marq::RuleId>) -> SpecReadiness {
+        SpecReadiness {
+            name: "app".to_string(),
+            advisory: false,
+            total: 3,
+            implemented: 3 - unimplemented.len(),
+            verified: 2,
+            added: 1,
+            unimplemented,
+            unverified_new,
+            waived: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ready_report_has_verdict_and_table() {
+        let report = ReleaseReport {
+            tag: "v2.0".to_string(),
+            previous_tag: Some("v1.0".to_string()),
+            specs: vec![spec(vec![], vec![])],
+        };
+        assert!(report.is_ready());
+        let md = report.to_markdown();
+        assert!(md.contains("# Release readiness: v2.0"));
+        assert!(md.contains("**Verdict:** ✅ Ready"));
+        assert!(md.contains("| app | 3 | 3 | 2 | 1 | ready |"));
+        assert!(!md.contains("## app"));
+    }
+
+    #[test]
+    fn blocked_report_lists_missing_rules() {
+        let report = ReleaseReport {
+            tag: "v2.0".to_string(),
+            previous_tag: None,
+            specs: vec![spec(vec![id("auth.login")], vec![id("auth.logout")])],
+        };
+        assert!(!report.is_ready());
+        let md = report.to_markdown();
+        assert!(md.contains("**Verdict:** ❌ Not ready"));
+        assert!(md.contains("every rule counts as new"));
+        assert!(md.contains("Rules without an implementation:\n\n- `auth.login`"));
+        assert!(md.contains("New rules without verification:\n\n- `auth.logout`"));
+    }
+
+    // r[verify coverage.waivers.report]
+    #[test]
+    fn waived_gaps_are_listed_without_blocking() {
+        let mut app = spec(vec![], vec![]);
+        app.waived = vec![(
+            id("auth.sso"),
+            "Needs an IdP in CI (until 2026-12-31)".into(),
+        )];
+        let report = ReleaseReport {
+            tag: "v2.0".to_string(),
+            previous_tag: Some("v1.0".to_string()),
+            specs: vec![app],
+        };
+        assert!(report.is_ready());
+        let md = report.to_markdown();
+        assert!(md.contains("| app | 3 | 3 | 2 | 1 | ready |"));
+        assert!(md.contains(
+            "Waived (accepted gaps that do not block):\n\n- `auth.sso`: Needs an IdP in CI (until 2026-12-31)"
+        ));
+    }
+
+    // r[verify coverage.enforcement]
+    #[test]
+    fn advisory_gaps_do_not_block() {
+        let mut rfc = spec(vec![id("rfc.section-4")], vec![]);
+        rfc.name = "rfc".to_string();
+        rfc.advisory = true;
+        let report = ReleaseReport {
+            tag: "v2.0".to_string(),
+            previous_tag: Some("v1.0".to_string()),
+            specs: vec![spec(vec![], vec![]), rfc],
+        };
+        assert!(report.is_ready());
+        let md = report.to_markdown();
+        assert!(md.contains("**Verdict:** ✅ Ready"));
+        assert!(md.contains("| rfc | 3 | 2 | 2 | 1 | advisory |"));
+        assert!(md.contains("## rfc\n\nAdvisory spec; these gaps do not block the release."));
+    }
+
+    fn git(root: &Path, args: &[&str]) {
+        git_capture(root, args).unwrap();
+    }
+
+    fn commit_and_tag(root: &Path, tag: &str) {
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", tag]);
+        git(root, &["tag", tag]);
+    }
+
+    // r[verify cli.release-check]
+    #[tokio::test]
+    async fn pins_rules_at_the_tag_and_measures_new_ones_since_the_previous_tag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+        git(root, &["config", "user.email", "t@example.com"]);
+        git(root, &["config", "user.name", "t"]);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn a() {}\n",
+        )
+        .unwrap();
+        commit_and_tag(root, "v1.0");
+
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MUST log out.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn a() {}\n// r[impl auth.logout]\nfn b() {}\n",
+        )
+        .unwrap();
+        commit_and_tag(root, "v1.1");
+
+        // Added after the tag, so not part of the pinned rule set.
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MUST log out.\n\n\
+             r[auth.expire]\nSessions MUST expire.\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            r#"specs (
+    {
+        name app
+        include (spec.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap();
+
+        let report = release_check(root, &config, "v1.1", None).await.unwrap();
+        assert_eq!(report.previous_tag.as_deref(), Some("v1.0"));
+        let app = &report.specs[0];
+        assert_eq!((app.total, app.implemented, app.added), (2, 2, 1));
+        assert!(app.unimplemented.is_empty());
+        assert_eq!(app.unverified_new, vec![id("auth.logout")]);
+        assert!(!report.is_ready());
+
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn a() {}\n// r[impl auth.logout]\n// r[verify auth.logout]\nfn b() {}\n",
+        )
+        .unwrap();
+        let report = release_check(root, &config, "v1.1", None).await.unwrap();
+        assert!(report.is_ready(), "{}", report.to_markdown());
+
+        assert!(release_check(root, &config, "v9.9", None).await.is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Extraction cache shared between machines.
+//!
+//! r[impl cache.remote]
+//!
+//! When the config has a `remote_cache` block, a source file that misses the
+//! in-memory cache is looked up at `<url>/<key>` before it is parsed, where
+//! the key hashes the file's content with everything else that affects
+//! parsing. Files parsed locally are uploaded under their key, so parallel
+//! CI jobs only parse each version of a file once between them. Like the
+//! webhook, requests shell out to `curl`, which also signs them for S3.
+//!
+//! The cache is an accelerator only: a missing entry, an unreachable store
+//! or an entry that doesn't decode falls back to parsing the file.
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::io::AsyncWriteExt;
+use tracey_core::code_units::{CodeUnit, CodeUnitKind};
+use tracey_core::{ParseWarning, RefVerb, ReqReference, Reqs, RuleId, SourceSpan, WarningKind};
+use tracing::{debug, warn};
+
+use crate::config::RemoteCacheConfig;
+
+/// Per-request timeout passed to curl, in seconds.
+const TIMEOUT_SECS: &str = "10";
+
+/// Exit code curl uses for HTTP responses >= 400 under `--fail`.
+const CURL_HTTP_ERROR: i32 = 22;
+
+/// Bumped whenever the entry layout or the parsers change what they produce
+/// for the same input, so old entries are never read back.
+const FORMAT: &str = "tracey-extract-v1";
+
+/// A parsed source file as stored in the cache. Entries carry no paths, so
+/// one can be reused from any checkout location.
+#[derive(Debug, Facet)]
+struct Entry {
+    references: Vec<EntryReference>,
+    warnings: Vec<EntryWarning>,
+    code_units: Vec<EntryCodeUnit>,
+}
+
+#[derive(Debug, Facet)]
+struct EntryReference {
+    prefix: String,
+    verb: RefVerb,
+    req_id: RuleId,
+    line: usize,
+    #[facet(default)]
+    end_line: Option<usize>,
+    #[facet(default)]
+    note: Option<String>,
+    #[facet(default)]
+    partial: bool,
+    span: SourceSpan,
+}
+
+#[derive(Debug, Facet)]
+struct EntryWarning {
+    line: usize,
+    span: SourceSpan,
+    kind: WarningKind,
+}
+
+#[derive(Debug, Facet)]
+struct EntryCodeUnit {
+    kind: CodeUnitKind,
+    name: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    start_byte: usize,
+    end_byte: usize,
+    req_refs: Vec<RuleId>,
+}
+
+/// Remote store for one build.
+pub struct RemoteCache {
+    config: RemoteCacheConfig,
+    /// Set once a request fails outright, so an unreachable store costs one
+    /// timeout per build rather than one per file.
+    unavailable: AtomicBool,
+}
+
+impl RemoteCache {
+    pub fn new(config: RemoteCacheConfig) -> Self {
+        Self {
+            config,
+            unavailable: AtomicBool::new(false),
+        }
+    }
+
+    /// Cache key of a source file: parsing depends on the file's extension
+    /// (which picks the grammar), the declared custom verbs and the tracey
+    /// version, besides the content itself.
+    pub fn key(path: &Path, content: &str, custom_verbs: &[String]) -> String {
+        let mut hasher = blake3::Hasher::new();
+        for part in [FORMAT, env!("CARGO_PKG_VERSION")] {
+            hasher.update(part.as_bytes());
+            hasher.update(b"\0");
+        }
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        hasher.update(extension.as_bytes());
+        hasher.update(b"\0");
+        for verb in custom_verbs {
+            hasher.update(verb.as_bytes());
+            hasher.update(b"\0");
+        }
+        hasher.update(b"\0");
+        hasher.update(content.as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// The parse results stored under `key`, with their paths set to `path`.
+    pub async fn get(&self, key: &str, path: &Path) -> Option<(Reqs, Vec<CodeUnit>)> {
+        if self.unavailable.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut command = match self.curl() {
+            Ok(command) => command,
+            Err(e) => {
+                self.give_up(&format!("{e:#}"));
+                return None;
+            }
+        };
+        command.arg(self.entry_url(key));
+        let output = match command.output().await {
+            Ok(output) => output,
+            Err(e) => {
+                self.give_up(&format!("failed to run curl: {e}"));
+                return None;
+            }
+        };
+        if !output.status.success() {
+            if output.status.code() != Some(CURL_HTTP_ERROR) {
+                self.give_up(String::from_utf8_lossy(&output.stderr).trim());
+            }
+            return None;
+        }
+        match decode(&output.stdout, path) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                debug!("Ignoring remote cache entry {key}: {e:#}");
+                None
+            }
+        }
+    }
+
+    /// Upload parse results under `key`, unless the cache is read-only.
+    pub async fn put(&self, key: &str, reqs: &Reqs, code_units: &[CodeUnit]) {
+        if self.config.read_only || self.unavailable.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Err(e) = self.upload(key, reqs, code_units).await {
+            self.give_up(&format!("{e:#}"));
+        }
+    }
+
+    async fn upload(&self, key: &str, reqs: &Reqs, code_units: &[CodeUnit]) -> Result<()> {
+        let body = encode(reqs, code_units)?;
+        let mut command = self.curl()?;
+        command
+            .arg("--header")
+            .arg("Content-Type: application/json")
+            .arg("--upload-file")
+            .arg("-")
+            .arg(self.entry_url(key))
+            .stdin(Stdio::piped());
+        let mut child = command.spawn().wrap_err("failed to run curl")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(body.as_bytes()).await?;
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            eyre::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+
+    fn entry_url(&self, key: &str) -> String {
+        format!("{}/{key}", self.config.url.trim_end_matches('/'))
+    }
+
+    /// curl with the options and credentials every request shares.
+    fn curl(&self) -> Result<tokio::process::Command> {
+        let mut command = tokio::process::Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--fail", "--max-time"])
+            .arg(TIMEOUT_SECS)
+            .arg("--user-agent")
+            .arg(concat!("tracey/", env!("CARGO_PKG_VERSION")))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(var) = &self.config.token_env {
+            let token = std::env::var(var)
+                .wrap_err_with(|| format!("token variable `{var}` is not set"))?;
+            command
+                .arg("--header")
+                .arg(format!("Authorization: Bearer {token}"));
+        }
+        if let Some(region) = &self.config.s3_region {
+            let credential =
+                |var: &str| std::env::var(var).wrap_err_with(|| format!("`{var}` is not set"));
+            command
+                .arg("--aws-sigv4")
+                .arg(format!("aws:amz:{region}:s3"))
+                .arg("--user")
+                .arg(format!(
+                    "{}:{}",
+                    credential("AWS_ACCESS_KEY_ID")?,
+                    credential("AWS_SECRET_ACCESS_KEY")?
+                ));
+        }
+        Ok(command)
+    }
+
+    fn give_up(&self, reason: &str) {
+        if !self.unavailable.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Remote cache {} unavailable, parsing locally: {reason}",
+                self.config.url
+            );
+        }
+    }
+}
+
+fn encode(reqs: &Reqs, code_units: &[CodeUnit]) -> Result<String> {
+    let entry = Entry {
+        references: reqs
+            .references
+            .iter()
+            .map(|r| EntryReference {
+                prefix: r.prefix.clone(),
+                verb: r.verb.clone(),
+                req_id: r.req_id.clone(),
+                line: r.line,
+                end_line: r.end_line,
+                note: r.note.clone(),
+                partial: r.partial,
+                span: r.span,
+            })
+            .collect(),
+        warnings: reqs
+            .warnings
+            .iter()
+            .map(|w| EntryWarning {
+                line: w.line,
+                span: w.span,
+                kind: w.kind.clone(),
+            })
+            .collect(),
+        code_units: code_units
+            .iter()
+            .map(|u| EntryCodeUnit {
+                kind: u.kind,
+                name: u.name.clone(),
+                start_line: u.start_line,
+                end_line: u.end_line,
+                start_byte: u.start_byte,
+                end_byte: u.end_byte,
+                req_refs: u.req_refs.clone(),
+            })
+            .collect(),
+    };
+    facet_json::to_string(&entry).wrap_err("failed to serialize cache entry")
+}
+
+fn decode(body: &[u8], path: &Path) -> Result<(Reqs, Vec<CodeUnit>)> {
+    let body = std::str::from_utf8(body)?;
+    let entry: Entry =
+        facet_json::from_str(body).map_err(|e| eyre::eyre!("failed to parse: {e}"))?;
+    let reqs = Reqs {
+        references: entry
+            .references
+            .into_iter()
+            .map(|r| ReqReference {
+                prefix: r.prefix,
+                verb: r.verb,
+                req_id: r.req_id,
+                file: path.to_path_buf(),
+                line: r.line,
+                end_line: r.end_line,
+                note: r.note,
+                partial: r.partial,
+                span: r.span,
+            })
+            .collect(),
+        warnings: entry
+            .warnings
+            .into_iter()
+            .map(|w| ParseWarning {
+                file: path.to_path_buf(),
+                line: w.line,
+                span: w.span,
+                kind: w.kind,
+            })
+            .collect(),
+    };
+    let code_units = entry
+        .code_units
+        .into_iter()
+        .map(|u| CodeUnit {
+            kind: u.kind,
+            name: u.name,
+            file: path.to_path_buf(),
+            start_line: u.start_line,
+            end_line: u.end_line,
+            start_byte: u.start_byte,
+            end_byte: u.end_byte,
+            req_refs: u.req_refs,
+        })
+        .collect();
+    Ok((reqs, code_units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cache.remote]
+    #[test]
+    fn entries_are_keyed_by_content_and_portable_across_checkouts() {
+        let content = "// r[impl auth.login]\nfn login() {}\n";
+        let here = Path::new("/ci/job-1/src/auth.rs");
+        let there = Path::new("/ci/job-2/src/auth.rs");
+
+        let key = RemoteCache::key(here, content, &[]);
+        assert_eq!(key, RemoteCache::key(there, content, &[]));
+        assert_ne!(key, RemoteCache::key(here, "fn other() {}\n", &[]));
+        assert_ne!(key, RemoteCache::key(Path::new("auth.py"), content, &[]));
+        assert_ne!(
+            key,
+            RemoteCache::key(here, content, &["documents".to_string()])
+        );
+
+        let reqs = Reqs::extract_from_content(here, content);
+        let units = tracey_core::code_units::extract(here, content).units;
+        assert!(!units.is_empty());
+        let body = encode(&reqs, &units).unwrap();
+        assert!(!body.contains("job-1"), "{body}");
+
+        let (decoded, decoded_units) = decode(body.as_bytes(), there).unwrap();
+        assert_eq!(decoded.references.len(), 1);
+        assert_eq!(decoded.references[0].file, there);
+        assert_eq!(decoded.references[0].req_id.base, "auth.login");
+        assert_eq!(decoded_units.len(), units.len());
+        assert!(decoded_units.iter().all(|u| u.file == there));
+    }
+}
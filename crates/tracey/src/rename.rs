@@ -0,0 +1,704 @@
+//! `tracey rename`, `tracey split` and `tracey merge` implementation.
+//!
+//! Renaming a rule rewrites its marker in the spec and every reference to it
+//! in the files the implementations scan, so include and exclude globs decide
+//! which files are touched. Versions are kept: `r[impl auth.login+2]` becomes
+//! `r[impl session.start+2]`.
+//!
+//! Splitting and merging cannot be done mechanically: someone has to decide
+//! which new rule each reference satisfies. These commands deprecate the old
+//! rules, add the new ones as drafts, and leave a TODO note on every
+//! reference to an old rule, reporting what is left to do.
+//!
+//! The edits are collected into a [`PatchSet`], so they can be previewed
+//! before anything is written.
+
+use eyre::{Result, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use tracey_core::markdown::edit::{self, TextEdit};
+use tracey_core::{ReqReference, parse_rule_id};
+
+use crate::config::Config;
+use crate::data::DashboardData;
+use crate::patch::PatchSet;
+
+/// The edits renaming one rule.
+#[derive(Debug)]
+pub struct RenamePlan {
+    /// Spec defining the rule.
+    pub spec: String,
+    /// Spec file holding the rule's marker, relative to the project root.
+    pub spec_file: PathBuf,
+    /// References rewritten, as (file relative to the project root, line).
+    pub references: Vec<(PathBuf, usize)>,
+    /// References to the rule that do not spell its ID, such as ones
+    /// written relative to another rule, left for manual review.
+    pub skipped: Vec<(PathBuf, usize)>,
+    pub patch: PatchSet,
+}
+
+/// Byte ranges of `base` in `text` where it stands as a whole rule ID: not
+/// part of a longer ID, though it may start with a `.` or carry a `+N`
+/// version.
+fn id_ranges(text: &str, base: &str) -> Vec<Range<usize>> {
+    let is_id = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_');
+    text.match_indices(base)
+        .filter(|&(start, _)| {
+            let clean_before = match text[..start].chars().next_back() {
+                None => true,
+                Some('.') => !text[..start - 1].chars().next_back().is_some_and(is_id),
+                Some(c) => !is_id(c),
+            };
+            let after = text[start + base.len()..].chars().next();
+            clean_before && !after.is_some_and(|c| is_id(c) || c == '.')
+        })
+        .map(|(start, _)| start..start + base.len())
+        .collect()
+}
+
+/// Where a rule is defined.
+struct Definition {
+    spec: String,
+    /// Spec file, relative to the project root.
+    file: PathBuf,
+    prefix: String,
+}
+
+/// Find the spec defining `base`, among `spec` only if given.
+fn locate(data: &DashboardData, spec: Option<&str>, base: &str) -> Result<Definition> {
+    // Spec name -> file defining the rule, over every implementation.
+    let mut defined: BTreeMap<&str, &str> = BTreeMap::new();
+    for ((spec_name, _), forward) in &data.forward_by_impl {
+        if spec.is_some_and(|s| s != spec_name) {
+            continue;
+        }
+        for rule in &forward.rules {
+            if rule.id.base == base
+                && let Some(file) = &rule.source_file
+            {
+                defined.insert(spec_name, file);
+            }
+        }
+    }
+    let (spec_name, file) = match defined.len() {
+        0 => bail!("no spec defines rule '{base}'"),
+        1 => defined.pop_first().unwrap(),
+        _ => bail!(
+            "rule '{base}' is defined in specs {}; pick one with --spec",
+            defined.keys().copied().collect::<Vec<_>>().join(", ")
+        ),
+    };
+    let prefix = data
+        .config
+        .specs
+        .iter()
+        .find(|s| s.name == spec_name)
+        .map(|s| s.prefix.clone())
+        .unwrap_or_else(|| "r".to_string());
+    Ok(Definition {
+        spec: spec_name.to_string(),
+        file: PathBuf::from(file),
+        prefix,
+    })
+}
+
+/// Fail unless `base` is a valid rule ID without a version.
+fn check_id(base: &str) -> Result<()> {
+    match parse_rule_id(base) {
+        Some(id) if id.base == base && crate::data::is_valid_rule_id(&id) => Ok(()),
+        _ => bail!("'{base}' is not a valid rule ID (give it without a version)"),
+    }
+}
+
+/// Whether `spec` defines a rule `base`.
+fn exists(data: &DashboardData, spec: &str, base: &str) -> bool {
+    data.forward_by_impl
+        .iter()
+        .filter(|((s, _), _)| s == spec)
+        .any(|(_, forward)| forward.rules.iter().any(|r| r.id.base == base))
+}
+
+/// Every scanned reference with `prefix` to one of `bases`, by file relative
+/// to the project root.
+fn references<'a>(
+    project_root: &Path,
+    data: &'a DashboardData,
+    prefix: &str,
+    bases: &[&str],
+) -> BTreeMap<PathBuf, Vec<&'a ReqReference>> {
+    let canonical_root = project_root.canonicalize().ok();
+    let mut out = BTreeMap::new();
+    for (file, reqs) in &data.source_reqs_by_file {
+        let refs: Vec<_> = reqs
+            .references
+            .iter()
+            .filter(|r| r.prefix == prefix && bases.contains(&r.req_id.base.as_str()))
+            .collect();
+        if !refs.is_empty() {
+            let rel = crate::adopt::relative_to(project_root, canonical_root.as_deref(), file);
+            out.insert(rel, refs);
+        }
+    }
+    out
+}
+
+/// Plan renaming the rule `old` to `new`, in `spec` if given. Nothing is
+/// written.
+// r[impl cli.rename]
+pub async fn plan_rename(
+    project_root: &Path,
+    config: &Config,
+    old: &str,
+    new: &str,
+    spec: Option<&str>,
+) -> Result<RenamePlan> {
+    check_id(new)?;
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let def = locate(&data, spec, old)?;
+    if exists(&data, &def.spec, new) {
+        bail!("rule '{new}' already exists in spec {}", def.spec);
+    }
+    let prefix = def.prefix;
+
+    let mut plan = RenamePlan {
+        spec: def.spec,
+        spec_file: def.file,
+        references: Vec::new(),
+        skipped: Vec::new(),
+        patch: PatchSet::new(project_root),
+    };
+
+    for (rel, refs) in references(project_root, &data, &prefix, &[old]) {
+        let source = std::fs::read_to_string(project_root.join(&rel))?;
+        // References sharing a span (`r[impl a.b, c.d]`) are rewritten once.
+        let mut ranges = BTreeSet::new();
+        for r in refs {
+            let start = r.span.offset;
+            let found = source
+                .get(start..start + r.span.length)
+                .map(|text| id_ranges(text, old))
+                .unwrap_or_default();
+            if found.is_empty() {
+                plan.skipped.push((rel.clone(), r.line));
+                continue;
+            }
+            ranges.extend(
+                found
+                    .into_iter()
+                    .map(|range| (range.start + start, range.end + start)),
+            );
+            plan.references.push((rel.clone(), r.line));
+        }
+        let edits = ranges
+            .into_iter()
+            .map(|(start, end)| TextEdit::new(start..end, new))
+            .collect();
+        let renamed = edit::apply_edits(&source, edits)
+            .map_err(|e| eyre::eyre!("failed to edit {}: {e}", rel.display()))?;
+        plan.patch.set(rel, source, renamed);
+    }
+
+    let mut found = false;
+    plan.patch.modify(&plan.spec_file, |content| {
+        let marker = edit::find_rule_markers(content)
+            .into_iter()
+            .find(|m| m.prefix == prefix && m.id.base == old);
+        match marker.map(|m| edit::apply_edits(content, vec![m.rename(new)])) {
+            Some(Ok(renamed)) => {
+                found = true;
+                renamed
+            }
+            _ => content.to_string(),
+        }
+    })?;
+    if !found {
+        bail!(
+            "marker {prefix}[{old}] not found in {}",
+            plan.spec_file.display()
+        );
+    }
+    Ok(plan)
+}
+
+/// Whether rules are split or merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restructure {
+    Split,
+    Merge,
+}
+
+/// The edits splitting one rule into several, or merging several into one.
+#[derive(Debug)]
+pub struct RestructurePlan {
+    pub kind: Restructure,
+    /// Rules being replaced; they are marked deprecated.
+    pub from: Vec<String>,
+    /// Rules replacing them.
+    pub into: Vec<String>,
+    /// Rules of `into` that were added as drafts, with the spec file they
+    /// were added to.
+    pub added: Vec<(String, PathBuf)>,
+    /// References given a TODO note, as (file, line).
+    pub annotated: Vec<(PathBuf, usize)>,
+    /// References that could not be given a note, such as region markers,
+    /// as (file, line).
+    pub skipped: Vec<(PathBuf, usize)>,
+    pub patch: PatchSet,
+}
+
+impl RestructurePlan {
+    /// Render what the edits leave for a person to finish, as markdown.
+    pub fn to_markdown(&self) -> String {
+        let list = |ids: &[String]| {
+            ids.iter()
+                .map(|id| format!("`{id}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# {} {} into {}",
+            match self.kind {
+                Restructure::Split => "Split",
+                Restructure::Merge => "Merge",
+            },
+            list(&self.from),
+            list(&self.into)
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{} now deprecated.", list(&self.from));
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## Needs manual review");
+        let _ = writeln!(out);
+        for (id, file) in &self.added {
+            let _ = writeln!(
+                out,
+                "- Write the text of draft rule `{id}` in {}.",
+                file.display()
+            );
+        }
+        for (file, line) in &self.annotated {
+            let _ = writeln!(
+                out,
+                "- {}:{line}: point the reference at {} and drop its TODO note.",
+                file.display(),
+                list(&self.into)
+            );
+        }
+        for (file, line) in &self.skipped {
+            let _ = writeln!(
+                out,
+                "- {}:{line}: could not be annotated; update the reference by hand.",
+                file.display()
+            );
+        }
+        if self.added.is_empty() && self.annotated.is_empty() && self.skipped.is_empty() {
+            let _ = writeln!(out, "Nothing.");
+        }
+        out
+    }
+}
+
+/// Offset where the rule whose marker ends at `marker_end` ends: the start of
+/// the first blank line after it, or the end of `content`.
+fn rule_end(content: &str, marker_end: usize) -> usize {
+    let mut offset = content[marker_end..]
+        .find('\n')
+        .map_or(content.len(), |i| marker_end + i + 1);
+    for line in content[offset..].split_inclusive('\n') {
+        if line.trim_start().trim_start_matches('>').trim().is_empty() {
+            break;
+        }
+        offset += line.len();
+    }
+    offset
+}
+
+/// Edit that gives a reference spanning `text` the note `todo`, appended to
+/// any note it already has; `None` for region markers, which take no note.
+fn todo_note(text: &str, has_note: bool, todo: &str) -> Option<String> {
+    let inner = text.strip_suffix(']')?;
+    if inner.ends_with(" begin") || inner.ends_with(" end") {
+        return None;
+    }
+    let separator = if has_note { "; " } else { " — " };
+    Some(format!("{inner}{separator}{todo}]"))
+}
+
+/// Plan replacing the rules `from` with `into`: `into` rules that do not
+/// exist are added as drafts after the first `from` rule, the `from` rules
+/// are marked deprecated, and every reference to them gets the note `todo`.
+async fn plan_restructure(
+    project_root: &Path,
+    config: &Config,
+    kind: Restructure,
+    from: &[String],
+    into: &[String],
+    spec: Option<&str>,
+    todo: &str,
+) -> Result<RestructurePlan> {
+    for id in into {
+        check_id(id)?;
+    }
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let defs = from
+        .iter()
+        .map(|id| locate(&data, spec, id))
+        .collect::<Result<Vec<_>>>()?;
+    let first = &defs[0];
+    if let Some(i) = defs.iter().position(|d| d.spec != first.spec) {
+        bail!(
+            "'{}' and '{}' are in different specs ({} and {})",
+            from[0],
+            from[i],
+            first.spec,
+            defs[i].spec
+        );
+    }
+    let prefix = &first.prefix;
+
+    let mut plan = RestructurePlan {
+        kind,
+        from: from.to_vec(),
+        into: into.to_vec(),
+        added: Vec::new(),
+        annotated: Vec::new(),
+        skipped: Vec::new(),
+        patch: PatchSet::new(project_root),
+    };
+    for id in into {
+        if !exists(&data, &first.spec, id) {
+            plan.added.push((id.clone(), first.file.clone()));
+        } else if kind == Restructure::Split {
+            bail!("rule '{id}' already exists in spec {}", first.spec);
+        }
+    }
+
+    // A rule merged into itself keeps its references as they are.
+    let bases: Vec<&str> = from
+        .iter()
+        .filter(|id| !into.contains(*id))
+        .map(String::as_str)
+        .collect();
+    for (rel, refs) in references(project_root, &data, prefix, &bases) {
+        let source = std::fs::read_to_string(project_root.join(&rel))?;
+        // References sharing a span (`r[impl a.b, c.d]`) get one note.
+        let mut spans = BTreeMap::new();
+        for r in refs {
+            spans.entry(r.span.offset).or_insert(r);
+        }
+        let mut edits = Vec::new();
+        for (start, r) in spans {
+            let range = start..start + r.span.length;
+            match source
+                .get(range.clone())
+                .and_then(|text| todo_note(text, r.note.is_some(), todo))
+            {
+                Some(text) => {
+                    edits.push(TextEdit::new(range, text));
+                    plan.annotated.push((rel.clone(), r.line));
+                }
+                None => plan.skipped.push((rel.clone(), r.line)),
+            }
+        }
+        let annotated = edit::apply_edits(&source, edits)
+            .map_err(|e| eyre::eyre!("failed to edit {}: {e}", rel.display()))?;
+        plan.patch.set(rel, source, annotated);
+    }
+
+    let files: BTreeSet<&PathBuf> = defs.iter().map(|d| &d.file).collect();
+    for file in files {
+        let mut missing = None;
+        plan.patch.modify(file, |content| {
+            let markers = edit::find_rule_markers(content);
+            let mut edits = Vec::new();
+            for (id, def) in from.iter().zip(&defs) {
+                if &def.file != file || into.contains(id) {
+                    continue;
+                }
+                let Some(marker) = markers
+                    .iter()
+                    .find(|m| &m.prefix == prefix && &m.id.base == id)
+                else {
+                    missing = Some(id.clone());
+                    return content.to_string();
+                };
+                edits.push(marker.set_attribute("status", Some("deprecated")));
+                if id == &from[0] && !plan.added.is_empty() {
+                    let end = rule_end(content, marker.span.end);
+                    let origin = match kind {
+                        Restructure::Split => "split from",
+                        Restructure::Merge => "merged from",
+                    };
+                    let mut scaffold = String::new();
+                    if !content[..end].ends_with('\n') {
+                        scaffold.push('\n');
+                    }
+                    for (new, _) in &plan.added {
+                        let _ = write!(
+                            scaffold,
+                            "\n{prefix}[{new} status=draft]\nTODO: {origin} `{}`.\n",
+                            from.join("`, `")
+                        );
+                    }
+                    edits.push(TextEdit::new(end..end, scaffold));
+                }
+            }
+            edit::apply_edits(content, edits).unwrap_or_else(|_| content.to_string())
+        })?;
+        if let Some(id) = missing {
+            bail!("marker {prefix}[{id}] not found in {}", file.display());
+        }
+    }
+    Ok(plan)
+}
+
+/// Plan splitting the rule `old` into the new rules `into`.
+// r[impl cli.split]
+pub async fn plan_split(
+    project_root: &Path,
+    config: &Config,
+    old: &str,
+    into: &[String],
+    spec: Option<&str>,
+) -> Result<RestructurePlan> {
+    if into.len() < 2 {
+        bail!("--into needs at least two rule IDs");
+    }
+    let todo = format!("TODO: split into {}", into.join(", "));
+    let from = [old.to_string()];
+    plan_restructure(
+        project_root,
+        config,
+        Restructure::Split,
+        &from,
+        into,
+        spec,
+        &todo,
+    )
+    .await
+}
+
+/// Plan merging the rules `from` into the rule `into`, which may be one of
+/// them or another existing rule.
+// r[impl cli.merge]
+pub async fn plan_merge(
+    project_root: &Path,
+    config: &Config,
+    from: &[String],
+    into: &str,
+    spec: Option<&str>,
+) -> Result<RestructurePlan> {
+    if from.len() < 2 {
+        bail!("merge needs at least two rule IDs");
+    }
+    let todo = format!("TODO: merged into {into}");
+    let into = [into.to_string()];
+    plan_restructure(
+        project_root,
+        config,
+        Restructure::Merge,
+        from,
+        &into,
+        spec,
+        &todo,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One spec, `spec.md`, implemented by `src/**/*.rs`.
+    fn demo_config() -> Config {
+        facet_styx::from_str(
+            r#"specs (
+    {
+        name demo
+        include (spec.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_whole_ids_only() {
+        let text = "r[impl auth.login+2, auth.login.retry]";
+        assert_eq!(id_ranges(text, "auth.login"), vec![7..17]);
+        assert_eq!(
+            id_ranges("r[depends .auth.login]", "auth.login"),
+            vec![11..21]
+        );
+        assert!(id_ranges("r[impl x.auth.login]", "auth.login").is_empty());
+        assert!(id_ranges("r[impl auth.login_v2]", "auth.login").is_empty());
+    }
+
+    // r[verify cli.rename]
+    #[tokio::test]
+    async fn renames_marker_and_references() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("vendor")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MUST log out.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\npub fn login() {}\n\n\
+             // r[impl auth.logout]\npub fn logout() {}\n\n\
+             // r[verify auth.login, auth.logout]\nfn test() {}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("vendor/lib.rs"), "// r[impl auth.login]\n").unwrap();
+        let config = demo_config();
+
+        let plan = plan_rename(root, &config, "auth.login", "session.start", None)
+            .await
+            .unwrap();
+        assert_eq!(plan.spec, "demo");
+        assert_eq!(plan.spec_file, PathBuf::from("spec.md"));
+        assert_eq!(plan.references.len(), 2);
+        assert!(plan.skipped.is_empty());
+        let files: Vec<&Path> = plan.patch.files().collect();
+        assert_eq!(files, [Path::new("spec.md"), Path::new("src/lib.rs")]);
+        let diff = plan.patch.to_diff();
+        assert!(
+            diff.contains("-r[auth.login]\n+r[session.start]\n"),
+            "{diff}"
+        );
+        assert!(
+            diff.contains("+// r[verify session.start, auth.logout]\n"),
+            "{diff}"
+        );
+
+        plan.patch.apply().unwrap();
+        let source = std::fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(source.starts_with("// r[impl session.start]\n"), "{source}");
+        // Files outside the include globs are left alone.
+        assert_eq!(
+            std::fs::read_to_string(root.join("vendor/lib.rs")).unwrap(),
+            "// r[impl auth.login]\n"
+        );
+
+        let err = plan_rename(root, &config, "session.start", "auth.logout", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+        let err = plan_rename(root, &config, "session.start", "bad id", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a valid rule ID"), "{err}");
+    }
+
+    #[test]
+    fn notes_and_rule_ends() {
+        assert_eq!(
+            todo_note("r[impl a.b]", false, "TODO: x").as_deref(),
+            Some("r[impl a.b — TODO: x]")
+        );
+        assert_eq!(
+            todo_note("r[impl a.b — TCP only]", true, "TODO: x").as_deref(),
+            Some("r[impl a.b — TCP only; TODO: x]")
+        );
+        assert_eq!(todo_note("r[impl a.b begin]", false, "TODO: x"), None);
+
+        let spec = "r[a.b]\nFirst line\nsecond line.\n\nr[c.d]\n";
+        assert_eq!(rule_end(spec, 6), 31);
+        let quoted = "> r[a.b]\n> Text.\n>\n> r[c.d]\n";
+        assert_eq!(rule_end(quoted, 8), 17);
+    }
+
+    // r[verify cli.split]
+    // r[verify cli.merge]
+    #[tokio::test]
+    async fn split_and_merge_leave_todo_notes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n\n\
+             r[auth.logout]\nUsers MUST log out.\n\n\
+             r[auth.expire]\nSessions MUST expire.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\npub fn login() {}\n\n\
+             // r[impl auth.logout — best effort]\npub fn logout() {}\n\n\
+             // r[impl auth.expire]\npub fn expire() {}\n",
+        )
+        .unwrap();
+        let config = demo_config();
+
+        let into = [
+            "auth.login.password".to_string(),
+            "auth.login.sso".to_string(),
+        ];
+        let plan = plan_split(root, &config, "auth.login", &into, None)
+            .await
+            .unwrap();
+        assert_eq!(plan.annotated, [(PathBuf::from("src/lib.rs"), 1)]);
+        assert_eq!(plan.added.len(), 2);
+        let spec = &plan.patch.get(Path::new("spec.md")).unwrap().updated;
+        assert!(
+            spec.contains(
+                "r[auth.login status=deprecated]\nUsers MUST log in.\n\n\
+                 r[auth.login.password status=draft]\nTODO: split from `auth.login`.\n\n\
+                 r[auth.login.sso status=draft]\nTODO: split from `auth.login`.\n\n\
+                 r[auth.logout]"
+            ),
+            "{spec}"
+        );
+        let source = &plan.patch.get(Path::new("src/lib.rs")).unwrap().updated;
+        assert!(
+            source.starts_with(
+                "// r[impl auth.login — TODO: split into auth.login.password, auth.login.sso]\n"
+            ),
+            "{source}"
+        );
+        let markdown = plan.to_markdown();
+        assert!(
+            markdown.contains("- Write the text of draft rule `auth.login.sso` in spec.md."),
+            "{markdown}"
+        );
+
+        // Merging into one of the merged rules keeps that rule as it is.
+        let from = ["auth.logout".to_string(), "auth.expire".to_string()];
+        let plan = plan_merge(root, &config, &from, "auth.expire", None)
+            .await
+            .unwrap();
+        assert!(plan.added.is_empty());
+        assert_eq!(plan.annotated, [(PathBuf::from("src/lib.rs"), 4)]);
+        let spec = &plan.patch.get(Path::new("spec.md")).unwrap().updated;
+        assert!(spec.contains("r[auth.logout status=deprecated]"), "{spec}");
+        assert!(spec.contains("r[auth.expire]\n"), "{spec}");
+        let source = &plan.patch.get(Path::new("src/lib.rs")).unwrap().updated;
+        assert!(
+            source
+                .contains("// r[impl auth.logout — best effort; TODO: merged into auth.expire]\n"),
+            "{source}"
+        );
+
+        let err = plan_split(root, &config, "auth.login", &into[..1], None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("at least two"), "{err}");
+    }
+}
@@ -0,0 +1,705 @@
+//! `tracey review` implementation.
+//!
+//! Turns a git diff into a reviewer checklist: for every changed source file,
+//! which rules are implemented or verified by the code being modified, which
+//! rules lost their last reference, and which references are new. Like
+//! `tracey bump`, this works directly on git and does not require the daemon.
+//!
+//! With `--format github`, the checklist becomes the job summary and the
+//! problems the diff touches — stale references on changed lines and MUST
+//! rules it touches that are not implemented — become error annotations.
+
+use eyre::{Result, WrapErr};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use tracey_core::{RefVerb, ReqReference, Reqs, RuleId, is_supported_extension};
+
+use crate::bump::{git_capture, git_cat_file};
+use crate::config::Config;
+
+/// What `tracey review` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Markdown checklist
+    #[default]
+    Markdown,
+    /// GitHub Actions job summary and workflow annotations
+    Github,
+}
+
+impl std::str::FromStr for Format {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" => Ok(Self::Markdown),
+            "github" => Ok(Self::Github),
+            other => Err(eyre::eyre!(
+                "Unsupported review format `{other}`; expected markdown or github"
+            )),
+        }
+    }
+}
+
+/// Review checklist for a diff.
+#[derive(Debug)]
+pub struct ReviewReport {
+    /// Revision the working tree was compared against.
+    pub base: String,
+    /// Changed files that touch spec-relevant code, in diff order.
+    pub files: Vec<FileReview>,
+    /// Problems the diff touches, by file and line.
+    pub problems: Vec<Problem>,
+}
+
+/// A problem at a line of a file, reported as an error annotation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Problem {
+    /// Path relative to the project root.
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+/// Escape workflow command data; property values also escape `:` and `,`.
+fn escape_workflow(text: &str, property: bool) -> String {
+    let mut out = text
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    if property {
+        out = out.replace(':', "%3A").replace(',', "%2C");
+    }
+    out
+}
+
+/// Spec-relevant changes in one file.
+#[derive(Debug, Default)]
+pub struct FileReview {
+    /// Path relative to the project root.
+    pub path: String,
+    /// Rules referenced by code in modified hunks.
+    pub touched: BTreeSet<RuleId>,
+    /// References removed by the diff that were the last of their kind for a rule.
+    pub lost: Vec<(RefVerb, RuleId)>,
+    /// References introduced by the diff, with their line in the new file.
+    pub added: Vec<(RefVerb, RuleId, usize)>,
+}
+
+impl FileReview {
+    fn is_empty(&self) -> bool {
+        self.touched.is_empty() && self.lost.is_empty() && self.added.is_empty()
+    }
+}
+
+impl ReviewReport {
+    /// Render the report as a markdown checklist.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Review checklist: changes since `{}`", self.base);
+        let _ = writeln!(out);
+        if self.files.is_empty() {
+            let _ = writeln!(out, "No changed file touches spec-relevant code.");
+            return out;
+        }
+        let _ = writeln!(
+            out,
+            "{} changed file(s) touch spec-relevant code.",
+            self.files.len()
+        );
+
+        if !self.problems.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## Problems");
+            let _ = writeln!(out);
+            for problem in &self.problems {
+                let _ = writeln!(
+                    out,
+                    "- {}:{}: {}",
+                    problem.file, problem.line, problem.message
+                );
+            }
+        }
+
+        for file in &self.files {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}", file.path);
+            let _ = writeln!(out);
+            for id in &file.touched {
+                let _ = writeln!(out, "- [ ] Modified code still satisfies `{id}`");
+            }
+            for (verb, id) in &file.lost {
+                let _ = writeln!(
+                    out,
+                    "- [ ] `{id}` lost its only `{verb}` reference; is that intended?"
+                );
+            }
+            for (verb, id, line) in &file.added {
+                let _ = writeln!(out, "- [ ] New `{verb}` reference to `{id}` (line {line})");
+            }
+        }
+
+        out
+    }
+
+    /// Render the problems as GitHub Actions `::error` workflow commands.
+    // r[impl cli.review.github]
+    pub fn to_github(&self) -> String {
+        let mut out = String::new();
+        for problem in &self.problems {
+            let _ = writeln!(
+                out,
+                "::error file={},line={}::{}",
+                escape_workflow(&problem.file, true),
+                problem.line,
+                escape_workflow(&problem.message, false)
+            );
+        }
+        out
+    }
+}
+
+/// Changed line ranges of one file, on both sides of the diff.
+#[derive(Debug, Default, PartialEq)]
+struct FileDiff {
+    /// Path before the change (`None` for added files).
+    old_path: Option<String>,
+    /// Path after the change (`None` for deleted files).
+    new_path: Option<String>,
+    /// 1-based line ranges replaced in the old file.
+    old_ranges: Vec<Range<usize>>,
+    /// 1-based line ranges written in the new file.
+    new_ranges: Vec<Range<usize>>,
+}
+
+/// Parse one side of a hunk header (`-12,3` / `+40`) into a line range.
+fn parse_hunk_range(spec: &str) -> Option<Range<usize>> {
+    let (start, len) = match spec.split_once(',') {
+        Some((start, len)) => (start.parse::<usize>().ok()?, len.parse::<usize>().ok()?),
+        None => (spec.parse::<usize>().ok()?, 1),
+    };
+    Some(start..start + len)
+}
+
+/// Parse `git diff --unified=0` output into per-file changed ranges.
+fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            files.push(FileDiff::default());
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            // A pure rename has no `---`/`+++` lines, only these headers.
+            if let Some(file) = files.last_mut() {
+                file.old_path = Some(path.to_string());
+            }
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = Some(path.to_string());
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            if let Some(file) = files.last_mut() {
+                file.old_path = path.strip_prefix("a/").map(String::from);
+            }
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = path.strip_prefix("b/").map(String::from);
+            }
+        } else if let Some(header) = line.strip_prefix("@@ ")
+            && let Some(file) = files.last_mut()
+        {
+            let mut parts = header.split_whitespace();
+            let old = parts.next().and_then(|s| s.strip_prefix('-'));
+            let new = parts.next().and_then(|s| s.strip_prefix('+'));
+            if let Some(range) = old.and_then(parse_hunk_range)
+                && !range.is_empty()
+            {
+                file.old_ranges.push(range);
+            }
+            if let Some(range) = new.and_then(parse_hunk_range)
+                && !range.is_empty()
+            {
+                file.new_ranges.push(range);
+            }
+        }
+    }
+    files
+}
+
+fn overlaps(ranges: &[Range<usize>], start: usize, end: usize) -> bool {
+    ranges.iter().any(|r| r.start <= end && start < r.end)
+}
+
+/// Rules referenced by code that intersects `ranges`: references on changed
+/// lines, plus the references attached to any code unit the ranges touch.
+fn touched_rules(
+    path: &Path,
+    content: &str,
+    refs: &[&ReqReference],
+    ranges: &[Range<usize>],
+) -> BTreeSet<RuleId> {
+    let mut touched: BTreeSet<RuleId> = refs
+        .iter()
+        .filter(|r| overlaps(ranges, r.line, r.line))
+        .map(|r| r.req_id.clone())
+        .collect();
+    for unit in tracey_core::code_units::extract(path, content).units {
+        if overlaps(ranges, unit.start_line, unit.end_line) {
+            touched.extend(unit.req_refs);
+        }
+    }
+    touched
+}
+
+/// Build the review checklist for the working tree against `base`.
+///
+/// Only references using a configured spec prefix are considered. A removed
+/// reference counts as "lost" when no reference with the same prefix, verb and
+/// rule base remains anywhere in the working tree.
+// r[impl cli.review]
+pub async fn review(project_root: &Path, config: &Config, base: &str) -> Result<ReviewReport> {
+    git_capture(
+        project_root,
+        &["rev-parse", "--verify", &format!("{base}^{{commit}}")],
+    )
+    .wrap_err_with(|| format!("unknown revision `{base}`"))?;
+    let diff = git_capture(
+        project_root,
+        &[
+            "diff",
+            "--relative",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            // r[impl history.renames]
+            // A moved file is diffed against its old path instead of
+            // showing up as every reference removed and re-added.
+            "--find-renames",
+            base,
+        ],
+    )?;
+
+    let data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+    let prefixes: BTreeSet<&str> = data
+        .config
+        .specs
+        .iter()
+        .map(|s| s.prefix.as_str())
+        .collect();
+    // (prefix, rule base) -> current version, to spot stale references.
+    let mut current: HashMap<(&str, &str), u32> = HashMap::new();
+    for ((spec, _), forward) in &data.forward_by_impl {
+        let Some(info) = data.config.specs.iter().find(|s| &s.name == spec) else {
+            continue;
+        };
+        for rule in &forward.rules {
+            current.insert(
+                (info.prefix.as_str(), rule.id.base.as_str()),
+                rule.id.version,
+            );
+        }
+    }
+    let mut problems = BTreeSet::new();
+    let mut touched_bases = BTreeSet::new();
+    let remaining = |r: &ReqReference| {
+        data.source_reqs_by_file.values().any(|reqs| {
+            reqs.references.iter().any(|other| {
+                other.prefix == r.prefix
+                    && other.verb == r.verb
+                    && other.req_id.base == r.req_id.base
+            })
+        })
+    };
+
+    let mut files = Vec::new();
+    for diff in parse_diff(&diff) {
+        let Some(display) = diff.new_path.as_ref().or(diff.old_path.as_ref()) else {
+            continue;
+        };
+        if !Path::new(display)
+            .extension()
+            .is_some_and(is_supported_extension)
+        {
+            continue;
+        }
+
+        let old_content = match &diff.old_path {
+            Some(path) => git_cat_file(project_root, base, &format!("./{path}"))?,
+            None => None,
+        };
+        let new_content = match &diff.new_path {
+            Some(path) => std::fs::read_to_string(project_root.join(path)).ok(),
+            None => None,
+        };
+        let old_reqs = old_content
+            .as_deref()
+            .map(|c| Reqs::extract_from_content(Path::new(display), c))
+            .unwrap_or_default();
+        let new_reqs = new_content
+            .as_deref()
+            .map(|c| Reqs::extract_from_content(Path::new(display), c))
+            .unwrap_or_default();
+        let relevant = |reqs: &Reqs| -> Vec<ReqReference> {
+            reqs.references
+                .iter()
+                .filter(|r| prefixes.contains(r.prefix.as_str()))
+                .cloned()
+                .collect()
+        };
+        let old_refs = relevant(&old_reqs);
+        let new_refs = relevant(&new_reqs);
+
+        for r in &new_refs {
+            if let Some(&version) = current.get(&(r.prefix.as_str(), r.req_id.base.as_str()))
+                && r.req_id.version < version
+                && overlaps(&diff.new_ranges, r.line, r.line)
+            {
+                problems.insert(Problem {
+                    file: display.clone(),
+                    line: r.line,
+                    message: format!(
+                        "Stale reference: `{}[{} {}]` points at an older version; the rule is now version {version}",
+                        r.prefix, r.verb, r.req_id
+                    ),
+                });
+            }
+        }
+
+        let mut review = FileReview {
+            path: display.clone(),
+            ..Default::default()
+        };
+        if let Some(content) = &old_content {
+            let refs: Vec<_> = old_refs.iter().collect();
+            review.touched.extend(touched_rules(
+                &PathBuf::from(display),
+                content,
+                &refs,
+                &diff.old_ranges,
+            ));
+        }
+        if let Some(content) = &new_content {
+            let refs: Vec<_> = new_refs.iter().collect();
+            review.touched.extend(touched_rules(
+                &PathBuf::from(display),
+                content,
+                &refs,
+                &diff.new_ranges,
+            ));
+        }
+
+        // Match references across both sides by (prefix, verb, id); whatever is
+        // left unmatched on either side was removed or added by the diff.
+        let key = |r: &ReqReference| (r.prefix.clone(), r.verb.clone(), r.req_id.clone());
+        let mut unmatched_old: Vec<&ReqReference> = old_refs.iter().collect();
+        for new_ref in &new_refs {
+            match unmatched_old.iter().position(|o| key(o) == key(new_ref)) {
+                Some(idx) => {
+                    unmatched_old.swap_remove(idx);
+                }
+                None => {
+                    review
+                        .added
+                        .push((new_ref.verb.clone(), new_ref.req_id.clone(), new_ref.line))
+                }
+            }
+        }
+        for old_ref in unmatched_old {
+            let entry = (old_ref.verb.clone(), old_ref.req_id.clone());
+            if !remaining(old_ref) && !review.lost.contains(&entry) {
+                review.lost.push(entry);
+            }
+        }
+        review.lost.sort_by(|a, b| a.1.cmp(&b.1));
+        review.added.sort_by_key(|(_, _, line)| *line);
+
+        // Rules whose references were only added or removed are already listed.
+        let listed: BTreeSet<&RuleId> = review
+            .lost
+            .iter()
+            .map(|(_, id)| id)
+            .chain(review.added.iter().map(|(_, id, _)| id))
+            .collect();
+        touched_bases.extend(listed.iter().map(|id| id.base.clone()));
+        touched_bases.extend(review.touched.iter().map(|id| id.base.clone()));
+        review.touched.retain(|id| !listed.contains(id));
+
+        if !review.is_empty() {
+            files.push(review);
+        }
+    }
+
+    // MUST rules the diff touches, with the implementations lacking them.
+    let mut unimplemented: BTreeMap<(&str, usize, &RuleId), Vec<String>> = BTreeMap::new();
+    for ((spec, impl_name), forward) in &data.forward_by_impl {
+        for rule in &forward.rules {
+            let must = rule
+                .level
+                .as_deref()
+                .map_or_else(|| rule.raw.contains("MUST"), |level| level == "must");
+            if must
+                && touched_bases.contains(&rule.id.base)
+                && !rule.is_implemented()
+                && !rule.is_waived()
+                && !matches!(rule.status.as_deref(), Some("draft" | "removed"))
+                && let (Some(file), Some(line)) = (&rule.source_file, rule.source_line)
+            {
+                unimplemented
+                    .entry((file, line, &rule.id))
+                    .or_default()
+                    .push(format!("{spec}/{impl_name}"));
+            }
+        }
+    }
+    for ((file, line, id), impls) in unimplemented {
+        problems.insert(Problem {
+            file: file.to_string(),
+            line,
+            message: format!(
+                "MUST rule `{id}` is touched by this change but not implemented in {}",
+                impls.join(", ")
+            ),
+        });
+    }
+
+    Ok(ReviewReport {
+        base: base.to_string(),
+        files,
+        problems: problems.into_iter().collect(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)] // hunk ranges, not ranges of items
+mod tests {
+    use super::*;
+
+    fn id(base: &str) -> RuleId {
+        tracey_core::parse_rule_id(base).unwrap()
+    }
+
+    #[test]
+    fn parses_unified_zero_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3,2 +3,3 @@ fn main() {
+@@ -10 +11,0 @@ fn other() {
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,4 @@
+";
+        let files = parse_diff(diff);
+        assert_eq!(
+            files,
+            vec![
+                FileDiff {
+                    old_path: Some("src/lib.rs".into()),
+                    new_path: Some("src/lib.rs".into()),
+                    old_ranges: vec![3..5, 10..11],
+                    new_ranges: vec![3..6],
+                },
+                FileDiff {
+                    old_path: None,
+                    new_path: Some("src/new.rs".into()),
+                    old_ranges: vec![],
+                    new_ranges: vec![1..5],
+                },
+            ]
+        );
+    }
+
+    // r[verify history.renames]
+    #[test]
+    fn parses_renamed_files() {
+        let diff = "\
+diff --git a/src/old.rs b/src/new.rs
+similarity index 100%
+rename from src/old.rs
+rename to src/new.rs
+diff --git a/src/a.rs b/src/b.rs
+similarity index 90%
+rename from src/a.rs
+rename to src/b.rs
+index 1111111..2222222 100644
+--- a/src/a.rs
++++ b/src/b.rs
+@@ -2 +2 @@
+";
+        let files = parse_diff(diff);
+        assert_eq!(
+            files,
+            vec![
+                FileDiff {
+                    old_path: Some("src/old.rs".into()),
+                    new_path: Some("src/new.rs".into()),
+                    old_ranges: vec![],
+                    new_ranges: vec![],
+                },
+                FileDiff {
+                    old_path: Some("src/a.rs".into()),
+                    new_path: Some("src/b.rs".into()),
+                    old_ranges: vec![2..3],
+                    new_ranges: vec![2..3],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn touched_rules_include_enclosing_code_unit() {
+        let content = "\
+// r[impl auth.login]
+fn login() {
+    check();
+    done();
+}
+
+fn unrelated() {}
+";
+        let path = Path::new("src/lib.rs");
+        let reqs = Reqs::extract_from_content(path, content);
+        let refs: Vec<_> = reqs.references.iter().collect();
+
+        let touched = touched_rules(path, content, &refs, &[4..5]);
+        assert_eq!(touched, BTreeSet::from([id("auth.login")]));
+
+        let untouched = touched_rules(path, content, &refs, &[7..8]);
+        assert!(untouched.is_empty());
+    }
+
+    // r[verify cli.review]
+    #[test]
+    fn checklist_lists_each_kind_of_change() {
+        let report = ReviewReport {
+            base: "main".to_string(),
+            files: vec![FileReview {
+                path: "src/auth.rs".to_string(),
+                touched: BTreeSet::from([id("auth.login")]),
+                lost: vec![(RefVerb::Impl, id("auth.logout"))],
+                added: vec![(RefVerb::Verify, id("auth.session"), 12)],
+            }],
+            problems: Vec::new(),
+        };
+        let md = report.to_markdown();
+        assert!(md.starts_with("# Review checklist: changes since `main`\n"));
+        assert!(md.contains("## src/auth.rs"));
+        assert!(md.contains("- [ ] Modified code still satisfies `auth.login`"));
+        assert!(md.contains("- [ ] `auth.logout` lost its only `impl` reference"));
+        assert!(md.contains("- [ ] New `verify` reference to `auth.session` (line 12)"));
+
+        let empty = ReviewReport {
+            base: "main".to_string(),
+            files: vec![],
+            problems: Vec::new(),
+        };
+        assert!(
+            empty
+                .to_markdown()
+                .contains("No changed file touches spec-relevant code.")
+        );
+    }
+
+    // r[verify cli.review.github]
+    #[test]
+    fn problems_become_workflow_errors() {
+        let report = ReviewReport {
+            base: "main".to_string(),
+            files: vec![FileReview {
+                path: "src/auth.rs".to_string(),
+                touched: BTreeSet::from([id("auth.login")]),
+                ..Default::default()
+            }],
+            problems: vec![
+                Problem {
+                    file: "docs/spec.md".to_string(),
+                    line: 7,
+                    message: "MUST rule `auth.login` is touched by this change but not implemented in app/rust, app/go".to_string(),
+                },
+                Problem {
+                    file: "src/a,b.rs".to_string(),
+                    line: 3,
+                    message: "50% done\nsee log".to_string(),
+                },
+            ],
+        };
+        assert_eq!(
+            report.to_github(),
+            "::error file=docs/spec.md,line=7::MUST rule `auth.login` is touched by this change but not implemented in app/rust, app/go\n\
+             ::error file=src/a%2Cb.rs,line=3::50%25 done%0Asee log\n"
+        );
+        let md = report.to_markdown();
+        assert!(
+            md.contains("## Problems\n\n- docs/spec.md:7: MUST rule `auth.login`"),
+            "{md}"
+        );
+    }
+
+    fn git(root: &Path, args: &[&str]) {
+        git_capture(root, args).unwrap();
+    }
+
+    // r[verify cli.review]
+    #[tokio::test]
+    async fn reviews_the_working_tree_against_a_revision() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+        git(root, &["config", "user.email", "t@example.com"]);
+        git(root, &["config", "user.name", "t"]);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers log in.\n\nr[auth.logout]\nUsers log out.\n\n\
+             r[auth.session]\nSessions expire.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn login() {\n    let a = 1;\n}\n\n\
+             // r[impl auth.logout]\nfn logout() {}\n",
+        )
+        .unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "base"]);
+
+        // Edit the body of login, drop logout's only reference, and verify sessions.
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn login() {\n    let a = 2;\n}\n\n\
+             fn logout() {}\n\n// r[verify auth.session]\nfn session() {}\n",
+        )
+        .unwrap();
+        let config: Config = facet_styx::from_str(
+            r#"specs (
+    {
+        name app
+        include (spec.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap();
+
+        let report = review(root, &config, "HEAD").await.unwrap();
+        assert_eq!(report.files.len(), 1, "{report:?}");
+        let file = &report.files[0];
+        assert_eq!(file.path, "src/lib.rs");
+        assert_eq!(file.touched, BTreeSet::from([id("auth.login")]));
+        assert_eq!(file.lost, vec![(RefVerb::Impl, id("auth.logout"))]);
+        assert_eq!(file.added, vec![(RefVerb::Verify, id("auth.session"), 8)]);
+
+        assert!(review(root, &config, "no-such-rev").await.is_err());
+    }
+}
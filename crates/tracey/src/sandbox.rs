@@ -0,0 +1,251 @@
+//! Restricted handling of untrusted spec sources.
+//!
+//! r[impl config.spec.sandbox]
+//!
+//! A spec with a `sandbox` block, such as a vendored third-party spec, is
+//! held to size and rule count limits before it is parsed, and the HTML
+//! rendered from it is sanitized before it reaches the dashboard or an
+//! export. Sanitizing is allowlist-based: only known-safe elements and
+//! attributes survive, so scripts, SVG animation, event handlers and `style`
+//! are all dropped, and URLs are restricted to an allowlist of schemes.
+
+use std::collections::HashSet;
+
+use eyre::{Result, bail};
+
+use crate::config::SandboxConfig;
+
+/// Default cap on the size of a single spec file, in kilobytes.
+pub const DEFAULT_MAX_FILE_KB: u64 = 512;
+
+/// Default cap on the number of rules a spec may define.
+pub const DEFAULT_MAX_RULES: u64 = 5000;
+
+/// URL schemes allowed when the config doesn't list any.
+pub const DEFAULT_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Elements removed together with everything inside them. Other elements
+/// outside the allowlist are removed but keep their text.
+const DROPPED_WITH_CONTENT: &[&str] = &[
+    "script", "style", "iframe", "object", "applet", "noscript", "template", "frameset", "noembed",
+    "xmp", "svg", "math",
+];
+
+/// Attributes kept on every allowed element, besides ammonia's defaults.
+const GENERIC_ATTRIBUTES: &[&str] = &["class", "id"];
+
+/// Prefix of the custom elements syntax highlighting wraps tokens in, such
+/// as `<a-k>` for a keyword.
+const HIGHLIGHT_TAG_PREFIX: &str = "a-";
+
+/// Sandbox limits resolved from config, with defaults filled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandbox {
+    /// Largest spec file accepted, in bytes.
+    pub max_file_bytes: u64,
+    /// Most rules the spec may define.
+    pub max_rules: u64,
+    /// Allowed URL schemes, lowercase.
+    pub url_schemes: Vec<String>,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::from_config(&SandboxConfig::default())
+    }
+}
+
+impl Sandbox {
+    /// Resolve limits from a spec's `sandbox` block.
+    pub fn from_config(config: &SandboxConfig) -> Self {
+        let url_schemes = if config.url_schemes.is_empty() {
+            DEFAULT_URL_SCHEMES.iter().map(|s| s.to_string()).collect()
+        } else {
+            config
+                .url_schemes
+                .iter()
+                .map(|s| s.to_ascii_lowercase())
+                .collect()
+        };
+        Self {
+            max_file_bytes: config
+                .max_file_kb
+                .unwrap_or(DEFAULT_MAX_FILE_KB)
+                .saturating_mul(1024),
+            max_rules: config.max_rules.unwrap_or(DEFAULT_MAX_RULES),
+            url_schemes,
+        }
+    }
+
+    /// Fail if a spec file of `len` bytes is over the size limit.
+    pub fn check_file_size(&self, path: &str, len: u64) -> Result<()> {
+        if len > self.max_file_bytes {
+            bail!(
+                "Sandboxed spec file {path} is {} KB, over the {} KB limit",
+                len.div_ceil(1024),
+                self.max_file_bytes / 1024
+            );
+        }
+        Ok(())
+    }
+
+    /// Fail if a spec defines more rules than allowed.
+    pub fn check_rule_count(&self, spec_name: &str, count: usize) -> Result<()> {
+        if count as u64 > self.max_rules {
+            bail!(
+                "Sandboxed spec '{spec_name}' defines {count} rules, over the limit of {}",
+                self.max_rules
+            );
+        }
+        Ok(())
+    }
+
+    /// Reduce rendered HTML to an allowlist of elements and attributes, with
+    /// URLs restricted to the allowed schemes.
+    pub fn sanitize_html(&self, html: &str) -> String {
+        let schemes: HashSet<&str> = self.url_schemes.iter().map(String::as_str).collect();
+        ammonia::Builder::default()
+            .add_tags(highlight_tags(html))
+            .clean_content_tags(DROPPED_WITH_CONTENT.iter().copied().collect())
+            .add_generic_attributes(GENERIC_ATTRIBUTES)
+            .generic_attribute_prefixes(HashSet::from(["data-"]))
+            .url_schemes(schemes)
+            .link_rel(None)
+            .clean(html)
+            .to_string()
+    }
+}
+
+/// The syntax highlighting elements used in `html`. They are inert and only
+/// ever kept without attributes.
+fn highlight_tags(html: &str) -> HashSet<&str> {
+    html.match_indices('<')
+        .filter_map(|(start, _)| {
+            let tag = &html[start + 1..];
+            let suffix = tag.strip_prefix(HIGHLIGHT_TAG_PREFIX)?;
+            let len = suffix
+                .find(|c: char| !c.is_ascii_lowercase())
+                .unwrap_or(suffix.len());
+            (len > 0).then(|| &tag[..HIGHLIGHT_TAG_PREFIX.len() + len])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sanitize(html: &str) -> String {
+        Sandbox::default().sanitize_html(html)
+    }
+
+    // r[verify config.spec.sandbox]
+    #[test]
+    fn strips_active_content() {
+        assert_eq!(sanitize("<p>a<script>alert(1)</script>b</p>"), "<p>ab</p>");
+        assert_eq!(
+            sanitize("<p>x</p><IFRAME src=\"https://evil\"></iframe><p>y</p>"),
+            "<p>x</p><p>y</p>"
+        );
+        assert_eq!(sanitize("<p><svg><script>x</script></svg></p>"), "<p></p>");
+        assert_eq!(sanitize("<p>a<!-- hidden --></p>"), "<p>a</p>");
+        assert_eq!(sanitize("<embed src=\"x.swf\">ok"), "ok");
+    }
+
+    #[test]
+    fn drops_svg_animation_payloads() {
+        for payload in [
+            "<svg><a><animate attributeName=\"href\" values=\"javascript:alert(1)\"/><text>x</text></a></svg>",
+            "<svg><set attributeName=\"href\" to=\"javascript:alert(1)\"/></svg>",
+            "<svg><animateMotion values=\"javascript:alert(1)\"/></svg>",
+            "<p><animate attributeName=\"href\" from=\"javascript:alert(1)\" to=\"x\"></animate>ok</p>",
+        ] {
+            let clean = sanitize(payload);
+            assert!(!clean.contains("javascript"), "{payload} -> {clean}");
+            assert!(!clean.to_ascii_lowercase().contains("<animate"), "{clean}");
+            assert!(!clean.contains("<set"), "{clean}");
+        }
+    }
+
+    #[test]
+    fn drops_styles() {
+        assert_eq!(
+            sanitize("<p style=\"background:url(javascript:x)\">a</p><style>p{}</style>"),
+            "<p>a</p>"
+        );
+    }
+
+    #[test]
+    fn drops_event_handlers() {
+        assert_eq!(
+            sanitize("<img src=\"a.png\" onerror=\"alert(1)\" alt=\"a\">"),
+            "<img src=\"a.png\" alt=\"a\">"
+        );
+        assert_eq!(
+            sanitize("<div class=rule ONCLICK=x>t</div>"),
+            "<div class=\"rule\">t</div>"
+        );
+    }
+
+    #[test]
+    fn restricts_url_schemes() {
+        assert_eq!(
+            sanitize("<a href=\"javascript:alert(1)\">x</a>"),
+            "<a>x</a>"
+        );
+        assert_eq!(
+            sanitize("<a href=\"jav&#x61;script&colon;alert(1)\">x</a>"),
+            "<a>x</a>"
+        );
+        assert_eq!(sanitize("<a href=\" java\tscript:x\">x</a>"), "<a>x</a>");
+        assert_eq!(sanitize("<img src=\"data:image/svg+xml,x\">"), "<img>");
+        for kept in [
+            "<a href=\"https://example.com/a:b\">x</a>",
+            "<a href=\"#r--auth.login\">x</a>",
+            "<a href=\"../other.md\">x</a>",
+            "<a href=\"mailto:a@example.com\">x</a>",
+            "<a href=\"path/with:colon\">x</a>",
+        ] {
+            assert_eq!(sanitize(kept), kept);
+        }
+        assert_eq!(
+            sanitize("<a href=../up/page.html>x</a>"),
+            "<a href=\"../up/page.html\">x</a>"
+        );
+
+        let custom = Sandbox::from_config(&SandboxConfig {
+            url_schemes: vec!["HTTPS".into()],
+            ..Default::default()
+        });
+        assert_eq!(
+            custom.sanitize_html("<a href=\"http://example.com\">x</a>"),
+            "<a>x</a>"
+        );
+    }
+
+    #[test]
+    fn leaves_ordinary_markup_alone() {
+        let html = "<h2 id=\"auth\">Auth</h2>\n<div class=\"rule\" data-rule=\"auth.login\"><p>Use <code>a &lt; b</code> &amp; more.</p><br/></div>";
+        assert_eq!(sanitize(html), html.replace("<br/>", "<br>"));
+        assert_eq!(sanitize("1 < 2 and 3 > 2"), "1 &lt; 2 and 3 &gt; 2");
+        assert_eq!(
+            sanitize("<code><a-k>fn</a-k> <a-f onclick=\"x\">main</a-f></code>"),
+            "<code><a-k>fn</a-k> <a-f>main</a-f></code>"
+        );
+    }
+
+    #[test]
+    fn enforces_limits() {
+        let sandbox = Sandbox::from_config(&SandboxConfig {
+            max_file_kb: Some(1),
+            max_rules: Some(2),
+            ..Default::default()
+        });
+        assert!(sandbox.check_file_size("spec.md", 1024).is_ok());
+        let err = sandbox.check_file_size("spec.md", 1025).unwrap_err();
+        assert!(err.to_string().contains("over the 1 KB limit"), "{err}");
+        assert!(sandbox.check_rule_count("vendor", 2).is_ok());
+        let err = sandbox.check_rule_count("vendor", 3).unwrap_err();
+        assert!(err.to_string().contains("3 rules"), "{err}");
+    }
+}
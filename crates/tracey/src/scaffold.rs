@@ -0,0 +1,297 @@
+//! `tracey scaffold` implementation.
+//!
+//! Generates a stub for a rule that has no code yet: a function carrying the
+//! rule text as its doc comment and an `impl` annotation, plus a test carrying
+//! a `verify` annotation. The stub is a starting point; the function bodies
+//! fail until they are filled in.
+
+use eyre::{Result, WrapErr};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracey_core::{RuleId, parse_rule_id};
+
+use crate::config::Config;
+
+/// Languages `tracey scaffold` can generate stubs for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    Python,
+    TypeScript,
+}
+
+impl Lang {
+    /// Infer the language from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            "ts" | "tsx" | "js" | "jsx" | "mts" | "mjs" => Some(Self::TypeScript),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Lang {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Ok(Self::Rust),
+            "python" | "py" => Ok(Self::Python),
+            "typescript" | "ts" | "javascript" | "js" => Ok(Self::TypeScript),
+            other => Err(eyre::eyre!(
+                "Unsupported language `{other}`; expected rust, python, or typescript"
+            )),
+        }
+    }
+}
+
+/// A rule found in the configured specs.
+#[derive(Debug)]
+pub struct ScaffoldRule {
+    /// Marker prefix (e.g. `r`).
+    pub prefix: String,
+    /// Current ID, including version.
+    pub id: RuleId,
+    /// Rule text as markdown, without blockquote markers.
+    pub text: String,
+}
+
+/// Find rule `rule` in the specs of `config`.
+///
+/// `rule` may omit the version; the current version is used.
+pub async fn find_rule(project_root: &Path, config: &Config, rule: &str) -> Result<ScaffoldRule> {
+    let wanted =
+        parse_rule_id(rule).ok_or_else(|| eyre::eyre!("`{rule}` is not a valid rule ID"))?;
+    for spec in &config.specs {
+        let patterns: Vec<&str> = spec.include.iter().map(String::as_str).collect();
+        let rules = crate::load_rules_from_globs(project_root, &patterns, true)
+            .await
+            .wrap_err_with(|| format!("failed to load spec '{}'", spec.name))?;
+        for found in rules {
+            if let Some(id) = parse_rule_id(&found.def.id.to_string())
+                && id.base == wanted.base
+            {
+                return Ok(ScaffoldRule {
+                    prefix: found.prefix,
+                    id,
+                    text: unquote(&found.def.raw),
+                });
+            }
+        }
+    }
+    Err(eyre::eyre!(
+        "Rule `{}` is not defined in any configured spec",
+        wanted.base
+    ))
+}
+
+/// Strip blockquote markers and surrounding blank lines from rule markdown.
+fn unquote(raw: &str) -> String {
+    raw.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            match trimmed.strip_prefix('>') {
+                Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                None => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// `channel.reset-behavior` -> `["channel", "reset", "behavior"]`
+fn words(base: &str) -> Vec<String> {
+    base.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+fn snake_case(base: &str) -> String {
+    words(base).join("_")
+}
+
+fn camel_case(base: &str) -> String {
+    let mut out = String::new();
+    for (i, word) in words(base).iter().enumerate() {
+        if i == 0 {
+            out.push_str(word);
+        } else {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                out.push(first.to_ascii_uppercase());
+                out.push_str(chars.as_str());
+            }
+        }
+    }
+    out
+}
+
+/// Render the stub source for `rule` in `lang`.
+// r[impl cli.scaffold]
+pub fn render(rule: &ScaffoldRule, lang: Lang) -> String {
+    let id = &rule.id;
+    let annotation = |verb: &str| format!("{}[{verb} {id}]", rule.prefix);
+    let mut out = String::new();
+    match lang {
+        Lang::Rust => {
+            let name = snake_case(&id.base);
+            for line in rule.text.lines() {
+                let _ = writeln!(out, "///{}{line}", if line.is_empty() { "" } else { " " });
+            }
+            let _ = writeln!(out, "///");
+            let _ = writeln!(out, "/// {}", annotation("impl"));
+            let _ = writeln!(out, "pub fn {name}() {{");
+            let _ = writeln!(out, "    todo!(\"{id}\")");
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "#[cfg(test)]");
+            let _ = writeln!(out, "mod tests {{");
+            let _ = writeln!(out, "    use super::*;");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "    // {}", annotation("verify"));
+            let _ = writeln!(out, "    #[test]");
+            let _ = writeln!(out, "    fn {name}_holds() {{");
+            let _ = writeln!(out, "        {name}();");
+            let _ = writeln!(out, "        todo!(\"verify {id}\")");
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+        Lang::Python => {
+            let name = snake_case(&id.base);
+            let text = rule.text.replace("\"\"\"", "\\\"\\\"\\\"");
+            let _ = writeln!(out, "# {}", annotation("impl"));
+            let _ = writeln!(out, "def {name}():");
+            let _ = writeln!(out, "    \"\"\"");
+            for line in text.lines() {
+                let _ = writeln!(out, "{}{line}", if line.is_empty() { "" } else { "    " });
+            }
+            let _ = writeln!(out, "    \"\"\"");
+            let _ = writeln!(out, "    raise NotImplementedError(\"{id}\")");
+            let _ = writeln!(out);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "# {}", annotation("verify"));
+            let _ = writeln!(out, "def test_{name}():");
+            let _ = writeln!(out, "    {name}()");
+            let _ = writeln!(out, "    raise NotImplementedError(\"verify {id}\")");
+        }
+        Lang::TypeScript => {
+            let name = camel_case(&id.base);
+            let _ = writeln!(out, "/**");
+            for line in rule.text.replace("*/", "*\\/").lines() {
+                let _ = writeln!(out, " *{}{line}", if line.is_empty() { "" } else { " " });
+            }
+            let _ = writeln!(out, " *");
+            let _ = writeln!(out, " * {}", annotation("impl"));
+            let _ = writeln!(out, " */");
+            let _ = writeln!(out, "export function {name}(): void {{");
+            let _ = writeln!(out, "  throw new Error(\"not implemented: {id}\");");
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "// {}", annotation("verify"));
+            let _ = writeln!(out, "test(\"{id}\", () => {{");
+            let _ = writeln!(out, "  {name}();");
+            let _ = writeln!(out, "  throw new Error(\"not implemented: verify {id}\");");
+            let _ = writeln!(out, "}});");
+        }
+    }
+    out
+}
+
+/// Write `source` to `out`, creating parent directories. Refuses to
+/// overwrite an existing file unless `force` is set.
+pub fn write_stub(out: &Path, source: &str, force: bool) -> Result<()> {
+    if out.exists() && !force {
+        eyre::bail!(
+            "{} already exists; pass --force to overwrite it",
+            out.display()
+        );
+    }
+    if let Some(parent) = out.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(out, source).wrap_err_with(|| format!("failed to write {}", out.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::{RefVerb, Reqs};
+
+    fn rule() -> ScaffoldRule {
+        ScaffoldRule {
+            prefix: "r".to_string(),
+            id: parse_rule_id("channel.reset-behavior+2").unwrap(),
+            text: unquote(
+                "> Resetting a channel MUST drop queued messages.\n>\n> See `r[channel.open]`.",
+            ),
+        }
+    }
+
+    #[test]
+    fn unquotes_blockquote_rules() {
+        assert_eq!(
+            rule().text,
+            "Resetting a channel MUST drop queued messages.\n\nSee `r[channel.open]`."
+        );
+    }
+
+    #[test]
+    fn names_follow_language_conventions() {
+        assert_eq!(
+            snake_case("channel.reset-behavior"),
+            "channel_reset_behavior"
+        );
+        assert_eq!(camel_case("channel.reset-behavior"), "channelResetBehavior");
+    }
+
+    // r[verify cli.scaffold]
+    #[test]
+    fn stubs_carry_impl_and_verify_annotations() {
+        let rule = rule();
+        for (lang, ext) in [
+            (Lang::Rust, "rs"),
+            (Lang::Python, "py"),
+            (Lang::TypeScript, "ts"),
+        ] {
+            let source = render(&rule, lang);
+            assert!(
+                source.contains("Resetting a channel MUST drop queued messages."),
+                "{source}"
+            );
+            let path = std::path::PathBuf::from(format!("stub.{ext}"));
+            let reqs = Reqs::extract_from_content(&path, &source);
+            let refs: Vec<_> = reqs
+                .references
+                .iter()
+                .map(|r| (r.verb.clone(), r.req_id.to_string()))
+                .collect();
+            assert_eq!(
+                refs,
+                [
+                    (RefVerb::Impl, "channel.reset-behavior+2".to_string()),
+                    (RefVerb::Verify, "channel.reset-behavior+2".to_string()),
+                ],
+                "{lang:?}:\n{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_to_overwrite_without_force() {
+        let temp = tempfile::tempdir().unwrap();
+        let out = temp.path().join("src/channel/reset.rs");
+        write_stub(&out, "first", false).unwrap();
+        assert!(write_stub(&out, "second", false).is_err());
+        write_stub(&out, "second", true).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "second");
+    }
+}
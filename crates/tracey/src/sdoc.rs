@@ -87,6 +87,11 @@ pub async fn extract_rules_from_sdoc(
             column,
             section: None,
             section_title,
+            section_path: view.section_path.iter().map(|s| s.to_string()).collect(),
+            examples: Vec::new(),
+            owner: None,
+            fanout: None,
+            params: Default::default(),
         });
     }
     Ok(rules)
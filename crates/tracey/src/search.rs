@@ -37,6 +37,8 @@ pub struct SearchResult {
     pub highlighted: String,
     /// Relevance score
     pub score: f32,
+    /// For Rule: the heading chain it sits under (`Transport > Flow Control`)
+    pub section: Option<String>,
 }
 
 /// A rule to be indexed
@@ -45,6 +47,8 @@ pub struct RuleEntry {
     pub id: String,
     /// Raw markdown source (without r[...] marker)
     pub raw: String,
+    /// Heading chain the rule sits under
+    pub section: Option<String>,
 }
 
 /// Search index abstraction
@@ -130,6 +134,8 @@ mod tantivy_impl {
             let content_field = schema_builder.add_text_field("content", text_options);
             // "rule_id" field: searchable rule ID with dot-separated parts (not stored)
             let rule_id_field = schema_builder.add_text_field("rule_id", rule_id_options);
+            // "section" field: heading chain of a rule (stored only)
+            let section_field = schema_builder.add_text_field("section", STORED);
             let schema = schema_builder.build();
 
             // Create index in RAM (small enough for most projects)
@@ -177,13 +183,17 @@ mod tantivy_impl {
             // Index rules - use raw markdown directly (rule_id_field handles ID search)
             // r[impl dashboard.search.render-requirements]
             for rule in rules {
-                index_writer.add_document(doc!(
+                let mut document = doc!(
                     kind_field => "rule",
                     id_field => rule.id.clone(),
                     line_field => 0u64,
                     content_field => rule.raw.clone(),
                     rule_id_field => rule.id.clone(),
-                ))?;
+                );
+                if let Some(section) = &rule.section {
+                    document.add_text(section_field, section);
+                }
+                index_writer.add_document(document)?;
             }
 
             index_writer.commit()?;
@@ -243,6 +253,7 @@ mod tantivy_impl {
             let id_field = self.schema.get_field("id").unwrap();
             let line_field = self.schema.get_field("line").unwrap();
             let content_field = self.schema.get_field("content").unwrap();
+            let section_field = self.schema.get_field("section").unwrap();
 
             let mut results: Vec<SearchResult> = top_docs
                 .into_iter()
@@ -258,6 +269,10 @@ mod tantivy_impl {
                     let id = doc.get_first(id_field)?.as_str()?.to_string();
                     let line = doc.get_first(line_field)?.as_u64()? as usize;
                     let content = doc.get_first(content_field)?.as_str()?.to_string();
+                    let section = doc
+                        .get_first(section_field)
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string);
 
                     // r[impl dashboard.search.render-requirements]
                     // r[impl dashboard.search.requirement-styling]
@@ -280,6 +295,7 @@ mod tantivy_impl {
                         content,
                         highlighted,
                         score,
+                        section,
                     })
                 })
                 .collect();
@@ -351,6 +367,7 @@ struct SimpleEntry {
     id: String,
     line: usize,
     content: String,
+    section: Option<String>,
 }
 
 /// Simple substring search fallback when tantivy is not available
@@ -392,6 +409,7 @@ impl SimpleIndex {
                         id: relative.clone(),
                         line: line_num,
                         content: content_with_context,
+                        section: None,
                     });
                 }
             }
@@ -404,6 +422,7 @@ impl SimpleIndex {
                 id: rule.id.clone(),
                 line: 0,
                 content: rule.raw.clone(),
+                section: rule.section.clone(),
             });
         }
 
@@ -434,6 +453,7 @@ impl SearchIndex for SimpleIndex {
                     content: e.content.clone(),
                     highlighted,
                     score: 1.0,
+                    section: e.section.clone(),
                 }
             })
             .collect();
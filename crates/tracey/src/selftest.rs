@@ -0,0 +1,487 @@
+//! `tracey selftest` implementation.
+//!
+//! Runs the reference lexer, the code-unit extractor, and the markdown
+//! processor over a corpus of pathological inputs and reports any that panic
+//! or exceed a time budget. The corpus is a fixed set of hand-written cases
+//! (embedded nulls, enormous lines, mixed encodings and line endings, deeply
+//! nested brackets, unterminated constructs) plus randomly generated inputs
+//! from a seeded generator, so a failure can be reproduced with `--seed`.
+
+use futures_util::FutureExt;
+use std::fmt::Write as _;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Options for a selftest run.
+#[derive(Debug, Clone)]
+pub struct SelftestOptions {
+    /// Seed for the random input generator.
+    pub seed: u64,
+    /// Number of random inputs to generate.
+    pub iterations: usize,
+    /// Time budget per input and processor.
+    pub budget: Duration,
+    /// Size in bytes of the "enormous" inputs.
+    pub large_bytes: usize,
+    /// Print each input before running it, so a crash that aborts the
+    /// process (e.g. a stack overflow) can be attributed.
+    pub verbose: bool,
+}
+
+impl Default for SelftestOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0x7ac3_5eed,
+            iterations: 200,
+            budget: Duration::from_secs(5),
+            large_bytes: 256 * 1024,
+            verbose: false,
+        }
+    }
+}
+
+/// What happened when a processor ran over an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    /// The processor panicked with this message.
+    Panicked(String),
+    /// The processor finished, but over budget.
+    Slow,
+}
+
+/// One processor run over one input.
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    /// Name of the input.
+    pub input: String,
+    /// Processor that was run (`lexer`, `code-units`, `markdown`, `markers`).
+    pub processor: &'static str,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Results of a selftest run.
+#[derive(Debug)]
+pub struct SelftestReport {
+    pub seed: u64,
+    pub budget: Duration,
+    pub results: Vec<CaseResult>,
+}
+
+impl SelftestReport {
+    pub fn failures(&self) -> impl Iterator<Item = &CaseResult> {
+        self.results.iter().filter(|r| r.outcome != Outcome::Ok)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for failure in self.failures() {
+            let what = match &failure.outcome {
+                Outcome::Ok => continue,
+                Outcome::Panicked(message) => format!("PANIC: {message}"),
+                Outcome::Slow => {
+                    format!("SLOW: {:.2?} (budget {:.2?})", failure.elapsed, self.budget)
+                }
+            };
+            let _ = writeln!(out, "{} [{}] {what}", failure.input, failure.processor);
+        }
+        let panics = self
+            .results
+            .iter()
+            .filter(|r| matches!(r.outcome, Outcome::Panicked(_)))
+            .count();
+        let slow = self
+            .results
+            .iter()
+            .filter(|r| r.outcome == Outcome::Slow)
+            .count();
+        let _ = writeln!(
+            out,
+            "{} run(s), {panics} panic(s), {slow} over budget (seed {:#x}).",
+            self.results.len(),
+            self.seed
+        );
+        out
+    }
+}
+
+/// A named input and the file name it is processed under.
+struct Input {
+    name: String,
+    file: &'static str,
+    content: String,
+}
+
+/// Small deterministic generator; good enough for picking fragments and
+/// keeps runs reproducible without a dependency.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Fragments the random generator stitches together. Chosen to hit marker
+/// parsing, comment and string boundaries, and multi-byte characters.
+const FRAGMENTS: &[&str] = &[
+    "r[",
+    "]",
+    "[",
+    "impl ",
+    "verify ",
+    "depends ",
+    "related ",
+    "a.b",
+    "auth.login+2",
+    "+",
+    "+99999999999",
+    " ",
+    "//",
+    "///",
+    "//!",
+    "/*",
+    "*/",
+    "#",
+    "--",
+    "<!--",
+    "-->",
+    "\"",
+    "'",
+    "`",
+    "```",
+    "\n",
+    "\r\n",
+    "\r",
+    "\t",
+    "\0",
+    "é",
+    "\u{200f}",
+    "\u{feff}",
+    "\u{fffd}",
+    "e\u{301}",
+    "𝕽",
+    "{",
+    "}",
+    "(",
+    ")",
+    ">",
+    "fn f() {",
+    "def f():",
+    "function f() {",
+    "- ",
+    "1. ",
+    "| a | b |",
+];
+
+const SOURCE_FILES: &[&str] = &["case.rs", "case.py", "case.ts"];
+
+/// File names random inputs rotate through, so every processor sees them.
+const RANDOM_FILES: &[&str] = &["case.rs", "case.py", "case.ts", "case.md"];
+
+fn corpus(options: &SelftestOptions) -> Vec<Input> {
+    let large = options.large_bytes.max(1);
+    let repeat = |s: &str, bytes: usize| s.repeat(bytes / s.len().max(1) + 1);
+
+    let sources: Vec<(&str, String)> = vec![
+        ("empty", String::new()),
+        (
+            "nul-bytes",
+            "// r[impl a\0.b]\n// r[\0verify a.b]\0\n\0".repeat(64),
+        ),
+        (
+            "enormous-line",
+            format!("// {}\n", repeat("r[impl a.b] ", large)),
+        ),
+        (
+            "enormous-rule-id",
+            format!("// r[impl {}]\n", repeat("a.", large)),
+        ),
+        (
+            "nested-brackets",
+            format!(
+                "// {}impl a.b{}\n",
+                "r[".repeat(large / 4),
+                "]".repeat(large / 4)
+            ),
+        ),
+        ("unterminated-markers", repeat("// r[impl \n", large)),
+        (
+            "unterminated-block-comment",
+            format!("/* r[impl a.b]\n{}", repeat("x", large)),
+        ),
+        (
+            "unterminated-string",
+            format!("let s = \"{}\n// r[impl a.b]\n", repeat("\\\"", large)),
+        ),
+        (
+            "mixed-line-endings",
+            repeat(
+                "// r[impl a.b]\r\n/// r[verify a.b]\r//! r[impl c.d]\n",
+                large,
+            ),
+        ),
+        ("mixed-encodings", {
+            // Latin-1 bytes decoded as UTF-8, a BOM, and bidi/combining marks.
+            let latin1 = String::from_utf8_lossy(b"// r[impl caf\xe9.rule]\n").into_owned();
+            format!("\u{feff}{latin1}// r[impl \u{202e}a.b\u{202c}]\n// r[impl e\u{301}.b]\n")
+                .repeat(256)
+        }),
+        (
+            "many-markers",
+            repeat(
+                "// r[impl a.b] r[verify c.d+3] r[depends e.f]\nfn f() {}\n",
+                large,
+            ),
+        ),
+        (
+            "deep-nesting",
+            format!(
+                "{}\n// r[impl a.b]\n{}",
+                "{".repeat(large / 8),
+                "}".repeat(large / 8)
+            ),
+        ),
+    ];
+
+    let markdown: Vec<(&str, String)> = vec![
+        ("md-empty", String::new()),
+        ("md-nul-bytes", "r[a\0.b]\nText MUST work.\0\n\n".repeat(64)),
+        (
+            "md-enormous-line",
+            format!("r[a.b]\n{}\n", repeat("MUST ", large)),
+        ),
+        (
+            "md-deep-blockquote",
+            format!("{} r[a.b]\nText MUST work.\n", ">".repeat(large / 64)),
+        ),
+        ("md-deep-list", {
+            // Indentation grows with depth, so keep the total near `large`.
+            let depth = (large as f64).sqrt() as usize / 2;
+            (0..depth)
+                .map(|i| format!("{}- r[a.b{i}]\n", "  ".repeat(i)))
+                .collect()
+        }),
+        (
+            "md-unterminated-fence",
+            format!("```\nr[a.b]\n{}", repeat("text\n", large)),
+        ),
+        (
+            "md-nested-brackets",
+            format!(
+                "{}a.b{}\nText MUST work.\n",
+                "r[".repeat(large / 64),
+                "]".repeat(large / 64)
+            ),
+        ),
+        (
+            "md-duplicate-rules",
+            repeat("r[a.b]\nText MUST work.\n\n", large / 8),
+        ),
+        (
+            "md-mixed-line-endings",
+            repeat("r[a.b]\r\nText MUST work.\r\rr[c.d]\nText.\n", large / 8),
+        ),
+    ];
+
+    let mut rng = XorShift(options.seed | 1);
+    let mut random = Vec::new();
+    for i in 0..options.iterations {
+        let len = 1 + rng.below(200);
+        let content: String = (0..len)
+            .map(|_| FRAGMENTS[rng.below(FRAGMENTS.len())])
+            .collect();
+        random.push((i, content));
+    }
+
+    let mut inputs = Vec::new();
+    for (name, content) in sources {
+        for file in SOURCE_FILES {
+            inputs.push(Input {
+                name: format!("{name} ({file})"),
+                file,
+                content: content.clone(),
+            });
+        }
+    }
+    for (name, content) in markdown {
+        inputs.push(Input {
+            name: name.to_string(),
+            file: "case.md",
+            content,
+        });
+    }
+    for (i, content) in random {
+        inputs.push(Input {
+            name: format!("random-{i}"),
+            file: RANDOM_FILES[i % RANDOM_FILES.len()],
+            content,
+        });
+    }
+    inputs
+}
+
+/// Run the self-check.
+// r[impl cli.selftest]
+pub async fn run(options: &SelftestOptions) -> SelftestReport {
+    // Capture panic messages instead of printing them mid-report.
+    let last_panic = Arc::new(Mutex::new(None::<String>));
+    let previous_hook = std::panic::take_hook();
+    {
+        let last_panic = last_panic.clone();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|l| format!(" at {}:{}", l.file(), l.line()))
+                .unwrap_or_default();
+            let payload = info
+                .payload()
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| info.payload().downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "<non-string panic>".to_string());
+            *last_panic.lock().unwrap() = Some(format!("{payload}{location}"));
+        }));
+    }
+
+    let mut results = Vec::new();
+    for input in corpus(options) {
+        if options.verbose {
+            eprintln!("{} ({} bytes)", input.name, input.content.len());
+        }
+        let path = Path::new(input.file);
+        let content = input.content.as_str();
+        let mut record = |processor: &'static str, caught: Result<(), ()>, elapsed: Duration| {
+            let outcome = match caught {
+                Err(()) => Outcome::Panicked(
+                    last_panic
+                        .lock()
+                        .unwrap()
+                        .take()
+                        .unwrap_or_else(|| "panicked".to_string()),
+                ),
+                Ok(()) if elapsed > options.budget => Outcome::Slow,
+                Ok(()) => Outcome::Ok,
+            };
+            results.push(CaseResult {
+                input: input.name.clone(),
+                processor,
+                outcome,
+                elapsed,
+            });
+        };
+
+        if input.file.ends_with(".md") {
+            let start = Instant::now();
+            let caught = AssertUnwindSafe(marq::render(content, &marq::RenderOptions::default()))
+                .catch_unwind()
+                .await
+                .map(drop)
+                .map_err(drop);
+            record("markdown", caught, start.elapsed());
+
+            let start = Instant::now();
+            let caught = std::panic::catch_unwind(|| {
+                tracey_core::markdown::edit::find_rule_markers(content);
+            })
+            .map_err(drop);
+            record("markers", caught, start.elapsed());
+        } else {
+            let start = Instant::now();
+            let caught = std::panic::catch_unwind(|| {
+                tracey_core::Reqs::extract_from_content(path, content);
+            })
+            .map_err(drop);
+            record("lexer", caught, start.elapsed());
+
+            let start = Instant::now();
+            let caught = std::panic::catch_unwind(|| {
+                tracey_core::code_units::extract(path, content);
+            })
+            .map_err(drop);
+            record("code-units", caught, start.elapsed());
+        }
+    }
+
+    std::panic::set_hook(previous_hook);
+
+    SelftestReport {
+        seed: options.seed,
+        budget: options.budget,
+        results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.selftest]
+    #[tokio::test]
+    async fn small_corpus_passes() {
+        let options = SelftestOptions {
+            iterations: 40,
+            budget: Duration::from_secs(60),
+            large_bytes: 2048,
+            ..Default::default()
+        };
+        let report = run(&options).await;
+        assert!(report.passed(), "{}", report.to_text());
+        assert!(report.results.iter().any(|r| r.processor == "markdown"));
+        assert!(report.results.iter().any(|r| r.processor == "code-units"));
+    }
+
+    #[test]
+    fn random_inputs_are_reproducible() {
+        let options = SelftestOptions {
+            iterations: 5,
+            large_bytes: 16,
+            ..Default::default()
+        };
+        let a: Vec<_> = corpus(&options).into_iter().map(|i| i.content).collect();
+        let b: Vec<_> = corpus(&options).into_iter().map(|i| i.content).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn report_lists_failures() {
+        let report = SelftestReport {
+            seed: 1,
+            budget: Duration::from_secs(1),
+            results: vec![
+                CaseResult {
+                    input: "nul-bytes (case.rs)".to_string(),
+                    processor: "lexer",
+                    outcome: Outcome::Panicked("boom".to_string()),
+                    elapsed: Duration::ZERO,
+                },
+                CaseResult {
+                    input: "empty (case.rs)".to_string(),
+                    processor: "lexer",
+                    outcome: Outcome::Ok,
+                    elapsed: Duration::ZERO,
+                },
+            ],
+        };
+        assert!(!report.passed());
+        let text = report.to_text();
+        assert!(text.starts_with("nul-bytes (case.rs) [lexer] PANIC: boom\n"));
+        assert!(text.contains("2 run(s), 1 panic(s), 0 over budget"));
+    }
+}
@@ -8,8 +8,8 @@
 //! The actual data building happens in `serve.rs`. This module wraps that
 //! data and provides query methods + formatting.
 
-use std::collections::BTreeMap;
-use tracey_core::RuleId;
+use std::collections::{BTreeMap, HashMap};
+use tracey_core::{RefVerb, RuleId};
 
 use crate::data::{ApiCodeRef, ApiFileEntry, ApiRule, DashboardData, ImplKey};
 
@@ -34,12 +34,20 @@ pub struct CoverageChange {
 #[derive(Debug, Clone, Default)]
 pub struct CoverageStats {
     pub total_rules: usize,
-    /// Rules with at least one exact implementation reference (not stale).
+    /// Rules with at least one exact implementation reference (not stale),
+    /// not counting partially implemented ones.
     pub impl_covered: usize,
+    /// Rules implemented only by `coverage=partial` references (not stale).
+    /// Mutually exclusive with impl_covered.
+    pub partial_covered: usize,
     /// Rules where any reference is stale. Mutually exclusive with impl_covered.
     pub stale_covered: usize,
     pub verify_covered: usize,
     pub fully_covered: usize, // both impl and verify
+    /// Rules with at least one reference whose verb has the `docs` role.
+    pub docs_covered: usize,
+    /// Rules with a proof a verification tool checked.
+    pub proven_covered: usize,
     pub impl_percent: f64,
     pub verify_percent: f64,
 }
@@ -51,22 +59,37 @@ impl CoverageStats {
         let stale_covered = rules.iter().filter(|r| r.is_stale).count();
         let impl_covered = rules
             .iter()
-            .filter(|r| !r.is_stale && !r.impl_refs.is_empty())
+            .filter(|r| !r.is_stale && r.is_implemented() && !r.is_partial())
             .count();
+        // r[impl coverage.partial]
+        let partial_covered = rules
+            .iter()
+            .filter(|r| !r.is_stale && r.is_partial())
+            .count();
+        let impl_weight: f64 = rules
+            .iter()
+            .filter(|r| !r.is_stale)
+            .map(ApiRule::impl_weight)
+            .sum();
         let verify_covered = rules.iter().filter(|r| !r.verify_refs.is_empty()).count();
         let fully_covered = rules
             .iter()
-            .filter(|r| !r.is_stale && !r.impl_refs.is_empty() && !r.verify_refs.is_empty())
+            .filter(|r| !r.is_stale && r.is_implemented() && !r.verify_refs.is_empty())
             .count();
+        let docs_covered = rules.iter().filter(|r| !r.docs_refs.is_empty()).count();
+        let proven_covered = rules.iter().filter(|r| r.formally_verified).count();
 
         Self {
             total_rules: total,
             impl_covered,
+            partial_covered,
             stale_covered,
             verify_covered,
             fully_covered,
+            docs_covered,
+            proven_covered,
             impl_percent: if total > 0 {
-                (impl_covered as f64 / total as f64) * 100.0
+                (impl_weight / total as f64) * 100.0
             } else {
                 0.0
             },
@@ -132,8 +155,8 @@ impl Delta {
             for new_rule in &new_forward.rules {
                 let old_rule = old_rules.get(&new_rule.id);
 
-                let was_impl_covered = old_rule.is_some_and(|r| !r.impl_refs.is_empty());
-                let is_impl_covered = !new_rule.impl_refs.is_empty();
+                let was_impl_covered = old_rule.is_some_and(|r| r.is_implemented());
+                let is_impl_covered = new_rule.is_implemented();
 
                 let was_verify_covered = old_rule.is_some_and(|r| !r.verify_refs.is_empty());
                 let is_verify_covered = !new_rule.verify_refs.is_empty();
@@ -253,16 +276,17 @@ impl<'a> QueryEngine<'a> {
         let stats = CoverageStats::from_rules(&forward.rules);
 
         // Filter uncovered rules, optionally by ID prefix (case-insensitive)
-        let uncovered_rules: Vec<&ApiRule> = forward
+        // r[impl coverage.waivers.report]
+        let (waived, uncovered_rules): (Vec<&ApiRule>, Vec<&ApiRule>) = forward
             .rules
             .iter()
-            .filter(|r| r.impl_refs.is_empty())
+            .filter(|r| !r.is_implemented())
             .filter(|r| {
                 prefix_filter
                     .map(|p| r.id.base.to_lowercase().starts_with(&p.to_lowercase()))
                     .unwrap_or(true)
             })
-            .collect();
+            .partition(|r| r.is_waived());
 
         // Build section mapping from outline
         let by_section = group_rules_by_section(&uncovered_rules);
@@ -273,6 +297,7 @@ impl<'a> QueryEngine<'a> {
             stats,
             by_section,
             total_uncovered: uncovered_rules.len(),
+            waived: waived.into_iter().filter_map(WaivedRule::of).collect(),
             prefix_filter: prefix_filter.map(|s| s.to_string()),
         })
     }
@@ -291,16 +316,16 @@ impl<'a> QueryEngine<'a> {
         let stats = CoverageStats::from_rules(&forward.rules);
 
         // Filter untested rules, optionally by ID prefix (case-insensitive)
-        let untested_rules: Vec<&ApiRule> = forward
+        let (waived, untested_rules): (Vec<&ApiRule>, Vec<&ApiRule>) = forward
             .rules
             .iter()
-            .filter(|r| !r.impl_refs.is_empty() && r.verify_refs.is_empty())
+            .filter(|r| r.is_implemented() && r.verify_refs.is_empty())
             .filter(|r| {
                 prefix_filter
                     .map(|p| r.id.base.to_lowercase().starts_with(&p.to_lowercase()))
                     .unwrap_or(true)
             })
-            .collect();
+            .partition(|r| r.is_waived());
 
         let by_section = group_rules_by_section(&untested_rules);
 
@@ -310,6 +335,7 @@ impl<'a> QueryEngine<'a> {
             stats,
             by_section,
             total_untested: untested_rules.len(),
+            waived: waived.into_iter().filter_map(WaivedRule::of).collect(),
             prefix_filter: prefix_filter.map(|s| s.to_string()),
         })
     }
@@ -481,10 +507,13 @@ impl<'a> QueryEngine<'a> {
                         html: rule.html.clone(),
                         source_file: rule.source_file.clone(),
                         source_line: rule.source_line,
+                        section_path: rule.section_path.clone(),
                         status: rule.status.clone(),
                         level: rule.level.clone(),
+                        owner: rule.owner.clone(),
                         is_stale: rule.is_stale,
                         coverage: Vec::new(), // Will be set at the end
+                        related: rule.related.clone(),
                     });
                 }
 
@@ -505,12 +534,310 @@ impl<'a> QueryEngine<'a> {
 
         result
     }
+
+    /// Compare implementations of a spec rule by rule
+    // r[impl daemon.roam.compare]
+    pub fn compare(&self, spec: &str, impls: &[String]) -> Option<CompareResult> {
+        let forwards = impls
+            .iter()
+            .map(|impl_name| {
+                self.data
+                    .forward_by_impl
+                    .get(&(spec.to_string(), impl_name.clone()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let side = |rule: Option<&ApiRule>| match rule {
+            Some(rule) => CompareSide {
+                impl_count: rule.impl_refs.len(),
+                verify_count: rule.verify_refs.len(),
+                is_stale: rule.is_stale,
+            },
+            None => CompareSide::default(),
+        };
+
+        let rows = forwards
+            .first()?
+            .rules
+            .iter()
+            .map(|rule| CompareRow {
+                id: rule.id.clone(),
+                coverage: forwards
+                    .iter()
+                    .map(|forward| side(forward.rules.iter().find(|r| r.id == rule.id)))
+                    .collect(),
+            })
+            .collect();
+
+        Some(CompareResult {
+            spec: spec.to_string(),
+            impls: impls.to_vec(),
+            rows,
+        })
+    }
+
+    /// Every annotation in files under `path`, grouped by rule and verb.
+    ///
+    /// `path` is relative to the project root and matches whole path
+    /// components, so `src/net` covers `src/net/tcp.rs` but not
+    /// `src/network.rs`. An empty path or `.` covers the whole project.
+    // r[impl daemon.roam.refs-in]
+    pub fn refs_in(&self, path: &str) -> RefsInResult {
+        let path = normalize_query_path(path);
+        let root = std::path::Path::new(&self.data.config.project_root);
+
+        let mut files = 0;
+        // Keyed by (prefix, rule, verb) so iteration is already grouped.
+        let mut by_verb: BTreeMap<(String, RuleId, RefVerb), Vec<ApiCodeRef>> = BTreeMap::new();
+        for (file, reqs) in &self.data.source_reqs_by_file {
+            let relative = file.strip_prefix(root).unwrap_or(file);
+            if !relative.starts_with(&path) || reqs.references.is_empty() {
+                continue;
+            }
+            files += 1;
+            let display = relative.display().to_string();
+            for r in &reqs.references {
+                by_verb
+                    .entry((r.prefix.clone(), r.req_id.clone(), r.verb.clone()))
+                    .or_default()
+                    .push(ApiCodeRef {
+                        file: display.clone(),
+                        line: r.line,
+                        end_line: r.end_line,
+                        note: r.note.clone(),
+                        partial: r.partial,
+                    });
+            }
+        }
+
+        let mut rules: Vec<RefsInRuleResult> = Vec::new();
+        for ((prefix, id, verb), refs) in by_verb {
+            let verb = (verb, refs);
+            match rules.last_mut() {
+                Some(last) if last.prefix == prefix && last.id == id => last.verbs.push(verb),
+                _ => rules.push(RefsInRuleResult {
+                    prefix,
+                    id,
+                    verbs: vec![verb],
+                }),
+            }
+        }
+
+        RefsInResult { path, files, rules }
+    }
+
+    /// Forward coverage of one implementation, rolled up by the directory
+    /// or crate containing each source file.
+    ///
+    /// Every file of the implementation lands in a group, so directories
+    /// and crates without a single reference still show up.
+    // r[impl daemon.roam.breakdown]
+    pub fn breakdown(
+        &self,
+        spec: &str,
+        impl_name: &str,
+        by: BreakdownBy,
+    ) -> Option<Vec<BreakdownGroupResult>> {
+        let key = (spec.to_string(), impl_name.to_string());
+        let forward = self.data.forward_by_impl.get(&key)?;
+        let reverse = self.data.reverse_by_impl.get(&key)?;
+        let root = std::path::Path::new(&self.data.config.project_root);
+
+        let mut crate_dirs: HashMap<String, String> = HashMap::new();
+        let mut group_of = |file: &str| -> String {
+            let dir = match std::path::Path::new(file).parent() {
+                Some(parent) => normalize_query_path(&parent.to_string_lossy()),
+                None => String::new(),
+            };
+            let group = match by {
+                BreakdownBy::Dir { depth: None } => dir,
+                BreakdownBy::Dir { depth: Some(depth) } => dir
+                    .split('/')
+                    .filter(|part| !part.is_empty())
+                    .take(depth)
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                BreakdownBy::Crate => crate_dirs
+                    .entry(dir)
+                    .or_insert_with_key(|dir| nearest_crate(root, dir))
+                    .clone(),
+            };
+            if group.is_empty() {
+                ".".to_string()
+            } else {
+                group
+            }
+        };
+
+        let mut groups: BTreeMap<String, BreakdownGroupResult> = BTreeMap::new();
+        for file in &reverse.files {
+            let group = groups
+                .entry(group_of(&file.path))
+                .or_insert_with_key(|path| BreakdownGroupResult::new(path));
+            group.files += 1;
+            group.total_units += file.total_units;
+            group.covered_units += file.covered_units;
+        }
+
+        for rule in &forward.rules {
+            for (refs, verify) in [(&rule.impl_refs, false), (&rule.verify_refs, true)] {
+                for r in refs {
+                    let group = groups
+                        .entry(group_of(&r.file))
+                        .or_insert_with_key(|path| BreakdownGroupResult::new(path));
+                    let (count, ids) = if verify {
+                        (&mut group.verify_count, &mut group.verified)
+                    } else {
+                        (&mut group.impl_count, &mut group.implemented)
+                    };
+                    *count += 1;
+                    // Rules are visited in ID order, so a repeat is always last.
+                    if ids.last() != Some(&rule.id) {
+                        ids.push(rule.id.clone());
+                    }
+                }
+            }
+        }
+
+        Some(groups.into_values().collect())
+    }
+}
+
+/// Closest directory at or above `dir` (relative to `root`) that holds a
+/// `Cargo.toml`, or `` when none does below the project root.
+fn nearest_crate(root: &std::path::Path, dir: &str) -> String {
+    let mut dir = std::path::Path::new(dir);
+    loop {
+        if root.join(dir).join("Cargo.toml").is_file() {
+            return dir.to_string_lossy().replace('\\', "/");
+        }
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => return String::new(),
+        }
+    }
+}
+
+/// `./src/net/` -> `src/net`; `.` -> ``
+fn normalize_query_path(path: &str) -> String {
+    path.split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 // ============================================================================
 // Query Results
 // ============================================================================
 
+/// How [`QueryEngine::breakdown`] groups files
+#[derive(Debug, Clone, Copy)]
+pub enum BreakdownBy {
+    /// By parent directory, cut to the first `depth` components if given
+    Dir { depth: Option<usize> },
+    /// By the nearest enclosing directory with a `Cargo.toml`
+    Crate,
+}
+
+/// Coverage of one directory or crate, from [`QueryEngine::breakdown`]
+#[derive(Debug, Clone)]
+pub struct BreakdownGroupResult {
+    pub path: String,
+    pub files: usize,
+    pub total_units: usize,
+    pub covered_units: usize,
+    pub impl_count: usize,
+    pub verify_count: usize,
+    /// Rules implemented in the group, sorted by ID
+    pub implemented: Vec<RuleId>,
+    /// Rules verified in the group, sorted by ID
+    pub verified: Vec<RuleId>,
+}
+
+impl BreakdownGroupResult {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            files: 0,
+            total_units: 0,
+            covered_units: 0,
+            impl_count: 0,
+            verify_count: 0,
+            implemented: Vec::new(),
+            verified: Vec::new(),
+        }
+    }
+}
+
+/// Annotations under a path, from [`QueryEngine::refs_in`]
+#[derive(Debug, Clone)]
+pub struct RefsInResult {
+    /// Normalized path that was searched
+    pub path: String,
+    /// Files under the path that contain annotations
+    pub files: usize,
+    pub rules: Vec<RefsInRuleResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RefsInRuleResult {
+    pub prefix: String,
+    pub id: RuleId,
+    /// References per verb, in canonical verb order
+    pub verbs: Vec<(RefVerb, Vec<ApiCodeRef>)>,
+}
+
+impl RefsInResult {
+    pub fn total(&self) -> usize {
+        self.rules
+            .iter()
+            .flat_map(|r| &r.verbs)
+            .map(|(_, refs)| refs.len())
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompareResult {
+    pub spec: String,
+    pub impls: Vec<String>,
+    pub rows: Vec<CompareRow>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompareRow {
+    pub id: RuleId,
+    /// Coverage in each implementation, in the order of `impls`
+    pub coverage: Vec<CompareSide>,
+}
+
+/// Coverage of one rule by one implementation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompareSide {
+    pub impl_count: usize,
+    pub verify_count: usize,
+    pub is_stale: bool,
+}
+
+impl CompareSide {
+    /// Implemented and not pointing at an older rule version
+    pub fn is_covered(&self) -> bool {
+        self.impl_count > 0 && !self.is_stale
+    }
+}
+
+impl CompareResult {
+    /// Rules another implementation covers but the one at `index` doesn't
+    pub fn missing(&self, index: usize) -> impl Iterator<Item = &RuleId> {
+        self.rows
+            .iter()
+            .filter(move |r| {
+                !r.coverage[index].is_covered() && r.coverage.iter().any(CompareSide::is_covered)
+            })
+            .map(|r| &r.id)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UncoveredResult {
     pub spec: String,
@@ -518,6 +845,8 @@ pub struct UncoveredResult {
     pub stats: CoverageStats,
     pub by_section: BTreeMap<String, Vec<RuleRef>>,
     pub total_uncovered: usize,
+    /// Uncovered rules left out of `by_section` by an active waiver
+    pub waived: Vec<WaivedRule>,
     pub prefix_filter: Option<String>,
 }
 
@@ -528,6 +857,8 @@ pub struct UntestedResult {
     pub stats: CoverageStats,
     pub by_section: BTreeMap<String, Vec<RuleRef>>,
     pub total_untested: usize,
+    /// Untested rules left out of `by_section` by an active waiver
+    pub waived: Vec<WaivedRule>,
     pub prefix_filter: Option<String>,
 }
 
@@ -535,6 +866,35 @@ pub struct UntestedResult {
 pub struct RuleRef {
     pub id: RuleId,
     pub impl_refs: Vec<ApiCodeRef>,
+    /// Fan-out shortfall (`3 of 17 files annotated`) or expired waiver
+    pub note: Option<String>,
+}
+
+/// A rule whose gap is accepted by a waiver
+#[derive(Debug, Clone)]
+pub struct WaivedRule {
+    pub id: RuleId,
+    pub reason: String,
+    pub expires: Option<String>,
+}
+
+impl WaivedRule {
+    fn of(rule: &ApiRule) -> Option<Self> {
+        let waiver = rule.waiver.as_ref()?;
+        Some(Self {
+            id: rule.id.clone(),
+            reason: waiver.reason.clone(),
+            expires: waiver.expires.clone(),
+        })
+    }
+
+    /// `reason (until 2026-12-31)`
+    pub fn describe(&self) -> String {
+        match &self.expires {
+            Some(expires) => format!("{} (until {expires})", self.reason),
+            None => self.reason.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -615,12 +975,18 @@ pub struct RuleInfo {
     pub html: String,
     pub source_file: Option<String>,
     pub source_line: Option<usize>,
+    /// Titles of the headings enclosing the rule, outermost first
+    pub section_path: Vec<String>,
     pub status: Option<String>,
     pub level: Option<String>,
+    /// Team or person owning the rule
+    pub owner: Option<String>,
     /// True if any reference to this rule is stale
     pub is_stale: bool,
     /// Coverage across all implementations
     pub coverage: Vec<ImplCoverage>,
+    /// Rules mentioned in this rule's prose
+    pub related: Vec<RuleId>,
 }
 
 impl RuleInfo {
@@ -649,14 +1015,26 @@ fn group_rules_by_section(rules: &[&ApiRule]) -> BTreeMap<String, Vec<RuleRef>>
 
     for rule in rules {
         let section = rule
-            .section_title
-            .as_ref()
-            .cloned()
+            .section_label()
             .unwrap_or_else(|| "Uncategorized".to_string());
 
         result.entry(section).or_default().push(RuleRef {
             id: rule.id.clone(),
             impl_refs: rule.impl_refs.clone(),
+            note: rule
+                .fanout
+                .as_ref()
+                .filter(|f| !f.is_satisfied())
+                .map(|f| f.summary())
+                .or_else(|| {
+                    let expires = rule
+                        .waiver
+                        .as_ref()
+                        .filter(|w| w.expired)?
+                        .expires
+                        .as_ref()?;
+                    Some(format!("waiver expired {expires}"))
+                }),
         });
     }
 
@@ -1012,6 +1390,9 @@ impl RuleInfo {
         {
             out.push_str(&format!("Defined in: {}:{}\n", file, line));
         }
+        if !self.section_path.is_empty() {
+            out.push_str(&format!("Section: {}\n", self.section_path.join(" > ")));
+        }
 
         if let Some(ref status) = self.status {
             out.push_str(&format!("Status: {}\n", status));
@@ -1019,6 +1400,10 @@ impl RuleInfo {
         if let Some(ref level) = self.level {
             out.push_str(&format!("Level: {}\n", level));
         }
+        if !self.related.is_empty() {
+            let related: Vec<_> = self.related.iter().map(ToString::to_string).collect();
+            out.push_str(&format!("Related: {}\n", related.join(", ")));
+        }
 
         // Show coverage per implementation
         out.push_str("\n## Coverage by Implementation\n\n");
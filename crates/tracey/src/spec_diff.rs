@@ -0,0 +1,731 @@
+//! `tracey spec manifest` and `tracey spec diff` implementation.
+//!
+//! A manifest is a JSON snapshot of every configured spec's rules: ID,
+//! version, level, status and text. Diffing two of them, a manifest against
+//! the working tree, or a git revision against the working tree, gives spec
+//! maintainers a change report for release notes and for telling
+//! implementers what moved. Like `tracey bump`,
+//! this works directly on files and git and does not require the daemon.
+
+use eyre::Result;
+use facet::Facet;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::bump::{git_capture, parse_spec_rules};
+use crate::config::Config;
+
+/// Rules of every configured spec at one point in time.
+#[derive(Debug, Clone, Default, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecManifest {
+    pub specs: Vec<ManifestSpec>,
+}
+
+/// Rules of one spec, ordered by ID.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ManifestSpec {
+    pub name: String,
+    /// The spec's configured `source_url`.
+    /// r[impl cli.spec.diff.metadata]
+    #[facet(default)]
+    pub source_url: Option<String>,
+    pub rules: Vec<ManifestRule>,
+}
+
+/// One rule definition.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ManifestRule {
+    /// Base rule ID, without the version suffix.
+    pub id: String,
+    pub version: u32,
+    #[facet(default)]
+    pub level: Option<String>,
+    #[facet(default)]
+    pub status: Option<String>,
+    /// Raw markdown of the rule, without its marker.
+    pub text: String,
+    /// `e[...]` examples written next to the rule.
+    #[facet(default)]
+    pub examples: Vec<ManifestExample>,
+    /// Marker attributes other than the ones marq reads (`owner=`, ...),
+    /// with quoted values unquoted.
+    #[facet(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// An example or counter-example of a rule.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ManifestExample {
+    /// `good` or `bad`.
+    pub kind: String,
+    /// Markdown of the example, without its marker.
+    pub text: String,
+}
+
+impl From<&marq::ReqDefinition> for ManifestRule {
+    fn from(def: &marq::ReqDefinition) -> Self {
+        Self {
+            id: def.id.base.clone(),
+            version: def.id.version,
+            level: def.metadata.level.map(|l| l.as_str().to_string()),
+            status: def.metadata.status.map(|s| s.as_str().to_string()),
+            text: def.raw.trim().to_string(),
+            examples: Vec::new(),
+            attributes: BTreeMap::new(),
+        }
+    }
+}
+
+/// Changes between two manifests, per spec.
+#[derive(Debug, Clone, Default, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecDiff {
+    /// Specs with at least one change, in the new manifest's order, followed
+    /// by specs that only exist in the old one.
+    pub specs: Vec<SpecChanges>,
+}
+
+/// Changes to the rules of one spec. A rule that was both reworded and
+/// bumped appears in both lists.
+#[derive(Debug, Clone, Default, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecChanges {
+    pub name: String,
+    /// The spec's `source_url`, if it changed.
+    #[facet(default)]
+    pub source_url: Option<MetadataChange>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Rules whose text changed beyond whitespace.
+    pub reworded: Vec<String>,
+    pub releveled: Vec<MetadataChange>,
+    /// Rules whose status (draft, stable, deprecated, ...) changed.
+    pub restatused: Vec<MetadataChange>,
+    pub reversioned: Vec<VersionChange>,
+}
+
+/// A metadata value that changed, `None` meaning unset.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct MetadataChange {
+    pub id: String,
+    #[facet(default)]
+    pub from: Option<String>,
+    #[facet(default)]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct VersionChange {
+    pub id: String,
+    pub from: u32,
+    pub to: u32,
+}
+
+impl SpecChanges {
+    fn is_empty(&self) -> bool {
+        self.source_url.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.reworded.is_empty()
+            && self.releveled.is_empty()
+            && self.restatused.is_empty()
+            && self.reversioned.is_empty()
+    }
+}
+
+/// What `tracey spec diff` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Markdown for release notes
+    #[default]
+    Markdown,
+    /// One line per change
+    Text,
+    /// The [`SpecDiff`] as JSON
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" => Ok(Self::Markdown),
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(eyre::eyre!(
+                "Unsupported spec diff format `{other}`; expected markdown, text or json"
+            )),
+        }
+    }
+}
+
+impl SpecDiff {
+    /// Render the diff in `format`.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Markdown => self.to_markdown(),
+            Format::Text => self.to_text(),
+            Format::Json => {
+                let mut out =
+                    facet_json::to_string_pretty(self).expect("JSON serialization failed");
+                out.push('\n');
+                out
+            }
+        }
+    }
+
+    /// Render the diff as plain text, one change per line, for scripts and
+    /// terminals.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if self.specs.is_empty() {
+            let _ = writeln!(out, "No rule changes.");
+            return out;
+        }
+        for spec in &self.specs {
+            let name = &spec.name;
+            if let Some(change) = &spec.source_url {
+                let _ = writeln!(
+                    out,
+                    "{name}: source_url {} -> {}",
+                    unset(&change.from),
+                    unset(&change.to)
+                );
+            }
+            for id in &spec.added {
+                let _ = writeln!(out, "{name}: added {id}");
+            }
+            for id in &spec.removed {
+                let _ = writeln!(out, "{name}: removed {id}");
+            }
+            for id in &spec.reworded {
+                let _ = writeln!(out, "{name}: reworded {id}");
+            }
+            for change in &spec.releveled {
+                let _ = writeln!(
+                    out,
+                    "{name}: releveled {} {} -> {}",
+                    change.id,
+                    unset(&change.from),
+                    unset(&change.to)
+                );
+            }
+            for change in &spec.restatused {
+                let _ = writeln!(
+                    out,
+                    "{name}: restatused {} {} -> {}",
+                    change.id,
+                    unset(&change.from),
+                    unset(&change.to)
+                );
+            }
+            for change in &spec.reversioned {
+                let _ = writeln!(
+                    out,
+                    "{name}: reversioned {} +{} -> +{}",
+                    change.id, change.from, change.to
+                );
+            }
+        }
+        out
+    }
+
+    /// Render the diff as markdown suitable for release notes.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        if self.specs.is_empty() {
+            let _ = writeln!(out, "No rule changes.");
+            return out;
+        }
+        for (i, spec) in self.specs.iter().enumerate() {
+            if i > 0 {
+                let _ = writeln!(out);
+            }
+            let _ = writeln!(out, "## {}", spec.name);
+            if let Some(change) = &spec.source_url {
+                let _ = writeln!(out);
+                let _ = writeln!(
+                    out,
+                    "Source URL: {} → {}",
+                    unset(&change.from),
+                    unset(&change.to)
+                );
+            }
+            write_ids(&mut out, "Added", &spec.added);
+            write_ids(&mut out, "Removed", &spec.removed);
+            write_ids(&mut out, "Reworded", &spec.reworded);
+            write_changes(&mut out, "Re-leveled", &spec.releveled);
+            write_changes(&mut out, "Status changed", &spec.restatused);
+            if !spec.reversioned.is_empty() {
+                let _ = writeln!(out);
+                let _ = writeln!(out, "Re-versioned:");
+                let _ = writeln!(out);
+                for change in &spec.reversioned {
+                    let _ = writeln!(out, "- `{}`: +{} → +{}", change.id, change.from, change.to);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn unset(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("none")
+}
+
+fn write_changes(out: &mut String, title: &str, changes: &[MetadataChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{title}:");
+    let _ = writeln!(out);
+    for change in changes {
+        let _ = writeln!(
+            out,
+            "- `{}`: {} → {}",
+            change.id,
+            unset(&change.from),
+            unset(&change.to)
+        );
+    }
+}
+
+fn write_ids(out: &mut String, title: &str, ids: &[String]) {
+    if ids.is_empty() {
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{title}:");
+    let _ = writeln!(out);
+    for id in ids {
+        let _ = writeln!(out, "- `{id}`");
+    }
+}
+
+/// Build the manifest of every configured spec, from the working tree or,
+/// when `revision` is given, from that git revision.
+// r[impl cli.spec.manifest]
+pub async fn manifest(
+    project_root: &Path,
+    config: &Config,
+    revision: Option<&str>,
+) -> Result<SpecManifest> {
+    let mut specs = Vec::new();
+    for spec in &config.specs {
+        let files = match revision {
+            Some(revision) => {
+                crate::release::spec_files_at_revision(project_root, revision, &spec.include)?
+            }
+            None => spec_files_in_worktree(project_root, &spec.include)?,
+        };
+        let mut rules = BTreeMap::new();
+        for (file, content) in files {
+            // r[impl cli.spec.manifest.examples]
+            let (_, mut examples) = crate::examples::extract(&content);
+            for (base, def) in parse_spec_rules(&content, &file).await? {
+                let mut rule = ManifestRule::from(&def);
+                rule.examples = examples
+                    .extract_if(.., |e| e.rule == base)
+                    .map(|e| ManifestExample {
+                        kind: e.kind().to_string(),
+                        text: e.markdown,
+                    })
+                    .collect();
+                // r[impl cli.spec.manifest.attributes]
+                rule.attributes = crate::marker_attributes(&content, def.marker_span);
+                rules.insert(base, rule);
+            }
+        }
+        specs.push(ManifestSpec {
+            name: spec.name.clone(),
+            source_url: spec.source_url.clone(),
+            rules: rules.into_values().collect(),
+        });
+    }
+    Ok(SpecManifest { specs })
+}
+
+/// Read every spec file matching `include` in the working tree, as
+/// `(path, content)` pairs. Files git ignores are skipped.
+fn spec_files_in_worktree(
+    project_root: &Path,
+    include: &[String],
+) -> Result<Vec<(String, String)>> {
+    let files = git_capture(
+        project_root,
+        &["ls-files", "--cached", "--others", "--exclude-standard"],
+    )?;
+    let matchers: Vec<_> = include
+        .iter()
+        .filter_map(|p| globset::Glob::new(p).ok())
+        .map(|g| g.compile_matcher())
+        .collect();
+
+    let mut contents = Vec::new();
+    for file in files.lines().map(str::trim) {
+        if file.is_empty() || !matchers.iter().any(|m| m.is_match(file)) {
+            continue;
+        }
+        // Deleted but still in the index.
+        let Ok(content) = std::fs::read_to_string(project_root.join(file)) else {
+            continue;
+        };
+        contents.push((file.to_string(), content));
+    }
+    Ok(contents)
+}
+
+/// Compare two manifests.
+// r[impl cli.spec.diff]
+pub fn diff(old: &SpecManifest, new: &SpecManifest) -> SpecDiff {
+    let old_specs: HashMap<&str, &ManifestSpec> =
+        old.specs.iter().map(|s| (s.name.as_str(), s)).collect();
+    let new_names: Vec<&str> = new.specs.iter().map(|s| s.name.as_str()).collect();
+
+    // A spec missing on one side compares as empty with the same URL, so
+    // adding or removing a spec only reports its rules.
+    let empty = |spec: &ManifestSpec| ManifestSpec {
+        rules: Vec::new(),
+        ..spec.clone()
+    };
+    let pairs = new
+        .specs
+        .iter()
+        .map(|spec| {
+            let before = old_specs
+                .get(spec.name.as_str())
+                .map_or_else(|| empty(spec), |s| (*s).clone());
+            (before, spec.clone())
+        })
+        .chain(
+            old.specs
+                .iter()
+                .filter(|s| !new_names.contains(&s.name.as_str()))
+                .map(|spec| (spec.clone(), empty(spec))),
+        );
+
+    let specs = pairs
+        .map(|(before, after)| diff_spec(&before, &after))
+        .filter(|changes| !changes.is_empty())
+        .collect();
+    SpecDiff { specs }
+}
+
+// r[impl cli.spec.diff.metadata]
+fn diff_spec(old: &ManifestSpec, new: &ManifestSpec) -> SpecChanges {
+    let before: BTreeMap<&str, &ManifestRule> =
+        old.rules.iter().map(|r| (r.id.as_str(), r)).collect();
+    let after: BTreeMap<&str, &ManifestRule> =
+        new.rules.iter().map(|r| (r.id.as_str(), r)).collect();
+
+    let mut changes = SpecChanges {
+        name: new.name.clone(),
+        source_url: (old.source_url != new.source_url).then(|| MetadataChange {
+            id: new.name.clone(),
+            from: old.source_url.clone(),
+            to: new.source_url.clone(),
+        }),
+        ..Default::default()
+    };
+    for (id, rule) in &after {
+        let Some(prev) = before.get(id) else {
+            changes.added.push(id.to_string());
+            continue;
+        };
+        if !same_words(&prev.text, &rule.text) {
+            changes.reworded.push(id.to_string());
+        }
+        if prev.level != rule.level {
+            changes.releveled.push(MetadataChange {
+                id: id.to_string(),
+                from: prev.level.clone(),
+                to: rule.level.clone(),
+            });
+        }
+        if prev.status != rule.status {
+            changes.restatused.push(MetadataChange {
+                id: id.to_string(),
+                from: prev.status.clone(),
+                to: rule.status.clone(),
+            });
+        }
+        if prev.version != rule.version {
+            changes.reversioned.push(VersionChange {
+                id: id.to_string(),
+                from: prev.version,
+                to: rule.version,
+            });
+        }
+    }
+    changes.removed = before
+        .keys()
+        .filter(|id| !after.contains_key(*id))
+        .map(|id| id.to_string())
+        .collect();
+    changes
+}
+
+/// Whether two texts differ only in whitespace, so reflowing a paragraph
+/// does not count as rewording it.
+fn same_words(a: &str, b: &str) -> bool {
+    a.split_whitespace().eq(b.split_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, version: u32, level: Option<&str>, text: &str) -> ManifestRule {
+        ManifestRule {
+            id: id.to_string(),
+            version,
+            level: level.map(str::to_string),
+            status: None,
+            text: text.to_string(),
+            examples: Vec::new(),
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    fn manifest(rules: Vec<ManifestRule>) -> SpecManifest {
+        SpecManifest {
+            specs: vec![ManifestSpec {
+                name: "main".to_string(),
+                source_url: None,
+                rules,
+            }],
+        }
+    }
+
+    fn git(root: &Path, args: &[&str]) {
+        git_capture(root, args).unwrap();
+    }
+
+    fn demo_config() -> Config {
+        facet_styx::from_str(
+            r#"specs (
+    {
+        name demo
+        source_url https://example.com/demo
+        include (docs/**/*.md)
+        impls (
+            {
+                name rust
+                include (src/**/*.rs)
+            }
+        )
+    }
+)"#,
+        )
+        .unwrap()
+    }
+
+    // r[verify cli.spec.manifest]
+    // r[verify cli.spec.manifest.attributes]
+    #[tokio::test]
+    async fn manifest_reads_the_worktree_or_a_revision() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+        git(root, &["config", "user.email", "t@example.com"]);
+        git(root, &["config", "user.name", "t"]);
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(
+            root.join("docs/spec.md"),
+            "# Spec\n\nr[auth.login]\nUsers MUST log in.\n",
+        )
+        .unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "base"]);
+
+        std::fs::write(
+            root.join("docs/spec.md"),
+            "# Spec\n\nr[auth.login+2 level=should status=stable]\nUsers SHOULD log in.\n\n\
+             r[auth.logout owner=\"Session team\"\n    level=must]\nUsers MUST log out.\n",
+        )
+        .unwrap();
+        let config = demo_config();
+
+        let current = super::manifest(root, &config, None).await.unwrap();
+        assert_eq!(current.specs.len(), 1);
+        let spec = &current.specs[0];
+        assert_eq!(spec.name, "demo");
+        assert_eq!(spec.source_url.as_deref(), Some("https://example.com/demo"));
+        let login = &spec.rules[0];
+        assert_eq!((login.id.as_str(), login.version), ("auth.login", 2));
+        assert_eq!(login.level.as_deref(), Some("should"));
+        assert_eq!(login.status.as_deref(), Some("stable"));
+        assert_eq!(login.text, "Users SHOULD log in.");
+        assert_eq!(spec.rules[1].id, "auth.logout");
+        assert_eq!(spec.rules[1].level.as_deref(), Some("must"));
+        assert_eq!(
+            spec.rules[1].attributes.get("owner").map(String::as_str),
+            Some("Session team")
+        );
+
+        let base = super::manifest(root, &config, Some("HEAD")).await.unwrap();
+        let rules = &base.specs[0].rules;
+        assert_eq!(rules.len(), 1);
+        assert_eq!((rules[0].id.as_str(), rules[0].version), ("auth.login", 1));
+        assert_eq!(rules[0].text, "Users MUST log in.");
+
+        let json = facet_json::to_string(&current).unwrap();
+        let parsed: SpecManifest = facet_json::from_str(&json).unwrap();
+        assert_eq!(parsed.specs[0].rules.len(), 2);
+        assert_eq!(
+            parsed.specs[0].rules[1].attributes,
+            spec.rules[1].attributes
+        );
+    }
+
+    // r[verify cli.spec.manifest.examples]
+    #[tokio::test]
+    async fn manifest_lists_each_rules_examples() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(
+            root.join("docs/spec.md"),
+            "# Spec\n\nr[auth.login]\nPasswords MUST be compared in constant time.\n\n\
+             e[auth.login good] Use `constant_time_eq`.\n\n\
+             e[auth.login bad]\nComparing with `==` leaks timing.\n\n\
+             r[auth.logout]\nLogout MUST clear the session.\n",
+        )
+        .unwrap();
+
+        let manifest = super::manifest(root, &demo_config(), None).await.unwrap();
+        let rules = &manifest.specs[0].rules;
+        let ids: Vec<&str> = rules.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["auth.login", "auth.logout"]);
+        let examples: Vec<(&str, &str)> = rules[0]
+            .examples
+            .iter()
+            .map(|e| (e.kind.as_str(), e.text.as_str()))
+            .collect();
+        assert_eq!(
+            examples,
+            [
+                ("good", "Use `constant_time_eq`."),
+                ("bad", "Comparing with `==` leaks timing."),
+            ]
+        );
+        assert!(rules[1].examples.is_empty());
+    }
+
+    // r[verify cli.spec.diff]
+    #[test]
+    fn diff_reports_each_kind_of_change() {
+        let old = manifest(vec![
+            rule(
+                "auth.login",
+                1,
+                Some("must"),
+                "Login MUST check\nthe password.",
+            ),
+            rule("auth.logout", 1, None, "Logout MUST clear the session."),
+            rule(
+                "auth.reset",
+                1,
+                Some("should"),
+                "Reset SHOULD email the user.",
+            ),
+            rule("auth.token", 2, None, "Tokens MUST expire."),
+        ]);
+        let new = manifest(vec![
+            rule(
+                "auth.login",
+                1,
+                Some("must"),
+                "Login MUST check the password.",
+            ),
+            rule("auth.reset", 1, Some("must"), "Reset MUST email the user."),
+            rule("auth.token", 3, None, "Tokens MUST expire after an hour."),
+            rule("auth.audit", 1, None, "Logins MUST be audited."),
+        ]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.specs.len(), 1);
+        let changes = &diff.specs[0];
+        assert_eq!(changes.added, ["auth.audit"]);
+        assert_eq!(changes.removed, ["auth.logout"]);
+        assert_eq!(changes.reworded, ["auth.reset", "auth.token"]);
+        assert_eq!(changes.releveled.len(), 1);
+        assert_eq!(changes.releveled[0].id, "auth.reset");
+        assert_eq!(changes.releveled[0].to.as_deref(), Some("must"));
+        assert_eq!(changes.reversioned.len(), 1);
+        assert_eq!(
+            (changes.reversioned[0].from, changes.reversioned[0].to),
+            (2, 3)
+        );
+
+        let markdown = diff.to_markdown();
+        assert!(
+            markdown.contains("Added:\n\n- `auth.audit`\n"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("- `auth.reset`: should → must"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("- `auth.token`: +2 → +3"), "{markdown}");
+
+        let json = facet_json::to_string(&diff).unwrap();
+        let parsed: SpecDiff = facet_json::from_str(&json).unwrap();
+        assert_eq!(parsed.specs[0].removed, ["auth.logout"]);
+
+        assert!(super::diff(&new, &new).specs.is_empty());
+    }
+
+    // r[verify cli.spec.diff.metadata]
+    #[test]
+    fn diff_reports_status_and_source_url_changes() {
+        let mut stable = rule("auth.login", 1, Some("must"), "Login MUST work.");
+        stable.status = Some("stable".to_string());
+        let mut deprecated = stable.clone();
+        deprecated.status = Some("deprecated".to_string());
+
+        let old = manifest(vec![stable]);
+        let mut new = manifest(vec![deprecated]);
+        new.specs[0].source_url = Some("https://example.com/spec".to_string());
+
+        let diff = diff(&old, &new);
+        let changes = &diff.specs[0];
+        assert!(changes.reworded.is_empty() && changes.releveled.is_empty());
+        assert_eq!(changes.restatused.len(), 1);
+        assert_eq!(changes.restatused[0].from.as_deref(), Some("stable"));
+        assert_eq!(changes.restatused[0].to.as_deref(), Some("deprecated"));
+        let url = changes.source_url.as_ref().unwrap();
+        assert_eq!(
+            (url.from.as_deref(), url.to.as_deref()),
+            (None, Some("https://example.com/spec"))
+        );
+
+        let text = diff.render(Format::Text);
+        assert_eq!(
+            text,
+            "main: source_url none -> https://example.com/spec\n\
+             main: restatused auth.login stable -> deprecated\n"
+        );
+        let markdown = diff.render(Format::Markdown);
+        assert!(
+            markdown.contains("- `auth.login`: stable → deprecated"),
+            "{markdown}"
+        );
+        let json = diff.render(Format::Json);
+        let parsed: SpecDiff = facet_json::from_str(&json).unwrap();
+        assert_eq!(parsed.specs[0].restatused[0].id, "auth.login");
+
+        assert!("html".parse::<Format>().is_err());
+        assert_eq!("TEXT".parse::<Format>().unwrap(), Format::Text);
+    }
+}
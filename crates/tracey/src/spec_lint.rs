@@ -0,0 +1,706 @@
+//! `tracey lint-spec` implementation.
+//!
+//! Checks spec markdown for the style problems rule extraction has no reason
+//! to reject: rules without an RFC 2119 keyword, rules too long to test as
+//! one requirement, headings with no rules under them, rules whose ID does
+//! not share its section's prefix, in-page links to anchors that do not
+//! exist, and rules that repeat another rule's text. The config's
+//! `spec_lints` block sets how severe each lint is, so a project can fail CI
+//! on some and silence others.
+
+use ariadne::{Color, Config as ReportConfig, Label, Report, ReportKind, Source};
+use eyre::{Result, WrapErr};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::Range;
+use std::path::Path;
+
+use tracey_core::markdown::edit::{RuleMarker, find_rule_markers};
+
+use crate::config::Config;
+use crate::config_check::Severity;
+
+/// RFC 2119 keywords, as whole uppercase words. `MUST NOT` and the other
+/// negated forms contain one of these.
+const KEYWORDS: [&str; 7] = [
+    "MUST",
+    "SHALL",
+    "SHOULD",
+    "MAY",
+    "REQUIRED",
+    "RECOMMENDED",
+    "OPTIONAL",
+];
+
+/// Sentences a rule may have unless configured otherwise.
+const DEFAULT_MAX_SENTENCES: usize = 5;
+
+/// Similarity, in percent, at which two rules count as duplicates unless
+/// configured otherwise.
+const DEFAULT_MIN_SIMILARITY: u32 = 90;
+
+/// One check `tracey lint-spec` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    /// Rule text without an RFC 2119 keyword.
+    MissingKeyword,
+    /// Rule text with more sentences than allowed.
+    LongRule,
+    /// Heading with no rule anywhere under it.
+    EmptySection,
+    /// Rule whose first ID segment differs from the other rules under its
+    /// heading.
+    PrefixMismatch,
+    /// In-page link to an anchor no heading or rule defines.
+    OrphanAnchor,
+    /// Rule whose text is nearly the same as an earlier rule's.
+    DuplicateText,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::MissingKeyword,
+        Lint::LongRule,
+        Lint::EmptySection,
+        Lint::PrefixMismatch,
+        Lint::OrphanAnchor,
+        Lint::DuplicateText,
+    ];
+
+    /// Name used in the config and in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::MissingKeyword => "missing-keyword",
+            Lint::LongRule => "long-rule",
+            Lint::EmptySection => "empty-section",
+            Lint::PrefixMismatch => "prefix-mismatch",
+            Lint::OrphanAnchor => "orphan-anchor",
+            Lint::DuplicateText => "duplicate-text",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+
+    fn default_severity(self) -> Severity {
+        match self {
+            Lint::OrphanAnchor => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// Parse a configured lint level: `Some` severity, or `None` for `off`.
+pub fn parse_level(level: &str) -> std::result::Result<Option<Severity>, String> {
+    match level {
+        "error" => Ok(Some(Severity::Error)),
+        "warning" => Ok(Some(Severity::Warning)),
+        "off" => Ok(None),
+        other => Err(format!(
+            "expected `error`, `warning` or `off`, got `{other}`"
+        )),
+    }
+}
+
+/// Lint settings resolved from the config.
+#[derive(Debug, Clone)]
+pub struct LintSettings {
+    levels: BTreeMap<Lint, Option<Severity>>,
+    max_sentences: usize,
+    min_similarity: f64,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        Self {
+            levels: Lint::ALL
+                .into_iter()
+                .map(|lint| (lint, Some(lint.default_severity())))
+                .collect(),
+            max_sentences: DEFAULT_MAX_SENTENCES,
+            min_similarity: f64::from(DEFAULT_MIN_SIMILARITY) / 100.0,
+        }
+    }
+}
+
+impl LintSettings {
+    /// Settings from the config's `spec_lints` block. Invalid entries were
+    /// already reported when the config was validated and are ignored.
+    // r[impl config.spec-lints]
+    pub fn from_config(config: &Config) -> Self {
+        let mut settings = Self::default();
+        let Some(lints) = &config.spec_lints else {
+            return settings;
+        };
+        for (name, level) in &lints.levels {
+            if let (Some(lint), Ok(severity)) = (Lint::from_name(name), parse_level(level)) {
+                settings.levels.insert(lint, severity);
+            }
+        }
+        if let Some(max) = lints.max_sentences {
+            settings.max_sentences = max as usize;
+        }
+        if let Some(min) = lints.min_similarity {
+            settings.min_similarity = f64::from(min.min(100)) / 100.0;
+        }
+        settings
+    }
+
+    fn severity(&self, lint: Lint) -> Option<Severity> {
+        self.levels.get(&lint).copied().flatten()
+    }
+}
+
+/// A spec markdown file to lint.
+#[derive(Debug, Clone)]
+pub struct SpecFile {
+    /// Path shown in reports, relative to the project root.
+    pub path: String,
+    pub source: String,
+}
+
+/// A lint that fired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub lint: Lint,
+    pub severity: Severity,
+    /// Index of the file in [`LintReport::files`].
+    pub file: usize,
+    /// Byte range in the file of the text at fault.
+    pub span: Range<usize>,
+    pub message: String,
+    /// What is wrong with that text, shown under it.
+    pub label: &'static str,
+}
+
+/// Outcome of `tracey lint-spec`.
+#[derive(Debug, Default)]
+pub struct LintReport {
+    pub files: Vec<SpecFile>,
+    pub findings: Vec<Finding>,
+}
+
+impl LintReport {
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    /// Render every finding with the spec source around it, followed by a
+    /// summary line.
+    pub fn render(&self, color: bool) -> String {
+        let mut out = String::new();
+        for finding in &self.findings {
+            let file = &self.files[finding.file];
+            let filename = file.path.as_str();
+            let (kind, label_color) = match finding.severity {
+                Severity::Error => (ReportKind::Error, Color::Red),
+                Severity::Warning => (ReportKind::Warning, Color::Yellow),
+            };
+            let mut buf = Vec::new();
+            let _ = Report::build(kind, (filename, finding.span.clone()))
+                .with_config(ReportConfig::default().with_color(color))
+                .with_code(finding.lint.name())
+                .with_message(&finding.message)
+                .with_label(
+                    Label::new((filename, finding.span.clone()))
+                        .with_message(finding.label)
+                        .with_color(label_color),
+                )
+                .finish()
+                .write((filename, Source::from(file.source.as_str())), &mut buf);
+            out.push_str(&String::from_utf8_lossy(&buf));
+        }
+        match (self.errors(), self.warnings()) {
+            (0, 0) => out.push_str(&format!(
+                "{} spec file(s): no problems found.\n",
+                self.files.len()
+            )),
+            (errors, warnings) => out.push_str(&format!(
+                "{} spec file(s): {errors} error(s), {warnings} warning(s).\n",
+                self.files.len()
+            )),
+        }
+        out
+    }
+}
+
+/// A heading and where its text sits.
+struct Heading {
+    level: usize,
+    slug: String,
+    span: Range<usize>,
+}
+
+/// A rule definition and the heading directly above it.
+struct Rule {
+    marker: RuleMarker,
+    text: String,
+    heading: Option<usize>,
+}
+
+/// What the lints need from one file.
+#[derive(Default)]
+struct ParsedFile {
+    headings: Vec<Heading>,
+    rules: Vec<Rule>,
+    /// In-page link fragments, without the `#`, and the span of `#fragment`.
+    anchor_links: Vec<(String, Range<usize>)>,
+}
+
+/// Heading anchor as markdown renderers generate it: lowercase, spaces as
+/// dashes, punctuation other than `-` and `_` dropped.
+fn heading_slug(title: &str) -> String {
+    title
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => Some(c.to_ascii_lowercase()),
+            c if c.is_whitespace() => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Strip blockquote markers (and the space after each) from a line.
+fn unquote(line: &str) -> Option<&str> {
+    let mut rest = line.trim_start().strip_prefix('>')?;
+    loop {
+        rest = rest.strip_prefix(' ').unwrap_or(rest);
+        match rest.strip_prefix('>') {
+            Some(inner) => rest = inner,
+            None => return Some(rest),
+        }
+    }
+}
+
+fn parse_file(source: &str) -> ParsedFile {
+    let mut parsed = ParsedFile::default();
+
+    // Lines with their byte offsets, and whether each is inside a fence.
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+    for raw in source.split_inclusive('\n') {
+        let line = raw.trim_end_matches(['\n', '\r']);
+        let content = unquote(line).unwrap_or(line).trim_start();
+        let fence = content.starts_with("```") || content.starts_with("~~~");
+        lines.push((offset, line, in_fence || fence));
+        if fence {
+            in_fence = !in_fence;
+        }
+        offset += raw.len();
+    }
+
+    for &(start, line, code) in &lines {
+        if code {
+            continue;
+        }
+        let hashes = line.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && line[hashes..].starts_with(' ') {
+            let title = line[hashes..].trim().trim_end_matches('#');
+            parsed.headings.push(Heading {
+                level: hashes,
+                slug: heading_slug(title),
+                span: start..start + line.len(),
+            });
+            continue;
+        }
+        let mut segment_start = start;
+        for (segment_index, segment) in line.split('`').enumerate() {
+            // Odd segments are inside inline code spans.
+            if segment_index % 2 == 0 {
+                for (at, _) in segment.match_indices("](#") {
+                    let fragment = &segment[at + 3..];
+                    let len = fragment.find([')', ' ']).unwrap_or(fragment.len());
+                    let hash = segment_start + at + 2;
+                    parsed
+                        .anchor_links
+                        .push((fragment[..len].to_string(), hash..hash + len + 1));
+                }
+            }
+            segment_start += segment.len() + 1;
+        }
+    }
+
+    let markers = find_rule_markers(source);
+    let starts: Vec<usize> = markers.iter().map(|m| m.span.start).collect();
+    for marker in markers {
+        let index = lines.partition_point(|&(start, _, _)| start <= marker.span.start) - 1;
+        let quoted = lines[index].1.trim_start().starts_with('>');
+        // The marker's attributes may continue over several lines.
+        let index = lines.partition_point(|&(start, _, _)| start < marker.span.end) - 1;
+        let (start, line, _) = lines[index];
+
+        // The rule's text: the rest of its blockquote, or of its paragraph,
+        // up to the next heading or rule.
+        let mut text = source[marker.span.end..start + line.len()]
+            .trim()
+            .to_string();
+        for &(next_start, next, _) in &lines[index + 1..] {
+            let body = if quoted { unquote(next) } else { Some(next) };
+            let Some(body) = body.filter(|b| quoted || !b.trim().is_empty()) else {
+                break;
+            };
+            let next_end = next_start + next.len();
+            if next.starts_with('#') || starts.iter().any(|&s| (next_start..next_end).contains(&s))
+            {
+                break;
+            }
+            text.push('\n');
+            text.push_str(body);
+        }
+        let heading = parsed
+            .headings
+            .partition_point(|h| h.span.start < marker.span.start)
+            .checked_sub(1);
+        parsed.rules.push(Rule {
+            marker,
+            text: text.trim().to_string(),
+            heading,
+        });
+    }
+    parsed
+}
+
+/// Sentences in a rule's text: runs ending in `.`, `!` or `?` followed by
+/// whitespace, plus any unterminated tail.
+fn sentence_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut has_words = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() {
+            has_words = true;
+        } else if matches!(c, '.' | '!' | '?')
+            && chars.peek().is_none_or(|next| next.is_whitespace())
+            && has_words
+        {
+            count += 1;
+            has_words = false;
+        }
+    }
+    count + usize::from(has_words)
+}
+
+fn has_keyword(text: &str) -> bool {
+    text.split(|c: char| !c.is_ascii_alphabetic())
+        .any(|word| KEYWORDS.contains(&word))
+}
+
+/// Text compared by `duplicate-text`: lowercase words, single-spaced.
+fn normalized(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lint the markdown files of one spec. `first_file` is the index the first
+/// of them has in the report.
+// r[impl cli.lint-spec]
+pub fn lint_files(files: &[SpecFile], first_file: usize, settings: &LintSettings) -> Vec<Finding> {
+    let parsed: Vec<ParsedFile> = files.iter().map(|f| parse_file(&f.source)).collect();
+    let mut findings = Vec::new();
+    let mut push = |lint: Lint, file: usize, span: Range<usize>, message: String, label| {
+        if let Some(severity) = settings.severity(lint) {
+            findings.push(Finding {
+                lint,
+                severity,
+                file: first_file + file,
+                span,
+                message,
+                label,
+            });
+        }
+    };
+
+    let mut anchors: HashSet<String> = HashSet::new();
+    for rule in parsed.iter().flat_map(|p| &p.rules) {
+        let id = &rule.marker.id;
+        for anchor in [id.base.clone(), id.to_string()] {
+            anchors.insert(format!("r-{anchor}"));
+            anchors.insert(format!("r--{anchor}"));
+        }
+    }
+
+    for (file, parsed) in parsed.iter().enumerate() {
+        for rule in &parsed.rules {
+            let id = &rule.marker.id;
+            if !has_keyword(&rule.text) {
+                push(
+                    Lint::MissingKeyword,
+                    file,
+                    rule.marker.span.clone(),
+                    format!("Rule {id} has no RFC 2119 keyword (MUST, SHOULD, MAY, ...)"),
+                    "no requirement keyword",
+                );
+            }
+            let sentences = sentence_count(&rule.text);
+            if sentences > settings.max_sentences {
+                push(
+                    Lint::LongRule,
+                    file,
+                    rule.marker.span.clone(),
+                    format!(
+                        "Rule {id} has {sentences} sentences, more than {}; consider splitting it",
+                        settings.max_sentences
+                    ),
+                    "too long",
+                );
+            }
+        }
+
+        for (index, heading) in parsed.headings.iter().enumerate() {
+            let end = parsed.headings[index + 1..]
+                .iter()
+                .find(|next| next.level <= heading.level)
+                .map_or(usize::MAX, |next| next.span.start);
+            let has_rules = parsed
+                .rules
+                .iter()
+                .any(|r| (heading.span.start..end).contains(&r.marker.span.start));
+            if !has_rules {
+                push(
+                    Lint::EmptySection,
+                    file,
+                    heading.span.clone(),
+                    "Section defines no rules".to_string(),
+                    "no rules under this heading",
+                );
+            }
+        }
+
+        let mut by_heading: BTreeMap<usize, Vec<&Rule>> = BTreeMap::new();
+        for rule in &parsed.rules {
+            if let Some(heading) = rule.heading {
+                by_heading.entry(heading).or_default().push(rule);
+            }
+        }
+        for rules in by_heading.values() {
+            let first_segment =
+                |rule: &Rule| rule.marker.id.base.split('.').next().map(str::to_string);
+            let mut counts: Vec<(String, usize)> = Vec::new();
+            for segment in rules.iter().copied().filter_map(first_segment) {
+                match counts.iter_mut().find(|(s, _)| *s == segment) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((segment, 1)),
+                }
+            }
+            // Earliest of the most common prefixes; no prefix when none repeats.
+            let Some((prefix, count)) = counts
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .filter(|(_, count)| *count > 1)
+            else {
+                continue;
+            };
+            for &rule in rules {
+                if first_segment(rule).as_ref() != Some(prefix) {
+                    push(
+                        Lint::PrefixMismatch,
+                        file,
+                        rule.marker.id_span.clone(),
+                        format!(
+                            "Rule {} does not start with `{prefix}.` like the {count} other rules in its section",
+                            rule.marker.id
+                        ),
+                        "unexpected prefix",
+                    );
+                }
+            }
+        }
+
+        let slugs: BTreeSet<&str> = parsed.headings.iter().map(|h| h.slug.as_str()).collect();
+        for (fragment, span) in &parsed.anchor_links {
+            if !slugs.contains(fragment.as_str()) && !anchors.contains(fragment) {
+                push(
+                    Lint::OrphanAnchor,
+                    file,
+                    span.clone(),
+                    format!("No heading or rule defines the anchor `#{fragment}`"),
+                    "anchor not found",
+                );
+            }
+        }
+    }
+
+    let texts: Vec<(usize, &Rule, String)> = parsed
+        .iter()
+        .enumerate()
+        .flat_map(|(file, p)| p.rules.iter().map(move |r| (file, r, normalized(&r.text))))
+        .filter(|(_, _, text)| !text.is_empty())
+        .collect();
+    for (later, (file, rule, text)) in texts.iter().enumerate() {
+        let original = texts[..later].iter().find(|(_, _, earlier)| {
+            // Levenshtein similarity can't exceed the ratio of the lengths.
+            let (short, long) = if earlier.len() < text.len() {
+                (earlier.len(), text.len())
+            } else {
+                (text.len(), earlier.len())
+            };
+            short as f64 / long as f64 >= settings.min_similarity
+                && strsim::normalized_levenshtein(earlier, text) >= settings.min_similarity
+        });
+        if let Some((_, earlier, _)) = original {
+            push(
+                Lint::DuplicateText,
+                *file,
+                rule.marker.span.clone(),
+                format!(
+                    "Rule {} repeats the text of rule {}",
+                    rule.marker.id, earlier.marker.id
+                ),
+                "duplicate text",
+            );
+        }
+    }
+
+    findings
+}
+
+/// Lint the markdown files of every spec, or only of `spec`.
+pub fn lint_spec(project_root: &Path, config: &Config, spec: Option<&str>) -> Result<LintReport> {
+    if let Some(name) = spec
+        && !config.specs.iter().any(|s| s.name == name)
+    {
+        eyre::bail!("No spec named '{name}' in the config");
+    }
+    let settings = LintSettings::from_config(config);
+    let mut report = LintReport::default();
+    for spec_config in config
+        .specs
+        .iter()
+        .filter(|s| spec.is_none_or(|name| s.name == name))
+    {
+        let paths: BTreeSet<_> = crate::config::spec_sources(spec_config)
+            .iter()
+            .filter_map(|source| source.files(project_root))
+            .flatten()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        let mut files = Vec::new();
+        for path in paths {
+            let source = std::fs::read_to_string(&path)
+                .wrap_err_with(|| format!("Failed to read spec file: {}", path.display()))?;
+            let relative = path.strip_prefix(project_root).unwrap_or(&path);
+            files.push(SpecFile {
+                path: relative.display().to_string(),
+                source,
+            });
+        }
+        let findings = lint_files(&files, report.files.len(), &settings);
+        report.findings.extend(findings);
+        report.files.extend(files);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "\
+# Auth
+
+## Login
+
+r[auth.login]
+Users MUST log in before they see their account.
+
+r[auth.logout]
+Users can log out. It ends the session. Cookies are cleared. Tokens are
+revoked. The page reloads. A message is shown.
+
+r[session.expiry]
+Sessions MUST expire after an hour, see [login](#login) and [expiry](#r--auth.expiry).
+
+> r[auth.reset]
+> Users MUST log in before they see their account!
+
+## Appendix
+
+Nothing here.
+
+```markdown
+## Not a heading
+r[code.block]
+```
+";
+
+    fn lint(source: &str, settings: &LintSettings) -> Vec<(Lint, String)> {
+        let files = [SpecFile {
+            path: "spec.md".to_string(),
+            source: source.to_string(),
+        }];
+        lint_files(&files, 0, settings)
+            .into_iter()
+            .map(|f| (f.lint, source[f.span].to_string()))
+            .collect()
+    }
+
+    // r[verify cli.lint-spec]
+    #[test]
+    fn every_lint_points_at_its_text() {
+        let found = lint(SPEC, &LintSettings::default());
+        assert_eq!(
+            found,
+            [
+                (Lint::MissingKeyword, "r[auth.logout]".to_string()),
+                (Lint::LongRule, "r[auth.logout]".to_string()),
+                (Lint::EmptySection, "## Appendix".to_string()),
+                (Lint::PrefixMismatch, "session.expiry".to_string()),
+                (Lint::OrphanAnchor, "#r--auth.expiry".to_string()),
+                (Lint::DuplicateText, "r[auth.reset]".to_string()),
+            ]
+        );
+    }
+
+    // r[verify config.spec-lints]
+    #[test]
+    fn levels_come_from_the_config() {
+        let config: Config = facet_styx::from_str(
+            "spec_lints {\n  levels {\n    missing-keyword error\n    empty-section off\n  }\n  max_sentences 6\n}\nspecs ()\n",
+        )
+        .unwrap();
+        let settings = LintSettings::from_config(&config);
+        assert_eq!(
+            settings.severity(Lint::MissingKeyword),
+            Some(Severity::Error)
+        );
+        assert_eq!(settings.severity(Lint::EmptySection), None);
+        assert_eq!(settings.severity(Lint::OrphanAnchor), Some(Severity::Error));
+
+        let found: Vec<Lint> = lint(SPEC, &settings).into_iter().map(|(l, _)| l).collect();
+        assert!(!found.contains(&Lint::EmptySection));
+        assert!(
+            !found.contains(&Lint::LongRule),
+            "six sentences are allowed"
+        );
+
+        let config: Config = facet_styx::from_str(
+            "spec_lints {\n  levels {\n    no-such-lint error\n    long-rule loud\n  }\n}\nspecs ()\n",
+        )
+        .unwrap();
+        let problems = crate::config::problems(&config);
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].message.contains("got `loud`"), "{problems:?}");
+        assert!(
+            problems[1]
+                .message
+                .starts_with("Unknown spec lint 'no-such-lint'")
+        );
+    }
+}
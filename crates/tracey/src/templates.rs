@@ -0,0 +1,176 @@
+//! `tracey new` implementation.
+//!
+//! Example projects embedded in the binary: each is a spec with rules, source
+//! annotated against it, tests, a tracey config, and a CI workflow, small
+//! enough to read in one sitting and complete enough to run every command on.
+
+use eyre::{Result, WrapErr};
+use std::path::{Path, PathBuf};
+
+/// An example project `tracey new` can write.
+#[derive(Debug)]
+pub struct Template {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Path relative to the project directory, and contents.
+    pub files: &'static [(&'static str, &'static str)],
+}
+
+const CI: (&str, &str) = (
+    ".github/workflows/tracey.yml",
+    include_str!("../templates/ci.yml"),
+);
+
+macro_rules! template_files {
+    ($name:literal: $($path:literal),* $(,)?) => {
+        &[
+            $(($path, include_str!(concat!("../templates/", $name, "/", $path))),)*
+            ("Cargo.toml", include_str!(concat!("../templates/", $name, "/Cargo.toml.in"))),
+            CI,
+        ]
+    };
+}
+
+/// Every template, in the order `tracey new --list` shows them.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "protocol-spec",
+        description: "A wire protocol: framing rules with examples, a Rust codec, and its tests",
+        files: template_files!("protocol-spec":
+            "README.md",
+            ".config/tracey/config.styx",
+            "docs/spec/framing.md",
+            "src/lib.rs",
+            "tests/framing.rs",
+        ),
+    },
+    Template {
+        name: "safety-critical",
+        description: "Versioned requirements for a brake controller, all implemented and verified",
+        files: template_files!("safety-critical":
+            "README.md",
+            ".config/tracey/config.styx",
+            "docs/spec/brake-controller.md",
+            "src/lib.rs",
+            "tests/controller.rs",
+        ),
+    },
+    Template {
+        name: "api-contract",
+        description: "An HTTP API contract implemented by a Rust server and a TypeScript client",
+        files: template_files!("api-contract":
+            "README.md",
+            ".config/tracey/config.styx",
+            "docs/spec/todo-api.md",
+            "src/lib.rs",
+            "tests/server.rs",
+            "client/client.ts",
+        ),
+    },
+];
+
+/// Look up a template by name.
+pub fn find(name: &str) -> Result<&'static Template> {
+    TEMPLATES.iter().find(|t| t.name == name).ok_or_else(|| {
+        let names: Vec<&str> = TEMPLATES.iter().map(|t| t.name).collect();
+        eyre::eyre!(
+            "Unknown template `{name}`; expected one of: {}",
+            names.join(", ")
+        )
+    })
+}
+
+/// The templates as a list for `tracey new --list`.
+pub fn list() -> String {
+    let width = TEMPLATES.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    TEMPLATES
+        .iter()
+        .map(|t| format!("{:width$}  {}\n", t.name, t.description))
+        .collect()
+}
+
+/// Write `template` into `dir`, which must not exist yet or be empty.
+/// Returns the written files.
+///
+/// r[impl cli.new]
+pub fn write(template: &Template, dir: &Path) -> Result<Vec<PathBuf>> {
+    if let Ok(mut entries) = std::fs::read_dir(dir)
+        && entries.next().is_some()
+    {
+        eyre::bail!(
+            "{} is not empty; pick a new directory for the example",
+            dir.display()
+        );
+    }
+    let mut written = Vec::with_capacity(template.files.len());
+    for (path, contents) in template.files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, contents)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.new]
+    #[tokio::test]
+    async fn templates_are_fully_traced_projects() {
+        for template in TEMPLATES {
+            let tmp = tempfile::tempdir().unwrap();
+            let dir = tmp.path().join(template.name);
+            write(template, &dir).unwrap();
+            assert!(write(template, &dir).is_err(), "{}", template.name);
+
+            let config = crate::load_config(&dir.join(".config/tracey/config.styx")).unwrap();
+            let data = crate::data::build_dashboard_data(&dir, &config, 0, true)
+                .await
+                .unwrap();
+            assert!(!data.forward_by_impl.is_empty(), "{}", template.name);
+            for (key, forward) in &data.forward_by_impl {
+                assert!(
+                    forward.rules.len() >= 4,
+                    "{}: {key:?} has {} rules",
+                    template.name,
+                    forward.rules.len()
+                );
+                let covered = forward
+                    .rules
+                    .iter()
+                    .filter(|r| !r.impl_refs.is_empty())
+                    .count();
+                assert!(covered > 0, "{}: {key:?} covers nothing", template.name);
+                if template.name == "safety-critical" {
+                    for rule in &forward.rules {
+                        assert!(
+                            !rule.impl_refs.is_empty() && !rule.verify_refs.is_empty(),
+                            "{} is not implemented and verified",
+                            rule.id
+                        );
+                    }
+                }
+                let validation = &data.validation_by_impl[key];
+                assert!(
+                    validation.errors.is_empty(),
+                    "{}: {:?}",
+                    template.name,
+                    validation.errors
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_templates_list_the_known_ones() {
+        let err = find("nope").unwrap_err().to_string();
+        assert!(err.contains("protocol-spec, safety-critical, api-contract"));
+        assert!(list().starts_with("protocol-spec  "));
+    }
+}
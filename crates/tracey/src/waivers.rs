@@ -0,0 +1,190 @@
+//! Documented exceptions to coverage checks.
+//!
+//! `.config/tracey/waivers.styx` lists rules a team has accepted leaving
+//! uncovered, each with a justification and an optional expiry date:
+//!
+//! ```styx
+//! waivers (
+//!   {
+//!     rule auth.legacy-login
+//!     reason "Removed in 3.0; see ADR-12"
+//!     expires 2026-12-31
+//!   }
+//! )
+//! ```
+//!
+//! A waived rule is left out of failing checks (tag and spec thresholds,
+//! release readiness) and listed on its own instead. Once its expiry date
+//! has passed the waiver no longer applies and the rule fails again.
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Waivers file, relative to the project root.
+pub const WAIVERS_FILE: &str = ".config/tracey/waivers.styx";
+
+/// Contents of the waivers file.
+#[derive(Debug, Clone, Default, Facet)]
+pub struct WaiversFile {
+    #[facet(default)]
+    pub waivers: Vec<Waiver>,
+}
+
+/// One accepted coverage gap.
+// r[impl config.waivers]
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct Waiver {
+    /// Rule ID without version, e.g. `auth.legacy-login`.
+    pub rule: String,
+    /// Why the gap is accepted.
+    pub reason: String,
+    /// Last day the waiver applies, as `YYYY-MM-DD`.
+    #[facet(default)]
+    pub expires: Option<String>,
+}
+
+impl Waiver {
+    /// `reason (until 2026-12-31)`
+    pub fn describe(&self) -> String {
+        match &self.expires {
+            Some(expires) => format!("{} (until {expires})", self.reason),
+            None => self.reason.clone(),
+        }
+    }
+
+    /// Whether the expiry date is before `today`.
+    pub fn is_expired(&self, today: time::Date) -> bool {
+        self.expires
+            .as_deref()
+            .and_then(parse_date)
+            .is_some_and(|expires| expires < today)
+    }
+}
+
+/// Waivers keyed by rule ID, as loaded from a project.
+#[derive(Debug, Clone, Default)]
+pub struct Waivers {
+    by_rule: BTreeMap<String, Waiver>,
+}
+
+impl Waivers {
+    /// Waiver of a rule, expired or not.
+    pub fn get(&self, base: &str) -> Option<&Waiver> {
+        self.by_rule.get(base)
+    }
+
+    /// Whether a rule has a waiver that has not expired.
+    pub fn is_waived(&self, base: &str, today: time::Date) -> bool {
+        self.get(base).is_some_and(|w| !w.is_expired(today))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_rule.is_empty()
+    }
+}
+
+/// Today's date in UTC, which waiver expiry is measured against.
+pub fn today() -> time::Date {
+    time::OffsetDateTime::now_utc().date()
+}
+
+fn waivers_path(project_root: &Path) -> PathBuf {
+    project_root.join(WAIVERS_FILE)
+}
+
+/// `2026-12-31` as a date.
+fn parse_date(value: &str) -> Option<time::Date> {
+    let mut parts = value.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    time::Date::from_calendar_date(year, time::Month::try_from(month).ok()?, day).ok()
+}
+
+/// Read the project's waivers. A missing file means no waivers; a file that
+/// doesn't parse, or a waiver without a rule or reason, with a malformed
+/// expiry date, or repeating another waiver's rule, is an error.
+pub fn load(project_root: &Path) -> Result<Waivers> {
+    let path = waivers_path(project_root);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Waivers::default()),
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    let file: WaiversFile = facet_styx::from_str(&content)
+        .wrap_err_with(|| format!("Failed to parse waivers file: {}", path.display()))?;
+
+    let mut by_rule = BTreeMap::new();
+    for waiver in file.waivers {
+        let problem = if waiver.rule.trim().is_empty() {
+            Some("a waiver has no rule".to_string())
+        } else if waiver.reason.trim().is_empty() {
+            Some(format!("the waiver of '{}' gives no reason", waiver.rule))
+        } else if let Some(expires) = waiver.expires.as_deref()
+            && parse_date(expires).is_none()
+        {
+            Some(format!(
+                "the waiver of '{}' expires on '{expires}', which is not a YYYY-MM-DD date",
+                waiver.rule
+            ))
+        } else if by_rule.contains_key(&waiver.rule) {
+            Some(format!("'{}' is waived more than once", waiver.rule))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            eyre::bail!("Invalid waivers file {}: {problem}", path.display());
+        }
+        by_rule.insert(waiver.rule.clone(), waiver);
+    }
+    Ok(Waivers { by_rule })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, content: &str) {
+        let path = waivers_path(dir);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    // r[verify config.waivers]
+    #[test]
+    fn waivers_load_and_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load(dir.path()).unwrap().is_empty());
+
+        write(
+            dir.path(),
+            "waivers (\n  {\n    rule auth.legacy\n    reason \"Going away in 3.0\"\n    expires 2026-06-30\n  }\n  {\n    rule auth.sso\n    reason \"Needs an IdP in CI\"\n  }\n)\n",
+        );
+        let waivers = load(dir.path()).unwrap();
+        let date = |s: &str| parse_date(s).unwrap();
+        assert!(waivers.is_waived("auth.legacy", date("2026-06-30")));
+        assert!(!waivers.is_waived("auth.legacy", date("2026-07-01")));
+        assert!(waivers.is_waived("auth.sso", date("2099-01-01")));
+        assert!(!waivers.is_waived("auth.login", date("2026-01-01")));
+        assert_eq!(
+            waivers.get("auth.legacy").unwrap().reason,
+            "Going away in 3.0"
+        );
+
+        write(
+            dir.path(),
+            "waivers (\n  {\n    rule auth.legacy\n    reason \"Soon\"\n    expires next-year\n  }\n)\n",
+        );
+        let message = load(dir.path()).unwrap_err().to_string();
+        assert!(message.contains("not a YYYY-MM-DD date"), "{message}");
+
+        write(
+            dir.path(),
+            "waivers (\n  {\n    rule auth.legacy\n    reason \"\"\n  }\n)\n",
+        );
+        let message = load(dir.path()).unwrap_err().to_string();
+        assert!(message.contains("gives no reason"), "{message}");
+    }
+}
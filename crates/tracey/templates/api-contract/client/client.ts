@@ -0,0 +1,41 @@
+// A client for the todo API, traced to docs/spec/todo-api.md.
+
+export interface Item {
+  id: number;
+  title: string;
+}
+
+export class TodoClient {
+  constructor(private baseUrl: string) {}
+
+  // r[impl api.items.create]
+  // r[impl api.items.title]
+  async create(title: string): Promise<Item> {
+    if (title.length < 1 || title.length > 200) {
+      throw new Error("title must be between 1 and 200 characters");
+    }
+    const response = await fetch(`${this.baseUrl}/items`, {
+      method: "POST",
+      headers: { "content-type": "application/json" },
+      body: JSON.stringify({ title }),
+    });
+    return this.parse(response, 201);
+  }
+
+  // r[impl api.items.get]
+  async get(id: number): Promise<Item | undefined> {
+    const response = await fetch(`${this.baseUrl}/items/${id}`);
+    if (response.status === 404) {
+      return undefined;
+    }
+    return this.parse(response, 200);
+  }
+
+  private async parse(response: Response, expected: number): Promise<Item> {
+    const body = await response.json();
+    if (response.status !== expected) {
+      throw new Error(body.error ?? `unexpected status ${response.status}`);
+    }
+    return body;
+  }
+}
@@ -0,0 +1,66 @@
+//! The todo server's request handling, traced to `docs/spec/todo-api.md`.
+//! Routing and HTTP are left out so the example has no dependencies.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub id: u64,
+    pub title: String,
+}
+
+/// A response: status code and JSON body.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Server {
+    items: BTreeMap<u64, Item>,
+    next_id: u64,
+}
+
+impl Server {
+    // r[impl api.items.create]
+    // r[impl api.items.title]
+    pub fn create(&mut self, title: &str) -> Response {
+        if !(1..=200).contains(&title.chars().count()) {
+            return error(400, "title must be between 1 and 200 characters");
+        }
+        self.next_id += 1;
+        let item = Item {
+            id: self.next_id,
+            title: title.to_string(),
+        };
+        self.items.insert(item.id, item.clone());
+        Response {
+            status: 201,
+            body: to_json(&item),
+        }
+    }
+
+    // r[impl api.items.get]
+    pub fn get(&self, id: u64) -> Response {
+        match self.items.get(&id) {
+            Some(item) => Response {
+                status: 200,
+                body: to_json(item),
+            },
+            None => error(404, "no such item"),
+        }
+    }
+}
+
+fn to_json(item: &Item) -> String {
+    format!(r#"{{"id":{},"title":{:?}}}"#, item.id, item.title)
+}
+
+// r[impl api.errors.body]
+fn error(status: u16, message: &str) -> Response {
+    Response {
+        status,
+        body: format!(r#"{{"error":{message:?}}}"#),
+    }
+}
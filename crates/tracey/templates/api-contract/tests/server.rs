@@ -0,0 +1,29 @@
+use todo_api::Server;
+
+// r[verify api.items.create]
+#[test]
+fn created_items_get_an_id() {
+    let mut server = Server::default();
+    let response = server.create("water the plants");
+    assert_eq!(response.status, 201);
+    assert_eq!(response.body, r#"{"id":1,"title":"water the plants"}"#);
+}
+
+// r[verify api.items.title]
+// r[verify api.errors.body]
+#[test]
+fn empty_titles_are_rejected() {
+    let mut server = Server::default();
+    let response = server.create("");
+    assert_eq!(response.status, 400);
+    assert!(response.body.starts_with(r#"{"error":"#));
+}
+
+// r[verify api.items.get]
+#[test]
+fn missing_items_are_not_found() {
+    let mut server = Server::default();
+    server.create("water the plants");
+    assert_eq!(server.get(1).status, 200);
+    assert_eq!(server.get(2).status, 404);
+}
@@ -0,0 +1,41 @@
+//! A length-prefixed framing codec, traced to `docs/spec/framing.md`.
+
+/// Largest payload a decoder accepts.
+pub const MAX_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Why a frame could not be decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The declared length is over [`MAX_LENGTH`].
+    TooLong(usize),
+}
+
+/// Prefix `payload` with its length.
+// r[impl frame.header]
+pub fn encode(payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Decode one frame from the start of `input`, returning its payload and the
+/// number of bytes consumed, or `None` when more input is needed.
+// r[impl frame.max-length]
+// r[impl frame.partial]
+pub fn decode(input: &[u8]) -> Result<Option<(&[u8], usize)>, DecodeError> {
+    let Some(header) = input.get(..4) else {
+        return Ok(None);
+    };
+    let len = u32::from_be_bytes(header.try_into().unwrap()) as usize;
+    if len > MAX_LENGTH {
+        return Err(DecodeError::TooLong(len));
+    }
+    Ok(input.get(4..4 + len).map(|payload| (payload, 4 + len)))
+}
+
+/// The reply to a `ping` frame.
+// r[impl message.ping]
+pub fn pong() -> Vec<u8> {
+    encode(&[])
+}
@@ -0,0 +1,29 @@
+use framing::{DecodeError, MAX_LENGTH, decode, encode, pong};
+
+// r[verify frame.header]
+#[test]
+fn header_is_big_endian_length() {
+    assert_eq!(encode(b"hello"), b"\x00\x00\x00\x05hello");
+}
+
+// r[verify frame.max-length]
+#[test]
+fn oversized_frames_are_rejected() {
+    let header = ((MAX_LENGTH + 1) as u32).to_be_bytes();
+    assert_eq!(decode(&header), Err(DecodeError::TooLong(MAX_LENGTH + 1)));
+}
+
+// r[verify frame.partial]
+#[test]
+fn partial_frames_need_more_input() {
+    let frame = encode(b"hello");
+    assert_eq!(decode(&frame[..2]), Ok(None));
+    assert_eq!(decode(&frame[..6]), Ok(None));
+    assert_eq!(decode(&frame), Ok(Some((&b"hello"[..], 9))));
+}
+
+// r[verify message.ping]
+#[test]
+fn pong_is_an_empty_frame() {
+    assert_eq!(pong(), [0, 0, 0, 0]);
+}
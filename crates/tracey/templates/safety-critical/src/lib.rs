@@ -0,0 +1,54 @@
+//! A brake controller, traced to `docs/spec/brake-controller.md`.
+
+/// Control cycles without a valid command before the watchdog trips.
+pub const WATCHDOG_CYCLES: u32 = 5;
+/// Largest pressure increase per control cycle, in percentage points.
+pub const MAX_RISE: u8 = 20;
+
+#[derive(Debug, Default)]
+pub struct Controller {
+    pressure: u8,
+    silent_cycles: u32,
+    fault: bool,
+}
+
+impl Controller {
+    /// Applied pressure, in percent.
+    pub fn pressure(&self) -> u8 {
+        self.pressure
+    }
+
+    pub fn in_fault(&self) -> bool {
+        self.fault
+    }
+
+    /// Run one control cycle with the command received during it, if any.
+    // r[impl brake.command.range+2]
+    // r[impl brake.command.rate]
+    // r[impl brake.fault.latch]
+    pub fn cycle(&mut self, command: Option<i32>) {
+        if self.fault {
+            return;
+        }
+        let Some(target) = command.and_then(|c| u8::try_from(c).ok()).filter(|c| *c <= 100) else {
+            self.watchdog();
+            return;
+        };
+        self.silent_cycles = 0;
+        self.pressure = target.min(self.pressure.saturating_add(MAX_RISE));
+    }
+
+    // r[impl brake.watchdog]
+    fn watchdog(&mut self) {
+        self.silent_cycles += 1;
+        if self.silent_cycles >= WATCHDOG_CYCLES {
+            self.pressure = 100;
+            self.fault = true;
+        }
+    }
+
+    /// Leave the fault state.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,50 @@
+use brake_controller::{Controller, WATCHDOG_CYCLES};
+
+// r[verify brake.command.range+2]
+#[test]
+fn out_of_range_commands_are_rejected() {
+    let mut controller = Controller::default();
+    controller.cycle(Some(10));
+    controller.cycle(Some(150));
+    controller.cycle(Some(-5));
+    assert_eq!(controller.pressure(), 10);
+}
+
+// r[verify brake.command.rate]
+#[test]
+fn pressure_rises_gradually() {
+    let mut controller = Controller::default();
+    controller.cycle(Some(100));
+    assert_eq!(controller.pressure(), 20);
+    controller.cycle(Some(100));
+    assert_eq!(controller.pressure(), 40);
+    controller.cycle(Some(0));
+    assert_eq!(controller.pressure(), 0);
+}
+
+// r[verify brake.watchdog]
+#[test]
+fn silence_applies_full_braking() {
+    let mut controller = Controller::default();
+    for _ in 0..WATCHDOG_CYCLES {
+        assert!(!controller.in_fault());
+        controller.cycle(None);
+    }
+    assert!(controller.in_fault());
+    assert_eq!(controller.pressure(), 100);
+}
+
+// r[verify brake.fault.latch]
+#[test]
+fn faults_latch_until_reset() {
+    let mut controller = Controller::default();
+    for _ in 0..WATCHDOG_CYCLES {
+        controller.cycle(None);
+    }
+    controller.cycle(Some(0));
+    assert!(controller.in_fault());
+    controller.reset();
+    controller.cycle(Some(0));
+    assert!(!controller.in_fault());
+    assert_eq!(controller.pressure(), 0);
+}
@@ -1,4 +1,5 @@
-//! Integration tests for `tracey pre-commit` and `tracey bump`.
+//! Integration tests for `tracey pre-commit`, `tracey bump`,
+//! `tracey install-hooks` and `tracey prepare-commit-msg`.
 //!
 //! Each test creates a real git repository in a temp directory, commits an
 //! initial spec file, stages a modification, then exercises the bump logic
@@ -8,8 +9,9 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
-use tracey::bump::{bump, detect_changed_rules, pre_commit};
-use tracey::config::{Config, SpecConfig};
+use tracey::bump::{Hook, HookOutcome, bump, detect_changed_rules, install_hooks, pre_commit};
+use tracey::commit_msg::{staged_coverage, write_trailer};
+use tracey::config::{Config, Impl, SpecConfig};
 
 // ============================================================================
 // Helpers
@@ -60,11 +62,10 @@ fn simple_config() -> Config {
     Config {
         specs: vec![SpecConfig {
             name: "test".to_string(),
-            prefix: None,
-            source_url: None,
             include: vec!["spec.md".to_string()],
-            impls: vec![],
+            ..Default::default()
         }],
+        ..Default::default()
     }
 }
 
@@ -304,6 +305,47 @@ async fn test_pre_commit_fails_on_unbumped_change() {
     );
 }
 
+/// A config whose spec has no implementations still loads, and its rule
+/// changes are still checked.
+#[tokio::test]
+async fn test_pre_commit_with_spec_only_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    let config_path = root.join(".config/tracey/config.styx");
+    fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+    fs::write(
+        &config_path,
+        "specs (\n  {\n    name test\n    include (spec.md)\n  }\n)\n",
+    )
+    .unwrap();
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let modified = INITIAL_SPEC.replace(
+        "Users MUST provide valid credentials to log in.",
+        "Users MUST provide valid credentials and a CAPTCHA to log in.",
+    );
+    fs::write(root.join("spec.md"), &modified).unwrap();
+    git_add(root, "spec.md");
+
+    let config = tracey::bump::load_config(&config_path).unwrap();
+    assert!(config.specs[0].impls.is_empty());
+    let passed = pre_commit(root, &config).await.unwrap();
+    assert!(!passed, "unbumped change must fail without implementations");
+
+    // A missing config checks nothing, but a broken one is an error.
+    let missing = tracey::bump::load_config(&root.join("missing.styx")).unwrap();
+    assert!(missing.specs.is_empty());
+    fs::write(
+        &config_path,
+        "specs (\n  { name test }\n  { name test }\n)\n",
+    )
+    .unwrap();
+    assert!(tracey::bump::load_config(&config_path).is_err());
+}
+
 /// Non-spec files staged alongside spec changes don't cause false positives.
 #[tokio::test]
 async fn test_non_spec_staged_files_are_ignored() {
@@ -344,7 +386,7 @@ async fn test_no_config_is_noop() {
     fs::write(root.join("spec.md"), &modified).unwrap();
     git_add(root, "spec.md");
 
-    let empty_config = Config { specs: vec![] };
+    let empty_config = Config::default();
     let changes = detect_changed_rules(root, &empty_config).await.unwrap();
     assert!(changes.is_empty(), "empty config should produce no changes");
 }
@@ -424,11 +466,10 @@ async fn test_renamed_spec_file_not_flagged() {
     let wildcard_config = Config {
         specs: vec![SpecConfig {
             name: "test".to_string(),
-            prefix: None,
-            source_url: None,
             include: vec!["**/*.md".to_string()],
-            impls: vec![],
+            ..Default::default()
         }],
+        ..Default::default()
     };
     let changes = detect_changed_rules(root, &wildcard_config).await.unwrap();
     assert!(
@@ -437,6 +478,40 @@ async fn test_renamed_spec_file_not_flagged() {
     );
 }
 
+/// Moving a spec file and editing a rule in the same commit is still caught:
+/// the new path is compared against the old path's HEAD content.
+// r[verify history.renames]
+#[tokio::test]
+async fn test_renamed_spec_file_with_edit_is_flagged() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::create_dir(root.join("docs")).unwrap();
+    fs::write(root.join("docs/spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let status = Command::new("git")
+        .args(["mv", "docs/spec.md", "docs/auth.md"])
+        .current_dir(root)
+        .status()
+        .expect("git not found");
+    assert!(status.success(), "git mv failed");
+    let modified = INITIAL_SPEC.replace(
+        "Users MUST provide valid credentials to log in.",
+        "Users MUST provide valid credentials and MFA to log in.",
+    );
+    fs::write(root.join("docs/auth.md"), &modified).unwrap();
+    git_add(root, "docs/auth.md");
+
+    let mut config = simple_config();
+    config.specs[0].include = vec!["docs/*.md".to_string()];
+    let changes = detect_changed_rules(root, &config).await.unwrap();
+    assert_eq!(changes.len(), 1, "expected the edited rule to be flagged");
+    assert_eq!(changes[0].rule_id.base, "auth.login");
+    assert_eq!(changes[0].file, Path::new("docs/auth.md"));
+}
+
 /// Running `bump` twice in a row without staging new changes is a no-op on the
 /// second call: the first bump increments the version, so the version check
 /// no longer flags the rule.
@@ -526,3 +601,177 @@ async fn test_bump_then_pre_commit_passes() {
     let passed = pre_commit(root, &config).await.unwrap();
     assert!(passed, "pre-commit should pass after bump");
 }
+
+/// Like `simple_config`, plus a Rust implementation under `src/`.
+fn config_with_impl() -> Config {
+    let mut config = simple_config();
+    config.specs[0].impls.push(Impl {
+        name: "rust".to_string(),
+        include: vec!["src/**/*.rs".to_string()],
+        exclude: vec![],
+        test_include: vec![],
+        proof_include: vec![],
+    });
+    config
+}
+
+/// The trailer reflects staged changes only, and replaces an earlier one.
+// r[verify cli.prepare-commit-msg]
+#[tokio::test]
+async fn test_prepare_commit_msg_summarizes_staged_coverage() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    fs::create_dir(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/login.rs"),
+        "// r[impl auth.login]\nfn login() {}\n",
+    )
+    .unwrap();
+    fs::write(root.join("src/session.rs"), "fn session() {}\n").unwrap();
+    git_commit_all(root, "initial");
+
+    // Staged: a new impl of auth.session and a test for auth.login.
+    fs::write(
+        root.join("src/session.rs"),
+        "// r[impl auth.session]\nfn session() {}\n\n// r[verify auth.login]\nfn test_login() {}\n",
+    )
+    .unwrap();
+    git_add(root, "src/session.rs");
+    // Unstaged: removing the only impl of auth.login must not count.
+    fs::write(root.join("src/login.rs"), "fn login() {}\n").unwrap();
+
+    let config = config_with_impl();
+    let delta = staged_coverage(root, &config).await.unwrap();
+    let trailer = delta
+        .trailer()
+        .expect("staged changes should produce a trailer");
+    assert_eq!(trailer, "+1 rule covered, 1 impl added, 1 verify added");
+
+    let message = root.join("COMMIT_EDITMSG");
+    fs::write(&message, "Add session handling\n").unwrap();
+    write_trailer(root, &message, &trailer).unwrap();
+    write_trailer(root, &message, &trailer).unwrap();
+    assert_eq!(
+        fs::read_to_string(&message).unwrap(),
+        format!("Add session handling\n\nTracey-Coverage: {trailer}\n")
+    );
+}
+
+/// Staging nothing spec-relevant produces no trailer.
+#[tokio::test]
+async fn test_prepare_commit_msg_ignores_irrelevant_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    fs::create_dir(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/login.rs"),
+        "// r[impl auth.login]\nfn login() {}\n",
+    )
+    .unwrap();
+    git_commit_all(root, "initial");
+
+    // Moving the reference and touching an unrelated file are not changes.
+    fs::write(
+        root.join("src/login.rs"),
+        "fn helper() {}\n\n// r[impl auth.login]\nfn login() {}\n",
+    )
+    .unwrap();
+    fs::write(root.join("README.md"), "# Project\n").unwrap();
+    git_add(root, ".");
+
+    let delta = staged_coverage(root, &config_with_impl()).await.unwrap();
+    assert!(delta.is_empty(), "unexpected delta: {delta:?}");
+    assert_eq!(delta.trailer(), None);
+}
+
+/// Installing hooks writes an executable script once and leaves foreign
+/// hooks alone unless forced.
+// r[verify cli.install-hooks]
+#[test]
+fn test_install_hooks() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    git_init(root);
+
+    let hooks = root.join(".git/hooks");
+    let outcomes = install_hooks(root, &[Hook::PreCommit], false).unwrap();
+    assert_eq!(
+        outcomes,
+        vec![HookOutcome::Installed(hooks.join("pre-commit"))]
+    );
+    let script = fs::read_to_string(hooks.join("pre-commit")).unwrap();
+    assert!(script.starts_with("#!/bin/sh\n"), "{script}");
+    assert!(script.contains("exec tracey pre-commit\n"), "{script}");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(hooks.join("pre-commit"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+
+    // Running again is a no-op.
+    let outcomes = install_hooks(root, &[Hook::PreCommit], false).unwrap();
+    assert!(matches!(outcomes[0], HookOutcome::AlreadyInstalled(_)));
+
+    // Someone else's hook is kept unless forced.
+    fs::write(hooks.join("prepare-commit-msg"), "#!/bin/sh\nexit 0\n").unwrap();
+    let outcomes = install_hooks(root, &[Hook::PrepareCommitMsg], false).unwrap();
+    assert!(matches!(outcomes[0], HookOutcome::Skipped(_)));
+    assert_eq!(
+        fs::read_to_string(hooks.join("prepare-commit-msg")).unwrap(),
+        "#!/bin/sh\nexit 0\n"
+    );
+    let outcomes = install_hooks(root, &[Hook::PrepareCommitMsg], true).unwrap();
+    assert!(matches!(outcomes[0], HookOutcome::Installed(_)));
+    let script = fs::read_to_string(hooks.join("prepare-commit-msg")).unwrap();
+    assert!(
+        script.contains("exec tracey prepare-commit-msg \"$@\"\n"),
+        "{script}"
+    );
+}
+
+/// A project below the top of the work tree is pinned with `--root`.
+#[test]
+fn test_install_hooks_in_subdirectory() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    git_init(root);
+    let project = root.join("tools/spec");
+    fs::create_dir_all(&project).unwrap();
+
+    install_hooks(&project, &[Hook::PreCommit], false).unwrap();
+    let script = fs::read_to_string(root.join(".git/hooks/pre-commit")).unwrap();
+    assert!(
+        script.contains("exec tracey --root 'tools/spec' pre-commit\n"),
+        "{script}"
+    );
+}
+
+/// A root whose path contains a quote still reaches tracey as one argument.
+#[test]
+fn test_install_hooks_quotes_the_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    git_init(root);
+    let project = root.join("it's here/spec");
+    fs::create_dir_all(&project).unwrap();
+
+    install_hooks(&project, &[Hook::PreCommit], false).unwrap();
+    let script = fs::read_to_string(root.join(".git/hooks/pre-commit")).unwrap();
+    let echo = script.replace("exec tracey", "printf '%s\\n'");
+    let output = Command::new("sh").arg("-c").arg(&echo).output().unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "--root\nit's here/spec\npre-commit\n",
+        "{script}"
+    );
+}
@@ -14,9 +14,8 @@ static TEST_TRACING: Once = Once::new();
 
 pub fn init_test_tracing() {
     TEST_TRACING.call_once(|| {
-        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-            EnvFilter::new("info,tracey::daemon=debug,tracey::daemon::client=debug")
-        });
+        let filter = EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new("info,tracey::daemon=debug,tracey_client=debug"));
         if let Err(e) = tracing_subscriber::fmt()
             .with_env_filter(filter)
             .with_test_writer()
@@ -0,0 +1,2 @@
+/// r[impl api.handler]
+pub fn users() {}
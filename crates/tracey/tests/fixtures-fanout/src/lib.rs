@@ -0,0 +1,5 @@
+/// r[impl storage.backend]
+pub fn flush() {}
+
+/// r[impl plain.rule]
+pub fn plain() {}
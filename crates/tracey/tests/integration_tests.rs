@@ -227,6 +227,782 @@ async fn test_rule_not_found() {
     assert!(rule.is_none(), "Expected nonexistent rule to return None");
 }
 
+// r[verify markdown.related.prose]
+#[tokio::test]
+async fn test_rule_records_prose_mentions_as_related() {
+    let service = create_test_service().await;
+    let info = rpc(service.client.rule(rid("auth.logout")).await).expect("auth.logout exists");
+    assert_eq!(info.related, vec![rid("auth.login")]);
+
+    let info = rpc(service.client.rule(rid("auth.login")).await).expect("auth.login exists");
+    assert!(info.related.is_empty(), "related: {:?}", info.related);
+}
+
+// r[verify markdown.related.links]
+#[tokio::test]
+async fn test_spec_html_links_related_rules() {
+    let service = create_test_service().await;
+    let spec = rpc(service
+        .client
+        .spec_content("test".to_string(), "rust".to_string())
+        .await)
+    .expect("spec content");
+    let html: String = spec.sections.iter().map(|s| s.html.as_str()).collect();
+    assert!(
+        html.contains(r#"<div class="req-related">Related: <a class="rule-ref" href="/test/rust/spec#r--auth.login" data-rule="auth.login">auth.login</a></div>"#),
+        "expected related link in spec HTML"
+    );
+}
+
+// r[verify dashboard.editing.copy.snippet]
+#[tokio::test]
+async fn test_spec_html_has_annotate_button_next_to_impl_refs() {
+    let service = create_test_service().await;
+    let spec = rpc(service
+        .client
+        .spec_content("test".to_string(), "rust".to_string())
+        .await)
+    .expect("spec content");
+    let html: String = spec.sections.iter().map(|s| s.html.as_str()).collect();
+    let container = html
+        .split(r#"<div class="req-container"#)
+        .find(|c| c.contains(r#"<button class="req-badge req-snippet" data-req-id="auth.login""#))
+        .expect("Annotate button for auth.login in spec HTML");
+    // The dashboard picks the snippet language from the first impl badge.
+    let impl_file = container
+        .split_once(r#"<a class="req-badge req-impl""#)
+        .and_then(|(_, badge)| badge.split_once(r#"data-file=""#))
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(file, _)| file)
+        .expect("impl badge with data-file");
+    assert!(impl_file.ends_with(".rs"), "{impl_file}");
+}
+
+// r[verify markdown.examples.html]
+#[tokio::test]
+async fn test_spec_html_lists_rule_examples() {
+    let service = create_test_service().await;
+    let spec = rpc(service
+        .client
+        .spec_content("test".to_string(), "rust".to_string())
+        .await)
+    .expect("spec content");
+    let html: String = spec.sections.iter().map(|s| s.html.as_str()).collect();
+    let examples = html
+        .split_once(r#"<details class="req-examples"><summary>Examples</summary>"#)
+        .expect("examples section in spec HTML")
+        .1;
+    assert!(examples.contains(r#"<div class="req-example req-example-good">"#));
+    assert!(examples.contains("auth.expired"));
+    assert!(examples.contains(r#"<div class="req-example req-example-bad">"#));
+    assert!(!html.contains("e[error.codes"), "marker leaked into HTML");
+}
+
+// ============================================================================
+// Compare API Tests
+// ============================================================================
+
+// r[verify daemon.roam.compare]
+#[tokio::test]
+async fn test_compare_same_impl() {
+    let service = create_test_service().await;
+    let comparison = rpc(service
+        .client
+        .compare(CompareRequest {
+            spec: None,
+            impls: vec!["rust".to_string(), "rust".to_string()],
+        })
+        .await);
+
+    assert_eq!(comparison.spec, "test");
+    assert!(!comparison.rules.is_empty(), "Expected rules to compare");
+    let login = comparison
+        .rules
+        .iter()
+        .find(|r| r.id == rid("auth.login"))
+        .expect("auth.login should be compared");
+    assert!(login.coverage[0].impl_count > 0);
+    assert_eq!(login.coverage[0].impl_count, login.coverage[1].impl_count);
+    assert_eq!(comparison.gaps.len(), 2);
+    assert!(comparison.gaps.iter().all(|gap| gap.missing.is_empty()));
+}
+
+#[tokio::test]
+async fn test_compare_unknown_impl() {
+    let service = create_test_service().await;
+    let result = service
+        .client
+        .compare(CompareRequest {
+            spec: Some("test".to_string()),
+            impls: vec!["rust".to_string(), "cobol".to_string()],
+        })
+        .await;
+
+    let Err(roam::RoamError::User(message)) = result else {
+        panic!("Expected error for unknown implementation");
+    };
+    assert!(message.contains("cobol"), "unexpected message: {message}");
+    assert!(message.contains("rust"), "unexpected message: {message}");
+}
+
+// r[verify daemon.roam.compare]
+#[tokio::test]
+async fn test_compare_needs_two_impls() {
+    let service = create_test_service().await;
+    let result = service
+        .client
+        .compare(CompareRequest {
+            spec: None,
+            impls: vec!["rust".to_string()],
+        })
+        .await;
+
+    let Err(roam::RoamError::User(message)) = result else {
+        panic!("Expected error for a single implementation");
+    };
+    assert!(
+        message.contains("at least two"),
+        "unexpected message: {message}"
+    );
+}
+
+// r[verify dashboard.coverage.compare]
+#[tokio::test]
+async fn test_compare_reports_rules_each_impl_is_missing() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path().to_path_buf();
+    std::fs::create_dir_all(root.join("rs")).unwrap();
+    std::fs::create_dir_all(root.join("go")).unwrap();
+    std::fs::create_dir_all(root.join("py")).unwrap();
+    std::fs::write(
+        root.join("config.styx"),
+        r#"
+specs (
+  {
+    name proto
+    include (proto.md)
+    impls (
+      {name rust, include (rs/**/*.rs)}
+      {name go, include (go/**/*.go)}
+      {name python, include (py/**/*.py)}
+    )
+  }
+)
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("proto.md"),
+        "r[frame.header]\nHeader.\n\nr[frame.body]\nBody.\n\nr[frame.trailer]\nTrailer.\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("rs/lib.rs"),
+        "// r[impl frame.header]\nfn header() {}\n\n// r[impl frame.body]\nfn body() {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("go/main.go"),
+        "// r[impl frame.header]\nfunc header() {}\n\n// r[impl frame.trailer]\nfunc trailer() {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("py/frames.py"),
+        "# r[impl frame.header]\ndef header(): pass\n\n# r[impl frame.body]\ndef body(): pass\n",
+    )
+    .unwrap();
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(root.clone(), root.join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = common::create_test_rpc_service(tracey::daemon::TraceyService::new(engine)).await;
+    let comparison = rpc(service
+        .client
+        .compare(CompareRequest {
+            spec: None,
+            impls: vec!["rust".to_string(), "go".to_string(), "python".to_string()],
+        })
+        .await);
+
+    assert_eq!(comparison.impls, ["rust", "go", "python"]);
+    let mut table: Vec<_> = comparison
+        .rules
+        .iter()
+        .map(|r| {
+            let counts: Vec<_> = r.coverage.iter().map(|c| c.impl_count).collect();
+            (r.id.to_string(), counts)
+        })
+        .collect();
+    table.sort();
+    assert_eq!(
+        table,
+        [
+            ("frame.body".to_string(), vec![1, 0, 1]),
+            ("frame.header".to_string(), vec![1, 1, 1]),
+            ("frame.trailer".to_string(), vec![0, 1, 0]),
+        ]
+    );
+    let gaps: Vec<_> = comparison
+        .gaps
+        .iter()
+        .map(|gap| (gap.impl_name.as_str(), gap.missing.clone()))
+        .collect();
+    assert_eq!(
+        gaps,
+        [
+            ("rust", vec![rid("frame.trailer")]),
+            ("go", vec![rid("frame.body")]),
+            ("python", vec![rid("frame.trailer")]),
+        ]
+    );
+}
+
+/// A project with a `documents` verb in the `docs` role: `auth.login` is
+/// implemented and documented, `auth.logout` only implemented.
+async fn create_docs_role_service() -> (tempfile::TempDir, common::RpcTestService) {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path().to_path_buf();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("config.styx"),
+        r#"
+verbs {
+  documents docs
+}
+specs (
+  {
+    name app
+    include (spec.md)
+    impls ({name rust, include (src/**/*.rs)})
+  }
+)
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("spec.md"),
+        "# Auth\n\nr[auth.login]\nLog in.\n\nr[auth.logout]\nLog out.\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "// r[impl auth.login]\n// r[documents auth.login]\nfn login() {}\n\n\
+         // r[impl auth.logout]\nfn logout() {}\n",
+    )
+    .unwrap();
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(root.clone(), root.join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = common::create_test_rpc_service(tracey::daemon::TraceyService::new(engine)).await;
+    (temp, service)
+}
+
+// r[verify dashboard.spec.verb-legend]
+#[tokio::test]
+async fn test_outline_reports_docs_coverage_and_verbs_by_role() {
+    let (_temp, service) = create_docs_role_service().await;
+
+    let config = rpc(service.client.config().await);
+    let docs_verbs: Vec<_> = config
+        .verbs
+        .iter()
+        .filter(|v| v.role == "docs")
+        .map(|v| v.name.as_str())
+        .collect();
+    assert_eq!(docs_verbs, ["documents"]);
+    assert!(
+        config
+            .verbs
+            .iter()
+            .any(|v| v.name == "impl" && v.role == "impl")
+    );
+
+    let spec = rpc(service
+        .client
+        .spec_content("app".to_string(), "rust".to_string())
+        .await)
+    .expect("spec content");
+    let auth = spec
+        .outline
+        .iter()
+        .find(|e| e.title == "Auth")
+        .expect("Auth heading");
+    assert_eq!(auth.aggregated.total, 2);
+    assert_eq!(auth.aggregated.impl_count, 2);
+    assert_eq!(auth.aggregated.docs_count, 1);
+}
+
+// ============================================================================
+// Scoped Reload Tests
+// ============================================================================
+
+// r[verify daemon.reload.scope]
+#[tokio::test]
+async fn test_reload_scope_only_rebuilds_the_requested_spec() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path().to_path_buf();
+    std::fs::create_dir_all(root.join("a")).unwrap();
+    std::fs::create_dir_all(root.join("b")).unwrap();
+    std::fs::write(
+        root.join("config.styx"),
+        r#"
+specs (
+  {
+    name alpha
+    include (alpha.md)
+    impls ({name rust, include (a/**/*.rs)})
+  }
+  {
+    name beta
+    include (beta.md)
+    impls ({name rust, include (b/**/*.rs)})
+  }
+)
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("alpha.md"), "a[one.rule]\nOne.\n").unwrap();
+    std::fs::write(root.join("beta.md"), "b[two.rule]\nTwo.\n").unwrap();
+    std::fs::write(root.join("a/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(root.join("b/lib.rs"), "fn b() {}\n").unwrap();
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(root.clone(), root.join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = common::create_test_rpc_service(tracey::daemon::TraceyService::new(engine)).await;
+    let impl_count = |spec: &'static str| {
+        let client = &service.client;
+        async move {
+            let forward = rpc(client.forward(spec.to_string(), "rust".to_string()).await)
+                .expect("forward data");
+            forward.rules[0].impl_refs.len()
+        }
+    };
+
+    std::fs::write(root.join("a/lib.rs"), "// a[impl one.rule]\nfn a() {}\n").unwrap();
+    std::fs::write(root.join("b/lib.rs"), "// b[impl two.rule]\nfn b() {}\n").unwrap();
+    let response = rpc(service
+        .client
+        .reload_scope(ReloadScopeRequest {
+            spec: Some("alpha".to_string()),
+            impl_name: None,
+            paths: vec!["a/lib.rs".to_string()],
+        })
+        .await);
+    let changed: Vec<_> = response
+        .changed
+        .iter()
+        .map(|c| format!("{}/{}", c.spec, c.impl_name))
+        .collect();
+    assert_eq!(changed, ["alpha/rust"]);
+    assert_eq!(impl_count("alpha").await, 1);
+    assert_eq!(impl_count("beta").await, 0, "beta is outside the scope");
+
+    rpc(service.client.reload().await);
+    assert_eq!(impl_count("beta").await, 1);
+
+    let result = service
+        .client
+        .reload_scope(ReloadScopeRequest {
+            spec: Some("gamma".to_string()),
+            impl_name: None,
+            paths: Vec::new(),
+        })
+        .await;
+    let Err(roam::RoamError::User(message)) = result else {
+        panic!("Expected error for unknown spec");
+    };
+    assert!(message.contains("alpha, beta"), "{message}");
+}
+
+// r[verify daemon.reload.vfs-scope]
+#[tokio::test]
+async fn test_vfs_change_only_rebuilds_the_including_impl() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path().to_path_buf();
+    std::fs::create_dir_all(root.join("a")).unwrap();
+    std::fs::create_dir_all(root.join("b")).unwrap();
+    std::fs::write(
+        root.join("config.styx"),
+        r#"
+specs (
+  {
+    name alpha
+    include (alpha.md)
+    impls ({name rust, include (a/**/*.rs)})
+  }
+  {
+    name beta
+    include (beta.md)
+    impls ({name rust, include (b/**/*.rs)})
+  }
+)
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("alpha.md"), "a[one.rule]\nOne.\n").unwrap();
+    std::fs::write(root.join("beta.md"), "b[two.rule]\nTwo.\n").unwrap();
+    std::fs::write(root.join("a/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(root.join("b/lib.rs"), "fn b() {}\n").unwrap();
+
+    let engine = tracey::daemon::Engine::new(root.clone(), root.join("config.styx"))
+        .await
+        .expect("Failed to create engine");
+
+    // Edited on disk without telling the engine: only a full rebuild sees it.
+    std::fs::write(root.join("b/lib.rs"), "// b[impl two.rule]\nfn b() {}\n").unwrap();
+    engine
+        .vfs_change(
+            root.join("a/lib.rs"),
+            "// a[impl one.rule]\nfn a() {}\n".to_string(),
+        )
+        .await;
+
+    let data = engine.data().await;
+    let impl_count = |spec: &str| {
+        data.forward_by_impl[&(spec.to_string(), "rust".to_string())].rules[0]
+            .impl_refs
+            .len()
+    };
+    assert_eq!(impl_count("alpha"), 1);
+    assert_eq!(impl_count("beta"), 0, "beta does not include a/lib.rs");
+}
+
+// r[verify daemon.reload.incremental]
+#[tokio::test]
+async fn test_rebuild_with_changes_only_rereads_changed_files() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path().to_path_buf();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("config.styx"),
+        "specs ({name test, include (spec.md), impls ({name rust, include (src/**/*.rs)})})\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("spec.md"),
+        "r[one.rule]\nOne.\n\nr[two.rule]\nTwo.\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/one.rs"), "fn one() {}\n").unwrap();
+    std::fs::write(root.join("src/two.rs"), "fn two() {}\n").unwrap();
+
+    let engine = tracey::daemon::Engine::new(root.clone(), root.join("config.styx"))
+        .await
+        .expect("Failed to create engine");
+    let impl_counts = |data: std::sync::Arc<tracey::data::DashboardData>| {
+        data.forward_by_impl[&("test".to_string(), "rust".to_string())]
+            .rules
+            .iter()
+            .map(|rule| rule.impl_refs.len())
+            .collect::<Vec<_>>()
+    };
+
+    // Both files change, but only one is reported: the other keeps the
+    // references extracted from it before, as it is not read again.
+    std::fs::write(
+        root.join("src/one.rs"),
+        "// r[impl one.rule]\nfn one() {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/two.rs"),
+        "// r[impl two.rule]\nfn two() {}\n",
+    )
+    .unwrap();
+    engine
+        .rebuild_with_changes(&[root.join("src/one.rs")])
+        .await
+        .unwrap();
+    assert_eq!(impl_counts(engine.data().await), [1, 0]);
+
+    // A rebuild without a list of changes checks every file.
+    engine.rebuild().await.unwrap();
+    assert_eq!(impl_counts(engine.data().await), [1, 1]);
+}
+
+// r[verify daemon.reload.watcher-scope]
+#[test]
+fn test_rebuild_scope_for_changed_paths() {
+    use tracey::data::RebuildScope;
+
+    let config = tracey::load_config(&fixtures_dir().join("config.styx")).unwrap();
+    let scope = |paths: &[&str]| {
+        let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+        RebuildScope::for_paths(&config, &paths)
+    };
+    let only = |spec: &str, impl_name: Option<&str>| RebuildScope {
+        spec: Some(spec.to_string()),
+        impl_name: impl_name.map(str::to_string),
+    };
+
+    assert_eq!(scope(&["spec.md"]), only("test", None));
+    assert_eq!(scope(&["other-spec.md"]), only("other", None));
+    // Both specs' rust implementations include src/.
+    assert_eq!(
+        scope(&["src/lib.rs"]),
+        RebuildScope {
+            spec: None,
+            impl_name: Some("rust".to_string()),
+        }
+    );
+    assert!(scope(&["spec.md", "other-spec.md"]).is_everything());
+    assert!(scope(&["README.md"]).is_everything());
+}
+
+// ============================================================================
+// Refs-in API Tests
+// ============================================================================
+
+// r[verify daemon.roam.refs-in]
+#[tokio::test]
+async fn test_refs_in_directory() {
+    let service = create_test_service_named("fanout").await;
+    let response = rpc(service
+        .client
+        .refs_in(RefsInRequest {
+            path: "./src/handlers/".to_string(),
+        })
+        .await);
+
+    assert_eq!(response.path, "src/handlers");
+    assert_eq!(response.files, 2);
+    assert_eq!(response.total, 2);
+    assert_eq!(response.rules.len(), 1);
+    let rule = &response.rules[0];
+    assert_eq!(rule.id, rid("api.handler"));
+    assert_eq!(rule.verbs.len(), 1);
+    assert_eq!(rule.verbs[0].verb, "impl");
+    let files: Vec<&str> = rule.verbs[0]
+        .locations
+        .iter()
+        .map(|l| l.file.as_str())
+        .collect();
+    assert_eq!(files, ["src/handlers/posts.rs", "src/handlers/users.rs"]);
+}
+
+// r[verify dashboard.sources.dir-refs]
+#[tokio::test]
+async fn test_refs_in_folder_links_each_annotation_to_its_line() {
+    let service = create_test_service().await;
+    let response = rpc(service
+        .client
+        .refs_in(RefsInRequest {
+            path: "src".to_string(),
+        })
+        .await);
+
+    let login = response
+        .rules
+        .iter()
+        .find(|r| r.id == rid("auth.login"))
+        .expect("auth.login is referenced under src");
+    let verbs: Vec<_> = login
+        .verbs
+        .iter()
+        .map(|v| {
+            let locations: Vec<_> = v
+                .locations
+                .iter()
+                .map(|l| format!("{}:{}", l.file, l.line))
+                .collect();
+            (v.verb.as_str(), locations)
+        })
+        .collect();
+    assert_eq!(
+        verbs,
+        [
+            ("impl", vec!["src/lib.rs:5".to_string()]),
+            (
+                "verify",
+                vec!["src/tests.rs:5".to_string(), "src/tests.rs:12".to_string()]
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_refs_in_matches_whole_components() {
+    let service = create_test_service_named("fanout").await;
+    let everything = rpc(service
+        .client
+        .refs_in(RefsInRequest {
+            path: String::new(),
+        })
+        .await);
+    assert_eq!(everything.files, 3);
+    assert_eq!(everything.total, 4);
+
+    // `src/hand` is a prefix of `src/handlers` but not a path component.
+    let partial = rpc(service
+        .client
+        .refs_in(RefsInRequest {
+            path: "src/hand".to_string(),
+        })
+        .await);
+    assert_eq!(partial.total, 0);
+    assert!(partial.rules.is_empty());
+
+    let file = rpc(service
+        .client
+        .refs_in(RefsInRequest {
+            path: "src/lib.rs".to_string(),
+        })
+        .await);
+    assert_eq!(file.files, 1);
+    assert_eq!(file.total, 2);
+}
+
+// r[verify daemon.roam.breakdown]
+#[tokio::test]
+async fn test_breakdown_groups_by_directory_and_crate() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let write = |path: &str, contents: &str| {
+        let path = temp.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "spec.md",
+        "r[net.send]\nThe client MUST send.\n\nr[net.recv]\nThe client MUST receive.\n",
+    );
+    write("crates/net/Cargo.toml", "[package]\nname = \"net\"\n");
+    write(
+        "crates/net/src/lib.rs",
+        "// r[impl net.send]\npub fn send() {}\n\n// r[verify net.send]\nfn test_send() {}\n",
+    );
+    write(
+        "crates/net/src/recv/mod.rs",
+        "// r[impl net.recv]\npub fn recv() {}\n",
+    );
+    write("crates/util/Cargo.toml", "[package]\nname = \"util\"\n");
+    write("crates/util/src/lib.rs", "pub fn helper() {}\n");
+    write(
+        "config.styx",
+        "specs (\n  {\n    name test\n    include (spec.md)\n    impls ({name rust, include (crates/**/*.rs)})\n  }\n)\n",
+    );
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+    let breakdown = |by: &str, depth: Option<usize>| BreakdownRequest {
+        spec: None,
+        impl_name: None,
+        by: Some(by.to_string()),
+        depth,
+    };
+
+    let by_crate = rpc(service.client.breakdown(breakdown("crate", None)).await);
+    assert_eq!(by_crate.by, "crate");
+    let paths: Vec<&str> = by_crate.groups.iter().map(|g| g.path.as_str()).collect();
+    assert_eq!(paths, ["crates/net", "crates/util"]);
+    let net = &by_crate.groups[0];
+    assert_eq!(net.files, 2);
+    assert_eq!(net.impl_count, 2);
+    assert_eq!(net.verify_count, 1);
+    assert_eq!(net.implemented, [rid("net.recv"), rid("net.send")]);
+    assert_eq!(net.verified, [rid("net.send")]);
+    // A crate without references still gets a row.
+    let util = &by_crate.groups[1];
+    assert_eq!(util.files, 1);
+    assert_eq!(util.impl_count + util.verify_count, 0);
+    assert!(util.implemented.is_empty());
+
+    let by_dir = rpc(service.client.breakdown(breakdown("dir", None)).await);
+    let paths: Vec<&str> = by_dir.groups.iter().map(|g| g.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["crates/net/src", "crates/net/src/recv", "crates/util/src"]
+    );
+
+    let shallow = rpc(service.client.breakdown(breakdown("dir", Some(1))).await);
+    assert_eq!(shallow.groups.len(), 1);
+    assert_eq!(shallow.groups[0].path, "crates");
+    assert_eq!(shallow.groups[0].files, 3);
+
+    let Err(roam::RoamError::User(message)) =
+        service.client.breakdown(breakdown("module", None)).await
+    else {
+        panic!("Expected error for unknown grouping");
+    };
+    assert!(message.contains("module"), "unexpected message: {message}");
+}
+
+// r[verify daemon.vfs.sync]
+#[tokio::test]
+async fn test_vfs_sync_returns_missing_and_stale_files() {
+    let service = create_test_service().await;
+    let hash = |content: &str| blake3::hash(content.as_bytes()).to_hex().to_string();
+    let open = fixtures_dir().join("src/open.rs").display().to_string();
+    let unchanged = fixtures_dir()
+        .join("src/unchanged.rs")
+        .display()
+        .to_string();
+    let edited = fixtures_dir().join("src/edited.rs").display().to_string();
+    rpc(service
+        .client
+        .vfs_open(unchanged.clone(), "// same\n".to_string())
+        .await);
+    rpc(service
+        .client
+        .vfs_open(edited.clone(), "// old\n".to_string())
+        .await);
+
+    let sync = |entries: Vec<(&String, &str)>| VfsSyncRequest {
+        entries: entries
+            .into_iter()
+            .map(|(path, content)| VfsSyncEntry {
+                path: path.clone(),
+                hash: hash(content),
+            })
+            .collect(),
+    };
+    let response = rpc(service
+        .client
+        .vfs_sync(sync(vec![
+            (&open, "// new\n"),
+            (&unchanged, "// same\n"),
+            (&edited, "// new\n"),
+        ]))
+        .await);
+    let mut needed = response.needed;
+    needed.sort();
+    let mut expected = vec![edited.clone(), open.clone()];
+    expected.sort();
+    assert_eq!(needed, expected);
+
+    // Syncing does not touch the overlay, so once the client sends the
+    // requested files everything converges.
+    rpc(service
+        .client
+        .vfs_open(open.clone(), "// new\n".to_string())
+        .await);
+    rpc(service
+        .client
+        .vfs_change(edited.clone(), "// new\n".to_string())
+        .await);
+    let response = rpc(service
+        .client
+        .vfs_sync(sync(vec![
+            (&open, "// new\n"),
+            (&unchanged, "// same\n"),
+            (&edited, "// new\n"),
+        ]))
+        .await);
+    assert!(response.needed.is_empty(), "{:?}", response.needed);
+}
+
 // ============================================================================
 // Config API Tests
 // ============================================================================
@@ -427,6 +1203,134 @@ specs (
     );
 }
 
+// r[verify config.spec.sandbox]
+#[tokio::test]
+async fn test_sandboxed_spec_html_is_sanitized() {
+    let temp = common::create_temp_project();
+    let spec_dir = temp.path().join("vendor");
+    std::fs::create_dir_all(&spec_dir).expect("Failed to create vendor dir");
+    std::fs::write(
+        spec_dir.join("spec.md"),
+        r#"# Vendor
+
+r[vendor.rule]
+Click <a href="javascript:alert(1)" onclick="steal()">here</a>.<script>alert(2)</script>
+"#,
+    )
+    .expect("Failed to write spec");
+    std::fs::write(
+        temp.path().join("config.styx"),
+        r#"
+specs (
+  {
+    name vendor
+    include (vendor/*.md)
+    sandbox {}
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to write config");
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let rpc_service = common::create_test_rpc_service(service).await;
+
+    let forward = rpc(rpc_service
+        .client
+        .forward("vendor".to_string(), "rust".to_string())
+        .await)
+    .expect("Expected forward data");
+    let spec = rpc(rpc_service
+        .client
+        .spec_content("vendor".to_string(), "rust".to_string())
+        .await)
+    .expect("Expected spec content");
+
+    let spec_html: String = spec.sections.iter().map(|s| s.html.as_str()).collect();
+    for html in [forward.rules[0].html.as_str(), spec_html.as_str()] {
+        assert!(html.contains(">here</a>"), "{html}");
+        assert!(!html.contains("javascript:"), "{html}");
+        assert!(!html.contains("onclick"), "{html}");
+        assert!(!html.contains("<script"), "{html}");
+    }
+    assert!(spec.head_injections.is_empty());
+}
+
+// r[verify daemon.audit.record]
+// r[verify daemon.audit.query]
+#[tokio::test]
+async fn test_mutations_are_recorded_in_the_audit_log() {
+    let (temp, service) = create_isolated_test_service().await;
+    assert!(rpc(service.client.audit(10).await).is_empty());
+
+    let hash = |content: &[u8]| blake3::hash(content).to_hex().to_string();
+    let spec_path = temp.path().join("spec.md");
+    let before = std::fs::read_to_string(&spec_path).unwrap();
+    service
+        .client
+        .update_file_range(UpdateFileRangeRequest {
+            path: "spec.md".to_string(),
+            start: 0,
+            end: 0,
+            content: "<!-- edited -->\n".to_string(),
+            file_hash: hash(before.as_bytes()),
+            client: None,
+        })
+        .await
+        .expect("update_file_range failed");
+    let after = std::fs::read(&spec_path).unwrap();
+
+    let config_path = temp.path().join("config.styx");
+    let config_before = std::fs::read(&config_path).unwrap();
+    rpc(service
+        .client
+        .config_add_include(ConfigPatternRequest {
+            spec: Some("test".to_string()),
+            impl_name: Some("rust".to_string()),
+            pattern: "lib/**/*.rs".to_string(),
+            client: Some("dashboard".to_string()),
+        })
+        .await);
+    let config_after = std::fs::read(&config_path).unwrap();
+
+    let entries = rpc(service.client.audit(10).await);
+    assert_eq!(entries.len(), 2, "{entries:?}");
+    let (config_entry, edit) = (&entries[0], &entries[1]);
+
+    assert_eq!(config_entry.action, "config_add_include");
+    assert_eq!(config_entry.client, "dashboard");
+    assert_eq!(config_entry.path, "config.styx");
+    assert_eq!(config_entry.before_hash, hash(&config_before));
+    assert_eq!(config_entry.after_hash, hash(&config_after));
+    assert_eq!(
+        config_entry.detail.as_deref(),
+        Some("test/rust: include lib/**/*.rs")
+    );
+
+    assert_eq!(edit.action, "update_file_range");
+    assert_eq!(edit.client, "unknown");
+    assert_eq!(edit.path, "spec.md");
+    assert_eq!(edit.before_hash, hash(before.as_bytes()));
+    assert_eq!(edit.after_hash, hash(&after));
+    assert!(edit.timestamp <= config_entry.timestamp);
+
+    let latest = rpc(service.client.audit(1).await);
+    assert_eq!(latest, entries[..1]);
+    let log = std::fs::read_to_string(temp.path().join(tracey::daemon::audit::AUDIT_FILE)).unwrap();
+    assert_eq!(log.lines().count(), 2);
+}
+
 // ============================================================================
 // LSP API Tests
 // ============================================================================
@@ -453,6 +1357,28 @@ async fn test_lsp_hover_on_reference() {
     assert!(!info.raw.is_empty(), "Expected rule raw markdown in hover");
 }
 
+// r[verify lsp.hover.examples]
+#[tokio::test]
+async fn test_lsp_hover_includes_examples() {
+    let service = create_test_service().await;
+
+    let content = std::fs::read_to_string(fixtures_dir().join("src/lib.rs")).unwrap();
+
+    let req = LspPositionRequest {
+        path: fixtures_dir().join("src/lib.rs").display().to_string(),
+        content: content.to_string(),
+        line: 33,      // r[impl error.codes] is on line 34 (0-indexed: 33)
+        character: 16, // Position within "error.codes"
+    };
+
+    let info = rpc(service.client.lsp_hover(req).await).expect("hover for error.codes");
+    assert_eq!(info.rule_id, "error.codes");
+    let kinds: Vec<_> = info.examples.iter().map(|e| e.kind.as_str()).collect();
+    assert_eq!(kinds, ["good", "bad"]);
+    assert!(info.examples[0].markdown.contains("```json"));
+    assert_eq!(info.examples[1].markdown, "A bare message with no code.");
+}
+
 #[tokio::test]
 async fn test_lsp_hover_outside_reference() {
     let service = create_test_service().await;
@@ -806,16 +1732,327 @@ async fn test_validate_returns_results() {
         impl_name: Some("rust".to_string()),
     };
 
-    let result = rpc(service.client.validate(req).await);
+    let result = rpc(service.client.validate(req).await);
+
+    assert_eq!(result.spec, "test");
+    assert_eq!(result.impl_name, "rust");
+    // The fixture has valid data, so should have no errors (or minimal)
+}
+
+#[tokio::test]
+async fn test_validate_reports_include_unparseable_files() {
+    let temp = common::create_temp_project();
+    std::fs::write(
+        temp.path().join("config.styx"),
+        r#"
+specs (
+  {
+    name test
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (
+          src/**/*.rs
+          justfile
+        )
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to write config");
+    std::fs::write(
+        temp.path().join("justfile"),
+        r#"# r[impl auth.login]
+run:
+  @echo "hello"
+"#,
+    )
+    .expect("Failed to write justfile");
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+
+    let result = rpc(service
+        .client
+        .validate(ValidateRequest {
+            spec: Some("test".to_string()),
+            impl_name: Some("rust".to_string()),
+        })
+        .await);
+
+    let include_error = result
+        .errors
+        .iter()
+        .find(|e| e.code == ValidationErrorCode::IncludeUnparseableFile)
+        .expect("Expected IncludeUnparseableFile validation error");
+    assert!(
+        include_error.message.contains("justfile"),
+        "Expected include parse failure message to mention justfile, got: {}",
+        include_error.message
+    );
+}
+
+// r[verify config.limits]
+// r[verify config.limits.skipped]
+#[tokio::test]
+async fn test_validate_warns_about_files_skipped_by_limits() {
+    let temp = common::create_temp_project();
+    std::fs::write(
+        temp.path().join("config.styx"),
+        r#"
+limits {
+  max_file_kb 1
+  max_files 1
+}
+specs (
+  {
+    name test
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to write config");
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+
+    let result = rpc(service
+        .client
+        .validate(ValidateRequest {
+            spec: Some("test".to_string()),
+            impl_name: Some("rust".to_string()),
+        })
+        .await);
+
+    assert_eq!(result.error_count, 0, "{:?}", result.errors);
+    assert_eq!(result.warning_count, 2);
+    let skipped: Vec<(&str, &str)> = result
+        .errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::SkippedFile)
+        .map(|e| (e.file.as_deref().unwrap_or_default(), e.message.as_str()))
+        .collect();
+    assert_eq!(skipped.len(), 2);
+    // lib.rs is over 1 KB; it still takes the one file slot, so tests.rs is
+    // skipped too.
+    assert_eq!(skipped[0].0, "src/lib.rs");
+    assert!(skipped[0].1.contains("max_file_kb"), "{}", skipped[0].1);
+    assert_eq!(skipped[1].0, "src/tests.rs");
+    assert!(skipped[1].1.contains("max_files"), "{}", skipped[1].1);
+}
+
+/// Coverage and ownership conflicts of the `core` and `ffi` implementations
+/// of a spec whose source trees overlap, with `extra` added to the spec.
+async fn overlapping_impls(extra: &str) -> Vec<(String, usize, Vec<String>)> {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let write = |path: &str, contents: &str| {
+        let path = temp.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "spec.md",
+        "r[core.run]\nThe core MUST run.\n\nr[ffi.call]\nThe FFI MUST be callable.\n\nr[common.log]\nBoth MUST log.\n",
+    );
+    write("src/lib.rs", "// r[impl core.run]\npub fn run() {}\n");
+    write("src/ffi/mod.rs", "// r[impl ffi.call]\npub fn call() {}\n");
+    write(
+        "src/common/log.rs",
+        "// r[impl common.log]\npub fn log() {}\n",
+    );
+    write(
+        "config.styx",
+        &format!(
+            "specs (\n  {{\n    name test\n    include (spec.md)\n{extra}    impls (\n      {{\n        name core\n        include (src/**/*.rs)\n      }}\n      {{\n        name ffi\n        include (src/ffi/**/*.rs src/common/**/*.rs)\n      }}\n    )\n  }}\n)\n"
+        ),
+    );
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+
+    let status = rpc(service.client.status().await);
+    let mut out = Vec::new();
+    for impl_status in &status.impls {
+        let result = rpc(service
+            .client
+            .validate(ValidateRequest {
+                spec: Some(impl_status.spec.clone()),
+                impl_name: Some(impl_status.impl_name.clone()),
+            })
+            .await);
+        assert_eq!(result.error_count, 0, "{:?}", result.errors);
+        let conflicts = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::OwnershipConflict)
+            .map(|e| e.file.clone().unwrap_or_default())
+            .collect();
+        out.push((
+            impl_status.impl_name.clone(),
+            impl_status.covered_rules,
+            conflicts,
+        ));
+    }
+    out
+}
+
+// r[verify config.spec.ownership]
+// r[verify config.spec.shared]
+// r[verify validation.ownership]
+#[tokio::test]
+async fn test_files_included_by_several_impls_have_one_owner() {
+    let conflicts = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+    // By default the implementation listed first owns shared files.
+    assert_eq!(
+        overlapping_impls("").await,
+        [
+            ("core".to_string(), 3, conflicts(&[])),
+            (
+                "ffi".to_string(),
+                0,
+                conflicts(&["src/common/log.rs", "src/ffi/mod.rs"])
+            ),
+        ]
+    );
+
+    // `specific` hands each file to the deepest matching pattern.
+    assert_eq!(
+        overlapping_impls("    ownership specific\n").await,
+        [
+            (
+                "core".to_string(),
+                1,
+                conflicts(&["src/common/log.rs", "src/ffi/mod.rs"])
+            ),
+            ("ffi".to_string(), 2, conflicts(&[])),
+        ]
+    );
+
+    // Shared files count for every implementation that includes them.
+    assert_eq!(
+        overlapping_impls("    ownership specific\n    shared (src/common/**)\n").await,
+        [
+            ("core".to_string(), 2, conflicts(&["src/ffi/mod.rs"])),
+            ("ffi".to_string(), 2, conflicts(&[])),
+        ]
+    );
+}
+
+// r[verify validation.tag-threshold]
+#[tokio::test]
+async fn test_validate_reports_tag_thresholds() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(
+        temp.path().join("config.styx"),
+        r#"
+tags {
+  security {
+    min_impl 100
+    min_verify 50
+  }
+  perf {
+    min_impl 50
+  }
+}
+specs (
+  {
+    name test
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to write config");
+    std::fs::write(
+        temp.path().join("spec.md"),
+        r#"# Spec
+
+r[auth.login tags=security]
+Login MUST check the password.
+
+r[auth.logout tags=security]
+Logout MUST clear the session.
+
+r[auth.audit tags=security status=draft]
+Audits MAY be logged.
+
+r[cache.warm tags=perf]
+The cache SHOULD be warmed on start.
+"#,
+    )
+    .expect("Failed to write spec");
+    std::fs::create_dir_all(temp.path().join("src")).expect("Failed to create src");
+    std::fs::write(
+        temp.path().join("src/lib.rs"),
+        "// r[impl auth.login]\n// r[verify auth.login]\n// r[impl cache.warm]\nfn f() {}\n",
+    )
+    .expect("Failed to write source");
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
 
-    assert_eq!(result.spec, "test");
-    assert_eq!(result.impl_name, "rust");
-    // The fixture has valid data, so should have no errors (or minimal)
+    let result = rpc(service
+        .client
+        .validate(ValidateRequest {
+            spec: Some("test".to_string()),
+            impl_name: Some("rust".to_string()),
+        })
+        .await);
+
+    let errors: Vec<_> = result
+        .errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::TagThreshold)
+        .collect();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(
+        errors[0].message,
+        "Tag 'security' is below its coverage minimum: 1 of 2 implemented (50%, minimum 100%)"
+    );
+    assert_eq!(errors[0].related_rules, vec![rid("auth.logout")]);
 }
 
+// r[verify validation.spec-threshold]
 #[tokio::test]
-async fn test_validate_reports_include_unparseable_files() {
-    let temp = common::create_temp_project();
+async fn test_validate_reports_spec_and_prefix_thresholds() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
     std::fs::write(
         temp.path().join("config.styx"),
         r#"
@@ -823,13 +2060,16 @@ specs (
   {
     name test
     include (spec.md)
+    threshold 50
+    thresholds {
+      channel. 100
+      auth. 50
+      missing. 100
+    }
     impls (
       {
         name rust
-        include (
-          src/**/*.rs
-          justfile
-        )
+        include (src/**/*.rs)
       }
     )
   }
@@ -838,13 +2078,32 @@ specs (
     )
     .expect("Failed to write config");
     std::fs::write(
-        temp.path().join("justfile"),
-        r#"# r[impl auth.login]
-run:
-  @echo "hello"
+        temp.path().join("spec.md"),
+        r#"# Spec
+
+r[channel.open]
+Channels MUST open.
+
+r[channel.close]
+Channels MUST close.
+
+r[channel.reset status=draft]
+Channels MAY reset.
+
+r[auth.login]
+Login MUST check the password.
+
+r[auth.logout]
+Logout MUST clear the session.
 "#,
     )
-    .expect("Failed to write justfile");
+    .expect("Failed to write spec");
+    std::fs::create_dir_all(temp.path().join("src")).expect("Failed to create src");
+    std::fs::write(
+        temp.path().join("src/lib.rs"),
+        "// r[impl channel.open]\n// r[impl auth.login]\nfn f() {}\n",
+    )
+    .expect("Failed to write source");
 
     let engine = Arc::new(
         tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
@@ -862,15 +2121,237 @@ run:
         })
         .await);
 
-    let include_error = result
+    // The spec (2 of 4) and `auth.` (1 of 2) meet 50%; only `channel.` fails.
+    let errors: Vec<_> = result
         .errors
         .iter()
-        .find(|e| e.code == ValidationErrorCode::IncludeUnparseableFile)
-        .expect("Expected IncludeUnparseableFile validation error");
+        .filter(|e| e.code == ValidationErrorCode::CoverageThreshold)
+        .collect();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(
+        errors[0].message,
+        "Rules under 'channel.' are below the coverage threshold: 1 of 2 implemented (50%, threshold 100%)"
+    );
+    assert_eq!(errors[0].related_rules, vec![rid("channel.close")]);
+}
+
+// r[verify coverage.test-impls]
+// r[verify config.impl.test_include.verify-only]
+#[tokio::test]
+async fn test_impls_in_test_files_can_count_as_verification() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let write = |path: &str, contents: &str| {
+        let path = temp.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "config.styx",
+        "specs (\n  {\n    name test\n    include (spec.md)\n    test_impls verify\n    impls ({name rust, include (src/**/*.rs), test_include (tests/**/*.rs)})\n  }\n)\n",
+    );
+    write(
+        "spec.md",
+        "r[sync.push]\nClients MUST push.\n\nr[sync.pull]\nClients MUST pull.\n",
+    );
+    write("src/lib.rs", "// r[impl sync.push]\nfn push() {}\n");
+    write(
+        "tests/sync.rs",
+        "// r[impl sync.pull]\n#[test]\nfn pull() {}\n",
+    );
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+
+    let uncovered = rpc(service
+        .client
+        .uncovered(UncoveredRequest {
+            spec: None,
+            impl_name: None,
+            prefix: None,
+        })
+        .await);
+    let listed: Vec<_> = uncovered
+        .by_section
+        .iter()
+        .flat_map(|s| &s.rules)
+        .map(|r| r.id.base.as_str())
+        .collect();
+    assert_eq!(listed, ["sync.pull"]);
+
+    let info = rpc(service.client.rule(rid("sync.pull")).await).expect("rule exists");
+    assert!(info.coverage[0].impl_refs.is_empty());
+    let verified: Vec<_> = info.coverage[0]
+        .verify_refs
+        .iter()
+        .map(|r| r.file.as_str())
+        .collect();
+    assert_eq!(verified, ["tests/sync.rs"]);
+
+    // The stray impl annotation is still reported.
+    let result = rpc(service
+        .client
+        .validate(ValidateRequest {
+            spec: None,
+            impl_name: None,
+        })
+        .await);
     assert!(
-        include_error.message.contains("justfile"),
-        "Expected include parse failure message to mention justfile, got: {}",
-        include_error.message
+        result
+            .errors
+            .iter()
+            .any(|e| e.code == ValidationErrorCode::ImplInTestFile),
+        "{:?}",
+        result.errors
+    );
+}
+
+// r[verify markdown.sections]
+#[tokio::test]
+async fn test_uncovered_rules_are_grouped_by_heading_chain() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let write = |path: &str, contents: &str| {
+        let path = temp.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "config.styx",
+        "specs (\n  {\n    name test\n    include (spec.md)\n    impls ({name rust, include (src/**/*.rs)})\n  }\n)\n",
+    );
+    write(
+        "spec.md",
+        "# Transport\n\nr[transport.frame]\nFrames MUST be framed.\n\n## Flow Control\n\nr[transport.window]\nPeers MUST respect the window.\n\n# Session\n\nr[session.open]\nSessions MUST open.\n",
+    );
+    write("src/lib.rs", "fn nothing() {}\n");
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+
+    let uncovered = rpc(service
+        .client
+        .uncovered(UncoveredRequest {
+            spec: None,
+            impl_name: None,
+            prefix: None,
+        })
+        .await);
+    let sections: Vec<_> = uncovered
+        .by_section
+        .iter()
+        .map(|s| {
+            let rules: Vec<_> = s.rules.iter().map(|r| r.id.base.as_str()).collect();
+            (s.section.as_str(), rules)
+        })
+        .collect();
+    assert_eq!(
+        sections,
+        [
+            ("Session", vec!["session.open"]),
+            ("Transport", vec!["transport.frame"]),
+            ("Transport > Flow Control", vec!["transport.window"]),
+        ]
+    );
+
+    let info = rpc(service.client.rule(rid("session.open")).await).expect("rule exists");
+    assert_eq!(info.section_path, ["Session"]);
+    let info = rpc(service.client.rule(rid("transport.window")).await).expect("rule exists");
+    assert_eq!(info.section_path, ["Transport", "Flow Control"]);
+}
+
+// r[verify coverage.waivers]
+// r[verify coverage.waivers.report]
+#[tokio::test]
+async fn test_waived_rules_leave_checks_until_they_expire() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let write = |path: &str, contents: &str| {
+        let path = temp.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "config.styx",
+        "specs (\n  {\n    name test\n    include (spec.md)\n    threshold 100\n    impls ({name rust, include (src/**/*.rs)})\n  }\n)\n",
+    );
+    write(
+        "spec.md",
+        "r[sync.push]\nClients MUST push.\n\nr[sync.pull]\nClients MUST pull.\n\nr[sync.merge]\nClients MUST merge.\n",
+    );
+    write("src/lib.rs", "// r[impl sync.push]\nfn push() {}\n");
+    write(
+        tracey::waivers::WAIVERS_FILE,
+        r#"waivers (
+  {
+    rule sync.merge
+    reason "Merging lands in 2.0"
+  }
+  {
+    rule sync.pull
+    reason "Was blocked on the server"
+    expires 2000-01-01
+  }
+)
+"#,
+    );
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(temp.path().to_path_buf(), temp.path().join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let service = common::create_test_rpc_service(service).await;
+
+    // The waived rule is out of scope; the expired waiver no longer helps.
+    let result = rpc(service
+        .client
+        .validate(ValidateRequest {
+            spec: None,
+            impl_name: None,
+        })
+        .await);
+    let errors: Vec<_> = result
+        .errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::CoverageThreshold)
+        .collect();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(
+        errors[0].message,
+        "Spec 'test' is below the coverage threshold: 1 of 2 implemented (50%, threshold 100%)"
+    );
+    assert_eq!(errors[0].related_rules, vec![rid("sync.pull")]);
+
+    let uncovered = rpc(service
+        .client
+        .uncovered(UncoveredRequest {
+            spec: None,
+            impl_name: None,
+            prefix: None,
+        })
+        .await);
+    assert_eq!(uncovered.uncovered_count, 1);
+    let listed: Vec<_> = uncovered
+        .by_section
+        .iter()
+        .flat_map(|s| &s.rules)
+        .map(|r| (r.id.base.as_str(), r.text.as_deref()))
+        .collect();
+    assert_eq!(listed, [("sync.pull", Some("waiver expired 2000-01-01"))]);
+    assert_eq!(uncovered.waived.len(), 1);
+    assert_eq!(uncovered.waived[0].id, rid("sync.merge"));
+    assert_eq!(
+        uncovered.waived[0].text.as_deref(),
+        Some("Merging lands in 2.0")
     );
 }
 
@@ -930,6 +2411,134 @@ async fn test_lsp_semantic_tokens() {
     assert!(!tokens.is_empty(), "Expected semantic tokens");
 }
 
+// r[verify lsp.semantic-tokens.rule-state]
+#[tokio::test]
+async fn test_lsp_semantic_tokens_encode_rule_state() {
+    use tracey_proto::semantic_modifiers::*;
+
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let project_root = temp.path().to_path_buf();
+
+    std::fs::create_dir_all(project_root.join("src")).expect("Failed to create src dir");
+    std::fs::write(
+        project_root.join("config.styx"),
+        r#"
+specs (
+  {
+    name test
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to write config");
+    let spec = r#"
+r[auth.login+2]
+Users MUST provide valid credentials to log in.
+
+r[auth.basic status=deprecated]
+Basic authentication MAY be supported.
+"#;
+    std::fs::write(project_root.join("spec.md"), spec).expect("Failed to write spec");
+    let source = "\
+// r[impl auth.login+2]
+// r[impl auth.login]
+// r[impl auth.basic]
+// r[impl auth.missing]
+fn login() {}
+";
+    std::fs::write(project_root.join("src/lib.rs"), source).expect("Failed to write src/lib.rs");
+
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(project_root.clone(), project_root.join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = tracey::daemon::TraceyService::new(engine);
+    let rpc_service = common::create_test_rpc_service(service).await;
+
+    let tokens = rpc(rpc_service
+        .client
+        .lsp_semantic_tokens(LspDocumentRequest {
+            path: project_root.join("src/lib.rs").display().to_string(),
+            content: source.to_string(),
+        })
+        .await);
+    let modifiers: Vec<(u32, u32)> = tokens.iter().map(|t| (t.line, t.modifiers)).collect();
+    assert_eq!(
+        modifiers,
+        vec![
+            (0, DECLARATION),
+            (1, STALE),
+            (2, DECLARATION | DEPRECATED),
+            (3, UNKNOWN),
+        ]
+    );
+
+    let tokens = rpc(rpc_service
+        .client
+        .lsp_semantic_tokens(LspDocumentRequest {
+            path: project_root.join("spec.md").display().to_string(),
+            content: spec.to_string(),
+        })
+        .await);
+    let modifiers: Vec<u32> = tokens.iter().map(|t| t.modifiers).collect();
+    assert_eq!(modifiers, vec![DEFINITION, DEFINITION | DEPRECATED]);
+}
+
+// ============================================================================
+// Folding Range Tests
+// ============================================================================
+
+// r[verify lsp.folding.annotated-units]
+#[tokio::test]
+async fn test_lsp_folding_ranges_for_annotated_code() {
+    let service = create_test_service().await;
+
+    let content = std::fs::read_to_string(fixtures_dir().join("src/lib.rs")).unwrap();
+
+    let req = LspDocumentRequest {
+        path: fixtures_dir().join("src/lib.rs").display().to_string(),
+        content,
+    };
+
+    let ranges = rpc(service.client.lsp_folding_ranges(req).await);
+
+    // `login` is annotated with auth.login and api.fetch; its range takes in
+    // the doc comment carrying the annotations and ends at the closing brace
+    let login = ranges
+        .iter()
+        .find(|r| r.collapsed_text.as_deref() == Some("auth.login, api.fetch"))
+        .unwrap_or_else(|| panic!("Expected a range for login, got: {ranges:?}"));
+    assert!(login.start_line <= 4, "{login:?}");
+    assert_eq!(login.end_line, 13);
+}
+
+// r[verify lsp.folding.rules]
+#[tokio::test]
+async fn test_lsp_folding_ranges_for_rules() {
+    let service = create_test_service().await;
+
+    let content = "# Spec\n\nr[auth.login]\nUsers MUST log in\nwith valid credentials.\n\nr[auth.logout]\nUsers MUST be able to log out.\n";
+
+    let req = LspDocumentRequest {
+        path: fixtures_dir().join("spec.md").display().to_string(),
+        content: content.to_string(),
+    };
+
+    let ranges = rpc(service.client.lsp_folding_ranges(req).await);
+
+    assert_eq!(ranges.len(), 2, "Expected one range per rule: {ranges:?}");
+    assert_eq!((ranges[0].start_line, ranges[0].end_line), (2, 4));
+    assert_eq!((ranges[1].start_line, ranges[1].end_line), (6, 7));
+}
+
 // ============================================================================
 // Code Lens Tests
 // ============================================================================
@@ -1304,6 +2913,52 @@ pub fn shm_impl() {}
     assert_eq!(shm.prefix, "shm", "shm should infer shm prefix");
 }
 
+// ============================================================================
+// Fan-out
+// ============================================================================
+
+/// r[verify coverage.fanout]
+/// r[verify markdown.syntax.fanout]
+#[tokio::test]
+async fn test_fanout_shortfalls_are_uncovered() {
+    let service = create_test_service_named("fanout").await;
+    let req = UncoveredRequest {
+        spec: Some("test".to_string()),
+        impl_name: Some("rust".to_string()),
+        prefix: None,
+    };
+    let response = rpc(service.client.uncovered(req).await);
+
+    let uncovered: Vec<(String, Option<String>)> = response
+        .by_section
+        .iter()
+        .flat_map(|s| &s.rules)
+        .map(|r| (r.id.to_string(), r.text.clone()))
+        .collect();
+    assert_eq!(
+        uncovered,
+        [
+            (
+                "api.handler".to_string(),
+                Some("2 of 3 files annotated".to_string())
+            ),
+            // The marker's `fanout=3` overrides the config's 2.
+            (
+                "storage.backend".to_string(),
+                Some("1 of 3 references annotated".to_string())
+            ),
+        ]
+    );
+
+    let status = rpc(service.client.status().await);
+    let impl_status = status
+        .impls
+        .iter()
+        .find(|i| i.spec == "test" && i.impl_name == "rust")
+        .expect("Expected test/rust impl");
+    assert_eq!(impl_status.covered_rules, 1);
+}
+
 // ============================================================================
 // test_include extraction
 // ============================================================================
@@ -70,6 +70,47 @@ async fn test_mcp_status_tool() {
     assert!(impl_status.covered_rules <= impl_status.total_rules);
 }
 
+// r[verify mcp.tool.status.docs]
+#[tokio::test]
+async fn test_mcp_status_tool_reports_documented_rules() {
+    let status = rpc(create_test_service().await.client.status().await);
+    assert!(
+        status.impls.iter().all(|s| s.documented_rules.is_none()),
+        "no verb has the docs role in the fixtures"
+    );
+
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path().to_path_buf();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("config.styx"),
+        "verbs {\n  documents docs\n}\n\
+         specs ({name app, include (spec.md), impls ({name rust, include (src/**/*.rs)})})\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("spec.md"),
+        "r[auth.login]\nLog in.\n\nr[auth.logout]\nLog out.\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "// r[documents auth.login]\nfn login() {}\n",
+    )
+    .unwrap();
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(root.clone(), root.join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = common::create_test_rpc_service(tracey::daemon::TraceyService::new(engine)).await;
+
+    let status = rpc(service.client.status().await);
+    let app = &status.impls[0];
+    assert_eq!((app.total_rules, app.documented_rules), (2, Some(1)));
+    assert_eq!(app.covered_rules, 0, "docs references do not implement");
+}
+
 // ============================================================================
 // tracey_uncovered Tool Tests
 // ============================================================================
@@ -247,6 +288,78 @@ async fn test_mcp_rule_tool_coverage_info() {
     }
 }
 
+// ============================================================================
+// tracey_annotation_snippet Tool Tests
+// ============================================================================
+
+// r[verify mcp.tool.annotation-snippet]
+#[tokio::test]
+async fn test_mcp_annotation_snippet_tool() {
+    let service = create_test_service().await;
+    let snippet = rpc(service
+        .client
+        .annotation_snippet(AnnotationSnippetRequest {
+            rule_id: rid("auth.login"),
+            language: "rs".to_string(),
+            verb: None,
+        })
+        .await);
+
+    assert_eq!(snippet.spec, "test");
+    assert_eq!(snippet.snippet, "// r[impl auth.login]");
+
+    let snippet = rpc(service
+        .client
+        .annotation_snippet(AnnotationSnippetRequest {
+            rule_id: rid("auth.login"),
+            language: "python".to_string(),
+            verb: Some("verify".to_string()),
+        })
+        .await);
+
+    assert_eq!(snippet.snippet, "# r[verify auth.login]");
+}
+
+// r[verify mcp.tool.annotation-snippet]
+#[tokio::test]
+async fn test_mcp_annotation_snippet_tool_errors() {
+    let service = create_test_service().await;
+
+    let unknown_rule = service
+        .client
+        .annotation_snippet(AnnotationSnippetRequest {
+            rule_id: rid("nonexistent.rule.id"),
+            language: "rs".to_string(),
+            verb: None,
+        })
+        .await;
+    assert!(unknown_rule.is_err(), "Expected error for unknown rule");
+
+    let unknown_language = service
+        .client
+        .annotation_snippet(AnnotationSnippetRequest {
+            rule_id: rid("auth.login"),
+            language: "brainfuck".to_string(),
+            verb: None,
+        })
+        .await;
+    assert!(
+        unknown_language.is_err(),
+        "Expected error for unsupported language"
+    );
+
+    // Has a comment syntax, but tracey does not scan it.
+    let unscanned = service
+        .client
+        .annotation_snippet(AnnotationSnippetRequest {
+            rule_id: rid("auth.login"),
+            language: "yaml".to_string(),
+            verb: None,
+        })
+        .await;
+    assert!(unscanned.is_err(), "Expected error for yaml");
+}
+
 // ============================================================================
 // tracey_config Tool Tests
 // ============================================================================